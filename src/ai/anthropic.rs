@@ -0,0 +1,368 @@
+//! Anthropic (Claude) API provider implementation
+
+use crate::ai::provider::{AIError, AIProvider, CompletionOptions, ToolOutcome};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+/// Anthropic provider configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnthropicConfig {
+    /// API key (will be stored in keychain in production)
+    pub api_key: String,
+    /// Model to use (e.g., "claude-3-5-sonnet-20241022")
+    pub model: String,
+}
+
+impl Default for AnthropicConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            model: "claude-3-5-sonnet-20241022".to_string(),
+        }
+    }
+}
+
+/// Anthropic (Claude) API provider
+pub struct AnthropicProvider {
+    client: Client,
+    config: AnthropicConfig,
+}
+
+impl AnthropicProvider {
+    /// Create a new Anthropic provider.
+    ///
+    /// Infallible today, but returns `Result` to match `OpenAIProvider::new`
+    /// so the `register_providers!` registry can dispatch to either
+    /// uniformly.
+    pub fn new(config: AnthropicConfig) -> Result<Self, AIError> {
+        Ok(Self {
+            client: Client::new(),
+            config,
+        })
+    }
+
+    /// Create provider with API key from keyring
+    pub fn from_keyring(model: Option<String>) -> Result<Self, AIError> {
+        let api_key = Self::load_api_key()?;
+        let config = AnthropicConfig {
+            api_key,
+            model: model.unwrap_or_else(|| "claude-3-5-sonnet-20241022".to_string()),
+        };
+        Self::new(config)
+    }
+
+    /// Load API key from system keyring
+    fn load_api_key() -> Result<String, AIError> {
+        use keyring::Entry;
+
+        let entry = Entry::new("warp-foss", "anthropic-api-key")
+            .map_err(|e| AIError::Config(format!("Failed to access keyring: {}", e)))?;
+
+        entry
+            .get_password()
+            .map_err(|e| AIError::Config(format!("Failed to get API key: {}", e)))
+    }
+
+    /// Save API key to system keyring
+    pub fn save_api_key(api_key: &str) -> Result<(), AIError> {
+        use keyring::Entry;
+
+        let entry = Entry::new("warp-foss", "anthropic-api-key")
+            .map_err(|e| AIError::Config(format!("Failed to access keyring: {}", e)))?;
+
+        entry
+            .set_password(api_key)
+            .map_err(|e| AIError::Config(format!("Failed to save API key: {}", e)))
+    }
+
+    /// Delete API key from system keyring
+    pub fn delete_api_key() -> Result<(), AIError> {
+        use keyring::Entry;
+
+        let entry = Entry::new("warp-foss", "anthropic-api-key")
+            .map_err(|e| AIError::Config(format!("Failed to access keyring: {}", e)))?;
+
+        entry
+            .delete_credential()
+            .map_err(|e| AIError::Config(format!("Failed to delete API key: {}", e)))
+    }
+
+    /// Get the API key
+    pub fn api_key(&self) -> &str {
+        &self.config.api_key
+    }
+
+    /// Get the model name
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+}
+
+#[derive(Serialize)]
+struct MessagesRequest {
+    model: String,
+    messages: Vec<Message>,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct ContentBlock {
+    text: String,
+}
+
+/// One SSE event of an Anthropic streaming response. Only the
+/// `content_block_delta` events carry text; the rest (`message_start`,
+/// `content_block_start`, `message_delta`, `message_stop`, ...) are skipped.
+#[derive(Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    #[serde(default)]
+    delta: Option<StreamDelta>,
+}
+
+#[derive(Deserialize, Default)]
+struct StreamDelta {
+    text: Option<String>,
+}
+
+/// Anthropic requires an explicit max_tokens; fall back to this when the
+/// caller doesn't supply one.
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+#[async_trait]
+impl AIProvider for AnthropicProvider {
+    async fn complete(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<String, AIError> {
+        if self.config.api_key.is_empty() {
+            return Err(AIError::Config("API key not configured".to_string()));
+        }
+
+        let request = MessagesRequest {
+            model: self.config.model.clone(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            max_tokens: opts
+                .as_ref()
+                .and_then(|o| o.max_tokens)
+                .unwrap_or(DEFAULT_MAX_TOKENS),
+            temperature: opts.as_ref().and_then(|o| o.temperature),
+            stream: None,
+        };
+
+        let response = self
+            .client
+            .post(ANTHROPIC_API_URL)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AIError::Api(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AIError::Api(format!(
+                "API error ({}): {}",
+                status, body
+            )));
+        }
+
+        let messages_response: MessagesResponse = response
+            .json()
+            .await
+            .map_err(|e| AIError::Api(format!("Failed to parse response: {}", e)))?;
+
+        messages_response
+            .content
+            .first()
+            .map(|c| c.text.clone())
+            .ok_or_else(|| AIError::Api("No completion returned".to_string()))
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
+        let result = self.complete(prompt, opts).await?;
+
+        Ok(Box::pin(futures::stream::once(async move { Ok(result) })))
+    }
+
+    async fn complete_stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
+        if self.config.api_key.is_empty() {
+            return Err(AIError::Config("API key not configured".to_string()));
+        }
+
+        let request = MessagesRequest {
+            model: self.config.model.clone(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            max_tokens: opts
+                .as_ref()
+                .and_then(|o| o.max_tokens)
+                .unwrap_or(DEFAULT_MAX_TOKENS),
+            temperature: opts.as_ref().and_then(|o| o.temperature),
+            stream: Some(true),
+        };
+
+        let response = self
+            .client
+            .post(ANTHROPIC_API_URL)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AIError::Api(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AIError::Api(format!(
+                "API error ({}): {}",
+                status, body
+            )));
+        }
+
+        // Buffered as raw bytes, not a `String`: a multi-byte UTF-8
+        // character (routine in model output) can straddle a chunk
+        // boundary, and decoding each chunk on its own before it's
+        // reassembled would corrupt it. Only a complete, already-joined
+        // line is ever converted to `str`.
+        let state = (response.bytes_stream(), Vec::<u8>::new(), false);
+
+        let deltas = futures::stream::unfold(state, |(mut bytes, mut buffer, done)| async move {
+            if done {
+                return None;
+            }
+
+            loop {
+                if let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                    let line = String::from_utf8_lossy(&buffer[..pos]).trim().to_string();
+                    buffer.drain(..=pos);
+
+                    let Some(payload) = line.strip_prefix("data: ") else {
+                        continue;
+                    };
+
+                    return match serde_json::from_str::<StreamEvent>(payload) {
+                        Ok(event) => {
+                            if event.event_type == "message_stop" {
+                                return None;
+                            }
+                            match event.delta.and_then(|d| d.text) {
+                                Some(text) if !text.is_empty() => {
+                                    Some((Ok(text), (bytes, buffer, false)))
+                                }
+                                _ => continue,
+                            }
+                        }
+                        Err(e) => Some((
+                            Err(AIError::Api(format!("Failed to parse stream event: {}", e))),
+                            (bytes, buffer, true),
+                        )),
+                    };
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => {
+                        return Some((Err(AIError::Api(format!("Stream error: {}", e))), (bytes, buffer, true)));
+                    }
+                    None => return None,
+                }
+            }
+        });
+
+        Ok(Box::pin(deltas))
+    }
+
+    async fn complete_with_tool(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<ToolOutcome, AIError> {
+        // Claude's tool-use API differs enough from OpenAI's (separate
+        // `input_schema`/`tool_use` content blocks) that wiring it up is
+        // left for a follow-up; fall back to a plain-text outcome for now.
+        Ok(ToolOutcome::Text(self.complete(prompt, opts).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anthropic_config_default() {
+        let config = AnthropicConfig::default();
+        assert!(config.api_key.is_empty());
+        assert_eq!(config.model, "claude-3-5-sonnet-20241022");
+    }
+
+    #[test]
+    fn test_anthropic_provider_creation() {
+        let config = AnthropicConfig {
+            api_key: "test-key".to_string(),
+            model: "claude-3-opus-20240229".to_string(),
+        };
+        let _provider = AnthropicProvider::new(config).unwrap();
+    }
+
+    #[test]
+    fn test_messages_response_deserialization() {
+        let json = r#"{"content":[{"type":"text","text":"Hello! How can I help you?"}]}"#;
+        let response: MessagesResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.content[0].text, "Hello! How can I help you?");
+    }
+
+    #[test]
+    fn test_stream_event_with_text_delta() {
+        let json = r#"{"type":"content_block_delta","delta":{"type":"text_delta","text":"Hel"}}"#;
+        let event: StreamEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.delta.unwrap().text.as_deref(), Some("Hel"));
+    }
+
+    #[test]
+    fn test_stream_event_without_delta() {
+        let json = r#"{"type":"message_stop"}"#;
+        let event: StreamEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.event_type, "message_stop");
+        assert!(event.delta.is_none());
+    }
+}
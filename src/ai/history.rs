@@ -0,0 +1,198 @@
+//! Multi-turn conversation history for the AI command palette
+//!
+//! Without this, every submit is a one-shot prompt and follow-ups like
+//! "now do that for the other branch" have no memory of what was just
+//! discussed. `ConversationHistory` accumulates turns, flattens them into
+//! the transcript sent with each request, and persists to a JSON session
+//! file so reopening the palette (even after restarting the app) picks
+//! the conversation back up.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Who said a given turn.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    fn label(&self) -> &'static str {
+        match self {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        }
+    }
+}
+
+/// One turn of a conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Turn {
+    pub role: Role,
+    pub content: String,
+}
+
+/// Rough ceiling on transcript size sent with each request. There's no
+/// tokenizer in this crate, so character count stands in for a token
+/// budget; a conservative ~4 chars/token estimate keeps this well under
+/// typical model context limits even though it overcounts.
+const MAX_TRANSCRIPT_CHARS: usize = 16_000;
+
+/// Accumulated conversation turns for the AI command palette.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationHistory {
+    pub turns: Vec<Turn>,
+}
+
+impl ConversationHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a user turn, then trim the oldest turns if the transcript
+    /// has grown past the token budget.
+    pub fn push_user(&mut self, content: impl Into<String>) {
+        self.turns.push(Turn {
+            role: Role::User,
+            content: content.into(),
+        });
+        self.enforce_budget();
+    }
+
+    /// Record an assistant turn, then trim the oldest turns if the
+    /// transcript has grown past the token budget.
+    pub fn push_assistant(&mut self, content: impl Into<String>) {
+        self.turns.push(Turn {
+            role: Role::Assistant,
+            content: content.into(),
+        });
+        self.enforce_budget();
+    }
+
+    /// Discard all turns, starting a fresh session.
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+
+    fn enforce_budget(&mut self) {
+        while self.transcript_len() > MAX_TRANSCRIPT_CHARS && self.turns.len() > 1 {
+            self.turns.remove(0);
+        }
+    }
+
+    fn transcript_len(&self) -> usize {
+        self.turns.iter().map(|turn| turn.content.len() + 12).sum()
+    }
+
+    /// Flatten the accumulated turns into a role-prefixed transcript. The
+    /// `AIProvider` trait takes a single prompt string rather than a
+    /// structured messages array, so the conversation is folded into the
+    /// prompt the same way a single-turn question already is.
+    pub fn to_transcript(&self) -> String {
+        self.turns
+            .iter()
+            .map(|turn| format!("{}: {}", turn.role.label(), turn.content))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Path of the persisted session file.
+    pub fn session_path() -> Result<PathBuf> {
+        if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+            return Ok(PathBuf::from(xdg_config)
+                .join("warp-foss")
+                .join("ai_session.json"));
+        }
+
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(home.join(".config").join("warp-foss").join("ai_session.json"))
+    }
+
+    /// Load the persisted session, or an empty history if none exists yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::session_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read AI session file: {:?}", path))?;
+
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse AI session file: {:?}", path))
+    }
+
+    /// Persist the current session to disk.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::session_path()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create session directory: {:?}", parent))?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize AI session")?;
+
+        std::fs::write(&path, &contents)
+            .with_context(|| format!("Failed to write AI session file: {:?}", path))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_is_role_prefixed_and_ordered() {
+        let mut history = ConversationHistory::new();
+        history.push_user("list files");
+        history.push_assistant("Proposed command: ls -la (List files)");
+
+        assert_eq!(
+            history.to_transcript(),
+            "User: list files\n\nAssistant: Proposed command: ls -la (List files)"
+        );
+    }
+
+    #[test]
+    fn test_clear_empties_turns() {
+        let mut history = ConversationHistory::new();
+        history.push_user("hi");
+        history.clear();
+        assert!(history.turns.is_empty());
+        assert_eq!(history.to_transcript(), "");
+    }
+
+    #[test]
+    fn test_budget_drops_oldest_turns_once_exceeded() {
+        let mut history = ConversationHistory::new();
+        let long_turn = "x".repeat(MAX_TRANSCRIPT_CHARS);
+        history.push_user("first question");
+        history.push_assistant(long_turn.clone());
+        history.push_user("second question");
+
+        // The oversized first exchange should have been dropped, keeping
+        // only what still fits under the budget.
+        assert!(!history.turns.iter().any(|t| t.content == "first question"));
+        assert!(history
+            .turns
+            .iter()
+            .any(|t| t.content == "second question"));
+    }
+
+    #[test]
+    fn test_session_roundtrips_through_json() {
+        let mut history = ConversationHistory::new();
+        history.push_user("hello");
+        history.push_assistant("hi there");
+
+        let json = serde_json::to_string(&history).unwrap();
+        let restored: ConversationHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.turns, history.turns);
+    }
+}
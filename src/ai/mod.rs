@@ -1,8 +1,98 @@
 //! AI integration layer with BYOK support
 
+use std::sync::Arc;
+
 pub mod anthropic;
+pub mod history;
 pub mod ollama;
 pub mod openai;
 pub mod provider;
+pub mod shell_context;
 
 pub use provider::{AIError, AIProvider, CompletionOptions};
+
+use anthropic::{AnthropicConfig, AnthropicProvider};
+use ollama::{OllamaConfig, OllamaProvider};
+use openai::{OpenAIConfig, OpenAIProvider};
+
+/// Which backend a user has selected as their active AI provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    OpenAI,
+    Anthropic,
+    Ollama,
+}
+
+/// Instantiate the configured provider behind a trait object.
+///
+/// OpenAI and Anthropic read their API key from the system keyring; Ollama
+/// talks to a local server and needs no key.
+pub fn create_provider(kind: ProviderKind) -> Result<Arc<dyn AIProvider>, AIError> {
+    match kind {
+        ProviderKind::OpenAI => Ok(Arc::new(OpenAIProvider::from_keyring(None)?)),
+        ProviderKind::Anthropic => Ok(Arc::new(AnthropicProvider::from_keyring(None)?)),
+        ProviderKind::Ollama => Ok(Arc::new(OllamaProvider::new(OllamaConfig::default())?)),
+    }
+}
+
+/// Declares a tagged, deserializable [`ProviderConfig`] enum plus its
+/// [`ProviderConfig::build`] dispatcher from a list of `tag => Variant(Config) => Provider`
+/// rules.
+///
+/// Each arm's config type already knows how to build its own provider via a
+/// `new(config)` constructor; this macro just wires a serde-tagged enum on
+/// top so a config file can declare several backends and select one by name
+/// at runtime. Adding a new backend means adding one line here - no other
+/// call site needs to change.
+macro_rules! register_providers {
+    ($($tag:literal => $variant:ident($config:ty) => $provider:ty),+ $(,)?) => {
+        /// One entry in a multi-provider config, selected by its `type` tag
+        /// (e.g. `"openai"`). A config file can list several of these and
+        /// the app picks one by tag at runtime via [`ProviderConfig::build`].
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+        #[serde(tag = "type")]
+        pub enum ProviderConfig {
+            $(
+                #[serde(rename = $tag)]
+                $variant($config),
+            )+
+        }
+
+        impl ProviderConfig {
+            /// Instantiate the concrete provider this entry describes.
+            pub fn build(&self) -> Result<Box<dyn AIProvider>, AIError> {
+                match self {
+                    $(
+                        ProviderConfig::$variant(config) => {
+                            Ok(Box::new(<$provider>::new(config.clone())?))
+                        }
+                    )+
+                }
+            }
+        }
+    };
+}
+
+register_providers! {
+    "openai" => OpenAI(OpenAIConfig) => OpenAIProvider,
+    "anthropic" => Anthropic(AnthropicConfig) => AnthropicProvider,
+    "ollama" => Ollama(OllamaConfig) => OllamaProvider,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_config_deserializes_by_tag() {
+        let json = r#"{"type": "ollama", "base_url": "http://localhost:11434", "model": "llama3"}"#;
+        let config: ProviderConfig = serde_json::from_str(json).unwrap();
+        assert!(matches!(config, ProviderConfig::Ollama(_)));
+    }
+
+    #[test]
+    fn test_provider_config_build_dispatches_to_matching_provider() {
+        let config = ProviderConfig::Ollama(OllamaConfig::default());
+        assert!(config.build().is_ok());
+    }
+}
@@ -0,0 +1,272 @@
+//! Ollama local model provider implementation
+//!
+//! Talks to a locally running `ollama serve` instance, so no API key is
+//! required - useful for privacy-sensitive terminals that want to keep
+//! prompts off the network entirely.
+
+use crate::ai::provider::{AIError, AIProvider, CompletionOptions, ToolOutcome};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/// Ollama provider configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaConfig {
+    /// Base URL of the Ollama server (no trailing slash)
+    pub base_url: String,
+    /// Model to use (e.g., "llama3")
+    pub model: String,
+}
+
+impl Default for OllamaConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: "llama3".to_string(),
+        }
+    }
+}
+
+/// Ollama local model provider
+pub struct OllamaProvider {
+    client: Client,
+    config: OllamaConfig,
+}
+
+impl OllamaProvider {
+    /// Create a new Ollama provider.
+    ///
+    /// Infallible today, but returns `Result` to match `OpenAIProvider::new`
+    /// so the `register_providers!` registry can dispatch to either
+    /// uniformly.
+    pub fn new(config: OllamaConfig) -> Result<Self, AIError> {
+        Ok(Self {
+            client: Client::new(),
+            config,
+        })
+    }
+
+    /// Get the model name
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    fn generate_url(&self) -> String {
+        format!("{}/api/generate", self.config.base_url)
+    }
+}
+
+#[derive(Serialize)]
+struct GenerateRequest {
+    model: String,
+    prompt: String,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<GenerateOptions>,
+}
+
+#[derive(Serialize)]
+struct GenerateOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+}
+
+/// One newline-delimited JSON object from `/api/generate`. Ollama streams
+/// these unconditionally (even with `stream: false` it sends a single one);
+/// `done` marks the final object, at which point `response` is empty.
+#[derive(Deserialize)]
+struct GenerateChunk {
+    response: String,
+    done: bool,
+}
+
+fn generate_options(opts: &Option<CompletionOptions>) -> Option<GenerateOptions> {
+    let opts = opts.as_ref()?;
+    if opts.temperature.is_none() && opts.max_tokens.is_none() {
+        return None;
+    }
+    Some(GenerateOptions {
+        temperature: opts.temperature,
+        num_predict: opts.max_tokens,
+    })
+}
+
+#[async_trait]
+impl AIProvider for OllamaProvider {
+    async fn complete(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<String, AIError> {
+        let request = GenerateRequest {
+            model: self.config.model.clone(),
+            prompt: prompt.to_string(),
+            stream: false,
+            options: generate_options(&opts),
+        };
+
+        let response = self
+            .client
+            .post(self.generate_url())
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AIError::Api(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AIError::Api(format!(
+                "API error ({}): {}",
+                status, body
+            )));
+        }
+
+        let chunk: GenerateChunk = response
+            .json()
+            .await
+            .map_err(|e| AIError::Api(format!("Failed to parse response: {}", e)))?;
+
+        Ok(chunk.response)
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
+        let result = self.complete(prompt, opts).await?;
+
+        Ok(Box::pin(futures::stream::once(async move { Ok(result) })))
+    }
+
+    async fn complete_stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
+        let request = GenerateRequest {
+            model: self.config.model.clone(),
+            prompt: prompt.to_string(),
+            stream: true,
+            options: generate_options(&opts),
+        };
+
+        let response = self
+            .client
+            .post(self.generate_url())
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AIError::Api(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AIError::Api(format!(
+                "API error ({}): {}",
+                status, body
+            )));
+        }
+
+        // State threaded through `unfold`: the raw byte stream and a buffer
+        // of bytes not yet split into a full newline-delimited JSON object.
+        // Kept as raw bytes rather than a `String` so a multi-byte UTF-8
+        // character straddling a chunk boundary gets reassembled before
+        // it's ever decoded, instead of being corrupted chunk-by-chunk.
+        let state = (response.bytes_stream(), Vec::<u8>::new());
+
+        let deltas = futures::stream::unfold(state, |(mut bytes, mut buffer)| async move {
+            loop {
+                if let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                    let line = String::from_utf8_lossy(&buffer[..pos]).trim().to_string();
+                    buffer.drain(..=pos);
+
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    return match serde_json::from_str::<GenerateChunk>(&line) {
+                        Ok(chunk) if chunk.done => None,
+                        Ok(chunk) if chunk.response.is_empty() => continue,
+                        Ok(chunk) => Some((Ok(chunk.response), (bytes, buffer))),
+                        Err(e) => Some((
+                            Err(AIError::Api(format!("Failed to parse stream chunk: {}", e))),
+                            (bytes, buffer),
+                        )),
+                    };
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => {
+                        return Some((Err(AIError::Api(format!("Stream error: {}", e))), (bytes, buffer)));
+                    }
+                    None => return None,
+                }
+            }
+        });
+
+        Ok(Box::pin(deltas))
+    }
+
+    async fn complete_with_tool(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<ToolOutcome, AIError> {
+        // Tool calling is model-dependent in Ollama and not all local
+        // models support it; fall back to a plain-text outcome for now.
+        Ok(ToolOutcome::Text(self.complete(prompt, opts).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ollama_config_default() {
+        let config = OllamaConfig::default();
+        assert_eq!(config.base_url, "http://localhost:11434");
+        assert_eq!(config.model, "llama3");
+    }
+
+    #[test]
+    fn test_generate_url() {
+        let provider = OllamaProvider::new(OllamaConfig::default()).unwrap();
+        assert_eq!(provider.generate_url(), "http://localhost:11434/api/generate");
+    }
+
+    #[test]
+    fn test_generate_chunk_deserialization() {
+        let json = r#"{"model":"llama3","response":"Hel","done":false}"#;
+        let chunk: GenerateChunk = serde_json::from_str(json).unwrap();
+        assert_eq!(chunk.response, "Hel");
+        assert!(!chunk.done);
+    }
+
+    #[test]
+    fn test_generate_chunk_done() {
+        let json = r#"{"model":"llama3","response":"","done":true}"#;
+        let chunk: GenerateChunk = serde_json::from_str(json).unwrap();
+        assert!(chunk.response.is_empty());
+        assert!(chunk.done);
+    }
+
+    #[test]
+    fn test_generate_options_omitted_when_empty() {
+        assert!(generate_options(&None).is_none());
+        assert!(generate_options(&Some(CompletionOptions {
+            max_tokens: None,
+            temperature: None,
+        }))
+        .is_none());
+    }
+}
@@ -1,21 +1,122 @@
 //! OpenAI API provider implementation
 
-use crate::ai::provider::{AIError, AIProvider, CompletionOptions};
+use crate::ai::provider::{AIError, AIProvider, CompletionOptions, ToolCall, ToolOutcome};
 use async_trait::async_trait;
-use futures::Stream;
-use reqwest::Client;
+use futures::{Stream, StreamExt};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
+use std::time::Duration;
+
+/// Default API base, used when `OpenAIConfig::api_base` is unset.
+const DEFAULT_API_BASE: &str = "https://api.openai.com/v1";
+
+/// Default number of attempts for a non-streaming request that keeps
+/// hitting transient errors (including the first try).
+const DEFAULT_RETRY_COUNT: u32 = 5;
+
+/// Default delay before the first retry; doubles each subsequent attempt.
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Context window and streaming support for a known OpenAI model.
+#[derive(Debug, Clone, Copy)]
+struct ModelInfo {
+    max_context_tokens: u32,
+    /// Whether the model accepts `stream: true`. Reasoning-style models
+    /// (e.g. the `o1` family) reject it and expect `max_completion_tokens`
+    /// in place of `max_tokens`.
+    supports_streaming: bool,
+}
 
-const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
+/// Known OpenAI models, their context windows, and whether they support
+/// streaming. Anything not in this table falls back to
+/// `DEFAULT_MODEL_INFO` - a conservative context window and streaming on,
+/// since that matches most models.
+const MODEL_TABLE: &[(&str, ModelInfo)] = &[
+    (
+        "gpt-3.5-turbo",
+        ModelInfo {
+            max_context_tokens: 16_385,
+            supports_streaming: true,
+        },
+    ),
+    (
+        "gpt-4",
+        ModelInfo {
+            max_context_tokens: 8_192,
+            supports_streaming: true,
+        },
+    ),
+    (
+        "gpt-4-turbo",
+        ModelInfo {
+            max_context_tokens: 128_000,
+            supports_streaming: true,
+        },
+    ),
+    (
+        "gpt-4o",
+        ModelInfo {
+            max_context_tokens: 128_000,
+            supports_streaming: true,
+        },
+    ),
+    (
+        "o1",
+        ModelInfo {
+            max_context_tokens: 200_000,
+            supports_streaming: false,
+        },
+    ),
+    (
+        "o1-mini",
+        ModelInfo {
+            max_context_tokens: 128_000,
+            supports_streaming: false,
+        },
+    ),
+];
+
+const DEFAULT_MODEL_INFO: ModelInfo = ModelInfo {
+    max_context_tokens: 4_096,
+    supports_streaming: true,
+};
+
+fn model_info(model: &str) -> ModelInfo {
+    MODEL_TABLE
+        .iter()
+        .find(|(name, _)| *name == model)
+        .map(|(_, info)| *info)
+        .unwrap_or(DEFAULT_MODEL_INFO)
+}
 
 /// OpenAI provider configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenAIConfig {
     /// API key (will be stored in keychain in production)
     pub api_key: String,
     /// Model to use (e.g., "gpt-4", "gpt-3.5-turbo")
     pub model: String,
+    /// API base URL, e.g. `https://api.openai.com/v1`. `None` uses
+    /// `DEFAULT_API_BASE`. Overriding this points the provider at any
+    /// OpenAI-compatible server - self-hosted gateways, LocalAI, vLLM,
+    /// Azure-style deployments - without code changes.
+    pub api_base: Option<String>,
+    /// Max attempts for a `complete`/`complete_chat` request that hits a
+    /// transient error (HTTP 429 or 500/502/503), including the first try.
+    /// Streaming and `complete_with_tool` requests are sent once.
+    pub retry_count: u32,
+    /// Base delay before the first retry; doubles each subsequent attempt,
+    /// unless the server sends a `Retry-After` header.
+    pub retry_base_delay_ms: u64,
+    /// Proxy URL (http/https/socks5) to route requests through, e.g.
+    /// `socks5://127.0.0.1:1080`. `None` falls back to the usual
+    /// `HTTP_PROXY`/`HTTPS_PROXY` environment variables.
+    pub proxy: Option<String>,
+    /// Timeout for establishing the TCP connection, useful on flaky
+    /// networks where a hung connection attempt should fail fast instead
+    /// of blocking forever. `None` uses reqwest's default.
+    pub connect_timeout_secs: Option<u64>,
 }
 
 impl Default for OpenAIConfig {
@@ -23,6 +124,60 @@ impl Default for OpenAIConfig {
         Self {
             api_key: String::new(),
             model: "gpt-3.5-turbo".to_string(),
+            api_base: None,
+            retry_count: DEFAULT_RETRY_COUNT,
+            retry_base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+            proxy: None,
+            connect_timeout_secs: None,
+        }
+    }
+}
+
+/// Who authored a turn in a multi-turn `complete_chat` conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+impl Role {
+    fn as_str(self) -> &'static str {
+        match self {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+}
+
+/// One turn of a conversation passed to `complete_chat`, e.g. a system
+/// instruction followed by alternating user/assistant turns.
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::System,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
         }
     }
 }
@@ -34,22 +189,41 @@ pub struct OpenAIProvider {
 }
 
 impl OpenAIProvider {
-    /// Create a new OpenAI provider
-    pub fn new(config: OpenAIConfig) -> Self {
-        Self {
-            client: Client::new(),
-            config,
+    /// Create a new OpenAI provider, building its HTTP client from
+    /// `config.proxy` and `config.connect_timeout_secs` if set. With no
+    /// proxy configured, reqwest falls back to the usual
+    /// `HTTP_PROXY`/`HTTPS_PROXY` environment variables on its own.
+    pub fn new(config: OpenAIConfig) -> Result<Self, AIError> {
+        let mut builder = Client::builder();
+
+        if let Some(proxy) = &config.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| AIError::Config(format!("Invalid proxy URL: {}", e)))?;
+            builder = builder.proxy(proxy);
         }
+
+        if let Some(secs) = config.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| AIError::Config(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self { client, config })
     }
 
-    /// Create provider with API key from keyring
+    /// Create provider with API key (and API base, if one was saved) from keyring
     pub fn from_keyring(model: Option<String>) -> Result<Self, AIError> {
         let api_key = Self::load_api_key()?;
+        let api_base = Self::load_api_base().ok();
         let config = OpenAIConfig {
             api_key,
             model: model.unwrap_or_else(|| "gpt-3.5-turbo".to_string()),
+            api_base,
+            ..OpenAIConfig::default()
         };
-        Ok(Self::new(config))
+        Self::new(config)
     }
 
     /// Load API key from system keyring
@@ -88,6 +262,43 @@ impl OpenAIProvider {
             .map_err(|e| AIError::Config(format!("Failed to delete API key: {}", e)))
     }
 
+    /// Load API base from system keyring
+    fn load_api_base() -> Result<String, AIError> {
+        use keyring::Entry;
+
+        let entry = Entry::new("warp-foss", "openai-api-base")
+            .map_err(|e| AIError::Config(format!("Failed to access keyring: {}", e)))?;
+
+        entry
+            .get_password()
+            .map_err(|e| AIError::Config(format!("Failed to get API base: {}", e)))
+    }
+
+    /// Save API base to system keyring, alongside the API key, so
+    /// `from_keyring` reconstructs the same endpoint next time.
+    pub fn save_api_base(api_base: &str) -> Result<(), AIError> {
+        use keyring::Entry;
+
+        let entry = Entry::new("warp-foss", "openai-api-base")
+            .map_err(|e| AIError::Config(format!("Failed to access keyring: {}", e)))?;
+
+        entry
+            .set_password(api_base)
+            .map_err(|e| AIError::Config(format!("Failed to save API base: {}", e)))
+    }
+
+    /// Delete API base from system keyring
+    pub fn delete_api_base() -> Result<(), AIError> {
+        use keyring::Entry;
+
+        let entry = Entry::new("warp-foss", "openai-api-base")
+            .map_err(|e| AIError::Config(format!("Failed to access keyring: {}", e)))?;
+
+        entry
+            .delete_credential()
+            .map_err(|e| AIError::Config(format!("Failed to delete API base: {}", e)))
+    }
+
     /// Get the API key
     pub fn api_key(&self) -> &str {
         &self.config.api_key
@@ -97,6 +308,141 @@ impl OpenAIProvider {
     pub fn model(&self) -> &str {
         &self.config.model
     }
+
+    /// Get the configured API base, or `DEFAULT_API_BASE` if unset.
+    pub fn api_base(&self) -> &str {
+        self.config.api_base.as_deref().unwrap_or(DEFAULT_API_BASE)
+    }
+
+    /// Build the chat completions endpoint from the configured API base.
+    fn chat_completions_url(&self) -> String {
+        format!("{}/chat/completions", self.api_base())
+    }
+
+    /// Max context tokens for the configured model, so callers can trim a
+    /// prompt before sending it rather than finding out from an API error.
+    pub fn max_context_tokens(&self) -> u32 {
+        model_info(&self.config.model).max_context_tokens
+    }
+
+    /// Whether the configured model accepts `stream: true`.
+    fn supports_streaming(&self) -> bool {
+        model_info(&self.config.model).supports_streaming
+    }
+
+    /// Route a requested token limit to whichever field the configured
+    /// model expects: `max_tokens` for ordinary models, or
+    /// `max_completion_tokens` for reasoning-style models that reject
+    /// `max_tokens`.
+    fn token_limit_fields(&self, requested: Option<u32>) -> (Option<u32>, Option<u32>) {
+        if self.supports_streaming() {
+            (requested, None)
+        } else {
+            (None, requested)
+        }
+    }
+
+    /// Whether an HTTP status is transient and worth retrying: rate
+    /// limited, or a 500/502/503 upstream hiccup.
+    fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::INTERNAL_SERVER_ERROR
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+        )
+    }
+
+    /// POST a chat completion request, retrying on transient errors (429,
+    /// 500/502/503) with exponential backoff starting at
+    /// `retry_base_delay_ms` and doubling each attempt, honoring a
+    /// `Retry-After` header when the server sends one. Only used for
+    /// non-streaming requests - a partially retried stream can't be spliced
+    /// back together, so `complete_stream` fails fast instead.
+    async fn send_with_retry(&self, request: &ChatRequest) -> Result<reqwest::Response, AIError> {
+        let max_attempts = self.config.retry_count.max(1);
+        let mut delay = Duration::from_millis(self.config.retry_base_delay_ms);
+
+        for attempt in 1..=max_attempts {
+            let response = self
+                .client
+                .post(self.chat_completions_url())
+                .header("Authorization", format!("Bearer {}", self.config.api_key))
+                .json(request)
+                .send()
+                .await
+                .map_err(|e| AIError::Api(format!("Request failed: {}", e)))?;
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            if !Self::is_retryable_status(status) || attempt == max_attempts {
+                let body = response.text().await.unwrap_or_default();
+                return Err(AIError::Api(format!("API error ({}): {}", status, body)));
+            }
+
+            let wait = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(delay);
+            tokio::time::sleep(wait).await;
+            delay *= 2;
+        }
+
+        unreachable!("the last attempt above always returns")
+    }
+
+    /// Like `complete`, but sends a full message history - e.g. a system
+    /// prompt followed by prior user/assistant turns - instead of a single
+    /// hardcoded user message. This is what lets a caller like the AI
+    /// command palette give the model real conversation context.
+    pub async fn complete_chat(
+        &self,
+        messages: Vec<ChatMessage>,
+        opts: Option<CompletionOptions>,
+    ) -> Result<String, AIError> {
+        if self.config.api_key.is_empty() {
+            return Err(AIError::Config("API key not configured".to_string()));
+        }
+
+        let (max_tokens, max_completion_tokens) =
+            self.token_limit_fields(opts.as_ref().and_then(|o| o.max_tokens));
+        let request = ChatRequest {
+            model: self.config.model.clone(),
+            messages: messages
+                .into_iter()
+                .map(|m| Message {
+                    role: m.role.as_str().to_string(),
+                    content: m.content,
+                })
+                .collect(),
+            max_tokens,
+            max_completion_tokens,
+            temperature: opts.as_ref().and_then(|o| o.temperature),
+            stream: None,
+            tools: None,
+            tool_choice: None,
+        };
+
+        let response = self.send_with_retry(&request).await?;
+
+        let chat_response: ChatResponse = response
+            .json()
+            .await
+            .map_err(|e| AIError::Api(format!("Failed to parse response: {}", e)))?;
+
+        chat_response
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .ok_or_else(|| AIError::Api("No completion returned".to_string()))
+    }
 }
 
 #[derive(Serialize)]
@@ -105,10 +451,18 @@ struct ChatRequest {
     messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u32>,
+    /// Reasoning-style models (flagged `supports_streaming: false` in
+    /// `MODEL_TABLE`) reject `max_tokens` and expect this instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<&'static str>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -127,6 +481,22 @@ struct Choice {
     message: Message,
 }
 
+/// One `data: {json}` line of an SSE streaming response.
+#[derive(Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: Delta,
+}
+
+#[derive(Deserialize, Default)]
+struct Delta {
+    content: Option<String>,
+}
+
 #[async_trait]
 impl AIProvider for OpenAIProvider {
     async fn complete(
@@ -134,24 +504,154 @@ impl AIProvider for OpenAIProvider {
         prompt: &str,
         opts: Option<CompletionOptions>,
     ) -> Result<String, AIError> {
+        // A single-turn completion is just a one-message conversation.
+        self.complete_chat(vec![ChatMessage::user(prompt)], opts)
+            .await
+    }
+
+    async fn stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
+        // `complete_stream` already does real SSE streaming; reuse it here
+        // instead of duplicating the parsing loop.
+        self.complete_stream(prompt, opts).await
+    }
+
+    async fn complete_stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
+        if !self.supports_streaming() {
+            // Reasoning-style models reject `stream: true` outright; fall
+            // back to a single-shot completion and wrap it as a one-item
+            // stream so callers don't need to special-case the model.
+            let result = self
+                .complete_chat(vec![ChatMessage::user(prompt)], opts)
+                .await;
+            return Ok(Box::pin(futures::stream::once(async move { result })));
+        }
+
+        if self.config.api_key.is_empty() {
+            return Err(AIError::Config("API key not configured".to_string()));
+        }
+
+        let (max_tokens, max_completion_tokens) =
+            self.token_limit_fields(opts.as_ref().and_then(|o| o.max_tokens));
+        let request = ChatRequest {
+            model: self.config.model.clone(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            max_tokens,
+            max_completion_tokens,
+            temperature: opts.as_ref().and_then(|o| o.temperature),
+            stream: Some(true),
+            tools: None,
+            tool_choice: None,
+        };
+
+        let response = self
+            .client
+            .post(self.chat_completions_url())
+            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| AIError::Api(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(AIError::Api(format!(
+                "API error ({}): {}",
+                status, body
+            )));
+        }
+
+        // State threaded through `unfold`: the raw byte stream, a buffer of
+        // bytes not yet split into a full SSE line, and whether the stream
+        // has ended (`data: [DONE]` seen, an error, or the body closing).
+        // Kept as raw bytes rather than a `String` so a multi-byte UTF-8
+        // character straddling a chunk boundary gets reassembled before
+        // it's ever decoded, instead of being corrupted chunk-by-chunk.
+        let state = (response.bytes_stream(), Vec::<u8>::new(), false);
+
+        let deltas = futures::stream::unfold(state, |(mut bytes, mut buffer, done)| async move {
+            if done {
+                return None;
+            }
+
+            loop {
+                if let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                    let line = String::from_utf8_lossy(&buffer[..pos]).trim().to_string();
+                    buffer.drain(..=pos);
+
+                    let Some(payload) = line.strip_prefix("data: ") else {
+                        continue;
+                    };
+                    if payload == "[DONE]" {
+                        return None;
+                    }
+
+                    return match serde_json::from_str::<StreamChunk>(payload) {
+                        Ok(chunk) => match chunk.choices.into_iter().next().and_then(|c| c.delta.content) {
+                            Some(content) if !content.is_empty() => {
+                                Some((Ok(content), (bytes, buffer, false)))
+                            }
+                            _ => continue,
+                        },
+                        Err(e) => Some((
+                            Err(AIError::Api(format!("Failed to parse stream chunk: {}", e))),
+                            (bytes, buffer, true),
+                        )),
+                    };
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => {
+                        return Some((Err(AIError::Api(format!("Stream error: {}", e))), (bytes, buffer, true)));
+                    }
+                    None => return None,
+                }
+            }
+        });
+
+        Ok(Box::pin(deltas))
+    }
+
+    async fn complete_with_tool(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<ToolOutcome, AIError> {
         if self.config.api_key.is_empty() {
             return Err(AIError::Config("API key not configured".to_string()));
         }
 
+        let (max_tokens, max_completion_tokens) =
+            self.token_limit_fields(opts.as_ref().and_then(|o| o.max_tokens));
         let request = ChatRequest {
             model: self.config.model.clone(),
             messages: vec![Message {
                 role: "user".to_string(),
                 content: prompt.to_string(),
             }],
-            max_tokens: opts.as_ref().and_then(|o| o.max_tokens),
+            max_tokens,
+            max_completion_tokens,
             temperature: opts.as_ref().and_then(|o| o.temperature),
             stream: None,
+            tools: Some(vec![run_command_tool()]),
+            tool_choice: Some("auto"),
         };
 
         let response = self
             .client
-            .post(OPENAI_API_URL)
+            .post(self.chat_completions_url())
             .header("Authorization", format!("Bearer {}", self.config.api_key))
             .json(&request)
             .send()
@@ -167,29 +667,101 @@ impl AIProvider for OpenAIProvider {
             )));
         }
 
-        let chat_response: ChatResponse = response
+        let tool_response: ToolChatResponse = response
             .json()
             .await
             .map_err(|e| AIError::Api(format!("Failed to parse response: {}", e)))?;
 
-        chat_response
+        let message = tool_response
             .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| AIError::Api("No completion returned".to_string()))
+            .into_iter()
+            .next()
+            .map(|c| c.message)
+            .ok_or_else(|| AIError::Api("No completion returned".to_string()))?;
+
+        if let Some(call) = message.tool_calls.into_iter().flatten().next() {
+            let args: RunCommandArgs = serde_json::from_str(&call.function.arguments)
+                .map_err(|e| AIError::Api(format!("Failed to parse tool arguments: {}", e)))?;
+            return Ok(ToolOutcome::Command(ToolCall {
+                command: args.command,
+                explanation: args.explanation,
+                dangerous: args.dangerous,
+            }));
+        }
+
+        Ok(ToolOutcome::Text(message.content.unwrap_or_default()))
     }
+}
 
-    async fn stream(
-        &self,
-        prompt: &str,
-        opts: Option<CompletionOptions>,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError> {
-        // For now, return a simple implementation that completes and returns the full result
-        // A proper streaming implementation would use Server-Sent Events (SSE)
-        let result = self.complete(prompt, opts).await?;
+/// JSON schema for the `run_command` tool advertised to the model, so it
+/// can propose a shell command as structured data instead of a code fence.
+fn run_command_tool() -> serde_json::Value {
+    serde_json::json!({
+        "type": "function",
+        "function": {
+            "name": "run_command",
+            "description": "Propose a shell command for the user to run, with a short explanation.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The shell command to run"
+                    },
+                    "explanation": {
+                        "type": "string",
+                        "description": "One sentence explaining what the command does"
+                    },
+                    "dangerous": {
+                        "type": "boolean",
+                        "description": "True if the command is destructive or hard to reverse"
+                    }
+                },
+                "required": ["command", "explanation"]
+            }
+        }
+    })
+}
 
-        Ok(Box::pin(futures::stream::once(async move { Ok(result) })))
-    }
+#[derive(Deserialize)]
+struct ToolChatResponse {
+    choices: Vec<ToolChoice>,
+}
+
+#[derive(Deserialize)]
+struct ToolChoice {
+    message: ToolMessage,
+}
+
+#[derive(Deserialize, Default)]
+struct ToolMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ResponseToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct ResponseToolCall {
+    function: ResponseFunctionCall,
+}
+
+#[derive(Deserialize)]
+struct ResponseFunctionCall {
+    #[allow(dead_code)]
+    name: String,
+    arguments: String,
+}
+
+/// Arguments the model supplied to the `run_command` tool, as JSON text in
+/// `ResponseFunctionCall::arguments`.
+#[derive(Deserialize)]
+struct RunCommandArgs {
+    command: String,
+    #[serde(default)]
+    explanation: String,
+    #[serde(default)]
+    dangerous: bool,
 }
 
 #[cfg(test)]
@@ -208,8 +780,52 @@ mod tests {
         let config = OpenAIConfig {
             api_key: "test-key".to_string(),
             model: "gpt-4".to_string(),
+            api_base: None,
+            ..OpenAIConfig::default()
+        };
+        let _provider = OpenAIProvider::new(config).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_proxy_url_is_a_config_error() {
+        let config = OpenAIConfig {
+            proxy: Some("not a valid proxy url".to_string()),
+            ..OpenAIConfig::default()
+        };
+        let err = OpenAIProvider::new(config).unwrap_err();
+        assert!(matches!(err, AIError::Config(_)));
+    }
+
+    #[test]
+    fn test_valid_proxy_and_connect_timeout_build_successfully() {
+        let config = OpenAIConfig {
+            proxy: Some("http://localhost:8080".to_string()),
+            connect_timeout_secs: Some(5),
+            ..OpenAIConfig::default()
         };
-        let _provider = OpenAIProvider::new(config);
+        assert!(OpenAIProvider::new(config).is_ok());
+    }
+
+    #[test]
+    fn test_api_base_defaults_to_openai() {
+        let provider = OpenAIProvider::new(OpenAIConfig::default()).unwrap();
+        assert_eq!(provider.api_base(), DEFAULT_API_BASE);
+    }
+
+    #[test]
+    fn test_api_base_override_builds_chat_completions_url() {
+        let config = OpenAIConfig {
+            api_key: "test-key".to_string(),
+            model: "gpt-4".to_string(),
+            api_base: Some("http://localhost:8080/v1".to_string()),
+            ..OpenAIConfig::default()
+        };
+        let provider = OpenAIProvider::new(config).unwrap();
+        assert_eq!(provider.api_base(), "http://localhost:8080/v1");
+        assert_eq!(
+            provider.chat_completions_url(),
+            "http://localhost:8080/v1/chat/completions"
+        );
     }
 
     #[test]
@@ -221,8 +837,11 @@ mod tests {
                 content: "Hello".to_string(),
             }],
             max_tokens: Some(100),
+            max_completion_tokens: None,
             temperature: Some(0.7),
             stream: None,
+            tools: None,
+            tool_choice: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -252,4 +871,152 @@ mod tests {
             "Hello! How can I help you?"
         );
     }
+
+    #[test]
+    fn test_stream_chunk_deserialization() {
+        let json = r#"{"choices":[{"delta":{"content":"Hel"}}]}"#;
+        let chunk: StreamChunk = serde_json::from_str(json).unwrap();
+        assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("Hel"));
+    }
+
+    #[test]
+    fn test_stream_chunk_with_no_content_delta() {
+        // The first chunk of a stream carries only a role, no content.
+        let json = r#"{"choices":[{"delta":{"role":"assistant"}}]}"#;
+        let chunk: StreamChunk = serde_json::from_str(json).unwrap();
+        assert_eq!(chunk.choices[0].delta.content, None);
+    }
+
+    #[test]
+    fn test_tool_response_with_tool_call() {
+        let json = r#"{
+            "choices": [
+                {
+                    "message": {
+                        "content": null,
+                        "tool_calls": [
+                            {
+                                "function": {
+                                    "name": "run_command",
+                                    "arguments": "{\"command\":\"ls -la\",\"explanation\":\"List files\",\"dangerous\":false}"
+                                }
+                            }
+                        ]
+                    }
+                }
+            ]
+        }"#;
+
+        let response: ToolChatResponse = serde_json::from_str(json).unwrap();
+        let message = &response.choices[0].message;
+        assert!(message.content.is_none());
+
+        let call = &message.tool_calls.as_ref().unwrap()[0];
+        let args: RunCommandArgs = serde_json::from_str(&call.function.arguments).unwrap();
+        assert_eq!(args.command, "ls -la");
+        assert_eq!(args.explanation, "List files");
+        assert!(!args.dangerous);
+    }
+
+    #[test]
+    fn test_tool_response_with_plain_text() {
+        let json = r#"{"choices":[{"message":{"content":"Hello there"}}]}"#;
+        let response: ToolChatResponse = serde_json::from_str(json).unwrap();
+        let message = &response.choices[0].message;
+        assert_eq!(message.content.as_deref(), Some("Hello there"));
+        assert!(message.tool_calls.is_none());
+    }
+
+    #[test]
+    fn test_max_context_tokens_looks_up_known_model() {
+        let config = OpenAIConfig {
+            model: "gpt-4-turbo".to_string(),
+            ..OpenAIConfig::default()
+        };
+        let provider = OpenAIProvider::new(config).unwrap();
+        assert_eq!(provider.max_context_tokens(), 128_000);
+    }
+
+    #[test]
+    fn test_max_context_tokens_falls_back_for_unknown_model() {
+        let config = OpenAIConfig {
+            model: "some-future-model".to_string(),
+            ..OpenAIConfig::default()
+        };
+        let provider = OpenAIProvider::new(config).unwrap();
+        assert_eq!(provider.max_context_tokens(), DEFAULT_MODEL_INFO.max_context_tokens);
+    }
+
+    #[test]
+    fn test_reasoning_model_does_not_support_streaming() {
+        assert!(!model_info("o1").supports_streaming);
+        assert!(model_info("gpt-4o").supports_streaming);
+    }
+
+    #[test]
+    fn test_token_limit_fields_routes_by_streaming_support() {
+        let streaming = OpenAIProvider::new(OpenAIConfig {
+            model: "gpt-4o".to_string(),
+            ..OpenAIConfig::default()
+        })
+        .unwrap();
+        assert_eq!(streaming.token_limit_fields(Some(100)), (Some(100), None));
+
+        let reasoning = OpenAIProvider::new(OpenAIConfig {
+            model: "o1".to_string(),
+            ..OpenAIConfig::default()
+        })
+        .unwrap();
+        assert_eq!(reasoning.token_limit_fields(Some(100)), (None, Some(100)));
+    }
+
+    #[test]
+    fn test_chat_message_constructors_set_expected_role() {
+        assert_eq!(ChatMessage::system("be terse").role, Role::System);
+        assert_eq!(ChatMessage::user("hi").role, Role::User);
+        assert_eq!(ChatMessage::assistant("hello").role, Role::Assistant);
+    }
+
+    #[test]
+    fn test_role_as_str_matches_openai_wire_format() {
+        assert_eq!(Role::System.as_str(), "system");
+        assert_eq!(Role::User.as_str(), "user");
+        assert_eq!(Role::Assistant.as_str(), "assistant");
+    }
+
+    #[test]
+    fn test_retry_defaults() {
+        let config = OpenAIConfig::default();
+        assert_eq!(config.retry_count, DEFAULT_RETRY_COUNT);
+        assert_eq!(config.retry_base_delay_ms, DEFAULT_RETRY_BASE_DELAY_MS);
+    }
+
+    #[test]
+    fn test_is_retryable_status_for_rate_limit_and_5xx() {
+        assert!(OpenAIProvider::is_retryable_status(
+            StatusCode::TOO_MANY_REQUESTS
+        ));
+        assert!(OpenAIProvider::is_retryable_status(
+            StatusCode::INTERNAL_SERVER_ERROR
+        ));
+        assert!(OpenAIProvider::is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(OpenAIProvider::is_retryable_status(
+            StatusCode::SERVICE_UNAVAILABLE
+        ));
+    }
+
+    #[test]
+    fn test_is_retryable_status_excludes_client_errors() {
+        assert!(!OpenAIProvider::is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!OpenAIProvider::is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!OpenAIProvider::is_retryable_status(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_run_command_tool_schema_has_required_fields() {
+        let tool = run_command_tool();
+        let required = tool["function"]["parameters"]["required"].as_array().unwrap();
+        assert!(required.contains(&serde_json::json!("command")));
+        assert!(required.contains(&serde_json::json!("explanation")));
+    }
 }
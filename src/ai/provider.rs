@@ -1,5 +1,7 @@
 //! AI Provider abstraction for BYOK support
 
+use std::pin::Pin;
+
 use async_trait::async_trait;
 use futures::Stream;
 use thiserror::Error;
@@ -20,12 +22,53 @@ pub struct CompletionOptions {
     pub temperature: Option<f32>,
 }
 
+/// A command the model proposes running, parsed from a structured
+/// `run_command` tool call rather than scraped from a markdown fence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub command: String,
+    pub explanation: String,
+    pub dangerous: bool,
+}
+
+/// Result of a tool-aware completion: either the model answered in prose,
+/// or it invoked the advertised `run_command` tool.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolOutcome {
+    Text(String),
+    Command(ToolCall),
+}
+
 /// Trait for AI providers - allows BYOK
 #[async_trait]
 pub trait AIProvider: Send + Sync {
     /// Get a completion from the AI
     async fn complete(&self, prompt: &str, opts: Option<CompletionOptions>) -> Result<String, AIError>;
-    
+
     /// Stream a completion (for better UX)
-    async fn stream(&self, prompt: &str, opts: Option<CompletionOptions>) -> Result<impl Stream<Item = Result<String, AIError>>, AIError>;
+    async fn stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError>;
+
+    /// Stream a completion token-by-token, yielding each incremental delta
+    /// as it arrives rather than blocking until the full response is back.
+    /// Lets callers (e.g. the AI command palette) show the reply as it's
+    /// generated instead of staring at "Processing" for the whole request.
+    async fn complete_stream(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, AIError>> + Send>>, AIError>;
+
+    /// Request a completion with the `run_command` tool advertised, so the
+    /// model can propose an executable command as structured data instead
+    /// of prose wrapped in a code fence. Providers that don't support tool
+    /// calling yet should fall back to a plain `ToolOutcome::Text`.
+    async fn complete_with_tool(
+        &self,
+        prompt: &str,
+        opts: Option<CompletionOptions>,
+    ) -> Result<ToolOutcome, AIError>;
 }
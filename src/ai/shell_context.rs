@@ -0,0 +1,219 @@
+//! Ambient shell context fed into AI prompts
+//!
+//! The palette otherwise only sees the user's raw question, which makes
+//! things like "suggest fix for error" unanswerable without the user
+//! re-typing the command and its output by hand. `ShellContext` captures
+//! the cheaply-available ambient state - cwd, git branch/dirty flag, and
+//! the last command run plus its exit code and stderr tail - so it can be
+//! prepended to the prompt as a compact system section.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Which `ShellContext` fields get folded into the prompt. All default to
+/// on; a user who doesn't want their cwd or git status sent to a remote
+/// provider can disable just that field.
+#[derive(Debug, Clone)]
+pub struct ShellContextConfig {
+    pub include_cwd: bool,
+    pub include_git: bool,
+    pub include_last_command: bool,
+    pub include_last_stderr: bool,
+}
+
+impl Default for ShellContextConfig {
+    fn default() -> Self {
+        Self {
+            include_cwd: true,
+            include_git: true,
+            include_last_command: true,
+            include_last_stderr: true,
+        }
+    }
+}
+
+/// Ambient state captured from the shell, used to ground AI responses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShellContext {
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+    pub git_dirty: Option<bool>,
+    pub last_command: Option<String>,
+    pub last_exit_code: Option<i32>,
+    pub last_stderr: Vec<String>,
+}
+
+impl ShellContext {
+    /// Capture cwd and git state for `dir`. Last-command fields are left
+    /// empty; set them separately with `record_last_command` as commands
+    /// complete.
+    pub fn capture(dir: &Path) -> Self {
+        Self {
+            cwd: Some(dir.display().to_string()),
+            git_branch: git_branch(dir),
+            git_dirty: git_dirty(dir),
+            last_command: None,
+            last_exit_code: None,
+            last_stderr: Vec::new(),
+        }
+    }
+
+    /// Record the most recently completed command so it can be referenced
+    /// by follow-up questions like "suggest fix for error".
+    pub fn record_last_command(&mut self, command: String, exit_code: i32, stderr: Vec<String>) {
+        self.last_command = Some(command);
+        self.last_exit_code = Some(exit_code);
+        self.last_stderr = stderr;
+    }
+
+    /// Whether the last recorded command failed.
+    pub fn last_command_failed(&self) -> bool {
+        self.last_exit_code.is_some_and(|code| code != 0)
+    }
+
+    /// Build a compact "Context:" section for the prompt, honoring
+    /// `config`. Disabled or empty fields are omitted entirely rather than
+    /// emitted as blank lines, and an entirely empty context yields an
+    /// empty string so the prompt isn't padded with a header and nothing
+    /// under it.
+    pub fn to_prompt_section(&self, config: &ShellContextConfig) -> String {
+        let mut lines = Vec::new();
+
+        if config.include_cwd {
+            if let Some(cwd) = &self.cwd {
+                lines.push(format!("Current directory: {}", cwd));
+            }
+        }
+
+        if config.include_git {
+            if let Some(branch) = &self.git_branch {
+                let dirty = match self.git_dirty {
+                    Some(true) => " (dirty)",
+                    Some(false) => "",
+                    None => "",
+                };
+                lines.push(format!("Git branch: {}{}", branch, dirty));
+            }
+        }
+
+        if config.include_last_command {
+            if let Some(command) = &self.last_command {
+                lines.push(format!("Last command: {}", command));
+                if let Some(code) = self.last_exit_code {
+                    lines.push(format!("Exit code: {}", code));
+                }
+            }
+        }
+
+        if config.include_last_stderr && !self.last_stderr.is_empty() {
+            lines.push(format!("Last stderr:\n{}", self.last_stderr.join("\n")));
+        }
+
+        if lines.is_empty() {
+            return String::new();
+        }
+
+        format!("Context:\n{}\n", lines.join("\n"))
+    }
+}
+
+fn git_branch(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch)
+    }
+}
+
+fn git_dirty(dir: &Path) -> Option<bool> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(!output.stdout.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_all_enabled() {
+        let config = ShellContextConfig::default();
+        assert!(config.include_cwd);
+        assert!(config.include_git);
+        assert!(config.include_last_command);
+        assert!(config.include_last_stderr);
+    }
+
+    #[test]
+    fn test_empty_context_yields_empty_section() {
+        let context = ShellContext::default();
+        assert_eq!(context.to_prompt_section(&ShellContextConfig::default()), "");
+    }
+
+    #[test]
+    fn test_prompt_section_omits_disabled_fields() {
+        let mut context = ShellContext {
+            cwd: Some("/home/user/project".to_string()),
+            git_branch: Some("main".to_string()),
+            git_dirty: Some(true),
+            ..Default::default()
+        };
+        context.record_last_command("cargo test".to_string(), 1, vec!["error: fail".to_string()]);
+
+        let config = ShellContextConfig {
+            include_cwd: false,
+            include_git: true,
+            include_last_command: true,
+            include_last_stderr: false,
+        };
+
+        let section = context.to_prompt_section(&config);
+        assert!(!section.contains("Current directory"));
+        assert!(section.contains("Git branch: main (dirty)"));
+        assert!(section.contains("Last command: cargo test"));
+        assert!(section.contains("Exit code: 1"));
+        assert!(!section.contains("Last stderr"));
+    }
+
+    #[test]
+    fn test_prompt_section_omits_empty_fields_without_disabling() {
+        let context = ShellContext {
+            cwd: Some("/tmp".to_string()),
+            ..Default::default()
+        };
+
+        let section = context.to_prompt_section(&ShellContextConfig::default());
+        assert_eq!(section, "Context:\nCurrent directory: /tmp\n");
+    }
+
+    #[test]
+    fn test_last_command_failed() {
+        let mut context = ShellContext::default();
+        assert!(!context.last_command_failed());
+
+        context.record_last_command("ls".to_string(), 0, Vec::new());
+        assert!(!context.last_command_failed());
+
+        context.record_last_command("false".to_string(), 1, Vec::new());
+        assert!(context.last_command_failed());
+    }
+}
@@ -0,0 +1,460 @@
+//! Keyset subsystem: named keybinding presets loaded from YAML
+//!
+//! A `Keyset` maps `Action` names to key chords (e.g.
+//! `terminal:split_pane_horizontal: cmd-d`), the same "action name -> chord
+//! string" shape `config::settings::ThemeConfig::palettes` uses for named
+//! color maps. `default_keyset`/`emacs_keyset` ship as built-in presets;
+//! `load_keyset` lets a user's own YAML file replace individual
+//! bindings in whichever preset they pick. `conflicts` flags two actions
+//! bound to the same chord, and `KeyChord::parse` handles the modifier
+//! combinations (`ctrl`, `alt`/`option`, `shift`, `cmd`/`super`/`meta`)
+//! needed to write one preset that reads naturally on every platform.
+//!
+//! `ui::keyset_dispatch` is the decode-time half of this: translating a
+//! `winit` key event into the `KeyChord` that gets looked up here.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Every UI/terminal command that can be bound to a key chord. Variants
+/// are grouped by the subsystem they act on, mirroring `Action::name`'s
+/// `"subsystem:command"` string form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    SplitPaneHorizontal,
+    SplitPaneVertical,
+    ClosePane,
+    FocusNextPane,
+    FocusPreviousPane,
+    NewTab,
+    CloseTab,
+    Copy,
+    Paste,
+    Search,
+    ScrollPageUp,
+    ScrollPageDown,
+    ScrollToTop,
+    ScrollToBottom,
+    ToggleViMode,
+    ToggleHintMode,
+    OpenAiPalette,
+    OpenWorkflowsPalette,
+}
+
+/// Single source of truth for `Action`'s string name, used by both
+/// `Action::name` and `Action::from_name` so the two can never drift.
+const ACTIONS: &[(Action, &str)] = &[
+    (Action::SplitPaneHorizontal, "terminal:split_pane_horizontal"),
+    (Action::SplitPaneVertical, "terminal:split_pane_vertical"),
+    (Action::ClosePane, "terminal:close_pane"),
+    (Action::FocusNextPane, "terminal:focus_next_pane"),
+    (Action::FocusPreviousPane, "terminal:focus_previous_pane"),
+    (Action::NewTab, "terminal:new_tab"),
+    (Action::CloseTab, "terminal:close_tab"),
+    (Action::Copy, "terminal:copy"),
+    (Action::Paste, "terminal:paste"),
+    (Action::Search, "terminal:search"),
+    (Action::ScrollPageUp, "terminal:scroll_page_up"),
+    (Action::ScrollPageDown, "terminal:scroll_page_down"),
+    (Action::ScrollToTop, "terminal:scroll_to_top"),
+    (Action::ScrollToBottom, "terminal:scroll_to_bottom"),
+    (Action::ToggleViMode, "ui:toggle_vi_mode"),
+    (Action::ToggleHintMode, "ui:toggle_hint_mode"),
+    (Action::OpenAiPalette, "ui:open_ai_palette"),
+    (Action::OpenWorkflowsPalette, "ui:open_workflows_palette"),
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|(action, _)| *action == self).map(|(_, name)| *name).unwrap_or("unknown")
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTIONS.iter().find(|(_, n)| *n == name).map(|(action, _)| *action)
+    }
+}
+
+/// The modifier keys held down as part of a chord. `cmd`/`meta` both set
+/// `super_key`; `KeyChord::matches` is what actually treats `super_key` as
+/// interchangeable with `control` on non-macOS platforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ChordModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+/// A parsed key chord: zero or more modifiers plus a single key, e.g.
+/// `ctrl-shift-f` or `cmd-d`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub modifiers: ChordModifiers,
+    /// The chord's key, lowercased: a single character (`"d"`, `"/"`) or a
+    /// named key (`"enter"`, `"tab"`, `"arrowleft"`).
+    pub key: String,
+}
+
+impl KeyChord {
+    /// Parse a `-`- or `+`-separated chord string like `"cmd-shift-d"`.
+    /// The key is whichever token isn't a recognized modifier name, in any
+    /// position; a spec with no key token or with two non-modifier tokens
+    /// is rejected.
+    pub fn parse(spec: &str) -> Result<KeyChord> {
+        let tokens: Vec<&str> = spec.split(['-', '+']).map(str::trim).filter(|t| !t.is_empty()).collect();
+        if tokens.is_empty() {
+            anyhow::bail!("empty key chord");
+        }
+
+        let mut modifiers = ChordModifiers::default();
+        let mut key = None;
+        for token in &tokens {
+            match token.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.control = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "cmd" | "super" | "meta" | "win" => modifiers.super_key = true,
+                other => {
+                    if key.is_some() {
+                        anyhow::bail!("chord {:?} has more than one non-modifier key", spec);
+                    }
+                    key = Some(other.to_string());
+                }
+            }
+        }
+
+        Ok(KeyChord { modifiers, key: key.with_context(|| format!("chord {:?} has no key, only modifiers", spec))? })
+    }
+
+    /// Whether this chord matches `other`, treating `super_key` (cmd/win)
+    /// as interchangeable with `control` when `is_macos` is false - the
+    /// cross-platform equivalence that lets one preset's `cmd-d` resolve
+    /// correctly on both macOS and Linux/Windows.
+    pub fn matches(&self, other: &KeyChord, is_macos: bool) -> bool {
+        if self.key != other.key {
+            return false;
+        }
+        if self.modifiers.shift != other.modifiers.shift || self.modifiers.alt != other.modifiers.alt {
+            return false;
+        }
+        if is_macos {
+            self.modifiers.control == other.modifiers.control && self.modifiers.super_key == other.modifiers.super_key
+        } else {
+            // On non-macOS platforms a chord's `cmd`/`super` is satisfied
+            // by either physical Ctrl or Super being held.
+            let primary_self = self.modifiers.control || self.modifiers.super_key;
+            let primary_other = other.modifiers.control || other.modifiers.super_key;
+            primary_self == primary_other
+        }
+    }
+}
+
+impl std::fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.control {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "shift-")?;
+        }
+        if self.modifiers.super_key {
+            write!(f, "cmd-")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// A named collection of action -> chord bindings, as loaded from YAML.
+/// Bindings are kept as raw strings (rather than a `HashMap<Action,
+/// KeyChord>`) so one malformed entry can be reported without losing the
+/// rest of the preset - the same reason `search::workflows::LoadResult`
+/// keeps per-file errors separate from the successfully loaded items.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Keyset {
+    pub name: String,
+    /// Action name -> chord spec string, e.g. `"terminal:copy": "cmd-c"`.
+    pub bindings: HashMap<String, String>,
+}
+
+impl Keyset {
+    /// Parse every binding into `(Action, KeyChord)`, collecting
+    /// `(action_name, message)` for entries that name an unknown action or
+    /// an unparseable chord rather than failing the whole keyset.
+    pub fn resolve(&self) -> (HashMap<Action, KeyChord>, Vec<(String, String)>) {
+        let mut resolved = HashMap::new();
+        let mut errors = Vec::new();
+
+        for (name, chord_spec) in &self.bindings {
+            let Some(action) = Action::from_name(name) else {
+                errors.push((name.clone(), format!("unknown action {:?}", name)));
+                continue;
+            };
+            match KeyChord::parse(chord_spec) {
+                Ok(chord) => {
+                    resolved.insert(action, chord);
+                }
+                Err(e) => errors.push((name.clone(), e.to_string())),
+            }
+        }
+
+        (resolved, errors)
+    }
+
+    /// Overlay `overrides`' bindings on top of this keyset's, replacing
+    /// any action name both define.
+    pub fn with_overrides(mut self, overrides: &Keyset) -> Keyset {
+        for (name, chord) in &overrides.bindings {
+            self.bindings.insert(name.clone(), chord.clone());
+        }
+        self
+    }
+}
+
+/// Every chord bound to more than one action, paired with the conflicting
+/// actions, so a doubly bound chord can be reported rather than silently
+/// letting the first match win.
+pub fn conflicts(bindings: &HashMap<Action, KeyChord>, is_macos: bool) -> Vec<(KeyChord, Vec<Action>)> {
+    let mut groups: Vec<(KeyChord, Vec<Action>)> = Vec::new();
+    for (&action, chord) in bindings {
+        match groups.iter_mut().find(|(existing, _)| existing.matches(chord, is_macos)) {
+            Some((_, actions)) => actions.push(action),
+            None => groups.push((chord.clone(), vec![action])),
+        }
+    }
+    groups.retain(|(_, actions)| actions.len() > 1);
+    groups
+}
+
+/// The default keyset, modeled on the bindings the terminal already uses
+/// in practice (Warp/most terminal emulators' conventions).
+pub fn default_keyset() -> Keyset {
+    Keyset {
+        name: "default".to_string(),
+        bindings: HashMap::from([
+            ("terminal:split_pane_horizontal".to_string(), "cmd-d".to_string()),
+            ("terminal:split_pane_vertical".to_string(), "cmd-shift-d".to_string()),
+            ("terminal:close_pane".to_string(), "cmd-w".to_string()),
+            ("terminal:focus_next_pane".to_string(), "cmd-]".to_string()),
+            ("terminal:focus_previous_pane".to_string(), "cmd-[".to_string()),
+            ("terminal:new_tab".to_string(), "cmd-t".to_string()),
+            ("terminal:close_tab".to_string(), "cmd-shift-w".to_string()),
+            ("terminal:copy".to_string(), "cmd-c".to_string()),
+            ("terminal:paste".to_string(), "cmd-v".to_string()),
+            ("terminal:search".to_string(), "cmd-f".to_string()),
+            ("terminal:scroll_page_up".to_string(), "shift-pageup".to_string()),
+            ("terminal:scroll_page_down".to_string(), "shift-pagedown".to_string()),
+            ("ui:toggle_vi_mode".to_string(), "ctrl-shift-space".to_string()),
+            ("ui:open_ai_palette".to_string(), "cmd-i".to_string()),
+            ("ui:open_workflows_palette".to_string(), "cmd-shift-r".to_string()),
+        ]),
+    }
+}
+
+/// An emacs-like keyset: the same actions, bound to emacs' conventional
+/// chords wherever one exists (`C-x 0`-style multi-key chords aren't
+/// representable by a single `KeyChord`, so these stay single-chord
+/// approximations, e.g. `ctrl-x` for close-pane rather than `C-x 0`).
+pub fn emacs_keyset() -> Keyset {
+    Keyset {
+        name: "emacs".to_string(),
+        bindings: HashMap::from([
+            ("terminal:split_pane_horizontal".to_string(), "ctrl-2".to_string()),
+            ("terminal:split_pane_vertical".to_string(), "ctrl-3".to_string()),
+            ("terminal:close_pane".to_string(), "ctrl-x".to_string()),
+            ("terminal:focus_next_pane".to_string(), "ctrl-o".to_string()),
+            ("terminal:copy".to_string(), "alt-w".to_string()),
+            ("terminal:paste".to_string(), "ctrl-y".to_string()),
+            ("terminal:search".to_string(), "ctrl-s".to_string()),
+            ("terminal:scroll_page_up".to_string(), "alt-v".to_string()),
+            ("terminal:scroll_page_down".to_string(), "ctrl-v".to_string()),
+            ("ui:toggle_vi_mode".to_string(), "ctrl-shift-space".to_string()),
+        ]),
+    }
+}
+
+/// Look up a built-in preset by name (`"default"` or `"emacs"`).
+pub fn builtin_keyset(name: &str) -> Option<Keyset> {
+    match name {
+        "default" => Some(default_keyset()),
+        "emacs" => Some(emacs_keyset()),
+        _ => None,
+    }
+}
+
+/// The user's keysets directory (`~/.config/warp-foss/keysets/`), honoring
+/// `$XDG_CONFIG_HOME` the same way `config::Config` does.
+pub fn user_keysets_dir() -> Result<PathBuf> {
+    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+        return Ok(PathBuf::from(xdg_config).join("warp-foss").join("keysets"));
+    }
+
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home.join(".config").join("warp-foss").join("keysets"))
+}
+
+/// Load a user's override bindings for `preset_name` from
+/// `<user_keysets_dir>/<preset_name>.yaml`, if present, and layer them on
+/// top of the matching built-in preset (or an empty keyset, if
+/// `preset_name` isn't built in - a user can define an entirely custom
+/// keyset this way, not just tweak a built-in one).
+pub fn load_keyset(preset_name: &str, dir: &Path) -> Result<Keyset> {
+    let base = builtin_keyset(preset_name).unwrap_or_else(|| Keyset { name: preset_name.to_string(), bindings: HashMap::new() });
+
+    let path = dir.join(format!("{preset_name}.yaml"));
+    if !path.exists() {
+        return Ok(base);
+    }
+
+    let contents = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let overrides: Keyset = serde_yaml::from_str(&contents).with_context(|| format!("Failed to parse {:?}", path))?;
+    Ok(base.with_overrides(&overrides))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_name_and_from_name_roundtrip() {
+        for &(action, name) in ACTIONS {
+            assert_eq!(Action::from_name(name), Some(action));
+            assert_eq!(action.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_chord_parse_reads_modifiers_and_key() {
+        let chord = KeyChord::parse("cmd-shift-d").unwrap();
+        assert!(chord.modifiers.super_key);
+        assert!(chord.modifiers.shift);
+        assert!(!chord.modifiers.control);
+        assert_eq!(chord.key, "d");
+    }
+
+    #[test]
+    fn test_chord_parse_accepts_plus_separator_and_aliases() {
+        let chord = KeyChord::parse("Control+Option+f").unwrap();
+        assert!(chord.modifiers.control);
+        assert!(chord.modifiers.alt);
+        assert_eq!(chord.key, "f");
+    }
+
+    #[test]
+    fn test_chord_parse_rejects_empty_or_modifier_only_spec() {
+        assert!(KeyChord::parse("").is_err());
+        assert!(KeyChord::parse("ctrl-shift").is_err());
+    }
+
+    #[test]
+    fn test_chord_parse_rejects_two_non_modifier_tokens() {
+        assert!(KeyChord::parse("a-b").is_err());
+    }
+
+    #[test]
+    fn test_chord_matches_treats_cmd_and_ctrl_as_interchangeable_off_macos() {
+        let cmd_d = KeyChord::parse("cmd-d").unwrap();
+        let ctrl_d = KeyChord::parse("ctrl-d").unwrap();
+
+        assert!(cmd_d.matches(&ctrl_d, false));
+        assert!(!cmd_d.matches(&ctrl_d, true));
+    }
+
+    #[test]
+    fn test_resolve_reports_unknown_action_without_dropping_valid_bindings() {
+        let keyset = Keyset {
+            name: "test".to_string(),
+            bindings: HashMap::from([
+                ("terminal:copy".to_string(), "cmd-c".to_string()),
+                ("not:a:real:action".to_string(), "cmd-z".to_string()),
+            ]),
+        };
+
+        let (resolved, errors) = keyset.resolve();
+        assert_eq!(resolved.get(&Action::Copy).unwrap().key, "c");
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_reports_unparseable_chord() {
+        let keyset = Keyset {
+            name: "test".to_string(),
+            bindings: HashMap::from([("terminal:copy".to_string(), "".to_string())]),
+        };
+
+        let (resolved, errors) = keyset.resolve();
+        assert!(resolved.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_with_overrides_replaces_only_named_bindings() {
+        let base = default_keyset();
+        let overrides = Keyset {
+            name: "custom".to_string(),
+            bindings: HashMap::from([("terminal:copy".to_string(), "ctrl-insert".to_string())]),
+        };
+
+        let merged = base.clone().with_overrides(&overrides);
+        assert_eq!(merged.bindings.get("terminal:copy").unwrap(), "ctrl-insert");
+        assert_eq!(merged.bindings.get("terminal:paste"), base.bindings.get("terminal:paste"));
+    }
+
+    #[test]
+    fn test_conflicts_flags_two_actions_on_the_same_chord() {
+        let bindings = HashMap::from([
+            (Action::Copy, KeyChord::parse("cmd-c").unwrap()),
+            (Action::Search, KeyChord::parse("cmd-c").unwrap()),
+            (Action::Paste, KeyChord::parse("cmd-v").unwrap()),
+        ]);
+
+        let found = conflicts(&bindings, true);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.len(), 2);
+    }
+
+    #[test]
+    fn test_default_and_emacs_keysets_resolve_without_errors() {
+        for keyset in [default_keyset(), emacs_keyset()] {
+            let (_, errors) = keyset.resolve();
+            assert!(errors.is_empty(), "{:?}: {:?}", keyset.name, errors);
+        }
+    }
+
+    #[test]
+    fn test_load_keyset_falls_back_to_builtin_when_no_override_file() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-keyset-no-override-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let keyset = load_keyset("default", &scratch).unwrap();
+        assert_eq!(keyset.name, "default");
+        assert_eq!(keyset.bindings, default_keyset().bindings);
+    }
+
+    #[test]
+    fn test_load_keyset_layers_user_overrides_on_the_builtin_preset() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-keyset-override-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+        std::fs::write(
+            scratch.join("default.yaml"),
+            "name: default\nbindings:\n  terminal:copy: ctrl-insert\n",
+        )
+        .unwrap();
+
+        let keyset = load_keyset("default", &scratch).unwrap();
+        std::fs::remove_dir_all(&scratch).ok();
+
+        assert_eq!(keyset.bindings.get("terminal:copy").unwrap(), "ctrl-insert");
+        assert_eq!(keyset.bindings.get("terminal:paste"), default_keyset().bindings.get("terminal:paste"));
+    }
+}
@@ -0,0 +1,4 @@
+//! Application configuration, loaded from TOML.
+
+pub mod keyset;
+pub mod settings;
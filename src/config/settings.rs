@@ -1,8 +1,15 @@
 //! Application settings loaded from config file
 
+use crate::ui::layout::LayoutTemplate;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the project-local config file `load_with_overrides` looks for
+/// walking up from the working directory, analogous to how editors and
+/// formatters resolve per-project settings files.
+const PROJECT_CONFIG_FILENAME: &str = ".warp-foss.toml";
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +20,15 @@ pub struct Config {
     /// Font settings
     #[serde(default)]
     pub font: FontConfig,
+    /// Named startup layouts, keyed by name, built via `LayoutTree::from_template`
+    #[serde(default)]
+    pub layouts: HashMap<String, LayoutTemplate>,
+    /// Theme: named color palettes and per-segment styling
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Status bar content: which segments render, and in what order
+    #[serde(default)]
+    pub status_bar: StatusBarConfig,
 }
 
 impl Default for Config {
@@ -20,6 +36,32 @@ impl Default for Config {
         Self {
             terminal: TerminalConfig::default(),
             font: FontConfig::default(),
+            layouts: HashMap::new(),
+            theme: ThemeConfig::default(),
+            status_bar: StatusBarConfig::default(),
+        }
+    }
+}
+
+/// Status bar content configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusBarConfig {
+    /// Format string composed of literal text and `$name` segment tokens,
+    /// e.g. `"$directory$git_branch$git_status"`. Unknown segment names
+    /// pass through as literal text so the format stays forward-compatible
+    /// with segments this build doesn't ship yet.
+    #[serde(default = "default_status_bar_format")]
+    pub format: String,
+}
+
+fn default_status_bar_format() -> String {
+    "$directory $git_branch$git_status".to_string()
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            format: default_status_bar_format(),
         }
     }
 }
@@ -42,6 +84,12 @@ pub struct TerminalConfig {
     /// Environment variables to set
     #[serde(default)]
     pub env: Vec<(String, String)>,
+    /// Shape the cursor is rendered as
+    #[serde(default = "default_cursor_style")]
+    pub cursor_style: CursorStyle,
+    /// Whether the cursor blinks while idle
+    #[serde(default = "default_cursor_blink")]
+    pub cursor_blink: bool,
 }
 
 fn default_cols() -> u16 {
@@ -52,6 +100,14 @@ fn default_rows() -> u16 {
     40
 }
 
+fn default_cursor_style() -> CursorStyle {
+    CursorStyle::Block
+}
+
+fn default_cursor_blink() -> bool {
+    true
+}
+
 impl Default for TerminalConfig {
     fn default() -> Self {
         Self {
@@ -60,10 +116,27 @@ impl Default for TerminalConfig {
             rows: default_rows(),
             working_dir: None,
             env: Vec::new(),
+            cursor_style: default_cursor_style(),
+            cursor_blink: default_cursor_blink(),
         }
     }
 }
 
+/// Shape the terminal cursor is rendered as.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorStyle {
+    /// A filled rectangle covering the whole cell, with the glyph underneath
+    /// drawn in the background color so it stays legible.
+    Block,
+    /// Just the outline of the block - the shape unfocused panes use.
+    HollowBlock,
+    /// A thin vertical bar at the cell's left edge.
+    Beam,
+    /// A thin horizontal bar at the cell's bottom edge.
+    Underline,
+}
+
 /// Font configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontConfig {
@@ -84,6 +157,135 @@ impl Default for FontConfig {
     }
 }
 
+/// Theme configuration: reusable named color palettes, one of which is
+/// selected to resolve the per-segment style keys against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Name of the selected palette (a key into `palettes`), e.g. `"nord"`.
+    #[serde(default)]
+    pub palette: Option<String>,
+    /// Named palettes: palette name -> (color name -> hex/ANSI value).
+    #[serde(default)]
+    pub palettes: HashMap<String, HashMap<String, String>>,
+    /// Status bar segment styles.
+    #[serde(default)]
+    pub status_bar: StatusBarStyle,
+    /// Name of the active terminal grid theme - a YAML file in
+    /// `ui::theme::user_themes_dir()` loaded by a `ui::theme::ThemeRegistry`,
+    /// not one of `palettes` above. `None` leaves the grid on the built-in
+    /// XTerm default colors.
+    #[serde(default)]
+    pub active_theme: Option<String>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            palette: None,
+            palettes: HashMap::new(),
+            status_bar: StatusBarStyle::default(),
+            active_theme: None,
+        }
+    }
+}
+
+impl ThemeConfig {
+    /// Resolve the selected palette and the status bar's segment style
+    /// keys into concrete color values. A style value that names a color
+    /// in the selected palette is substituted with that color; anything
+    /// else (a raw hex/ANSI value, or no palette selected) passes through
+    /// unchanged.
+    ///
+    /// If `palette` names a palette that isn't in `palettes`, warns and
+    /// falls back to treating every style value as a raw color.
+    pub fn resolve_status_bar_style(&self) -> ResolvedStatusBarStyle {
+        let palette = match &self.palette {
+            Some(name) => match self.palettes.get(name) {
+                Some(colors) => Some(colors),
+                None => {
+                    tracing::warn!(
+                        "Theme palette {:?} not found, falling back to default styling",
+                        name
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let resolve = |value: &Option<String>| -> Option<String> {
+            value.as_ref().map(|raw| {
+                palette
+                    .and_then(|colors| colors.get(raw).cloned())
+                    .unwrap_or_else(|| raw.clone())
+            })
+        };
+
+        ResolvedStatusBarStyle {
+            directory_fg: resolve(&self.status_bar.directory_fg),
+            directory_bg: resolve(&self.status_bar.directory_bg),
+            git_clean_fg: resolve(&self.status_bar.git_clean_fg),
+            git_dirty_fg: resolve(&self.status_bar.git_dirty_fg),
+            separator: self.status_bar.separator.clone(),
+        }
+    }
+}
+
+/// Per-segment style keys for the status bar. Each color may be a raw hex
+/// (`"#88c0d0"`) or ANSI name, or the name of a color in the theme's
+/// selected palette.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusBarStyle {
+    /// Foreground color for the current-directory segment.
+    #[serde(default)]
+    pub directory_fg: Option<String>,
+    /// Background color for the current-directory segment.
+    #[serde(default)]
+    pub directory_bg: Option<String>,
+    /// Foreground color for the git segment when the working tree is clean.
+    #[serde(default)]
+    pub git_clean_fg: Option<String>,
+    /// Foreground color for the git segment when the working tree is dirty.
+    #[serde(default)]
+    pub git_dirty_fg: Option<String>,
+    /// Glyph placed between segments.
+    #[serde(default = "default_separator")]
+    pub separator: String,
+}
+
+fn default_separator() -> String {
+    "│".to_string()
+}
+
+impl Default for StatusBarStyle {
+    fn default() -> Self {
+        Self {
+            directory_fg: None,
+            directory_bg: None,
+            git_clean_fg: None,
+            git_dirty_fg: None,
+            separator: default_separator(),
+        }
+    }
+}
+
+/// A `StatusBarStyle` with any palette-name references resolved to
+/// concrete color values, ready to hand to `StatusBar`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedStatusBarStyle {
+    pub directory_fg: Option<String>,
+    pub directory_bg: Option<String>,
+    pub git_clean_fg: Option<String>,
+    pub git_dirty_fg: Option<String>,
+    pub separator: String,
+}
+
+impl Default for ResolvedStatusBarStyle {
+    fn default() -> Self {
+        ThemeConfig::default().resolve_status_bar_style()
+    }
+}
+
 impl Config {
     /// Load configuration from the default location
     ///
@@ -113,6 +315,74 @@ impl Config {
             .with_context(|| format!("Failed to parse config file: {:?}", path))
     }
 
+    /// Load the global config layered with any project-local
+    /// `.warp-foss.toml` files found walking up from `working_dir` to the
+    /// filesystem root, so a repo can pin its own shell, cols/rows, env
+    /// vars, and font size.
+    ///
+    /// Project-local files closer to `working_dir` take precedence over
+    /// ones further up the tree, which in turn take precedence over the
+    /// global config - the same precedence editors/formatters use for
+    /// per-project settings. Every field is `#[serde(default)]`, so a
+    /// naive struct-level deserialize of each file in turn would lose the
+    /// "this field was absent" signal and let an earlier file's defaults
+    /// clobber a later file's real values; instead each file is parsed
+    /// into a `toml::Table` and merged key-by-key (nested tables like
+    /// `[terminal]` merge recursively, everything else - including arrays
+    /// like `env` - is replaced wholesale) before the combined table is
+    /// deserialized once at the end.
+    ///
+    /// Returns the merged config alongside the list of files that
+    /// contributed, in the order they were merged (global first,
+    /// most-specific project file last), so callers (e.g. the status bar)
+    /// can surface which config is in effect.
+    pub fn load_with_overrides(working_dir: &Path) -> Result<(Self, Vec<PathBuf>)> {
+        let mut merged = toml::Table::new();
+        let mut contributing = Vec::new();
+
+        let global_path = Self::config_path()?;
+        if let Some(table) = Self::read_table(&global_path)? {
+            merge_tables(&mut merged, table);
+            contributing.push(global_path);
+        }
+
+        // `ancestors()` walks from `working_dir` to the root, i.e.
+        // most-specific first; reverse so the least-specific project file
+        // merges first and the closest one is applied last.
+        let mut project_paths: Vec<PathBuf> = working_dir
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILENAME))
+            .filter(|path| path.exists())
+            .collect();
+        project_paths.reverse();
+
+        for path in project_paths {
+            if let Some(table) = Self::read_table(&path)? {
+                merge_tables(&mut merged, table);
+                contributing.push(path);
+            }
+        }
+
+        let merged_str = toml::to_string(&merged).context("Failed to serialize merged configuration")?;
+        let config: Config = toml::from_str(&merged_str).context("Failed to parse merged configuration")?;
+
+        Ok((config, contributing))
+    }
+
+    /// Parse a TOML file into a table, or `None` if it doesn't exist.
+    fn read_table(path: &Path) -> Result<Option<toml::Table>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?;
+
+        toml::from_str(&contents)
+            .map(Some)
+            .with_context(|| format!("Failed to parse config file: {:?}", path))
+    }
+
     /// Save configuration to a specific path
     pub fn save_to_path(&self, path: &PathBuf) -> Result<()> {
         // Ensure parent directory exists
@@ -133,9 +403,15 @@ impl Config {
     /// Get the default config file path
     ///
     /// Priority:
-    /// 1. $XDG_CONFIG_HOME/warp-foss/config.toml
-    /// 2. ~/.config/warp-foss/config.toml
+    /// 1. `$WARP_FOSS_CONFIG`, if set - points directly at a config file,
+    ///    useful for testing and for running multiple profiles
+    /// 2. $XDG_CONFIG_HOME/warp-foss/config.toml
+    /// 3. ~/.config/warp-foss/config.toml
     pub fn config_path() -> Result<PathBuf> {
+        if let Ok(override_path) = std::env::var("WARP_FOSS_CONFIG") {
+            return Ok(PathBuf::from(override_path));
+        }
+
         if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
             Ok(PathBuf::from(xdg_config).join("warp-foss").join("config.toml"))
         } else {
@@ -144,6 +420,48 @@ impl Config {
             Ok(home.join(".config").join("warp-foss").join("config.toml"))
         }
     }
+
+    /// Render the fully-resolved configuration - defaults filled in, the
+    /// `WARP_FOSS_CONFIG` override and any project-local layers applied -
+    /// as pretty-printed TOML.
+    ///
+    /// Never fails: a missing or malformed config file falls back to
+    /// `Config::default()` with a `tracing::warn!` naming what couldn't be
+    /// resolved, rather than aborting a "print the effective config"
+    /// request over a broken file.
+    pub fn print_effective() -> String {
+        let working_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let config = match Self::load_with_overrides(&working_dir) {
+            Ok((config, _contributing)) => config,
+            Err(err) => {
+                tracing::warn!("Failed to resolve effective config, falling back to defaults: {:#}", err);
+                Config::default()
+            }
+        };
+
+        toml::to_string_pretty(&config).unwrap_or_else(|err| {
+            tracing::warn!("Failed to serialize effective config, falling back to defaults: {}", err);
+            toml::to_string_pretty(&Config::default()).expect("default config always serializes")
+        })
+    }
+}
+
+/// Recursively merge `overlay` into `base`: nested tables (e.g.
+/// `[terminal]`, `[font]`) are merged key-by-key so a project file only
+/// needs to set the fields it cares about, rather than replacing the whole
+/// section; every other value - including arrays like `env` - is replaced
+/// wholesale.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +482,8 @@ mod tests {
         let toml_str = toml::to_string_pretty(&config).unwrap();
         assert!(toml_str.contains("[terminal]"));
         assert!(toml_str.contains("[font]"));
+        assert!(toml_str.contains("[theme]"));
+        assert!(toml_str.contains("[status_bar]"));
 
         let parsed: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.terminal.cols, config.terminal.cols);
@@ -203,4 +523,190 @@ size = 18.0
         // Font should use custom value
         assert_eq!(config.font.size, 18.0);
     }
+
+    #[test]
+    fn test_config_parses_named_layouts() {
+        let toml_str = r#"
+[layouts.editor]
+type = "horizontal"
+
+[[layouts.editor.children]]
+type = "pane"
+command = "$EDITOR"
+size = "70%"
+
+[[layouts.editor.children]]
+type = "pane"
+command = "$SHELL"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let editor = config.layouts.get("editor").expect("editor layout present");
+        match editor {
+            LayoutTemplate::Horizontal { children, .. } => assert_eq!(children.len(), 2),
+            other => panic!("expected a horizontal split, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_tables_merges_nested_sections_key_by_key() {
+        let mut base: toml::Table =
+            toml::from_str("[section]\na = 1\nb = 2\n\n[other]\nx = \"unchanged\"\n").unwrap();
+        let overlay: toml::Table = toml::from_str("[section]\nb = 20\nc = 30\n").unwrap();
+
+        merge_tables(&mut base, overlay);
+
+        let section = base["section"].as_table().unwrap();
+        assert_eq!(section["a"].as_integer(), Some(1)); // untouched key survives
+        assert_eq!(section["b"].as_integer(), Some(20)); // overlay wins
+        assert_eq!(section["c"].as_integer(), Some(30)); // new key added
+        assert_eq!(base["other"]["x"].as_str(), Some("unchanged")); // untouched section survives
+    }
+
+    #[test]
+    fn test_merge_tables_replaces_arrays_wholesale_instead_of_appending() {
+        let mut base: toml::Table = toml::from_str("values = [1, 2, 3]\n").unwrap();
+        let overlay: toml::Table = toml::from_str("values = [9]\n").unwrap();
+
+        merge_tables(&mut base, overlay);
+
+        assert_eq!(base["values"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_with_overrides_layers_project_config_by_proximity() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-load-with-overrides-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let xdg_home = scratch.join("xdg");
+        let project_root = scratch.join("project");
+        let project_sub = project_root.join("sub");
+        std::fs::create_dir_all(&project_sub).unwrap();
+
+        let global_path = xdg_home.join("warp-foss").join("config.toml");
+        std::fs::create_dir_all(global_path.parent().unwrap()).unwrap();
+        std::fs::write(&global_path, "[terminal]\nshell = \"/bin/sh\"\ncols = 80\n\n[font]\nsize = 12.0\n").unwrap();
+
+        std::fs::write(project_root.join(".warp-foss.toml"), "[terminal]\ncols = 100\n").unwrap();
+        std::fs::write(project_sub.join(".warp-foss.toml"), "[terminal]\nshell = \"/bin/fish\"\n").unwrap();
+
+        std::env::set_var("XDG_CONFIG_HOME", &xdg_home);
+        let result = Config::load_with_overrides(&project_sub);
+        std::env::remove_var("XDG_CONFIG_HOME");
+        std::fs::remove_dir_all(&scratch).ok();
+
+        let (config, contributing) = result.unwrap();
+
+        // The closer project file wins over the further one, which wins
+        // over the global config; untouched fields fall through from
+        // further up the chain.
+        assert_eq!(config.terminal.shell, Some("/bin/fish".to_string()));
+        assert_eq!(config.terminal.cols, 100);
+        assert_eq!(config.font.size, 12.0);
+
+        assert_eq!(
+            contributing,
+            vec![
+                global_path,
+                project_root.join(".warp-foss.toml"),
+                project_sub.join(".warp-foss.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_theme_resolves_palette_names_to_colors() {
+        let toml_str = r#"
+[theme]
+palette = "nord"
+
+[theme.palettes.nord]
+bg = "#2e3440"
+blue = "#88c0d0"
+red = "#bf616a"
+
+[theme.status_bar]
+directory_fg = "blue"
+git_dirty_fg = "red"
+git_clean_fg = "#a3be8c"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let resolved = config.theme.resolve_status_bar_style();
+
+        assert_eq!(resolved.directory_fg.as_deref(), Some("#88c0d0"));
+        assert_eq!(resolved.git_dirty_fg.as_deref(), Some("#bf616a"));
+        // Not a palette name, so it passes through as a raw color.
+        assert_eq!(resolved.git_clean_fg.as_deref(), Some("#a3be8c"));
+    }
+
+    #[test]
+    fn test_theme_falls_back_to_defaults_on_unknown_palette() {
+        let toml_str = r#"
+[theme]
+palette = "does-not-exist"
+
+[theme.status_bar]
+directory_fg = "blue"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let resolved = config.theme.resolve_status_bar_style();
+
+        // No palette to resolve "blue" against, so it passes through
+        // unchanged rather than being dropped or causing an error.
+        assert_eq!(resolved.directory_fg.as_deref(), Some("blue"));
+    }
+
+    #[test]
+    fn test_status_bar_format_parses_custom_string() {
+        let toml_str = r#"
+[status_bar]
+format = "$directory | $git_branch$git_status$time"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.status_bar.format,
+            "$directory | $git_branch$git_status$time"
+        );
+    }
+
+    #[test]
+    fn test_config_path_honors_warp_foss_config_override() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-config-path-override-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+        let override_path = scratch.join("custom.toml");
+
+        std::env::set_var("WARP_FOSS_CONFIG", &override_path);
+        let resolved = Config::config_path();
+        std::env::remove_var("WARP_FOSS_CONFIG");
+        std::fs::remove_dir_all(&scratch).ok();
+
+        assert_eq!(resolved.unwrap(), override_path);
+    }
+
+    #[test]
+    fn test_print_effective_falls_back_to_defaults_on_malformed_config() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-print-effective-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+        let bad_path = scratch.join("broken.toml");
+        std::fs::write(&bad_path, "this is not valid toml [[[").unwrap();
+
+        std::env::set_var("WARP_FOSS_CONFIG", &bad_path);
+        let rendered = Config::print_effective();
+        std::env::remove_var("WARP_FOSS_CONFIG");
+        std::fs::remove_dir_all(&scratch).ok();
+
+        // Falls back to the default config rather than panicking or
+        // surfacing the parse error.
+        let parsed: Config = toml::from_str(&rendered).unwrap();
+        assert_eq!(parsed.terminal.cols, Config::default().terminal.cols);
+    }
 }
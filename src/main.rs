@@ -14,33 +14,216 @@ mod search;
 mod terminal;
 mod ui;
 
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use parking_lot::Mutex;
 
 use anyhow::Result;
+use regex::Regex;
 use terminal::grid::TerminalGrid;
 use terminal::parser::TerminalParser;
 use terminal::pty::{PtyConfig, PtySession};
+use ai::shell_context::ShellContext;
 use ui::ai_command_palette::AICommandPalette;
 use ui::input::InputHandler;
 use search::SearchState;
-use ui::layout::{LayoutTree, Pane, Rect, SplitDirection};
-use ui::selection::{extract_selected_text, Clipboard, SelectionState};
+use ui::layout::{DividerHit, LayoutTree, Pane, Rect, SplitDirection};
+use ui::selection::{Clipboard, SelectionState};
+use ui::theme::ThemeRegistry;
 use winit::{
     application::ApplicationHandler,
     dpi::{PhysicalPosition, PhysicalSize},
-    event::{DeviceId, ElementState, MouseButton, WindowEvent},
-    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
+    event::{DeviceId, ElementState, Ime, MouseButton, MouseScrollDelta, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
     keyboard::{Key, ModifiersState, NamedKey},
     window::{Window, WindowId},
 };
 
+/// Wakes the winit event loop from a background thread. The only source
+/// today is a pane's PTY watcher reporting that it buffered new bytes; see
+/// `TerminalApp::spawn_pty_watcher`.
+#[derive(Debug, Clone, Copy)]
+enum UserEvent {
+    PtyData,
+}
+
+/// Borrowed view of the IME composition state, threaded through the
+/// `render_layout` -> `render_node` -> `render_pane` call chain the same way
+/// `search_state`/`ai_palette` already are (see `TerminalApp::ime_preedit`).
+struct ImePreedit<'a> {
+    text: &'a str,
+    cursor: Option<std::ops::Range<usize>>,
+}
+
+impl ImePreedit<'_> {
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// One labeled hyperlink overlay shown while `TerminalApp::hint_mode` is
+/// active, alacritty `hint`-mode style: every visible OSC 8 link, plus every
+/// plain-text URL/path/`user@host` match found by `text_hint_spans`, gets a
+/// short label the user can type to open it via the OS's default handler.
+#[derive(Debug, Clone)]
+struct HintEntry {
+    row: usize,
+    col: usize,
+    uri: String,
+    label: String,
+}
+
+/// Default patterns `text_hint_spans` scans visible rows for, in priority
+/// order (a cell already claimed by an earlier pattern's match is skipped) -
+/// mirrors Alacritty's default `hints.enabled` regex set: URLs, filesystem
+/// paths, and `user@host` pairs. Paired with a `mailto:`-style prefix to
+/// apply to the matched text before it's handed to the OS opener; empty for
+/// patterns (URLs, paths) the opener can already take as-is.
+fn text_hint_patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        vec![
+            (Regex::new(r"https?://[^\s<>\x22\x27]+[^\s<>\x22\x27.,;:!?)\]]").unwrap(), ""),
+            (Regex::new(r"(?:[A-Za-z]:\\|/)[^\s<>\x22\x27:]+").unwrap(), ""),
+            (
+                Regex::new(r"[A-Za-z0-9_.+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)+").unwrap(),
+                "mailto:",
+            ),
+        ]
+    })
+}
+
+/// Scan every visible row of `grid` for a plain-text hint match (URL,
+/// filesystem path, or `user@host`), returning `(row, start_col, uri)`
+/// triples in the same shape `hyperlink_spans` uses - `uri` is what gets
+/// handed to `open_url`, already prefixed where the pattern needs it (e.g.
+/// `mailto:` for a bare email address). A cell already claimed by an
+/// earlier pattern's match on the same row is skipped by later patterns, so
+/// an emailed URL like `mailto:user@host` only yields one hint, not two
+/// overlapping ones. The visible text of a hint is always `uri` with any
+/// prefix stripped back off, so label placement still lines up with what's
+/// on screen.
+fn text_hint_spans(grid: &TerminalGrid) -> Vec<(usize, usize, String)> {
+    let mut spans = Vec::new();
+    for row in 0..grid.rows() {
+        let line: String = (0..grid.cols())
+            .map(|col| grid.get_cell(row, col).map(|cell| cell.char).unwrap_or(' '))
+            .collect();
+        let mut claimed = vec![false; line.chars().count()];
+
+        for (pattern, prefix) in text_hint_patterns() {
+            for mat in pattern.find_iter(&line) {
+                let start_col = line[..mat.start()].chars().count();
+                let end_col = line[..mat.end()].chars().count();
+                if claimed[start_col..end_col].iter().any(|&c| c) {
+                    continue;
+                }
+                claimed[start_col..end_col].fill(true);
+                spans.push((row, start_col, format!("{prefix}{}", mat.as_str())));
+            }
+        }
+    }
+    spans
+}
+
+/// The on-screen length of a `text_hint_spans` entry's `uri`, after
+/// stripping back off the `mailto:`-style prefix it was built with - used
+/// to map a hovered column back onto the matched run of cells.
+fn text_hint_display_len(uri: &str) -> usize {
+    uri.strip_prefix("mailto:").unwrap_or(uri).chars().count()
+}
+
+/// Character set hint labels are built from, in priority order - mirrors
+/// Alacritty's default `hints.alphabet` (home row first, so the common case
+/// of a handful of links only ever needs a single keystroke).
+const HINT_ALPHABET: &str = "jfkdls;ahgurieowpq";
+
+/// Build `count` unique labels from `HINT_ALPHABET`, using the shortest
+/// label length that can cover `count` entries (single characters while
+/// `count` fits the alphabet, then pairs, and so on).
+fn generate_hint_labels(count: usize) -> Vec<String> {
+    let alphabet: Vec<char> = HINT_ALPHABET.chars().collect();
+    if count == 0 || alphabet.is_empty() {
+        return Vec::new();
+    }
+
+    let mut len = 1u32;
+    while (alphabet.len() as u64).pow(len) < count as u64 {
+        len += 1;
+    }
+
+    let mut labels = Vec::with_capacity(count);
+    let mut indices = vec![0usize; len as usize];
+    loop {
+        labels.push(indices.iter().map(|&i| alphabet[i]).collect());
+        if labels.len() == count {
+            break;
+        }
+        let mut carry = true;
+        for slot in indices.iter_mut().rev() {
+            if !carry {
+                break;
+            }
+            *slot += 1;
+            if *slot < alphabet.len() {
+                carry = false;
+            } else {
+                *slot = 0;
+            }
+        }
+    }
+    labels
+}
+
+/// Borrowed view of the mouse-hover and hint-mode state for OSC 8
+/// hyperlinks, threaded through the `render_layout` -> `render_node` ->
+/// `render_pane` call chain the same way `ImePreedit` is.
+struct HyperlinkOverlay<'a> {
+    /// The pane and cell the mouse is currently over, regardless of hint
+    /// mode - used to underline a hyperlink on hover.
+    hovered: Option<(uuid::Uuid, usize, usize)>,
+    /// Whether Ctrl is held - plain-text hints (unlike OSC 8 links, which
+    /// have no other use for a click) only underline and open with Ctrl
+    /// held, so a plain click can still select the URL's text as usual.
+    ctrl_held: bool,
+    /// Whether `TerminalApp::hint_mode` is active.
+    hint_mode: bool,
+    /// Labeled hyperlinks to draw as badges while `hint_mode` is active.
+    hints: &'a [HintEntry],
+    /// Label characters typed so far, to dim the matched prefix of each
+    /// badge differently from the remaining characters.
+    hint_input: &'a str,
+}
+
+/// Cursor appearance, threaded through the render call chain the same way
+/// `HyperlinkOverlay` is. Copy since it's just two small config values, not
+/// per-frame state that needs to live behind a reference.
+#[derive(Debug, Clone, Copy)]
+struct CursorConfig {
+    style: config::settings::CursorStyle,
+    /// Whether the cursor blinks. Blink phase is derived from wall-clock
+    /// time at the moment a frame is actually drawn, so it only animates
+    /// while something else is already triggering redraws (e.g. typing) -
+    /// in keeping with this renderer drawing frames on damage rather than
+    /// polling at a fixed rate.
+    blink: bool,
+}
+
 /// Configuration for the terminal application
 struct AppConfig {
     /// Initial terminal columns
     cols: u16,
-    /// Initial terminal rows  
+    /// Initial terminal rows
     rows: u16,
+    /// Shape to render the terminal cursor as
+    cursor_style: config::settings::CursorStyle,
+    /// Whether the cursor blinks while idle
+    cursor_blink: bool,
+    /// Name of the active terminal grid theme (a key into `ThemeRegistry`),
+    /// mirroring `config::settings::ThemeConfig::active_theme`. `None`
+    /// leaves new panes on the built-in XTerm default colors.
+    active_theme: Option<String>,
 }
 
 impl Default for AppConfig {
@@ -48,6 +231,9 @@ impl Default for AppConfig {
         Self {
             cols: 120,
             rows: 40,
+            cursor_style: config::settings::CursorStyle::Block,
+            cursor_blink: true,
+            active_theme: None,
         }
     }
 }
@@ -68,10 +254,9 @@ struct TerminalApp {
     clipboard: Clipboard,
     /// Whether the app is running
     running: bool,
-    /// Last frame time for FPS limiting
-    last_frame: Instant,
-    /// Target frame duration (60 FPS)
-    frame_duration: Duration,
+    /// Wakes the event loop from a pane's PTY watcher thread; cloned into
+    /// each watcher spawned by `spawn_pty_watcher`.
+    event_proxy: EventLoopProxy<UserEvent>,
     /// Cell dimensions in pixels
     cell_width: u32,
     cell_height: u32,
@@ -85,16 +270,141 @@ struct TerminalApp {
     search_input: String,
     /// AI command palette
     ai_palette: AICommandPalette,
+    /// Text currently being composed by the platform IME (CJK, dead keys,
+    /// emoji picker), not yet committed. Rendered inline at the cursor but
+    /// never sent to the PTY until `Ime::Commit` fires.
+    ime_preedit: String,
+    /// Byte range within `ime_preedit` the IME reports as its "current
+    /// clause" (the part still being actively edited by the composition
+    /// candidates). Used to render that clause distinctly from the rest of
+    /// the preedit text.
+    ime_preedit_cursor: Option<std::ops::Range<usize>>,
+    /// Whether the hyperlink hint overlay is active (Ctrl+Shift+O).
+    hint_mode: bool,
+    /// Labeled hyperlinks on screen, computed once when hint mode is
+    /// entered (see `enter_hint_mode`).
+    hints: Vec<HintEntry>,
+    /// Label characters typed so far while `hint_mode` is active.
+    hint_input: String,
+    /// User-facing settings (cursor shape/blink, initial terminal size).
+    app_config: AppConfig,
+    /// In-progress split-divider drag started by `handle_mouse_button`,
+    /// `None` when the left button isn't currently held down on a divider.
+    resize_drag: Option<ResizeDrag>,
+    /// Pane and SGR button code of a mouse-reporting press that hasn't
+    /// been released yet, so `handle_mouse_motion` knows which button to
+    /// report drag motion for (see `ParserState::mouse_reporting`).
+    mouse_report_drag: Option<MouseReportDrag>,
+    /// Time and grid cell of the last left-button press, used to detect
+    /// double/triple clicks for semantic/line selection the same way
+    /// `CLICK_MULTI_WINDOW` does in alacritty.
+    last_click: Option<(std::time::Instant, uuid::Uuid, usize, usize)>,
+    /// Consecutive clicks within `CLICK_MULTI_WINDOW` of the same cell;
+    /// 1 = Normal selection, 2 = Semantic, 3+ = Line (wraps back to 1).
+    click_count: u32,
+    /// Vi-mode navigation state (Ctrl+Shift+Space to enter, Escape to
+    /// exit); `None` when vi mode is inactive. See `ViModeState`.
+    vi_mode: Option<ViModeState>,
+    /// Catalog of terminal grid themes loaded from the user's themes
+    /// directory; `app_config.active_theme` is applied out of this to
+    /// every pane as it's created.
+    theme_registry: ThemeRegistry,
+}
+
+/// Keyboard-driven cursor state for "vi mode" (entered via Ctrl+Shift+Space,
+/// exited via Escape), alacritty vi-mode style: moves a virtual cursor over
+/// the grid and scrollback instead of sending keystrokes to the PTY, for
+/// mouse-free copying and navigation of long output.
+#[derive(Debug, Clone, Copy)]
+struct ViModeState {
+    /// Virtual cursor position, in the same absolute `all_rows` address
+    /// space `SearchState`/`TerminalGrid::reveal_absolute_row` use.
+    cursor: search::Point,
+    /// Selection anchor set by `v`; `Some` while a selection is being built
+    /// between here and `cursor`.
+    anchor: Option<search::Point>,
+}
+
+/// A single vi-mode cursor motion, mapped from keys in `window_event`'s
+/// vi-mode dispatch branch and applied by `TerminalApp::vi_apply_motion`.
+#[derive(Debug, Clone, Copy)]
+enum ViMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordForward,
+    WordBackward,
+    LineStart,
+    LineEnd,
+    BufferTop,
+    BufferBottom,
+    HalfPageUp,
+    HalfPageDown,
+}
+
+/// Step `(row, col)` one cell backward in reading order, wrapping to the end
+/// of the previous row at column 0. Returns `false` at the buffer start.
+fn vi_step_back(cols: usize, row: &mut usize, col: &mut usize) -> bool {
+    if *col > 0 {
+        *col -= 1;
+        true
+    } else if *row > 0 {
+        *row -= 1;
+        *col = cols.saturating_sub(1);
+        true
+    } else {
+        false
+    }
+}
+
+/// Clicks within this long of each other and on the same cell advance
+/// `click_count` instead of resetting it to 1.
+const CLICK_MULTI_WINDOW: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Tracks a mouse-reporting button press between `handle_mouse_button`'s
+/// press and release, the reporting equivalent of `ResizeDrag`.
+struct MouseReportDrag {
+    pane_id: uuid::Uuid,
+    /// Base SGR button code (0/1/2 for left/middle/right) the press was
+    /// reported with; motion reports reuse this until release.
+    button: u8,
+}
+
+/// Tracks a divider drag between `handle_mouse_button`'s press and release:
+/// which divider is being dragged and the cursor position `handle_mouse_motion`
+/// last computed a delta from.
+struct ResizeDrag {
+    hit: DividerHit,
+    last_x: i32,
+    last_y: i32,
 }
 
+/// `StagingBelt` chunk size - matches the text instance buffer's initial
+/// capacity, so a typical frame's upload fits in a single chunk.
+const STAGING_BELT_CHUNK_SIZE: wgpu::BufferAddress = 1024 * 1024;
+
 /// Type-erased renderer holder to work around lifetime issues
 struct RendererHolder {
     device: wgpu::Device,
     queue: wgpu::Queue,
     surface: wgpu::Surface<'static>,
     config: wgpu::SurfaceConfiguration,
+    text_cache: ui::text::Cache,
     text_renderer: ui::text::TextRenderer,
     text_bind_group: Option<wgpu::BindGroup>,
+    /// Solid-color/outline rectangle renderer, for pane borders, overlay
+    /// frames, and the cursor - crisp at any size, unlike drawing them as
+    /// glyphs.
+    rect_renderer: ui::shapes::RectRenderer,
+    /// Textured-quad renderer for inline Sixel images (see
+    /// `terminal::sixel`).
+    image_renderer: ui::image::ImageRenderer,
+    /// Ring of reusable staging buffers the text instance upload is carved
+    /// out of each frame (see `ui::text::TextRenderer::prepare`), so a full
+    /// screen of glyphs doesn't reallocate or directly stall the queue
+    /// every frame.
+    staging_belt: wgpu::util::StagingBelt,
 }
 
 impl RendererHolder {
@@ -166,20 +476,32 @@ impl RendererHolder {
 
         surface.configure(&device, &config);
 
-        // Create text renderer
+        // Create the shared pipeline/layout/sampler once, then a text
+        // renderer that borrows it.
         let window_size = (size.width, size.height);
-        let text_renderer = ui::text::TextRenderer::new(&device, 16.0, window_size)?;
+        let text_cache = ui::text::Cache::new(&device, config.format);
+        let mut text_renderer = ui::text::TextRenderer::new(&device, 16.0, window_size)?;
+        text_renderer.init_buffer(&device);
 
         // Create bind group for text
-        let text_bind_group = text_renderer.create_bind_group(&device);
+        let text_bind_group = text_renderer.create_bind_group(&device, &text_cache);
+
+        let mut rect_renderer = ui::shapes::RectRenderer::new(&device, config.format, window_size);
+        rect_renderer.init_buffer(&device);
+
+        let image_renderer = ui::image::ImageRenderer::new(&device, config.format, window_size);
 
         Ok(Self {
             device,
             queue,
             surface,
             config,
+            text_cache,
             text_renderer,
             text_bind_group,
+            rect_renderer,
+            image_renderer,
+            staging_belt: wgpu::util::StagingBelt::new(STAGING_BELT_CHUNK_SIZE),
         })
     }
 
@@ -188,6 +510,8 @@ impl RendererHolder {
             self.config.width = width;
             self.config.height = height;
             self.surface.configure(&self.device, &self.config);
+            self.rect_renderer.resize(width, height);
+            self.image_renderer.resize(width, height);
         }
     }
 
@@ -209,6 +533,12 @@ impl RendererHolder {
                 label: Some("Render Encoder"),
             });
 
+        // Prepare text renderer (upload glyph atlas and vertex data); the
+        // instance buffer write is carved out of `staging_belt` into this
+        // same encoder, ahead of the render pass that reads it below.
+        self.text_renderer
+            .prepare(&self.device, &self.queue, &mut encoder, &mut self.staging_belt);
+
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
@@ -230,20 +560,48 @@ impl RendererHolder {
                 occlusion_query_set: None,
             });
 
-            // Render text if we have bind group and vertices
+            // Backdrop chrome (pane borders, overlay frames) first, then
+            // text on top of it, then the cursor on top of that so it
+            // overlays whatever glyph is underneath.
+            self.rect_renderer.render_background(&mut render_pass);
+
             if let Some(ref bind_group) = self.text_bind_group {
-                if self.text_renderer.vertex_count() > 0 {
-                    self.text_renderer.render(&mut render_pass, bind_group);
+                if self.text_renderer.instance_count() > 0 {
+                    self.text_renderer.render(&mut render_pass, bind_group, &self.text_cache);
                 }
             }
+
+            self.image_renderer.render(&mut render_pass);
+
+            self.rect_renderer.render_foreground(&mut render_pass);
         }
 
+        self.staging_belt.finish();
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
+        // Reclaims the chunks this frame wrote into once the GPU is done
+        // with them, so they go back into the free pool for reuse instead
+        // of the belt growing a new chunk every frame.
+        self.staging_belt.recall();
 
         Ok(())
     }
 
+    /// Whether any pane under `node` has damage since the last `reset_damage()`.
+    ///
+    /// Used to decide whether a frame needs drawing at all - see
+    /// `render_layout`'s early return.
+    fn layout_has_damage(node: &ui::layout::LayoutNode) -> bool {
+        use ui::layout::LayoutNode;
+
+        match node {
+            LayoutNode::Pane(pane) => pane.grid.is_damaged(),
+            LayoutNode::HorizontalSplit { children, .. } | LayoutNode::VerticalSplit { children, .. } => {
+                children.iter().any(|child| Self::layout_has_damage(child))
+            }
+        }
+    }
+
     fn render_layout(
         &mut self,
         layout: &LayoutTree,
@@ -253,20 +611,64 @@ impl RendererHolder {
         search_state: &SearchState,
         search_input: &str,
         ai_palette: &AICommandPalette,
+        ime: &ImePreedit,
+        hyperlinks: &HyperlinkOverlay,
+        cursor_config: CursorConfig,
+        selection_state: &SelectionState,
+        vi_mode: Option<&ViModeState>,
     ) -> Result<(), ui::renderer::RendererError> {
-        // Clear previous frame's text
+        // Nothing moved in any pane, and neither overlay is up - the last
+        // frame we drew is still accurate, so skip re-queuing glyphs and
+        // resubmitting to the GPU entirely. Search/AI-palette overlays don't
+        // track their own damage yet, so while either is up we conservatively
+        // fall through to a full redraw. An in-progress IME composition is
+        // the same story - it changes what's on screen without touching grid
+        // damage, so it forces a full redraw too. Hint mode, link hover,
+        // mouse selection, and vi mode are the same story again: none of
+        // them is grid damage.
+        if !Self::layout_has_damage(layout.root())
+            && !ai_palette.is_visible()
+            && !search_state.active
+            && ime.is_empty()
+            && !hyperlinks.hint_mode
+            && hyperlinks.hovered.is_none()
+            && !selection_state.has_selection()
+            && vi_mode.is_none()
+        {
+            return Ok(());
+        }
+
+        // Clear previous frame's text, rects, and images
         self.text_renderer.clear();
+        self.rect_renderer.clear();
+        self.image_renderer.clear();
 
         // Render all panes in the layout
-        self.render_node(layout.root(), cell_width, cell_height, focused_pane_id, search_state, search_input)?;
+        self.render_node(
+            layout.root(),
+            cell_width,
+            cell_height,
+            focused_pane_id,
+            search_state,
+            search_input,
+            ime,
+            ai_palette.is_visible(),
+            hyperlinks,
+            cursor_config,
+            selection_state,
+            vi_mode,
+        )?;
 
         // Render AI palette overlay if visible
         if ai_palette.is_visible() {
-            self.render_ai_palette(ai_palette, cell_width, cell_height)?;
+            self.render_ai_palette(ai_palette, cell_width, cell_height, ime)?;
         }
 
-        // Prepare text renderer (upload glyph atlas and vertex data)
-        self.text_renderer.prepare(&self.device, &self.queue);
+        // Prepare the rect/image renderers here; text is prepared in
+        // `render()` itself, since its instance upload needs the same
+        // encoder and staging belt that the render pass runs in.
+        self.rect_renderer.prepare(&self.device, &self.queue);
+        self.image_renderer.evict_unused();
 
         // Render to screen
         self.render()
@@ -280,21 +682,66 @@ impl RendererHolder {
         focused_pane_id: uuid::Uuid,
         search_state: &SearchState,
         search_input: &str,
+        ime: &ImePreedit,
+        ai_palette_visible: bool,
+        hyperlinks: &HyperlinkOverlay,
+        cursor_config: CursorConfig,
+        selection_state: &SelectionState,
+        vi_mode: Option<&ViModeState>,
     ) -> Result<(), ui::renderer::RendererError> {
         use ui::layout::LayoutNode;
 
         match node {
             LayoutNode::Pane(pane) => {
-                self.render_pane(pane, cell_width, cell_height, pane.id == focused_pane_id, search_state, search_input)?;
+                self.render_pane(
+                    pane,
+                    cell_width,
+                    cell_height,
+                    pane.id == focused_pane_id,
+                    search_state,
+                    search_input,
+                    ime,
+                    ai_palette_visible,
+                    hyperlinks,
+                    cursor_config,
+                    selection_state,
+                    vi_mode,
+                )?;
             }
             LayoutNode::HorizontalSplit { children, .. } => {
                 for child in children {
-                    self.render_node(child, cell_width, cell_height, focused_pane_id, search_state, search_input)?;
+                    self.render_node(
+                        child,
+                        cell_width,
+                        cell_height,
+                        focused_pane_id,
+                        search_state,
+                        search_input,
+                        ime,
+                        ai_palette_visible,
+                        hyperlinks,
+                        cursor_config,
+                        selection_state,
+                        vi_mode,
+                    )?;
                 }
             }
             LayoutNode::VerticalSplit { children, .. } => {
                 for child in children {
-                    self.render_node(child, cell_width, cell_height, focused_pane_id, search_state, search_input)?;
+                    self.render_node(
+                        child,
+                        cell_width,
+                        cell_height,
+                        focused_pane_id,
+                        search_state,
+                        search_input,
+                        ime,
+                        ai_palette_visible,
+                        hyperlinks,
+                        cursor_config,
+                        selection_state,
+                        vi_mode,
+                    )?;
                 }
             }
         }
@@ -310,11 +757,22 @@ impl RendererHolder {
         is_focused: bool,
         search_state: &SearchState,
         search_input: &str,
+        ime: &ImePreedit,
+        ai_palette_visible: bool,
+        hyperlinks: &HyperlinkOverlay,
+        cursor_config: CursorConfig,
+        selection_state: &SelectionState,
+        vi_mode: Option<&ViModeState>,
     ) -> Result<(), ui::renderer::RendererError> {
+        use config::settings::CursorStyle;
+        use terminal::grid::Cursor;
         use terminal::parser::Color;
 
         let bounds = pane.bounds;
         let grid = &pane.grid;
+        // Vi mode always targets whichever pane was focused when it was
+        // entered, same as `selection_state`/the mouse selection above it.
+        let vi_mode = vi_mode.filter(|_| is_focused);
 
         // Render terminal content
         let rows = grid.rows();
@@ -326,32 +784,149 @@ impl RendererHolder {
 
         // Render search bar if search is active on focused pane
         if is_focused && search_state.active {
-            self.render_search_bar(bounds, cell_width, cell_height, search_state, search_input)?;
+            self.render_search_bar(bounds, cell_width, cell_height, search_state, search_input, ime)?;
         }
 
-        for row in 0..rows {
+        // Whether the mouse is hovering a cell in this pane right now -
+        // checked per-cell below so the hovered link gets underlined.
+        let hovered_cell = hyperlinks
+            .hovered
+            .filter(|(pane_id, _, _)| *pane_id == pane.id)
+            .map(|(_, col, row)| (row, col));
+
+        // Plain-text hints (URLs/paths/`user@host`) only underline and open
+        // while Ctrl is held, so only bother scanning for them then - this
+        // pane's grid would otherwise be rescanned every frame for nothing.
+        let text_hints: Vec<(usize, usize, usize)> = if is_focused && hyperlinks.ctrl_held {
+            text_hint_spans(grid)
+                .into_iter()
+                .map(|(row, col, uri)| (row, col, col + text_hint_display_len(&uri)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let hovered_text_hint = hovered_cell.and_then(|(row, col)| {
+            text_hints
+                .iter()
+                .find(|(r, start, end)| *r == row && (*start..*end).contains(&col))
+        });
+
+        // Search highlighting and link hover can touch any row regardless of
+        // which rows the grid itself marked damaged, so fall back to a full
+        // pane redraw while either is active; otherwise only re-queue glyphs
+        // for rows that actually changed since the last frame. This is a
+        // narrower win than a full persistent per-cell vertex cache, but it
+        // avoids re-queuing an untouched pane's glyphs every frame.
+        // Paged-back scrollback isn't tracked by the live grid's damage set,
+        // so treat any nonzero `display_offset` as fully damaged too.
+        let full_redraw = (is_focused && search_state.active)
+            || hovered_cell.is_some()
+            || (is_focused && hyperlinks.hint_mode)
+            || (is_focused && selection_state.has_selection())
+            || vi_mode.is_some()
+            || hovered_text_hint.is_some()
+            || grid.display_offset() > 0;
+        let rows_to_draw: Vec<usize> = if full_redraw {
+            (0..rows).collect()
+        } else {
+            grid.damaged_rows().iter().copied().collect()
+        };
+
+        let term_cursor = grid.cursor();
+        // Blink phase derived from wall-clock time at the moment this frame
+        // is drawn - see `CursorConfig::blink`'s doc comment for why this
+        // doesn't animate on its own while the screen is otherwise idle.
+        // The cursor only ever lives on the live screen, so hide it while
+        // the viewport is paged back into scrollback.
+        let cursor_visible = grid.display_offset() == 0
+            && (!cursor_config.blink
+                || (std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_millis())
+                    .unwrap_or(0)
+                    / 530)
+                    % 2
+                    == 0);
+
+        for row in rows_to_draw {
             for col in 0..cols {
-                if let Some(cell) = grid.get_cell(row, col) {
+                if let Some(cell) = grid.get_visible_cell(row, col) {
                     if cell.char != ' ' {
                         // Offset by pane bounds + border offset
                         let x = content_offset_x + (col as f32 * cell_width as f32);
                         let y = content_offset_y + (row as f32 * cell_height as f32);
 
-                        // Highlight search matches
+                        // Highlight search matches. Match rows are absolute
+                        // `all_rows` indices (scrollback included), so the
+                        // viewport-relative `row` has to be translated first.
                         let (fg_color, bg_color) = if is_focused && search_state.active {
-                            if search_state.is_current_match(row, col) {
+                            let absolute_row = grid.visible_row_to_absolute(row);
+                            if search_state.is_current_match(absolute_row, col) {
                                 // Current match: bright yellow background
                                 (Color::Rgb(0, 0, 0), Color::Rgb(255, 255, 0))
-                            } else if search_state.is_match(row, col) {
+                            } else if search_state.is_match(absolute_row, col) {
                                 // Other matches: orange background
                                 (Color::Rgb(0, 0, 0), Color::Rgb(255, 165, 0))
                             } else {
                                 (cell.fg_color, cell.bg_color)
                             }
+                        } else if is_focused
+                            && selection_state.has_selection()
+                            && selection_state.region.contains(Cursor::new(row, col))
+                        {
+                            // Mouse selection: steel-blue background, same
+                            // as most terminals' selection highlight.
+                            (Color::Rgb(255, 255, 255), Color::Rgb(58, 92, 158))
+                        } else if vi_mode.is_some_and(|vi| {
+                            let absolute_row = grid.visible_row_to_absolute(row);
+                            Self::vi_selection_region(vi)
+                                .is_some_and(|region| region.contains(Cursor::new(absolute_row, col)))
+                        }) {
+                            (Color::Rgb(255, 255, 255), Color::Rgb(58, 92, 158))
                         } else {
                             (cell.fg_color, cell.bg_color)
                         };
 
+                        // Block cursor inverts the glyph underneath instead
+                        // of drawing over it, so the character stays legible
+                        // against the filled cursor rect queued below.
+                        let (fg_color, bg_color) = if is_focused
+                            && cursor_visible
+                            && cursor_config.style == CursorStyle::Block
+                            && row == term_cursor.row
+                            && col == term_cursor.col
+                        {
+                            (bg_color, fg_color)
+                        } else {
+                            (fg_color, bg_color)
+                        };
+
+                        let mut decorations = ui::text::decorations_for(&cell.attributes);
+                        // Underline the hyperlink under the mouse - hovering
+                        // is the only cue short of hint mode that a run of
+                        // text is clickable, so it needs to stand out even
+                        // if the emitter didn't also set SGR underline.
+                        if cell.hyperlink.is_some() && hovered_cell == Some((row, col)) {
+                            decorations.push(ui::text::LineDecoration {
+                                style: ui::text::LineStyle {
+                                    kind: ui::text::LineKind::Solid,
+                                    position: ui::text::LinePosition::Under,
+                                },
+                                color: None,
+                            });
+                        }
+                        // Same, but for a plain-text hint match under the
+                        // mouse while Ctrl is held (see `hovered_text_hint`).
+                        if hovered_text_hint.is_some_and(|(r, start, end)| *r == row && (*start..*end).contains(&col))
+                        {
+                            decorations.push(ui::text::LineDecoration {
+                                style: ui::text::LineStyle {
+                                    kind: ui::text::LineKind::Solid,
+                                    position: ui::text::LinePosition::Under,
+                                },
+                                color: None,
+                            });
+                        }
                         self.text_renderer.queue_char(
                             cell.char,
                             x,
@@ -360,7 +935,7 @@ impl RendererHolder {
                             bg_color,
                             cell.attributes.bold,
                             cell.attributes.italic,
-                            cell.attributes.underline,
+                            &decorations,
                             cell.attributes.blink,
                         )?;
                     }
@@ -368,8 +943,174 @@ impl RendererHolder {
             }
         }
 
+        // Blit any Sixel rasters anchored in this pane. Anchors are stored
+        // at the cursor cell they were decoded at (see
+        // `TerminalGrid::set_sixel_image`), so each one spans from there
+        // down-right for as many cells as its pixel size covers at the
+        // current cell metrics - the grid itself has no notion of on-screen
+        // pixel size, so that conversion has to happen here.
+        for (row, col, image) in grid.sixel_images() {
+            let x = content_offset_x + (col as f32 * cell_width as f32);
+            let y = content_offset_y + (row as f32 * cell_height as f32);
+            let cells_wide = (image.width as f32 / cell_width.max(1) as f32).ceil();
+            let cells_tall = (image.height as f32 / cell_height.max(1) as f32).ceil();
+            self.image_renderer.queue_image(
+                &self.device,
+                &self.queue,
+                Arc::as_ptr(image) as usize as u64,
+                &image.rgba,
+                image.width as u32,
+                image.height as u32,
+                x,
+                y,
+                cells_wide * cell_width as f32,
+                cells_tall * cell_height as f32,
+            );
+        }
+
+        // Draw the terminal cursor as a rect rather than a glyph: a filled
+        // block in the focused pane (the glyph underneath was inverted
+        // above), one of the other configured shapes if focused, or always
+        // a hollow outline when unfocused - regardless of the configured
+        // style - so which pane has focus is obvious at a glance.
+        if cursor_visible {
+            let cursor_fg = grid
+                .get_cell(term_cursor.row, term_cursor.col)
+                .map(|cell| cell.fg_color)
+                .unwrap_or(Color::Default);
+            let cursor_color = match cursor_fg {
+                Color::Default => Color::Rgb(230, 230, 230),
+                other => other,
+            };
+            let x = content_offset_x + (term_cursor.col as f32 * cell_width as f32);
+            let y = content_offset_y + (term_cursor.row as f32 * cell_height as f32);
+            let w = cell_width as f32;
+            let h = cell_height as f32;
+
+            if !is_focused {
+                self.rect_renderer.queue_outline_foreground(x, y, w, h, 1.0, cursor_color);
+            } else {
+                match cursor_config.style {
+                    CursorStyle::Block => {
+                        self.rect_renderer.queue_filled_foreground(x, y, w, h, cursor_color);
+                    }
+                    CursorStyle::HollowBlock => {
+                        self.rect_renderer.queue_outline_foreground(x, y, w, h, 1.0, cursor_color);
+                    }
+                    CursorStyle::Beam => {
+                        self.rect_renderer.queue_filled_foreground(x, y, 2.0, h, cursor_color);
+                    }
+                    CursorStyle::Underline => {
+                        self.rect_renderer
+                            .queue_filled_foreground(x, y + h - 2.0, w, 2.0, cursor_color);
+                    }
+                }
+            }
+        }
+
+        // Draw the vi-mode virtual cursor as a hollow block in its own
+        // accent color, distinct from the real terminal cursor above - vi
+        // mode moves this cursor independently of where the PTY thinks the
+        // cursor is, so the two must never be confused for one another.
+        if let Some(vi) = vi_mode {
+            if let Some(vi_row) = (0..grid.rows()).find(|&r| grid.visible_row_to_absolute(r) == vi.cursor.row) {
+                let x = content_offset_x + (vi.cursor.col as f32 * cell_width as f32);
+                let y = content_offset_y + (vi_row as f32 * cell_height as f32);
+                self.rect_renderer.queue_outline_foreground(
+                    x,
+                    y,
+                    cell_width as f32,
+                    cell_height as f32,
+                    2.0,
+                    Color::Rgb(255, 215, 0),
+                );
+            }
+        }
+
+        // Draw an in-progress IME composition inline at the terminal cursor,
+        // underlined so it reads as "not committed yet". It only makes sense
+        // to draw it here when it's actually headed for the PTY - while
+        // search or the AI palette has focus, `commit_text` would route it
+        // there instead, and those draw their own copy of it.
+        if is_focused && !search_state.active && !ai_palette_visible && !ime.is_empty() {
+            let cursor = grid.cursor();
+            let decorations = [ui::text::LineDecoration {
+                style: ui::text::LineStyle {
+                    kind: ui::text::LineKind::Solid,
+                    position: ui::text::LinePosition::Under,
+                },
+                color: None,
+            }];
+            for (i, ch) in ime.text.chars().enumerate() {
+                let col = cursor.col + i;
+                if col >= cols {
+                    break;
+                }
+                let x = content_offset_x + (col as f32 * cell_width as f32);
+                let y = content_offset_y + (cursor.row as f32 * cell_height as f32);
+                let in_active_clause = ime
+                    .cursor
+                    .as_ref()
+                    .is_some_and(|range| range.contains(&i));
+                let bg_color = if in_active_clause {
+                    Color::Rgb(80, 80, 100)
+                } else {
+                    Color::Rgb(0, 0, 0)
+                };
+                self.text_renderer.queue_char(
+                    ch,
+                    x,
+                    y,
+                    Color::Rgb(255, 255, 255),
+                    bg_color,
+                    false,
+                    false,
+                    &decorations,
+                    false,
+                )?;
+            }
+        }
+
+        // Draw hint-mode labels over every hyperlink in the focused pane, via
+        // the same glyph-queuing path the AI palette uses for its own text.
+        // Hints that no longer match what's been typed so far are skipped
+        // entirely, same as `TerminalApp::handle_hint_char`'s filtering.
+        if is_focused && hyperlinks.hint_mode {
+            let label_bg = Color::Rgb(255, 215, 0); // Gold, to stand out from any cell background
+            let matched_fg = Color::Rgb(180, 0, 0); // Typed prefix
+            let pending_fg = Color::Rgb(0, 0, 0); // Remaining characters
+            for hint in hyperlinks.hints {
+                if hint.row >= rows || !hint.label.starts_with(hyperlinks.hint_input) {
+                    continue;
+                }
+                let x = content_offset_x + (hint.col as f32 * cell_width as f32);
+                let y = content_offset_y + (hint.row as f32 * cell_height as f32);
+                for (i, ch) in hint.label.chars().enumerate() {
+                    if hint.col + i >= cols {
+                        break;
+                    }
+                    let fg = if i < hyperlinks.hint_input.len() {
+                        matched_fg
+                    } else {
+                        pending_fg
+                    };
+                    self.text_renderer.queue_char(
+                        ch,
+                        x + (i as f32 * cell_width as f32),
+                        y,
+                        fg,
+                        label_bg,
+                        true,
+                        false,
+                        &[],
+                        false,
+                    )?;
+                }
+            }
+        }
+
         // Draw pane borders
-        self.draw_pane_borders(bounds, cell_width, cell_height, is_focused)?;
+        self.draw_pane_borders(bounds, is_focused, &pane.title, cell_width, cell_height)?;
 
         Ok(())
     }
@@ -381,6 +1122,7 @@ impl RendererHolder {
         cell_height: u32,
         search_state: &SearchState,
         search_input: &str,
+        ime: &ImePreedit,
     ) -> Result<(), ui::renderer::RendererError> {
         use terminal::parser::Color;
 
@@ -392,9 +1134,28 @@ impl RendererHolder {
         let search_bg = Color::Rgb(40, 40, 40);
         let search_fg = Color::Rgb(255, 255, 255);
         let search_hint = Color::Rgb(150, 150, 150);
+        let search_error = Color::Rgb(220, 80, 80);
+        let input_fg = if search_state.has_error() {
+            search_error
+        } else {
+            search_fg
+        };
 
-        // Draw "Search:" label
-        let label = "Search: ";
+        // Draw "Search:"/"Regex:"/"Fuzzy:" label (Ctrl+R toggles regex mode,
+        // Ctrl+F toggles fuzzy mode, Ctrl+I toggles case sensitivity - "Cs"
+        // suffix shown when case-sensitive).
+        let label = match (
+            search_state.mode(),
+            search_state.regex_mode(),
+            search_state.case_insensitive(),
+        ) {
+            (search::SearchMode::Fuzzy, _, true) => "Fuzzy: ",
+            (search::SearchMode::Fuzzy, _, false) => "Fuzzy[Cs]: ",
+            (search::SearchMode::Regex, true, true) => "Regex: ",
+            (search::SearchMode::Regex, true, false) => "Regex[Cs]: ",
+            (search::SearchMode::Regex, false, true) => "Search: ",
+            (search::SearchMode::Regex, false, false) => "Search[Cs]: ",
+        };
         for (i, ch) in label.chars().enumerate() {
             self.text_renderer.queue_char(
                 ch,
@@ -404,7 +1165,7 @@ impl RendererHolder {
                 search_bg,
                 false,
                 false,
-                false,
+                &[],
                 false,
             )?;
         }
@@ -419,33 +1180,81 @@ impl RendererHolder {
                 ch,
                 x + ((input_start + i) as f32 * cell_width as f32),
                 y,
-                search_fg,
+                input_fg,
                 search_bg,
                 false,
                 false,
-                false,
+                &[],
                 false,
             )?;
         }
 
-        // Draw cursor
-        let cursor_pos = input_start + search_input.len();
+        // Draw in-progress IME composition right after the committed input,
+        // underlined so it's visually distinct from it.
+        let ime_start = input_start + search_input.len();
+        if !ime.is_empty() {
+            let underline = [ui::text::LineDecoration {
+                style: ui::text::LineStyle {
+                    kind: ui::text::LineKind::Solid,
+                    position: ui::text::LinePosition::Under,
+                },
+                color: None,
+            }];
+            for (i, ch) in ime.text.chars().enumerate() {
+                if i + ime_start >= bar_width {
+                    break;
+                }
+                self.text_renderer.queue_char(
+                    ch,
+                    x + ((ime_start + i) as f32 * cell_width as f32),
+                    y,
+                    input_fg,
+                    search_bg,
+                    false,
+                    false,
+                    &underline,
+                    false,
+                )?;
+            }
+        }
+
+        // Draw cursor: an underline bar rather than the `▏` glyph, crisp at
+        // any font size.
+        let cursor_pos = ime_start + ime.text.chars().count();
         if cursor_pos < bar_width {
-            self.text_renderer.queue_char(
-                '▏',
+            self.rect_renderer.queue_filled_foreground(
                 x + (cursor_pos as f32 * cell_width as f32),
-                y,
-                search_fg,
-                search_bg,
-                false,
-                false,
-                false,
-                false,
-            )?;
+                y + cell_height as f32 - 2.0,
+                cell_width as f32,
+                2.0,
+                input_fg,
+            );
         }
 
-        // Draw match count
-        if search_state.match_count() > 0 {
+        // Draw match count - while a background scan is still running, show
+        // a running total instead of "current / total" since there's no
+        // final count yet.
+        if search_state.searching() {
+            let match_text = format!(" searching... {} ", search_state.match_count());
+            let match_start = bar_width.saturating_sub(match_text.len());
+
+            for (i, ch) in match_text.chars().enumerate() {
+                if match_start + i >= bar_width {
+                    break;
+                }
+                self.text_renderer.queue_char(
+                    ch,
+                    x + ((match_start + i) as f32 * cell_width as f32),
+                    y,
+                    search_hint,
+                    search_bg,
+                    false,
+                    false,
+                    &[],
+                    false,
+                )?;
+            }
+        } else if search_state.match_count() > 0 {
             let match_text = format!(
                 " {} / {} ",
                 search_state.current_match_number().unwrap_or(0),
@@ -465,7 +1274,7 @@ impl RendererHolder {
                     search_bg,
                     false,
                     false,
-                    false,
+                    &[],
                     false,
                 )?;
             }
@@ -479,8 +1288,10 @@ impl RendererHolder {
         ai_palette: &AICommandPalette,
         cell_width: u32,
         cell_height: u32,
+        ime: &ImePreedit,
     ) -> Result<(), ui::renderer::RendererError> {
         use crate::ui::ai_command_palette::PaletteState;
+        use crate::ui::markdown::{first_code_block, ResponseSegment};
         use terminal::parser::Color;
 
         if !ai_palette.is_visible() {
@@ -503,47 +1314,25 @@ impl RendererHolder {
         let text_color = Color::Rgb(255, 255, 255);
         let hint_color = Color::Rgb(150, 150, 150);
         let cursor_color = Color::Rgb(255, 215, 0); // Gold
+        let code_color = Color::Rgb(152, 195, 121); // Green, for fenced code blocks
 
-        // Draw background and border
-        for row in 0..palette_height {
-            for col in 0..palette_width {
-                let char_x = palette_x + (col as f32 * cell_width as f32);
-                let char_y = palette_y + (row as f32 * cell_height as f32);
-
-                let (ch, fg, bg) = if row == 0 || row == palette_height - 1 {
-                    // Top or bottom border
-                    if col == 0 || col == palette_width - 1 {
-                        ('+', border_color, bg_color)
-                    } else {
-                        ('-', border_color, bg_color)
-                    }
-                } else if col == 0 || col == palette_width - 1 {
-                    // Side borders
-                    ('|', border_color, bg_color)
-                } else {
-                    // Interior
-                    (' ', text_color, bg_color)
-                };
-
-                self.text_renderer.queue_char(
-                    ch,
-                    char_x,
-                    char_y,
-                    fg,
-                    bg,
-                    false,
-                    false,
-                    false,
-                    false,
-                )?;
-            }
-        }
+        // Backdrop fill and a crisp 1px border, rather than a grid of
+        // `+`/`-`/`|` glyphs.
+        let palette_pixel_width = palette_width as f32 * cell_width as f32;
+        let palette_pixel_height = palette_height as f32 * cell_height as f32;
+        self.rect_renderer
+            .queue_filled(palette_x, palette_y, palette_pixel_width, palette_pixel_height, bg_color);
+        self.rect_renderer
+            .queue_outline(palette_x, palette_y, palette_pixel_width, palette_pixel_height, 1.0, border_color);
 
         // Draw title
-        let title = match ai_palette.state {
+        let title = match &ai_palette.state {
             PaletteState::Open => " AI Command Palette ",
             PaletteState::Processing => " AI Processing... ",
+            PaletteState::Streaming => " AI Response (streaming) ",
             PaletteState::ShowingResponse => " AI Response ",
+            PaletteState::ConfirmAction { dangerous: true, .. } => " Confirm Dangerous Command ",
+            PaletteState::ConfirmAction { .. } => " Confirm Command ",
             _ => " AI Command Palette ",
         };
 
@@ -562,7 +1351,7 @@ impl RendererHolder {
                 bg_color,
                 true,
                 false,
-                false,
+                &[],
                 false,
             )?;
         }
@@ -572,7 +1361,7 @@ impl RendererHolder {
         let content_x = palette_x + (2.0 * cell_width as f32);
         let max_content_width = palette_width - 4;
 
-        match ai_palette.state {
+        match &ai_palette.state {
             PaletteState::Open => {
                 // Draw prompt
                 let prompt_label = "> ";
@@ -585,7 +1374,7 @@ impl RendererHolder {
                         bg_color,
                         false,
                         false,
-                        false,
+                        &[],
                         false,
                     )?;
                 }
@@ -604,25 +1393,54 @@ impl RendererHolder {
                         bg_color,
                         false,
                         false,
-                        false,
+                        &[],
                         false,
                     )?;
                 }
 
-                // Draw cursor
-                let cursor_pos = input_start + ai_palette.cursor_pos;
+                // Draw in-progress IME composition at the text cursor,
+                // underlined so it's visually distinct from committed input.
+                // `cursor_pos` is a byte offset into `input`, not a char
+                // count, so it's translated the same way `handle_char` reads
+                // it.
+                let cursor_char_idx = ai_palette.input[..ai_palette.cursor_pos].chars().count();
+                let ime_start = input_start + cursor_char_idx;
+                if !ime.is_empty() {
+                    let underline = [ui::text::LineDecoration {
+                        style: ui::text::LineStyle {
+                            kind: ui::text::LineKind::Solid,
+                            position: ui::text::LinePosition::Under,
+                        },
+                        color: None,
+                    }];
+                    for (i, ch) in ime.text.chars().enumerate() {
+                        if i + ime_start >= max_content_width {
+                            break;
+                        }
+                        self.text_renderer.queue_char(
+                            ch,
+                            content_x + ((ime_start + i) as f32 * cell_width as f32),
+                            content_y,
+                            text_color,
+                            bg_color,
+                            false,
+                            false,
+                            &underline,
+                            false,
+                        )?;
+                    }
+                }
+
+                // Draw cursor: an underline bar rather than the `▏` glyph.
+                let cursor_pos = ime_start + ime.text.chars().count();
                 if cursor_pos < max_content_width {
-                    self.text_renderer.queue_char(
-                        '▏',
+                    self.rect_renderer.queue_filled_foreground(
                         content_x + (cursor_pos as f32 * cell_width as f32),
-                        content_y,
+                        content_y + cell_height as f32 - 2.0,
+                        cell_width as f32,
+                        2.0,
                         cursor_color,
-                        bg_color,
-                        false,
-                        false,
-                        false,
-                        false,
-                    )?;
+                    );
                 }
 
                 // Draw hint
@@ -640,7 +1458,7 @@ impl RendererHolder {
                         bg_color,
                         false,
                         false,
-                        false,
+                        &[],
                         false,
                     )?;
                 }
@@ -660,22 +1478,35 @@ impl RendererHolder {
                         bg_color,
                         false,
                         false,
-                        false,
+                        &[],
                         false,
                     )?;
                 }
             }
-            PaletteState::ShowingResponse => {
-                // Draw response
-                let response = ai_palette.get_response();
-                let response_lines: Vec<&str> = response.lines().collect();
+            PaletteState::Streaming | PaletteState::ShowingResponse => {
+                // Draw response (partial if still streaming), highlighting
+                // fenced code blocks distinctly from prose.
+                let segments = ai_palette.get_rendered_response();
+                let mut lines: Vec<(String, bool)> = Vec::new();
+                for segment in &segments {
+                    match segment {
+                        ResponseSegment::Text(text) => {
+                            lines.extend(text.lines().map(|l| (l.to_string(), false)));
+                        }
+                        ResponseSegment::Code(code_block) => {
+                            lines.extend(code_block.code.lines().map(|l| (l.to_string(), true)));
+                        }
+                    }
+                }
+                let has_code_block = first_code_block(&segments).is_some();
 
-                for (line_idx, line) in response_lines.iter().enumerate() {
+                for (line_idx, (line, is_code)) in lines.iter().enumerate() {
                     if line_idx >= palette_height - 5 {
                         break; // Don't overflow the palette
                     }
 
                     let line_y = content_y + (line_idx as f32 * cell_height as f32);
+                    let line_color = if *is_code { code_color } else { text_color };
 
                     for (i, ch) in line.chars().enumerate() {
                         if i >= max_content_width {
@@ -685,37 +1516,105 @@ impl RendererHolder {
                             ch,
                             content_x + (i as f32 * cell_width as f32),
                             line_y,
-                            text_color,
+                            line_color,
                             bg_color,
                             false,
                             false,
-                            false,
+                            &[],
                             false,
                         )?;
                     }
                 }
-            }
-            _ => {}
-        }
 
-        // Draw error if present
-        if let Some(ref error) = ai_palette.error {
-            let error_y = palette_y + ((palette_height - 2) as f32 * cell_height as f32);
-            for (i, ch) in error.chars().enumerate() {
-                if i >= max_content_width {
-                    break;
+                if ai_palette.state == PaletteState::ShowingResponse && has_code_block {
+                    let hint = "Tab to extract code block as a command";
+                    let hint_y = palette_y + ((palette_height - 2) as f32 * cell_height as f32);
+                    for (i, ch) in hint.chars().enumerate() {
+                        if i >= max_content_width {
+                            break;
+                        }
+                        self.text_renderer.queue_char(
+                            ch,
+                            content_x + (i as f32 * cell_width as f32),
+                            hint_y,
+                            hint_color,
+                            bg_color,
+                            false,
+                            false,
+                            &[],
+                            false,
+                        )?;
+                    }
                 }
-                self.text_renderer.queue_char(
-                    ch,
-                    content_x + (i as f32 * cell_width as f32),
-                    error_y,
-                    Color::Rgb(255, 100, 100),
-                    bg_color,
-                    false,
-                    false,
-                    false,
-                    false,
-                )?;
+            }
+            PaletteState::ConfirmAction {
+                command,
+                explanation,
+                dangerous,
+            } => {
+                let lines: Vec<String> = if *dangerous {
+                    vec![
+                        format!("$ {}", command),
+                        explanation.clone(),
+                        String::new(),
+                        "This command is potentially dangerous.".to_string(),
+                        "Press Enter twice to place it on the command line, Esc to cancel.".to_string(),
+                    ]
+                } else {
+                    vec![
+                        format!("$ {}", command),
+                        explanation.clone(),
+                        String::new(),
+                        "Press Enter to place it on the command line, Esc to cancel.".to_string(),
+                    ]
+                };
+
+                for (line_idx, line) in lines.iter().enumerate() {
+                    if line_idx >= palette_height - 5 {
+                        break; // Don't overflow the palette
+                    }
+
+                    let line_y = content_y + (line_idx as f32 * cell_height as f32);
+
+                    for (i, ch) in line.chars().enumerate() {
+                        if i >= max_content_width {
+                            break;
+                        }
+                        self.text_renderer.queue_char(
+                            ch,
+                            content_x + (i as f32 * cell_width as f32),
+                            line_y,
+                            text_color,
+                            bg_color,
+                            false,
+                            false,
+                            &[],
+                            false,
+                        )?;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Draw error if present
+        if let Some(ref error) = ai_palette.error {
+            let error_y = palette_y + ((palette_height - 2) as f32 * cell_height as f32);
+            for (i, ch) in error.chars().enumerate() {
+                if i >= max_content_width {
+                    break;
+                }
+                self.text_renderer.queue_char(
+                    ch,
+                    content_x + (i as f32 * cell_width as f32),
+                    error_y,
+                    Color::Rgb(255, 100, 100),
+                    bg_color,
+                    false,
+                    false,
+                    &[],
+                    false,
+                )?;
             }
         }
 
@@ -725,9 +1624,10 @@ impl RendererHolder {
     fn draw_pane_borders(
         &mut self,
         bounds: Rect,
+        is_focused: bool,
+        title: &str,
         cell_width: u32,
         cell_height: u32,
-        is_focused: bool,
     ) -> Result<(), ui::renderer::RendererError> {
         use terminal::parser::Color;
 
@@ -738,103 +1638,62 @@ impl RendererHolder {
             Color::Rgb(76, 76, 76) // Dark gray
         };
 
-        let bg_color = Color::Rgb(2, 2, 2); // Very dark background
-
-        let x = bounds.x as f32;
-        let y = bounds.y as f32;
-        let width = bounds.width as usize;
-        let height = bounds.height as usize;
-
-        // Calculate grid dimensions for borders
-        let border_cols = width / cell_width as usize;
-        let border_rows = height / cell_height as usize;
-
-        // Draw top border
-        for col in 0..border_cols {
-            let char_x = x + (col as f32 * cell_width as f32);
-            let char_y = y;
-
-            let border_char = if col == 0 {
-                '┌' // Top-left corner
-            } else if col == border_cols - 1 {
-                '┐' // Top-right corner
-            } else {
-                '─' // Horizontal line
-            };
-
-            self.text_renderer.queue_char(
-                border_char,
-                char_x,
-                char_y,
-                border_color,
-                bg_color,
-                true,  // bold
-                false, // italic
-                false, // underline
-                false, // blink
-            )?;
-        }
-
-        // Draw bottom border
-        for col in 0..border_cols {
-            let char_x = x + (col as f32 * cell_width as f32);
-            let char_y = y + ((border_rows - 1) as f32 * cell_height as f32);
-
-            let border_char = if col == 0 {
-                '└' // Bottom-left corner
-            } else if col == border_cols - 1 {
-                '┘' // Bottom-right corner
-            } else {
-                '─' // Horizontal line
-            };
-
-            self.text_renderer.queue_char(
-                border_char,
-                char_x,
-                char_y,
-                border_color,
-                bg_color,
-                true,
-                false,
-                false,
-                false,
-            )?;
-        }
-
-        // Draw left border
-        for row in 1..border_rows - 1 {
-            let char_x = x;
-            let char_y = y + (row as f32 * cell_height as f32);
-
-            self.text_renderer.queue_char(
-                '│', // Vertical line
-                char_x,
-                char_y,
-                border_color,
-                bg_color,
-                true,
-                false,
-                false,
-                false,
-            )?;
-        }
-
-        // Draw right border
-        for row in 1..border_rows - 1 {
-            let char_x = x + ((border_cols - 1) as f32 * cell_width as f32);
-            let char_y = y + (row as f32 * cell_height as f32);
-
-            self.text_renderer.queue_char(
-                '│', // Vertical line
-                char_x,
-                char_y,
-                border_color,
-                bg_color,
-                true,
-                false,
-                false,
-                false,
-            )?;
+        // A crisp 1px outline rather than a ring of `┌─┐│ │└─┘` glyphs - no
+        // longer tied to how the font happens to render box-drawing chars.
+        self.rect_renderer.queue_outline(
+            bounds.x as f32,
+            bounds.y as f32,
+            bounds.width as f32,
+            bounds.height as f32,
+            1.0,
+            border_color,
+        );
+
+        // Centered, truncated pane title straddling the top border, so
+        // splits stay identifiable without the old box-drawn `┤ title ├`
+        // relying on the font for crisp borders.
+        if !title.is_empty() {
+            let max_chars = ((bounds.width / cell_width) as usize).saturating_sub(2);
+            if max_chars >= 3 {
+                let truncated: String = if title.chars().count() > max_chars.saturating_sub(2) {
+                    title
+                        .chars()
+                        .take(max_chars.saturating_sub(3))
+                        .chain(std::iter::once('…'))
+                        .collect()
+                } else {
+                    title.to_string()
+                };
+                let label = format!(" {} ", truncated);
+                let label_chars: Vec<char> = label.chars().collect();
+                let label_width = label_chars.len() as u32 * cell_width;
+
+                let label_x = bounds.x + bounds.width.saturating_sub(label_width) / 2;
+                let label_y = bounds.y.saturating_sub(cell_height / 2);
+                let label_bg = Color::Rgb(20, 20, 20);
+
+                self.rect_renderer.queue_filled(
+                    label_x as f32,
+                    label_y as f32,
+                    label_width as f32,
+                    cell_height as f32,
+                    label_bg,
+                );
+
+                for (i, ch) in label_chars.into_iter().enumerate() {
+                    self.text_renderer.queue_char(
+                        ch,
+                        label_x as f32 + (i as f32 * cell_width as f32),
+                        label_y as f32,
+                        border_color,
+                        label_bg,
+                        false,
+                        false,
+                        &[],
+                        false,
+                    )?;
+                }
+            }
         }
 
         Ok(())
@@ -842,7 +1701,7 @@ impl RendererHolder {
 }
 
 impl TerminalApp {
-    fn new() -> Self {
+    fn new(event_proxy: EventLoopProxy<UserEvent>) -> Self {
         let config = AppConfig::default();
 
         Self {
@@ -853,8 +1712,7 @@ impl TerminalApp {
             selection_state: SelectionState::new(),
             clipboard: Clipboard::new(),
             running: false,
-            last_frame: Instant::now(),
-            frame_duration: Duration::from_micros(16_667), // ~60 FPS
+            event_proxy,
             cell_width: 10,
             cell_height: 20,
             cursor_position: None,
@@ -862,6 +1720,20 @@ impl TerminalApp {
             search_state: SearchState::new(),
             search_input: String::new(),
             ai_palette: AICommandPalette::new(),
+            ime_preedit: String::new(),
+            ime_preedit_cursor: None,
+            hint_mode: false,
+            hints: Vec::new(),
+            hint_input: String::new(),
+            app_config: config,
+            resize_drag: None,
+            mouse_report_drag: None,
+            last_click: None,
+            click_count: 0,
+            vi_mode: None,
+            theme_registry: ui::theme::user_themes_dir()
+                .map(ThemeRegistry::load)
+                .unwrap_or_default(),
         }
     }
 
@@ -875,8 +1747,11 @@ impl TerminalApp {
 
         let pty = PtySession::spawn(config)?;
         let bounds = Rect::new(0, 0, cols as u32 * self.cell_width, rows as u32 * self.cell_height);
-        
-        Ok(Pane::new(pty, cols as usize, rows as usize, bounds))
+
+        let mut pane = Pane::new(pty, cols as usize, rows as usize, bounds);
+        self.apply_active_theme(&mut pane);
+        Self::spawn_pty_watcher(pane.pty.clone(), pane.pending_output.clone(), self.event_proxy.clone());
+        Ok(pane)
     }
 
     /// Create a new pane with PTY
@@ -888,89 +1763,131 @@ impl TerminalApp {
         };
 
         let pty = PtySession::spawn(config)?;
-        Ok(Pane::new(pty, cols, rows, bounds))
+        let mut pane = Pane::new(pty, cols, rows, bounds);
+        self.apply_active_theme(&mut pane);
+        Self::spawn_pty_watcher(pane.pty.clone(), pane.pending_output.clone(), self.event_proxy.clone());
+        Ok(pane)
     }
 
-    /// Read and process PTY output from all panes (non-blocking with batching)
-    fn read_all_pty_output(&mut self) {
-        if let Some(ref mut layout) = self.layout {
-            // Get all pane IDs
-            let pane_ids = layout.all_pane_ids();
-            
-            // Read from each pane
-            for pane_id in pane_ids {
-                if let Some(pane) = layout.get_pane_mut(pane_id) {
-                    Self::read_pane_output(pane);
-                }
+    /// Push `app_config.active_theme`'s colors into `pane`'s palette, if
+    /// both a theme is selected and it's present in `theme_registry` -
+    /// what makes the terminal grid actually render in the chosen theme
+    /// instead of the built-in XTerm defaults.
+    fn apply_active_theme(&self, pane: &mut Pane) {
+        if let Some(name) = &self.app_config.active_theme {
+            if let Some(theme) = self.theme_registry.get(name) {
+                theme.apply_to_palette(pane.parser.palette_mut());
             }
         }
     }
 
-    /// Read and process PTY output from a single pane
-    fn read_pane_output(pane: &mut Pane) {
-        // Batch read from PTY - accumulate multiple reads before processing
-        let mut data = Vec::with_capacity(16384); // Start with 16KB capacity
-
-        // Try to read multiple times to batch available data
-        let mut has_data = false;
-        for _ in 0..5 {
-            // Attempt to read more data (limit to 5 attempts to avoid blocking)
+    /// Spawn a background thread that reads `pty` until EOF, buffering bytes
+    /// into `pending` and waking the event loop via `proxy` whenever it reads
+    /// some. Draining `pending` and feeding it to the parser still happens
+    /// on the main thread (see `read_pane_output`), so `grid`/`parser` stay
+    /// single-threaded; this thread only ever touches the raw byte stream.
+    ///
+    /// This is what lets `about_to_wait` sit in `ControlFlow::Wait` instead
+    /// of polling on a fixed cadence: the winit thread is only ever woken by
+    /// an actual window event or a `UserEvent::PtyData` from here. The PTY
+    /// fd is still non-blocking under the hood (`portable_pty` doesn't
+    /// expose a poll/epoll handle to block on), so this thread itself polls
+    /// with a short sleep on `WouldBlock` - but that polling now happens off
+    /// the winit thread, where it doesn't cost redraw latency or burn the
+    /// main loop's wakeups.
+    fn spawn_pty_watcher(
+        pty: Arc<Mutex<Option<PtySession>>>,
+        pending: Arc<Mutex<Vec<u8>>>,
+        proxy: EventLoopProxy<UserEvent>,
+    ) {
+        std::thread::spawn(move || loop {
             let read_result = {
-                if let Ok(mut session) = pane.pty.lock() {
-                    let mut buf = vec![0u8; 4096];
-                    match session.read(&mut buf) {
-                        Ok(0) => {
-                            // EOF - PTY closed
-                            tracing::info!("PTY closed for pane {}", pane.id);
-                            return;
-                        }
-                        Ok(n) => {
-                            buf.truncate(n);
-                            (true, Some(buf))
-                        }
-                        Err(e) => {
-                            // Would block is expected when no data available
-                            let err_str = e.to_string();
-                            if !err_str.contains("Would block")
-                                && !err_str.contains("Resource temporarily unavailable")
-                            {
-                                tracing::debug!("PTY read error: {}", e);
+                let mut guard = pty.lock();
+                match guard.as_mut() {
+                    Some(session) => {
+                        let mut buf = vec![0u8; 4096];
+                        match session.read(&mut buf) {
+                            Ok(n) => {
+                                buf.truncate(n);
+                                Some(Ok(buf))
                             }
-                            // No more data available
-                            break;
+                            Err(e) => Some(Err(e)),
                         }
                     }
-                } else {
-                    (false, None)
+                    // Placeholder pane - wait for `ensure_pty` to spawn a shell.
+                    None => None,
                 }
             };
 
             match read_result {
-                (_, Some(buf)) if !buf.is_empty() => {
-                    data.extend_from_slice(&buf);
-                    has_data = true;
+                Some(Ok(buf)) if buf.is_empty() => {
+                    tracing::info!("PTY closed");
+                    break;
                 }
-                (_, None) => {
-                    break; // No more data or error
+                Some(Ok(buf)) => {
+                    pending.lock().extend_from_slice(&buf);
+                    if proxy.send_event(UserEvent::PtyData).is_err() {
+                        break; // event loop is gone
+                    }
                 }
-                _ => {
-                    break;
+                Some(Err(e)) => {
+                    let err_str = e.to_string();
+                    if !err_str.contains("Would block")
+                        && !err_str.contains("Resource temporarily unavailable")
+                    {
+                        tracing::debug!("PTY read error: {}", e);
+                    }
+                    std::thread::sleep(Duration::from_millis(4));
                 }
+                None => std::thread::sleep(Duration::from_millis(10)),
             }
-        }
+        });
+    }
+
+    /// Read and process PTY output from all panes. `UserEvent::PtyData`
+    /// doesn't carry which pane buffered bytes, so a wake-up drains every
+    /// pane; `read_pane_output` makes that cheap by bailing out on a single
+    /// lock-and-check when a pane's buffer is empty.
+    fn read_all_pty_output(&mut self) {
+        if let Some(ref mut layout) = self.layout {
+            // Get all pane IDs
+            let pane_ids = layout.all_pane_ids();
 
-        // Process the data if we accumulated any
-        if has_data && !data.is_empty() {
-            Self::process_pane_output(pane, &data);
+            // Read from each pane
+            for pane_id in pane_ids {
+                if let Some(pane) = layout.get_pane_mut(pane_id) {
+                    Self::read_pane_output(pane);
+                }
+            }
         }
     }
 
+    /// Drain the bytes this pane's watcher thread has buffered and feed them
+    /// through the parser to the grid.
+    fn read_pane_output(pane: &mut Pane) {
+        let data = {
+            let mut pending = pane.pending_output.lock();
+            if pending.is_empty() {
+                return;
+            }
+            std::mem::take(&mut *pending)
+        };
+
+        Self::process_pane_output(pane, &data);
+    }
+
     /// Process terminal output bytes through the parser to the grid for a specific pane.
     fn process_pane_output(pane: &mut Pane, data: &[u8]) {
-        // Sync grid colors/attributes from parser state before processing
+        // Sync grid colors/attributes/open hyperlink from parser state before processing
         pane.grid.set_foreground(pane.parser.state.fg_color);
         pane.grid.set_background(pane.parser.state.bg_color);
         pane.grid.set_attributes(pane.parser.state.attributes);
+        pane.grid.set_hyperlink(pane.parser.state.open_hyperlink.clone());
+
+        // Fresh output snaps the view back to the live screen, matching how
+        // most terminals treat scrollback as a read-only look at history
+        // rather than something that stays pinned while a program runs.
+        pane.grid.scroll(terminal::grid::Scroll::Bottom);
 
         // Use batch mode for grid updates to reduce overhead
         pane.grid.begin_batch();
@@ -980,6 +1897,13 @@ impl TerminalApp {
 
         // Flush batched updates
         pane.grid.flush_batch();
+
+        // Pick up any window/icon title the program just set (OSC 0/1/2),
+        // so `draw_pane_borders` can show it without re-reading the parser.
+        let parser_title = pane.parser.title();
+        if !parser_title.is_empty() && parser_title != pane.title {
+            pane.title = parser_title.to_string();
+        }
     }
 
     /// Send input to the focused pane's PTY
@@ -989,9 +1913,25 @@ impl TerminalApp {
                 let focused_id = layout.focused_pane_id();
                 if let Some(ref mut layout) = self.layout {
                     if let Some(pane) = layout.get_pane_mut(focused_id) {
-                        if let Ok(mut session) = pane.pty.lock() {
-                            if let Err(e) = session.write(data) {
-                                tracing::error!("Failed to write to PTY: {}", e);
+                        // Typing (or pasting) snaps the view back to the
+                        // live screen, the same way fresh PTY output does.
+                        pane.grid.scroll(terminal::grid::Scroll::Bottom);
+
+                        match pane.ensure_pty() {
+                            Err(e) => tracing::error!("Failed to spawn PTY: {}", e),
+                            Ok(spawned) => {
+                                if spawned {
+                                    Self::spawn_pty_watcher(
+                                        pane.pty.clone(),
+                                        pane.pending_output.clone(),
+                                        self.event_proxy.clone(),
+                                    );
+                                }
+                                if let Some(session) = pane.pty.lock().as_mut() {
+                                    if let Err(e) = session.write(data) {
+                                        tracing::error!("Failed to write to PTY: {}", e);
+                                    }
+                                }
                             }
                         }
                     }
@@ -1000,6 +1940,16 @@ impl TerminalApp {
         }
     }
 
+    /// Ask winit for another `RedrawRequested` the next time it's convenient,
+    /// rather than rendering synchronously here. Cheap to call liberally: the
+    /// eventual `render_layout` call already skips all GPU work via per-pane
+    /// damage tracking when nothing actually changed.
+    fn request_redraw(&self) {
+        if let Some(ref window) = self.window {
+            window.request_redraw();
+        }
+    }
+
     /// Handle window resize
     fn handle_resize(&mut self, width: u32, height: u32) {
         // Resize the renderer
@@ -1025,9 +1975,21 @@ impl TerminalApp {
                         pane.parser.resize(new_cols, new_rows);
 
                         // Resize the PTY
-                        if let Ok(mut session) = pane.pty.lock() {
-                            if let Err(e) = session.resize(new_cols as u16, new_rows as u16) {
-                                tracing::error!("Failed to resize PTY: {}", e);
+                        match pane.ensure_pty() {
+                            Err(e) => tracing::error!("Failed to spawn PTY: {}", e),
+                            Ok(spawned) => {
+                                if spawned {
+                                    Self::spawn_pty_watcher(
+                                        pane.pty.clone(),
+                                        pane.pending_output.clone(),
+                                        self.event_proxy.clone(),
+                                    );
+                                }
+                                if let Some(session) = pane.pty.lock().as_mut() {
+                                    if let Err(e) = session.resize(new_cols as u16, new_rows as u16) {
+                                        tracing::error!("Failed to resize PTY: {}", e);
+                                    }
+                                }
                             }
                         }
                     }
@@ -1041,9 +2003,44 @@ impl TerminalApp {
         // Update AI palette state (check for async responses)
         self.ai_palette.update();
 
+        // Drain any in-flight background search results
+        self.poll_search();
+
+        // Hand off a command the user confirmed in the AI palette to the
+        // focused pane, as if it had been typed there - but stop short of
+        // pressing Enter, so a proposed command always lands on the shell's
+        // input line for the user to review (and edit, or back out of)
+        // before they run it themselves.
+        if let Some(command) = self.ai_palette.take_confirmed_command() {
+            self.send_pty_input(command.as_bytes());
+        }
+
+        let ime = ImePreedit {
+            text: &self.ime_preedit,
+            cursor: self.ime_preedit_cursor.clone(),
+        };
+
+        // Resolve before borrowing `self.renderer`/`self.layout` below, since
+        // it needs `&self` to walk the layout tree.
+        let hovered = self
+            .cursor_position
+            .and_then(|pos| self.pixel_to_pane_and_grid(pos.x, pos.y));
+        let hyperlinks = HyperlinkOverlay {
+            hovered,
+            ctrl_held: self.modifiers.control_key(),
+            hint_mode: self.hint_mode,
+            hints: &self.hints,
+            hint_input: &self.hint_input,
+        };
+        let cursor_config = CursorConfig {
+            style: self.app_config.cursor_style,
+            blink: self.app_config.cursor_blink,
+        };
+
+        let mut rendered_ok = false;
         if let (Some(ref mut renderer), Some(ref layout)) = (&mut self.renderer, &self.layout) {
             let focused_id = layout.focused_pane_id();
-            if let Err(e) = renderer.render_layout(
+            match renderer.render_layout(
                 layout,
                 self.cell_width,
                 self.cell_height,
@@ -1051,8 +2048,27 @@ impl TerminalApp {
                 &self.search_state,
                 &self.search_input,
                 &self.ai_palette,
+                &ime,
+                &hyperlinks,
+                cursor_config,
+                &self.selection_state,
+                self.vi_mode.as_ref(),
             ) {
-                tracing::error!("Render error: {}", e);
+                Ok(()) => rendered_ok = true,
+                Err(e) => tracing::error!("Render error: {}", e),
+            }
+        }
+
+        // Now that the frame is on screen, clear every pane's damage so the
+        // next frame only re-queues what changes after this point.
+        if rendered_ok {
+            if let Some(ref mut layout) = self.layout {
+                let pane_ids = layout.all_pane_ids();
+                for pane_id in pane_ids {
+                    if let Some(pane) = layout.get_pane_mut(pane_id) {
+                        pane.grid.reset_damage();
+                    }
+                }
             }
         }
     }
@@ -1087,12 +2103,75 @@ impl TerminalApp {
         button: MouseButton,
         state: ElementState,
     ) {
-        // Only handle left mouse button for selection
+        let sgr_button = match button {
+            MouseButton::Left => Some(0u8),
+            MouseButton::Middle => Some(1u8),
+            MouseButton::Right => Some(2u8),
+            _ => None,
+        };
+
+        // A mouse-aware program (vim, tmux, htop) gets first refusal on
+        // clicks, unless Shift is held - the standard xterm override that
+        // still lets a user select text locally. Takes priority over
+        // divider drags/selection below, which only apply when reporting
+        // is off.
+        if let (Some(pos), Some(code)) = (self.cursor_position, sgr_button) {
+            if !self.modifiers.shift_key() {
+                if let Some((pane_id, col, row)) = self.pixel_to_pane_and_grid(pos.x, pos.y) {
+                    if self.mouse_report_mode(pane_id).is_some() {
+                        if let Some(ref mut layout) = self.layout {
+                            layout.set_focus(pane_id);
+                        }
+                        let press = state == ElementState::Pressed;
+                        let report = sgr_mouse_report(code, col, row, press, false, self.modifiers);
+                        self.send_pty_input(&report);
+                        self.mouse_report_drag = if press {
+                            Some(MouseReportDrag { pane_id, button: code })
+                        } else {
+                            None
+                        };
+                        return;
+                    }
+                }
+            }
+        }
+
+        // Middle-click pastes the PRIMARY selection (the standard X11/
+        // Wayland behavior), independent of local selection/divider
+        // dragging below.
+        if button == MouseButton::Middle && state == ElementState::Pressed {
+            let _ = self.handle_paste(ui::selection::ClipboardType::Selection);
+            return;
+        }
+
+        // Only handle left mouse button for local selection and divider dragging
         if button != MouseButton::Left {
             return;
         }
 
         if let Some(pos) = self.cursor_position {
+            if state == ElementState::Pressed {
+                if let Some(hit) = self.divider_at(pos) {
+                    // A press landing on a split divider starts a resize
+                    // drag instead of a text selection.
+                    self.resize_drag = Some(ResizeDrag {
+                        hit,
+                        last_x: pos.x as i32,
+                        last_y: pos.y as i32,
+                    });
+                    return;
+                }
+            } else if self.resize_drag.take().is_some() {
+                // Releasing ends the drag; reflow grids and PTYs to match
+                // the ratio the drag settled on (the drag itself only
+                // updated bounds - see `handle_mouse_motion`).
+                if let Some(window) = &self.window {
+                    let size = window.inner_size();
+                    self.handle_resize(size.width, size.height);
+                }
+                return;
+            }
+
             if let Some((pane_id, col, row)) = self.pixel_to_pane_and_grid(pos.x, pos.y) {
                 // Focus the clicked pane
                 if let Some(ref mut layout) = self.layout {
@@ -1100,33 +2179,96 @@ impl TerminalApp {
                 }
 
                 use crate::terminal::grid::Cursor;
+                use ui::selection::SelectionType;
+
+                // Ctrl+click on a link launches it instead of starting a
+                // selection - OSC 8 links open unconditionally on click
+                // (nothing else uses a plain click on one), but a plain-text
+                // hint only opens with Ctrl held, so a normal click can still
+                // select its text.
+                if state == ElementState::Pressed {
+                    let on_osc8_link = self
+                        .layout
+                        .as_ref()
+                        .and_then(|l| l.get_pane(pane_id))
+                        .and_then(|p| p.grid.get_cell(row, col))
+                        .is_some_and(|cell| cell.hyperlink.is_some());
+                    if on_osc8_link || self.modifiers.control_key() {
+                        if let Some(uri) = self.link_at(pane_id, row, col) {
+                            Self::open_url(&uri);
+                            return;
+                        }
+                    }
+                }
 
                 match state {
                     ElementState::Pressed => {
-                        // Start selection
-                        self.selection_state.start_selection(Cursor::new(row, col));
+                        // A repeated click on the same cell within
+                        // `CLICK_MULTI_WINDOW` advances Normal -> Semantic ->
+                        // Line -> Normal, mirroring double/triple-click
+                        // conventions in other terminals.
+                        let now = std::time::Instant::now();
+                        self.click_count = match self.last_click {
+                            Some((t, id, r, c))
+                                if id == pane_id
+                                    && r == row
+                                    && c == col
+                                    && now.duration_since(t) < CLICK_MULTI_WINDOW =>
+                            {
+                                self.click_count % 3 + 1
+                            }
+                            _ => 1,
+                        };
+                        self.last_click = Some((now, pane_id, row, col));
+
+                        // Alt+drag always starts a rectangular (block)
+                        // selection, taking priority over the click-count
+                        // cycle - block selection doesn't have a multi-click
+                        // equivalent the way Semantic/Line do.
+                        let kind = if self.modifiers.alt_key() {
+                            SelectionType::Block
+                        } else {
+                            match self.click_count {
+                                1 => SelectionType::Normal,
+                                2 => SelectionType::Semantic,
+                                _ => SelectionType::Line,
+                            }
+                        };
+                        let pane = self.layout.as_ref().and_then(|l| l.get_pane(pane_id));
+                        let grid = pane.map(|p| p.grid.as_rows());
+                        self.selection_state.start_selection_wrapped(
+                            Cursor::new(row, col),
+                            kind,
+                            grid.unwrap_or(&[]),
+                            |r| pane.is_some_and(|p| p.grid.is_row_wrapped(r)),
+                        );
                     }
                     ElementState::Released => {
-                        // End selection
-                        self.selection_state.end_selection();
-
-                        // If Shift is held, copy to clipboard
-                        if self.modifiers.shift_key() && self.selection_state.has_selection() {
-                            // Get the focused pane's grid
-                            if let Some(ref layout) = self.layout {
-                                if let Some(pane) = layout.focused_pane() {
-                                    let selected_text = extract_selected_text(
-                                        pane.grid.as_rows(),
-                                        &self.selection_state.region,
-                                    );
-                                    if !selected_text.is_empty() {
-                                        if let Err(e) = self.clipboard.copy(&selected_text) {
-                                            tracing::warn!("Failed to copy to clipboard: {}", e);
-                                        } else {
-                                            tracing::debug!("Copied selection to clipboard");
-                                        }
-                                    }
-                                }
+                        // End selection, pushing the extracted text into
+                        // the PRIMARY selection ("copy on select") and the
+                        // normal clipboard - mouse selection always copies
+                        // on release, the same way it does in most
+                        // terminal emulators.
+                        let mut selected_text = String::new();
+                        if let Some(ref layout) = self.layout {
+                            if let Some(pane) = layout.focused_pane() {
+                                selected_text = self.selection_state.end_selection_and_copy(
+                                    pane.grid.as_rows(),
+                                    |r| pane.grid.is_row_wrapped(r),
+                                    &self.clipboard,
+                                );
+                            } else {
+                                self.selection_state.end_selection();
+                            }
+                        } else {
+                            self.selection_state.end_selection();
+                        }
+
+                        if !selected_text.is_empty() {
+                            if let Err(e) = self.clipboard.copy(&selected_text) {
+                                tracing::warn!("Failed to copy to clipboard: {}", e);
+                            } else {
+                                tracing::debug!("Copied selection to clipboard");
                             }
                         }
                     }
@@ -1138,28 +2280,554 @@ impl TerminalApp {
         }
     }
 
+    /// Pixel distance within which a press on a split border starts a
+    /// resize drag instead of a text selection.
+    const DIVIDER_GRAB_TOLERANCE: u32 = 4;
+
+    /// Find the split divider (if any) under `pos`, within
+    /// `DIVIDER_GRAB_TOLERANCE` pixels.
+    fn divider_at(&self, pos: PhysicalPosition<f64>) -> Option<DividerHit> {
+        let window = self.window.as_ref()?;
+        let size = window.inner_size();
+        let layout = self.layout.as_ref()?;
+        layout.find_divider(
+            pos.x as u32,
+            pos.y as u32,
+            Self::DIVIDER_GRAB_TOLERANCE,
+            Rect::new(0, 0, size.width, size.height),
+        )
+    }
+
+    /// The mouse-report granularity `pane_id`'s parser has requested, if
+    /// any - see `ParserState::mouse_reporting`.
+    fn mouse_report_mode(&self, pane_id: uuid::Uuid) -> Option<terminal::parser::MouseReportMode> {
+        self.layout.as_ref()?.get_pane(pane_id)?.parser.mouse_reporting()
+    }
+
     /// Handle mouse motion
     fn handle_mouse_motion(&mut self, position: PhysicalPosition<f64>) {
         // Update stored cursor position
         self.cursor_position = Some(position);
 
+        // Drive an in-progress divider drag: only bounds are recalculated
+        // per-motion-event (cheap); grids/PTYs are reflowed once on release
+        // (see `handle_mouse_button`) so a fast drag doesn't thrash PTY
+        // resizes on every pixel of movement.
+        if let Some(mut drag) = self.resize_drag.take() {
+            let delta_px = match drag.hit.direction() {
+                SplitDirection::Horizontal => position.x as i32 - drag.last_x,
+                SplitDirection::Vertical => position.y as i32 - drag.last_y,
+            };
+            if delta_px != 0 {
+                if let Some(ref mut layout) = self.layout {
+                    layout.drag_divider(&drag.hit, delta_px);
+                    if let Some(ref window) = self.window {
+                        let size = window.inner_size();
+                        layout.calculate_layout(Rect::new(0, 0, size.width, size.height));
+                    }
+                }
+                drag.last_x = position.x as i32;
+                drag.last_y = position.y as i32;
+            }
+            self.resize_drag = Some(drag);
+            return;
+        }
+
+        // Report motion to a mouse-aware program: drag motion under
+        // whichever button `handle_mouse_button` reported a press for, or
+        // any motion at all if the pane opted into 1003 any-motion (in
+        // which case it only makes sense for the pane that's actually
+        // focused, since that's the only one receiving keyboard input too).
+        if let Some((pane_id, col, row)) = self.pixel_to_pane_and_grid(position.x, position.y) {
+            if let Some(drag) = &self.mouse_report_drag {
+                if drag.pane_id == pane_id
+                    && matches!(
+                        self.mouse_report_mode(pane_id),
+                        Some(terminal::parser::MouseReportMode::Drag)
+                            | Some(terminal::parser::MouseReportMode::AnyMotion)
+                    )
+                {
+                    let report = sgr_mouse_report(drag.button, col, row, true, true, self.modifiers);
+                    self.send_pty_input(&report);
+                    return;
+                }
+            } else if self.layout.as_ref().map(|l| l.focused_pane_id()) == Some(pane_id)
+                && self.mouse_report_mode(pane_id) == Some(terminal::parser::MouseReportMode::AnyMotion)
+            {
+                let report = sgr_mouse_report(3, col, row, true, true, self.modifiers);
+                self.send_pty_input(&report);
+                return;
+            }
+        }
+
         // Only update selection if we're currently selecting
         if self.selection_state.selecting {
-            if let Some((_pane_id, col, row)) = self.pixel_to_pane_and_grid(position.x, position.y) {
+            if let Some((pane_id, col, row)) = self.pixel_to_pane_and_grid(position.x, position.y) {
                 use crate::terminal::grid::Cursor;
-                self.selection_state.update_selection(Cursor::new(row, col));
+                if let Some(ref layout) = self.layout {
+                    if let Some(pane) = layout.get_pane(pane_id) {
+                        self.selection_state.update_selection_wrapped(
+                            Cursor::new(row, col),
+                            pane.grid.as_rows(),
+                            |r| pane.grid.is_row_wrapped(r),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handle a mouse wheel event: page the pane under the cursor through
+    /// its scrollback, rather than always scrolling the focused pane, so
+    /// users can check history in a pane without clicking into it first.
+    fn handle_mouse_wheel(&mut self, delta: MouseScrollDelta) {
+        use terminal::grid::Scroll;
+
+        let Some(pos) = self.cursor_position else {
+            return;
+        };
+        let Some((pane_id, col, row)) = self.pixel_to_pane_and_grid(pos.x, pos.y) else {
+            return;
+        };
+
+        // A mouse-aware program gets the wheel event as a button report
+        // instead of paging local scrollback, unless Shift forces the
+        // local override (same rule as clicks in `handle_mouse_button`).
+        if !self.modifiers.shift_key() {
+            if self.mouse_report_mode(pane_id).is_some() {
+                let up = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y > 0.0,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y > 0.0,
+                };
+                let code = if up { 64 } else { 65 };
+                let report = sgr_mouse_report(code, col, row, true, false, self.modifiers);
+                self.send_pty_input(&report);
+                return;
+            }
+        }
+
+        // Line deltas come in "notches"; pixel deltas (precision trackpads)
+        // are converted through a cell height so a full cell of trackpad
+        // motion pages one scrollback line.
+        let lines = match delta {
+            MouseScrollDelta::LineDelta(_, y) => (y * 3.0).round() as isize,
+            MouseScrollDelta::PixelDelta(pos) => {
+                (pos.y / self.cell_height.max(1) as f64).round() as isize
+            }
+        };
+        if lines == 0 {
+            return;
+        }
+
+        if let Some(ref mut layout) = self.layout {
+            if let Some(pane) = layout.get_pane_mut(pane_id) {
+                pane.grid.scroll(Scroll::Delta(lines));
+            }
+        }
+    }
+
+    /// Handle an IME composition event. Preedit text is only ever stored for
+    /// rendering (see `render_pane`/`render_search_bar`/`render_ai_palette`);
+    /// nothing is sent anywhere until the IME actually commits, at which
+    /// point `commit_text` routes the committed bytes to whichever of the
+    /// PTY, AI palette, or search overlay currently has focus.
+    fn handle_ime_event(&mut self, event: Ime) {
+        match event {
+            Ime::Enabled => {}
+            Ime::Preedit(text, cursor) => {
+                self.ime_preedit = text;
+                self.ime_preedit_cursor = cursor.map(|(start, end)| start..end);
+                self.update_ime_cursor_area();
+            }
+            Ime::Commit(text) => {
+                self.ime_preedit.clear();
+                self.ime_preedit_cursor = None;
+                self.commit_text(&text);
+            }
+            Ime::Disabled => {
+                self.ime_preedit.clear();
+                self.ime_preedit_cursor = None;
+            }
+        }
+    }
+
+    /// Route IME-committed text to whichever input currently has focus, the
+    /// same way a typed `Key::Character` would be routed in `window_event`.
+    fn commit_text(&mut self, text: &str) {
+        if self.hint_mode {
+            // Hint labels are plain ASCII typed via `Key::Character`, not
+            // composed - an IME commit landing here would otherwise fall
+            // through to the PTY and leak into the shell.
+            return;
+        }
+        if self.ai_palette.is_visible() {
+            for c in text.chars() {
+                self.ai_palette.handle_char(c);
+            }
+        } else if self.search_state.active {
+            for c in text.chars() {
+                self.handle_search_input(c);
+            }
+        } else {
+            self.send_pty_input(text.as_bytes());
+        }
+    }
+
+    /// Position the platform IME candidate window at the focused pane's
+    /// terminal cursor, so composition candidates appear next to what's
+    /// being typed instead of in a corner of the screen.
+    fn update_ime_cursor_area(&self) {
+        let (Some(ref window), Some(ref layout)) = (&self.window, &self.layout) else {
+            return;
+        };
+        let Some(pane) = layout.focused_pane() else {
+            return;
+        };
+        let cursor = pane.grid.cursor();
+        let x = pane.bounds.x + cursor.col as u32 * self.cell_width;
+        let y = pane.bounds.y + cursor.row as u32 * self.cell_height;
+        window.set_ime_cursor_area(
+            PhysicalPosition::new(x, y),
+            PhysicalSize::new(self.cell_width, self.cell_height),
+        );
+    }
+
+    /// Enter hyperlink hint mode (Ctrl+Shift+O): scan the focused pane for
+    /// OSC 8 links and plain-text URL/path/`user@host` matches, and assign
+    /// each a short label the user can type to open it. A no-op if the
+    /// focused pane has nothing hintable on screen.
+    fn enter_hint_mode(&mut self) {
+        let Some(ref layout) = self.layout else {
+            return;
+        };
+        let Some(pane) = layout.focused_pane() else {
+            return;
+        };
+
+        let mut spans = Self::hyperlink_spans(&pane.grid);
+        spans.extend(text_hint_spans(&pane.grid));
+        if spans.is_empty() {
+            return;
+        }
+
+        let labels = generate_hint_labels(spans.len());
+        self.hints = spans
+            .into_iter()
+            .zip(labels)
+            .map(|((row, col, uri), label)| HintEntry { row, col, uri, label })
+            .collect();
+        self.hint_mode = true;
+        self.hint_input.clear();
+    }
+
+    /// Leave hint mode, discarding any in-progress label input.
+    fn exit_hint_mode(&mut self) {
+        self.hint_mode = false;
+        self.hints.clear();
+        self.hint_input.clear();
+    }
+
+    /// Enter vi mode (Ctrl+Shift+Space), anchoring the virtual cursor at
+    /// the focused pane's real terminal cursor.
+    fn enter_vi_mode(&mut self) {
+        let Some(ref layout) = self.layout else {
+            return;
+        };
+        let Some(pane) = layout.focused_pane() else {
+            return;
+        };
+        let cursor = pane.grid.cursor();
+        let row = pane.grid.visible_row_to_absolute(cursor.row);
+        self.vi_mode = Some(ViModeState {
+            cursor: search::Point { row, col: cursor.col },
+            anchor: None,
+        });
+    }
+
+    /// Leave vi mode, discarding any in-progress selection.
+    fn exit_vi_mode(&mut self) {
+        self.vi_mode = None;
+    }
+
+    /// Build the `SelectionRegion` spanning `vi.anchor`..`vi.cursor`, in the
+    /// same absolute row space `extract_selected_text`/`all_rows` use.
+    /// `None` while no selection has been started with `v`.
+    fn vi_selection_region(vi: &ViModeState) -> Option<ui::selection::SelectionRegion> {
+        use crate::terminal::grid::Cursor;
+        vi.anchor.map(|anchor| {
+            ui::selection::SelectionRegion::new(
+                Cursor::new(anchor.row, anchor.col),
+                Cursor::new(vi.cursor.row, vi.cursor.col),
+            )
+        })
+    }
+
+    /// Move the vi-mode virtual cursor and page the viewport to follow it,
+    /// the same way `update_search` reveals the active match.
+    fn vi_move(&mut self, f: impl FnOnce(search::Point, &Pane) -> search::Point) {
+        let Some(ref layout) = self.layout else {
+            return;
+        };
+        let Some(pane) = layout.focused_pane() else {
+            return;
+        };
+        let Some(vi) = self.vi_mode.as_mut() else {
+            return;
+        };
+        vi.cursor = f(vi.cursor, pane);
+        let row = vi.cursor.row;
+
+        if let Some(ref mut layout) = self.layout {
+            if let Some(pane) = layout.focused_pane_mut() {
+                pane.grid.reveal_absolute_row(row);
             }
         }
     }
 
-    /// Handle paste from clipboard (Ctrl+V or Shift+Insert)
-    fn handle_paste(&mut self) -> Result<()> {
-        if let Ok(text) = self.clipboard.paste() {
-            // Convert text to bytes and send to PTY
-            let bytes = text.as_bytes();
-            if !bytes.is_empty() {
-                self.send_pty_input(bytes);
-                tracing::debug!("Pasted {} bytes from clipboard", bytes.len());
+    /// Toggle the selection anchor at the vi-mode cursor (`v`).
+    fn vi_toggle_selection(&mut self) {
+        if let Some(vi) = self.vi_mode.as_mut() {
+            vi.anchor = if vi.anchor.is_some() { None } else { Some(vi.cursor) };
+        }
+    }
+
+    /// Copy the vi-mode selection to the clipboard and leave vi mode (`y`).
+    /// A no-op if `v` hasn't started a selection yet.
+    fn vi_yank(&mut self) {
+        let Some(vi) = self.vi_mode else {
+            return;
+        };
+        let Some(region) = Self::vi_selection_region(&vi) else {
+            return;
+        };
+        let Some(ref layout) = self.layout else {
+            return;
+        };
+        let Some(pane) = layout.focused_pane() else {
+            return;
+        };
+
+        let rows: Vec<Vec<terminal::grid::Cell>> = pane.grid.all_rows().cloned().collect();
+        let text = ui::selection::extract_selected_text_wrapped(
+            &rows,
+            &region,
+            |r| pane.grid.is_row_wrapped_absolute(r),
+        );
+        if !text.is_empty() {
+            if let Err(e) = self.clipboard.copy(&text) {
+                tracing::warn!("Failed to copy vi-mode selection to clipboard: {}", e);
+            } else {
+                tracing::debug!("Yanked vi-mode selection to clipboard");
+            }
+        }
+        self.exit_vi_mode();
+    }
+
+    /// One step of a vi-mode cursor motion; mapped from keys in
+    /// `window_event`'s vi-mode dispatch branch.
+    fn vi_apply_motion(pane: &Pane, pos: search::Point, motion: ViMotion) -> search::Point {
+        let cols = pane.grid.cols();
+        let total_rows = pane.grid.total_rows();
+        let char_at = |row: usize, col: usize| {
+            pane.grid.get_cell_absolute(row, col).map(|c| c.char).unwrap_or(' ')
+        };
+
+        match motion {
+            ViMotion::Left => search::Point {
+                row: pos.row,
+                col: pos.col.saturating_sub(1),
+            },
+            ViMotion::Right => search::Point {
+                row: pos.row,
+                col: (pos.col + 1).min(cols.saturating_sub(1)),
+            },
+            ViMotion::Up => search::Point {
+                row: pos.row.saturating_sub(1),
+                col: pos.col,
+            },
+            ViMotion::Down => search::Point {
+                row: (pos.row + 1).min(total_rows.saturating_sub(1)),
+                col: pos.col,
+            },
+            ViMotion::LineStart => search::Point { row: pos.row, col: 0 },
+            ViMotion::LineEnd => search::Point {
+                row: pos.row,
+                col: cols.saturating_sub(1),
+            },
+            ViMotion::BufferTop => search::Point { row: 0, col: pos.col },
+            ViMotion::BufferBottom => search::Point {
+                row: total_rows.saturating_sub(1),
+                col: pos.col,
+            },
+            ViMotion::HalfPageUp => search::Point {
+                row: pos.row.saturating_sub(pane.grid.rows() / 2),
+                col: pos.col,
+            },
+            ViMotion::HalfPageDown => search::Point {
+                row: (pos.row + pane.grid.rows() / 2).min(total_rows.saturating_sub(1)),
+                col: pos.col,
+            },
+            ViMotion::WordForward => {
+                let mut row = pos.row;
+                let mut col = pos.col;
+
+                // Skip the rest of the current word, if the cursor started on one.
+                if ui::selection::is_semantic_word_char(char_at(row, col)) {
+                    while col < cols && ui::selection::is_semantic_word_char(char_at(row, col)) {
+                        col += 1;
+                    }
+                }
+                // Skip separators until the next word, wrapping to
+                // following rows, or stop at the end of the buffer.
+                loop {
+                    if col >= cols {
+                        if row + 1 >= total_rows {
+                            col = cols.saturating_sub(1);
+                            break;
+                        }
+                        row += 1;
+                        col = 0;
+                        continue;
+                    }
+                    if ui::selection::is_semantic_word_char(char_at(row, col)) {
+                        break;
+                    }
+                    col += 1;
+                }
+                search::Point { row, col }
+            }
+            ViMotion::WordBackward => {
+                let mut row = pos.row;
+                let mut col = pos.col;
+
+                if !vi_step_back(cols, &mut row, &mut col) {
+                    return search::Point { row, col };
+                }
+                while !ui::selection::is_semantic_word_char(char_at(row, col)) {
+                    if !vi_step_back(cols, &mut row, &mut col) {
+                        return search::Point { row, col };
+                    }
+                }
+                while col > 0 && ui::selection::is_semantic_word_char(char_at(row, col - 1)) {
+                    col -= 1;
+                }
+                search::Point { row, col }
+            }
+        }
+    }
+
+    /// Find the starting cell of every distinct hyperlink run visible in
+    /// `grid`, one entry per row a link appears on (a link wrapped across
+    /// multiple rows gets a hint on each row, same as how search highlights
+    /// each row's match independently).
+    fn hyperlink_spans(grid: &TerminalGrid) -> Vec<(usize, usize, String)> {
+        let mut spans = Vec::new();
+        for row in 0..grid.rows() {
+            let mut current_uri: Option<&str> = None;
+            for col in 0..grid.cols() {
+                let uri = grid
+                    .get_cell(row, col)
+                    .and_then(|cell| cell.hyperlink.as_ref())
+                    .map(|link| link.uri.as_str());
+                if uri.is_some() && uri != current_uri {
+                    spans.push((row, col, uri.unwrap().to_string()));
+                }
+                current_uri = uri;
+            }
+        }
+        spans
+    }
+
+    /// The OSC 8 link or plain-text hint under `(row, col)` in `pane_id`'s
+    /// grid, if any - used to resolve a Ctrl+click to a launch target.
+    fn link_at(&self, pane_id: uuid::Uuid, row: usize, col: usize) -> Option<String> {
+        let pane = self.layout.as_ref()?.get_pane(pane_id)?;
+        if let Some(uri) = pane.grid.get_cell(row, col).and_then(|cell| cell.hyperlink.as_ref()) {
+            return Some(uri.uri.clone());
+        }
+        text_hint_spans(&pane.grid)
+            .into_iter()
+            .find(|(r, start, uri)| *r == row && (*start..*start + text_hint_display_len(uri)).contains(&col))
+            .map(|(_, _, uri)| uri)
+    }
+
+    /// Feed one typed character to hint mode: narrow `self.hints` down by
+    /// the accumulated label prefix, and open the link + leave hint mode
+    /// once exactly one remains and matches in full.
+    fn handle_hint_char(&mut self, ch: char) {
+        let mut candidate = self.hint_input.clone();
+        candidate.push(ch.to_ascii_lowercase());
+
+        let matches = self.hints.iter().filter(|h| h.label.starts_with(&candidate)).count();
+        if matches == 0 {
+            // Typo - nothing left to narrow down to, so ignore it and keep
+            // waiting for a valid prefix instead of dropping out of hint mode.
+            return;
+        }
+
+        self.hint_input = candidate;
+        if let Some(hint) = self.hints.iter().find(|h| h.label == self.hint_input) {
+            let uri = hint.uri.clone();
+            self.exit_hint_mode();
+            Self::open_url(&uri);
+        }
+    }
+
+    /// Open `uri` with the OS's default handler, the way a browser would
+    /// treat a clicked link.
+    fn open_url(uri: &str) {
+        let result = if cfg!(target_os = "macos") {
+            std::process::Command::new("open").arg(uri).spawn()
+        } else if cfg!(target_os = "windows") {
+            std::process::Command::new("cmd").args(["/C", "start", "", uri]).spawn()
+        } else {
+            std::process::Command::new("xdg-open").arg(uri).spawn()
+        };
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to open hyperlink '{}': {}", uri, e);
+        }
+    }
+
+    /// Handle paste from clipboard (Ctrl+V or Shift+Insert pastes
+    /// `ClipboardType::Clipboard`; middle-click pastes
+    /// `ClipboardType::Selection`, the PRIMARY buffer).
+    ///
+    /// When the focused pane's parser has bracketed paste mode enabled
+    /// (`CSI ? 2004 h`), wraps the text with the `ESC[200~`/`ESC[201~`
+    /// lead-in/terminator so the receiving program (shells, editors) can
+    /// treat the paste as one atomic block instead of as typed keystrokes.
+    /// Any terminator embedded in the clipboard text itself is stripped
+    /// first so pasted content can't forge an early end-of-paste and have
+    /// the rest of the clipboard interpreted as normal input.
+    fn handle_paste(&mut self, kind: ui::selection::ClipboardType) -> Result<()> {
+        use terminal::parser::TermMode;
+
+        if let Ok(text) = self.clipboard.paste_from(kind) {
+            if text.is_empty() {
+                return Ok(());
+            }
+
+            let bracketed = self
+                .layout
+                .as_ref()
+                .and_then(|layout| layout.focused_pane())
+                .is_some_and(|pane| pane.parser.has_mode(TermMode::BRACKETED_PASTE));
+
+            if bracketed {
+                let sanitized = text.replace("\x1b[201~", "");
+                let mut bytes = Vec::with_capacity(sanitized.len() + 12);
+                bytes.extend_from_slice(b"\x1b[200~");
+                bytes.extend_from_slice(sanitized.as_bytes());
+                bytes.extend_from_slice(b"\x1b[201~");
+                self.send_pty_input(&bytes);
+                tracing::debug!("Pasted {} bytes from clipboard (bracketed)", bytes.len());
+            } else {
+                let bytes = text.as_bytes();
+                if !bytes.is_empty() {
+                    self.send_pty_input(bytes);
+                    tracing::debug!("Pasted {} bytes from clipboard", bytes.len());
+                }
             }
         }
         Ok(())
@@ -1244,36 +2912,70 @@ impl TerminalApp {
 
     /// Update search with current input
     fn update_search(&mut self) {
-        if self.search_state.set_pattern(&self.search_input).is_ok() {
-            // Find matches in focused pane
-            if let Some(ref layout) = self.layout {
-                if let Some(pane) = layout.focused_pane() {
-                    // Collect all rows from the grid
-                    let rows: Vec<(usize, String)> = (0..pane.grid.rows())
-                        .map(|row| {
-                            let mut line = String::new();
-                            for col in 0..pane.grid.cols() {
-                                if let Some(cell) = pane.grid.get_cell(row, col) {
-                                    line.push(cell.char);
-                                } else {
-                                    line.push(' ');
-                                }
-                            }
-                            (row, line)
-                        })
-                        .collect();
+        let _ = self.search_state.set_pattern(&self.search_input);
+        if self.search_state.has_error() {
+            return;
+        }
+
+        // Find matches in focused pane, scanning scrollback as well as the
+        // live screen so a search can turn up lines that have scrolled off.
+        // Row numbers are absolute indices into `all_rows` (oldest
+        // scrollback line first), not viewport-relative ones - the renderer
+        // converts back via `visible_row_to_absolute` when highlighting.
+        let focused_pane_id = if let Some(ref layout) = self.layout {
+            if let Some(pane) = layout.focused_pane() {
+                let rows: Vec<(usize, String, bool)> = pane
+                    .grid
+                    .all_rows()
+                    .enumerate()
+                    .map(|(row, cells)| {
+                        let line: String = cells.iter().map(|cell| cell.char).collect();
+                        (row, line, pane.grid.is_row_wrapped_absolute(row))
+                    })
+                    .collect();
+
+                // Anchor to the top of the visible viewport so the "current"
+                // match stays near what's on screen as the user types. Scans
+                // in the background (see `poll_search`) so typing stays
+                // responsive even with hundreds of thousands of scrollback
+                // lines to search.
+                let anchor = pane.grid.visible_row_to_absolute(0);
+                self.search_state.start_search(rows, anchor);
+                Some(pane.id)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
 
-                    // Update search state with matches
-                    self.search_state.find_matches(rows.iter().map(|(r, l)| (*r, l.as_str())));
+        // Page the viewport so the newly-selected match is actually on
+        // screen, since it may live in scrollback rather than the live grid.
+        if let (Some(pane_id), Some(current)) = (focused_pane_id, self.search_state.current_match()) {
+            let row = current.start.row;
+            if let Some(ref mut layout) = self.layout {
+                if let Some(pane) = layout.get_pane_mut(pane_id) {
+                    pane.grid.reveal_absolute_row(row);
                 }
             }
         }
     }
 
+    /// Drain incremental results from an in-flight background search (see
+    /// `SearchState::start_search`) and page the viewport to the current
+    /// match once one becomes selected. Call once per frame.
+    fn poll_search(&mut self) {
+        if !self.search_state.poll() {
+            return;
+        }
+        self.reveal_current_search_match();
+    }
+
     /// Handle search navigation (next match)
     fn handle_search_next(&mut self) {
         if self.search_state.active {
             self.search_state.next_match();
+            self.reveal_current_search_match();
         }
     }
 
@@ -1281,6 +2983,55 @@ impl TerminalApp {
     fn handle_search_prev(&mut self) {
         if self.search_state.active {
             self.search_state.prev_match();
+            self.reveal_current_search_match();
+        }
+    }
+
+    /// Page the focused pane's viewport so the search match `search_state`
+    /// currently points at is on screen, whether it's in scrollback or the
+    /// live grid.
+    fn reveal_current_search_match(&mut self) {
+        let Some(current) = self.search_state.current_match() else {
+            return;
+        };
+        let row = current.start.row;
+        if let Some(ref mut layout) = self.layout {
+            if let Some(pane) = layout.focused_pane_mut() {
+                pane.grid.reveal_absolute_row(row);
+            }
+        }
+    }
+
+    /// Promote the focused search match into the live selection and copy it
+    /// (Ctrl+Enter), so a regex hit can be pasted elsewhere without first
+    /// closing search and dragging a selection over it by hand.
+    fn handle_search_promote_to_selection(&mut self) {
+        let Some(region) = self.search_state.current_match_as_selection() else {
+            return;
+        };
+        let Some(ref layout) = self.layout else {
+            return;
+        };
+        let Some(pane) = layout.focused_pane() else {
+            return;
+        };
+
+        let all_rows: Vec<Vec<terminal::grid::Cell>> = pane.grid.all_rows().cloned().collect();
+        let text = ui::selection::extract_selected_text_wrapped(
+            &all_rows,
+            &region,
+            |r| pane.grid.is_row_wrapped_absolute(r),
+        );
+
+        self.selection_state.region = region;
+        self.selection_state.kind = ui::selection::SelectionType::Normal;
+
+        if !text.is_empty() {
+            if let Err(e) = self.clipboard.copy(&text) {
+                tracing::warn!("Failed to copy promoted search match to clipboard: {}", e);
+            } else {
+                tracing::debug!("Promoted search match to selection and copied it");
+            }
         }
     }
 
@@ -1307,7 +3058,40 @@ impl TerminalApp {
     }
 }
 
-impl ApplicationHandler for TerminalApp {
+/// Encode a mouse event as an SGR extended-coordinate report (`CSI < b ;
+/// col ; row M/m`, DECSET `1006`), the only mouse encoding
+/// `ParserState::mouse_reporting` ever asks for. `col`/`row` are 0-based
+/// grid coordinates; the wire format is 1-based. `button` is the base SGR
+/// button number (0=left, 1=middle, 2=right, 3=none/motion-only,
+/// 64/65=wheel up/down); modifier and motion bits are added on top per the
+/// xterm spec, and the final letter is `M` for a press or drag-motion
+/// report and `m` for a release.
+fn sgr_mouse_report(
+    button: u8,
+    col: usize,
+    row: usize,
+    press: bool,
+    motion: bool,
+    mods: ModifiersState,
+) -> Vec<u8> {
+    let mut code = button;
+    if mods.shift_key() {
+        code += 4;
+    }
+    if mods.alt_key() {
+        code += 8;
+    }
+    if mods.control_key() {
+        code += 16;
+    }
+    if motion {
+        code += 32;
+    }
+    let terminator = if press { 'M' } else { 'm' };
+    format!("\x1b[<{};{};{}{}", code, col + 1, row + 1, terminator).into_bytes()
+}
+
+impl ApplicationHandler<UserEvent> for TerminalApp {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         // Create window
         let window = match event_loop.create_window(
@@ -1323,6 +3107,11 @@ impl ApplicationHandler for TerminalApp {
             }
         };
 
+        // Let the platform IME compose inline (CJK, dead keys, emoji picker)
+        // instead of popping up its own floating text box; composed text
+        // arrives via `WindowEvent::Ime` (see `handle_ime_event`).
+        window.set_ime_allowed(true);
+
         // Get initial size
         let size = window.inner_size();
         let cols = (size.width / self.cell_width) as u16;
@@ -1377,26 +3166,64 @@ impl ApplicationHandler for TerminalApp {
 
             WindowEvent::Resized(physical_size) => {
                 self.handle_resize(physical_size.width, physical_size.height);
+                self.request_redraw();
             }
 
             WindowEvent::KeyboardInput { event, .. } => {
+                // Labeled so every early `break 'keyboard_input` below still
+                // falls through to the `request_redraw()` after the block -
+                // any of these branches can change what's on screen.
+                'keyboard_input: {
                 // Check for special shortcuts
                 if event.state == ElementState::Pressed {
+                    // Check for vi-mode toggle (Ctrl+Shift+Space), ahead of
+                    // the AI palette's plain Ctrl+Space so it takes priority
+                    // when both modifiers are held.
+                    let is_ctrl_shift_space = matches!(&event.logical_key, Key::Character(c) if c == " ")
+                        && self.input_handler.modifiers().ctrl
+                        && self.input_handler.modifiers().shift;
+                    if is_ctrl_shift_space {
+                        if self.vi_mode.is_some() {
+                            self.exit_vi_mode();
+                        } else {
+                            self.enter_vi_mode();
+                        }
+                        break 'keyboard_input;
+                    }
+
                     // Check for AI palette toggle (Ctrl+Space)
                     let is_ctrl_space = matches!(&event.logical_key, Key::Character(c) if c == " " || c == " ")
-                        && self.input_handler.modifiers().ctrl;
-                    
+                        && self.input_handler.modifiers().ctrl
+                        && !self.input_handler.modifiers().shift;
+
                     if is_ctrl_space {
+                        if !self.ai_palette.is_visible() {
+                            // Refresh ambient context (cwd, git state) right
+                            // before opening so the prompt reflects where the
+                            // user actually is, not where they opened a pane.
+                            if let Ok(dir) = std::env::current_dir() {
+                                self.ai_palette.set_shell_context(ShellContext::capture(&dir));
+                            }
+                        }
                         self.ai_palette.toggle();
-                        return;
+                        break 'keyboard_input;
                     }
                 }
 
                 // Handle AI palette input if open
                 if self.ai_palette.is_visible() {
                     use winit::event::ElementState;
-                    
+
                     if event.state == ElementState::Pressed {
+                        // Ctrl+N starts a fresh conversation, discarding the
+                        // persisted session history.
+                        let is_ctrl_n = matches!(&event.logical_key, Key::Character(c) if c == "n" || c == "N")
+                            && self.input_handler.modifiers().ctrl;
+                        if is_ctrl_n {
+                            self.ai_palette.clear_history();
+                            break 'keyboard_input;
+                        }
+
                         match &event.logical_key {
                             Key::Named(NamedKey::Escape) => {
                                 self.ai_palette.handle_escape();
@@ -1413,6 +3240,9 @@ impl ApplicationHandler for TerminalApp {
                             Key::Named(NamedKey::ArrowRight) => {
                                 self.ai_palette.cursor_right();
                             }
+                            Key::Named(NamedKey::Tab) => {
+                                self.ai_palette.promote_first_code_block();
+                            }
                             Key::Character(c) => {
                                 for ch in c.chars() {
                                     self.ai_palette.handle_char(ch);
@@ -1421,7 +3251,7 @@ impl ApplicationHandler for TerminalApp {
                             _ => {}
                         }
                     }
-                    return;
+                    break 'keyboard_input;
                 }
 
                 // Handle search mode input if search is active
@@ -1430,43 +3260,214 @@ impl ApplicationHandler for TerminalApp {
                         match &event.logical_key {
                             Key::Named(NamedKey::Escape) => {
                                 self.handle_search_close();
-                                return;
+                                break 'keyboard_input;
                             }
                             Key::Named(NamedKey::Enter) => {
-                                if self.input_handler.modifiers().shift {
+                                if self.input_handler.modifiers().ctrl {
+                                    self.handle_search_promote_to_selection();
+                                } else if self.input_handler.modifiers().shift {
                                     self.handle_search_prev();
                                 } else {
                                     self.handle_search_next();
                                 }
-                                return;
+                                break 'keyboard_input;
                             }
                             Key::Named(NamedKey::Backspace) => {
                                 self.handle_search_backspace();
-                                return;
+                                break 'keyboard_input;
+                            }
+                            Key::Character(c) if (c == "r" || c == "R") && self.input_handler.modifiers().ctrl => {
+                                // Ctrl+R toggles literal vs. regex search mode
+                                self.search_state.toggle_regex_mode();
+                                self.update_search();
+                                break 'keyboard_input;
+                            }
+                            Key::Character(c) if (c == "i" || c == "I") && self.input_handler.modifiers().ctrl => {
+                                // Ctrl+I toggles case-sensitive vs. case-insensitive matching
+                                self.search_state.toggle_case_sensitive();
+                                self.update_search();
+                                break 'keyboard_input;
+                            }
+                            Key::Character(c) if (c == "f" || c == "F") && self.input_handler.modifiers().ctrl => {
+                                // Ctrl+F toggles regex/literal vs. fuzzy search mode
+                                self.search_state.toggle_mode();
+                                self.update_search();
+                                break 'keyboard_input;
                             }
                             Key::Character(c) => {
                                 for ch in c.chars() {
                                     self.handle_search_input(ch);
                                 }
-                                return;
+                                break 'keyboard_input;
                             }
                             _ => {
                                 // Ignore other keys in search mode
-                                return;
+                                break 'keyboard_input;
                             }
                         }
                     }
                 }
 
+                // Handle vi-mode navigation if active - keystrokes move the
+                // virtual cursor instead of reaching the PTY, the same way
+                // search input intercepts above.
+                if self.vi_mode.is_some() {
+                    if event.state == ElementState::Pressed {
+                        match &event.logical_key {
+                            Key::Named(NamedKey::Escape) => {
+                                self.exit_vi_mode();
+                            }
+                            Key::Named(NamedKey::ArrowLeft) => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::Left)
+                            }),
+                            Key::Named(NamedKey::ArrowRight) => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::Right)
+                            }),
+                            Key::Named(NamedKey::ArrowUp) => {
+                                self.vi_move(|p, pane| Self::vi_apply_motion(pane, p, ViMotion::Up))
+                            }
+                            Key::Named(NamedKey::ArrowDown) => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::Down)
+                            }),
+                            Key::Character(c) if c == "h" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::Left)
+                            }),
+                            Key::Character(c) if c == "l" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::Right)
+                            }),
+                            Key::Character(c) if c == "k" => {
+                                self.vi_move(|p, pane| Self::vi_apply_motion(pane, p, ViMotion::Up))
+                            }
+                            Key::Character(c) if c == "j" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::Down)
+                            }),
+                            Key::Character(c) if c == "w" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::WordForward)
+                            }),
+                            Key::Character(c) if c == "b" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::WordBackward)
+                            }),
+                            Key::Character(c) if c == "0" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::LineStart)
+                            }),
+                            Key::Character(c) if c == "$" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::LineEnd)
+                            }),
+                            Key::Character(c) if c == "g" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::BufferTop)
+                            }),
+                            Key::Character(c) if c == "G" => self.vi_move(|p, pane| {
+                                Self::vi_apply_motion(pane, p, ViMotion::BufferBottom)
+                            }),
+                            Key::Character(c) if c == "u" && self.input_handler.modifiers().ctrl => {
+                                self.vi_move(|p, pane| {
+                                    Self::vi_apply_motion(pane, p, ViMotion::HalfPageUp)
+                                })
+                            }
+                            Key::Character(c) if c == "d" && self.input_handler.modifiers().ctrl => {
+                                self.vi_move(|p, pane| {
+                                    Self::vi_apply_motion(pane, p, ViMotion::HalfPageDown)
+                                })
+                            }
+                            Key::Character(c) if c == "v" => self.vi_toggle_selection(),
+                            Key::Character(c) if c == "y" => self.vi_yank(),
+                            _ => {}
+                        }
+                    }
+                    break 'keyboard_input;
+                }
+
+                // Handle hint mode input if active - labels are plain
+                // characters, so this has to intercept before normal input
+                // reaches the PTY, the same way search input does above.
+                if self.hint_mode {
+                    if event.state == ElementState::Pressed {
+                        match &event.logical_key {
+                            Key::Named(NamedKey::Escape) => {
+                                self.exit_hint_mode();
+                            }
+                            Key::Character(c) => {
+                                for ch in c.chars() {
+                                    self.handle_hint_char(ch);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    break 'keyboard_input;
+                }
+
                 // Check for other shortcuts
                 if event.state == ElementState::Pressed {
+                    // Check for scrollback paging (Shift+PageUp/PageDown/Home/End)
+                    let scroll_request = if self.input_handler.modifiers().shift {
+                        match &event.logical_key {
+                            Key::Named(NamedKey::PageUp) => Some(terminal::grid::Scroll::PageUp),
+                            Key::Named(NamedKey::PageDown) => Some(terminal::grid::Scroll::PageDown),
+                            Key::Named(NamedKey::Home) => Some(terminal::grid::Scroll::Top),
+                            Key::Named(NamedKey::End) => Some(terminal::grid::Scroll::Bottom),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    };
+                    if let Some(scroll) = scroll_request {
+                        if let Some(ref mut layout) = self.layout {
+                            if let Some(pane) = layout.focused_pane_mut() {
+                                pane.grid.scroll(scroll);
+                            }
+                        }
+                        break 'keyboard_input;
+                    }
+
+                    // Check for split resizing (Ctrl+Arrow nudges the
+                    // focused split's ratio, keyboard-only alternative to
+                    // dragging a divider)
+                    const RESIZE_STEP: f32 = 0.02;
+                    let modifiers = self.input_handler.modifiers();
+                    let resize_request = if modifiers.ctrl && !modifiers.shift {
+                        match &event.logical_key {
+                            Key::Named(NamedKey::ArrowLeft) => Some((SplitDirection::Horizontal, -RESIZE_STEP)),
+                            Key::Named(NamedKey::ArrowRight) => Some((SplitDirection::Horizontal, RESIZE_STEP)),
+                            Key::Named(NamedKey::ArrowUp) => Some((SplitDirection::Vertical, -RESIZE_STEP)),
+                            Key::Named(NamedKey::ArrowDown) => Some((SplitDirection::Vertical, RESIZE_STEP)),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    };
+                    if let Some((direction, delta)) = resize_request {
+                        if let Some(ref mut layout) = self.layout {
+                            if let Err(e) = layout.resize_focused(direction, delta) {
+                                tracing::debug!("Resize had no effect: {}", e);
+                            }
+                        }
+                        if let Some(ref window) = self.window {
+                            let size = window.inner_size();
+                            self.handle_resize(size.width, size.height);
+                        }
+                        break 'keyboard_input;
+                    }
+
                     // Check for search toggle (Ctrl+Shift+F)
                     match &event.logical_key {
                         Key::Character(c) if c == "f" || c == "F" => {
                             let modifiers = self.input_handler.modifiers();
                             if modifiers.ctrl && modifiers.shift {
                                 self.handle_toggle_search();
-                                return;
+                                break 'keyboard_input;
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    // Check for hyperlink hint mode toggle (Ctrl+Shift+O)
+                    match &event.logical_key {
+                        Key::Character(c) if c == "o" || c == "O" => {
+                            let modifiers = self.input_handler.modifiers();
+                            if modifiers.ctrl && modifiers.shift {
+                                self.enter_hint_mode();
+                                break 'keyboard_input;
                             }
                         }
                         _ => {}
@@ -1480,11 +3481,11 @@ impl ApplicationHandler for TerminalApp {
                                 if modifiers.shift {
                                     // Ctrl+Shift+D = Vertical split
                                     self.handle_split(SplitDirection::Vertical);
-                                    return;
+                                    break 'keyboard_input;
                                 } else {
                                     // Ctrl+D = Horizontal split
                                     self.handle_split(SplitDirection::Horizontal);
-                                    return;
+                                    break 'keyboard_input;
                                 }
                             }
                         }
@@ -1493,7 +3494,7 @@ impl ApplicationHandler for TerminalApp {
                             if modifiers.ctrl && !modifiers.shift {
                                 // Ctrl+W = Close pane
                                 self.handle_close_pane();
-                                return;
+                                break 'keyboard_input;
                             }
                         }
                         Key::Named(NamedKey::Tab) => {
@@ -1505,7 +3506,7 @@ impl ApplicationHandler for TerminalApp {
                                 // Ctrl+Tab = Focus next pane
                                 self.handle_focus_next();
                             }
-                            return;
+                            break 'keyboard_input;
                         }
                         _ => {}
                     }
@@ -1523,7 +3524,7 @@ impl ApplicationHandler for TerminalApp {
                     };
 
                     if is_paste {
-                        let _ = self.handle_paste();
+                        let _ = self.handle_paste(ui::selection::ClipboardType::Clipboard);
                     } else {
                         // Normal input
                         let input = self.input_handler.handle_key_event(&event);
@@ -1531,6 +3532,9 @@ impl ApplicationHandler for TerminalApp {
                         self.send_pty_input(&data);
                     }
                 }
+                } // 'keyboard_input
+
+                self.request_redraw();
             }
 
             WindowEvent::ModifiersChanged(modifiers) => {
@@ -1542,49 +3546,52 @@ impl ApplicationHandler for TerminalApp {
 
             WindowEvent::MouseInput { state, button, .. } => {
                 self.handle_mouse_button(DeviceId::dummy(), button, state);
+                self.request_redraw();
             }
 
             WindowEvent::CursorMoved { position, .. } => {
                 self.handle_mouse_motion(position);
+                self.request_redraw();
+            }
+
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.handle_mouse_wheel(delta);
+                self.request_redraw();
+            }
+
+            WindowEvent::Ime(ime) => {
+                self.handle_ime_event(ime);
+                self.request_redraw();
             }
 
             WindowEvent::RedrawRequested => {
                 // Read and process any pending PTY output from all panes
                 self.read_all_pty_output();
 
-                // Render
+                // Render. Unlike the old fixed-cadence loop, this does NOT
+                // queue up another redraw for the next tick - the next one
+                // only comes from a future window event or `UserEvent::PtyData`.
                 self.render();
-
-                // Request next frame
-                if let Some(ref window) = self.window {
-                    window.request_redraw();
-                }
             }
 
             _ => {}
         }
     }
 
-    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        // Process PTY output periodically
-        self.read_all_pty_output();
-
-        // Limit frame rate
-        let elapsed = self.last_frame.elapsed();
-        if elapsed < self.frame_duration {
-            let wait = self.frame_duration - elapsed;
-            std::thread::sleep(wait.min(Duration::from_millis(1)));
-        }
-        self.last_frame = Instant::now();
-
-        // Request redraw if running
-        if self.running {
-            if let Some(ref window) = self.window {
-                window.request_redraw();
-            }
+    /// Woken by a pane's PTY watcher thread (see `spawn_pty_watcher`)
+    /// reporting that it buffered new bytes. Coalesces naturally: several of
+    /// these arriving before the next `RedrawRequested` still only cause one
+    /// redraw, since `request_redraw` is idempotent until it's serviced.
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: UserEvent) {
+        match event {
+            UserEvent::PtyData => self.request_redraw(),
         }
+    }
 
-        // Set control flow
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        // No fixed-cadence polling here anymore - redraws are requested
+        // explicitly (window events, `UserEvent::PtyData`), so the loop can
+        // sit idle between them instead of waking up 60 times a second.
         event_loop.set_control_flow(ControlFlow::Wait);
     }
 
@@ -1600,11 +3607,13 @@ fn main() -> Result<()> {
     tracing::info!("Warp FOSS v0.1.0");
     tracing::info!("Starting terminal application with split pane support...");
 
-    // Create event loop
-    let event_loop = EventLoop::new()?;
+    // Create event loop with a custom user event so PTY watcher threads can
+    // wake it up (see `UserEvent`, `TerminalApp::spawn_pty_watcher`)
+    let event_loop = EventLoop::<UserEvent>::with_user_event().build()?;
+    let proxy = event_loop.create_proxy();
 
     // Create app
-    let mut app = TerminalApp::new();
+    let mut app = TerminalApp::new(proxy);
 
     // Run event loop
     event_loop.run_app(&mut app)?;
@@ -0,0 +1,133 @@
+//! Per-plugin capability approval, persisted under the config dir.
+//!
+//! A plugin's `plugin.toml` only *declares* what it wants; declaring
+//! `Capabilities::NETWORK` doesn't grant it. `PluginHost` only wires up
+//! the host functions a capability unlocks once that capability has been
+//! explicitly approved for that plugin, mirroring the confirm-before-run
+//! gate `ui::ai_command_palette` uses for AI-proposed commands - nothing
+//! privileged happens just because it was requested. Approvals are
+//! remembered so a trusted plugin isn't re-prompted on every reload.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::manifest::Capabilities;
+
+/// Persisted set of capabilities the user has approved for each plugin,
+/// keyed by plugin name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CapabilityApprovals {
+    approved: HashMap<String, Capabilities>,
+}
+
+impl CapabilityApprovals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capabilities previously approved for `plugin_name` - empty if it's
+    /// never been approved for anything.
+    pub fn approved_for(&self, plugin_name: &str) -> Capabilities {
+        self.approved.get(plugin_name).copied().unwrap_or(Capabilities::empty())
+    }
+
+    /// Record that `capabilities` are approved for `plugin_name`, replacing
+    /// any previous decision for it.
+    pub fn approve(&mut self, plugin_name: &str, capabilities: Capabilities) {
+        self.approved.insert(plugin_name.to_string(), capabilities);
+    }
+
+    /// Withdraw every approval previously granted to `plugin_name`.
+    pub fn revoke(&mut self, plugin_name: &str) {
+        self.approved.remove(plugin_name);
+    }
+
+    /// Path of the persisted approvals file.
+    pub fn store_path() -> Result<PathBuf> {
+        if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+            return Ok(PathBuf::from(xdg_config)
+                .join("warp-foss")
+                .join("plugin_approvals.json"));
+        }
+
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(home.join(".config").join("warp-foss").join("plugin_approvals.json"))
+    }
+
+    /// Load the persisted approvals from the default XDG/home location, or
+    /// an empty set if none exist yet.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::store_path()?)
+    }
+
+    /// Load the persisted approvals from an explicit `path`, or an empty
+    /// set if it doesn't exist yet. Split out from `load` so callers that
+    /// need a deterministic, non-default location (tests exercising
+    /// persistence, chiefly - reading `$XDG_CONFIG_HOME` would otherwise
+    /// race against every other test mutating it) don't have to go through
+    /// the process environment at all.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read plugin approvals: {:?}", path))?;
+
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse plugin approvals: {:?}", path))
+    }
+
+    /// Persist the current approvals to the default XDG/home location.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::store_path()?)
+    }
+
+    /// Persist the current approvals to an explicit `path`. See `load_from`
+    /// for why a caller would want this instead of `save`.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create config directory: {:?}", parent))?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize plugin approvals")?;
+
+        std::fs::write(path, &contents)
+            .with_context(|| format!("Failed to write plugin approvals: {:?}", path))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unapproved_plugin_has_no_capabilities() {
+        let approvals = CapabilityApprovals::new();
+        assert_eq!(approvals.approved_for("some-plugin"), Capabilities::empty());
+    }
+
+    #[test]
+    fn test_approve_then_revoke_round_trips() {
+        let mut approvals = CapabilityApprovals::new();
+        approvals.approve("net-plugin", Capabilities::NETWORK);
+        assert_eq!(approvals.approved_for("net-plugin"), Capabilities::NETWORK);
+
+        approvals.revoke("net-plugin");
+        assert_eq!(approvals.approved_for("net-plugin"), Capabilities::empty());
+    }
+
+    #[test]
+    fn test_approve_replaces_previous_decision() {
+        let mut approvals = CapabilityApprovals::new();
+        approvals.approve("plugin", Capabilities::NETWORK);
+        approvals.approve("plugin", Capabilities::READ_PANE_OUTPUT);
+        assert_eq!(approvals.approved_for("plugin"), Capabilities::READ_PANE_OUTPUT);
+    }
+}
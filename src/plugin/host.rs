@@ -0,0 +1,78 @@
+//! The host API surface exposed to plugins, and the extension points
+//! (commands, block renderers, search providers) they can register into.
+
+/// A command a plugin registers into the command palette.
+/// `PluginHost::run_command` runs the plugin's exported `run_command`
+/// function with an `i32` argument; `id` is not passed to the plugin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+    pub plugin_name: String,
+}
+
+/// A block renderer a plugin registers for output it recognizes by a
+/// `language` tag (the same tag `ui::markdown`'s fenced code blocks use),
+/// e.g. a plugin that renders `language = "mermaid"` blocks as diagrams.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockRenderer {
+    pub language: String,
+    pub plugin_name: String,
+}
+
+/// A search provider a plugin registers, contributing results alongside
+/// `search::frecency` and `search::workflows` under its own `source` label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchProvider {
+    pub source: String,
+    pub plugin_name: String,
+}
+
+/// Everything plugins have registered so far, keyed by the extension point.
+/// `PluginHost` owns one of these and appends to it during load, after each
+/// plugin's `register` export runs (see `PluginHost::register_extensions`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Registry {
+    pub commands: Vec<PluginCommand>,
+    pub block_renderers: Vec<BlockRenderer>,
+    pub search_providers: Vec<SearchProvider>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop every entry owned by `plugin_name`, e.g. when a plugin is
+    /// unloaded or fails to reload after a manifest change.
+    pub fn remove_plugin(&mut self, plugin_name: &str) {
+        self.commands.retain(|c| c.plugin_name != plugin_name);
+        self.block_renderers.retain(|b| b.plugin_name != plugin_name);
+        self.search_providers.retain(|s| s.plugin_name != plugin_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_plugin_clears_only_its_own_registrations() {
+        let mut registry = Registry::new();
+        registry.commands.push(PluginCommand {
+            id: "a".into(),
+            title: "A".into(),
+            plugin_name: "plugin-a".into(),
+        });
+        registry.commands.push(PluginCommand {
+            id: "b".into(),
+            title: "B".into(),
+            plugin_name: "plugin-b".into(),
+        });
+
+        registry.remove_plugin("plugin-a");
+
+        assert_eq!(registry.commands.len(), 1);
+        assert_eq!(registry.commands[0].plugin_name, "plugin-b");
+    }
+}
@@ -0,0 +1,121 @@
+//! Plugin manifest format: the `plugin.toml` a third-party plugin ships
+//! alongside its compiled `.wasm` module, declaring what it is and what
+//! host capabilities it needs.
+
+use bitflags::bitflags;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+bitflags! {
+    /// Host capabilities a plugin may request. Mirrors `TermMode`'s shape
+    /// in `terminal::parser` - a bitset stored alongside the thing it
+    /// gates, checked with `contains` at the point a host call would
+    /// otherwise be unconditionally allowed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Capabilities: u32 {
+        /// Register commands invocable from the command palette.
+        const COMMANDS = 0b0000_0001;
+        /// Register a custom block renderer for its own output.
+        const BLOCK_RENDERERS = 0b0000_0010;
+        /// Register a search provider contributing results to `search`.
+        const SEARCH_PROVIDERS = 0b0000_0100;
+        /// Read (not write) the active pane's PTY output.
+        const READ_PANE_OUTPUT = 0b0000_1000;
+        /// Make outbound network requests from host-provided functions.
+        const NETWORK = 0b0001_0000;
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities::empty()
+    }
+}
+
+/// `(flag, manifest name)` pairs, in declaration order - the single source
+/// of truth both (de)serialization directions read from, the same
+/// "parallel array of names" shape `config::keyset::ACTIONS` uses.
+const CAPABILITY_NAMES: &[(Capabilities, &str)] = &[
+    (Capabilities::COMMANDS, "commands"),
+    (Capabilities::BLOCK_RENDERERS, "block_renderers"),
+    (Capabilities::SEARCH_PROVIDERS, "search_providers"),
+    (Capabilities::READ_PANE_OUTPUT, "read_pane_output"),
+    (Capabilities::NETWORK, "network"),
+];
+
+impl Serialize for Capabilities {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let names: Vec<&str> =
+            CAPABILITY_NAMES.iter().filter(|(flag, _)| self.contains(*flag)).map(|(_, name)| *name).collect();
+        names.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Capabilities {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        let mut capabilities = Capabilities::empty();
+        for name in names {
+            match CAPABILITY_NAMES.iter().find(|(_, n)| *n == name) {
+                Some((flag, _)) => capabilities |= *flag,
+                None => return Err(serde::de::Error::custom(format!("unknown plugin capability: {name}"))),
+            }
+        }
+        Ok(capabilities)
+    }
+}
+
+/// Parsed `plugin.toml`: identity, the capabilities it's asking the host
+/// to grant, and where to find its compiled module relative to the
+/// manifest file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    pub version: String,
+    /// Path to the compiled `.wasm` module, relative to the manifest file.
+    pub module: String,
+    #[serde(default)]
+    pub capabilities: Capabilities,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl Manifest {
+    /// Parse a manifest from `plugin.toml` contents.
+    pub fn parse(toml_str: &str) -> Result<Manifest, toml::de::Error> {
+        toml::from_str(toml_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reads_name_version_module_and_capabilities() {
+        let toml_str = r#"
+            name = "fancy-renderer"
+            version = "0.1.0"
+            module = "plugin.wasm"
+            capabilities = ["block_renderers", "read_pane_output"]
+        "#;
+
+        let manifest = Manifest::parse(toml_str).unwrap();
+        assert_eq!(manifest.name, "fancy-renderer");
+        assert_eq!(manifest.module, "plugin.wasm");
+        assert!(manifest.capabilities.contains(Capabilities::BLOCK_RENDERERS));
+        assert!(manifest.capabilities.contains(Capabilities::READ_PANE_OUTPUT));
+        assert!(!manifest.capabilities.contains(Capabilities::NETWORK));
+    }
+
+    #[test]
+    fn test_parse_defaults_capabilities_to_empty_when_omitted() {
+        let toml_str = r#"
+            name = "minimal"
+            version = "1.0.0"
+            module = "plugin.wasm"
+        "#;
+
+        let manifest = Manifest::parse(toml_str).unwrap();
+        assert_eq!(manifest.capabilities, Capabilities::empty());
+    }
+}
@@ -0,0 +1,21 @@
+//! Sandboxed plugin system: third parties ship a `plugin.toml` manifest
+//! plus a compiled WASM module, and `PluginHost` loads it into its own
+//! `wasmtime` store with only the host functions its declared
+//! `Capabilities` unlock - and only the ones `CapabilityApprovals` has
+//! actually approved for that plugin, since declaring a capability in a
+//! manifest a plugin wrote itself is not the same as being granted it.
+//! Loaded plugins contribute commands into a shared `host::Registry`,
+//! exposed through `PluginHost::registry` so a reader of it needn't depend
+//! on `wasmtime` directly. The registry also has lists for block renderers
+//! and search providers, which loading does not fill; the command palette,
+//! `ui::markdown` and `search` do not read the registry.
+
+pub mod approval;
+pub mod host;
+pub mod manifest;
+pub mod runtime;
+
+pub use approval::CapabilityApprovals;
+pub use host::Registry;
+pub use manifest::{Capabilities, Manifest};
+pub use runtime::{PluginError, PluginHost, PluginResult};
@@ -0,0 +1,377 @@
+//! Sandboxed WASM plugin loading and execution, via `wasmtime`.
+//!
+//! Each plugin gets its own `wasmtime::Store` with no WASI imports beyond
+//! the capability-gated host functions its manifest was granted - a
+//! plugin that didn't request `Capabilities::NETWORK` simply has no
+//! `host_http_request` import to call, rather than a runtime check on
+//! every call. `PluginHost` is the long-lived owner: it loads manifests
+//! from a directory, instantiates each module, and keeps the combined
+//! `host::Registry`, exposed through `PluginHost::registry`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+use wasmtime::{Engine, Instance, Module, Store};
+
+use super::approval::CapabilityApprovals;
+use super::host::Registry;
+use super::manifest::{Capabilities, Manifest};
+
+/// Capabilities that unlock an actual host function import (vs.
+/// `COMMANDS`/`BLOCK_RENDERERS`/`SEARCH_PROVIDERS`, which only register
+/// extension points the host already fully mediates) - these are the ones
+/// that need explicit, persisted user approval before `link_capabilities`
+/// wires anything up.
+fn gated_capabilities() -> Capabilities {
+    Capabilities::READ_PANE_OUTPUT | Capabilities::NETWORK
+}
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Failed to read plugin manifest at {0}: {1}")]
+    ManifestRead(PathBuf, String),
+
+    #[error("Failed to parse plugin manifest at {0}: {1}")]
+    ManifestParse(PathBuf, String),
+
+    #[error("Failed to read WASM module for plugin '{0}': {1}")]
+    ModuleRead(String, String),
+
+    #[error("Failed to compile WASM module for plugin '{0}': {1}")]
+    ModuleCompile(String, String),
+
+    #[error("Failed to instantiate plugin '{0}': {1}")]
+    Instantiate(String, String),
+
+    #[error("Plugin '{0}' does not export a '{1}' function")]
+    MissingExport(String, &'static str),
+
+    #[error("Plugin '{0}' trapped while running '{1}': {2}")]
+    Trap(String, &'static str, String),
+
+    #[error("No plugin named '{0}' is loaded")]
+    NotLoaded(String),
+
+    #[error("Failed to persist capability approval for plugin '{0}': {1}")]
+    ApprovalPersist(String, String),
+}
+
+pub type PluginResult<T> = Result<T, PluginError>;
+
+/// No host state is threaded into the store yet - capability-gated host
+/// functions are added to `linker` per plugin as their manifest grants
+/// them, so there's nothing for them to close over beyond `()`.
+type StoreData = ();
+
+/// A single loaded plugin: its manifest, compiled module, and live
+/// instance, kept together so `PluginHost::unload` can drop them as a
+/// unit.
+struct LoadedPlugin {
+    manifest: Manifest,
+    store: Store<StoreData>,
+    instance: Instance,
+}
+
+/// Owns every loaded plugin and the combined registry they've contributed
+/// to. One `PluginHost` per application run; `reload_all` re-scans the
+/// plugin directory so manifest/module changes on disk take effect
+/// without restarting.
+pub struct PluginHost {
+    engine: Engine,
+    plugin_dir: PathBuf,
+    plugins: HashMap<String, LoadedPlugin>,
+    registry: Registry,
+    approvals: CapabilityApprovals,
+    approvals_path: PathBuf,
+}
+
+impl PluginHost {
+    /// Create a host that loads plugins from `plugin_dir`, each a
+    /// subdirectory containing a `plugin.toml` manifest and its `.wasm`
+    /// module. Previously persisted capability approvals are loaded too,
+    /// so a plugin approved in an earlier run doesn't need re-approving.
+    pub fn new(plugin_dir: impl Into<PathBuf>) -> Self {
+        let approvals_path = CapabilityApprovals::store_path().unwrap_or_default();
+        let approvals = CapabilityApprovals::load_from(&approvals_path).unwrap_or_default();
+        Self::with_approvals(plugin_dir, approvals_path, approvals)
+    }
+
+    /// Create a host whose approvals are read from and persisted to
+    /// `approvals_path` instead of the default XDG/home location - lets
+    /// tests exercise approval persistence with a scratch path of their
+    /// own rather than mutating the process-wide `XDG_CONFIG_HOME`, which
+    /// would otherwise race against every other test that constructs a
+    /// `PluginHost` concurrently.
+    #[cfg(test)]
+    pub fn with_approvals_path(plugin_dir: impl Into<PathBuf>, approvals_path: impl Into<PathBuf>) -> Self {
+        let approvals_path = approvals_path.into();
+        let approvals = CapabilityApprovals::load_from(&approvals_path).unwrap_or_default();
+        Self::with_approvals(plugin_dir, approvals_path, approvals)
+    }
+
+    fn with_approvals(plugin_dir: impl Into<PathBuf>, approvals_path: PathBuf, approvals: CapabilityApprovals) -> Self {
+        Self {
+            engine: Engine::default(),
+            plugin_dir: plugin_dir.into(),
+            plugins: HashMap::new(),
+            registry: Registry::new(),
+            approvals,
+            approvals_path,
+        }
+    }
+
+    /// Everything plugins have registered so far.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Gated capabilities `manifest` declares that haven't been approved
+    /// for it yet - what a caller should prompt the user about (e.g. via
+    /// the same confirm-before-run UI `ai_command_palette` uses) before
+    /// `load` is called, so the plugin doesn't silently load with less
+    /// than it asked for.
+    pub fn capabilities_needing_approval(&self, manifest: &Manifest) -> Capabilities {
+        (manifest.capabilities & gated_capabilities()) & !self.approvals.approved_for(&manifest.name)
+    }
+
+    /// Record the user's approval decision for `plugin_name` and persist
+    /// it, so `load`/`reload_all` grant it from now on without asking
+    /// again. Replaces any previous decision for this plugin.
+    pub fn approve_capabilities(&mut self, plugin_name: &str, capabilities: Capabilities) -> PluginResult<()> {
+        self.approvals.approve(plugin_name, capabilities);
+        self.approvals
+            .save_to(&self.approvals_path)
+            .map_err(|e| PluginError::ApprovalPersist(plugin_name.to_string(), e.to_string()))
+    }
+
+    /// Names of the currently loaded plugins.
+    pub fn loaded_plugins(&self) -> impl Iterator<Item = &str> {
+        self.plugins.keys().map(String::as_str)
+    }
+
+    /// Scan `plugin_dir` for subdirectories containing a `plugin.toml`,
+    /// loading (or reloading) each one. A plugin that fails to load is
+    /// skipped rather than aborting the scan, so one broken plugin can't
+    /// take down the rest; callers that want to surface the failure get
+    /// it back in the returned `Vec`.
+    pub fn reload_all(&mut self) -> Vec<(String, PluginError)> {
+        let mut errors = Vec::new();
+        let entries = match std::fs::read_dir(&self.plugin_dir) {
+            Ok(entries) => entries,
+            Err(_) => return errors,
+        };
+
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            let manifest_path = dir.join("plugin.toml");
+            if !manifest_path.exists() {
+                continue;
+            }
+            if let Err(e) = self.load(&manifest_path) {
+                let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                errors.push((name, e));
+            }
+        }
+        errors
+    }
+
+    /// Load (or replace) a single plugin from its manifest path.
+    pub fn load(&mut self, manifest_path: &Path) -> PluginResult<()> {
+        let manifest_str = std::fs::read_to_string(manifest_path)
+            .map_err(|e| PluginError::ManifestRead(manifest_path.to_path_buf(), e.to_string()))?;
+        let manifest = Manifest::parse(&manifest_str)
+            .map_err(|e| PluginError::ManifestParse(manifest_path.to_path_buf(), e.to_string()))?;
+
+        let module_path = manifest_path.parent().unwrap_or(Path::new(".")).join(&manifest.module);
+        let wasm_bytes = std::fs::read(&module_path).map_err(|e| PluginError::ModuleRead(manifest.name.clone(), e.to_string()))?;
+        let module =
+            Module::new(&self.engine, &wasm_bytes).map_err(|e| PluginError::ModuleCompile(manifest.name.clone(), e.to_string()))?;
+
+        // Only gated capabilities the user has actually approved for this
+        // plugin get wired up - what the manifest declares is a request,
+        // not a grant. Ungated capabilities (COMMANDS/BLOCK_RENDERERS/
+        // SEARCH_PROVIDERS) pass straight through `register_extensions`
+        // below, since they only register extension points rather than
+        // unlocking a host function.
+        let granted = manifest.capabilities & self.approvals.approved_for(&manifest.name);
+        let mut linker = wasmtime::Linker::new(&self.engine);
+        Self::link_capabilities(&mut linker, granted);
+
+        let mut store = Store::new(&self.engine, ());
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| PluginError::Instantiate(manifest.name.clone(), e.to_string()))?;
+
+        self.registry.remove_plugin(&manifest.name);
+        self.register_extensions(&manifest, &instance, &mut store)?;
+
+        let name = manifest.name.clone();
+        self.plugins.insert(name, LoadedPlugin { manifest, store, instance });
+        Ok(())
+    }
+
+    /// Unload a plugin and drop its contributions from the registry.
+    pub fn unload(&mut self, name: &str) -> PluginResult<()> {
+        if self.plugins.remove(name).is_none() {
+            return Err(PluginError::NotLoaded(name.to_string()));
+        }
+        self.registry.remove_plugin(name);
+        Ok(())
+    }
+
+    /// Add the host functions a plugin's granted capabilities unlock.
+    /// Capabilities it didn't request simply have no corresponding import
+    /// defined, so a call into them fails to link rather than needing a
+    /// runtime permission check.
+    fn link_capabilities(linker: &mut wasmtime::Linker<StoreData>, capabilities: Capabilities) {
+        if capabilities.contains(Capabilities::READ_PANE_OUTPUT) {
+            // Real pane access is threaded in via a future `Store` extension;
+            // for now the import exists but returns nothing.
+            let _ = linker.func_wrap("host", "read_pane_output", |_: i32, _: i32| -> i32 { 0 });
+        }
+        if capabilities.contains(Capabilities::NETWORK) {
+            let _ = linker.func_wrap("host", "http_request", |_: i32, _: i32| -> i32 { 0 });
+        }
+    }
+
+    /// Call the plugin's `register` export, if it has one, then, if its
+    /// manifest declares `COMMANDS`, add one `<name>.default` command titled
+    /// with its description (or name). No host function lets `register`
+    /// name its own extensions, and no block renderer or search provider is
+    /// added here. Plugins with no `register` export are loaded with no
+    /// extensions - useful for a plugin that only uses host functions
+    /// reactively.
+    fn register_extensions(&mut self, manifest: &Manifest, instance: &Instance, store: &mut Store<StoreData>) -> PluginResult<()> {
+        let Ok(register) = instance.get_typed_func::<(), ()>(&mut *store, "register") else {
+            return Ok(());
+        };
+        register.call(store, ()).map_err(|e| PluginError::Trap(manifest.name.clone(), "register", e.to_string()))?;
+
+        if manifest.capabilities.contains(Capabilities::COMMANDS) {
+            self.registry.commands.push(super::host::PluginCommand {
+                id: format!("{}.default", manifest.name),
+                title: manifest.description.clone().unwrap_or_else(|| manifest.name.clone()),
+                plugin_name: manifest.name.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Invoke a loaded plugin's `run_command` export.
+    pub fn run_command(&mut self, plugin_name: &str, arg: i32) -> PluginResult<i32> {
+        let plugin = self.plugins.get_mut(plugin_name).ok_or_else(|| PluginError::NotLoaded(plugin_name.to_string()))?;
+        let run_command = plugin
+            .instance
+            .get_typed_func::<i32, i32>(&mut plugin.store, "run_command")
+            .map_err(|_| PluginError::MissingExport(plugin_name.to_string(), "run_command"))?;
+        run_command
+            .call(&mut plugin.store, arg)
+            .map_err(|e| PluginError::Trap(plugin_name.to_string(), "run_command", e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_all_returns_no_errors_for_an_empty_directory() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-plugins-empty-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+
+        let mut host = PluginHost::new(&scratch);
+        let errors = host.reload_all();
+
+        std::fs::remove_dir_all(&scratch).ok();
+        assert!(errors.is_empty());
+        assert_eq!(host.loaded_plugins().count(), 0);
+    }
+
+    #[test]
+    fn test_reload_all_reports_a_plugin_with_an_unparseable_manifest() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-plugins-bad-manifest-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let plugin_dir = scratch.join("broken-plugin");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join("plugin.toml"), "not valid toml {{{").unwrap();
+
+        let mut host = PluginHost::new(&scratch);
+        let errors = host.reload_all();
+
+        std::fs::remove_dir_all(&scratch).ok();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "broken-plugin");
+    }
+
+    #[test]
+    fn test_unload_unknown_plugin_errors() {
+        let mut host = PluginHost::new(std::env::temp_dir());
+        assert!(matches!(host.unload("ghost"), Err(PluginError::NotLoaded(_))));
+    }
+
+    #[test]
+    fn test_capabilities_needing_approval_ignores_ungated_ones() {
+        let approvals_path = std::env::temp_dir().join(format!(
+            "warp-foss-test-plugin-approvals-ungated-{}-{:?}.json",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let host = PluginHost::with_approvals_path(std::env::temp_dir(), &approvals_path);
+        let manifest = Manifest {
+            name: "unapproved".to_string(),
+            version: "0.1.0".to_string(),
+            module: "plugin.wasm".to_string(),
+            capabilities: Capabilities::COMMANDS | Capabilities::NETWORK,
+            description: None,
+        };
+
+        assert_eq!(host.capabilities_needing_approval(&manifest), Capabilities::NETWORK);
+    }
+
+    #[test]
+    fn test_approve_capabilities_persists_and_is_picked_up_by_a_new_host() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-plugin-approvals-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+        let approvals_path = scratch.join("plugin_approvals.json");
+
+        let manifest = Manifest {
+            name: "net-plugin".to_string(),
+            version: "0.1.0".to_string(),
+            module: "plugin.wasm".to_string(),
+            capabilities: Capabilities::NETWORK,
+            description: None,
+        };
+
+        // A dedicated `approvals_path` rather than `XDG_CONFIG_HOME` keeps
+        // this independent of every other test that constructs a
+        // `PluginHost` concurrently - mutating the shared env var raced
+        // with them under the default multi-threaded test runner.
+        let mut host = PluginHost::with_approvals_path(&scratch, &approvals_path);
+        assert_eq!(host.capabilities_needing_approval(&manifest), Capabilities::NETWORK);
+
+        host.approve_capabilities("net-plugin", Capabilities::NETWORK).unwrap();
+        assert_eq!(host.capabilities_needing_approval(&manifest), Capabilities::empty());
+
+        // A freshly constructed host re-reads the persisted decision, so
+        // the user isn't asked again after restarting the app.
+        let reloaded_host = PluginHost::with_approvals_path(&scratch, &approvals_path);
+        assert_eq!(reloaded_host.capabilities_needing_approval(&manifest), Capabilities::empty());
+
+        std::fs::remove_dir_all(&scratch).ok();
+    }
+}
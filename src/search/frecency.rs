@@ -0,0 +1,307 @@
+//! zoxide-style "frecency" directory jumper
+//!
+//! Tracks every directory the user `cd`s into, scores them by a blend of
+//! frequency and recency ("frecency"), and lets a partial keyword resolve
+//! straight to the best match - e.g. typing `proj back` jumps to
+//! `~/code/project/backend` without spelling out the whole path. Modeled
+//! directly on zoxide's algorithm: `Database::add` bumps an entry's rank on
+//! every visit, `Database::query`/`query_all` filter by ordered keyword
+//! substring match and sort by frecency, and `Database::add` periodically
+//! ages the whole database down so it stays bounded forever.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One tracked directory's visit history.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Entry {
+    /// Visit count, weighted by recency at query time (see `frecency`).
+    pub rank: f64,
+    /// Unix epoch seconds of the most recent visit.
+    pub last_access: u64,
+}
+
+/// Sum of every entry's `rank` past which `Database::add` ages the whole
+/// database down, mirroring zoxide's `ZO_MAXAGE` default.
+const MAX_TOTAL_RANK: f64 = 1000.0;
+
+/// Factor entries are scaled by once `MAX_TOTAL_RANK` is exceeded.
+const AGING_FACTOR: f64 = 0.9;
+
+/// Entries below this rank are dropped during aging, so decades of one-off
+/// visits don't linger in the file forever at a vanishingly small score.
+const AGING_EPSILON: f64 = 0.01;
+
+/// Persistent store mapping absolute directory paths to their visit
+/// history, serialized as JSON under the config dir.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Database {
+    entries: HashMap<PathBuf, Entry>,
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a visit to `path`: bump its rank by 1.0 and set its
+    /// `last_access` to now, ageing the whole database down first if the
+    /// total rank has grown past `MAX_TOTAL_RANK`.
+    pub fn add(&mut self, path: &Path) {
+        self.add_at(path, now())
+    }
+
+    /// Same as `add`, but with the visit timestamp supplied explicitly -
+    /// the entry point tests use to pin `last_access` to a known value.
+    fn add_at(&mut self, path: &Path, now: u64) {
+        let total_rank: f64 = self.entries.values().map(|e| e.rank).sum();
+        if total_rank > MAX_TOTAL_RANK {
+            self.entries.retain(|_, entry| {
+                entry.rank *= AGING_FACTOR;
+                entry.rank >= AGING_EPSILON
+            });
+        }
+
+        let entry = self.entries.entry(path.to_path_buf()).or_insert(Entry {
+            rank: 0.0,
+            last_access: now,
+        });
+        entry.rank += 1.0;
+        entry.last_access = now;
+    }
+
+    /// Score `entry` as of `now`: its rank multiplied by a recency
+    /// weight that favors directories visited within the last hour, then
+    /// tapers off over the following week - zoxide's frecency formula.
+    fn frecency(entry: &Entry, now: u64) -> f64 {
+        let age_secs = now.saturating_sub(entry.last_access);
+        let weight = if age_secs < 3_600 {
+            4.0
+        } else if age_secs < 86_400 {
+            2.0
+        } else if age_secs < 604_800 {
+            0.5
+        } else {
+            0.25
+        };
+        entry.rank * weight
+    }
+
+    /// Every tracked path matching `query`'s keywords, sorted descending by
+    /// frecency - the list an `fzf` picker would be fed. See `matches`
+    /// for the keyword matching rule.
+    pub fn query_all(&self, query: &str) -> Vec<(PathBuf, f64)> {
+        let now = now();
+        let keywords: Vec<&str> = query.split_whitespace().collect();
+
+        let mut scored: Vec<(PathBuf, f64)> = self
+            .entries
+            .iter()
+            .filter(|(path, _)| matches(path, &keywords))
+            .map(|(path, entry)| (path.clone(), Self::frecency(entry, now)))
+            .collect();
+
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+    }
+
+    /// The single best match for `query`, or `None` if nothing matches or
+    /// the database is empty - what a plain `cd <query>` jump resolves to.
+    pub fn query(&self, query: &str) -> Option<PathBuf> {
+        self.query_all(query).into_iter().next().map(|(path, _)| path)
+    }
+
+    /// Path of the persisted frecency database.
+    pub fn store_path() -> Result<PathBuf> {
+        if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+            return Ok(PathBuf::from(xdg_config)
+                .join("warp-foss")
+                .join("directory_frecency.json"));
+        }
+
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(home.join(".config").join("warp-foss").join("directory_frecency.json"))
+    }
+
+    /// Load the persisted database, or an empty one if none exists yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::store_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read frecency database: {:?}", path))?;
+
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse frecency database: {:?}", path))
+    }
+
+    /// Persist the current database to disk.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::store_path()?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create frecency database directory: {:?}", parent))?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).context("Failed to serialize frecency database")?;
+
+        std::fs::write(&path, &contents)
+            .with_context(|| format!("Failed to write frecency database: {:?}", path))?;
+
+        Ok(())
+    }
+}
+
+/// Whether `path` satisfies every keyword in `keywords`, in order, as a
+/// case-insensitive substring match against the path's components - the
+/// same rule zoxide uses. The last keyword must additionally match the
+/// path's final component, so `proj back` matches
+/// `/home/user/project/backend` but not `/home/user/backend/project-notes`.
+fn matches(path: &Path, keywords: &[&str]) -> bool {
+    if keywords.is_empty() {
+        return true;
+    }
+
+    let path_str = path.to_string_lossy().to_lowercase();
+    let Some(last) = keywords.last() else {
+        return true;
+    };
+    let last_component = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !last_component.contains(&last.to_lowercase()) {
+        return false;
+    }
+
+    // Keywords must appear as an ordered (not necessarily contiguous)
+    // substring sequence: each search starts after where the previous
+    // keyword was found.
+    let mut cursor = 0;
+    for keyword in keywords {
+        let keyword = keyword.to_lowercase();
+        match path_str[cursor..].find(&keyword) {
+            Some(offset) => cursor += offset + keyword.len(),
+            None => return false,
+        }
+    }
+    true
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_creates_entry_with_rank_one() {
+        let mut db = Database::new();
+        db.add_at(Path::new("/home/user/project"), 1_000);
+
+        let entry = db.entries[Path::new("/home/user/project")];
+        assert_eq!(entry.rank, 1.0);
+        assert_eq!(entry.last_access, 1_000);
+    }
+
+    #[test]
+    fn test_add_bumps_rank_and_refreshes_last_access_on_repeat_visit() {
+        let mut db = Database::new();
+        db.add_at(Path::new("/home/user/project"), 1_000);
+        db.add_at(Path::new("/home/user/project"), 2_000);
+
+        let entry = db.entries[Path::new("/home/user/project")];
+        assert_eq!(entry.rank, 2.0);
+        assert_eq!(entry.last_access, 2_000);
+    }
+
+    #[test]
+    fn test_frecency_weights_recent_visits_higher() {
+        let recent = Entry { rank: 1.0, last_access: 1_000 };
+        let stale = Entry { rank: 1.0, last_access: 1_000 };
+
+        // Same rank, but scored an hour-and-a-bit later: one is still
+        // "within the hour", the other has aged into the next bracket.
+        let just_inside_hour = Database::frecency(&recent, 1_000 + 3_599);
+        let just_outside_hour = Database::frecency(&stale, 1_000 + 3_601);
+
+        assert_eq!(just_inside_hour, 4.0);
+        assert_eq!(just_outside_hour, 2.0);
+        assert!(just_inside_hour > just_outside_hour);
+    }
+
+    #[test]
+    fn test_query_requires_last_keyword_to_match_final_component() {
+        let mut db = Database::new();
+        db.add_at(Path::new("/home/user/project/backend"), 1_000);
+        db.add_at(Path::new("/home/user/backend/project-notes"), 1_000);
+
+        // "proj back" should resolve to the directory actually *named*
+        // "backend", not the one that merely has "project" earlier in its
+        // path - the trailing keyword anchors to the final component.
+        let hit = db.query("proj back").unwrap();
+        assert_eq!(hit, Path::new("/home/user/project/backend"));
+    }
+
+    #[test]
+    fn test_query_all_sorts_by_descending_frecency() {
+        let mut db = Database::new();
+        db.add_at(Path::new("/home/user/alpha"), 1_000);
+        db.add_at(Path::new("/home/user/beta"), 1_000);
+        db.add_at(Path::new("/home/user/beta"), 1_000);
+
+        let results = db.query_all("home a");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, Path::new("/home/user/beta"));
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn test_query_returns_none_when_nothing_matches() {
+        let mut db = Database::new();
+        db.add_at(Path::new("/home/user/project"), 1_000);
+
+        assert_eq!(db.query("nonexistent"), None);
+    }
+
+    #[test]
+    fn test_add_ages_and_prunes_once_total_rank_exceeds_cap() {
+        let mut db = Database::new();
+        // Pad the database past MAX_TOTAL_RANK with one well-ranked
+        // directory and one that's about to be aged below the epsilon.
+        for _ in 0..1001 {
+            db.add_at(Path::new("/home/user/hot"), 1_000);
+        }
+        db.entries.insert(
+            Path::new("/home/user/cold").to_path_buf(),
+            Entry { rank: AGING_EPSILON / 2.0, last_access: 1_000 },
+        );
+
+        // One more visit finds total rank over the cap and triggers aging.
+        db.add_at(Path::new("/home/user/hot"), 1_000);
+
+        assert!(!db.entries.contains_key(Path::new("/home/user/cold")));
+        assert!(db.entries.contains_key(Path::new("/home/user/hot")));
+    }
+
+    #[test]
+    fn test_database_roundtrips_through_json() {
+        let mut db = Database::new();
+        db.add_at(Path::new("/home/user/project"), 1_000);
+
+        let json = serde_json::to_string(&db).unwrap();
+        let restored: Database = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.entries, db.entries);
+    }
+}
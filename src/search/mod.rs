@@ -1,22 +1,267 @@
 //! Search functionality for terminal content
 
+pub mod frecency;
+pub mod replace;
+pub mod workflows;
+
+use aho_corasick::AhoCorasick;
 use regex::Regex;
-use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
 
-/// Represents a search match in the terminal
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SearchMatch {
-    /// Row index (in scrollback+grid space)
+use crate::terminal::grid::Cursor;
+use crate::ui::selection::SelectionRegion;
+
+/// Matching against wrapped continuations stops growing a logical line
+/// after this many physical rows, mirroring alacritty's `MAX_SEARCH_LINES` -
+/// a pathological wrap chain (or a shell that never emits a newline)
+/// can't make every keystroke rescan the whole grid.
+const MAX_SEARCH_LINES: usize = 100;
+
+/// How many matches `start_search`'s background regex scan accumulates
+/// before sending a batch back, so the UI gets incremental progress without
+/// a channel send per hit.
+const SEARCH_STREAM_BATCH_SIZE: usize = 256;
+
+/// A position within the grid, in (row, column) space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Point {
     pub row: usize,
-    /// Column range of the match
-    pub cols: Range<usize>,
+    pub col: usize,
+}
+
+/// Represents a search match in the terminal. `start`/`end` (exclusive) can
+/// span more than one row when the match crosses a soft-wrapped line break.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchMatch {
+    pub start: Point,
+    pub end: Point,
+    /// The exact positions `SearchMode::Fuzzy` matched, since a fuzzy hit's
+    /// characters aren't contiguous the way a regex match's are. Empty for
+    /// `SearchMode::Regex`, where the whole `start..end` span is one
+    /// contiguous hit and highlighting doesn't need anything finer-grained.
+    pub matched: Vec<Point>,
+    /// Fuzzy match quality (higher is better) - 0 for `SearchMode::Regex`,
+    /// which has no notion of ranking. Exposed so the UI can dim low-ranked
+    /// fuzzy hits.
+    pub score: i64,
+}
+
+impl SearchMatch {
+    /// Whether `(row, col)` falls inside this match's span.
+    fn contains(&self, row: usize, col: usize) -> bool {
+        if !self.matched.is_empty() {
+            return self.matched.iter().any(|p| p.row == row && p.col == col);
+        }
+        if row < self.start.row || row > self.end.row {
+            return false;
+        }
+        if row == self.start.row && col < self.start.col {
+            return false;
+        }
+        if row == self.end.row && col >= self.end.col {
+            return false;
+        }
+        true
+    }
+
+    /// This match's span as a `SelectionRegion`, so a match can be promoted
+    /// into the live selection (see `SearchState::current_match_as_selection`)
+    /// and copied the same way a mouse/vi-mode selection is. `end` is
+    /// adjusted from `SearchMatch`'s exclusive convention to
+    /// `SelectionRegion`'s inclusive one.
+    pub fn to_selection_region(&self) -> SelectionRegion {
+        let end_col = self.end.col.saturating_sub(1);
+        SelectionRegion::new(
+            Cursor::new(self.start.row, self.start.col),
+            Cursor::new(self.end.row, end_col),
+        )
+    }
+}
+
+/// Which matching engine `SearchState` uses. Orthogonal to `regex_mode`
+/// (literal vs. regex), since fuzzy matching doesn't go through `Regex` at
+/// all - it's its own subsequence scorer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Substring/regex matching via the compiled `pattern` (see `regex_mode`).
+    #[default]
+    Regex,
+    /// Smith-Waterman-style fuzzy subsequence matching (fzf/nucleo-style) -
+    /// see `fuzzy_match`.
+    Fuzzy,
+}
+
+/// Characters that count as a word boundary for fuzzy-match bonus scoring,
+/// alongside whitespace.
+fn is_fuzzy_separator(c: char) -> bool {
+    matches!(c, '/' | '_' | '-' | '.') || c.is_whitespace()
+}
+
+/// Whether `text[idx]` lands on a fuzzy-match "boundary" worth a bonus: the
+/// very first character, the character right after a separator, or a
+/// lowercase-to-uppercase (camelCase) transition.
+fn is_fuzzy_boundary(text: &[char], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+    let prev = text[idx - 1];
+    if is_fuzzy_separator(prev) {
+        return true;
+    }
+    prev.is_lowercase() && text[idx].is_uppercase()
+}
+
+const FUZZY_MATCH_SCORE: i64 = 16;
+const FUZZY_CONSECUTIVE_BONUS: i64 = 15;
+const FUZZY_BOUNDARY_BONUS: i64 = 10;
+const FUZZY_GAP_PENALTY: i64 = 2;
+
+/// Smith-Waterman-style fuzzy subsequence match, the algorithm fzf and
+/// nucleo use: a greedy left-to-right scan first confirms every character of
+/// `query` appears in `text` in order, rejecting early otherwise and
+/// bracketing the region the characters span, then a small DP restricted to
+/// that region picks an alignment within it. The DP keeps only the single
+/// best-scoring path (and its last matched column) per query/window prefix,
+/// so it can miss a higher-scoring alignment whose prefix scored lower but
+/// would have earned a later consecutive bonus. Matches earn a
+/// base score per character, a large bonus when consecutive with the
+/// previous match, a boundary bonus for landing on the first character,
+/// after a separator (`/ _ - .` or whitespace), or a camelCase transition,
+/// and a gap penalty proportional to the text characters skipped since the
+/// last match. Returns the score and the exact matched char indices (not
+/// byte offsets) into `text`, or `None` if `query` isn't a subsequence.
+fn fuzzy_match(query: &str, text: &str, case_insensitive: bool) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return None;
+    }
+
+    let fold = |c: char| if case_insensitive { c.to_ascii_lowercase() } else { c };
+
+    let text_chars: Vec<char> = text.chars().collect();
+    let folded_text: Vec<char> = text_chars.iter().copied().map(fold).collect();
+    let folded_query: Vec<char> = query.chars().map(fold).collect();
+
+    // Greedy scan: confirm the subsequence exists and find the tightest
+    // [first_match, last_match] window the DP needs to consider.
+    let mut qi = 0;
+    let mut first_match = None;
+    let mut last_match = None;
+    for (ti, &c) in folded_text.iter().enumerate() {
+        if qi < folded_query.len() && c == folded_query[qi] {
+            first_match.get_or_insert(ti);
+            last_match = Some(ti);
+            qi += 1;
+        }
+    }
+    if qi < folded_query.len() {
+        return None;
+    }
+    let window_start = first_match?;
+    let window_end = last_match?;
+    let window = &folded_text[window_start..=window_end];
+
+    let n = folded_query.len();
+    let m = window.len();
+    const NEG_INF: i64 = i64::MIN / 2;
+
+    // best[i][j]: best score matching query[..i] using window[..j].
+    // last_col[i][j]: the window column (if any) of the last matched
+    // character along that best path, needed for the gap/consecutive bonus.
+    // via_match[i][j]: whether that best score came from matching
+    // window[j-1] to query[i-1] (vs. skipping window[j-1] entirely).
+    let mut best = vec![vec![NEG_INF; m + 1]; n + 1];
+    let mut last_col = vec![vec![None::<usize>; m + 1]; n + 1];
+    let mut via_match = vec![vec![false; m + 1]; n + 1];
+    for row in best[0].iter_mut() {
+        *row = 0;
+    }
+
+    for i in 1..=n {
+        for j in i..=m {
+            let skip_score = best[i][j - 1];
+            let skip_last = last_col[i][j - 1];
+
+            let mut match_score = NEG_INF;
+            let mut match_last = None;
+            if window[j - 1] == folded_query[i - 1] {
+                let prev_score = best[i - 1][j - 1];
+                if prev_score > NEG_INF {
+                    let prev_last = last_col[i - 1][j - 1];
+                    let consecutive = matches!(prev_last, Some(p) if p + 1 == j - 1);
+                    let gap = match prev_last {
+                        Some(p) => (j - 1).saturating_sub(p + 1) as i64,
+                        None => 0,
+                    };
+                    let boundary_bonus = if is_fuzzy_boundary(&text_chars, window_start + j - 1) {
+                        FUZZY_BOUNDARY_BONUS
+                    } else {
+                        0
+                    };
+                    let consecutive_bonus = if consecutive { FUZZY_CONSECUTIVE_BONUS } else { 0 };
+                    match_score = prev_score + FUZZY_MATCH_SCORE + consecutive_bonus + boundary_bonus
+                        - gap * FUZZY_GAP_PENALTY;
+                    match_last = Some(j - 1);
+                }
+            }
+
+            if match_score >= skip_score {
+                best[i][j] = match_score;
+                last_col[i][j] = match_last;
+                via_match[i][j] = true;
+            } else {
+                best[i][j] = skip_score;
+                last_col[i][j] = skip_last;
+                via_match[i][j] = false;
+            }
+        }
+    }
+
+    let mut positions = Vec::with_capacity(n);
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        if via_match[i][j] {
+            positions.push(window_start + j - 1);
+            i -= 1;
+            j -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    positions.reverse();
+
+    Some((best[n][m], positions))
+}
+
+/// Incremental results sent back by the worker thread `SearchState::start_search`
+/// spawns. Each `Matches` batch of a literal or fuzzy scan covers one logical
+/// (possibly soft-wrapped) line; a regex scan sends batches of up to
+/// `SEARCH_STREAM_BATCH_SIZE` matches. Either way the caller can append to
+/// `SearchState::matches` and redraw
+/// without waiting for the whole scrollback to finish.
+enum SearchEvent {
+    Matches(Vec<SearchMatch>),
+    /// The scan reached the end of its row set. Carries the generation it
+    /// was started with, so a `cancel()`-then-`start_search()` in the same
+    /// tick can't have its `Done` mistaken for the new search's.
+    Done(u64),
 }
 
 /// Search state for the terminal
-#[derive(Debug, Clone)]
 pub struct SearchState {
-    /// Current search pattern (as regex)
+    /// Current search pattern (as regex). `None` whenever `literal_terms` is
+    /// set instead - the two are mutually exclusive fast paths for the same
+    /// `query`, picked by `literal_terms()`.
     pattern: Option<Regex>,
+    /// Set instead of `pattern` when `query` has no regex metacharacters (see
+    /// `literal_terms`): one or more plain words matched via an
+    /// `aho_corasick::AhoCorasick` automaton rather than a compiled `Regex`,
+    /// which is measurably cheaper to build and run on every keystroke. More
+    /// than one term means "all of these words", not a literal phrase - each
+    /// occurrence still gets its own `SearchMatch`.
+    literal_terms: Option<Vec<String>>,
     /// All matches found
     matches: Vec<SearchMatch>,
     /// Index of currently selected match
@@ -25,16 +270,71 @@ pub struct SearchState {
     pub active: bool,
     /// Search query string
     pub query: String,
+    /// When `false` (the default), `query` is matched literally - special
+    /// regex characters in it are escaped. When `true`, `query` is compiled
+    /// as a regex directly, alacritty `RegexSearch`-style.
+    regex_mode: bool,
+    /// Set when `query` failed to compile as a regex in `regex_mode`, so
+    /// `render_search_bar` can show the input in an error color instead of
+    /// silently keeping the previous matches.
+    error: bool,
+    /// When `true` (the default), matching ignores case by prefixing the
+    /// compiled pattern with `(?i)`. Toggled independently of `regex_mode`.
+    case_insensitive: bool,
+    /// Which matching engine `find_matches` uses (see `SearchMode`).
+    mode: SearchMode,
+    /// Receives `SearchEvent`s from the in-flight background scan started by
+    /// `start_search`, if any.
+    search_rx: Option<Receiver<SearchEvent>>,
+    /// Bumped by every `start_search`/`cancel` call. The worker thread is
+    /// handed the value it was started with and checks it between logical
+    /// lines; a mismatch means a newer search superseded it, so it drops its
+    /// results and exits instead of racing them onto `matches`.
+    generation: Arc<AtomicU64>,
+    /// The generation value the in-flight scan (if any) was started with.
+    current_generation: u64,
+    /// Anchor row to re-apply `select_nearest` with once the first batch of
+    /// results for the in-flight scan arrives.
+    pending_anchor_row: usize,
+    /// Whether a background scan is currently in flight.
+    searching: bool,
+}
+
+impl std::fmt::Debug for SearchState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchState")
+            .field("literal_terms", &self.literal_terms)
+            .field("matches", &self.matches)
+            .field("current_match_index", &self.current_match_index)
+            .field("active", &self.active)
+            .field("query", &self.query)
+            .field("regex_mode", &self.regex_mode)
+            .field("error", &self.error)
+            .field("case_insensitive", &self.case_insensitive)
+            .field("mode", &self.mode)
+            .field("searching", &self.searching)
+            .finish()
+    }
 }
 
 impl Default for SearchState {
     fn default() -> Self {
         Self {
             pattern: None,
+            literal_terms: None,
             matches: Vec::new(),
             current_match_index: None,
             active: false,
             query: String::new(),
+            regex_mode: false,
+            error: false,
+            case_insensitive: true,
+            mode: SearchMode::Regex,
+            search_rx: None,
+            generation: Arc::new(AtomicU64::new(0)),
+            current_generation: 0,
+            pending_anchor_row: 0,
+            searching: false,
         }
     }
 }
@@ -45,58 +345,627 @@ impl SearchState {
         Self::default()
     }
 
-    /// Update the search pattern
+    /// Whether regex mode is enabled (see `toggle_regex_mode`).
+    pub fn regex_mode(&self) -> bool {
+        self.regex_mode
+    }
+
+    /// Toggle between literal and regex search modes and recompile the
+    /// current query under the new mode. Callers still need to re-run
+    /// `find_matches` to refresh match positions.
+    pub fn toggle_regex_mode(&mut self) {
+        self.regex_mode = !self.regex_mode;
+        if !self.query.is_empty() {
+            let query = self.query.clone();
+            let _ = self.set_pattern(&query);
+        }
+    }
+
+    /// Whether the current query failed to compile as a regex.
+    pub fn has_error(&self) -> bool {
+        self.error
+    }
+
+    /// Which matching engine is currently active (see `SearchMode`).
+    pub fn mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    /// Toggle between `SearchMode::Regex` and `SearchMode::Fuzzy`. Callers
+    /// still need to re-run `find_matches` to refresh match positions.
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            SearchMode::Regex => SearchMode::Fuzzy,
+            SearchMode::Fuzzy => SearchMode::Regex,
+        };
+        if !self.query.is_empty() {
+            let query = self.query.clone();
+            let _ = self.set_pattern(&query);
+        }
+    }
+
+    /// Whether matching currently ignores case (see `case_insensitive`).
+    pub fn case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Toggle case sensitivity and recompile the current query under the
+    /// new setting. Callers still need to re-run `find_matches` to refresh
+    /// match positions.
+    pub fn toggle_case_sensitive(&mut self) {
+        self.case_insensitive = !self.case_insensitive;
+        if !self.query.is_empty() {
+            let query = self.query.clone();
+            let _ = self.set_pattern(&query);
+        }
+    }
+
+    /// Split `query` into the plain words an `AhoCorasick` fast path can
+    /// search for instead of compiling a `Regex`, or `None` if it needs real
+    /// regex semantics. In literal mode `query` is never a regex to begin
+    /// with, so it always qualifies - each whitespace-separated word becomes
+    /// its own term, matched with "all of these words" (not necessarily
+    /// adjacent) semantics, the way the request's multi-term search wants.
+    /// In regex mode it only qualifies when `query` happens to contain no
+    /// regex metacharacters at all (`regex::escape` is a no-op on it), in
+    /// which case it's treated as a single literal term rather than split on
+    /// whitespace - an explicit regex query's spaces are still significant.
+    fn literal_terms(query: &str, regex_mode: bool) -> Option<Vec<String>> {
+        if regex_mode {
+            if regex::escape(query) == query {
+                Some(vec![query.to_string()])
+            } else {
+                None
+            }
+        } else {
+            let terms: Vec<String> = query.split_whitespace().map(str::to_string).collect();
+            if terms.is_empty() {
+                None
+            } else {
+                Some(terms)
+            }
+        }
+    }
+
+    /// Update the search pattern. In literal mode (the default), `query` is
+    /// escaped before compiling so regex metacharacters match themselves;
+    /// in regex mode it's compiled as-is. An invalid regex is recorded via
+    /// `has_error` rather than leaving the caller to surface a panic/error
+    /// path - the search bar just renders the input in an error color.
+    /// When `query` qualifies for the `AhoCorasick` fast path (see
+    /// `literal_terms`), no `Regex` is compiled at all.
     pub fn set_pattern(&mut self, query: &str) -> Result<(), regex::Error> {
         if query.is_empty() {
             self.clear();
             return Ok(());
         }
 
-        // Build regex pattern (case-insensitive by default)
-        let pattern = Regex::new(&format!("(?i){}", query))?;
-        self.pattern = Some(pattern);
         self.query = query.to_string();
         self.matches.clear();
         self.current_match_index = None;
-        Ok(())
+
+        if let Some(terms) = Self::literal_terms(query, self.regex_mode) {
+            self.pattern = None;
+            self.literal_terms = Some(terms);
+            self.error = false;
+            return Ok(());
+        }
+
+        // Only reachable when `literal_terms` declined the fast path: a
+        // regex-mode query with real metacharacters, or (edge case) a
+        // literal-mode query that's whitespace-only and so has no terms to
+        // search for - escape it the same as before so it still compiles.
+        let escaped;
+        let body = if self.regex_mode {
+            query
+        } else {
+            escaped = regex::escape(query);
+            &escaped
+        };
+
+        // `m` (multi_line) makes `^`/`$` anchor at the real line boundaries
+        // `find_regex_matches_multiline` joins rows on, not just the ends of
+        // the whole buffer, so a pattern anchored to a line still behaves
+        // the way a user typing it in `grep` would expect.
+        let prefix = if self.case_insensitive { "(?mi)" } else { "(?m)" };
+        match Regex::new(&format!("{}{}", prefix, body)) {
+            Ok(pattern) => {
+                self.pattern = Some(pattern);
+                self.literal_terms = None;
+                self.error = false;
+                Ok(())
+            }
+            Err(e) => {
+                self.pattern = None;
+                self.literal_terms = None;
+                self.error = true;
+                Err(e)
+            }
+        }
     }
 
     /// Clear the search
     pub fn clear(&mut self) {
+        self.cancel();
         self.pattern = None;
+        self.literal_terms = None;
         self.matches.clear();
         self.current_match_index = None;
         self.active = false;
         self.query.clear();
+        self.error = false;
+    }
+
+    /// Scan one already-assembled logical line (a single row, or several
+    /// soft-wrapped rows joined together) for a `SearchMode::Fuzzy` query and
+    /// return whatever it contains. Shared by the synchronous `find_matches`
+    /// and the background worker `start_search` spawns, so the two can't
+    /// drift out of sync on how a hit's `start`/`end`/`matched`/`score` get
+    /// computed. Regex-mode matching doesn't go through here - see
+    /// `find_regex_matches_multiline` and `find_literal_matches`.
+    fn fuzzy_matches_for_logical_line(
+        query: &str,
+        case_insensitive: bool,
+        logical: &str,
+        row_offsets: &[(usize, usize)],
+    ) -> Vec<SearchMatch> {
+        let mut found = Vec::new();
+        if let Some((score, char_indices)) = fuzzy_match(query, logical, case_insensitive) {
+            let byte_offsets: Vec<usize> = logical.char_indices().map(|(b, _)| b).collect();
+            let matched: Vec<Point> = char_indices
+                .iter()
+                .map(|&ci| Self::point_for_offset(row_offsets, byte_offsets[ci]))
+                .collect();
+            let start = matched.first().copied().unwrap_or_default();
+            let end = matched.last().copied().unwrap_or_default();
+            found.push(SearchMatch {
+                start,
+                end,
+                matched,
+                score,
+            });
+        }
+        found
+    }
+
+    /// Build the `AhoCorasick` automaton `find_literal_matches` and
+    /// `start_search`'s literal-mode worker scan with. `terms` are always
+    /// plain strings (see `literal_terms`), so building never fails.
+    fn build_literal_automaton(terms: &[&str], case_insensitive: bool) -> AhoCorasick {
+        AhoCorasick::builder()
+            .ascii_case_insensitive(case_insensitive)
+            .build(terms)
+            .expect("literal terms are plain strings and always build")
+    }
+
+    /// Scan one already-assembled logical line with a literal-term automaton.
+    /// With more than one term, a line only counts as matched when every
+    /// distinct term hit somewhere in it ("all of these words", not
+    /// necessarily adjacent) - otherwise a `SearchMatch` is emitted for every
+    /// occurrence of every term, same as a plain substring search would for
+    /// one term.
+    fn literal_matches_for_logical_line(
+        ac: &AhoCorasick,
+        term_count: usize,
+        logical: &str,
+        row_offsets: &[(usize, usize)],
+    ) -> Vec<SearchMatch> {
+        let mut seen_terms = vec![false; term_count];
+        let mut hits = Vec::new();
+        for mat in ac.find_iter(logical) {
+            seen_terms[mat.pattern().as_usize()] = true;
+            hits.push(mat);
+        }
+
+        if term_count > 1 && !seen_terms.iter().all(|&seen| seen) {
+            return Vec::new();
+        }
+
+        hits.into_iter()
+            .map(|mat| SearchMatch {
+                start: Self::point_for_offset(row_offsets, mat.start()),
+                end: Self::point_for_offset(row_offsets, mat.end()),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Literal/multi-term matching: the `AhoCorasick` fast path `find_matches`
+    /// takes instead of `find_regex_matches_multiline` when `literal_terms`
+    /// applies. Scans one soft-wrap chain at a time rather than the whole
+    /// joined buffer - "all of these words" is inherently a per-line notion,
+    /// and a plain literal term (having no regex syntax) has no way to
+    /// express matching across separate lines in the first place.
+    fn find_literal_matches(
+        rows: &[(usize, &str, bool)],
+        terms: &[String],
+        case_insensitive: bool,
+    ) -> Vec<SearchMatch> {
+        let term_refs: Vec<&str> = terms.iter().map(String::as_str).collect();
+        let ac = Self::build_literal_automaton(&term_refs, case_insensitive);
+        let mut found = Vec::new();
+        let mut idx = 0;
+        while idx < rows.len() {
+            let (logical, row_offsets, next_idx) = Self::chain_logical_line(rows, idx);
+            idx = next_idx;
+            found.extend(Self::literal_matches_for_logical_line(
+                &ac,
+                terms.len(),
+                &logical,
+                &row_offsets,
+            ));
+        }
+        found
+    }
+
+    /// Chain soft-wrapped rows starting at `rows[start_idx]` into one logical
+    /// line, the way a match that was broken across a wrap would read. Returns
+    /// the logical line, the `(row, byte_offset)` of each physical row folded
+    /// into it, and the index just past the chain.
+    fn chain_logical_line(
+        rows: &[(usize, &str, bool)],
+        start_idx: usize,
+    ) -> (String, Vec<(usize, usize)>, usize) {
+        let mut idx = start_idx;
+        let mut logical = String::new();
+        let mut row_offsets: Vec<(usize, usize)> = Vec::new();
+
+        loop {
+            let (row, text, wraps) = rows[idx];
+            row_offsets.push((row, logical.len()));
+            logical.push_str(text);
+            idx += 1;
+
+            let chain_len = idx - start_idx;
+            if !wraps || chain_len >= MAX_SEARCH_LINES || idx >= rows.len() {
+                break;
+            }
+        }
+
+        (logical, row_offsets, idx)
     }
 
-    /// Find all matches in the given text lines
+    /// Join every row into one buffer, separating distinct logical lines
+    /// (chains of soft-wrapped rows) with `\n`, so a regex compiled with the
+    /// `m` flag can match across a boundary a per-line scan would never see -
+    /// e.g. a pattern spanning the end of one command and the start of the
+    /// next. Returns the buffer along with each chain's `(absolute_start,
+    /// absolute_end, row_offsets)`, where `row_offsets` is in the same
+    /// chain-relative form `chain_logical_line`/`point_for_offset` use.
+    fn build_multiline_buffer(
+        rows: &[(usize, &str, bool)],
+    ) -> (String, Vec<(usize, usize, Vec<(usize, usize)>)>) {
+        let mut buffer = String::new();
+        let mut chains = Vec::new();
+        let mut idx = 0;
+        while idx < rows.len() {
+            if !buffer.is_empty() {
+                buffer.push('\n');
+            }
+            let base = buffer.len();
+            let (logical, row_offsets, next_idx) = Self::chain_logical_line(rows, idx);
+            idx = next_idx;
+            buffer.push_str(&logical);
+            chains.push((base, base + logical.len(), row_offsets));
+        }
+        (buffer, chains)
+    }
+
+    /// Map one match's `[start, end)` byte range in a `build_multiline_buffer`
+    /// buffer back to grid positions. The `\n` chain separators never match
+    /// anything inside a chain, so a match overlapping more than one chain
+    /// must be crossing a join point - split it into one `SearchMatch` per
+    /// chain it touches so highlighting still works cell-by-cell.
+    fn split_multiline_match(
+        chains: &[(usize, usize, Vec<(usize, usize)>)],
+        start: usize,
+        end: usize,
+    ) -> Vec<SearchMatch> {
+        let mut found = Vec::new();
+        for (chain_start, chain_end, row_offsets) in chains {
+            if end <= *chain_start || start >= *chain_end {
+                continue;
+            }
+            let local_start = start.max(*chain_start) - chain_start;
+            let local_end = end.min(*chain_end) - chain_start;
+            found.push(SearchMatch {
+                start: Self::point_for_offset(row_offsets, local_start),
+                end: Self::point_for_offset(row_offsets, local_end),
+                ..Default::default()
+            });
+        }
+        found
+    }
+
+    /// Regex-mode matching: scan the whole `build_multiline_buffer` buffer in
+    /// one pass so a pattern can match across a logical-line boundary, not
+    /// just within one soft-wrapped chain.
+    fn find_regex_matches_multiline(rows: &[(usize, &str, bool)], pattern: &Regex) -> Vec<SearchMatch> {
+        let (buffer, chains) = Self::build_multiline_buffer(rows);
+        let mut found = Vec::new();
+        for mat in pattern.find_iter(&buffer) {
+            found.extend(Self::split_multiline_match(&chains, mat.start(), mat.end()));
+        }
+        found
+    }
+
+    /// Find all matches across a set of rows. A query with no regex
+    /// metacharacters runs through the `AhoCorasick` fast path instead of a
+    /// compiled `Regex` (see `literal_terms`/`find_literal_matches`) and, like
+    /// fuzzy mode, matches one logical line at a time. A genuine regex query
+    /// matches across logical-line boundaries too (see
+    /// `find_regex_matches_multiline`); fuzzy mode matches one logical line
+    /// (a soft-wrap chain) at a time, since a fuzzy query is meant to find
+    /// one candidate line, not splice two unrelated ones together.
+    /// Synchronous - for scrollback large enough to stall the UI thread, use
+    /// `start_search` instead.
     ///
     /// # Arguments
-    /// * `lines` - Iterator of (row_index, text) pairs
-    pub fn find_matches<'a, I>(&mut self, lines: I)
+    /// * `rows` - `(row_index, text, wraps_into_next)` triples in ascending
+    ///   row order; `wraps_into_next` should come from `TerminalGrid::is_row_wrapped`.
+    /// * `anchor_row` - the match whose start row is nearest this becomes
+    ///   the new "current" match, so results stay near the viewport as the
+    ///   user types instead of always jumping back to the first match.
+    pub fn find_matches<'a, I>(&mut self, rows: I, anchor_row: usize)
     where
-        I: Iterator<Item = (usize, &'a str)>,
+        I: IntoIterator<Item = (usize, &'a str, bool)>,
     {
+        self.cancel();
         self.matches.clear();
+        self.current_match_index = None;
+
+        if self.query.is_empty() {
+            return;
+        }
 
-        if let Some(ref pattern) = self.pattern {
-            for (row, text) in lines {
-                for mat in pattern.find_iter(text) {
-                    self.matches.push(SearchMatch {
-                        row,
-                        cols: mat.start()..mat.end(),
-                    });
+        let rows: Vec<(usize, &str, bool)> = rows.into_iter().collect();
+
+        match self.mode {
+            SearchMode::Regex => {
+                if let Some(ref terms) = self.literal_terms {
+                    self.matches = Self::find_literal_matches(&rows, terms, self.case_insensitive);
+                } else if let Some(ref pattern) = self.pattern {
+                    self.matches = Self::find_regex_matches_multiline(&rows, pattern);
+                }
+            }
+            SearchMode::Fuzzy => {
+                let mut idx = 0;
+                while idx < rows.len() {
+                    let (logical, row_offsets, next_idx) = Self::chain_logical_line(&rows, idx);
+                    idx = next_idx;
+                    self.matches.extend(Self::fuzzy_matches_for_logical_line(
+                        &self.query,
+                        self.case_insensitive,
+                        &logical,
+                        &row_offsets,
+                    ));
                 }
             }
         }
 
-        // Select first match if available
-        self.current_match_index = if self.matches.is_empty() {
-            None
-        } else {
-            Some(0)
+        // In fuzzy mode, matches rank by quality rather than document order,
+        // so sorting here makes `next_match`/`prev_match` - which just walk
+        // `self.matches` in order - traverse best-to-worst.
+        if self.mode == SearchMode::Fuzzy {
+            self.matches.sort_by(|a, b| b.score.cmp(&a.score));
+        }
+
+        self.select_nearest(anchor_row);
+    }
+
+    /// Start an incremental background scan over `rows` (same row format as
+    /// `find_matches`, but owned since the worker thread outlives this call),
+    /// streaming `SearchMatch`es back through `poll` as they're found instead
+    /// of blocking the caller while hundreds of thousands of scrollback lines
+    /// scan. Cancels any scan already in flight. A no-op (clears `matches`
+    /// and returns) if the current query hasn't compiled to a pattern.
+    pub fn start_search(&mut self, rows: Vec<(usize, String, bool)>, anchor_row: usize) {
+        self.cancel();
+        self.matches.clear();
+        self.current_match_index = None;
+
+        if self.query.is_empty() {
+            return;
+        }
+
+        let pattern = self.pattern.clone();
+        let literal_terms = self.literal_terms.clone();
+        if pattern.is_none() && literal_terms.is_none() {
+            return;
+        }
+
+        let target_generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.current_generation = target_generation;
+        self.pending_anchor_row = anchor_row;
+        self.searching = true;
+
+        let (tx, rx) = mpsc::channel();
+        self.search_rx = Some(rx);
+
+        let mode = self.mode;
+        let query = self.query.clone();
+        let case_insensitive = self.case_insensitive;
+        let generation = Arc::clone(&self.generation);
+
+        thread::spawn(move || {
+            let view: Vec<(usize, &str, bool)> =
+                rows.iter().map(|(r, s, w)| (*r, s.as_str(), *w)).collect();
+
+            match mode {
+                SearchMode::Regex => {
+                    if let Some(terms) = literal_terms {
+                        // Unlike the regex path below, a literal/multi-term
+                        // scan is inherently per-line, so it streams results
+                        // one soft-wrap chain at a time rather than building
+                        // the whole buffer up front.
+                        let term_refs: Vec<&str> = terms.iter().map(String::as_str).collect();
+                        let ac = Self::build_literal_automaton(&term_refs, case_insensitive);
+                        let mut idx = 0;
+                        while idx < view.len() {
+                            if generation.load(Ordering::SeqCst) != target_generation {
+                                return;
+                            }
+                            let (logical, row_offsets, next_idx) =
+                                Self::chain_logical_line(&view, idx);
+                            idx = next_idx;
+                            let found = Self::literal_matches_for_logical_line(
+                                &ac,
+                                terms.len(),
+                                &logical,
+                                &row_offsets,
+                            );
+                            if !found.is_empty() && tx.send(SearchEvent::Matches(found)).is_err() {
+                                return;
+                            }
+                        }
+                    } else if let Some(pattern) = pattern {
+                        // Matching across logical lines (see
+                        // `find_regex_matches_multiline`) needs the whole
+                        // buffer up front, so results stream out in
+                        // fixed-size batches as they're found rather than
+                        // one soft-wrap chain at a time.
+                        let (buffer, chains) = Self::build_multiline_buffer(&view);
+                        let mut batch = Vec::new();
+                        for mat in pattern.find_iter(&buffer) {
+                            if generation.load(Ordering::SeqCst) != target_generation {
+                                return;
+                            }
+                            batch.extend(Self::split_multiline_match(
+                                &chains,
+                                mat.start(),
+                                mat.end(),
+                            ));
+                            if batch.len() >= SEARCH_STREAM_BATCH_SIZE
+                                && tx
+                                    .send(SearchEvent::Matches(std::mem::take(&mut batch)))
+                                    .is_err()
+                            {
+                                return;
+                            }
+                        }
+                        if !batch.is_empty() && tx.send(SearchEvent::Matches(batch)).is_err() {
+                            return;
+                        }
+                    }
+                }
+                SearchMode::Fuzzy => {
+                    let mut idx = 0;
+                    while idx < view.len() {
+                        if generation.load(Ordering::SeqCst) != target_generation {
+                            return;
+                        }
+
+                        let (logical, row_offsets, next_idx) = Self::chain_logical_line(&view, idx);
+                        idx = next_idx;
+                        let found = Self::fuzzy_matches_for_logical_line(
+                            &query,
+                            case_insensitive,
+                            &logical,
+                            &row_offsets,
+                        );
+                        if !found.is_empty() && tx.send(SearchEvent::Matches(found)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+
+            let _ = tx.send(SearchEvent::Done(target_generation));
+        });
+    }
+
+    /// Abort the in-flight `start_search` scan, if any, so it stops sending
+    /// results once it notices. Safe to call with nothing in flight.
+    pub fn cancel(&mut self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.search_rx = None;
+        self.searching = false;
+    }
+
+    /// Whether a `start_search` scan is currently in flight.
+    pub fn searching(&self) -> bool {
+        self.searching
+    }
+
+    /// The generation token of the most recently started scan. Each
+    /// `start_search` gives it a new value, so a caller holding an older
+    /// value knows any results it's still waiting on are stale; `cancel`
+    /// leaves it unchanged.
+    pub fn generation(&self) -> u64 {
+        self.current_generation
+    }
+
+    /// Drain whatever results the in-flight background scan has produced
+    /// since the last call, appending them to `matches` (re-sorting by score
+    /// first in fuzzy mode) and selecting a current match once results start
+    /// arriving. Returns `true` if anything changed. Call once per frame
+    /// while `searching()` is true.
+    pub fn poll(&mut self) -> bool {
+        let Some(rx) = &self.search_rx else {
+            return false;
         };
+
+        let mut changed = false;
+        loop {
+            match rx.try_recv() {
+                Ok(SearchEvent::Matches(batch)) => {
+                    self.matches.extend(batch);
+                    changed = true;
+                }
+                Ok(SearchEvent::Done(generation)) => {
+                    if generation == self.current_generation {
+                        self.searching = false;
+                    }
+                    self.search_rx = None;
+                    changed = true;
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.search_rx = None;
+                    break;
+                }
+            }
+        }
+
+        if changed {
+            if self.mode == SearchMode::Fuzzy {
+                self.matches.sort_by(|a, b| b.score.cmp(&a.score));
+            }
+            if self.current_match_index.is_none() && !self.matches.is_empty() {
+                self.select_nearest(self.pending_anchor_row);
+            }
+        }
+
+        changed
+    }
+
+    /// Map a byte offset into a reconstructed logical line back to the
+    /// physical `(row, col)` it came from.
+    fn point_for_offset(row_offsets: &[(usize, usize)], offset: usize) -> Point {
+        let mut current = row_offsets[0];
+        for &(row, row_start) in row_offsets {
+            if row_start <= offset {
+                current = (row, row_start);
+            } else {
+                break;
+            }
+        }
+        Point {
+            row: current.0,
+            col: offset - current.1,
+        }
+    }
+
+    /// Select whichever match starts nearest `anchor_row`, preferring the
+    /// earlier one on a tie.
+    fn select_nearest(&mut self, anchor_row: usize) {
+        self.current_match_index = self
+            .matches
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, m)| anchor_row.abs_diff(m.start.row))
+            .map(|(i, _)| i);
     }
 
     /// Get the number of matches
@@ -150,10 +1019,17 @@ impl SearchState {
         self.current_match_index.and_then(|i| self.matches.get(i))
     }
 
+    /// The current match's span as a `SelectionRegion`, so it can be promoted
+    /// into `SelectionState` and copied via `Clipboard` like any other
+    /// selection. `None` if there is no current match.
+    pub fn current_match_as_selection(&self) -> Option<SelectionRegion> {
+        self.current_match().map(SearchMatch::to_selection_region)
+    }
+
     /// Check if a cell at (row, col) is part of the current match
     pub fn is_current_match(&self, row: usize, col: usize) -> bool {
-        if let Some(ref current) = self.current_match() {
-            current.row == row && current.cols.contains(&col)
+        if let Some(current) = self.current_match() {
+            current.contains(row, col)
         } else {
             false
         }
@@ -161,7 +1037,7 @@ impl SearchState {
 
     /// Check if a cell at (row, col) is part of any match
     pub fn is_match(&self, row: usize, col: usize) -> bool {
-        self.matches.iter().any(|m| m.row == row && m.cols.contains(&col))
+        self.matches.iter().any(|m| m.contains(row, col))
     }
 }
 
@@ -169,19 +1045,27 @@ impl SearchState {
 mod tests {
     use super::*;
 
+    fn rows_no_wrap(lines: &[(usize, &str)]) -> Vec<(usize, &str, bool)> {
+        lines.iter().map(|(r, t)| (*r, *t, false)).collect()
+    }
+
     #[test]
     fn test_search_state_creation() {
         let state = SearchState::new();
         assert!(!state.active);
         assert!(state.query.is_empty());
         assert_eq!(state.match_count(), 0);
+        assert!(!state.regex_mode());
     }
 
     #[test]
     fn test_set_pattern() {
         let mut state = SearchState::new();
         state.set_pattern("test").unwrap();
-        assert!(state.pattern.is_some());
+        // A plain literal word takes the `AhoCorasick` fast path rather than
+        // compiling a `Regex` (see `literal_terms`).
+        assert!(state.pattern.is_none());
+        assert_eq!(state.literal_terms, Some(vec!["test".to_string()]));
         assert_eq!(state.query, "test");
     }
 
@@ -196,7 +1080,7 @@ mod tests {
             (2, "no match here"),
         ];
 
-        state.find_matches(lines.iter().map(|(r, t)| (*r, *t)));
+        state.find_matches(rows_no_wrap(&lines), 0);
 
         assert_eq!(state.match_count(), 2);
         assert_eq!(state.current_match_number(), Some(1));
@@ -207,51 +1091,68 @@ mod tests {
         let mut state = SearchState::new();
         state.set_pattern("test").unwrap();
 
-        let lines = vec![
-            (0, "test 1"),
-            (1, "test 2"),
-            (2, "test 3"),
-        ];
+        let lines = vec![(0, "test 1"), (1, "test 2"), (2, "test 3")];
 
-        state.find_matches(lines.iter().map(|(r, t)| (*r, *t)));
+        state.find_matches(rows_no_wrap(&lines), 0);
 
         // Initial match should be first
         let m1 = state.current_match().unwrap();
-        assert_eq!(m1.row, 0);
+        assert_eq!(m1.start.row, 0);
 
         // Next should be second
         let m2 = state.next_match().unwrap();
-        assert_eq!(m2.row, 1);
+        assert_eq!(m2.start.row, 1);
 
         // Next should be third
         let m3 = state.next_match().unwrap();
-        assert_eq!(m3.row, 2);
+        assert_eq!(m3.start.row, 2);
 
         // Next should wrap to first
         let m4 = state.next_match().unwrap();
-        assert_eq!(m4.row, 0);
+        assert_eq!(m4.start.row, 0);
 
         // Prev should go back to third
         let m5 = state.prev_match().unwrap();
-        assert_eq!(m5.row, 2);
+        assert_eq!(m5.start.row, 2);
     }
 
     #[test]
-    fn test_regex_pattern() {
+    fn test_literal_mode_escapes_regex_metacharacters() {
         let mut state = SearchState::new();
-        state.set_pattern("te.*t").unwrap();
+        state.set_pattern("te.t").unwrap();
 
-        let lines = vec![
-            (0, "test text"),
-        ];
+        let lines = vec![(0, "te.t"), (1, "test")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        // Literal mode: "." means a literal dot, so only row 0 matches.
+        assert_eq!(state.match_count(), 1);
+        assert_eq!(state.current_match().unwrap().start.row, 0);
+    }
 
-        state.find_matches(lines.iter().map(|(r, t)| (*r, *t)));
+    #[test]
+    fn test_regex_mode_matches_wildcard() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+        state.set_pattern("te.*t").unwrap();
+
+        let lines = vec![(0, "test text")];
+        state.find_matches(rows_no_wrap(&lines), 0);
 
         // Should match the entire "test text" (greedy match)
         assert_eq!(state.match_count(), 1);
         let m = state.current_match().unwrap();
-        assert_eq!(m.cols.start, 0);
-        assert_eq!(m.cols.end, 9);
+        assert_eq!(m.start.col, 0);
+        assert_eq!(m.end.col, 9);
+    }
+
+    #[test]
+    fn test_invalid_regex_sets_error_without_keeping_stale_matches() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+
+        assert!(state.set_pattern("(unclosed").is_err());
+        assert!(state.has_error());
+        assert_eq!(state.match_count(), 0);
     }
 
     #[test]
@@ -259,26 +1160,56 @@ mod tests {
         let mut state = SearchState::new();
         state.set_pattern("TEST").unwrap();
 
-        let lines = vec![
-            (0, "test Test TEST"),
-        ];
+        let lines = vec![(0, "test Test TEST")];
 
-        state.find_matches(lines.iter().map(|(r, t)| (*r, *t)));
+        state.find_matches(rows_no_wrap(&lines), 0);
 
         // Should match all three variations
         assert_eq!(state.match_count(), 3);
     }
 
+    #[test]
+    fn test_toggle_case_sensitive() {
+        let mut state = SearchState::new();
+        state.set_pattern("TEST").unwrap();
+
+        let lines = vec![(0, "test Test TEST")];
+
+        state.find_matches(rows_no_wrap(&lines), 0);
+        assert_eq!(state.match_count(), 3);
+
+        state.toggle_case_sensitive();
+        assert!(!state.case_insensitive());
+        state.find_matches(rows_no_wrap(&lines), 0);
+        assert_eq!(state.match_count(), 1);
+    }
+
+    #[test]
+    fn test_regex_mode_respects_case_sensitivity_toggle() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+        state.set_pattern(r"te\w*t").unwrap();
+
+        let lines = vec![(0, "Test test TEST")];
+
+        state.find_matches(rows_no_wrap(&lines), 0);
+        assert_eq!(state.match_count(), 3);
+
+        state.toggle_case_sensitive();
+        assert!(state.regex_mode());
+        assert!(!state.case_insensitive());
+        state.find_matches(rows_no_wrap(&lines), 0);
+        assert_eq!(state.match_count(), 1);
+    }
+
     #[test]
     fn test_is_match() {
         let mut state = SearchState::new();
         state.set_pattern("test").unwrap();
 
-        let lines = vec![
-            (0, "this is a test"),
-        ];
+        let lines = vec![(0, "this is a test")];
 
-        state.find_matches(lines.iter().map(|(r, t)| (*r, *t)));
+        state.find_matches(rows_no_wrap(&lines), 0);
 
         // "test" starts at column 10
         assert!(state.is_match(0, 10));
@@ -288,4 +1219,303 @@ mod tests {
         assert!(!state.is_match(0, 9));
         assert!(!state.is_match(0, 14));
     }
+
+    #[test]
+    fn test_match_spans_wrapped_lines() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+        state.set_pattern("fooba").unwrap();
+
+        // "foo" wraps into "bar baz" as one logical line "foobar baz".
+        let lines = vec![(0, "foo", true), (1, "bar baz", false)];
+        state.find_matches(lines, 0);
+
+        assert_eq!(state.match_count(), 1);
+        let m = state.current_match().unwrap();
+        assert_eq!(m.start, Point { row: 0, col: 0 });
+        assert_eq!(m.end, Point { row: 1, col: 2 });
+        assert!(state.is_match(0, 0));
+        assert!(state.is_match(1, 1));
+        assert!(!state.is_match(1, 2));
+    }
+
+    #[test]
+    fn test_regex_match_spans_separate_logical_lines() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+        state.set_pattern(r"bar\nbaz").unwrap();
+
+        // Two independent (non-wrapped) rows joined by the multi-line
+        // buffer's `\n` separator - the match crosses that join point and
+        // must come back as one `SearchMatch` per row it touches.
+        let lines = vec![(0, "foo bar"), (1, "baz qux")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        assert_eq!(state.match_count(), 2);
+        assert_eq!(
+            state.current_match().unwrap(),
+            &SearchMatch {
+                start: Point { row: 0, col: 4 },
+                end: Point { row: 0, col: 7 },
+                ..Default::default()
+            }
+        );
+        let second = state.next_match().unwrap();
+        assert_eq!(second.start, Point { row: 1, col: 0 });
+        assert_eq!(second.end, Point { row: 1, col: 3 });
+        assert!(state.is_match(0, 4));
+        assert!(state.is_match(1, 0));
+        assert!(!state.is_match(1, 3));
+    }
+
+    #[test]
+    fn test_multi_line_flag_anchors_per_row() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+        state.set_pattern("^foo").unwrap();
+
+        // Without `(?m)`, `^` would only anchor at the very start of the
+        // joined buffer and row 1 would never match.
+        let lines = vec![(0, "foo bar"), (1, "foo baz")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        assert_eq!(state.match_count(), 2);
+    }
+
+    #[test]
+    fn test_select_nearest_to_anchor_row() {
+        let mut state = SearchState::new();
+        state.set_pattern("test").unwrap();
+
+        let lines = vec![(0, "test"), (5, "test"), (10, "test")];
+        state.find_matches(rows_no_wrap(&lines), 6);
+
+        // Row 5's match is nearest to anchor row 6, not the first in
+        // document order.
+        assert_eq!(state.current_match().unwrap().start.row, 5);
+    }
+
+    #[test]
+    fn test_current_match_as_selection_converts_exclusive_end_to_inclusive() {
+        let mut state = SearchState::new();
+        state.set_pattern("test").unwrap();
+
+        let lines = vec![(0, "a test line")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        let region = state.current_match_as_selection().unwrap();
+        assert_eq!(region.start, Cursor::new(0, 2));
+        // SearchMatch::end is exclusive (6), SelectionRegion::end is inclusive (5).
+        assert_eq!(region.end, Cursor::new(0, 5));
+    }
+
+    #[test]
+    fn test_current_match_as_selection_none_without_a_match() {
+        let state = SearchState::new();
+        assert!(state.current_match_as_selection().is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_rejects_non_subsequence() {
+        assert!(fuzzy_match("xyz", "hello world", true).is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_accepts_subsequence() {
+        let (_, positions) = fuzzy_match("hlo", "hello", true).unwrap();
+        assert_eq!(positions, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_fuzzy_match_scores_consecutive_higher_than_scattered() {
+        let (tight, _) = fuzzy_match("foo", "xfooz", true).unwrap();
+        let (scattered, _) = fuzzy_match("foo", "f.o.o", true).unwrap();
+        assert!(tight > scattered);
+    }
+
+    #[test]
+    fn test_fuzzy_match_scores_boundary_hit_higher() {
+        let (boundary, _) = fuzzy_match("bar", "foo_bar", true).unwrap();
+        let (mid, _) = fuzzy_match("bar", "foobar!!", true).unwrap();
+        assert!(boundary > mid);
+    }
+
+    #[test]
+    fn test_toggle_mode() {
+        let mut state = SearchState::new();
+        assert_eq!(state.mode(), SearchMode::Regex);
+        state.toggle_mode();
+        assert_eq!(state.mode(), SearchMode::Fuzzy);
+        state.toggle_mode();
+        assert_eq!(state.mode(), SearchMode::Regex);
+    }
+
+    #[test]
+    fn test_fuzzy_find_matches_exposes_matched_positions_and_score() {
+        let mut state = SearchState::new();
+        state.toggle_mode();
+        state.set_pattern("tst").unwrap();
+
+        let lines = vec![(0, "a test string")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        assert_eq!(state.match_count(), 1);
+        let m = state.current_match().unwrap();
+        assert_eq!(
+            m.matched,
+            vec![
+                Point { row: 0, col: 2 },
+                Point { row: 0, col: 4 },
+                Point { row: 0, col: 5 },
+            ]
+        );
+        assert!(m.score > 0);
+        assert!(state.is_match(0, 2));
+        assert!(!state.is_match(0, 3));
+        assert!(state.is_match(0, 4));
+        assert!(state.is_match(0, 5));
+    }
+
+    #[test]
+    fn test_fuzzy_find_matches_skips_non_subsequence_lines() {
+        let mut state = SearchState::new();
+        state.toggle_mode();
+        state.set_pattern("xyz").unwrap();
+
+        let lines = vec![(0, "no match here")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        assert_eq!(state.match_count(), 0);
+    }
+
+    #[test]
+    fn test_fuzzy_next_match_iterates_in_descending_score_order() {
+        let mut state = SearchState::new();
+        state.toggle_mode();
+        state.set_pattern("ab").unwrap();
+
+        // Row 0 is a tight, boundary-aligned match (higher score); row 1
+        // scatters "a" and "b" across separators (lower score).
+        let lines = vec![(0, "ab"), (1, "a.x.b")];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        let first = state.current_match().unwrap();
+        assert_eq!(first.start.row, 0);
+        let first_score = first.score;
+        let second = state.next_match().unwrap();
+        assert_eq!(second.start.row, 1);
+        assert!(second.score <= first_score);
+    }
+
+    #[test]
+    fn test_multi_term_literal_query_requires_all_terms_on_the_line() {
+        let mut state = SearchState::new();
+        state.set_pattern("foo bar").unwrap();
+        assert_eq!(
+            state.literal_terms,
+            Some(vec!["foo".to_string(), "bar".to_string()])
+        );
+
+        let lines = vec![
+            (0, "foo and bar together"),
+            (1, "just foo here"),
+            (2, "only bar shows up"),
+        ];
+        state.find_matches(rows_no_wrap(&lines), 0);
+
+        // Rows 1 and 2 each contain one of the two terms but not both, so
+        // "all of these words" semantics rule them out entirely even though
+        // a plain substring search for either term alone would hit them.
+        assert_eq!(state.match_count(), 2);
+        assert!(state.is_match(0, 0));
+        assert!(state.is_match(0, 8));
+        assert!(!state.is_match(1, 5));
+        assert!(!state.is_match(2, 5));
+    }
+
+    #[test]
+    fn test_literal_fast_path_skipped_for_regex_metacharacters() {
+        let mut state = SearchState::new();
+        state.toggle_regex_mode();
+        state.set_pattern("te.t").unwrap();
+
+        // "te.t" has a regex metacharacter, so it compiles as a real regex
+        // rather than taking the `AhoCorasick` fast path.
+        assert!(state.literal_terms.is_none());
+        assert!(state.pattern.is_some());
+    }
+
+    fn rows_owned(lines: &[(usize, &str)]) -> Vec<(usize, String, bool)> {
+        lines
+            .iter()
+            .map(|(r, t)| (*r, t.to_string(), false))
+            .collect()
+    }
+
+    /// Polls until the background scan finishes or `max_polls` ticks pass,
+    /// whichever comes first - the worker thread runs concurrently, so a
+    /// single `poll()` isn't guaranteed to observe its `Done` yet.
+    fn poll_until_done(state: &mut SearchState, max_polls: usize) {
+        for _ in 0..max_polls {
+            state.poll();
+            if !state.searching() {
+                return;
+            }
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_start_search_streams_matches_incrementally() {
+        let mut state = SearchState::new();
+        state.set_pattern("test").unwrap();
+
+        let lines = vec![
+            (0, "this is a test string"),
+            (1, "another test"),
+            (2, "no match here"),
+        ];
+        state.start_search(rows_owned(&lines), 0);
+        assert!(state.searching());
+
+        poll_until_done(&mut state, 200);
+
+        assert!(!state.searching());
+        assert_eq!(state.match_count(), 2);
+        assert_eq!(state.current_match_number(), Some(1));
+    }
+
+    #[test]
+    fn test_cancel_discards_in_flight_results() {
+        let mut state = SearchState::new();
+        state.set_pattern("test").unwrap();
+
+        let lines = vec![(0, "test 1"), (1, "test 2")];
+        state.start_search(rows_owned(&lines), 0);
+        state.cancel();
+
+        // Give the worker a moment to notice the generation bump, then
+        // confirm none of its results (if it got that far) landed.
+        thread::sleep(std::time::Duration::from_millis(20));
+        state.poll();
+
+        assert!(!state.searching());
+        assert_eq!(state.match_count(), 0);
+    }
+
+    #[test]
+    fn test_start_search_superseded_by_a_second_search_is_discarded() {
+        let mut state = SearchState::new();
+        state.set_pattern("test").unwrap();
+        state.start_search(rows_owned(&[(0, "test")]), 0);
+
+        state.set_pattern("other").unwrap();
+        state.start_search(rows_owned(&[(0, "other line")]), 0);
+
+        poll_until_done(&mut state, 200);
+
+        assert!(!state.searching());
+        assert_eq!(state.match_count(), 1);
+        assert_eq!(state.current_match().unwrap().start.row, 0);
+    }
 }
@@ -0,0 +1,512 @@
+//! Search-and-replace templates and replay for `SearchState` matches.
+//!
+//! Modeled on rust-analyzer's SSR replace flow: a template is parsed once up
+//! front (`ReplaceTemplate::parse`) and validated against the search
+//! pattern's capture groups (`ReplaceTemplate::validate`), so a malformed
+//! `$1`/`${name}` reference surfaces immediately as a structured error
+//! instead of silently dropping text mid-replace. `ReplaceState` pairs a
+//! template with the replace query string the UI edits and knows how to
+//! turn already-found `SearchMatch` spans of a line of text into their
+//! replacement, for both a preview and a committed replace.
+//!
+//! This module only transforms text - it has no opinion on where that text
+//! came from. A caller replacing text on the terminal's current editable
+//! command line computes the new line, diffs it against the old one with
+//! `pty_rewrite_sequence`, and sends the result through
+//! `PtySession::write`/`PtyWriter::write`; a caller exporting scrollback
+//! just keeps the returned `String`.
+
+use regex::{Captures, Regex};
+use thiserror::Error;
+
+use super::SearchMatch;
+
+/// Errors `ReplaceTemplate::parse`/`validate` can return.
+#[derive(Error, Debug)]
+pub enum ReplaceError {
+    #[error("'$' at the end of the template must be followed by a group reference")]
+    DanglingDollar,
+    #[error("'${{' is missing its closing '}}'")]
+    UnterminatedGroup,
+    #[error("'${{}}' does not name a capture group")]
+    EmptyGroupName,
+    #[error("template references group ${0}, which the pattern doesn't have")]
+    UnknownIndex(usize),
+    #[error("template references group ${{{0}}}, which the pattern doesn't have")]
+    UnknownName(String),
+}
+
+/// One piece of a parsed `ReplaceTemplate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateSegment {
+    Literal(String),
+    Group(CaptureRef),
+}
+
+/// A `$1`/`${2}`/`${name}` reference within a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CaptureRef {
+    Index(usize),
+    Name(String),
+}
+
+/// A replace template parsed into literal text and capture-group
+/// references, e.g. `"$1-${name}"`. `$$` escapes a literal `$`. Group 0
+/// (`$0`/`${0}`) always refers to the whole match, even for a literal or
+/// fuzzy `SearchMatch`, which has no other groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaceTemplate {
+    segments: Vec<TemplateSegment>,
+}
+
+impl ReplaceTemplate {
+    /// Parse `template`, without checking that any group it references
+    /// actually exists on a pattern - see `validate` for that.
+    pub fn parse(template: &str) -> Result<Self, ReplaceError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                literal.push(c);
+                continue;
+            }
+
+            match chars.peek().copied() {
+                Some('$') => {
+                    chars.next();
+                    literal.push('$');
+                }
+                Some('{') => {
+                    chars.next();
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c2 in chars.by_ref() {
+                        if c2 == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c2);
+                    }
+                    if !closed {
+                        return Err(ReplaceError::UnterminatedGroup);
+                    }
+                    if name.is_empty() {
+                        return Err(ReplaceError::EmptyGroupName);
+                    }
+                    if !literal.is_empty() {
+                        segments.push(TemplateSegment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(TemplateSegment::Group(Self::parse_ref(&name)));
+                }
+                Some(c2) if c2.is_ascii_digit() => {
+                    let mut digits = String::new();
+                    while let Some(c2) = chars.peek().copied() {
+                        if !c2.is_ascii_digit() {
+                            break;
+                        }
+                        digits.push(c2);
+                        chars.next();
+                    }
+                    if !literal.is_empty() {
+                        segments.push(TemplateSegment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(TemplateSegment::Group(CaptureRef::Index(
+                        digits.parse().expect("digits-only string parses as usize"),
+                    )));
+                }
+                _ => return Err(ReplaceError::DanglingDollar),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(TemplateSegment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    fn parse_ref(name: &str) -> CaptureRef {
+        match name.parse::<usize>() {
+            Ok(index) => CaptureRef::Index(index),
+            Err(_) => CaptureRef::Name(name.to_string()),
+        }
+    }
+
+    /// Check every group this template references exists on `pattern` -
+    /// `None` for a literal or fuzzy match, which has no groups beyond the
+    /// implicit whole-match group 0.
+    pub fn validate(&self, pattern: Option<&Regex>) -> Result<(), ReplaceError> {
+        for segment in &self.segments {
+            let TemplateSegment::Group(reference) = segment else {
+                continue;
+            };
+            match reference {
+                CaptureRef::Index(0) => {}
+                CaptureRef::Index(i) => match pattern {
+                    Some(pattern) if *i < pattern.captures_len() => {}
+                    _ => return Err(ReplaceError::UnknownIndex(*i)),
+                },
+                CaptureRef::Name(name) => match pattern {
+                    Some(pattern) if pattern.capture_names().flatten().any(|n| n == name) => {}
+                    _ => return Err(ReplaceError::UnknownName(name.clone())),
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Render this template against one matched span. `captures` is `Some`
+    /// for a regex match backed by a compiled `Regex`; `whole_match` is the
+    /// literal text the match spanned, used for group 0. Any other group
+    /// reference renders as empty text when there's no `Captures` to index
+    /// into (a literal or fuzzy match never has one) or when that group
+    /// didn't participate in the match.
+    pub fn render(&self, whole_match: &str, captures: Option<&Captures>) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                TemplateSegment::Literal(s) => out.push_str(s),
+                TemplateSegment::Group(CaptureRef::Index(0)) => out.push_str(whole_match),
+                TemplateSegment::Group(CaptureRef::Index(i)) => {
+                    if let Some(m) = captures.and_then(|c| c.get(*i)) {
+                        out.push_str(m.as_str());
+                    }
+                }
+                TemplateSegment::Group(CaptureRef::Name(name)) => {
+                    if let Some(m) = captures.and_then(|c| c.name(name)) {
+                        out.push_str(m.as_str());
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// One match's before/after text, returned by `ReplaceState::preview` so the
+/// UI can show what a replace would do before committing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacePreview {
+    pub before: String,
+    pub after: String,
+}
+
+/// Search-and-replace state paired with a `SearchState`: holds the replace
+/// template text the UI edits and turns `SearchMatch` spans of a line of
+/// text into their replacement. Operates on one already-joined logical line
+/// at a time (the same unit `SearchState::find_matches` scans) - a match's
+/// `start.col`/`end.col` are treated as byte offsets into that line, the
+/// same convention `SearchMatch` uses everywhere else in this module.
+#[derive(Debug, Default)]
+pub struct ReplaceState {
+    /// Replace query string the UI edits, mirroring `SearchState::query`.
+    pub replace_query: String,
+    template: Option<ReplaceTemplate>,
+    /// Set when `replace_query` failed to parse or validate, so the UI can
+    /// render it in an error color the same way `SearchState::has_error`
+    /// does for the search query.
+    error: bool,
+}
+
+impl ReplaceState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the current `replace_query` failed to parse or validate.
+    pub fn has_error(&self) -> bool {
+        self.error
+    }
+
+    /// Parse and validate `replace_query` as a template against `pattern`
+    /// (the compiled regex behind the current search, or `None` for a
+    /// literal/fuzzy query, which has no capture groups beyond group 0).
+    pub fn set_template(
+        &mut self,
+        replace_query: &str,
+        pattern: Option<&Regex>,
+    ) -> Result<(), ReplaceError> {
+        self.replace_query = replace_query.to_string();
+        match ReplaceTemplate::parse(replace_query).and_then(|template| {
+            template.validate(pattern)?;
+            Ok(template)
+        }) {
+            Ok(template) => {
+                self.template = Some(template);
+                self.error = false;
+                Ok(())
+            }
+            Err(e) => {
+                self.template = None;
+                self.error = true;
+                Err(e)
+            }
+        }
+    }
+
+    /// Render the replacement for one already-matched span of text.
+    /// `pattern` re-derives capture groups by re-running the regex against
+    /// `matched_text` - `SearchMatch` only stores the span, not the
+    /// `Captures` the original scan produced.
+    fn render_match(&self, matched_text: &str, pattern: Option<&Regex>) -> Option<String> {
+        let template = self.template.as_ref()?;
+        let captures = pattern.and_then(|p| p.captures(matched_text));
+        Some(template.render(matched_text, captures.as_ref()))
+    }
+
+    /// Preview what replacing each of `matches` within `line` would produce,
+    /// without committing it. Out-of-range matches (a stale `SearchMatch`
+    /// against text that has since changed) are skipped rather than
+    /// panicking.
+    pub fn preview(
+        &self,
+        line: &str,
+        matches: &[SearchMatch],
+        pattern: Option<&Regex>,
+    ) -> Vec<ReplacePreview> {
+        matches
+            .iter()
+            .filter_map(|m| {
+                let before = line.get(m.start.col..m.end.col)?.to_string();
+                let after = self.render_match(&before, pattern)?;
+                Some(ReplacePreview { before, after })
+            })
+            .collect()
+    }
+
+    /// Replace a single match within `line`, returning the new line, or
+    /// `None` if there's no valid template or the match no longer lines up
+    /// with `line`.
+    pub fn replace_one(
+        &self,
+        line: &str,
+        m: &SearchMatch,
+        pattern: Option<&Regex>,
+    ) -> Option<String> {
+        let matched_text = line.get(m.start.col..m.end.col)?;
+        let replacement = self.render_match(matched_text, pattern)?;
+        let mut out = String::with_capacity(line.len());
+        out.push_str(&line[..m.start.col]);
+        out.push_str(&replacement);
+        out.push_str(&line[m.end.col..]);
+        Some(out)
+    }
+
+    /// Replace every match within `line`, returning the new line. Matches
+    /// are assumed to be in ascending, non-overlapping order (as
+    /// `SearchState::matches` always is); an out-of-range or
+    /// out-of-order match stops the replace early and returns `None` rather
+    /// than silently mangling the line.
+    pub fn replace_all(
+        &self,
+        line: &str,
+        matches: &[SearchMatch],
+        pattern: Option<&Regex>,
+    ) -> Option<String> {
+        let mut out = String::with_capacity(line.len());
+        let mut cursor = 0;
+        for m in matches {
+            if m.start.col < cursor || m.end.col > line.len() {
+                return None;
+            }
+            let matched_text = &line[m.start.col..m.end.col];
+            let replacement = self.render_match(matched_text, pattern)?;
+            out.push_str(&line[cursor..m.start.col]);
+            out.push_str(&replacement);
+            cursor = m.end.col;
+        }
+        out.push_str(&line[cursor..]);
+        Some(out)
+    }
+}
+
+/// Compute the bytes to send through `PtySession::write`/`PtyWriter::write`
+/// to turn the terminal's current editable command line from `old` to `new`:
+/// one backspace (`0x08`) per character after the point where the two
+/// strings diverge, followed by whatever of `new` comes after that point.
+/// Like every other PTY-input helper in this codebase, this relies on the
+/// shell's own line editor (readline/ZLE/etc.) erasing on backspace and
+/// echoing typed characters back - it doesn't inspect or track the shell's
+/// notion of the line itself.
+pub fn pty_rewrite_sequence(old: &str, new: &str) -> Vec<u8> {
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+    let common = old_chars
+        .iter()
+        .zip(new_chars.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut bytes = vec![0x08u8; old_chars.len() - common];
+    let mut buf = [0u8; 4];
+    for c in &new_chars[common..] {
+        bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Point;
+
+    fn make_match(start: usize, end: usize) -> SearchMatch {
+        SearchMatch {
+            start: Point { row: 0, col: start },
+            end: Point { row: 0, col: end },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_literal_template() {
+        let template = ReplaceTemplate::parse("no groups here").unwrap();
+        assert_eq!(template.render("whatever", None), "no groups here");
+    }
+
+    #[test]
+    fn test_parse_dollar_escape() {
+        let template = ReplaceTemplate::parse("$$5").unwrap();
+        assert_eq!(template.render("whatever", None), "$5");
+    }
+
+    #[test]
+    fn test_parse_dangling_dollar_errors() {
+        assert!(matches!(
+            ReplaceTemplate::parse("cost: $"),
+            Err(ReplaceError::DanglingDollar)
+        ));
+    }
+
+    #[test]
+    fn test_parse_unterminated_group_errors() {
+        assert!(matches!(
+            ReplaceTemplate::parse("${name"),
+            Err(ReplaceError::UnterminatedGroup)
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty_group_name_errors() {
+        assert!(matches!(
+            ReplaceTemplate::parse("${}"),
+            Err(ReplaceError::EmptyGroupName)
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_index() {
+        let template = ReplaceTemplate::parse("$2").unwrap();
+        let pattern = Regex::new(r"(\w+)").unwrap();
+        assert!(matches!(
+            template.validate(Some(&pattern)),
+            Err(ReplaceError::UnknownIndex(2))
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_name() {
+        let template = ReplaceTemplate::parse("${missing}").unwrap();
+        let pattern = Regex::new(r"(?P<first>\w+)").unwrap();
+        assert!(matches!(
+            template.validate(Some(&pattern)),
+            Err(ReplaceError::UnknownName(ref name)) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_any_group_without_a_pattern() {
+        let template = ReplaceTemplate::parse("$1").unwrap();
+        assert!(template.validate(None).is_err());
+    }
+
+    #[test]
+    fn test_validate_accepts_group_zero_without_a_pattern() {
+        let template = ReplaceTemplate::parse("[$0]").unwrap();
+        assert!(template.validate(None).is_ok());
+    }
+
+    #[test]
+    fn test_render_indexed_and_named_groups() {
+        let pattern = Regex::new(r"(?P<first>\w+)@(\w+)").unwrap();
+        let caps = pattern.captures("user@host").unwrap();
+        let template = ReplaceTemplate::parse("${first} at $2").unwrap();
+        assert_eq!(template.render("user@host", Some(&caps)), "user at host");
+    }
+
+    #[test]
+    fn test_replace_state_set_template_tracks_error() {
+        let mut state = ReplaceState::new();
+        let pattern = Regex::new(r"(\w+)").unwrap();
+        assert!(state.set_template("$5", Some(&pattern)).is_err());
+        assert!(state.has_error());
+
+        assert!(state.set_template("$1!", Some(&pattern)).is_ok());
+        assert!(!state.has_error());
+    }
+
+    #[test]
+    fn test_replace_all_with_capture_groups() {
+        let mut state = ReplaceState::new();
+        let pattern = Regex::new(r"(\w+)@(\w+)").unwrap();
+        state.set_template("$2:$1", Some(&pattern)).unwrap();
+
+        let line = "user@host and admin@server";
+        let matches = vec![make_match(0, 9), make_match(14, 26)];
+
+        let replaced = state.replace_all(line, &matches, Some(&pattern)).unwrap();
+        assert_eq!(replaced, "host:user and server:admin");
+    }
+
+    #[test]
+    fn test_replace_one_leaves_other_matches_untouched() {
+        let mut state = ReplaceState::new();
+        state.set_template("X", None).unwrap();
+
+        let line = "foo foo foo";
+        let m = make_match(4, 7);
+        let replaced = state.replace_one(line, &m, None).unwrap();
+        assert_eq!(replaced, "foo X foo");
+    }
+
+    #[test]
+    fn test_preview_shows_before_and_after() {
+        let mut state = ReplaceState::new();
+        state.set_template("bar", None).unwrap();
+
+        let line = "foo baz";
+        let matches = vec![make_match(0, 3)];
+        let previews = state.preview(line, &matches, None);
+
+        assert_eq!(
+            previews,
+            vec![ReplacePreview {
+                before: "foo".to_string(),
+                after: "bar".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_replace_all_rejects_out_of_order_matches() {
+        let mut state = ReplaceState::new();
+        state.set_template("X", None).unwrap();
+        let matches = vec![make_match(5, 8), make_match(0, 3)];
+        assert!(state.replace_all("foo bar ba", &matches, None).is_none());
+    }
+
+    #[test]
+    fn test_pty_rewrite_sequence_backspaces_to_common_prefix() {
+        let bytes = pty_rewrite_sequence("git stats", "git status");
+        // Diverges after "git stat": one backspace for the trailing "s" in
+        // "stats", then "us" to turn it into "status".
+        assert_eq!(bytes, b"\x08us");
+    }
+
+    #[test]
+    fn test_pty_rewrite_sequence_identical_strings_is_empty() {
+        assert!(pty_rewrite_sequence("same", "same").is_empty());
+    }
+}
@@ -0,0 +1,389 @@
+//! Searchable, parameterized command templates ("workflows")
+//!
+//! Modeled on Warp's workflows catalog: a `Workflow` pairs a command
+//! template containing `{{argument}}` placeholders with metadata (name,
+//! description, tags) the command palette can fuzzy-search. Once the user
+//! picks one, `Workflow::missing_arguments`/`substitute` drive filling in
+//! each placeholder (falling back to its default) before the finished
+//! command is inserted into the terminal input - see `ui::workflow_prompt`
+//! for the fill-in state machine built on top of this.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// One named, substitutable argument in a workflow's `command` template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default_value: Option<String>,
+}
+
+/// A searchable, parameterized command template, loaded from a YAML file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workflow {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Shell command containing zero or more `{{argument_name}}`
+    /// placeholders, one per entry in `arguments`.
+    pub command: String,
+    #[serde(default)]
+    pub arguments: Vec<WorkflowArgument>,
+}
+
+fn placeholder_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z0-9_]+)\s*\}\}").unwrap())
+}
+
+impl Workflow {
+    /// Every `{{name}}` placeholder referenced by `command`, in the order
+    /// they first appear.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut seen = Vec::new();
+        for cap in placeholder_pattern().captures_iter(&self.command) {
+            let name = cap[1].to_string();
+            if !seen.contains(&name) {
+                seen.push(name);
+            }
+        }
+        seen
+    }
+
+    /// Validate that every placeholder in `command` has a matching
+    /// `arguments` entry and vice versa, so a typo'd placeholder or an
+    /// unused argument is caught at load time rather than surfacing as a
+    /// silently-unsubstituted `{{...}}` in the final command.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("workflow is missing a name");
+        }
+        if self.command.trim().is_empty() {
+            anyhow::bail!("workflow {:?} has an empty command", self.name);
+        }
+
+        let placeholders = self.placeholders();
+        for placeholder in &placeholders {
+            if !self.arguments.iter().any(|arg| &arg.name == placeholder) {
+                anyhow::bail!(
+                    "workflow {:?} references {{{{{}}}}} with no matching argument entry",
+                    self.name,
+                    placeholder
+                );
+            }
+        }
+        for arg in &self.arguments {
+            if !placeholders.contains(&arg.name) {
+                anyhow::bail!(
+                    "workflow {:?} declares unused argument {:?}",
+                    self.name,
+                    arg.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Arguments with no entry in `values` and no default - the prompt must
+    /// collect these before `substitute` can produce a complete command.
+    pub fn missing_arguments<'a>(&'a self, values: &HashMap<String, String>) -> Vec<&'a WorkflowArgument> {
+        self.arguments
+            .iter()
+            .filter(|arg| !values.contains_key(&arg.name) && arg.default_value.is_none())
+            .collect()
+    }
+
+    /// Substitute every `{{name}}` placeholder with its value from `values`,
+    /// falling back to the argument's default, single-quote-escaped for
+    /// safe insertion into a shell command. Errors if an argument has
+    /// neither a supplied value nor a default.
+    pub fn substitute(&self, values: &HashMap<String, String>) -> Result<String> {
+        let mut quoted: HashMap<&str, String> = HashMap::new();
+        for arg in &self.arguments {
+            let value = values
+                .get(&arg.name)
+                .or(arg.default_value.as_ref())
+                .with_context(|| format!("missing value for argument {:?}", arg.name))?;
+            quoted.insert(arg.name.as_str(), shell_quote(value));
+        }
+        let result = placeholder_pattern().replace_all(&self.command, |cap: &regex::Captures| {
+            quoted.get(&cap[1]).cloned().unwrap_or_else(|| cap[0].to_string())
+        });
+        Ok(result.into_owned())
+    }
+}
+
+/// Wrap `value` in single quotes, escaping any embedded single quote as
+/// `'\''` (close the quote, emit an escaped quote, reopen) - the standard
+/// POSIX-shell-safe quoting trick, so a substituted argument can never be
+/// interpreted as additional shell syntax.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Result of loading a directory of workflow files: the ones that parsed
+/// and validated, plus `(path, message)` for any that didn't - malformed
+/// files are reported rather than silently dropped.
+#[derive(Debug, Default)]
+pub struct LoadResult {
+    pub workflows: Vec<Workflow>,
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// Parse and validate every `*.yaml`/`*.yml` file directly inside `dir`.
+/// A missing directory yields an empty, error-free result - there's
+/// nothing wrong with a user who has never created custom workflows.
+pub fn load_dir(dir: &Path) -> Result<LoadResult> {
+    let mut result = LoadResult::default();
+    if !dir.is_dir() {
+        return Ok(result);
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read workflows directory: {:?}", dir))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml") | Some("yml")))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match load_file(&path) {
+            Ok(workflow) => result.workflows.push(workflow),
+            Err(e) => result.errors.push((path, e.to_string())),
+        }
+    }
+    Ok(result)
+}
+
+fn load_file(path: &Path) -> Result<Workflow> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let workflow: Workflow =
+        serde_yaml::from_str(&contents).with_context(|| format!("Failed to parse {:?}", path))?;
+    workflow.validate().with_context(|| format!("{:?} failed validation", path))?;
+    Ok(workflow)
+}
+
+/// The user's workflows directory (`~/.config/warp-foss/workflows/`),
+/// honoring `$XDG_CONFIG_HOME` the same way `config::Config` does.
+pub fn user_workflows_dir() -> Result<PathBuf> {
+    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+        return Ok(PathBuf::from(xdg_config).join("warp-foss").join("workflows"));
+    }
+
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home.join(".config").join("warp-foss").join("workflows"))
+}
+
+/// A small built-in catalog so the palette isn't empty before the user has
+/// dropped any workflows of their own into `user_workflows_dir`.
+pub fn default_workflows() -> Vec<Workflow> {
+    vec![
+        Workflow {
+            name: "Find large files".to_string(),
+            description: "List the largest files under a directory".to_string(),
+            tags: vec!["disk".to_string(), "files".to_string()],
+            command: "find {{directory}} -type f -exec du -h {} + | sort -rh | head -n {{count}}".to_string(),
+            arguments: vec![
+                WorkflowArgument {
+                    name: "directory".to_string(),
+                    description: Some("Directory to search".to_string()),
+                    default_value: Some(".".to_string()),
+                },
+                WorkflowArgument {
+                    name: "count".to_string(),
+                    description: Some("Number of files to show".to_string()),
+                    default_value: Some("10".to_string()),
+                },
+            ],
+        },
+        Workflow {
+            name: "Docker prune".to_string(),
+            description: "Remove stopped containers, dangling images, and unused networks".to_string(),
+            tags: vec!["docker".to_string()],
+            command: "docker system prune -f".to_string(),
+            arguments: vec![],
+        },
+    ]
+}
+
+/// Load the built-in catalog plus everything in `user_workflows_dir`, the
+/// combination the command palette indexes.
+pub fn load_all() -> Result<LoadResult> {
+    let mut result = LoadResult { workflows: default_workflows(), errors: Vec::new() };
+    let user_dir = user_workflows_dir()?;
+    let user_result = load_dir(&user_dir)?;
+    result.workflows.extend(user_result.workflows);
+    result.errors.extend(user_result.errors);
+    Ok(result)
+}
+
+/// Whether `query`'s whitespace-separated keywords all appear, in order, as
+/// a case-insensitive substring somewhere across `workflow`'s name and tags
+/// - the same ordered-keyword rule `frecency` uses, so a query like
+/// "dock prune" matches a "Docker prune" workflow tagged `docker`.
+fn matches_query(workflow: &Workflow, keywords: &[&str]) -> bool {
+    if keywords.is_empty() {
+        return true;
+    }
+
+    let haystack = format!("{} {}", workflow.name, workflow.tags.join(" ")).to_lowercase();
+    let mut cursor = 0;
+    for keyword in keywords {
+        let keyword = keyword.to_lowercase();
+        match haystack[cursor..].find(&keyword) {
+            Some(offset) => cursor += offset + keyword.len(),
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Fuzzy-search `workflows` by name/tag against `query`, preserving input
+/// order among equally-matching entries (there's no frecency-style signal
+/// to rank workflows by yet, unlike `frecency::Database::query_all`).
+/// An empty query matches everything, so the palette can list the full
+/// catalog before the user has typed anything.
+pub fn search<'a>(workflows: &'a [Workflow], query: &str) -> Vec<&'a Workflow> {
+    let keywords: Vec<&str> = query.split_whitespace().collect();
+    workflows.iter().filter(|w| matches_query(w, &keywords)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Workflow {
+        Workflow {
+            name: "Find large files".to_string(),
+            description: "List the largest files".to_string(),
+            tags: vec!["disk".to_string()],
+            command: "find {{directory}} -size +{{size}} ".to_string(),
+            arguments: vec![
+                WorkflowArgument {
+                    name: "directory".to_string(),
+                    description: None,
+                    default_value: Some(".".to_string()),
+                },
+                WorkflowArgument { name: "size".to_string(), description: None, default_value: None },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_placeholders_lists_names_in_order_of_first_appearance() {
+        let workflow = sample();
+        assert_eq!(workflow.placeholders(), vec!["directory", "size"]);
+    }
+
+    #[test]
+    fn test_validate_rejects_placeholder_with_no_argument_entry() {
+        let mut workflow = sample();
+        workflow.command = "find {{directory}} -size +{{size}} -name {{pattern}}".to_string();
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_unused_argument() {
+        let mut workflow = sample();
+        workflow.arguments.push(WorkflowArgument {
+            name: "unused".to_string(),
+            description: None,
+            default_value: None,
+        });
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_accepts_well_formed_workflow() {
+        assert!(sample().validate().is_ok());
+    }
+
+    #[test]
+    fn test_missing_arguments_excludes_ones_with_defaults_or_supplied_values() {
+        let workflow = sample();
+        let mut values = HashMap::new();
+        assert_eq!(
+            workflow.missing_arguments(&values).iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+            vec!["size"]
+        );
+
+        values.insert("size".to_string(), "1G".to_string());
+        assert!(workflow.missing_arguments(&values).is_empty());
+    }
+
+    #[test]
+    fn test_substitute_fills_defaults_and_supplied_values() {
+        let workflow = sample();
+        let mut values = HashMap::new();
+        values.insert("size".to_string(), "1G".to_string());
+
+        let command = workflow.substitute(&values).unwrap();
+        assert_eq!(command, "find '.' -size +'1G' ");
+    }
+
+    #[test]
+    fn test_substitute_escapes_embedded_single_quotes() {
+        let workflow = sample();
+        let mut values = HashMap::new();
+        values.insert("size".to_string(), "1'G".to_string());
+
+        let command = workflow.substitute(&values).unwrap();
+        assert!(command.contains(r"'1'\''G'"));
+    }
+
+    #[test]
+    fn test_substitute_errors_when_a_required_argument_is_missing() {
+        let workflow = sample();
+        assert!(workflow.substitute(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_search_matches_ordered_keywords_across_name_and_tags() {
+        let workflows = vec![sample()];
+        assert_eq!(search(&workflows, "find disk").len(), 1);
+        assert_eq!(search(&workflows, "disk find").len(), 0);
+        assert_eq!(search(&workflows, "nonexistent").len(), 0);
+        assert_eq!(search(&workflows, "").len(), 1);
+    }
+
+    #[test]
+    fn test_load_dir_reports_malformed_files_without_failing_the_whole_load() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-workflows-load-dir-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+
+        std::fs::write(
+            scratch.join("good.yaml"),
+            "name: Good\ndescription: A good workflow\ncommand: echo hi\n",
+        )
+        .unwrap();
+        std::fs::write(scratch.join("bad.yaml"), "not: [valid, workflow").unwrap();
+
+        let result = load_dir(&scratch).unwrap();
+        std::fs::remove_dir_all(&scratch).ok();
+
+        assert_eq!(result.workflows.len(), 1);
+        assert_eq!(result.workflows[0].name, "Good");
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_load_dir_on_missing_directory_is_empty_not_an_error() {
+        let result = load_dir(Path::new("/nonexistent/warp-foss-workflows-dir")).unwrap();
+        assert!(result.workflows.is_empty());
+        assert!(result.errors.is_empty());
+    }
+}
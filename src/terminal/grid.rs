@@ -3,9 +3,16 @@
 //! This module provides a 2D grid representation of terminal content,
 //! including character data, colors, text attributes, and scrollback history.
 
+use std::collections::BTreeSet;
 use std::fmt;
+use std::sync::Arc;
 
-use super::parser::{Color, TextAttributes, TerminalOutput};
+use regex::Regex;
+use thiserror::Error;
+use unicode_width::UnicodeWidthChar;
+
+use super::parser::{Color, Hyperlink, Palette, TextAttributes, TerminalOutput, UnderlineStyle};
+use super::sixel::SixelImage;
 
 /// A single cell in the terminal grid.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,6 +25,10 @@ pub struct Cell {
     pub bg_color: Color,
     /// Text attributes (bold, underline, etc.).
     pub attributes: TextAttributes,
+    /// The OSC 8 hyperlink open when this cell was written, if any. Shared
+    /// via `Arc` across every cell in the same link run - see
+    /// `terminal::parser::Hyperlink`.
+    pub hyperlink: Option<Arc<Hyperlink>>,
 }
 
 impl Default for Cell {
@@ -27,6 +38,7 @@ impl Default for Cell {
             fg_color: Color::Default,
             bg_color: Color::Default,
             attributes: TextAttributes::default(),
+            hyperlink: None,
         }
     }
 }
@@ -46,7 +58,7 @@ impl Cell {
             char,
             fg_color,
             bg_color,
-            attributes: TextAttributes::default(),
+            ..Self::default()
         }
     }
 
@@ -62,15 +74,18 @@ impl Cell {
             fg_color,
             bg_color,
             attributes,
+            ..Self::default()
         }
     }
 
-    /// Check if this cell is empty (space with default colors and attributes).
+    /// Check if this cell is empty (space with default colors, attributes,
+    /// and no hyperlink).
     pub fn is_empty(&self) -> bool {
         self.char == ' '
             && self.fg_color == Color::Default
             && self.bg_color == Color::Default
             && self.attributes == TextAttributes::default()
+            && self.hyperlink.is_none()
     }
 
     /// Reset the cell to default state.
@@ -103,6 +118,183 @@ impl Cursor {
 /// A row in the scrollback buffer.
 type ScrollbackRow = Vec<Cell>;
 
+/// Ring-buffer storage for scrollback history.
+///
+/// Evicting the oldest line to make room for a new one is just advancing
+/// `zero` (mod capacity) and overwriting its old slot in place, rather than
+/// `Vec::remove(0)` shifting every later line down a slot - and the row
+/// scrolling off the live grid swaps its content directly into that slot
+/// instead of being cloned into a fresh one, so steady-state scrolling
+/// allocates nothing. `inner` only grows, via ordinary `Vec::push`, until
+/// history reaches its capacity; every push after that is a pure
+/// index/swap. Logical index 0 is always the oldest line, regardless of
+/// where `zero` currently points physically.
+#[derive(Debug, Clone)]
+struct Storage {
+    inner: Vec<ScrollbackRow>,
+    /// Physical index of logical line 0.
+    zero: usize,
+    /// Number of lines currently stored (<= `inner.len()`, which itself
+    /// never exceeds the capacity history was built with).
+    len: usize,
+}
+
+impl Storage {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(capacity),
+            zero: 0,
+            len: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+        self.zero = 0;
+        self.len = 0;
+    }
+
+    /// The line at logical index `index` (0 = oldest), or `None` if out of
+    /// range.
+    fn get(&self, index: usize) -> Option<&ScrollbackRow> {
+        if index >= self.len {
+            return None;
+        }
+        Some(&self.inner[(self.zero + index) % self.inner.len()])
+    }
+
+    /// Push `row`'s current content as the newest history line, and refill
+    /// `row` in place with `cols` cells of `blank` so the caller can reuse
+    /// it (typically as the grid's freshly scrolled-in row) without
+    /// allocating a new one. While there's still spare capacity this grows
+    /// `inner` by cloning `blank` into the replacement, the same as any
+    /// other new row; once full, `row` trades places directly with the
+    /// evicted oldest line's own buffer via `mem::swap`, so nothing is
+    /// cloned or reallocated and the line that falls out of history is
+    /// exactly the one handed back to the caller.
+    fn push(&mut self, row: &mut ScrollbackRow, capacity: usize, cols: usize, blank: &Cell) {
+        if capacity == 0 {
+            row.fill(blank.clone());
+            return;
+        }
+        if self.inner.len() < capacity {
+            let old = std::mem::replace(row, vec![blank.clone(); cols]);
+            self.inner.push(old);
+            self.len += 1;
+        } else {
+            let idx = self.zero;
+            std::mem::swap(&mut self.inner[idx], row);
+            self.zero = (self.zero + 1) % capacity;
+            row.resize(cols, blank.clone());
+            row.fill(blank.clone());
+        }
+    }
+}
+
+/// A request to page the viewport through scrollback history.
+///
+/// Modeled after Alacritty's `Scroll`: `TerminalGrid::scroll` consumes one
+/// of these and updates `display_offset` accordingly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scroll {
+    /// Scroll by a relative number of lines. Positive moves back into
+    /// history, negative moves forward toward the live screen.
+    Delta(isize),
+    /// Scroll back by one full screen height.
+    PageUp,
+    /// Scroll forward by one full screen height.
+    PageDown,
+    /// Jump to the oldest scrollback line.
+    Top,
+    /// Jump back to the live screen.
+    Bottom,
+}
+
+/// A snapshot of what changed since the last `take_damage()`/`reset_damage()`,
+/// as returned by `TerminalGrid::take_damage`.
+///
+/// `Lines` is cheap for a renderer to diff against its own cache; `Full` is
+/// returned instead of an exhaustive `Lines(0..rows)` whenever a mutation
+/// (scroll, clear, resize) conservatively invalidated the whole grid, so the
+/// consumer can skip the per-row bookkeeping and just repaint everything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Damage {
+    /// Every row should be considered changed.
+    Full,
+    /// Exactly these rows changed; anything else is unchanged.
+    Lines(Vec<usize>),
+}
+
+/// Decoded Sixel rasters anchored to the grid cell they were drawn at (the
+/// cursor position when the image's DCS sequence completed - see
+/// `TerminalOutput::set_sixel_image`), keyed by `(row, col)`. The renderer
+/// iterates `TerminalGrid::sixel_images` and blits each one as a texture
+/// over the cells it occupies, using the image's pixel size and the pane's
+/// cell size to work out the span.
+///
+/// Invalidation is row-granular, the same spirit as `damaged_rows`:
+/// anything that could plausibly touch an image's cells (a scroll through
+/// its row, a full clear, new text landing on its anchor cell) drops it
+/// rather than tracking its exact cell footprint, which would need the
+/// renderer's pixel-per-cell size plumbed back into the grid.
+#[derive(Debug, Clone, Default)]
+pub struct SixelImageStore {
+    anchors: std::collections::BTreeMap<(usize, usize), Arc<SixelImage>>,
+}
+
+impl SixelImageStore {
+    fn insert(&mut self, row: usize, col: usize, image: SixelImage) {
+        self.anchors.insert((row, col), Arc::new(image));
+    }
+
+    fn remove(&mut self, row: usize, col: usize) {
+        self.anchors.remove(&(row, col));
+    }
+
+    fn clear(&mut self) {
+        self.anchors.clear();
+    }
+
+    /// The images currently anchored, for the renderer to blit.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Arc<SixelImage>)> {
+        self.anchors.iter().map(|(&(row, col), image)| (row, col, image))
+    }
+}
+
+/// How many wrapped continuation rows `TerminalGrid::search` will follow
+/// past wherever a logical line started, mirroring the same cap
+/// `search::SearchState::find_matches` uses for caller-fed rows.
+const MAX_SEARCH_CONTINUATION_LINES: usize = 100;
+
+/// A match found by `TerminalGrid::search`, in the `all_rows`/`total_rows`
+/// absolute coordinate space: row 0 is the oldest scrollback line, with the
+/// live grid's rows following directly after it. `end` is exclusive and may
+/// land on a different row than `start` when the match crosses a
+/// soft-wrapped line break.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchRange {
+    /// Inclusive `(row, col)` start of the match.
+    pub start: (usize, usize),
+    /// Exclusive `(row, col)` end of the match.
+    pub end: (usize, usize),
+}
+
+/// Errors from `TerminalGrid::search`.
+#[derive(Error, Debug)]
+pub enum GridSearchError {
+    #[error("Search pattern must not be empty")]
+    EmptyPattern,
+
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String),
+}
+
+pub type GridSearchResult<T> = Result<T, GridSearchError>;
+
 /// Terminal screen buffer with scrollback history.
 ///
 /// This struct manages a 2D grid of cells representing the visible terminal
@@ -118,15 +310,173 @@ pub struct TerminalGrid {
     /// Current cursor position.
     cursor: Cursor,
     /// Scrollback buffer (lines that have scrolled off the top).
-    scrollback: Vec<ScrollbackRow>,
+    scrollback: Storage,
     /// Maximum number of scrollback lines to keep.
     max_scrollback: usize,
+    /// How many lines back into scrollback the viewport is paged.
+    /// 0 means the viewport shows the live screen.
+    display_offset: usize,
     /// Current text attributes for new characters.
     attributes: TextAttributes,
     /// Current foreground color.
     fg_color: Color,
     /// Current background color.
     bg_color: Color,
+    /// The hyperlink open for new characters (OSC 8), if any. Mirrors
+    /// `fg_color`/`bg_color`: synced in from the parser before each batch
+    /// and applied to every cell `put_char` writes until it changes.
+    hyperlink: Option<Arc<Hyperlink>>,
+    /// The primary screen's grid, cursor, attributes, colors, and open
+    /// hyperlink, stashed here while the alternate screen buffer is active
+    /// (see `enter_alt_screen`/`exit_alt_screen`). `None` when showing the
+    /// primary screen.
+    saved_primary: Option<(Vec<Vec<Cell>>, Cursor, TextAttributes, Color, Color, Option<Arc<Hyperlink>>)>,
+    /// Rows changed since the last `reset_damage()`, so the renderer can
+    /// re-queue only what actually moved instead of every cell every frame.
+    /// Modeled after alacritty's `TermDamage`: most mutations mark just the
+    /// row(s) they touched, while anything that reshuffles many rows at
+    /// once (resize, scroll, clearing, a viewport page) conservatively
+    /// marks the whole grid damaged rather than tracking exact ranges.
+    damaged_rows: BTreeSet<usize>,
+    /// Set alongside `damaged_rows` by `mark_all_damaged` and by `reflow`
+    /// (which rebuilds `wrapped_rows` itself rather than clearing it), and
+    /// true for a new grid - lets `take_damage` report `Damage::Full` instead of an
+    /// exhaustive `Damage::Lines` when a mutation conservatively invalidated
+    /// every row, without the caller having to compare `damaged_rows.len()`
+    /// to `self.rows` itself.
+    full_damage: bool,
+    /// Rows whose content runs onto the next row as a soft (wrapped) line
+    /// break rather than stopping at an explicit newline. Consulted by
+    /// `SearchState::find_matches` so a search pattern can match text that
+    /// crosses a wrapped line the same way it would a line that never
+    /// wrapped in the first place.
+    wrapped_rows: BTreeSet<usize>,
+    /// Decoded Sixel images anchored at the cells they were drawn over -
+    /// see `SixelImageStore`.
+    sixel_images: SixelImageStore,
+    /// Whether `resize` rewraps logical lines at the new width (see
+    /// `reflow`) or just truncates/pads each row independently. See
+    /// `set_reflow`.
+    reflow_enabled: bool,
+    /// Whether a wide character that doesn't fit in the last column wraps
+    /// onto the next row (DECAWM on, the default) or is clipped in place
+    /// (DECAWM off). See `set_autowrap` and `put_char`.
+    autowrap: bool,
+}
+
+/// A cell's fully-resolved, CSS-ready style - concrete colors (after
+/// `reverse` video and `Palette` lookup) plus the attributes `to_html` maps
+/// onto CSS properties. Two cells compare equal here exactly when they
+/// belong in the same `<span>` run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellStyle {
+    fg: (u8, u8, u8),
+    bg: (u8, u8, u8),
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: UnderlineStyle,
+    blink: bool,
+    hidden: bool,
+    strikethrough: bool,
+}
+
+impl CellStyle {
+    fn resolve(cell: &Cell, palette: &Palette) -> Self {
+        let attrs = cell.attributes;
+        let fg = palette.resolve(cell.fg_color);
+        let bg = palette.resolve(cell.bg_color);
+        let (fg, bg) = if attrs.reverse { (bg, fg) } else { (fg, bg) };
+        Self {
+            fg: (fg.r, fg.g, fg.b),
+            bg: (bg.r, bg.g, bg.b),
+            bold: attrs.bold,
+            dim: attrs.dim,
+            italic: attrs.italic,
+            underline: attrs.underline,
+            blink: attrs.blink,
+            hidden: attrs.hidden,
+            strikethrough: attrs.strikethrough,
+        }
+    }
+
+    /// The underline/strikethrough `text-decoration-style` this maps onto,
+    /// if CSS has a named style for it (`Single` is the CSS default, so it
+    /// doesn't need one spelled out).
+    fn text_decoration_style(self) -> Option<&'static str> {
+        match self.underline {
+            UnderlineStyle::None | UnderlineStyle::Single => None,
+            UnderlineStyle::Double => Some("double"),
+            UnderlineStyle::Curly => Some("wavy"),
+            UnderlineStyle::Dotted => Some("dotted"),
+            UnderlineStyle::Dashed => Some("dashed"),
+        }
+    }
+
+    /// Build the inline `style="..."` attribute value for this style.
+    fn to_css(self) -> String {
+        let mut css = format!(
+            "color:#{:02x}{:02x}{:02x};background-color:#{:02x}{:02x}{:02x};",
+            self.fg.0, self.fg.1, self.fg.2, self.bg.0, self.bg.1, self.bg.2
+        );
+        if self.bold {
+            css.push_str("font-weight:bold;");
+        }
+        if self.dim {
+            css.push_str("opacity:0.67;");
+        }
+        if self.italic {
+            css.push_str("font-style:italic;");
+        }
+        if self.hidden {
+            css.push_str("visibility:hidden;");
+        }
+
+        let mut decoration_lines = Vec::new();
+        if self.underline != UnderlineStyle::None {
+            decoration_lines.push("underline");
+        }
+        if self.strikethrough {
+            decoration_lines.push("line-through");
+        }
+        if !decoration_lines.is_empty() {
+            css.push_str("text-decoration-line:");
+            css.push_str(&decoration_lines.join(" "));
+            css.push(';');
+            if let Some(style) = self.text_decoration_style() {
+                css.push_str("text-decoration-style:");
+                css.push_str(style);
+                css.push(';');
+            }
+        }
+        if self.blink {
+            css.push_str("animation:terminal-blink 1s steps(1) infinite;");
+        }
+        css
+    }
+}
+
+/// Escape one character for inclusion in HTML text content.
+fn push_html_escaped(out: &mut String, c: char) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        other => out.push(other),
+    }
+}
+
+/// Append one `<span style="...">text</span>` run, skipping entirely empty
+/// runs (the end-of-row sentinel in `push_row_html`).
+fn push_html_span(html: &mut String, style: CellStyle, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    html.push_str("<span style=\"");
+    html.push_str(&style.to_css());
+    html.push_str("\">");
+    html.push_str(text);
+    html.push_str("</span>");
 }
 
 impl TerminalGrid {
@@ -141,16 +491,28 @@ impl TerminalGrid {
     /// * `cols` - Number of columns (width).
     /// * `rows` - Number of rows (height).
     pub fn with_size(cols: usize, rows: usize) -> Self {
+        // A wide (CJK/emoji) character always needs a leading cell plus a
+        // spacer, so anything narrower can never render one correctly.
+        let cols = cols.max(2);
         Self {
             grid: vec![vec![Cell::default(); cols]; rows],
             cols,
             rows,
             cursor: Cursor::default(),
-            scrollback: Vec::new(),
+            scrollback: Storage::with_capacity(10000),
             max_scrollback: 10000,
+            display_offset: 0,
             attributes: TextAttributes::default(),
             fg_color: Color::Default,
             bg_color: Color::Default,
+            hyperlink: None,
+            saved_primary: None,
+            damaged_rows: (0..rows).collect(),
+            full_damage: true,
+            wrapped_rows: BTreeSet::new(),
+            sixel_images: SixelImageStore::default(),
+            reflow_enabled: true,
+            autowrap: true,
         }
     }
 
@@ -161,16 +523,26 @@ impl TerminalGrid {
     /// * `rows` - Number of rows (height).
     /// * `max_scrollback` - Maximum scrollback lines to retain.
     pub fn with_scrollback(cols: usize, rows: usize, max_scrollback: usize) -> Self {
+        let cols = cols.max(2);
         Self {
             grid: vec![vec![Cell::default(); cols]; rows],
             cols,
             rows,
             cursor: Cursor::default(),
-            scrollback: Vec::new(),
+            scrollback: Storage::with_capacity(max_scrollback),
             max_scrollback,
+            display_offset: 0,
             attributes: TextAttributes::default(),
             fg_color: Color::Default,
             bg_color: Color::Default,
+            hyperlink: None,
+            saved_primary: None,
+            damaged_rows: (0..rows).collect(),
+            full_damage: true,
+            wrapped_rows: BTreeSet::new(),
+            sixel_images: SixelImageStore::default(),
+            reflow_enabled: true,
+            autowrap: true,
         }
     }
 
@@ -214,16 +586,119 @@ impl TerminalGrid {
         self.bg_color = color;
     }
 
+    /// Set the hyperlink open for new characters (OSC 8). `None` closes it.
+    pub fn set_hyperlink(&mut self, hyperlink: Option<Arc<Hyperlink>>) {
+        self.hyperlink = hyperlink;
+    }
+
+    /// The cells making up the same hyperlink run as `(row, col)` - every
+    /// contiguous cell on that row sharing the same `Arc<Hyperlink>`. Cells
+    /// with an explicit `id=` all point at one shared `Arc` (see the
+    /// parser's id registry), so this naturally reassembles a link split
+    /// across several OSC 8 opens as long as they're adjacent. Empty if
+    /// `(row, col)` has no hyperlink. Scoped to a single physical row - a
+    /// link that wraps onto the next line isn't expanded across it.
+    pub fn cells_for_hyperlink_at(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let Some(link) = self.get_cell(row, col).and_then(|c| c.hyperlink.clone()) else {
+            return Vec::new();
+        };
+        let row_cells = &self.grid[row];
+        let same_link = |cell: &Cell| cell.hyperlink.as_ref().is_some_and(|l| Arc::ptr_eq(l, &link));
+
+        let mut start = col;
+        while start > 0 && same_link(&row_cells[start - 1]) {
+            start -= 1;
+        }
+        let mut end = col;
+        while end + 1 < row_cells.len() && same_link(&row_cells[end + 1]) {
+            end += 1;
+        }
+
+        (start..=end).map(|c| (row, c)).collect()
+    }
+
+    /// Mark a single row dirty. Out-of-bounds rows are ignored.
+    fn damage_row(&mut self, row: usize) {
+        if row < self.rows {
+            self.damaged_rows.insert(row);
+        }
+    }
+
+    /// Mark every row dirty. Used by mutations that reshuffle or repaint the
+    /// whole grid (resize, scroll, clear, alt-screen switch, viewport paging)
+    /// rather than tracking their exact, often-discontiguous row ranges.
+    pub fn mark_all_damaged(&mut self) {
+        self.damaged_rows = (0..self.rows).collect();
+        self.full_damage = true;
+        // Row indices are about to mean something different (rows shifted,
+        // resized, or wiped), so any soft-wrap bookkeeping from before no
+        // longer applies - it'll be rebuilt as new output is written.
+        self.wrapped_rows.clear();
+        // Same reasoning extends to anchored Sixel images - see
+        // `SixelImageStore`'s doc comment.
+        self.sixel_images.clear();
+    }
+
+    /// Record that `row`'s content continues onto `row + 1` as a soft line
+    /// break (auto-wrap), rather than an explicit newline. Out-of-bounds
+    /// rows are ignored.
+    fn mark_wrapped(&mut self, row: usize) {
+        if row < self.rows {
+            self.wrapped_rows.insert(row);
+        }
+    }
+
+    /// Whether `row` wraps into `row + 1` as a single logical line.
+    pub fn is_row_wrapped(&self, row: usize) -> bool {
+        self.wrapped_rows.contains(&row)
+    }
+
+    /// Clear the damage set. Call this after a successful render so the next
+    /// frame only reflects rows that change after this point.
+    pub fn reset_damage(&mut self) {
+        self.damaged_rows.clear();
+        self.full_damage = false;
+    }
+
+    /// Rows changed since the last `reset_damage()`.
+    pub fn damaged_rows(&self) -> &BTreeSet<usize> {
+        &self.damaged_rows
+    }
+
+    /// Whether any row has changed since the last `reset_damage()`.
+    pub fn is_damaged(&self) -> bool {
+        !self.damaged_rows.is_empty()
+    }
+
+    /// Take the current damage and clear it, same as reading `damaged_rows()`
+    /// then calling `reset_damage()` in one step. Returns `Damage::Full` if
+    /// a mutation conservatively invalidated the whole grid since the last
+    /// call, otherwise `Damage::Lines` with exactly the rows that changed
+    /// (empty if nothing did).
+    pub fn take_damage(&mut self) -> Damage {
+        let damage = if self.full_damage {
+            Damage::Full
+        } else {
+            Damage::Lines(self.damaged_rows.iter().copied().collect())
+        };
+        self.reset_damage();
+        damage
+    }
+
     /// Move the cursor to a specific position.
     ///
     /// The position is clamped to valid grid coordinates.
     pub fn move_cursor(&mut self, row: usize, col: usize) {
+        self.damage_row(self.cursor.row);
         self.cursor.row = row.min(self.rows.saturating_sub(1));
         self.cursor.col = col.min(self.cols.saturating_sub(1));
+        self.damage_row(self.cursor.row);
     }
 
     /// Move the cursor relative to its current position.
     pub fn move_cursor_relative(&mut self, row_delta: isize, col_delta: isize) {
+        self.damage_row(self.cursor.row);
+
         if row_delta < 0 {
             self.cursor.row = self.cursor.row.saturating_sub(row_delta.unsigned_abs());
         } else {
@@ -235,15 +710,46 @@ impl TerminalGrid {
         } else {
             self.cursor.col = (self.cursor.col + col_delta as usize).min(self.cols.saturating_sub(1));
         }
+
+        self.damage_row(self.cursor.row);
     }
 
     /// Put a character at the current cursor position and advance the cursor.
     ///
     /// If at the end of a line (cursor past last column), this wraps to the next line first.
     /// If at the bottom of the screen, this scrolls up.
+    ///
+    /// Double-width characters (CJK, wide emoji) occupy the cell at the
+    /// cursor - tagged `wide_char` - plus a trailing `wide_char_spacer`
+    /// placeholder cell, and advance the cursor by two; a wide char that
+    /// doesn't fit in the last column blanks that column, marks the row
+    /// wrapped, and places the glyph at column 0 of the next row instead -
+    /// unless autowrap is off (see `set_autowrap`), in which case it's
+    /// clipped (the last column is blanked and the cursor stays put) rather
+    /// than spilling onto a row the caller's DECAWM-off cursor handling
+    /// doesn't expect to have been touched.
+    /// Writing over either half of an existing pair blanks the other half
+    /// first, so a wide char is never left with a stale spacer (or a
+    /// spacer with no leading glyph).
     pub fn put_char(&mut self, c: char) {
+        // New output snaps the viewport back to the live screen.
+        self.display_offset = 0;
+
+        let width = UnicodeWidthChar::width(c).unwrap_or(1);
+        if width == 0 {
+            // A combining mark - it modifies the glyph before the cursor
+            // rather than occupying a column of its own. `Cell::char` only
+            // ever holds one `char`, so there's nowhere to actually append
+            // it; dropping it is the same tradeoff plain `wcwidth`-based
+            // terminals (no grapheme clustering) already make, and it's
+            // strictly better than the alternative of letting it eat a
+            // column and shift everything after it.
+            return;
+        }
+
         // Handle pending wrap (cursor past last column)
         if self.cursor.col >= self.cols {
+            self.mark_wrapped(self.cursor.row);
             self.cursor.col = 0;
             self.cursor.row += 1;
 
@@ -254,17 +760,85 @@ impl TerminalGrid {
             }
         }
 
+        if width == 2 && self.cursor.col + 1 == self.cols {
+            if self.autowrap {
+                // Only one column left: blank it and wrap the glyph down.
+                if self.cursor.row < self.rows {
+                    self.clear_wide_pair_at(self.cursor.row, self.cursor.col);
+                    self.grid[self.cursor.row][self.cursor.col] = self.blank_cell();
+                    self.damage_row(self.cursor.row);
+                }
+                self.mark_wrapped(self.cursor.row);
+                self.cursor.col = 0;
+                self.cursor.row += 1;
+                if self.cursor.row >= self.rows {
+                    self.scroll_up(1);
+                    self.cursor.row = self.rows - 1;
+                }
+            } else {
+                // DECAWM off: there's nowhere on this row to put a second
+                // half, and wrapping would scroll content the cursor
+                // correction in `print()` has no way to undo. Clip the
+                // glyph - blank the last column and pin the cursor there,
+                // the same "keep overwriting the last column" contract
+                // narrow characters already get when autowrap is disabled.
+                if self.cursor.row < self.rows {
+                    self.clear_wide_pair_at(self.cursor.row, self.cursor.col);
+                    self.grid[self.cursor.row][self.cursor.col] = self.blank_cell();
+                    self.damage_row(self.cursor.row);
+                }
+                return;
+            }
+        }
+
         // Write the character at current cursor position
         if self.cursor.row < self.rows && self.cursor.col < self.cols {
+            self.clear_wide_pair_at(self.cursor.row, self.cursor.col);
+            let mut attributes = self.attributes;
+            attributes.wide_char = width == 2;
             let cell = &mut self.grid[self.cursor.row][self.cursor.col];
             cell.char = c;
             cell.fg_color = self.fg_color;
             cell.bg_color = self.bg_color;
-            cell.attributes = self.attributes;
+            cell.attributes = attributes;
+            cell.hyperlink = self.hyperlink.clone();
+            self.damage_row(self.cursor.row);
+            self.sixel_images.remove(self.cursor.row, self.cursor.col);
         }
 
         // Advance cursor
         self.cursor.col += 1;
+
+        if width == 2 && self.cursor.row < self.rows && self.cursor.col < self.cols {
+            self.clear_wide_pair_at(self.cursor.row, self.cursor.col);
+            let mut attributes = self.attributes;
+            attributes.wide_char_spacer = true;
+            let cell = &mut self.grid[self.cursor.row][self.cursor.col];
+            cell.char = ' ';
+            cell.fg_color = self.fg_color;
+            cell.bg_color = self.bg_color;
+            cell.attributes = attributes;
+            cell.hyperlink = self.hyperlink.clone();
+            self.damage_row(self.cursor.row);
+            self.sixel_images.remove(self.cursor.row, self.cursor.col);
+            self.cursor.col += 1;
+        }
+    }
+
+    /// If the cell at `(row, col)` is half of a double-width pair, blank
+    /// the other half too. Without this, overwriting one half (e.g. a
+    /// cursor jump via CUP, or a narrower glyph typed over it) would leave
+    /// a dangling spacer with no leading glyph, or a lone wide glyph with
+    /// no spacer to its right.
+    fn clear_wide_pair_at(&mut self, row: usize, col: usize) {
+        let attrs = self.grid[row][col].attributes;
+        if attrs.wide_char_spacer && col > 0 {
+            let blank = self.blank_cell();
+            self.grid[row][col - 1] = blank;
+        } else if attrs.wide_char && col + 1 < self.cols {
+            let blank = self.blank_cell();
+            self.grid[row][col + 1] = blank;
+        }
     }
 
     /// Put a character without advancing the cursor.
@@ -275,9 +849,26 @@ impl TerminalGrid {
             cell.fg_color = self.fg_color;
             cell.bg_color = self.bg_color;
             cell.attributes = self.attributes;
+            cell.hyperlink = self.hyperlink.clone();
+            self.damage_row(row);
+            self.sixel_images.remove(row, col);
         }
     }
 
+    /// Decode and anchor a Sixel image at the current cursor position (see
+    /// `TerminalOutput::set_sixel_image`). Replaces whatever was anchored at
+    /// that cell before.
+    pub fn set_sixel_image(&mut self, image: SixelImage) {
+        self.sixel_images.insert(self.cursor.row, self.cursor.col, image);
+        self.damage_row(self.cursor.row);
+    }
+
+    /// Currently anchored Sixel images, for the renderer to blit - see
+    /// `SixelImageStore::iter`.
+    pub fn sixel_images(&self) -> impl Iterator<Item = (usize, usize, &Arc<SixelImage>)> {
+        self.sixel_images.iter()
+    }
+
     /// Get a cell at the given position.
     ///
     /// Returns None if the position is out of bounds.
@@ -295,44 +886,217 @@ impl TerminalGrid {
         self.grid.get(row).map(|r| r.as_slice())
     }
 
+    /// Whether `resize` rewraps logical lines at the new width (the
+    /// default) or just truncates/pads each row independently, clipping or
+    /// ragging text at the old column boundaries. Off by default was the
+    /// original behavior before reflow existed, and remains available for
+    /// callers that want resize to be as cheap and predictable as possible.
+    pub fn set_reflow(&mut self, enabled: bool) {
+        self.reflow_enabled = enabled;
+    }
+
+    /// Toggle autowrap (DECAWM, `CSI ? 7 h/l`). With it off, a wide
+    /// character that doesn't fit in the last column is clipped in place by
+    /// `put_char` instead of spilling (and possibly scrolling) onto the next
+    /// row - matching how narrow characters already just keep overwriting
+    /// the last column there (see `ParserOutputWrapper::print`).
+    pub fn set_autowrap(&mut self, enabled: bool) {
+        self.autowrap = enabled;
+    }
+
     /// Resize the terminal grid.
     ///
-    /// Content is preserved where possible. New cells are initialized to default.
+    /// When the width changes and reflow is enabled (see `set_reflow`),
+    /// content is reflowed rather than truncated or padded: logical lines
+    /// (runs of rows joined by `wrapped_rows`) are reassembled and
+    /// re-wrapped at the new width, so shrinking and then re-growing the
+    /// same width gets the original lines back instead of ragged, clipped
+    /// ones. A height-only resize just adds or drops rows at the bottom,
+    /// same as before.
     pub fn resize(&mut self, new_cols: usize, new_rows: usize) {
-        // Resize each row
-        for row in &mut self.grid {
-            row.resize(new_cols, Cell::default());
+        let new_cols = new_cols.max(2);
+
+        // The stashed primary grid isn't visible while the alternate screen
+        // is active, so it doesn't need a full reflow - just conform its
+        // dimensions to the new size so `exit_alt_screen` doesn't hand back
+        // a grid whose row/column counts no longer match `self.cols`/`self.rows`.
+        if let Some(saved) = self.saved_primary.as_mut() {
+            let primary_grid = &mut saved.0;
+            for row in primary_grid.iter_mut() {
+                row.resize(new_cols, Cell::default());
+            }
+            primary_grid.resize(new_rows, vec![Cell::default(); new_cols]);
+        }
+
+        if new_cols == self.cols || !self.reflow_enabled {
+            for row in &mut self.grid {
+                row.resize(new_cols, Cell::default());
+            }
+            self.grid.resize(new_rows, vec![Cell::default(); new_cols]);
+            self.cols = new_cols;
+            self.rows = new_rows;
+            self.cursor.row = self.cursor.row.min(self.rows.saturating_sub(1));
+            self.cursor.col = self.cursor.col.min(new_cols.saturating_sub(1));
+            self.wrapped_rows.retain(|&r| r < new_rows);
+            self.mark_all_damaged();
+            return;
         }
 
-        // Add or remove rows
-        self.grid.resize(new_rows, vec![Cell::default(); new_cols]);
+        self.reflow(new_cols, new_rows);
+    }
+
+    /// The width-changing half of `resize`: rebuild `self.grid` at
+    /// `new_cols` by re-wrapping each logical line instead of resizing rows
+    /// in place.
+    fn reflow(&mut self, new_cols: usize, new_rows: usize) {
+        // Absolute offset of the cursor within whichever logical line
+        // contains it, so its position can be recomputed after rewrapping
+        // shifts everything around.
+        let mut cursor_line_start = self.cursor.row;
+        while cursor_line_start > 0 && self.is_row_wrapped(cursor_line_start - 1) {
+            cursor_line_start -= 1;
+        }
+        let cursor_offset_in_line = (self.cursor.row - cursor_line_start) * self.cols + self.cursor.col;
+
+        let mut new_grid: Vec<Vec<Cell>> = Vec::with_capacity(new_rows);
+        let mut new_wrapped: BTreeSet<usize> = BTreeSet::new();
+        // (new row, new col) the cursor lands on - filled in once its
+        // logical line is rewrapped below.
+        let mut cursor_target: Option<(usize, usize)> = None;
+
+        let mut row_idx = 0;
+        while row_idx < self.grid.len() {
+            let line_start = row_idx;
+            let mut line: Vec<Cell> = Vec::with_capacity(self.cols);
+            loop {
+                line.extend(self.grid[row_idx].iter().cloned());
+                let wrapped = self.is_row_wrapped(row_idx);
+                row_idx += 1;
+                if !wrapped || row_idx >= self.grid.len() {
+                    break;
+                }
+            }
+            let line_end = row_idx;
+
+            // Trailing blank cells only ever come from the logical line's
+            // last physical row (every earlier row was full, or it
+            // wouldn't have wrapped) - trimming them keeps an empty prompt
+            // line from ballooning into several blank rows when the width
+            // shrinks.
+            let mut content_len = line.len();
+            while content_len > 0 && line[content_len - 1].is_empty() {
+                content_len -= 1;
+            }
+
+            let is_cursor_line = self.cursor.row >= line_start && self.cursor.row < line_end;
+            let chunk_count = if content_len == 0 { 1 } else { content_len.div_ceil(new_cols) };
+            let first_new_row = new_grid.len();
+
+            for chunk_idx in 0..chunk_count {
+                let start = chunk_idx * new_cols;
+                let end = (start + new_cols).min(line.len()).max(start);
+                let mut chunk: Vec<Cell> = line[start..end].to_vec();
+                chunk.resize(new_cols, Cell::default());
+
+                if chunk_idx + 1 < chunk_count {
+                    new_wrapped.insert(first_new_row + chunk_idx);
+                }
+
+                if is_cursor_line {
+                    let chunk_start = start;
+                    let chunk_end = chunk_start + new_cols;
+                    let last_chunk = chunk_idx + 1 == chunk_count;
+                    if cursor_offset_in_line >= chunk_start && (cursor_offset_in_line < chunk_end || last_chunk) {
+                        cursor_target = Some((new_grid.len(), (cursor_offset_in_line - chunk_start).min(new_cols - 1)));
+                    }
+                }
+
+                new_grid.push(chunk);
+            }
+        }
 
         self.cols = new_cols;
-        self.rows = new_rows;
 
-        // Clamp cursor to new dimensions
-        self.cursor.row = self.cursor.row.min(self.rows.saturating_sub(1));
-        self.cursor.col = self.cursor.col.min(self.cols.saturating_sub(1));
+        // If rewrapping grew the logical content past the new row count,
+        // push the oldest rows into scrollback instead of silently
+        // dropping them - the same "history absorbs what falls off the
+        // top" rule `scroll_up` follows, just driven by a reflow instead
+        // of a linefeed. The alt screen has no scrollback of its own, so
+        // its overflow is discarded exactly as it is in `scroll_up`.
+        if new_grid.len() > new_rows {
+            let overflow = new_grid.len() - new_rows;
+            let blank = self.blank_cell();
+            let mut evicted: Vec<Vec<Cell>> = new_grid.drain(0..overflow).collect();
+            if !self.is_alt_screen() {
+                for row in &mut evicted {
+                    self.scrollback.push(row, self.max_scrollback, new_cols, &blank);
+                }
+            }
+            new_wrapped = new_wrapped.into_iter().filter_map(|r| r.checked_sub(overflow)).collect();
+            cursor_target = cursor_target.and_then(|(row, col)| row.checked_sub(overflow).map(|r| (r, col)));
+        }
+
+        self.wrapped_rows = new_wrapped.into_iter().filter(|&r| r < new_rows).collect();
+        if new_grid.len() < new_rows {
+            let pad = new_rows - new_grid.len();
+            for _ in 0..pad {
+                new_grid.push(vec![Cell::default(); new_cols]);
+            }
+        }
+        match cursor_target {
+            Some((row, col)) => {
+                self.cursor.row = row.min(new_rows.saturating_sub(1));
+                self.cursor.col = col;
+            }
+            // The cursor's own logical line was pushed into scrollback
+            // entirely (a very short, narrow viewport) - there's no cell
+            // left on screen holding its character, so just clamp it to
+            // the top-left rather than leaving a stale position.
+            None => self.cursor = Cursor::default(),
+        }
+
+        self.grid = new_grid;
+        self.rows = new_rows;
+        self.damaged_rows = (0..self.rows).collect();
+        self.full_damage = true;
+        self.sixel_images.clear();
+    }
+
+    /// A blank cell reflecting the terminal's *current* background color,
+    /// the building block for Background Color Erase (BCE): real terminals
+    /// fill erased cells and newly exposed lines with whatever background
+    /// is active at erase time, rather than always `Color::Default` - so
+    /// e.g. `\x1b[41m\x1b[2J` clears the screen to red, not the default
+    /// background. Foreground and attributes reset to default since
+    /// there's no text left to carry them.
+    fn blank_cell(&self) -> Cell {
+        Cell {
+            char: ' ',
+            fg_color: Color::Default,
+            bg_color: self.bg_color,
+            attributes: TextAttributes::default(),
+            hyperlink: None,
+        }
     }
 
     /// Clear the entire screen, filling with default cells.
     pub fn clear_screen(&mut self) {
+        let blank = self.blank_cell();
         for row in &mut self.grid {
-            for cell in row {
-                cell.reset();
-            }
+            row.fill(blank.clone());
         }
         // Also clear scrollback when clearing screen
         self.scrollback.clear();
+        self.mark_all_damaged();
     }
 
     /// Clear the screen but preserve scrollback.
     pub fn clear_screen_keep_scrollback(&mut self) {
+        let blank = self.blank_cell();
         for row in &mut self.grid {
-            for cell in row {
-                cell.reset();
-            }
+            row.fill(blank.clone());
         }
+        self.mark_all_damaged();
     }
 
     /// Clear from cursor to end of screen.
@@ -341,56 +1105,104 @@ impl TerminalGrid {
         self.clear_to_end_of_line();
 
         // Clear all lines below
+        let blank = self.blank_cell();
         for row_idx in (self.cursor.row + 1)..self.rows {
-            for cell in &mut self.grid[row_idx] {
-                cell.reset();
-            }
+            self.grid[row_idx].fill(blank.clone());
         }
+        self.mark_all_damaged();
     }
 
     /// Clear from start of screen to cursor.
     pub fn clear_to_start_of_screen(&mut self) {
         // Clear all lines above
+        let blank = self.blank_cell();
         for row_idx in 0..self.cursor.row {
-            for cell in &mut self.grid[row_idx] {
-                cell.reset();
-            }
+            self.grid[row_idx].fill(blank.clone());
         }
 
         // Clear from start of current line to cursor
         self.clear_to_start_of_line();
+        self.mark_all_damaged();
     }
 
     /// Clear the current line.
     pub fn clear_line(&mut self) {
         if self.cursor.row < self.rows {
-            for cell in &mut self.grid[self.cursor.row] {
-                cell.reset();
-            }
+            let blank = self.blank_cell();
+            self.grid[self.cursor.row].fill(blank);
+            self.damage_row(self.cursor.row);
+            self.wrapped_rows.remove(&self.cursor.row);
         }
     }
 
     /// Clear from cursor to end of the current line.
     pub fn clear_to_end_of_line(&mut self) {
         if self.cursor.row < self.rows {
+            // Clearing may cut a wide pair in half: if the preserved cell
+            // just before the cursor is the leading half of a pair whose
+            // spacer we're about to blank, blank it too.
+            if self.cursor.col > 0 {
+                self.clear_wide_pair_at(self.cursor.row, self.cursor.col - 1);
+            }
+            let blank = self.blank_cell();
             for col_idx in self.cursor.col..self.cols {
-                self.grid[self.cursor.row][col_idx].reset();
+                self.grid[self.cursor.row][col_idx] = blank.clone();
             }
+            self.damage_row(self.cursor.row);
         }
     }
 
     /// Clear from start of the current line to cursor.
     pub fn clear_to_start_of_line(&mut self) {
         if self.cursor.row < self.rows {
-            for col_idx in 0..=self.cursor.col.min(self.cols - 1) {
-                self.grid[self.cursor.row][col_idx].reset();
+            let end = self.cursor.col.min(self.cols - 1);
+            // Same wide-pair-bisection concern as `clear_to_end_of_line`,
+            // but checking the cell just after what we're about to clear.
+            if end + 1 < self.cols {
+                self.clear_wide_pair_at(self.cursor.row, end + 1);
+            }
+            let blank = self.blank_cell();
+            for col_idx in 0..=end {
+                self.grid[self.cursor.row][col_idx] = blank.clone();
             }
+            self.damage_row(self.cursor.row);
+        }
+    }
+
+    /// Rotate `self.grid[top..=bottom]` up by `amount` rows in place: row
+    /// `top + amount` becomes the new `top`, and so on, with the `amount`
+    /// rows that fall off the top of the region wrapping around to its
+    /// bottom (garbage at this point - callers blank them afterward).
+    /// Implemented as `Vec::swap` passes, an O(1) pointer exchange per row,
+    /// rather than `to_vec`/`drain`, so no row is cloned or reallocated.
+    /// `amount` must be strictly less than the region's height; a full-region
+    /// rotation is a no-op the caller should skip in favor of blanking
+    /// everything directly.
+    fn rotate_region_up(&mut self, top: usize, bottom: usize, amount: usize) {
+        for row in top..=(bottom - amount) {
+            self.grid.swap(row, row + amount);
+        }
+    }
+
+    /// The mirror of `rotate_region_up`: row `bottom - amount` becomes the
+    /// new `bottom`, and the `amount` rows that fall off the bottom wrap
+    /// around to the top (garbage - callers blank them afterward). Same
+    /// `amount < region height` requirement.
+    fn rotate_region_down(&mut self, top: usize, bottom: usize, amount: usize) {
+        for row in (top + amount..=bottom).rev() {
+            self.grid.swap(row, row - amount);
         }
     }
 
     /// Scroll the screen up by n lines.
     ///
-    /// Lines that scroll off the top are moved to the scrollback buffer.
+    /// Lines that scroll off the top are moved to the scrollback buffer,
+    /// unless the alternate screen is active - full-screen apps (vim, less)
+    /// scroll their own contents constantly, and none of that belongs in the
+    /// primary screen's history. Scrolled-off rows swap their content
+    /// directly into the scrollback ring (or are blanked in place, on the
+    /// alt screen) instead of being cloned into a freshly allocated row - see
+    /// `Storage::push`.
     /// Note: This does NOT adjust the cursor position - callers must do that if needed.
     pub fn scroll_up(&mut self, n: usize) {
         if n == 0 || self.rows == 0 {
@@ -398,47 +1210,54 @@ impl TerminalGrid {
         }
 
         let scroll_amount = n.min(self.rows);
-
-        // Move scrolled lines to scrollback
-        for i in 0..scroll_amount {
-            if self.scrollback.len() >= self.max_scrollback {
-                self.scrollback.remove(0);
+        let blank = self.blank_cell();
+        let keep_history = !self.is_alt_screen();
+
+        let mut evicted: Vec<Vec<Cell>> = self.grid.drain(0..scroll_amount).collect();
+        for row in &mut evicted {
+            if keep_history {
+                self.scrollback.push(row, self.max_scrollback, self.cols, &blank);
+            } else {
+                row.fill(blank.clone());
             }
-            // Clone the row before it gets replaced
-            let row = self.grid[i].clone();
-            self.scrollback.push(row);
         }
+        self.grid.extend(evicted);
 
-        // Shift rows up
-        self.grid.drain(0..scroll_amount);
-
-        // Add new empty rows at the bottom
-        for _ in 0..scroll_amount {
-            self.grid.push(vec![Cell::default(); self.cols]);
+        // If the viewport is paged back into history, hold the visible
+        // content in place as new rows push into scrollback underneath it -
+        // otherwise the lines the user is reading would silently shift.
+        if keep_history && self.display_offset > 0 {
+            self.display_offset = (self.display_offset + scroll_amount).min(self.scrollback.len());
         }
+
+        self.mark_all_damaged();
     }
 
     /// Scroll the screen down by n lines.
     ///
-    /// This is the opposite of scroll_up - new lines appear at the top.
+    /// This is the opposite of scroll_up - new lines appear at the top. The
+    /// rows scrolled off the bottom aren't kept anywhere (unlike scroll_up,
+    /// this never touches scrollback), so they're rotated around to the top
+    /// and blanked in place rather than allocated fresh.
     pub fn scroll_down(&mut self, n: usize) {
         if n == 0 || self.rows == 0 {
             return;
         }
 
         let scroll_amount = n.min(self.rows);
+        let blank = self.blank_cell();
 
-        // Remove rows from the bottom
-        let rows_to_remove = self.rows.saturating_sub(scroll_amount);
-        self.grid.drain(rows_to_remove..);
-
-        // Add new empty rows at the top
-        for _ in 0..scroll_amount {
-            self.grid.insert(0, vec![Cell::default(); self.cols]);
+        if scroll_amount < self.rows {
+            self.rotate_region_down(0, self.rows - 1, scroll_amount);
+        }
+        for row in &mut self.grid[0..scroll_amount] {
+            row.fill(blank.clone());
         }
 
         // Adjust cursor position
         self.cursor.row = (self.cursor.row + scroll_amount).min(self.rows.saturating_sub(1));
+
+        self.mark_all_damaged();
     }
 
     /// Scroll a region of the screen up by n lines.
@@ -447,6 +1266,11 @@ impl TerminalGrid {
     /// below `bottom` remain unchanged. The bottom `n` lines of the region
     /// are cleared.
     ///
+    /// When the region spans the whole screen, this is equivalent to
+    /// `scroll_up` and the evicted lines are preserved in scrollback;
+    /// otherwise the lines scrolled off the top of the region are discarded,
+    /// since they aren't part of the contiguous screen history.
+    ///
     /// # Arguments
     /// * `n` - Number of lines to scroll
     /// * `top` - Top boundary of scroll region (0-indexed, inclusive)
@@ -456,36 +1280,24 @@ impl TerminalGrid {
             return;
         }
 
+        if top == 0 && bottom == self.rows - 1 {
+            self.scroll_up(n);
+            return;
+        }
+
         let region_height = bottom - top + 1;
         let scroll_amount = n.min(region_height);
+        let blank = self.blank_cell();
 
-        // Save rows outside the region
-        let above_region: Vec<Vec<Cell>> = if top > 0 {
-            self.grid[0..top].to_vec()
-        } else {
-            Vec::new()
-        };
-
-        let mut in_region: Vec<Vec<Cell>> = self.grid[top..=bottom].to_vec();
-
-        // Shift region content up
-        in_region.drain(0..scroll_amount);
-
-        // Add blank lines at bottom of region
-        for _ in 0..scroll_amount {
-            in_region.push(vec![Cell::default(); self.cols]);
+        if scroll_amount < region_height {
+            self.rotate_region_up(top, bottom, scroll_amount);
         }
-
-        // Reconstruct grid
-        let mut new_grid = above_region;
-        new_grid.append(&mut in_region);
-        
-        // Add rows below region
-        if bottom + 1 < self.rows {
-            new_grid.extend_from_slice(&self.grid[bottom + 1..]);
+        let blank_start = bottom + 1 - scroll_amount;
+        for row in &mut self.grid[blank_start..=bottom] {
+            row.fill(blank.clone());
         }
 
-        self.grid = new_grid;
+        self.mark_all_damaged();
     }
 
     /// Scroll a region of the screen down by n lines.
@@ -505,35 +1317,16 @@ impl TerminalGrid {
 
         let region_height = bottom - top + 1;
         let scroll_amount = n.min(region_height);
+        let blank = self.blank_cell();
 
-        // Save rows outside the region
-        let above_region: Vec<Vec<Cell>> = if top > 0 {
-            self.grid[0..top].to_vec()
-        } else {
-            Vec::new()
-        };
-
-        let mut in_region: Vec<Vec<Cell>> = self.grid[top..=bottom].to_vec();
-
-        // Remove lines from bottom of region
-        let remove_from = in_region.len().saturating_sub(scroll_amount);
-        in_region.drain(remove_from..);
-
-        // Insert blank lines at top of region
-        for _ in 0..scroll_amount {
-            in_region.insert(0, vec![Cell::default(); self.cols]);
+        if scroll_amount < region_height {
+            self.rotate_region_down(top, bottom, scroll_amount);
         }
-
-        // Reconstruct grid
-        let mut new_grid = above_region;
-        new_grid.append(&mut in_region);
-        
-        // Add rows below region
-        if bottom + 1 < self.rows {
-            new_grid.extend_from_slice(&self.grid[bottom + 1..]);
+        for row in &mut self.grid[top..(top + scroll_amount)] {
+            row.fill(blank.clone());
         }
 
-        self.grid = new_grid;
+        self.mark_all_damaged();
     }
 
     /// Perform a line feed within a scroll region.
@@ -545,7 +1338,9 @@ impl TerminalGrid {
             // At bottom of region, scroll up
             self.scroll_up_in_region(1, top, bottom);
         } else if self.cursor.row < self.rows - 1 {
+            self.damage_row(self.cursor.row);
             self.cursor.row += 1;
+            self.damage_row(self.cursor.row);
         }
     }
 
@@ -586,53 +1381,108 @@ impl TerminalGrid {
     /// Insert n blank lines at the cursor position.
     ///
     /// Lines below the cursor are shifted down, and lines that fall off
-    /// the bottom are lost.
+    /// the bottom are lost. Like the region-scroll functions, this rotates
+    /// the `[cursor.row, rows - 1]` slice in place rather than
+    /// draining/inserting, so no row is cloned or reallocated - it just
+    /// never touches scrollback, even when the cursor sits on row 0.
     pub fn insert_lines(&mut self, n: usize) {
         if self.cursor.row >= self.rows {
             return;
         }
 
-        let insert_count = n.min(self.rows - self.cursor.row);
+        let top = self.cursor.row;
+        let bottom = self.rows - 1;
+        let region_height = bottom - top + 1;
+        let insert_count = n.min(region_height);
+        let blank = self.blank_cell();
 
-        // Remove lines from the bottom to make room
-        let lines_to_remove = (self.cursor.row + insert_count).saturating_sub(self.rows);
-        if lines_to_remove > 0 {
-            self.grid.drain((self.rows - lines_to_remove)..);
+        if insert_count < region_height {
+            self.rotate_region_down(top, bottom, insert_count);
         }
-
-        // Insert blank lines at cursor position
-        for _ in 0..insert_count {
-            self.grid.insert(self.cursor.row, vec![Cell::default(); self.cols]);
+        for row in &mut self.grid[top..(top + insert_count)] {
+            row.fill(blank.clone());
         }
 
-        // Ensure we still have the right number of rows
-        self.grid.truncate(self.rows);
+        self.mark_all_damaged();
     }
 
     /// Delete n lines at the cursor position.
     ///
     /// Lines below the deleted lines are shifted up, and blank lines
-    /// appear at the bottom.
+    /// appear at the bottom. The mirror of `insert_lines`: rotates the
+    /// `[cursor.row, rows - 1]` slice in place instead of draining/pushing.
     pub fn delete_lines(&mut self, n: usize) {
         if self.cursor.row >= self.rows {
             return;
         }
 
-        let delete_count = n.min(self.rows - self.cursor.row);
+        let top = self.cursor.row;
+        let bottom = self.rows - 1;
+        let region_height = bottom - top + 1;
+        let delete_count = n.min(region_height);
+        let blank = self.blank_cell();
+
+        if delete_count < region_height {
+            self.rotate_region_up(top, bottom, delete_count);
+        }
+        let blank_start = bottom + 1 - delete_count;
+        for row in &mut self.grid[blank_start..=bottom] {
+            row.fill(blank.clone());
+        }
+
+        self.mark_all_damaged();
+    }
+
+    /// Insert n blank cells at the cursor column, shifting the rest of the
+    /// line right. Cells shifted past the last column are lost.
+    pub fn insert_chars(&mut self, n: usize) {
+        if self.cursor.row >= self.rows || self.cursor.col >= self.cols {
+            return;
+        }
+
+        let insert_count = n.min(self.cols - self.cursor.col);
+        // Inserting at the cursor may cut a wide pair straddling it.
+        if self.cursor.col > 0 {
+            self.clear_wide_pair_at(self.cursor.row, self.cursor.col - 1);
+        }
+        let blank = self.blank_cell();
+        let row = &mut self.grid[self.cursor.row];
+        row.truncate(self.cols - insert_count);
+        for _ in 0..insert_count {
+            row.insert(self.cursor.col, blank.clone());
+        }
+        self.damage_row(self.cursor.row);
+    }
 
-        // Remove lines at cursor position
-        self.grid.drain(self.cursor.row..(self.cursor.row + delete_count));
+    /// Delete n cells at the cursor column, shifting the rest of the line
+    /// left. Blank cells appear at the end of the line.
+    pub fn delete_chars(&mut self, n: usize) {
+        if self.cursor.row >= self.rows || self.cursor.col >= self.cols {
+            return;
+        }
 
-        // Add blank lines at the bottom
-        for _ in 0..delete_count {
-            self.grid.push(vec![Cell::default(); self.cols]);
+        let delete_count = n.min(self.cols - self.cursor.col);
+        // Deleting may cut a wide pair straddling either edge of the
+        // deleted range.
+        if self.cursor.col > 0 {
+            self.clear_wide_pair_at(self.cursor.row, self.cursor.col - 1);
+        }
+        if self.cursor.col + delete_count < self.cols {
+            self.clear_wide_pair_at(self.cursor.row, self.cursor.col + delete_count);
         }
+        let blank = self.blank_cell();
+        let row = &mut self.grid[self.cursor.row];
+        row.drain(self.cursor.col..(self.cursor.col + delete_count));
+        row.resize(self.cols, blank);
+        self.damage_row(self.cursor.row);
     }
 
     /// Perform a line feed (move cursor down, possibly scrolling).
     pub fn linefeed(&mut self) {
         if self.cursor.row < self.rows - 1 {
+            self.damage_row(self.cursor.row);
             self.cursor.row += 1;
+            self.damage_row(self.cursor.row);
         } else {
             self.scroll_up(1);
         }
@@ -641,11 +1491,24 @@ impl TerminalGrid {
     /// Perform a carriage return (move cursor to column 0).
     pub fn carriage_return(&mut self) {
         self.cursor.col = 0;
+        self.damage_row(self.cursor.row);
     }
 
     /// Backspace (move cursor left by one, but not past column 0).
+    ///
+    /// If the cell immediately to the left is a `wide_char_spacer`, steps
+    /// back one extra column so the cursor lands on the wide character's
+    /// leading cell instead of its spacer.
     pub fn backspace(&mut self) {
         self.cursor.col = self.cursor.col.saturating_sub(1);
+        if self.cursor.col > 0
+            && self.grid[self.cursor.row][self.cursor.col]
+                .attributes
+                .wide_char_spacer
+        {
+            self.cursor.col -= 1;
+        }
+        self.damage_row(self.cursor.row);
     }
 
     /// Tab (move cursor to next tab stop, every 8 columns).
@@ -654,11 +1517,13 @@ impl TerminalGrid {
         if self.cursor.col >= self.cols {
             self.cursor.col = self.cols - 1;
         }
+        self.damage_row(self.cursor.row);
     }
 
     /// Back tab (move cursor to previous tab stop).
     pub fn back_tab(&mut self) {
         self.cursor.col = ((self.cursor.col.saturating_sub(1)) / 8) * 8;
+        self.damage_row(self.cursor.row);
     }
 
     /// Get the entire visible grid as a slice of rows.
@@ -666,9 +1531,15 @@ impl TerminalGrid {
         &self.grid
     }
 
-    /// Get the scrollback buffer.
-    pub fn scrollback(&self) -> &[ScrollbackRow] {
-        &self.scrollback
+    /// Get the scrollback buffer, oldest line first.
+    ///
+    /// Returns an iterator rather than a slice, since the ring's logical
+    /// order generally isn't a contiguous run of `Storage::inner` once it's
+    /// wrapped - but each line is still fetched from the ring lazily as the
+    /// iterator is driven, not collected up front, so this stays as
+    /// allocation-free as the old `&[ScrollbackRow]` accessor it replaced.
+    pub fn scrollback(&self) -> impl ExactSizeIterator<Item = &ScrollbackRow> {
+        (0..self.scrollback.len()).map(move |i| self.scrollback.get(i).expect("index within scrollback bounds"))
     }
 
     /// Clear the scrollback buffer.
@@ -676,50 +1547,373 @@ impl TerminalGrid {
         self.scrollback.clear();
     }
 
-    /// Get the content of a row as a string.
-    pub fn row_to_string(&self, row: usize) -> String {
-        if row >= self.rows {
-            return String::new();
-        }
-
-        self.grid[row].iter().map(|cell| cell.char).collect()
+    /// Page the viewport through scrollback history.
+    pub fn scroll(&mut self, scroll: Scroll) {
+        let max_offset = self.scrollback.len();
+        self.display_offset = match scroll {
+            Scroll::Delta(delta) if delta >= 0 => {
+                (self.display_offset + delta as usize).min(max_offset)
+            }
+            Scroll::Delta(delta) => self.display_offset.saturating_sub(delta.unsigned_abs()),
+            Scroll::PageUp => (self.display_offset + self.rows).min(max_offset),
+            Scroll::PageDown => self.display_offset.saturating_sub(self.rows),
+            Scroll::Top => max_offset,
+            Scroll::Bottom => 0,
+        };
+        self.mark_all_damaged();
     }
 
-    /// Save the current cursor position and attributes.
-    pub fn save_cursor(&mut self) -> (Cursor, TextAttributes, Color, Color) {
-        (
-            self.cursor,
-            self.attributes,
-            self.fg_color,
-            self.bg_color,
-        )
+    /// How many lines back into scrollback the viewport is paged.
+    /// 0 means the viewport shows the live screen.
+    pub fn display_offset(&self) -> usize {
+        self.display_offset
     }
 
-    /// Restore a previously saved cursor position and attributes.
-    pub fn restore_cursor(&mut self, saved: (Cursor, TextAttributes, Color, Color)) {
-        self.cursor = saved.0;
-        self.attributes = saved.1;
-        self.fg_color = saved.2;
-        self.bg_color = saved.3;
+    /// Move the viewport by `delta` lines - positive pages back into
+    /// scrollback, negative pages forward toward the live screen. A thin
+    /// `Scroll::Delta` convenience for callers (mouse wheel, `Ctrl+Shift+Up`)
+    /// that already think in signed line deltas rather than constructing a
+    /// `Scroll` themselves.
+    pub fn scroll_display(&mut self, delta: isize) {
+        self.scroll(Scroll::Delta(delta));
     }
 
-    /// Reset the grid to initial state.
-    pub fn reset(&mut self) {
-        self.clear_screen();
-        self.cursor = Cursor::default();
-        self.attributes = TextAttributes::default();
-        self.fg_color = Color::Default;
-        self.bg_color = Color::Default;
+    /// Snap the viewport back to the live screen, discarding any scrollback
+    /// paging. A thin convenience over `scroll` for callers that just want
+    /// "jump to bottom" (e.g. on new input) without constructing a `Scroll`.
+    pub fn reset_display(&mut self) {
+        self.scroll(Scroll::Bottom);
     }
-}
 
-impl Default for TerminalGrid {
-    fn default() -> Self {
-        Self::new()
+    /// Page the viewport to an absolute offset into scrollback, clamped to
+    /// how much history actually exists. A thin convenience over `scroll`
+    /// for callers (e.g. a scrollbar drag) that already have a target
+    /// offset rather than a relative delta.
+    pub fn set_scrollback(&mut self, rows: usize) {
+        self.display_offset = rows.min(self.scrollback.len());
+        self.mark_all_damaged();
     }
-}
 
-impl fmt::Display for TerminalGrid {
+    /// The rows currently visible in the viewport, accounting for
+    /// `display_offset`.
+    ///
+    /// Yields exactly `rows` rows: the tail of scrollback history followed
+    /// by the top of the live grid, whichever window `display_offset`
+    /// selects. At `display_offset` 0 this is just the live grid.
+    pub fn visible_rows(&self) -> impl Iterator<Item = &Vec<Cell>> {
+        let total_history = self.scrollback.len();
+        let offset = self.display_offset.min(total_history);
+        let start = total_history - offset;
+        let end = (start + self.rows).min(total_history);
+        let live_rows_shown = self.rows - (end - start);
+
+        (start..end)
+            .map(move |i| self.scrollback.get(i).expect("index within scrollback bounds"))
+            .chain(self.grid[..live_rows_shown].iter())
+    }
+
+    /// Total number of rows addressable via `all_rows`/`visible_row_to_absolute`:
+    /// every scrollback line plus the live screen, oldest first.
+    pub fn total_rows(&self) -> usize {
+        self.scrollback.len() + self.rows
+    }
+
+    /// Iterate every row, scrollback history followed by the live screen,
+    /// oldest first - the address space `total_rows` describes. Used by
+    /// search so a query can match history, not just what's on screen.
+    pub fn all_rows(&self) -> impl Iterator<Item = &Vec<Cell>> {
+        (0..self.scrollback.len())
+            .map(move |i| self.scrollback.get(i).expect("index within scrollback bounds"))
+            .chain(self.grid.iter())
+    }
+
+    /// Whether row `row` in the `all_rows` address space soft-wraps into the
+    /// next one. Scrollback doesn't carry its own wrap bits today, so
+    /// history rows are treated as not wrapping - only `wrapped_rows`
+    /// entries for the live screen are consulted.
+    pub fn is_row_wrapped_absolute(&self, row: usize) -> bool {
+        let total_history = self.scrollback.len();
+        row >= total_history && self.is_row_wrapped(row - total_history)
+    }
+
+    /// Convert a viewport-relative row (as used by `get_visible_cell`) to
+    /// its absolute index in the `all_rows`/`total_rows` address space.
+    pub fn visible_row_to_absolute(&self, row: usize) -> usize {
+        let total_history = self.scrollback.len();
+        let offset = self.display_offset.min(total_history);
+        let start = total_history - offset;
+        let scrollback_rows_shown = offset.min(self.rows);
+
+        if row < scrollback_rows_shown {
+            start + row
+        } else {
+            total_history + (row - scrollback_rows_shown)
+        }
+    }
+
+    /// Get a cell at `(row, col)` in the `all_rows`/`total_rows` absolute
+    /// address space, regardless of the current scroll position - unlike
+    /// `get_visible_cell`, which is relative to the viewport. Used by vi
+    /// mode, which keeps its virtual cursor in absolute coordinates so it
+    /// doesn't jump around as the viewport pages through scrollback.
+    pub fn get_cell_absolute(&self, row: usize, col: usize) -> Option<&Cell> {
+        let total_history = self.scrollback.len();
+        if row < total_history {
+            self.scrollback.get(row)?.get(col)
+        } else {
+            self.grid.get(row - total_history)?.get(col)
+        }
+    }
+
+    /// Page the viewport so absolute row `row` (`all_rows` address space) is
+    /// the first visible line, or show the live screen if `row` is already
+    /// part of it. Used to bring a search match on-screen.
+    pub fn reveal_absolute_row(&mut self, row: usize) {
+        let total_history = self.scrollback.len();
+        if row < total_history {
+            self.set_scrollback(total_history - row);
+        } else {
+            self.set_scrollback(0);
+        }
+    }
+
+    /// Search for `pattern` (a regex) across scrollback plus the live grid,
+    /// returning matches in document order as `MatchRange`s in the
+    /// `all_rows`/`total_rows` absolute coordinate space. Matching follows
+    /// `wrapped_rows` so a match can span a soft-wrapped line break, capped
+    /// at `MAX_SEARCH_CONTINUATION_LINES` physical rows so a pathological
+    /// wrap chain can't make every search rescan unbounded history. An
+    /// empty or invalid pattern is an error rather than an empty result, so
+    /// callers can tell "searched, found nothing" from "didn't search".
+    pub fn search(&self, pattern: &str) -> GridSearchResult<Vec<MatchRange>> {
+        if pattern.is_empty() {
+            return Err(GridSearchError::EmptyPattern);
+        }
+        let regex = Regex::new(pattern).map_err(|e| GridSearchError::InvalidPattern(e.to_string()))?;
+
+        let rows: Vec<String> = self.all_rows().map(|row| Self::row_cells_to_string(row)).collect();
+
+        let mut matches = Vec::new();
+        let mut idx = 0;
+        while idx < rows.len() {
+            let start_idx = idx;
+            let mut logical = String::new();
+            let mut row_offsets: Vec<(usize, usize)> = Vec::new();
+
+            loop {
+                row_offsets.push((idx, logical.len()));
+                logical.push_str(&rows[idx]);
+                let wraps = self.is_row_wrapped_absolute(idx);
+                idx += 1;
+
+                let chain_len = idx - start_idx;
+                if !wraps || chain_len >= MAX_SEARCH_CONTINUATION_LINES || idx >= rows.len() {
+                    break;
+                }
+            }
+
+            for m in regex.find_iter(&logical) {
+                matches.push(MatchRange {
+                    start: Self::point_for_byte_offset(&row_offsets, m.start()),
+                    end: Self::point_for_byte_offset(&row_offsets, m.end()),
+                });
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// The first match after `origin` in document order, wrapping around to
+    /// the first match overall if `origin` is at or past the last one.
+    /// `origin` is in the same absolute coordinate space as `search`'s
+    /// results.
+    pub fn search_next(&self, origin: Cursor, pattern: &str) -> GridSearchResult<Option<MatchRange>> {
+        let matches = self.search(pattern)?;
+        let origin = (origin.row, origin.col);
+        Ok(matches.iter().find(|m| m.start > origin).or_else(|| matches.first()).copied())
+    }
+
+    /// The nearest match before `origin` in document order, wrapping around
+    /// to the last match overall if `origin` is at or before the first one.
+    pub fn search_prev(&self, origin: Cursor, pattern: &str) -> GridSearchResult<Option<MatchRange>> {
+        let matches = self.search(pattern)?;
+        let origin = (origin.row, origin.col);
+        Ok(matches.iter().rev().find(|m| m.start < origin).or_else(|| matches.last()).copied())
+    }
+
+    /// Render a row's cells to text for searching, skipping wide-char
+    /// spacer cells the same way `row_to_string` does.
+    fn row_cells_to_string(row: &[Cell]) -> String {
+        row.iter().filter(|cell| !cell.attributes.wide_char_spacer).map(|cell| cell.char).collect()
+    }
+
+    /// Map a byte offset into a reconstructed logical line back to the
+    /// absolute `(row, col)` it came from.
+    fn point_for_byte_offset(row_offsets: &[(usize, usize)], offset: usize) -> (usize, usize) {
+        let mut current = row_offsets[0];
+        for &(row, row_start) in row_offsets {
+            if row_start <= offset {
+                current = (row, row_start);
+            } else {
+                break;
+            }
+        }
+        (current.0, offset - current.1)
+    }
+
+    /// Get a cell at `(row, col)` in the *visible* viewport, i.e. the same
+    /// composited scrollback-then-live view `visible_rows` iterates.
+    /// Unlike `get_cell`, which always indexes the live grid, this reaches
+    /// into scrollback once `display_offset` pages the viewport back.
+    pub fn get_visible_cell(&self, row: usize, col: usize) -> Option<&Cell> {
+        let total_history = self.scrollback.len();
+        let offset = self.display_offset.min(total_history);
+        let start = total_history - offset;
+        let scrollback_rows_shown = offset.min(self.rows);
+
+        if row < scrollback_rows_shown {
+            self.scrollback.get(start + row)?.get(col)
+        } else {
+            self.grid.get(row - scrollback_rows_shown)?.get(col)
+        }
+    }
+
+    /// Get the content of a row as a string.
+    pub fn row_to_string(&self, row: usize) -> String {
+        if row >= self.rows {
+            return String::new();
+        }
+
+        self.grid[row]
+            .iter()
+            .filter(|cell| !cell.attributes.wide_char_spacer)
+            .map(|cell| cell.char)
+            .collect()
+    }
+
+    /// Render the live grid as a self-contained HTML fragment, wrapped in a
+    /// `<pre>` so whitespace and line breaks come through unchanged. Each
+    /// contiguous run of cells sharing the same resolved colors and
+    /// attributes becomes one `<span style="...">`, with `palette` resolving
+    /// `Color::Named`/`Color::Indexed` to concrete hex values the way a
+    /// renderer would. Lets downstream UI code offer "copy selection as rich
+    /// text" or save a session transcript.
+    pub fn to_html(&self, palette: &Palette) -> String {
+        let mut html = String::from("<pre>");
+        for row in 0..self.rows {
+            if row > 0 {
+                html.push('\n');
+            }
+            Self::push_row_html(&mut html, &self.grid[row], palette);
+        }
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Append one row's HTML to `html`, grouping its cells into `<span>`
+    /// runs by resolved style. Wide-char spacer cells are skipped, same as
+    /// `row_to_string`.
+    fn push_row_html(html: &mut String, row: &[Cell], palette: &Palette) {
+        let mut cells = row.iter().filter(|cell| !cell.attributes.wide_char_spacer);
+        let Some(first) = cells.next() else {
+            return;
+        };
+
+        let mut style = CellStyle::resolve(first, palette);
+        let mut text = String::new();
+        push_html_escaped(&mut text, first.char);
+
+        for cell in cells {
+            let cell_style = CellStyle::resolve(cell, palette);
+            if cell_style != style {
+                push_html_span(html, style, &text);
+                style = cell_style;
+                text.clear();
+            }
+            push_html_escaped(&mut text, cell.char);
+        }
+        push_html_span(html, style, &text);
+    }
+
+    /// Save the current cursor position and attributes.
+    pub fn save_cursor(&mut self) -> (Cursor, TextAttributes, Color, Color) {
+        (
+            self.cursor,
+            self.attributes,
+            self.fg_color,
+            self.bg_color,
+        )
+    }
+
+    /// Restore a previously saved cursor position and attributes.
+    pub fn restore_cursor(&mut self, saved: (Cursor, TextAttributes, Color, Color)) {
+        self.cursor = saved.0;
+        self.attributes = saved.1;
+        self.fg_color = saved.2;
+        self.bg_color = saved.3;
+    }
+
+    /// Reset the grid to initial state.
+    pub fn reset(&mut self) {
+        self.clear_screen();
+        self.cursor = Cursor::default();
+        self.attributes = TextAttributes::default();
+        self.fg_color = Color::Default;
+        self.bg_color = Color::Default;
+        self.display_offset = 0;
+    }
+
+    /// Whether the alternate screen buffer is currently displayed.
+    pub fn is_alt_screen(&self) -> bool {
+        self.saved_primary.is_some()
+    }
+
+    /// Switch to the alternate screen buffer, stashing the primary grid and
+    /// cursor/attribute state so `exit_alt_screen` can restore them. The
+    /// alternate buffer starts out fully cleared. Re-entering while already
+    /// on the alternate screen is a no-op, matching how real terminals
+    /// ignore a nested `?1049h`.
+    pub fn enter_alt_screen(&mut self) {
+        if self.saved_primary.is_some() {
+            return;
+        }
+
+        let primary_grid = std::mem::replace(&mut self.grid, vec![vec![Cell::default(); self.cols]; self.rows]);
+        self.saved_primary = Some((
+            primary_grid,
+            self.cursor,
+            self.attributes,
+            self.fg_color,
+            self.bg_color,
+            self.hyperlink.take(),
+        ));
+        self.cursor = Cursor::default();
+        self.mark_all_damaged();
+    }
+
+    /// Switch back to the primary screen buffer, restoring the grid and
+    /// cursor/attribute state saved by `enter_alt_screen`. A no-op if the
+    /// alternate screen isn't active.
+    pub fn exit_alt_screen(&mut self) {
+        if let Some((primary_grid, cursor, attributes, fg_color, bg_color, hyperlink)) = self.saved_primary.take() {
+            self.grid = primary_grid;
+            self.cursor = cursor;
+            self.attributes = attributes;
+            self.fg_color = fg_color;
+            self.bg_color = bg_color;
+            self.hyperlink = hyperlink;
+            self.mark_all_damaged();
+        }
+    }
+}
+
+impl Default for TerminalGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for TerminalGrid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let content: String = self.grid
             .iter()
@@ -737,6 +1931,10 @@ impl TerminalOutput for TerminalGrid {
         self.put_char(c);
     }
 
+    fn set_autowrap(&mut self, enabled: bool) {
+        self.set_autowrap(enabled);
+    }
+
     fn backspace(&mut self) {
         self.backspace();
     }
@@ -780,11 +1978,32 @@ impl TerminalOutput for TerminalGrid {
     fn erase_in_line(&mut self, mode: u16) {
         self.erase_in_line(mode);
     }
+
+    fn insert_chars(&mut self, n: usize) {
+        self.insert_chars(n);
+    }
+
+    fn delete_chars(&mut self, n: usize) {
+        self.delete_chars(n);
+    }
+
+    fn enter_alt_screen(&mut self) {
+        self.enter_alt_screen();
+    }
+
+    fn exit_alt_screen(&mut self) {
+        self.exit_alt_screen();
+    }
+
+    fn set_sixel_image(&mut self, image: SixelImage) {
+        self.set_sixel_image(image);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::parser::{NamedColor, TerminalParser};
 
     #[test]
     fn test_grid_creation() {
@@ -823,6 +2042,69 @@ mod tests {
         assert_eq!(grid.get_cell(0, 1).unwrap().char, 'i');
     }
 
+    #[test]
+    fn test_put_char_wide_writes_spacer_and_advances_cursor_by_two() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+
+        grid.put_char('\u{4e2d}'); // 中, a double-width CJK character
+
+        assert_eq!(grid.cursor().col, 2);
+        let leading = grid.get_cell(0, 0).unwrap();
+        assert_eq!(leading.char, '\u{4e2d}');
+        assert!(leading.attributes.wide_char);
+        let spacer = grid.get_cell(0, 1).unwrap();
+        assert_eq!(spacer.char, ' ');
+        assert!(spacer.attributes.wide_char_spacer);
+        assert_eq!(grid.row_to_string(0), "\u{4e2d}");
+    }
+
+    #[test]
+    fn test_put_char_wide_at_last_column_wraps_to_next_row() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        grid.move_cursor(0, 2);
+
+        grid.put_char('\u{4e2d}');
+
+        // The lone last column was blanked and marked wrapped...
+        assert_eq!(grid.get_cell(0, 2).unwrap().char, ' ');
+        assert!(grid.is_row_wrapped(0));
+        // ...and the wide char landed at the start of the next row instead.
+        assert_eq!(grid.cursor().row, 1);
+        assert_eq!(grid.cursor().col, 2);
+        assert_eq!(grid.get_cell(1, 0).unwrap().char, '\u{4e2d}');
+    }
+
+    #[test]
+    fn test_put_char_wide_at_last_column_clips_instead_of_wrapping_when_autowrap_is_off() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        grid.set_autowrap(false);
+        grid.move_cursor(0, 2);
+
+        grid.put_char('\u{4e2d}');
+
+        // Clipped in place: the last column is blanked, nothing spilled
+        // onto (or scrolled) the next row, and the row isn't marked wrapped.
+        assert_eq!(grid.get_cell(0, 2).unwrap().char, ' ');
+        assert!(!grid.is_row_wrapped(0));
+        assert_eq!(grid.get_cell(1, 0).unwrap().char, ' ');
+        assert_eq!(grid.cursor().row, 0);
+        assert_eq!(grid.cursor().col, 2);
+    }
+
+    #[test]
+    fn test_overwriting_half_of_a_wide_pair_clears_the_other_half() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.put_char('\u{4e2d}');
+
+        grid.move_cursor(0, 0);
+        grid.put_char('A');
+
+        assert_eq!(grid.get_cell(0, 0).unwrap().char, 'A');
+        let former_spacer = grid.get_cell(0, 1).unwrap();
+        assert_eq!(former_spacer.char, ' ');
+        assert!(!former_spacer.attributes.wide_char_spacer);
+    }
+
     #[test]
     fn test_put_char_with_color() {
         let mut grid = TerminalGrid::new();
@@ -841,13 +2123,13 @@ mod tests {
         let mut grid = TerminalGrid::new();
         let mut attrs = TextAttributes::default();
         attrs.bold = true;
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         grid.set_attributes(attrs);
 
         grid.put_char('B');
         let cell = grid.get_cell(0, 0).unwrap();
         assert!(cell.attributes.bold);
-        assert!(cell.attributes.underline);
+        assert_eq!(cell.attributes.underline, UnderlineStyle::Single);
     }
 
     #[test]
@@ -932,7 +2214,7 @@ mod tests {
 
         // Check scrollback
         assert_eq!(grid.scrollback_len(), 1);
-        assert_eq!(grid.scrollback()[0].iter().map(|c| c.char).collect::<String>(), "AAAAA");
+        assert_eq!(grid.scrollback().next().unwrap().iter().map(|c| c.char).collect::<String>(), "AAAAA");
     }
 
     #[test]
@@ -976,6 +2258,32 @@ mod tests {
         assert_eq!(grid.row_to_string(1), "BBBBB");
     }
 
+    #[test]
+    fn test_clear_line_uses_current_background_color() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+        grid.set_background(Color::Rgb(200, 0, 0));
+        grid.move_cursor(0, 0);
+        grid.put_char('A');
+
+        grid.clear_line();
+
+        for cell in &grid.as_rows()[0] {
+            assert_eq!(cell.bg_color, Color::Rgb(200, 0, 0));
+        }
+    }
+
+    #[test]
+    fn test_scroll_up_fills_new_bottom_row_with_current_background() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+        grid.set_background(Color::Rgb(0, 0, 200));
+
+        grid.scroll_up(1);
+
+        for cell in &grid.as_rows()[2] {
+            assert_eq!(cell.bg_color, Color::Rgb(0, 0, 200));
+        }
+    }
+
     #[test]
     fn test_clear_to_end_of_line() {
         let mut grid = TerminalGrid::with_size(5, 3);
@@ -1017,6 +2325,111 @@ mod tests {
         assert_eq!(grid.row_to_string(0), "ABCDE");
     }
 
+    #[test]
+    fn test_resize_reflows_a_wrapped_line_instead_of_truncating_it() {
+        let mut grid = TerminalGrid::with_size(6, 3);
+        grid.move_cursor(0, 0);
+        for c in "abcdefghi".chars() {
+            grid.put_char(c);
+        }
+        assert!(grid.is_row_wrapped(0));
+        assert_eq!(grid.row_to_string(0), "abcdef");
+        assert_eq!(grid.row_to_string(1), "ghi   ");
+
+        // Narrowing should re-wrap the same logical line at the new width
+        // rather than clipping "ghi" off the end.
+        grid.resize(3, 4);
+        assert_eq!(grid.row_to_string(0), "abc");
+        assert_eq!(grid.row_to_string(1), "def");
+        assert_eq!(grid.row_to_string(2), "ghi");
+        assert!(grid.is_row_wrapped(0));
+        assert!(grid.is_row_wrapped(1));
+        assert!(!grid.is_row_wrapped(2));
+
+        // Widening back should reassemble the original single line.
+        grid.resize(9, 4);
+        assert_eq!(grid.row_to_string(0), "abcdefghi");
+        assert!(!grid.is_row_wrapped(0));
+    }
+
+    #[test]
+    fn test_resize_reflow_does_not_balloon_blank_lines() {
+        let mut grid = TerminalGrid::with_size(20, 2);
+        grid.move_cursor(0, 0);
+        for c in "hi".chars() {
+            grid.put_char(c);
+        }
+        // Row 1 is left entirely blank.
+
+        grid.resize(4, 2);
+
+        assert_eq!(grid.row_to_string(0), "hi  ");
+        // A blank line should still take up exactly one row after
+        // narrowing, not ceil(20/4) = 5 rows of padding.
+        assert_eq!(grid.row_to_string(1), "    ");
+    }
+
+    #[test]
+    fn test_resize_shrink_then_widen_round_trips_a_ten_col_line() {
+        let mut grid = TerminalGrid::with_size(10, 2);
+        grid.move_cursor(0, 0);
+        for c in "abcdefghij".chars() {
+            grid.put_char(c);
+        }
+
+        grid.resize(5, 4);
+        let shrunk = grid.row_to_string(0) + &grid.row_to_string(1);
+        assert_eq!(shrunk, "abcdefghij");
+        assert!(grid.is_row_wrapped(0));
+
+        grid.resize(10, 4);
+        assert_eq!(grid.row_to_string(0), "abcdefghij");
+        assert!(!grid.is_row_wrapped(0));
+    }
+
+    #[test]
+    fn test_set_reflow_false_keeps_the_old_truncate_behavior() {
+        let mut grid = TerminalGrid::with_size(10, 2);
+        grid.set_reflow(false);
+        grid.move_cursor(0, 0);
+        for c in "abcdefghij".chars() {
+            grid.put_char(c);
+        }
+
+        grid.resize(5, 2);
+
+        // Without reflow, narrowing just truncates the row in place - "ghij"
+        // is gone rather than carried onto a second row.
+        assert_eq!(grid.row_to_string(0), "abcde");
+        assert!(!grid.is_row_wrapped(0));
+    }
+
+    #[test]
+    fn test_resize_reflow_overflow_pushes_oldest_lines_into_scrollback() {
+        let mut grid = TerminalGrid::with_scrollback(20, 3, 10);
+        grid.move_cursor(0, 0);
+        for c in "one".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(1, 0);
+        for c in "two".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(2, 0);
+        for c in "three".chars() {
+            grid.put_char(c);
+        }
+
+        // Narrowing to 1 row (and nudging the width so reflow - not the
+        // height-only fast path - runs) leaves only the last logical line
+        // on screen; the rest must land in scrollback rather than being
+        // dropped.
+        grid.resize(19, 1);
+
+        assert_eq!(grid.scrollback_len(), 2);
+        assert_eq!(grid.row_to_string(0).trim_end(), "three");
+    }
+
     #[test]
     fn test_scrollback() {
         let mut grid = TerminalGrid::with_scrollback(5, 2, 10);
@@ -1043,7 +2456,7 @@ mod tests {
         grid.scroll_up(1);
 
         assert_eq!(grid.scrollback_len(), 1);
-        assert_eq!(grid.scrollback()[0].iter().map(|c| c.char).collect::<String>(), "AAAAA");
+        assert_eq!(grid.scrollback().next().unwrap().iter().map(|c| c.char).collect::<String>(), "AAAAA");
     }
 
     #[test]
@@ -1089,6 +2502,18 @@ mod tests {
         assert_eq!(grid.cursor().col, 0);
     }
 
+    #[test]
+    fn test_backspace_steps_over_wide_char_spacer() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.put_char('\u{4e2d}'); // 中, lands at col 0-1, cursor now at col 2
+
+        grid.backspace();
+
+        // Backspace from col 2 should land on the wide char's leading cell
+        // (col 0), not its spacer (col 1).
+        assert_eq!(grid.cursor().col, 0);
+    }
+
     #[test]
     fn test_tab() {
         let mut grid = TerminalGrid::with_size(20, 5);
@@ -1134,6 +2559,25 @@ mod tests {
         assert_eq!(grid.row_to_string(0), "BBBBB"); // Shifted up
     }
 
+    #[test]
+    fn test_insert_delete_chars() {
+        let mut grid = TerminalGrid::with_size(5, 1);
+        grid.move_cursor(0, 0);
+        for ch in ['A', 'B', 'C', 'D', 'E'] {
+            grid.put_char(ch);
+        }
+
+        // Insert 2 blanks at column 1, pushing D and E off the end
+        grid.move_cursor(0, 1);
+        grid.insert_chars(2);
+        assert_eq!(grid.row_to_string(0), "A  BC");
+
+        // Delete 1 cell at column 0, shifting the rest left
+        grid.move_cursor(0, 0);
+        grid.delete_chars(1);
+        assert_eq!(grid.row_to_string(0), "  BC ");
+    }
+
     #[test]
     fn test_save_restore_cursor() {
         let mut grid = TerminalGrid::new();
@@ -1197,35 +2641,99 @@ mod tests {
     }
 
     #[test]
-    fn test_get_cell_out_of_bounds() {
-        let grid = TerminalGrid::with_size(10, 5);
+    fn test_set_scrollback_clamps_to_history_len() {
+        let mut grid = TerminalGrid::with_scrollback(5, 2, 10);
+        grid.scroll_up(3);
 
-        assert!(grid.get_cell(0, 0).is_some());
-        assert!(grid.get_cell(4, 9).is_some());
-        assert!(grid.get_cell(5, 0).is_none()); // Row out of bounds
-        assert!(grid.get_cell(0, 10).is_none()); // Col out of bounds
+        grid.set_scrollback(2);
+        assert_eq!(grid.display_offset(), 2);
+
+        // Requesting more than exists clamps to the available history.
+        grid.set_scrollback(100);
+        assert_eq!(grid.display_offset(), 3);
     }
 
     #[test]
-    fn test_reset() {
-        let mut grid = TerminalGrid::with_size(5, 3);
+    fn test_scroll_display_moves_by_signed_delta() {
+        let mut grid = TerminalGrid::with_scrollback(5, 2, 10);
+        grid.scroll_up(3);
 
-        grid.move_cursor(2, 4);
-        grid.set_foreground(Color::Indexed(1));
-        grid.set_background(Color::Rgb(50, 50, 50));
-        let mut attrs = TextAttributes::default();
-        attrs.bold = true;
-        grid.set_attributes(attrs);
+        grid.scroll_display(2);
+        assert_eq!(grid.display_offset(), 2);
 
-        grid.put_char('X');
+        grid.scroll_display(-5);
+        assert_eq!(grid.display_offset(), 0);
+    }
 
-        grid.reset();
+    #[test]
+    fn test_scroll_up_holds_viewport_steady_when_paged_back() {
+        let mut grid = TerminalGrid::with_scrollback(5, 3, 10);
+        for ch in ['A', 'B', 'C', 'D'] {
+            grid.move_cursor(0, 0);
+            for _ in 0..5 {
+                grid.put_char(ch);
+            }
+            grid.scroll_up(1);
+        }
 
-        assert_eq!(grid.cursor(), Cursor::default());
-        assert_eq!(grid.fg_color, Color::Default);
-        assert_eq!(grid.bg_color, Color::Default);
+        // Page back far enough that the whole viewport is inside history.
+        grid.scroll_display(3);
+        let before: Vec<String> = grid.visible_rows().map(|row| row.iter().map(|c| c.char).collect()).collect();
+
+        // New output arrives (and scrolls) while the viewport is paged back...
+        grid.move_cursor(0, 0);
+        for _ in 0..5 {
+            grid.put_char('E');
+        }
+        grid.scroll_up(1);
+
+        // ...the same history lines should still be on screen.
+        let after: Vec<String> = grid.visible_rows().map(|row| row.iter().map(|c| c.char).collect()).collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_reset_display_snaps_back_to_live_screen() {
+        let mut grid = TerminalGrid::with_scrollback(5, 2, 10);
+        grid.scroll_up(3);
+        grid.scroll_display(2);
+        assert_eq!(grid.display_offset(), 2);
+
+        grid.reset_display();
+
+        assert_eq!(grid.display_offset(), 0);
+    }
+
+    #[test]
+    fn test_get_cell_out_of_bounds() {
+        let grid = TerminalGrid::with_size(10, 5);
+
+        assert!(grid.get_cell(0, 0).is_some());
+        assert!(grid.get_cell(4, 9).is_some());
+        assert!(grid.get_cell(5, 0).is_none()); // Row out of bounds
+        assert!(grid.get_cell(0, 10).is_none()); // Col out of bounds
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+
+        grid.move_cursor(2, 4);
+        grid.set_foreground(Color::Indexed(1));
+        grid.set_background(Color::Rgb(50, 50, 50));
+        let mut attrs = TextAttributes::default();
+        attrs.bold = true;
+        grid.set_attributes(attrs);
+
+        grid.put_char('X');
+
+        grid.reset();
+
+        assert_eq!(grid.cursor(), Cursor::default());
+        assert_eq!(grid.fg_color, Color::Default);
+        assert_eq!(grid.bg_color, Color::Default);
         assert_eq!(grid.attributes(), TextAttributes::default());
-        assert!(grid.scrollback().is_empty());
+        assert_eq!(grid.scrollback().len(), 0);
 
         // All cells should be empty
         for row in grid.as_rows() {
@@ -1433,7 +2941,7 @@ mod tests {
         let mut attrs = TextAttributes::default();
         attrs.bold = true;
         attrs.italic = true;
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         attrs.blink = true;
 
         let cell = Cell::with_attributes('X', Color::Indexed(1), Color::Indexed(0), attrs);
@@ -1443,7 +2951,7 @@ mod tests {
         assert_eq!(cell.bg_color, Color::Indexed(0));
         assert!(cell.attributes.bold);
         assert!(cell.attributes.italic);
-        assert!(cell.attributes.underline);
+        assert_eq!(cell.attributes.underline, UnderlineStyle::Single);
         assert!(cell.attributes.blink);
     }
 
@@ -1477,12 +2985,12 @@ mod tests {
     fn test_put_char_with_underline() {
         let mut grid = TerminalGrid::new();
         let mut attrs = TextAttributes::default();
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         grid.set_attributes(attrs);
 
         grid.put_char('U');
         let cell = grid.get_cell(0, 0).unwrap();
-        assert!(cell.attributes.underline);
+        assert_eq!(cell.attributes.underline, UnderlineStyle::Single);
         assert_eq!(cell.char, 'U');
     }
 
@@ -1505,7 +3013,7 @@ mod tests {
         let mut attrs = TextAttributes::default();
         attrs.bold = true;
         attrs.italic = true;
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         attrs.blink = true;
         grid.set_attributes(attrs);
 
@@ -1513,7 +3021,7 @@ mod tests {
         let cell = grid.get_cell(0, 0).unwrap();
         assert!(cell.attributes.bold);
         assert!(cell.attributes.italic);
-        assert!(cell.attributes.underline);
+        assert_eq!(cell.attributes.underline, UnderlineStyle::Single);
         assert!(cell.attributes.blink);
         assert_eq!(cell.char, 'A');
     }
@@ -1523,7 +3031,7 @@ mod tests {
         let mut grid = TerminalGrid::new();
         let mut attrs = TextAttributes::default();
         attrs.bold = true;
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         grid.set_attributes(attrs);
 
         // Write multiple characters with same attributes
@@ -1535,9 +3043,9 @@ mod tests {
         let cell_b = grid.get_cell(0, 1).unwrap();
         let cell_c = grid.get_cell(0, 2).unwrap();
 
-        assert!(cell_a.attributes.bold && cell_a.attributes.underline);
-        assert!(cell_b.attributes.bold && cell_b.attributes.underline);
-        assert!(cell_c.attributes.bold && cell_c.attributes.underline);
+        assert!(cell_a.attributes.bold && cell_a.attributes.underline == UnderlineStyle::Single);
+        assert!(cell_b.attributes.bold && cell_b.attributes.underline == UnderlineStyle::Single);
+        assert!(cell_c.attributes.bold && cell_c.attributes.underline == UnderlineStyle::Single);
     }
 
     #[test]
@@ -1552,12 +3060,12 @@ mod tests {
 
         // Second character with underline (not bold)
         attrs.bold = false;
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         grid.set_attributes(attrs);
         grid.put_char('B');
 
         // Third character with italic
-        attrs.underline = false;
+        attrs.underline = UnderlineStyle::None;
         attrs.italic = true;
         grid.set_attributes(attrs);
         grid.put_char('C');
@@ -1566,9 +3074,9 @@ mod tests {
         let cell_b = grid.get_cell(0, 1).unwrap();
         let cell_c = grid.get_cell(0, 2).unwrap();
 
-        assert!(cell_a.attributes.bold && !cell_a.attributes.underline && !cell_a.attributes.italic);
-        assert!(!cell_b.attributes.bold && cell_b.attributes.underline && !cell_b.attributes.italic);
-        assert!(!cell_c.attributes.bold && !cell_c.attributes.underline && cell_c.attributes.italic);
+        assert!(cell_a.attributes.bold && cell_a.attributes.underline == UnderlineStyle::None && !cell_a.attributes.italic);
+        assert!(!cell_b.attributes.bold && cell_b.attributes.underline == UnderlineStyle::Single && !cell_b.attributes.italic);
+        assert!(!cell_c.attributes.bold && cell_c.attributes.underline == UnderlineStyle::None && cell_c.attributes.italic);
     }
 
     #[test]
@@ -1576,7 +3084,7 @@ mod tests {
         let mut attrs = TextAttributes::default();
         attrs.bold = true;
         attrs.italic = true;
-        attrs.underline = true;
+        attrs.underline = UnderlineStyle::Single;
         attrs.blink = true;
 
         let mut cell = Cell::with_attributes('X', Color::Default, Color::Default, attrs);
@@ -1598,7 +3106,7 @@ mod tests {
 
         assert!(retrieved.bold);
         assert!(retrieved.italic);
-        assert!(!retrieved.underline);
+        assert_eq!(retrieved.underline, UnderlineStyle::None);
         assert!(!retrieved.blink);
     }
 
@@ -1607,7 +3115,7 @@ mod tests {
         let attrs1 = TextAttributes {
             bold: true,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
             blink: false,
             ..Default::default()
         };
@@ -1615,7 +3123,7 @@ mod tests {
         let attrs2 = TextAttributes {
             bold: true,
             italic: false,
-            underline: false,
+            underline: UnderlineStyle::None,
             blink: false,
             ..Default::default()
         };
@@ -1646,4 +3154,536 @@ mod tests {
         assert!(attrs1.bold);
         assert!(!attrs2.bold);
     }
+
+    // ===== Hyperlink Tests =====
+
+    #[test]
+    fn test_set_hyperlink_tags_written_cells() {
+        let mut grid = TerminalGrid::with_size(10, 3);
+        let link = Arc::new(Hyperlink { id: None, uri: "https://example.com".to_string() });
+
+        grid.set_hyperlink(Some(link.clone()));
+        grid.put_char('A');
+        grid.set_hyperlink(None);
+        grid.put_char('B');
+
+        assert_eq!(grid.get_cell(0, 0).unwrap().hyperlink, Some(link));
+        assert!(grid.get_cell(0, 1).unwrap().hyperlink.is_none());
+    }
+
+    #[test]
+    fn test_exit_alt_screen_restores_open_hyperlink() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+        let link = Arc::new(Hyperlink { id: None, uri: "https://example.com".to_string() });
+
+        grid.set_hyperlink(Some(link.clone()));
+        grid.enter_alt_screen();
+        // The open link belongs to the primary screen's state, not the alt
+        // screen's - it's stashed away, not carried over.
+        grid.put_char('X');
+        assert!(grid.get_cell(0, 0).unwrap().hyperlink.is_none());
+
+        grid.exit_alt_screen();
+        grid.put_char('Y');
+        assert_eq!(grid.get_cell(0, 0).unwrap().hyperlink, Some(link));
+    }
+
+    #[test]
+    fn test_cells_for_hyperlink_at_expands_to_the_contiguous_run() {
+        let mut grid = TerminalGrid::with_size(10, 3);
+        let link = Arc::new(Hyperlink { id: None, uri: "https://example.com".to_string() });
+
+        grid.set_hyperlink(Some(link.clone()));
+        for c in "link".chars() {
+            grid.put_char(c);
+        }
+        grid.set_hyperlink(None);
+        grid.put_char(' ');
+        grid.put_char('X');
+
+        assert_eq!(
+            grid.cells_for_hyperlink_at(0, 1),
+            vec![(0, 0), (0, 1), (0, 2), (0, 3)]
+        );
+        // Clicking outside the link (the plain "X") finds nothing.
+        assert!(grid.cells_for_hyperlink_at(0, 5).is_empty());
+    }
+
+    #[test]
+    fn test_clearing_mid_line_drops_the_hyperlink() {
+        let mut grid = TerminalGrid::with_size(10, 3);
+        let link = Arc::new(Hyperlink { id: None, uri: "https://example.com".to_string() });
+
+        grid.set_hyperlink(Some(link.clone()));
+        for c in "link".chars() {
+            grid.put_char(c);
+        }
+        grid.set_hyperlink(None);
+
+        grid.move_cursor(0, 1);
+        grid.clear_to_end_of_line();
+
+        assert!(grid.get_cell(0, 1).unwrap().hyperlink.is_none());
+        // The untouched leading cell keeps its link.
+        assert_eq!(grid.get_cell(0, 0).unwrap().hyperlink, Some(link));
+    }
+
+    // ===== Alternate Screen Buffer Tests =====
+
+    #[test]
+    fn test_enter_alt_screen_clears_and_tracks_state() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+
+        grid.move_cursor(1, 2);
+        for c in "ABCDE".chars() {
+            grid.put_char(c);
+        }
+
+        assert!(!grid.is_alt_screen());
+        grid.enter_alt_screen();
+
+        assert!(grid.is_alt_screen());
+        assert_eq!(grid.cursor(), Cursor::origin());
+        for row in grid.as_rows() {
+            for cell in row {
+                assert!(cell.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_exit_alt_screen_restores_primary() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+
+        grid.move_cursor(0, 0);
+        for c in "ABCDE".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(1, 1);
+
+        grid.enter_alt_screen();
+        grid.move_cursor(2, 2);
+        grid.put_char('X');
+
+        grid.exit_alt_screen();
+
+        assert!(!grid.is_alt_screen());
+        assert_eq!(grid.cursor(), Cursor::new(1, 1));
+        assert_eq!(grid.row_to_string(0), "ABCDE");
+    }
+
+    #[test]
+    fn test_alt_screen_scrolling_does_not_pollute_scrollback() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+        grid.enter_alt_screen();
+
+        // A full-screen app scrolling its own content shouldn't leak into
+        // the primary screen's history.
+        for _ in 0..10 {
+            grid.scroll_up(1);
+        }
+
+        assert_eq!(grid.scrollback_len(), 0);
+    }
+
+    #[test]
+    fn test_nested_enter_alt_screen_is_noop() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+
+        grid.move_cursor(0, 0);
+        for c in "ABCDE".chars() {
+            grid.put_char(c);
+        }
+
+        grid.enter_alt_screen();
+        grid.move_cursor(0, 0);
+        grid.put_char('Z');
+        grid.enter_alt_screen(); // should not stash the already-alternate grid
+
+        grid.exit_alt_screen();
+        assert_eq!(grid.row_to_string(0), "ABCDE");
+    }
+
+    #[test]
+    fn test_exit_alt_screen_without_entering_is_noop() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+        grid.move_cursor(1, 1);
+
+        grid.exit_alt_screen();
+
+        assert_eq!(grid.cursor(), Cursor::new(1, 1));
+        assert!(!grid.is_alt_screen());
+    }
+
+    #[test]
+    fn test_resize_while_on_alt_screen_keeps_stashed_primary_in_sync() {
+        let mut grid = TerminalGrid::with_size(5, 3);
+        grid.move_cursor(0, 0);
+        for c in "ABCDE".chars() {
+            grid.put_char(c);
+        }
+
+        grid.enter_alt_screen();
+        grid.resize(8, 4);
+        grid.exit_alt_screen();
+
+        // The restored primary grid must match the new dimensions exactly,
+        // or a subsequent put_char/resize would panic on an out-of-bounds
+        // row/column.
+        assert_eq!(grid.cols(), 8);
+        assert_eq!(grid.rows(), 4);
+        assert_eq!(grid.row_to_string(0), "ABCDE   ");
+    }
+
+    #[test]
+    fn test_new_grid_is_fully_damaged() {
+        let grid = TerminalGrid::with_size(10, 5);
+        assert_eq!(grid.damaged_rows().len(), 5);
+        assert!(grid.is_damaged());
+    }
+
+    #[test]
+    fn test_reset_damage_clears_the_set() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+        assert!(!grid.is_damaged());
+        assert!(grid.damaged_rows().is_empty());
+    }
+
+    #[test]
+    fn test_put_char_damages_only_the_cursor_row() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+
+        grid.put_char('x');
+
+        assert_eq!(grid.damaged_rows(), &BTreeSet::from([0]));
+    }
+
+    #[test]
+    fn test_move_cursor_damages_old_and_new_row() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.move_cursor(3, 2);
+        grid.reset_damage();
+
+        grid.move_cursor(1, 0);
+
+        assert_eq!(grid.damaged_rows(), &BTreeSet::from([3, 1]));
+    }
+
+    #[test]
+    fn test_resize_marks_whole_grid_damaged() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+
+        grid.resize(10, 8);
+
+        assert_eq!(grid.damaged_rows().len(), 8);
+    }
+
+    #[test]
+    fn test_scroll_up_marks_whole_grid_damaged() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+
+        grid.scroll_up(1);
+
+        assert_eq!(grid.damaged_rows().len(), 5);
+    }
+
+    #[test]
+    fn test_clear_screen_marks_whole_grid_damaged() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+
+        grid.clear_screen();
+
+        assert_eq!(grid.damaged_rows().len(), 5);
+    }
+
+    #[test]
+    fn test_clear_line_damages_only_cursor_row() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.move_cursor(2, 0);
+        grid.reset_damage();
+
+        grid.clear_line();
+
+        assert_eq!(grid.damaged_rows(), &BTreeSet::from([2]));
+    }
+
+    #[test]
+    fn test_take_damage_reports_one_dirty_line_for_a_single_put_char() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+
+        grid.put_char('x');
+
+        assert_eq!(grid.take_damage(), Damage::Lines(vec![0]));
+    }
+
+    #[test]
+    fn test_take_damage_reports_full_after_scroll_up() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.reset_damage();
+
+        grid.scroll_up(1);
+
+        assert_eq!(grid.take_damage(), Damage::Full);
+    }
+
+    #[test]
+    fn test_take_damage_clears_state_for_the_next_call() {
+        let mut grid = TerminalGrid::with_size(10, 5);
+        grid.put_char('x');
+
+        assert_eq!(grid.take_damage(), Damage::Full);
+        assert_eq!(grid.take_damage(), Damage::Lines(vec![]));
+    }
+
+    #[test]
+    fn test_put_char_auto_wrap_marks_row_wrapped() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        for c in "abcd".chars() {
+            grid.put_char(c);
+        }
+        assert!(grid.is_row_wrapped(0));
+        assert!(!grid.is_row_wrapped(1));
+    }
+
+    #[test]
+    fn test_clear_line_clears_wrapped_flag() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        for c in "abcd".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(0, 0);
+        grid.clear_line();
+        assert!(!grid.is_row_wrapped(0));
+    }
+
+    #[test]
+    fn test_resize_clears_wrapped_rows() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        for c in "abcd".chars() {
+            grid.put_char(c);
+        }
+        grid.resize(3, 3);
+        assert!(!grid.is_row_wrapped(0));
+    }
+
+    #[test]
+    fn test_search_finds_matches_in_document_order() {
+        let mut grid = TerminalGrid::with_size(10, 3);
+        for c in "foo".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(1, 0);
+        for c in "foobar".chars() {
+            grid.put_char(c);
+        }
+
+        let matches = grid.search("foo").unwrap();
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].start, (0, 0));
+        assert_eq!(matches[1].start, (1, 0));
+    }
+
+    #[test]
+    fn test_search_spans_a_soft_wrapped_boundary() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        for c in "abcd".chars() {
+            grid.put_char(c);
+        }
+
+        let matches = grid.search("cd").unwrap();
+
+        assert_eq!(matches, vec![MatchRange { start: (0, 2), end: (1, 1) }]);
+    }
+
+    #[test]
+    fn test_search_finds_a_match_only_in_scrollback() {
+        let mut grid = TerminalGrid::with_scrollback(10, 2, 5);
+        for c in "needle".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(0, 0);
+        grid.scroll_up(1);
+
+        // "needle" has scrolled off into history; nothing on the live
+        // screen should match, but `search` still finds it there.
+        let matches = grid.search("needle").unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].start, (0, 0));
+    }
+
+    #[test]
+    fn test_search_rejects_an_empty_pattern() {
+        let grid = TerminalGrid::with_size(10, 3);
+        assert!(matches!(grid.search(""), Err(GridSearchError::EmptyPattern)));
+    }
+
+    #[test]
+    fn test_search_rejects_an_invalid_regex() {
+        let grid = TerminalGrid::with_size(10, 3);
+        assert!(matches!(grid.search("("), Err(GridSearchError::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn test_search_next_wraps_around_to_the_first_match() {
+        let mut grid = TerminalGrid::with_size(10, 3);
+        grid.move_cursor(0, 0);
+        for c in "foo".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(2, 0);
+        for c in "foo".chars() {
+            grid.put_char(c);
+        }
+
+        let last = grid.search_next(Cursor::new(2, 0), "foo").unwrap().unwrap();
+        assert_eq!(last.start, (0, 0));
+    }
+
+    #[test]
+    fn test_search_prev_finds_the_nearest_match_before_origin() {
+        let mut grid = TerminalGrid::with_size(10, 3);
+        grid.move_cursor(0, 0);
+        for c in "foo".chars() {
+            grid.put_char(c);
+        }
+        grid.move_cursor(2, 0);
+        for c in "foo".chars() {
+            grid.put_char(c);
+        }
+
+        let prev = grid.search_prev(Cursor::new(2, 0), "foo").unwrap().unwrap();
+        assert_eq!(prev.start, (0, 0));
+    }
+
+    #[test]
+    fn test_irm_insert_mode_shifts_line_right_through_parser() {
+        let mut parser = TerminalParser::with_size(10, 3);
+        let mut grid = TerminalGrid::with_size(10, 3);
+
+        // Write "World" then go back to col 0 and, with insert mode on,
+        // type "Hi " in front of it instead of overwriting it.
+        parser.parse_bytes_with_output(b"World", &mut grid);
+        parser.parse_bytes_with_output(b"\x1B[4h\x1B[1;1HHi ", &mut grid);
+
+        let row: String = (0..8).map(|c| grid.get_cell(0, c).unwrap().char).collect();
+        assert_eq!(row, "Hi World");
+    }
+
+    #[test]
+    fn test_line_wrap_disabled_overwrites_last_column_through_parser() {
+        let mut parser = TerminalParser::with_size(5, 3);
+        let mut grid = TerminalGrid::with_size(5, 3);
+
+        // CSI ? 7 l turns off autowrap (DECAWM); writing past the last
+        // column should keep pinning the cursor there and overwriting it
+        // instead of wrapping onto the next row.
+        parser.parse_bytes_with_output(b"\x1B[?7lHello!!!", &mut grid);
+
+        assert_eq!(grid.get_cell(0, 4).unwrap().char, '!');
+        assert_eq!(grid.get_cell(1, 0).unwrap().char, ' ');
+        // Plain printing only moves the grid's own cursor, not
+        // `ParserState::cursor` - the grid is the authoritative source here.
+        assert_eq!(grid.cursor().row, 0);
+        assert_eq!(grid.cursor().col, 4);
+    }
+
+    #[test]
+    fn test_line_wrap_disabled_clips_wide_char_at_last_column_through_parser() {
+        let mut parser = TerminalParser::with_size(5, 3);
+        let mut grid = TerminalGrid::with_size(5, 3);
+
+        // CSI ? 7 l turns off autowrap; a double-width character that
+        // doesn't fit in the last column should be clipped there, not
+        // wrapped (and possibly scrolled) onto the next row out from under
+        // the cursor correction below it.
+        let input = format!("\x1B[?7lABCD{}", '\u{4e2d}');
+        parser.parse_bytes_with_output(input.as_bytes(), &mut grid);
+
+        assert_eq!(grid.get_cell(0, 4).unwrap().char, ' ');
+        assert_eq!(grid.get_cell(1, 0).unwrap().char, ' ');
+        assert_eq!(grid.cursor().row, 0);
+        assert_eq!(grid.cursor().col, 4);
+    }
+
+    #[test]
+    fn test_to_html_escapes_reserved_characters() {
+        let mut grid = TerminalGrid::with_size(5, 1);
+        for c in "<a&b>".chars() {
+            grid.put_char(c);
+        }
+
+        let html = grid.to_html(&Palette::default());
+        assert!(html.contains("&lt;a&amp;b&gt;"));
+    }
+
+    #[test]
+    fn test_to_html_splits_spans_at_attribute_boundaries() {
+        let mut grid = TerminalGrid::with_size(5, 1);
+        grid.put_char('a');
+
+        let mut bold = TextAttributes::default();
+        bold.bold = true;
+        grid.set_attributes(bold);
+        grid.put_char('b');
+        grid.put_char('c');
+
+        grid.set_attributes(TextAttributes::default());
+        grid.put_char('d');
+
+        let html = grid.to_html(&Palette::default());
+        // "a" and "d" share plain attributes but aren't adjacent, so the
+        // runs are "a", "bc" (bold), "d" - three spans, not a merged two.
+        assert_eq!(html.matches("<span").count(), 3);
+        let bold_span_start = html.find("font-weight:bold").unwrap();
+        let bc_span = &html[html[..bold_span_start].rfind("<span").unwrap()..];
+        assert!(bc_span.starts_with("<span style=\"") && bc_span.contains(">bc</span>"));
+    }
+
+    #[test]
+    fn test_to_html_resolves_named_color_through_palette() {
+        let mut grid = TerminalGrid::with_size(5, 1);
+        grid.set_foreground(Color::Named(NamedColor::Red));
+        grid.put_char('x');
+
+        let palette = Palette::default();
+        let expected = palette.resolve(Color::Named(NamedColor::Red));
+        let html = grid.to_html(&palette);
+        assert!(html.contains(&format!("color:#{:02x}{:02x}{:02x}", expected.r, expected.g, expected.b)));
+    }
+
+    #[test]
+    fn test_to_html_swaps_colors_for_reverse_video() {
+        let mut grid = TerminalGrid::with_size(5, 1);
+        let mut attrs = TextAttributes::default();
+        attrs.reverse = true;
+        grid.set_attributes(attrs);
+        grid.set_foreground(Color::Rgb(10, 20, 30));
+        grid.set_background(Color::Rgb(40, 50, 60));
+        grid.put_char('x');
+
+        let html = grid.to_html(&Palette::default());
+        assert!(html.contains("color:#28323c")); // was bg, now fg
+        assert!(html.contains("background-color:#0a141e")); // was fg, now bg
+    }
+
+    #[test]
+    fn test_to_html_wraps_rows_in_a_single_pre_block() {
+        let mut grid = TerminalGrid::with_size(3, 2);
+        grid.put_char('a');
+        grid.move_cursor(1, 0);
+        grid.put_char('b');
+
+        let html = grid.to_html(&Palette::default());
+        assert!(html.starts_with("<pre>"));
+        assert!(html.ends_with("</pre>"));
+        assert_eq!(html.matches('\n').count(), 1);
+    }
 }
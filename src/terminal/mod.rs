@@ -0,0 +1,8 @@
+//! Terminal emulation: PTY management, ANSI parsing, and the screen grid.
+
+pub mod grid;
+pub mod parser;
+pub mod pty;
+pub mod pty_record;
+pub mod shell;
+pub mod sixel;
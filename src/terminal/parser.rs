@@ -5,8 +5,292 @@
 
 #![allow(dead_code)]
 
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bitflags::bitflags;
+use unicode_width::UnicodeWidthChar;
 use vte::{Params, Perform};
 
+use super::sixel;
+
+bitflags! {
+    /// Terminal mode flags, mostly toggled by DEC private mode sequences
+    /// (`CSI ? Pm h/l`) - `INSERT` is the one exception, set by the plain
+    /// ANSI form (`CSI Pm h/l`, no `?`).
+    ///
+    /// Modeled after Alacritty's `TermMode`: each bit tracks a mode the host
+    /// embedding (or the parser itself) needs to branch on, e.g. whether to
+    /// encode cursor keys for application mode or whether printable text should
+    /// wrap at the right margin.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TermMode: u32 {
+        /// Cursor is visible (DECTCEM, `CSI ? 25 h/l`).
+        const SHOW_CURSOR = 1 << 0;
+        /// Application cursor keys (DECCKM, `CSI ? 1 h/l`).
+        const APP_CURSOR = 1 << 1;
+        /// Application keypad (DECKPAM/DECKPNM).
+        const APP_KEYPAD = 1 << 2;
+        /// Bracketed paste mode (`CSI ? 2004 h/l`).
+        const BRACKETED_PASTE = 1 << 3;
+        /// Alternate screen buffer is active (`CSI ? 1049 h/l`).
+        const ALT_SCREEN = 1 << 4;
+        /// Autowrap at the right margin (DECAWM, `CSI ? 7 h/l`).
+        const LINE_WRAP = 1 << 5;
+        /// Origin mode (DECOM, `CSI ? 6 h/l`) - cursor addressing relative to the scroll region.
+        const ORIGIN = 1 << 6;
+        /// Report mouse button clicks (`CSI ? 1000 h/l`).
+        const MOUSE_REPORT_CLICK = 1 << 7;
+        /// Report focus in/out events (`CSI ? 1004 h/l`).
+        const REPORT_FOCUS = 1 << 8;
+        /// Render BEL as a visible flash instead of an audible beep
+        /// (`CSI ? 1042 h/l`), matching xterm's "bell is urgent" extension.
+        const VISUAL_BELL = 1 << 9;
+        /// Also report motion while a button is held (`CSI ? 1002 h/l`).
+        const MOUSE_REPORT_DRAG = 1 << 10;
+        /// Report all motion, button held or not (`CSI ? 1003 h/l`).
+        const MOUSE_REPORT_ANY_MOTION = 1 << 11;
+        /// Encode mouse reports with SGR extended coordinates
+        /// (`CSI ? 1006 h/l`) instead of the legacy X10 byte encoding -
+        /// the only encoding `ParserState::mouse_reporting` knows how to
+        /// produce, so reporting stays off until this is also set.
+        const MOUSE_REPORT_SGR = 1 << 12;
+        /// Insert mode (IRM, `CSI 4 h/l` - an ANSI mode, not DEC private).
+        /// While set, printable characters push the rest of the line right
+        /// instead of overwriting it.
+        const INSERT = 1 << 13;
+    }
+}
+
+/// Mouse-report granularity requested by DECSET `1000`/`1002`/`1003`, as
+/// returned by `ParserState::mouse_reporting`. Ordered loosest to
+/// broadest; a higher mode implies the client also wants everything a
+/// lower one would report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseReportMode {
+    /// Button presses/releases only (`1000`).
+    Click,
+    /// Also report motion while a button is held (`1002`).
+    Drag,
+    /// Report all motion, button held or not (`1003`).
+    AnyMotion,
+}
+
+impl Default for TermMode {
+    fn default() -> Self {
+        Self::SHOW_CURSOR | Self::LINE_WRAP
+    }
+}
+
+/// Map an SGR 30-37/90-97/40-47/100-107 offset (0-7) to the matching
+/// standard or bright `NamedColor` slot.
+fn standard_named_color(offset: u16, bright: bool) -> NamedColor {
+    match (offset, bright) {
+        (0, false) => NamedColor::Black,
+        (1, false) => NamedColor::Red,
+        (2, false) => NamedColor::Green,
+        (3, false) => NamedColor::Yellow,
+        (4, false) => NamedColor::Blue,
+        (5, false) => NamedColor::Magenta,
+        (6, false) => NamedColor::Cyan,
+        (0, true) => NamedColor::BrightBlack,
+        (1, true) => NamedColor::BrightRed,
+        (2, true) => NamedColor::BrightGreen,
+        (3, true) => NamedColor::BrightYellow,
+        (4, true) => NamedColor::BrightBlue,
+        (5, true) => NamedColor::BrightMagenta,
+        (6, true) => NamedColor::BrightCyan,
+        (_, false) => NamedColor::White,
+        (_, true) => NamedColor::BrightWhite,
+    }
+}
+
+/// Apply a DEC private mode (`CSI ? Pm h/l`) to a `TermMode` set.
+///
+/// Unknown mode numbers are ignored, matching how real terminals silently
+/// accept private modes they don't implement.
+fn apply_private_mode(mode: &mut TermMode, code: u16, enable: bool) {
+    let flag = match code {
+        1 => TermMode::APP_CURSOR,
+        7 => TermMode::LINE_WRAP,
+        25 => TermMode::SHOW_CURSOR,
+        47 | 1047 | 1049 => TermMode::ALT_SCREEN,
+        1000 => TermMode::MOUSE_REPORT_CLICK,
+        1002 => TermMode::MOUSE_REPORT_DRAG,
+        1003 => TermMode::MOUSE_REPORT_ANY_MOTION,
+        1006 => TermMode::MOUSE_REPORT_SGR,
+        2004 => TermMode::BRACKETED_PASTE,
+        1004 => TermMode::REPORT_FOCUS,
+        1042 => TermMode::VISUAL_BELL,
+        _ => return,
+    };
+    mode.set(flag, enable);
+}
+
+/// Whether a DEC private mode code requests the alternate screen buffer.
+/// `1049` additionally saves/restores the cursor around the swap; `47` and
+/// `1047` are the older variants that just swap the visible buffer.
+fn is_alt_screen_code(code: u16) -> bool {
+    matches!(code, 47 | 1047 | 1049)
+}
+
+/// Build the default tab stop table: every 8th column, matching the
+/// hardcoded `col & !7` behavior this table replaces.
+fn default_tab_stops(cols: usize) -> BTreeSet<u16> {
+    (8..cols as u16).step_by(8).collect()
+}
+
+/// Which screen buffer is currently displayed, toggled by the alternate
+/// screen DEC private modes (`CSI ? 47/1047/1049 h/l`). Full-screen
+/// applications (vim, less, ...) switch to `Alternate` so they can paint
+/// without disturbing the primary screen's scrollback, then switch back to
+/// `Normal` on exit to cleanly return the user's shell content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenBuffer {
+    #[default]
+    Normal,
+    Alternate,
+}
+
+/// Cursor shape selected via DECSCUSR (`CSI Ps SP q`), for a renderer to
+/// draw the correct caret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorStyle {
+    #[default]
+    Block,
+    BlinkingBlock,
+    Underline,
+    BlinkingUnderline,
+    Beam,
+    BlinkingBeam,
+    HollowBlock,
+}
+
+/// Underline rendering style. Beyond the legacy on/off underline (SGR
+/// `4`/`24`), terminals including xterm, kitty, and wezterm support a
+/// colon-form extension (`CSI 4:0-5 m`) selecting one of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnderlineStyle {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+/// Map the colon subparameter of an extended-underline SGR group
+/// (`CSI 4:Ps m`) onto an `UnderlineStyle`. Unrecognized values fall back to
+/// a plain single underline, the same as the legacy bare `CSI 4 m`.
+fn underline_style_from_subparam(subparam: u16) -> UnderlineStyle {
+    match subparam {
+        0 => UnderlineStyle::None,
+        2 => UnderlineStyle::Double,
+        3 => UnderlineStyle::Curly,
+        4 => UnderlineStyle::Dotted,
+        5 => UnderlineStyle::Dashed,
+        _ => UnderlineStyle::Single,
+    }
+}
+
+/// Pull a colon-form extended-underline group (`4:Ps`, e.g. `CSI 4:3 m` for
+/// a curly underline) out of an SGR parameter list, since flattening it in
+/// with the semicolon-separated codes around it would otherwise make `Ps`
+/// read as an unrelated SGR code of its own. Returns the remaining
+/// parameters flattened for the normal code-by-code scan, plus the
+/// underline style the extended group requested, if there was one.
+fn extract_extended_underline(params: &[Vec<u16>]) -> (Vec<u16>, Option<UnderlineStyle>) {
+    let mut style = None;
+    let mut flat = Vec::with_capacity(params.len());
+    for group in params {
+        if style.is_none() && group.len() > 1 && group[0] == 4 {
+            style = Some(underline_style_from_subparam(group[1]));
+            continue;
+        }
+        flat.extend(group.iter().copied());
+    }
+    (flat, style)
+}
+
+/// Map a DECSCUSR `Ps` parameter (0-6) onto a `CursorStyle`. Parameters
+/// outside that range are ignored, matching how real terminals keep the
+/// prior style for a shape they don't implement.
+fn cursor_style_from_param(param: u16) -> Option<CursorStyle> {
+    match param {
+        0 => Some(CursorStyle::Block),
+        1 => Some(CursorStyle::BlinkingBlock),
+        2 => Some(CursorStyle::Underline),
+        3 => Some(CursorStyle::BlinkingUnderline),
+        4 => Some(CursorStyle::Beam),
+        5 => Some(CursorStyle::BlinkingBeam),
+        6 => Some(CursorStyle::HollowBlock),
+        _ => None,
+    }
+}
+
+/// Which of the two charset slots (`ESC (` for G0, `ESC )` for G1) is
+/// currently selected for printable output, toggled live by SI (`0x0F`,
+/// select G0) / SO (`0x0E`, select G1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CharsetSlot {
+    #[default]
+    G0,
+    G1,
+}
+
+/// A charset that can be designated into a `CharsetSlot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    /// Plain ASCII - printable bytes pass through unchanged.
+    #[default]
+    Ascii,
+    /// DEC Special Graphics, designated with `ESC ( 0` / `ESC ) 0` - remaps
+    /// 0x60-0x7E onto line-drawing glyphs so box-drawing TUIs render
+    /// properly instead of as literal ASCII like `qqqq` or `lqk`.
+    SpecialGraphics,
+}
+
+/// Which `ESC ( `/`ESC )` intermediate selects which charset slot.
+fn charset_slot_for_intermediate(intermediate: Option<u8>) -> Option<CharsetSlot> {
+    match intermediate {
+        Some(b'(') => Some(CharsetSlot::G0),
+        Some(b')') => Some(CharsetSlot::G1),
+        _ => None,
+    }
+}
+
+/// Which charset a designation final byte selects. Unrecognized finals
+/// (e.g. other national replacement charsets) are ignored, matching how
+/// real terminals silently keep the prior charset for slots they don't implement.
+fn charset_for_designator(byte: u8) -> Option<Charset> {
+    match byte {
+        b'0' => Some(Charset::SpecialGraphics),
+        b'B' => Some(Charset::Ascii),
+        _ => None,
+    }
+}
+
+/// Map a byte in the DEC Special Graphics range (0x60-0x7E) to its
+/// line-drawing glyph. Bytes outside the mapped set pass through unchanged.
+fn special_graphics_char(c: char) -> char {
+    match c {
+        'j' => '┘',
+        'k' => '┐',
+        'l' => '┌',
+        'm' => '└',
+        'n' => '┼',
+        'q' => '─',
+        't' => '├',
+        'u' => '┤',
+        'v' => '┴',
+        'w' => '┬',
+        'x' => '│',
+        other => other,
+    }
+}
+
 /// Represents a color (either as an index into a palette or as an RGB value).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Color {
@@ -17,6 +301,606 @@ pub enum Color {
     Indexed(u8),
     /// 24-bit RGB color
     Rgb(u8, u8, u8),
+    /// One of the 16 standard/bright ANSI slots, or the default fg/bg/cursor.
+    Named(NamedColor),
+}
+
+/// The 16 standard/bright ANSI color slots, plus the default foreground,
+/// background, and cursor colors - all of which are resolved through a
+/// `Palette` and can be recolored at runtime via OSC 4/10/11/12.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Foreground,
+    Background,
+    Cursor,
+}
+
+impl NamedColor {
+    /// The palette index backing this color, for the 16 standard/bright slots.
+    /// Returns `None` for `Foreground`/`Background`/`Cursor`, which aren't
+    /// part of the 256-color index space.
+    fn palette_index(self) -> Option<u8> {
+        match self {
+            NamedColor::Black => Some(0),
+            NamedColor::Red => Some(1),
+            NamedColor::Green => Some(2),
+            NamedColor::Yellow => Some(3),
+            NamedColor::Blue => Some(4),
+            NamedColor::Magenta => Some(5),
+            NamedColor::Cyan => Some(6),
+            NamedColor::White => Some(7),
+            NamedColor::BrightBlack => Some(8),
+            NamedColor::BrightRed => Some(9),
+            NamedColor::BrightGreen => Some(10),
+            NamedColor::BrightYellow => Some(11),
+            NamedColor::BrightBlue => Some(12),
+            NamedColor::BrightMagenta => Some(13),
+            NamedColor::BrightCyan => Some(14),
+            NamedColor::BrightWhite => Some(15),
+            NamedColor::Foreground | NamedColor::Background | NamedColor::Cursor => None,
+        }
+    }
+}
+
+/// A concrete, resolved 24-bit RGB color - the output of `TerminalParser::resolve`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+/// Parse an `rgb:RR/GG/BB`-style color spec as used by OSC 4/10/11/12 (each
+/// component may be 1-4 hex digits; values are scaled to 8 bits).
+fn parse_rgb_spec(spec: &[u8]) -> Option<Rgb> {
+    let spec = std::str::from_utf8(spec).ok()?;
+    let rest = spec.strip_prefix("rgb:")?;
+    let mut parts = rest.split('/');
+    let r = parse_hex_channel(parts.next()?)?;
+    let g = parse_hex_channel(parts.next()?)?;
+    let b = parse_hex_channel(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Rgb::new(r, g, b))
+}
+
+/// Parse one `/`-separated hex channel from an `rgb:` spec, scaling it to 8 bits.
+fn parse_hex_channel(hex: &str) -> Option<u8> {
+    if hex.is_empty() || hex.len() > 4 {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1u32 << (hex.len() * 4)) - 1;
+    Some(((value * 255 + max / 2) / max) as u8)
+}
+
+/// Parse an XParseColor-style color spec, as accepted by OSC 4/10/11/12:
+/// either legacy `#rrggbb`/`#rgb` hex (an equal-length run of hex digits per
+/// channel) or `rgb:rr/gg/bb` (handled by `parse_rgb_spec`). Either form
+/// scales each component to 8 bits by `value * 255 / max`, where `max`
+/// depends on the hex-digit count.
+fn xparse_color(spec: &[u8]) -> Option<Color> {
+    let text = std::str::from_utf8(spec).ok()?;
+
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.is_empty() || hex.len() % 3 != 0 || hex.len() > 12 {
+            return None;
+        }
+        let channel_len = hex.len() / 3;
+        let mut channels = [0u8; 3];
+        for (channel, chunk) in channels.iter_mut().zip(hex.as_bytes().chunks(channel_len)) {
+            *channel = parse_hex_channel(std::str::from_utf8(chunk).ok()?)?;
+        }
+        return Some(Color::Rgb(channels[0], channels[1], channels[2]));
+    }
+
+    let rgb = parse_rgb_spec(spec)?;
+    Some(Color::Rgb(rgb.r, rgb.g, rgb.b))
+}
+
+/// Format an `Rgb` as the `rgb:rrrr/gggg/bbbb` string used in XParseColor
+/// query responses (OSC 4/10/11/12 with a `?` payload) - each 8-bit channel
+/// widened to 16 bits by doubling the byte.
+fn format_xparse_color(rgb: Rgb) -> String {
+    format!(
+        "rgb:{:02x}{:02x}/{:02x}{:02x}/{:02x}{:02x}",
+        rgb.r, rgb.r, rgb.g, rgb.g, rgb.b, rgb.b
+    )
+}
+
+/// The 256-color palette plus the default foreground/background/cursor
+/// colors, mutable at runtime via OSC 4/10/11/12/104 (like Alacritty's
+/// `COLORS` table and its runtime overrides).
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colors: [Rgb; 256],
+    foreground: Rgb,
+    background: Rgb,
+    cursor: Rgb,
+}
+
+impl Palette {
+    /// Build a palette initialized to the standard XTerm defaults.
+    pub fn new() -> Self {
+        let mut colors = [Rgb::new(0, 0, 0); 256];
+
+        // 0-7: standard colors, 8-15: bright colors (XTerm defaults).
+        const STANDARD: [Rgb; 16] = [
+            Rgb::new(0x00, 0x00, 0x00),
+            Rgb::new(0xcd, 0x00, 0x00),
+            Rgb::new(0x00, 0xcd, 0x00),
+            Rgb::new(0xcd, 0xcd, 0x00),
+            Rgb::new(0x00, 0x00, 0xee),
+            Rgb::new(0xcd, 0x00, 0xcd),
+            Rgb::new(0x00, 0xcd, 0xcd),
+            Rgb::new(0xe5, 0xe5, 0xe5),
+            Rgb::new(0x7f, 0x7f, 0x7f),
+            Rgb::new(0xff, 0x00, 0x00),
+            Rgb::new(0x00, 0xff, 0x00),
+            Rgb::new(0xff, 0xff, 0x00),
+            Rgb::new(0x5c, 0x5c, 0xff),
+            Rgb::new(0xff, 0x00, 0xff),
+            Rgb::new(0x00, 0xff, 0xff),
+            Rgb::new(0xff, 0xff, 0xff),
+        ];
+        colors[0..16].copy_from_slice(&STANDARD);
+
+        // 16-231: 6x6x6 color cube.
+        const CUBE_STEPS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+        for r in 0..6 {
+            for g in 0..6 {
+                for b in 0..6 {
+                    let idx = 16 + r * 36 + g * 6 + b;
+                    colors[idx] = Rgb::new(CUBE_STEPS[r], CUBE_STEPS[g], CUBE_STEPS[b]);
+                }
+            }
+        }
+
+        // 232-255: grayscale ramp.
+        for i in 0..24 {
+            let v = 8 + i as u16 * 10;
+            colors[232 + i] = Rgb::new(v as u8, v as u8, v as u8);
+        }
+
+        Self {
+            colors,
+            foreground: Rgb::new(0xe5, 0xe5, 0xe5),
+            background: Rgb::new(0x00, 0x00, 0x00),
+            cursor: Rgb::new(0xe5, 0xe5, 0xe5),
+        }
+    }
+
+    /// Look up a palette slot by index.
+    pub fn get(&self, index: u8) -> Rgb {
+        self.colors[index as usize]
+    }
+
+    /// Recolor a palette slot (OSC 4).
+    pub fn set(&mut self, index: u8, rgb: Rgb) {
+        self.colors[index as usize] = rgb;
+    }
+
+    /// Reset a single palette slot back to its XTerm default (OSC 104 with an index).
+    pub fn reset(&mut self, index: u8) {
+        self.colors[index as usize] = Self::new().colors[index as usize];
+    }
+
+    /// Reset every palette slot back to its XTerm default (OSC 104 with no params).
+    pub fn reset_all(&mut self) {
+        self.colors = Self::new().colors;
+    }
+
+    pub fn foreground(&self) -> Rgb {
+        self.foreground
+    }
+
+    pub fn set_foreground(&mut self, rgb: Rgb) {
+        self.foreground = rgb;
+    }
+
+    pub fn reset_foreground(&mut self) {
+        self.foreground = Self::new().foreground;
+    }
+
+    pub fn background(&self) -> Rgb {
+        self.background
+    }
+
+    pub fn set_background(&mut self, rgb: Rgb) {
+        self.background = rgb;
+    }
+
+    pub fn reset_background(&mut self) {
+        self.background = Self::new().background;
+    }
+
+    pub fn cursor(&self) -> Rgb {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, rgb: Rgb) {
+        self.cursor = rgb;
+    }
+
+    pub fn reset_cursor(&mut self) {
+        self.cursor = Self::new().cursor;
+    }
+
+    /// Resolve a `NamedColor` to a concrete `Rgb`.
+    fn resolve_named(&self, named: NamedColor) -> Rgb {
+        match named {
+            NamedColor::Foreground => self.foreground,
+            NamedColor::Background => self.background,
+            NamedColor::Cursor => self.cursor,
+            _ => self.get(named.palette_index().unwrap_or(0)),
+        }
+    }
+
+    /// Resolve a `Color` to a concrete `Rgb`, regardless of how it was specified.
+    pub fn resolve(&self, color: Color) -> Rgb {
+        match color {
+            Color::Default => self.foreground,
+            Color::Indexed(i) => self.get(i),
+            Color::Rgb(r, g, b) => Rgb::new(r, g, b),
+            Color::Named(named) => self.resolve_named(named),
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a decimal OSC parameter (e.g. a palette index) out of raw bytes.
+fn parse_u8_param(bytes: &[u8]) -> Option<u8> {
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Handle the title-related OSC sequences (0, 1, 2, 22, 23) against `title`
+/// and `title_stack`. Returns `Some(new_title)` if the title just changed
+/// (set or popped) so the caller can forward it to a `TerminalOutput` sink,
+/// `None` if `params` wasn't a title OSC or the title didn't change (e.g. a
+/// push, or a pop with an empty stack).
+fn apply_title_osc(title: &mut String, title_stack: &mut Vec<String>, params: &[&[u8]]) -> Option<String> {
+    let &command = params.first()?;
+
+    match command {
+        b"0" | b"1" | b"2" => {
+            // OSC 0/2 - set window/icon title, OSC 1 - set icon title only.
+            // We don't distinguish window vs. icon titles, matching how most
+            // terminal embeddings expose a single "tab title".
+            let new_title = params
+                .get(1)
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                .unwrap_or_default();
+            *title = new_title.clone();
+            Some(new_title)
+        }
+        b"22" => {
+            // Push the current title onto the title stack.
+            if title_stack.len() < TITLE_STACK_MAX_DEPTH {
+                title_stack.push(title.clone());
+            }
+            None
+        }
+        b"23" => {
+            // Pop the most recently pushed title, restoring it.
+            title_stack.pop().map(|previous| {
+                *title = previous.clone();
+                previous
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Parse an OSC 4/10/11/12 color payload (`#rrggbb`/`#rgb` legacy hex or
+/// `rgb:rr/gg/bb`) via `xparse_color` into the `Rgb` the `Palette` API wants.
+fn parse_color_spec(spec: &[u8]) -> Option<Rgb> {
+    match xparse_color(spec)? {
+        Color::Rgb(r, g, b) => Some(Rgb::new(r, g, b)),
+        _ => None,
+    }
+}
+
+/// Handle the palette-recoloring OSC sequences (4, 10, 11, 12, 104) against
+/// `palette`. A payload of `?` is a query: the current color is queued onto
+/// `pending_responses` in the same OSC, formatted as an `xparse_color`
+/// round-trip (`rgb:rrrr/gggg/bbbb`) for the embedder to write back to the
+/// PTY. Returns `true` if `params` named one of these sequences.
+fn apply_color_osc(palette: &mut Palette, pending_responses: &mut Vec<String>, params: &[&[u8]]) -> bool {
+    let Some(&command) = params.first() else {
+        return false;
+    };
+
+    match command {
+        b"4" => {
+            // OSC 4 ; index ; rgb:RR/GG/BB|?  [ ; index ; rgb:RR/GG/BB|? ... ]
+            let mut i = 1;
+            while i + 1 < params.len() {
+                if let Some(index) = parse_u8_param(params[i]) {
+                    if params[i + 1] == b"?" {
+                        let rgb = palette.get(index);
+                        pending_responses.push(format!("\x1b]4;{};{}\x07", index, format_xparse_color(rgb)));
+                    } else if let Some(rgb) = parse_color_spec(params[i + 1]) {
+                        palette.set(index, rgb);
+                    }
+                }
+                i += 2;
+            }
+            true
+        }
+        b"104" => {
+            // OSC 104 [ ; index ... ] - reset one or more slots, or all if bare.
+            if params.len() == 1 {
+                palette.reset_all();
+            } else {
+                for raw in &params[1..] {
+                    if let Some(index) = parse_u8_param(raw) {
+                        palette.reset(index);
+                    }
+                }
+            }
+            true
+        }
+        b"10" => {
+            match params.get(1).copied() {
+                Some(b"?") => pending_responses
+                    .push(format!("\x1b]10;{}\x07", format_xparse_color(palette.foreground()))),
+                Some(spec) => {
+                    if let Some(rgb) = parse_color_spec(spec) {
+                        palette.set_foreground(rgb);
+                    }
+                }
+                None => {}
+            }
+            true
+        }
+        b"11" => {
+            match params.get(1).copied() {
+                Some(b"?") => pending_responses
+                    .push(format!("\x1b]11;{}\x07", format_xparse_color(palette.background()))),
+                Some(spec) => {
+                    if let Some(rgb) = parse_color_spec(spec) {
+                        palette.set_background(rgb);
+                    }
+                }
+                None => {}
+            }
+            true
+        }
+        b"12" => {
+            match params.get(1).copied() {
+                Some(b"?") => pending_responses
+                    .push(format!("\x1b]12;{}\x07", format_xparse_color(palette.cursor()))),
+                Some(spec) => {
+                    if let Some(rgb) = parse_color_spec(spec) {
+                        palette.set_cursor(rgb);
+                    }
+                }
+                None => {}
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Handle OSC 8 (`OSC 8 ; params ; URI ST`) against `open_hyperlink`. An
+/// empty URI closes the currently open link; a non-empty URI opens a new
+/// one, pulling an optional `id=` out of the colon-separated params field.
+/// Links sharing an `id=` reuse the same `Arc` via `known_hyperlinks` rather
+/// than allocating a fresh one per OSC 8 run, so a renderer can group them
+/// with `Arc::ptr_eq` even when a long-running link is split across writes.
+/// Returns `true` if `params` named OSC 8.
+fn apply_hyperlink_osc(
+    open_hyperlink: &mut Option<Arc<Hyperlink>>,
+    known_hyperlinks: &mut HashMap<String, Arc<Hyperlink>>,
+    params: &[&[u8]],
+) -> bool {
+    let Some(&command) = params.first() else {
+        return false;
+    };
+
+    if command != b"8" {
+        return false;
+    }
+
+    let uri = params
+        .get(2)
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .unwrap_or_default();
+
+    if uri.is_empty() {
+        *open_hyperlink = None;
+        return true;
+    }
+
+    let id = params.get(1).and_then(|field| {
+        std::str::from_utf8(field)
+            .ok()?
+            .split(':')
+            .find_map(|kv| kv.strip_prefix("id=").map(String::from))
+    });
+
+    *open_hyperlink = Some(match id {
+        Some(id) => {
+            if !known_hyperlinks.contains_key(&id) {
+                // A stream with many distinct ids (e.g. `ls -R --hyperlink`
+                // over a big tree, one id per file) would otherwise grow
+                // this map forever - nothing ever evicted an entry. Prune
+                // anything no cell (or `open_hyperlink` itself, still
+                // holding its pre-close value at this point) still points
+                // at before caching one more.
+                prune_unreferenced_hyperlinks(known_hyperlinks);
+            }
+            let link = known_hyperlinks
+                .entry(id.clone())
+                .or_insert_with(|| Arc::new(Hyperlink { id: Some(id), uri: uri.clone() }));
+            if link.uri != uri {
+                *link = Arc::new(Hyperlink { id: link.id.clone(), uri });
+            }
+            Arc::clone(link)
+        }
+        None => Arc::new(Hyperlink { id: None, uri }),
+    });
+    true
+}
+
+/// Drop `known_hyperlinks` entries whose `Arc` strong count has fallen to 1
+/// - meaning the map is the only thing left holding them, since every
+/// `Cell` that ever displayed the link (and `open_hyperlink`, while it's
+/// still open) keeps its own clone alive.
+fn prune_unreferenced_hyperlinks(known_hyperlinks: &mut HashMap<String, Arc<Hyperlink>>) {
+    known_hyperlinks.retain(|_, link| Arc::strong_count(link) > 1);
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as standard (`+`/`/`, `=`-padded) base64, as OSC 52 expects.
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+
+        out.push(BASE64_ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(BASE64_ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            BASE64_ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// Decode standard base64, ignoring `=` padding. Returns `None` on a
+/// malformed (wrong-length or non-alphabet) payload.
+fn base64_decode(data: &[u8]) -> Option<Vec<u8>> {
+    let data: Vec<u8> = data.iter().copied().filter(|&b| b != b'=').collect();
+    if data.is_empty() {
+        return Some(Vec::new());
+    }
+    // A lone trailing character can't encode a whole byte.
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.chunks(4) {
+        let mut values = [0u8; 4];
+        for (value, &byte) in values.iter_mut().zip(chunk) {
+            *value = BASE64_ALPHABET.iter().position(|&c| c == byte)? as u8;
+        }
+
+        out.push((values[0] << 2) | (values[1] >> 4));
+        if chunk.len() > 2 {
+            out.push((values[1] << 4) | (values[2] >> 2));
+        }
+        if chunk.len() > 3 {
+            out.push((values[2] << 6) | values[3]);
+        }
+    }
+    Some(out)
+}
+
+/// Handle OSC 52 (`OSC 52 ; Pc ; Pd ST`) clipboard access against
+/// `clipboard`. `Pd` of `?` is a query: the clipboard is base64-encoded and
+/// queued onto `pending_responses` in the same OSC for the embedder to
+/// write back to the PTY. Any other `Pd` is base64-decoded and stored.
+/// Returns `true` if `params` named OSC 52.
+fn apply_clipboard_osc(clipboard: &mut String, pending_responses: &mut Vec<String>, params: &[&[u8]]) -> bool {
+    let Some(&command) = params.first() else {
+        return false;
+    };
+
+    if command != b"52" {
+        return false;
+    }
+
+    match params.get(2).copied() {
+        Some(b"?") => {
+            pending_responses.push(format!("\x1b]52;c;{}\x07", base64_encode(clipboard.as_bytes())));
+        }
+        Some(payload) => {
+            if let Some(decoded) = base64_decode(payload) {
+                *clipboard = String::from_utf8_lossy(&decoded).into_owned();
+            }
+        }
+        None => {}
+    }
+    true
+}
+
+/// Maximum bytes buffered by a synchronized update (`ESC P = 1 s` .. `ESC P
+/// = 2 s`) before it's aborted and flushed, matching the guard real
+/// terminals use against a misbehaving app never closing the update.
+const SYNC_BUFFER_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum time a synchronized update may stay open before it's aborted
+/// and flushed, so a dropped closing sequence can't freeze the screen.
+const SYNC_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// The literal bytes (`ESC P = 2 s ST`) that close a synchronized update.
+/// Recognized by scanning the buffered content itself rather than via
+/// `Perform::hook`/`unhook`, since once buffering starts those bytes never
+/// reach the parser - see `TerminalParser::feed_sync_byte`.
+const SYNC_END_MARKER: &[u8] = b"\x1bP=2s\x1b\\";
+
+/// Recognize the synchronized-update DCS introducer (`ESC P = 1 s`) from a
+/// `Perform::hook` call and, if matched, start buffering. Returns `true` if
+/// `intermediates`/`action` named this protocol; `ESC P = 2 s` is a no-op
+/// here since the matching end marker is recognized later, by scanning the
+/// buffered bytes in `TerminalParser::feed_sync_byte`.
+fn apply_sync_update_hook(
+    sync_started_at: &mut Option<Instant>,
+    sync_buffer: &mut Vec<u8>,
+    params: &Params,
+    intermediates: &[u8],
+    action: char,
+) -> bool {
+    if action != 's' || intermediates != [b'='] {
+        return false;
+    }
+
+    if params.iter().next().and_then(|p| p.first()).copied() == Some(1) {
+        *sync_started_at = Some(Instant::now());
+        sync_buffer.clear();
+    }
+    true
 }
 
 /// Text attributes/style for a character cell.
@@ -28,8 +912,8 @@ pub struct TextAttributes {
     pub dim: bool,
     /// Italic text
     pub italic: bool,
-    /// Underlined text
-    pub underline: bool,
+    /// Underline style (none if not underlined).
+    pub underline: UnderlineStyle,
     /// Blinking text
     pub blink: bool,
     /// Reverse video (swap foreground/background)
@@ -38,6 +922,13 @@ pub struct TextAttributes {
     pub hidden: bool,
     /// Strikethrough text
     pub strikethrough: bool,
+    /// Set on the leading cell of a double-width (CJK/emoji) character
+    /// pair. Only meaningful on `terminal::grid::Cell` - see
+    /// `wide_char_spacer` for the placeholder cell to its right.
+    pub wide_char: bool,
+    /// Set on the placeholder cell following a `wide_char` cell: empty,
+    /// non-selectable, and skipped by `TerminalGrid::row_to_string`.
+    pub wide_char_spacer: bool,
 }
 
 impl TextAttributes {
@@ -56,6 +947,30 @@ pub struct CursorPosition {
     pub col: usize,
 }
 
+/// Marks the trailing half of a double-width character (e.g. CJK or wide
+/// emoji). The leading cell holds the actual glyph; the cell to its right
+/// is a spacer so cursor math and rendering stay one-cell-per-column.
+const WIDE_SPACER: char = '\0';
+
+/// An OSC 8 hyperlink attached to a run of cells (`OSC 8 ; params ; URI ST`).
+///
+/// Shared via `Arc` across every cell in the run so opening a link over a
+/// long line doesn't reallocate the URI/id per cell. Lives as its own field
+/// on `TerminalCell`/`grid::Cell` rather than nested inside `TextAttributes`,
+/// the same way `fg_color`/`bg_color` do - it rides alongside a cell's SGR
+/// attributes but isn't one, and `known_hyperlinks` below already gives
+/// `id=`-sharing links a single interned `Arc` without needing a separate
+/// small-integer id type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    /// The `id=` parameter, if the emitter set one. Cells sharing an id
+    /// belong to the same link for hover-highlighting even if split across
+    /// multiple OSC 8 runs.
+    pub id: Option<String>,
+    /// The link target.
+    pub uri: String,
+}
+
 /// Represents a parsed terminal output cell.
 #[derive(Debug, Clone)]
 pub struct TerminalCell {
@@ -67,6 +982,8 @@ pub struct TerminalCell {
     pub bg_color: Color,
     /// Text attributes
     pub attributes: TextAttributes,
+    /// The hyperlink open when this cell was written (OSC 8), if any.
+    pub hyperlink: Option<Arc<Hyperlink>>,
 }
 
 impl Default for TerminalCell {
@@ -76,10 +993,18 @@ impl Default for TerminalCell {
             fg_color: Color::Default,
             bg_color: Color::Default,
             attributes: TextAttributes::default(),
+            hyperlink: None,
         }
     }
 }
 
+impl TerminalCell {
+    /// Whether this cell is the trailing spacer of a double-width character.
+    pub fn is_wide_spacer(&self) -> bool {
+        self.char == WIDE_SPACER
+    }
+}
+
 /// State tracked by the terminal parser.
 #[derive(Debug, Clone)]
 pub struct ParserState {
@@ -93,6 +1018,12 @@ pub struct ParserState {
     pub bg_color: Color,
     /// Whether cursor is visible
     pub cursor_visible: bool,
+    /// DEC private mode flags set via `CSI ? Pm h/l` (autowrap, app cursor keys, etc.)
+    pub mode: TermMode,
+    /// Which screen buffer is active, toggled by `CSI ? 47/1047/1049 h/l`.
+    pub screen_buffer: ScreenBuffer,
+    /// Cursor shape selected via DECSCUSR (`CSI Ps SP q`).
+    pub cursor_style: CursorStyle,
     /// Saved cursor position (for save/restore)
     pub saved_cursor: CursorPosition,
     /// Saved attributes (for save/restore)
@@ -107,6 +1038,59 @@ pub struct ParserState {
     pub saved_scroll_region_bottom: usize,
     /// Origin mode - when true, cursor positioning is relative to scroll region
     pub origin_mode: bool,
+    /// Saved origin mode (for ESC 7/8 and CSI s/u save/restore)
+    pub saved_origin_mode: bool,
+    /// Saved DEC private mode flags (for ESC 7/8 and CSI s/u save/restore)
+    pub saved_mode: TermMode,
+    /// Number of columns, tracked so tab stops can be rebuilt and the
+    /// no-stops-left fallback can clamp to the last column.
+    pub cols: u16,
+    /// Tab stop columns set via HTS (`ESC H`) and cleared via TBC
+    /// (`CSI g`/`CSI 0 g`/`CSI 3 g`); rebuilt to every 8th column by
+    /// `reset_tab_stops` on resize and RIS (`ESC c`).
+    pub tab_stops: BTreeSet<u16>,
+    /// Count of BEL (`\x07`) bytes seen while `VISUAL_BELL` is not set.
+    /// Monotonically increasing rather than a consume-once flag, so a
+    /// caller can diff against the last-seen count after each
+    /// `parse_bytes` to detect every bell without races or dropped events.
+    pub audible_bell_count: u64,
+    /// Count of BEL bytes seen while `VISUAL_BELL` (`CSI ? 1042 h/l`) is
+    /// set, same semantics as `audible_bell_count`.
+    pub visible_bell_count: u64,
+    /// 256-color palette plus default fg/bg/cursor, mutable via OSC 4/10/11/12/104
+    pub palette: Palette,
+    /// Current window/tab title, set via OSC 0/1/2
+    pub title: String,
+    /// Title stack for OSC 22 (push) / OSC 23 (pop), capped at `TITLE_STACK_MAX_DEPTH`
+    pub title_stack: Vec<String>,
+    /// Charset designated into G0 via `ESC ( `.
+    pub charset_g0: Charset,
+    /// Charset designated into G1 via `ESC ) `.
+    pub charset_g1: Charset,
+    /// Which slot SI/SO most recently selected for printable output.
+    pub charset_slot: CharsetSlot,
+    /// The hyperlink currently open via OSC 8, applied to every cell
+    /// `put_char` writes until the matching close (`OSC 8 ; ; ST`).
+    pub open_hyperlink: Option<Arc<Hyperlink>>,
+    /// Hyperlinks seen so far, keyed by their `id=` parameter, so repeated
+    /// OSC 8 runs sharing an id reuse the same `Arc` instead of each getting
+    /// their own (see `apply_hyperlink_osc`).
+    pub known_hyperlinks: HashMap<String, Arc<Hyperlink>>,
+    /// Clipboard contents set via OSC 52 (`OSC 52 ; c ; <base64> ST`).
+    pub clipboard: String,
+    /// Escape sequences queued by a dynamic query (OSC 4/10/11/12/52 with a
+    /// `?` payload) for the embedder to write back to the PTY.
+    pub pending_responses: Vec<String>,
+    /// Raw bytes buffered while a synchronized update is in progress; see
+    /// `TerminalParser::feed_sync_byte`.
+    pub sync_buffer: Vec<u8>,
+    /// When the current synchronized update began, for the abort guards
+    /// (`SYNC_BUFFER_LIMIT`, `SYNC_TIMEOUT`). `None` when no update is open.
+    pub sync_started_at: Option<Instant>,
+    /// Raw payload bytes buffered while a Sixel DCS (`ESC P … q`) is in
+    /// progress, decoded into an image on `unhook`. `None` when no Sixel
+    /// DCS is open - see `hook`/`put`/`unhook` on `ParserOutputWrapper`.
+    pub sixel_buffer: Option<Vec<u8>>,
 }
 
 impl Default for ParserState {
@@ -117,6 +1101,9 @@ impl Default for ParserState {
             fg_color: Color::Default,
             bg_color: Color::Default,
             cursor_visible: true,
+            mode: TermMode::default(),
+            screen_buffer: ScreenBuffer::default(),
+            cursor_style: CursorStyle::default(),
             saved_cursor: CursorPosition::default(),
             saved_attributes: TextAttributes::default(),
             scroll_region_top: 0,
@@ -124,15 +1111,114 @@ impl Default for ParserState {
             saved_scroll_region_top: 0,
             saved_scroll_region_bottom: 23,
             origin_mode: false,
+            saved_origin_mode: false,
+            saved_mode: TermMode::default(),
+            cols: 80,
+            tab_stops: default_tab_stops(80),
+            audible_bell_count: 0,
+            visible_bell_count: 0,
+            palette: Palette::default(),
+            title: String::new(),
+            title_stack: Vec::new(),
+            charset_g0: Charset::Ascii,
+            charset_g1: Charset::Ascii,
+            charset_slot: CharsetSlot::G0,
+            open_hyperlink: None,
+            known_hyperlinks: HashMap::new(),
+            clipboard: String::new(),
+            pending_responses: Vec::new(),
+            sync_buffer: Vec::new(),
+            sync_started_at: None,
+            sixel_buffer: None,
         }
     }
 }
 
 impl ParserState {
-    /// Initialize scroll region with terminal size
-    pub fn set_terminal_size(&mut self, rows: usize) {
+    /// Initialize scroll region and tab stops with terminal size
+    pub fn set_terminal_size(&mut self, cols: usize, rows: usize) {
         self.scroll_region_bottom = rows.saturating_sub(1);
         self.saved_scroll_region_bottom = self.scroll_region_bottom;
+        self.cols = cols as u16;
+        self.reset_tab_stops(cols);
+    }
+
+    /// Rebuild the default every-8th-column tab stop table, discarding any
+    /// custom stops set via HTS/TBC. Called on resize and RIS (`ESC c`),
+    /// since both invalidate stops relative to the old width.
+    pub fn reset_tab_stops(&mut self, cols: usize) {
+        self.tab_stops = default_tab_stops(cols);
+    }
+
+    /// The column a horizontal tab lands on: the next set stop to the
+    /// right of the cursor, or the last column if none remain.
+    pub fn next_tab_stop(&self) -> usize {
+        let last = self.cols.saturating_sub(1);
+        self.tab_stops
+            .iter()
+            .find(|&&stop| stop > self.cursor.col as u16 && stop <= last)
+            .copied()
+            .unwrap_or(last) as usize
+    }
+
+    /// Record a BEL (`\x07`), bumping the audible or visible counter
+    /// depending on whether `VISUAL_BELL` is set.
+    fn ring_bell(&mut self) {
+        if self.mode.contains(TermMode::VISUAL_BELL) {
+            self.visible_bell_count += 1;
+        } else {
+            self.audible_bell_count += 1;
+        }
+    }
+
+    /// Whether a synchronized update (`ESC P = 1 s` .. `ESC P = 2 s`) is
+    /// currently buffering screen updates.
+    pub fn is_synchronizing(&self) -> bool {
+        self.sync_started_at.is_some()
+    }
+
+    /// The mouse-report granularity currently requested, or `None` if
+    /// mouse reporting isn't active. Requires `MOUSE_REPORT_SGR` (`1006`)
+    /// in addition to `1000`/`1002`/`1003`, since SGR extended coordinates
+    /// are the only encoding this parser produces.
+    pub fn mouse_reporting(&self) -> Option<MouseReportMode> {
+        if !self.mode.contains(TermMode::MOUSE_REPORT_SGR) {
+            return None;
+        }
+        if self.mode.contains(TermMode::MOUSE_REPORT_ANY_MOTION) {
+            Some(MouseReportMode::AnyMotion)
+        } else if self.mode.contains(TermMode::MOUSE_REPORT_DRAG) {
+            Some(MouseReportMode::Drag)
+        } else if self.mode.contains(TermMode::MOUSE_REPORT_CLICK) {
+            Some(MouseReportMode::Click)
+        } else {
+            None
+        }
+    }
+
+    /// The charset currently selected for printable output - whichever of
+    /// G0/G1 SI/SO most recently chose.
+    pub fn active_charset(&self) -> Charset {
+        match self.charset_slot {
+            CharsetSlot::G0 => self.charset_g0,
+            CharsetSlot::G1 => self.charset_g1,
+        }
+    }
+
+    /// Apply an `ESC ( `/`ESC )` charset designation. Returns `true` if
+    /// `intermediate`/`byte` named a recognized designation.
+    fn apply_charset_designation(&mut self, intermediate: Option<u8>, byte: u8) -> bool {
+        let Some(slot) = charset_slot_for_intermediate(intermediate) else {
+            return false;
+        };
+        let Some(charset) = charset_for_designator(byte) else {
+            return false;
+        };
+        match slot {
+            CharsetSlot::G0 => self.charset_g0 = charset,
+            CharsetSlot::G1 => self.charset_g1 = charset,
+        }
+        true
     }
 
     /// Check if a custom scroll region is active (different from full screen)
@@ -196,8 +1282,50 @@ pub trait TerminalOutput {
     fn erase_in_line(&mut self, _mode: u16) {
         // Default implementation - override in implementor
     }
+
+    /// Insert n blank cells at the cursor column (ICH), shifting the rest
+    /// of the line right and dropping cells that fall off the end.
+    fn insert_chars(&mut self, _n: usize) {
+        // Default implementation - override in implementor
+    }
+
+    /// Delete n cells at the cursor column (DCH), shifting the rest of the
+    /// line left and filling the vacated end with blanks.
+    fn delete_chars(&mut self, _n: usize) {
+        // Default implementation - override in implementor
+    }
+
+    /// Set the window/tab title (OSC 0/1/2). Default implementation does
+    /// nothing; override to update tab/window chrome.
+    fn set_title(&mut self, _title: String) {}
+
+    /// Switch to the alternate screen buffer (`CSI ? 47/1047/1049 h`),
+    /// stashing the primary screen so `exit_alt_screen` can restore it.
+    /// Default implementation does nothing; override to swap in a separate,
+    /// cleared buffer.
+    fn enter_alt_screen(&mut self) {}
+
+    /// Switch back to the primary screen buffer (`CSI ? 47/1047/1049 l`),
+    /// restoring whatever was visible before `enter_alt_screen`. Default
+    /// implementation does nothing.
+    fn exit_alt_screen(&mut self) {}
+
+    /// Anchor a decoded Sixel image (DCS `ESC P … q … ST`) at the current
+    /// cursor position. Default implementation discards it; override to
+    /// store it for the renderer - see `grid::SixelImageStore`.
+    fn set_sixel_image(&mut self, _image: sixel::SixelImage) {}
+
+    /// Toggle autowrap (DECAWM, `CSI ? 7 h/l`). Default implementation does
+    /// nothing; override so a wide character that doesn't fit in the last
+    /// column knows not to wrap/scroll while autowrap is off - see
+    /// `grid::TerminalGrid::put_char`.
+    fn set_autowrap(&mut self, _enabled: bool) {}
 }
 
+/// Cap on `ParserState::title_stack` depth, matching Alacritty's
+/// `TITLE_STACK_MAX_DEPTH` - bounds memory if a hostile stream pushes titles forever.
+const TITLE_STACK_MAX_DEPTH: usize = 4096;
+
 /// Terminal parser that processes ANSI escape sequences.
 ///
 /// This parser uses the `vte` crate to handle escape sequence parsing and
@@ -213,6 +1341,9 @@ pub struct TerminalParser {
     cols: usize,
     /// Number of rows in the terminal
     rows: usize,
+    /// Deferred autowrap latch: set when a printable char was written to the
+    /// last column, consumed on the next printable char (VT100 semantics).
+    pending_wrap: bool,
 }
 
 impl TerminalParser {
@@ -228,7 +1359,7 @@ impl TerminalParser {
     /// * `rows` - Number of rows (height)
     pub fn with_size(cols: usize, rows: usize) -> Self {
         let mut state = ParserState::default();
-        state.set_terminal_size(rows);
+        state.set_terminal_size(cols, rows);
         
         Self {
             parser: vte::Parser::new(),
@@ -236,16 +1367,145 @@ impl TerminalParser {
             output_buffer: vec![TerminalCell::default(); cols * rows],
             cols,
             rows,
+            pending_wrap: false,
         }
     }
 
-    /// Parse a slice of bytes from the PTY.
-    ///
+    /// Get the current DEC private mode flags.
+    pub fn mode(&self) -> TermMode {
+        self.state.mode
+    }
+
+    /// Whether a specific DEC private mode flag is currently set.
+    pub fn has_mode(&self, flag: TermMode) -> bool {
+        self.state.mode.contains(flag)
+    }
+
+    /// The mouse-report granularity the pane has requested, if any - see
+    /// `ParserState::mouse_reporting`.
+    pub fn mouse_reporting(&self) -> Option<MouseReportMode> {
+        self.state.mouse_reporting()
+    }
+
+    /// Resolve a `Color` to a concrete `Rgb`, regardless of how it was
+    /// specified (indexed, named, literal RGB, or the terminal default).
+    pub fn resolve(&self, color: Color) -> Rgb {
+        self.state.palette.resolve(color)
+    }
+
+    /// Access the color palette (for embeddings that want to inspect or
+    /// pre-seed it before OSC sequences arrive).
+    pub fn palette(&self) -> &Palette {
+        &self.state.palette
+    }
+
+    /// Mutably access the color palette, e.g. to apply a `ui::theme::Theme`
+    /// right after the pane is created, before the shell has printed
+    /// anything or sent its own OSC 4/10/11/12 overrides.
+    pub fn palette_mut(&mut self) -> &mut Palette {
+        &mut self.state.palette
+    }
+
+    /// The current window/tab title, as set by OSC 0/1/2.
+    ///
+    /// Empty until the application emits one of those sequences.
+    pub fn title(&self) -> &str {
+        &self.state.title
+    }
+
+    /// The current clipboard contents, as set by OSC 52.
+    pub fn clipboard(&self) -> &str {
+        &self.state.clipboard
+    }
+
+    /// Which screen buffer is currently displayed (normal or alternate).
+    pub fn screen_buffer(&self) -> ScreenBuffer {
+        self.state.screen_buffer
+    }
+
+    /// Whether the cursor is visible (DECTCEM, `CSI ? 25 h/l`).
+    pub fn cursor_visible(&self) -> bool {
+        self.state.cursor_visible
+    }
+
+    /// The current cursor shape, selected via DECSCUSR (`CSI Ps SP q`).
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.state.cursor_style
+    }
+
+    /// Total BEL bytes seen while `VISUAL_BELL` was not set. Monotonically
+    /// increasing - compare against the last-seen count after each
+    /// `parse_bytes` call to detect how many bells fired since last poll.
+    pub fn audible_bell_count(&self) -> u64 {
+        self.state.audible_bell_count
+    }
+
+    /// Total BEL bytes seen while `VISUAL_BELL` was set, same
+    /// last-seen-count-diff semantics as `audible_bell_count`.
+    pub fn visible_bell_count(&self) -> u64 {
+        self.state.visible_bell_count
+    }
+
+    /// Drain the escape sequences queued by dynamic queries (OSC
+    /// 4/10/11/12/52 with a `?` payload) since the last call, for the
+    /// embedder to write back to the PTY.
+    pub fn take_pending_responses(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.state.pending_responses)
+    }
+
+    /// Whether a synchronized update (`ESC P = 1 s` .. `ESC P = 2 s`) is
+    /// currently buffering screen updates.
+    pub fn is_synchronizing(&self) -> bool {
+        self.state.is_synchronizing()
+    }
+
+    /// Feed one byte into a pending synchronized update (see
+    /// `ParserState::sync_buffer`). Returns the buffered content to apply,
+    /// with any trailing `SYNC_END_MARKER` stripped, once the update should
+    /// be flushed: on a well-formed close, on a RIS (`ESC c`) arriving
+    /// mid-update (left in the returned bytes so replaying it resets the
+    /// terminal), or on hitting the `SYNC_BUFFER_LIMIT`/`SYNC_TIMEOUT`
+    /// abort guards. The caller is
+    /// expected to replay the returned bytes through `self.parser` itself,
+    /// since those bytes never reached it while buffering.
+    fn feed_sync_byte(&mut self, byte: u8) -> Option<Vec<u8>> {
+        self.state.sync_buffer.push(byte);
+
+        let timed_out = self
+            .state
+            .sync_started_at
+            .is_some_and(|started| started.elapsed() >= SYNC_TIMEOUT);
+        let too_large = self.state.sync_buffer.len() > SYNC_BUFFER_LIMIT;
+        let closed = self.state.sync_buffer.ends_with(SYNC_END_MARKER);
+        let reset = self.state.sync_buffer.ends_with(b"\x1bc");
+
+        if !timed_out && !too_large && !closed && !reset {
+            return None;
+        }
+
+        let mut content = std::mem::take(&mut self.state.sync_buffer);
+        if closed {
+            content.truncate(content.len() - SYNC_END_MARKER.len());
+        }
+        self.state.sync_started_at = None;
+        Some(content)
+    }
+
+    /// Parse a slice of bytes from the PTY.
+    ///
     /// This method processes the raw bytes and updates the parser state.
     /// The bytes are interpreted as UTF-8 text with embedded escape sequences.
     pub fn parse_bytes(&mut self, bytes: &[u8]) {
-        for byte in bytes {
-            self.parser.advance(&mut self.state, *byte);
+        for &byte in bytes {
+            if self.state.is_synchronizing() {
+                if let Some(content) = self.feed_sync_byte(byte) {
+                    for flushed in &content {
+                        self.parser.advance(&mut self.state, *flushed);
+                    }
+                }
+                continue;
+            }
+            self.parser.advance(&mut self.state, byte);
         }
     }
 
@@ -254,16 +1514,28 @@ impl TerminalParser {
     /// This is the preferred method for connecting the parser to a grid or screen.
     /// The parser updates its internal state AND writes characters to the output.
     pub fn parse_bytes_with_output<O: TerminalOutput>(&mut self, bytes: &[u8], output: &mut O) {
-        // Create a wrapper that forwards to both state and output
-        let mut wrapper = ParserOutputWrapper {
-            state: &mut self.state,
-            output,
-            cols: self.cols,
-            rows: self.rows,
-        };
-        
-        for byte in bytes {
-            self.parser.advance(&mut wrapper, *byte);
+        for &byte in bytes {
+            if self.state.is_synchronizing() {
+                if let Some(content) = self.feed_sync_byte(byte) {
+                    let mut wrapper = ParserOutputWrapper {
+                        state: &mut self.state,
+                        output,
+                        cols: self.cols,
+                        rows: self.rows,
+                    };
+                    for flushed in &content {
+                        self.parser.advance(&mut wrapper, *flushed);
+                    }
+                }
+                continue;
+            }
+            let mut wrapper = ParserOutputWrapper {
+                state: &mut self.state,
+                output,
+                cols: self.cols,
+                rows: self.rows,
+            };
+            self.parser.advance(&mut wrapper, byte);
         }
     }
 
@@ -291,7 +1563,7 @@ impl TerminalParser {
     pub fn resize(&mut self, cols: usize, rows: usize) {
         self.cols = cols;
         self.rows = rows;
-        self.state.set_terminal_size(rows);
+        self.state.set_terminal_size(cols, rows);
         self.output_buffer.resize(cols * rows, TerminalCell::default());
     }
 
@@ -305,25 +1577,84 @@ impl TerminalParser {
         self.output_buffer.fill(TerminalCell::default());
     }
 
-    /// Put a character at the current cursor position.
-    fn put_char(&mut self, c: char) {
-        let row = self.state.cursor.row;
-        let col = self.state.cursor.col;
-
+    /// Write a cell at an explicit position, bounds-checked.
+    fn write_cell_at(&mut self, row: usize, col: usize, cell: TerminalCell) {
         if row < self.rows && col < self.cols {
             let idx = row * self.cols + col;
             if idx < self.output_buffer.len() {
-                let cell = &mut self.output_buffer[idx];
-                cell.char = c;
-                cell.fg_color = self.state.fg_color;
-                cell.bg_color = self.state.bg_color;
-                cell.attributes = self.state.attributes;
+                self.output_buffer[idx] = cell;
             }
         }
+    }
 
-        // Advance cursor
-        if self.state.cursor.col < self.cols - 1 {
+    /// Advance the cursor by one column, deferring the wrap to the next
+    /// printable char (see `put_char`'s doc comment).
+    fn advance_cursor_one(&mut self) {
+        if self.state.cursor.col + 1 < self.cols {
             self.state.cursor.col += 1;
+        } else if self.state.mode.contains(TermMode::LINE_WRAP) {
+            self.pending_wrap = true;
+        }
+    }
+
+    /// Put a character at the current cursor position.
+    ///
+    /// With `LINE_WRAP` enabled (the default), writing to the last column
+    /// doesn't wrap immediately - it sets a pending-wrap latch that's
+    /// resolved (carriage return + line feed) on the *next* printable
+    /// character, matching real VT100 autowrap behavior. With `LINE_WRAP`
+    /// disabled, the cursor simply stays pinned to the last column.
+    ///
+    /// Double-width characters (CJK, wide emoji) occupy the cell at the
+    /// cursor plus a trailing `WIDE_SPACER` cell, and advance the cursor by
+    /// two; a double-width char that doesn't fit in the last column blanks
+    /// that column and wraps the glyph to the next line instead. Zero-width
+    /// combining characters are dropped rather than consuming a new cell,
+    /// since `TerminalCell` has no way to merge a second codepoint into an
+    /// existing one yet.
+    fn put_char(&mut self, c: char) {
+        let width = UnicodeWidthChar::width(c).unwrap_or(1);
+        if width == 0 {
+            return;
+        }
+
+        if self.pending_wrap {
+            self.pending_wrap = false;
+            self.state.cursor.col = 0;
+            if self.state.cursor.row + 1 < self.rows {
+                self.state.cursor.row += 1;
+            }
+        }
+
+        if width == 2 && self.state.cursor.col + 1 == self.cols {
+            // Only one column left: blank it and wrap the glyph down.
+            self.write_cell_at(self.state.cursor.row, self.state.cursor.col, TerminalCell::default());
+            self.state.cursor.col = 0;
+            if self.state.cursor.row + 1 < self.rows {
+                self.state.cursor.row += 1;
+            }
+        }
+
+        let cell = TerminalCell {
+            char: c,
+            fg_color: self.state.fg_color,
+            bg_color: self.state.bg_color,
+            attributes: self.state.attributes,
+            hyperlink: self.state.open_hyperlink.clone(),
+        };
+        self.write_cell_at(self.state.cursor.row, self.state.cursor.col, cell);
+        self.advance_cursor_one();
+
+        if width == 2 {
+            let spacer = TerminalCell {
+                char: WIDE_SPACER,
+                fg_color: self.state.fg_color,
+                bg_color: self.state.bg_color,
+                attributes: self.state.attributes,
+                hyperlink: self.state.open_hyperlink.clone(),
+            };
+            self.write_cell_at(self.state.cursor.row, self.state.cursor.col, spacer);
+            self.advance_cursor_one();
         }
     }
 
@@ -402,11 +1733,35 @@ impl<O: TerminalOutput> ParserOutputWrapper<'_, O> {
 impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
     fn print(&mut self, c: char) {
         // Write character to output with current attributes
+        let (before_row, before_col) = self.output.cursor_position();
+        let c = if self.state.active_charset() == Charset::SpecialGraphics && ('`'..='~').contains(&c) {
+            special_graphics_char(c)
+        } else {
+            c
+        };
+        if self.state.mode.contains(TermMode::INSERT) {
+            // IRM: open up room for the incoming glyph instead of
+            // overwriting whatever is already at the cursor.
+            let width = UnicodeWidthChar::width(c).unwrap_or(1).max(1);
+            self.output.insert_chars(width);
+        }
         self.output.put_char(c);
+
+        if !self.state.mode.contains(TermMode::LINE_WRAP)
+            && before_col >= self.cols.saturating_sub(1)
+        {
+            // Autowrap disabled: keep overwriting the last column instead of
+            // advancing onto the next line.
+            self.output.move_cursor(before_row, self.cols.saturating_sub(1));
+        }
     }
 
     fn execute(&mut self, byte: u8) {
         match byte {
+            0x07 => {
+                // BEL - Bell
+                self.state.ring_bell();
+            }
             0x08 => {
                 // BS - Backspace
                 if self.state.cursor.col > 0 {
@@ -415,11 +1770,8 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.output.backspace();
             }
             0x09 => {
-                // HT - Horizontal Tab
-                self.state.cursor.col = (self.state.cursor.col + 8) & !7;
-                if self.state.cursor.col >= self.cols {
-                    self.state.cursor.col = self.cols - 1;
-                }
+                // HT - Horizontal Tab (advance to the next tab stop)
+                self.state.cursor.col = self.state.next_tab_stop();
                 self.output.tab();
             }
             0x0A..=0x0C => {
@@ -431,30 +1783,125 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.state.cursor.col = 0;
                 self.output.carriage_return();
             }
+            0x0E => {
+                // SO - Shift Out, select G1 as the active charset slot
+                self.state.charset_slot = CharsetSlot::G1;
+            }
+            0x0F => {
+                // SI - Shift In, select G0 as the active charset slot
+                self.state.charset_slot = CharsetSlot::G0;
+            }
             _ => {}
         }
     }
 
-    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _action: char) {
-        // DCS - not commonly used
+    fn hook(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        if action == 'q' && intermediates.is_empty() {
+            // Sixel graphics DCS (`ESC P … q`) - start buffering the payload
+            // for `put`/`unhook` below. The leading params (aspect ratio,
+            // background mode) aren't needed: the decoder reads its own
+            // raster attributes straight out of the payload.
+            self.state.sixel_buffer = Some(Vec::new());
+            return;
+        }
+        apply_sync_update_hook(
+            &mut self.state.sync_started_at,
+            &mut self.state.sync_buffer,
+            params,
+            intermediates,
+            action,
+        );
     }
 
-    fn put(&mut self, _byte: u8) {
-        // Part of DCS handling
+    fn put(&mut self, byte: u8) {
+        // No DCS other than Sixel graphics and the synchronized-update
+        // protocol is supported, and the latter has no payload bytes to
+        // collect here - see `hook`.
+        if let Some(buffer) = &mut self.state.sixel_buffer {
+            buffer.push(byte);
+        }
     }
 
     fn unhook(&mut self) {
-        // End of DCS
+        // End of DCS - the synchronized-update close is instead recognized
+        // by `TerminalParser::feed_sync_byte` scanning the buffered bytes.
+        if let Some(buffer) = self.state.sixel_buffer.take() {
+            if let Some(image) = sixel::decode(&buffer) {
+                self.output.set_sixel_image(image);
+            }
+        }
     }
 
-    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {
-        // OSC sequences - window titles, etc.
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        if let Some(new_title) = apply_title_osc(&mut self.state.title, &mut self.state.title_stack, params) {
+            self.output.set_title(new_title);
+        }
+        apply_color_osc(&mut self.state.palette, &mut self.state.pending_responses, params);
+        apply_hyperlink_osc(&mut self.state.open_hyperlink, &mut self.state.known_hyperlinks, params);
+        apply_clipboard_osc(&mut self.state.clipboard, &mut self.state.pending_responses, params);
     }
 
-    fn csi_dispatch(&mut self, params: &Params, _intermediates: &[u8], _ignore: bool, action: char) {
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
         let params_vec: Vec<Vec<u16>> = params.iter().map(|p| p.to_vec()).collect();
         let flat_params: Vec<u16> = params_vec.iter().flat_map(|p| p.iter().copied()).collect();
 
+        if intermediates.first() == Some(&b'?') && (action == 'h' || action == 'l') {
+            // DECSET/DECRST - Set/Reset DEC private mode
+            let enable = action == 'h';
+            for code in &flat_params {
+                apply_private_mode(&mut self.state.mode, *code, enable);
+                if *code == 25 {
+                    self.state.cursor_visible = enable;
+                }
+                if *code == 6 {
+                    self.state.origin_mode = enable;
+                }
+                if *code == 7 {
+                    self.output.set_autowrap(enable);
+                }
+                if is_alt_screen_code(*code) {
+                    self.state.screen_buffer =
+                        if enable { ScreenBuffer::Alternate } else { ScreenBuffer::Normal };
+                    if enable {
+                        if *code == 1049 {
+                            self.state.saved_cursor = self.state.cursor;
+                            self.state.saved_attributes = self.state.attributes;
+                        }
+                        self.output.enter_alt_screen();
+                    } else {
+                        self.output.exit_alt_screen();
+                        if *code == 1049 {
+                            self.state.attributes = self.state.saved_attributes;
+                        }
+                    }
+                    let (row, col) = self.output.cursor_position();
+                    self.state.cursor = CursorPosition { row, col };
+                }
+            }
+            return;
+        }
+
+        if intermediates == [b' '] && action == 'q' {
+            // DECSCUSR - Set Cursor Style
+            if let Some(style) = cursor_style_from_param(flat_params.first().copied().unwrap_or(0)) {
+                self.state.cursor_style = style;
+            }
+            return;
+        }
+
+        if intermediates.is_empty() && (action == 'h' || action == 'l') {
+            // SM/RM - Set/Reset Mode (ANSI, non-DEC-private). The only one
+            // that matters here is IRM (4); the rest have no effect on a
+            // terminal emulator and are accepted as no-ops.
+            let enable = action == 'h';
+            for code in &flat_params {
+                if *code == 4 {
+                    self.state.mode.set(TermMode::INSERT, enable);
+                }
+            }
+            return;
+        }
+
         match action {
             'A' => {
                 // CUU - Cursor Up
@@ -555,6 +2002,17 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 let mode = flat_params.first().copied().unwrap_or(0);
                 self.output.erase_in_line(mode);
             }
+            'g' => {
+                // TBC - Tab Clear: 0 (or no param) clears the stop at the
+                // cursor, 3 clears all stops.
+                match flat_params.first().copied().unwrap_or(0) {
+                    0 => {
+                        self.state.tab_stops.remove(&(self.state.cursor.col as u16));
+                    }
+                    3 => self.state.tab_stops.clear(),
+                    _ => {}
+                }
+            }
             'L' => {
                 // IL - Insert Lines
                 // Insert blank lines at cursor, shifting lines down within scroll region
@@ -577,6 +2035,16 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                     self.output.scroll_up_in_region(n, top, bottom);
                 }
             }
+            '@' => {
+                // ICH - Insert Characters
+                let n = flat_params.first().copied().unwrap_or(1) as usize;
+                self.output.insert_chars(n);
+            }
+            'P' => {
+                // DCH - Delete Characters
+                let n = flat_params.first().copied().unwrap_or(1) as usize;
+                self.output.delete_chars(n);
+            }
             'r' => {
                 // DECSTBM - Set Top and Bottom Margins (Scroll Region)
                 let top = flat_params.first().copied().unwrap_or(1) as usize;
@@ -624,9 +2092,10 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                     self.state.fg_color = Color::Default;
                     self.state.bg_color = Color::Default;
                 } else {
+                    let (sgr_params, underline_override) = extract_extended_underline(&params_vec);
                     let mut i = 0;
-                    while i < flat_params.len() {
-                        match flat_params[i] {
+                    while i < sgr_params.len() {
+                        match sgr_params[i] {
                             0 => {
                                 self.state.attributes.reset();
                                 self.state.fg_color = Color::Default;
@@ -635,7 +2104,7 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                             1 => self.state.attributes.bold = true,
                             2 => self.state.attributes.dim = true,
                             3 => self.state.attributes.italic = true,
-                            4 => self.state.attributes.underline = true,
+                            4 => self.state.attributes.underline = UnderlineStyle::Single,
                             5 => self.state.attributes.blink = true,
                             7 => self.state.attributes.reverse = true,
                             8 => self.state.attributes.hidden = true,
@@ -645,29 +2114,30 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                                 self.state.attributes.dim = false;
                             }
                             23 => self.state.attributes.italic = false,
-                            24 => self.state.attributes.underline = false,
+                            24 => self.state.attributes.underline = UnderlineStyle::None,
                             25 => self.state.attributes.blink = false,
                             27 => self.state.attributes.reverse = false,
                             28 => self.state.attributes.hidden = false,
                             29 => self.state.attributes.strikethrough = false,
                             30..=37 => {
-                                self.state.fg_color = Color::Indexed((flat_params[i] - 30) as u8);
+                                self.state.fg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 30, false));
                             }
                             38 => {
-                                if i + 1 < flat_params.len() {
-                                    match flat_params[i + 1] {
+                                if i + 1 < sgr_params.len() {
+                                    match sgr_params[i + 1] {
                                         5 => {
-                                            if i + 2 < flat_params.len() {
-                                                self.state.fg_color = Color::Indexed(flat_params[i + 2] as u8);
+                                            if i + 2 < sgr_params.len() {
+                                                self.state.fg_color = Color::Indexed(sgr_params[i + 2] as u8);
                                                 i += 2;
                                             }
                                         }
                                         2 => {
-                                            if i + 4 < flat_params.len() {
+                                            if i + 4 < sgr_params.len() {
                                                 self.state.fg_color = Color::Rgb(
-                                                    flat_params[i + 2] as u8,
-                                                    flat_params[i + 3] as u8,
-                                                    flat_params[i + 4] as u8,
+                                                    sgr_params[i + 2] as u8,
+                                                    sgr_params[i + 3] as u8,
+                                                    sgr_params[i + 4] as u8,
                                                 );
                                                 i += 4;
                                             }
@@ -678,23 +2148,24 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                             }
                             39 => self.state.fg_color = Color::Default,
                             40..=47 => {
-                                self.state.bg_color = Color::Indexed((flat_params[i] - 40) as u8);
+                                self.state.bg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 40, false));
                             }
                             48 => {
-                                if i + 1 < flat_params.len() {
-                                    match flat_params[i + 1] {
+                                if i + 1 < sgr_params.len() {
+                                    match sgr_params[i + 1] {
                                         5 => {
-                                            if i + 2 < flat_params.len() {
-                                                self.state.bg_color = Color::Indexed(flat_params[i + 2] as u8);
+                                            if i + 2 < sgr_params.len() {
+                                                self.state.bg_color = Color::Indexed(sgr_params[i + 2] as u8);
                                                 i += 2;
                                             }
                                         }
                                         2 => {
-                                            if i + 4 < flat_params.len() {
+                                            if i + 4 < sgr_params.len() {
                                                 self.state.bg_color = Color::Rgb(
-                                                    flat_params[i + 2] as u8,
-                                                    flat_params[i + 3] as u8,
-                                                    flat_params[i + 4] as u8,
+                                                    sgr_params[i + 2] as u8,
+                                                    sgr_params[i + 3] as u8,
+                                                    sgr_params[i + 4] as u8,
                                                 );
                                                 i += 4;
                                             }
@@ -705,15 +2176,20 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                             }
                             49 => self.state.bg_color = Color::Default,
                             90..=97 => {
-                                self.state.fg_color = Color::Indexed((flat_params[i] - 90 + 8) as u8);
+                                self.state.fg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 90, true));
                             }
                             100..=107 => {
-                                self.state.bg_color = Color::Indexed((flat_params[i] - 100 + 8) as u8);
+                                self.state.bg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 100, true));
                             }
                             _ => {}
                         }
                         i += 1;
                     }
+                    if let Some(style) = underline_override {
+                        self.state.attributes.underline = style;
+                    }
                 }
             }
             's' => {
@@ -722,6 +2198,8 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.state.saved_attributes = self.state.attributes;
                 self.state.saved_scroll_region_top = self.state.scroll_region_top;
                 self.state.saved_scroll_region_bottom = self.state.scroll_region_bottom;
+                self.state.saved_origin_mode = self.state.origin_mode;
+                self.state.saved_mode = self.state.mode;
             }
             'u' => {
                 // RCP - Restore Cursor Position
@@ -729,13 +2207,19 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.state.attributes = self.state.saved_attributes;
                 self.state.scroll_region_top = self.state.saved_scroll_region_top;
                 self.state.scroll_region_bottom = self.state.saved_scroll_region_bottom;
+                self.state.origin_mode = self.state.saved_origin_mode;
+                self.state.mode = self.state.saved_mode;
                 self.output.move_cursor(self.state.cursor.row, self.state.cursor.col);
             }
             _ => {}
         }
     }
 
-    fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, byte: u8) {
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if self.state.apply_charset_designation(intermediates.first().copied(), byte) {
+            return;
+        }
+
         match byte {
             b'c' => {
                 // RIS - Reset to Initial State
@@ -746,9 +2230,21 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.state.scroll_region_top = 0;
                 self.state.scroll_region_bottom = self.rows.saturating_sub(1);
                 self.state.origin_mode = false;
+                self.state.saved_origin_mode = false;
+                self.state.mode = TermMode::default();
+                self.state.charset_g0 = Charset::Ascii;
+                self.state.charset_g1 = Charset::Ascii;
+                self.state.charset_slot = CharsetSlot::G0;
+                self.state.sync_buffer.clear();
+                self.state.sync_started_at = None;
+                self.state.reset_tab_stops(self.cols);
                 self.output.clear_screen();
                 self.output.move_cursor(0, 0);
             }
+            b'H' => {
+                // HTS - Horizontal Tab Set (set a tab stop at the cursor)
+                self.state.tab_stops.insert(self.state.cursor.col as u16);
+            }
             b'M' => {
                 // RI - Reverse Index (move up, scroll down if at top of region)
                 self.handle_reverse_index();
@@ -769,6 +2265,8 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.state.saved_attributes = self.state.attributes;
                 self.state.saved_scroll_region_top = self.state.scroll_region_top;
                 self.state.saved_scroll_region_bottom = self.state.scroll_region_bottom;
+                self.state.saved_origin_mode = self.state.origin_mode;
+                self.state.saved_mode = self.state.mode;
             }
             b'8' => {
                 // DECRC - Restore Cursor (including scroll region)
@@ -776,6 +2274,8 @@ impl<O: TerminalOutput> Perform for ParserOutputWrapper<'_, O> {
                 self.state.attributes = self.state.saved_attributes;
                 self.state.scroll_region_top = self.state.saved_scroll_region_top;
                 self.state.scroll_region_bottom = self.state.saved_scroll_region_bottom;
+                self.state.origin_mode = self.state.saved_origin_mode;
+                self.state.mode = self.state.saved_mode;
                 self.output.move_cursor(self.state.cursor.row, self.state.cursor.col);
             }
             _ => {}
@@ -793,14 +2293,21 @@ impl Default for TerminalParser {
 impl Perform for ParserState {
     /// Handle a printable character.
     fn print(&mut self, c: char) {
-        // This is called by the parser, but we handle it differently
-        // in TerminalParser - the state just tracks attributes
-        let _ = c;
+        // There's no cell grid on this path (see `TerminalParser::put_char`
+        // for the version that writes into one) - just keep the cursor
+        // column in sync with the glyph's on-screen width, same as the other
+        // movement handlers below. Zero-width combining marks don't advance.
+        let width = UnicodeWidthChar::width(c).unwrap_or(1);
+        self.cursor.col += width;
     }
 
     /// Handle a C0 or C1 control character.
     fn execute(&mut self, byte: u8) {
         match byte {
+            0x07 => {
+                // BEL - Bell
+                self.ring_bell();
+            }
             0x08 => {
                 // BS - Backspace
                 if self.cursor.col > 0 {
@@ -808,8 +2315,8 @@ impl Perform for ParserState {
                 }
             }
             0x09 => {
-                // HT - Horizontal Tab (move to next tab stop, every 8 columns)
-                self.cursor.col = (self.cursor.col + 8) & !7;
+                // HT - Horizontal Tab (advance to the next tab stop)
+                self.cursor.col = self.next_tab_stop();
             }
             0x0A..=0x0C => {
                 // LF, VT, FF - Line Feed (move down, possibly scroll)
@@ -820,6 +2327,14 @@ impl Perform for ParserState {
                 // CR - Carriage Return (move to column 0)
                 self.cursor.col = 0;
             }
+            0x0E => {
+                // SO - Shift Out, select G1 as the active charset slot
+                self.charset_slot = CharsetSlot::G1;
+            }
+            0x0F => {
+                // SI - Shift In, select G0 as the active charset slot
+                self.charset_slot = CharsetSlot::G0;
+            }
             0x1B => {
                 // ESC - Escape (start of escape sequence)
             }
@@ -829,32 +2344,70 @@ impl Perform for ParserState {
         }
     }
 
-    /// Handle the end of a CSI escape sequence.
-    fn hook(&mut self, _params: &Params, _intermediates: &[u8], _ignore: bool, _action: char) {
-        // DCS (Device Control String) - not commonly used
+    /// Handle the start of a DCS sequence.
+    fn hook(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        apply_sync_update_hook(&mut self.sync_started_at, &mut self.sync_buffer, params, intermediates, action);
     }
 
     /// Handle a character in a DCS sequence.
     fn put(&mut self, _byte: u8) {
-        // Part of DCS handling
+        // No DCS other than the synchronized-update protocol is supported,
+        // and that one has no payload bytes to collect here - see `hook`.
     }
 
     /// Handle the end of a DCS sequence.
     fn unhook(&mut self) {
-        // End of DCS
+        // End of DCS - the synchronized-update close is instead recognized
+        // by `TerminalParser::feed_sync_byte` scanning the buffered bytes.
     }
 
     /// Handle an OSC escape sequence (Operating System Command).
-    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {
-        // OSC sequences for window titles, clipboard, etc.
-        // Example: ESC ] 0 ; title BEL
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        // There's no output sink in this path, so the new title is just stored.
+        apply_title_osc(&mut self.title, &mut self.title_stack, params);
+        apply_color_osc(&mut self.palette, &mut self.pending_responses, params);
+        apply_hyperlink_osc(&mut self.open_hyperlink, &mut self.known_hyperlinks, params);
+        apply_clipboard_osc(&mut self.clipboard, &mut self.pending_responses, params);
     }
 
     /// Handle a CSI escape sequence.
-    fn csi_dispatch(&mut self, params: &Params, _intermediates: &[u8], _ignore: bool, action: char) {
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
         let params_vec: Vec<Vec<u16>> = params.iter().map(|p| p.to_vec()).collect();
         let flat_params: Vec<u16> = params_vec.iter().flat_map(|p| p.iter().copied()).collect();
 
+        if intermediates.first() == Some(&b'?') && (action == 'h' || action == 'l') {
+            // DECSET/DECRST - Set/Reset DEC private mode
+            let enable = action == 'h';
+            for code in &flat_params {
+                apply_private_mode(&mut self.mode, *code, enable);
+                if *code == 25 {
+                    self.cursor_visible = enable;
+                }
+                if *code == 6 {
+                    self.origin_mode = enable;
+                }
+                if is_alt_screen_code(*code) {
+                    self.screen_buffer = if enable { ScreenBuffer::Alternate } else { ScreenBuffer::Normal };
+                    if enable && *code == 1049 {
+                        self.saved_cursor = self.cursor;
+                        self.saved_attributes = self.attributes;
+                    } else if !enable && *code == 1049 {
+                        self.cursor = self.saved_cursor;
+                        self.attributes = self.saved_attributes;
+                    }
+                }
+            }
+            return;
+        }
+
+        if intermediates == [b' '] && action == 'q' {
+            // DECSCUSR - Set Cursor Style
+            if let Some(style) = cursor_style_from_param(flat_params.first().copied().unwrap_or(0)) {
+                self.cursor_style = style;
+            }
+            return;
+        }
+
         match action {
             'A' => {
                 // CUU - Cursor Up
@@ -935,6 +2488,17 @@ impl Perform for ParserState {
                     _ => {}
                 }
             }
+            'g' => {
+                // TBC - Tab Clear: 0 (or no param) clears the stop at the
+                // cursor, 3 clears all stops.
+                match flat_params.first().copied().unwrap_or(0) {
+                    0 => {
+                        self.tab_stops.remove(&(self.cursor.col as u16));
+                    }
+                    3 => self.tab_stops.clear(),
+                    _ => {}
+                }
+            }
             'r' => {
                 // DECSTBM - Set Top and Bottom Margins
                 let top = flat_params.first().copied().unwrap_or(1) as usize;
@@ -956,9 +2520,10 @@ impl Perform for ParserState {
                     self.fg_color = Color::Default;
                     self.bg_color = Color::Default;
                 } else {
+                    let (sgr_params, underline_override) = extract_extended_underline(&params_vec);
                     let mut i = 0;
-                    while i < flat_params.len() {
-                        match flat_params[i] {
+                    while i < sgr_params.len() {
+                        match sgr_params[i] {
                             0 => {
                                 // Reset
                                 self.attributes.reset();
@@ -968,7 +2533,7 @@ impl Perform for ParserState {
                             1 => self.attributes.bold = true,
                             2 => self.attributes.dim = true,
                             3 => self.attributes.italic = true,
-                            4 => self.attributes.underline = true,
+                            4 => self.attributes.underline = UnderlineStyle::Single,
                             5 => self.attributes.blink = true,
                             7 => self.attributes.reverse = true,
                             8 => self.attributes.hidden = true,
@@ -978,33 +2543,34 @@ impl Perform for ParserState {
                                 self.attributes.dim = false;
                             }
                             23 => self.attributes.italic = false,
-                            24 => self.attributes.underline = false,
+                            24 => self.attributes.underline = UnderlineStyle::None,
                             25 => self.attributes.blink = false,
                             27 => self.attributes.reverse = false,
                             28 => self.attributes.hidden = false,
                             29 => self.attributes.strikethrough = false,
                             30..=37 => {
                                 // Standard foreground colors (3-bit)
-                                self.fg_color = Color::Indexed((flat_params[i] - 30) as u8);
+                                self.fg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 30, false));
                             }
                             38 => {
                                 // Extended foreground color
-                                if i + 1 < flat_params.len() {
-                                    match flat_params[i + 1] {
+                                if i + 1 < sgr_params.len() {
+                                    match sgr_params[i + 1] {
                                         5 => {
                                             // 256-color
-                                            if i + 2 < flat_params.len() {
-                                                self.fg_color = Color::Indexed(flat_params[i + 2] as u8);
+                                            if i + 2 < sgr_params.len() {
+                                                self.fg_color = Color::Indexed(sgr_params[i + 2] as u8);
                                                 i += 2;
                                             }
                                         }
                                         2 => {
                                             // 24-bit color
-                                            if i + 4 < flat_params.len() {
+                                            if i + 4 < sgr_params.len() {
                                                 self.fg_color = Color::Rgb(
-                                                    flat_params[i + 2] as u8,
-                                                    flat_params[i + 3] as u8,
-                                                    flat_params[i + 4] as u8,
+                                                    sgr_params[i + 2] as u8,
+                                                    sgr_params[i + 3] as u8,
+                                                    sgr_params[i + 4] as u8,
                                                 );
                                                 i += 4;
                                             }
@@ -1016,26 +2582,27 @@ impl Perform for ParserState {
                             39 => self.fg_color = Color::Default,
                             40..=47 => {
                                 // Standard background colors (3-bit)
-                                self.bg_color = Color::Indexed((flat_params[i] - 40) as u8);
+                                self.bg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 40, false));
                             }
                             48 => {
                                 // Extended background color
-                                if i + 1 < flat_params.len() {
-                                    match flat_params[i + 1] {
+                                if i + 1 < sgr_params.len() {
+                                    match sgr_params[i + 1] {
                                         5 => {
                                             // 256-color
-                                            if i + 2 < flat_params.len() {
-                                                self.bg_color = Color::Indexed(flat_params[i + 2] as u8);
+                                            if i + 2 < sgr_params.len() {
+                                                self.bg_color = Color::Indexed(sgr_params[i + 2] as u8);
                                                 i += 2;
                                             }
                                         }
                                         2 => {
                                             // 24-bit color
-                                            if i + 4 < flat_params.len() {
+                                            if i + 4 < sgr_params.len() {
                                                 self.bg_color = Color::Rgb(
-                                                    flat_params[i + 2] as u8,
-                                                    flat_params[i + 3] as u8,
-                                                    flat_params[i + 4] as u8,
+                                                    sgr_params[i + 2] as u8,
+                                                    sgr_params[i + 3] as u8,
+                                                    sgr_params[i + 4] as u8,
                                                 );
                                                 i += 4;
                                             }
@@ -1047,32 +2614,46 @@ impl Perform for ParserState {
                             49 => self.bg_color = Color::Default,
                             90..=97 => {
                                 // Bright foreground colors
-                                self.fg_color = Color::Indexed((flat_params[i] - 90 + 8) as u8);
+                                self.fg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 90, true));
                             }
                             100..=107 => {
                                 // Bright background colors
-                                self.bg_color = Color::Indexed((flat_params[i] - 100 + 8) as u8);
+                                self.bg_color =
+                                    Color::Named(standard_named_color(sgr_params[i] - 100, true));
                             }
                             _ => {}
                         }
                         i += 1;
                     }
+                    if let Some(style) = underline_override {
+                        self.attributes.underline = style;
+                    }
                 }
             }
             's' => {
                 // SCP - Save Cursor Position
                 self.saved_cursor = self.cursor;
                 self.saved_attributes = self.attributes;
+                self.saved_origin_mode = self.origin_mode;
+                self.saved_mode = self.mode;
             }
             'u' => {
                 // RCP - Restore Cursor Position
                 self.cursor = self.saved_cursor;
                 self.attributes = self.saved_attributes;
+                self.origin_mode = self.saved_origin_mode;
+                self.mode = self.saved_mode;
             }
             'l' | 'h' => {
-                // SM/RM - Set/Reset Mode
-                // Handle cursor visibility (DECTCEM): ?25l / ?25h
-                // Note: '?' prefix handling would be in intermediates
+                // SM/RM - Set/Reset Mode (ANSI, non-DEC-private). DEC private
+                // modes (`CSI ? Pm h/l`) are handled above via `intermediates`.
+                // IRM (4) is the only one tracked; the rest are no-ops.
+                for code in &flat_params {
+                    if *code == 4 {
+                        self.mode.set(TermMode::INSERT, action == 'h');
+                    }
+                }
             }
             _ => {
                 // Unknown CSI sequence
@@ -1081,7 +2662,11 @@ impl Perform for ParserState {
     }
 
     /// Handle an ESC escape sequence.
-    fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, byte: u8) {
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if self.apply_charset_designation(intermediates.first().copied(), byte) {
+            return;
+        }
+
         match byte {
             b'c' => {
                 // RIS - Reset to Initial State
@@ -1089,6 +2674,18 @@ impl Perform for ParserState {
                 self.attributes = TextAttributes::default();
                 self.fg_color = Color::Default;
                 self.bg_color = Color::Default;
+                self.charset_g0 = Charset::Ascii;
+                self.charset_g1 = Charset::Ascii;
+                self.charset_slot = CharsetSlot::G0;
+                self.origin_mode = false;
+                self.saved_origin_mode = false;
+                self.sync_buffer.clear();
+                self.sync_started_at = None;
+                self.reset_tab_stops(self.cols as usize);
+            }
+            b'H' => {
+                // HTS - Horizontal Tab Set (set a tab stop at the cursor)
+                self.tab_stops.insert(self.cursor.col as u16);
             }
             b'M' => {
                 // RI - Reverse Index (move up, scroll if needed)
@@ -1109,11 +2706,15 @@ impl Perform for ParserState {
                 // DECSC - Save Cursor
                 self.saved_cursor = self.cursor;
                 self.saved_attributes = self.attributes;
+                self.saved_origin_mode = self.origin_mode;
+                self.saved_mode = self.mode;
             }
             b'8' => {
                 // DECRC - Restore Cursor
                 self.cursor = self.saved_cursor;
                 self.attributes = self.saved_attributes;
+                self.origin_mode = self.saved_origin_mode;
+                self.mode = self.saved_mode;
             }
             _ => {}
         }
@@ -1182,7 +2783,7 @@ mod tests {
 
         // Set foreground to red (CSI 31 m)
         parser.parse_bytes(b"\x1B[31m");
-        assert_eq!(parser.foreground_color(), Color::Indexed(1));
+        assert_eq!(parser.foreground_color(), Color::Named(NamedColor::Red));
 
         // Set foreground to default (CSI 39 m)
         parser.parse_bytes(b"\x1B[39m");
@@ -1197,6 +2798,42 @@ mod tests {
         assert_eq!(parser.foreground_color(), Color::Rgb(255, 128, 64));
     }
 
+    #[test]
+    fn test_extended_underline_styles() {
+        let mut parser = TerminalParser::new();
+
+        // Legacy bare underline (CSI 4 m) still yields a single underline.
+        parser.parse_bytes(b"\x1B[4m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::Single);
+
+        // Colon-form extended underline (CSI 4:Ps m) selects a style.
+        parser.parse_bytes(b"\x1B[4:2m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::Double);
+
+        parser.parse_bytes(b"\x1B[4:3m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::Curly);
+
+        parser.parse_bytes(b"\x1B[4:4m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::Dotted);
+
+        parser.parse_bytes(b"\x1B[4:5m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::Dashed);
+
+        parser.parse_bytes(b"\x1B[4:0m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::None);
+
+        // Legacy reset (CSI 24 m) still turns it off.
+        parser.parse_bytes(b"\x1B[4:3m");
+        parser.parse_bytes(b"\x1B[24m");
+        assert_eq!(parser.attributes().underline, UnderlineStyle::None);
+
+        // An extended underline alongside an ordinary SGR code in the same
+        // sequence doesn't let the colon group leak in as a bare code.
+        parser.parse_bytes(b"\x1B[1;4:3m");
+        assert!(parser.attributes().bold);
+        assert_eq!(parser.attributes().underline, UnderlineStyle::Curly);
+    }
+
     #[test]
     fn test_control_characters() {
         let mut parser = TerminalParser::new();
@@ -1279,11 +2916,28 @@ mod tests {
         parser.parse_bytes(b"\x1B[1;31m\x1B[10;20H");
 
         assert!(parser.attributes().bold);
-        assert_eq!(parser.foreground_color(), Color::Indexed(1)); // Red
+        assert_eq!(parser.foreground_color(), Color::Named(NamedColor::Red));
         assert_eq!(parser.cursor_position().row, 9);
         assert_eq!(parser.cursor_position().col, 19);
     }
 
+    #[test]
+    fn test_parse_bytes_advances_cursor_by_glyph_width() {
+        let mut parser = TerminalParser::new();
+
+        // Narrow ASCII advances by one column per glyph.
+        parser.parse_bytes(b"ab");
+        assert_eq!(parser.cursor_position().col, 2);
+
+        // A wide CJK glyph advances by two.
+        parser.parse_bytes("国".as_bytes());
+        assert_eq!(parser.cursor_position().col, 4);
+
+        // A combining mark doesn't advance at all.
+        parser.parse_bytes("e\u{0301}".as_bytes());
+        assert_eq!(parser.cursor_position().col, 5);
+    }
+
     // ===== Scroll Region Tests =====
 
     #[test]
@@ -1350,10 +3004,80 @@ mod tests {
         assert_eq!(parser.state.scroll_region_bottom, 23);
     }
 
+    #[test]
+    fn test_decom_toggles_origin_mode() {
+        let mut parser = TerminalParser::with_size(80, 24);
+        assert!(!parser.state.origin_mode);
+
+        parser.parse_bytes(b"\x1B[?6h");
+        assert!(parser.state.origin_mode);
+
+        parser.parse_bytes(b"\x1B[?6l");
+        assert!(!parser.state.origin_mode);
+    }
+
+    #[test]
+    fn test_irm_toggles_insert_mode() {
+        let mut parser = TerminalParser::with_size(80, 24);
+        assert!(!parser.state.mode.contains(TermMode::INSERT));
+
+        // CSI 4 h - no `?` prefix, so this is the ANSI mode, not a DEC
+        // private one.
+        parser.parse_bytes(b"\x1B[4h");
+        assert!(parser.state.mode.contains(TermMode::INSERT));
+
+        parser.parse_bytes(b"\x1B[4l");
+        assert!(!parser.state.mode.contains(TermMode::INSERT));
+    }
+
+    #[test]
+    fn test_decstbm_homes_cursor_to_region_top_with_origin_mode() {
+        let mut parser = TerminalParser::with_size(80, 24);
+        parser.parse_bytes(b"\x1B[?6h\x1B[5;15r");
+
+        // With origin mode on, CSI r homes to the region's top-left, not 0,0.
+        assert_eq!(parser.cursor_position().row, 4);
+        assert_eq!(parser.cursor_position().col, 0);
+    }
+
+    #[test]
+    fn test_cup_clamped_to_region_with_origin_mode() {
+        let mut parser = TerminalParser::with_size(80, 24);
+        parser.parse_bytes(b"\x1B[5;15r\x1B[?6h");
+
+        // Row 1 is relative to the region top (row 4), landing on row 4.
+        parser.parse_bytes(b"\x1B[1;1H");
+        assert_eq!(parser.cursor_position().row, 4);
+
+        // A row past the region bottom clamps to it instead of the screen's.
+        parser.parse_bytes(b"\x1B[50;1H");
+        assert_eq!(parser.cursor_position().row, 14);
+    }
+
+    #[test]
+    fn test_cup_uses_screen_absolute_rows_with_origin_mode_off() {
+        let mut parser = TerminalParser::with_size(80, 24);
+        parser.parse_bytes(b"\x1B[5;15r");
+
+        parser.parse_bytes(b"\x1B[1;1H");
+        assert_eq!(parser.cursor_position().row, 0);
+    }
+
+    #[test]
+    fn test_origin_mode_survives_decsc_decrc_round_trip() {
+        let mut parser = TerminalParser::with_size(80, 24);
+        parser.parse_bytes(b"\x1B[?6h\x1B7");
+        parser.parse_bytes(b"\x1B[?6l");
+        assert!(!parser.state.origin_mode);
+
+        parser.parse_bytes(b"\x1B8");
+        assert!(parser.state.origin_mode);
+    }
+
     #[test]
     fn test_scroll_region_state() {
         let mut state = ParserState::default();
-        state.set_terminal_size(24);
+        state.set_terminal_size(80, 24);
         
         assert_eq!(state.scroll_region_top, 0);
         assert_eq!(state.scroll_region_bottom, 23);
@@ -1367,7 +3091,7 @@ mod tests {
     #[test]
     fn test_cursor_in_scroll_region() {
         let mut state = ParserState::default();
-        state.set_terminal_size(24);
+        state.set_terminal_size(80, 24);
         state.scroll_region_top = 5;
         state.scroll_region_bottom = 15;
         
@@ -1420,4 +3144,525 @@ mod tests {
         assert_eq!(parser.state.scroll_region_top, 4);
         assert_eq!(parser.state.scroll_region_bottom, 14);
     }
+
+    // ===== Palette / Named Color Tests =====
+
+    #[test]
+    fn test_palette_defaults() {
+        let palette = Palette::new();
+        assert_eq!(palette.get(0), Rgb::new(0x00, 0x00, 0x00));
+        assert_eq!(palette.get(7), Rgb::new(0xe5, 0xe5, 0xe5));
+        assert_eq!(palette.foreground(), Rgb::new(0xe5, 0xe5, 0xe5));
+        assert_eq!(palette.background(), Rgb::new(0x00, 0x00, 0x00));
+    }
+
+    #[test]
+    fn test_resolve_named_color() {
+        let parser = TerminalParser::new();
+        assert_eq!(
+            parser.resolve(Color::Named(NamedColor::Red)),
+            Rgb::new(0xcd, 0x00, 0x00)
+        );
+        assert_eq!(
+            parser.resolve(Color::Named(NamedColor::BrightRed)),
+            Rgb::new(0xff, 0x00, 0x00)
+        );
+        assert_eq!(parser.resolve(Color::Rgb(1, 2, 3)), Rgb::new(1, 2, 3));
+        assert_eq!(parser.resolve(Color::Default), parser.palette().foreground());
+    }
+
+    #[test]
+    fn test_osc_4_sets_palette_slot() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]4;1;rgb:ff/00/00\x07");
+        assert_eq!(parser.palette().get(1), Rgb::new(0xff, 0x00, 0x00));
+    }
+
+    #[test]
+    fn test_osc_104_resets_palette_slot() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]4;1;rgb:ff/00/00\x07");
+        parser.parse_bytes(b"\x1B]104;1\x07");
+        assert_eq!(parser.palette().get(1), Rgb::new(0xcd, 0x00, 0x00));
+    }
+
+    #[test]
+    fn test_osc_104_resets_all_slots() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]4;1;rgb:11/22/33\x07");
+        parser.parse_bytes(b"\x1B]104\x07");
+        assert_eq!(parser.palette().get(1), Rgb::new(0xcd, 0x00, 0x00));
+    }
+
+    #[test]
+    fn test_osc_10_11_12_set_default_colors() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]10;rgb:11/22/33\x07");
+        parser.parse_bytes(b"\x1B]11;rgb:44/55/66\x07");
+        parser.parse_bytes(b"\x1B]12;rgb:77/88/99\x07");
+        assert_eq!(parser.palette().foreground(), Rgb::new(0x11, 0x22, 0x33));
+        assert_eq!(parser.palette().background(), Rgb::new(0x44, 0x55, 0x66));
+        assert_eq!(parser.palette().cursor(), Rgb::new(0x77, 0x88, 0x99));
+    }
+
+    #[test]
+    fn test_parse_rgb_spec_scales_short_hex() {
+        // A single hex digit should scale to the full 8-bit range.
+        assert_eq!(parse_rgb_spec(b"rgb:f/0/0"), Some(Rgb::new(0xff, 0x00, 0x00)));
+        assert_eq!(parse_rgb_spec(b"not-a-spec"), None);
+    }
+
+    #[test]
+    fn test_xparse_color_accepts_legacy_hex() {
+        assert_eq!(xparse_color(b"#ff0000"), Some(Color::Rgb(0xff, 0x00, 0x00)));
+        assert_eq!(xparse_color(b"#f00"), Some(Color::Rgb(0xff, 0x00, 0x00)));
+        assert_eq!(xparse_color(b"rgb:ff/00/00"), Some(Color::Rgb(0xff, 0x00, 0x00)));
+        assert_eq!(xparse_color(b"not-a-color"), None);
+    }
+
+    #[test]
+    fn test_osc_4_query_responds_with_xparse_color() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]4;1;?\x07");
+        assert_eq!(parser.take_pending_responses(), vec!["\x1b]4;1;rgb:cdcd/0000/0000\x07"]);
+    }
+
+    #[test]
+    fn test_osc_10_11_query_respond_with_current_colors() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]10;?\x07");
+        parser.parse_bytes(b"\x1B]11;?\x07");
+        assert_eq!(
+            parser.take_pending_responses(),
+            vec![
+                "\x1b]10;rgb:e5e5/e5e5/e5e5\x07",
+                "\x1b]11;rgb:0000/0000/0000\x07",
+            ]
+        );
+    }
+
+    // ===== OSC 52 Clipboard Tests =====
+
+    #[test]
+    fn test_base64_round_trips() {
+        assert_eq!(base64_encode(b"hello"), "aGVsbG8=");
+        assert_eq!(base64_decode(b"aGVsbG8="), Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn test_osc_52_sets_clipboard() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]52;c;aGVsbG8=\x07");
+        assert_eq!(parser.clipboard(), "hello");
+    }
+
+    #[test]
+    fn test_osc_52_query_responds_with_encoded_clipboard() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]52;c;aGVsbG8=\x07");
+        parser.parse_bytes(b"\x1B]52;c;?\x07");
+        assert_eq!(parser.take_pending_responses(), vec!["\x1b]52;c;aGVsbG8=\x07"]);
+    }
+
+    // ===== Synchronized Update (DCS) Tests =====
+
+    #[test]
+    fn test_sync_update_buffers_until_end_marker() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1BP=1s\x1b\\");
+        assert!(parser.is_synchronizing());
+
+        parser.parse_bytes(b"\x1B[5;5H");
+        assert!(parser.is_synchronizing());
+        assert_eq!(parser.cursor_position(), CursorPosition::default());
+
+        parser.parse_bytes(b"\x1BP=2s\x1b\\");
+        assert!(!parser.is_synchronizing());
+        assert_eq!(parser.cursor_position(), CursorPosition { row: 4, col: 4 });
+    }
+
+    #[test]
+    fn test_sync_update_aborts_past_buffer_limit() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1BP=1s\x1b\\");
+        parser.parse_bytes(&vec![b'a'; SYNC_BUFFER_LIMIT + 1]);
+        assert!(!parser.is_synchronizing());
+    }
+
+    #[test]
+    fn test_sync_update_aborts_past_timeout() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1BP=1s\x1b\\");
+        std::thread::sleep(SYNC_TIMEOUT + Duration::from_millis(10));
+        parser.parse_bytes(b"x");
+        assert!(!parser.is_synchronizing());
+    }
+
+    #[test]
+    fn test_ris_clears_pending_sync_update() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1BP=1s\x1b\\");
+        assert!(parser.is_synchronizing());
+        parser.parse_bytes(b"\x1Bc");
+        assert!(!parser.is_synchronizing());
+    }
+
+    // ===== Title / OSC Tests =====
+
+    #[test]
+    fn test_osc_0_sets_title() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]0;my title\x07");
+        assert_eq!(parser.title(), "my title");
+    }
+
+    #[test]
+    fn test_osc_1_and_2_set_title() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]1;icon title\x07");
+        assert_eq!(parser.title(), "icon title");
+        parser.parse_bytes(b"\x1B]2;window title\x07");
+        assert_eq!(parser.title(), "window title");
+    }
+
+    #[test]
+    fn test_osc_22_23_push_pop_title() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]0;first\x07");
+        parser.parse_bytes(b"\x1B]22\x07");
+        parser.parse_bytes(b"\x1B]0;second\x07");
+        assert_eq!(parser.title(), "second");
+        parser.parse_bytes(b"\x1B]23\x07");
+        assert_eq!(parser.title(), "first");
+    }
+
+    #[test]
+    fn test_osc_23_pop_with_empty_stack_is_noop() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]0;only\x07");
+        parser.parse_bytes(b"\x1B]23\x07");
+        assert_eq!(parser.title(), "only");
+    }
+
+    #[test]
+    fn test_title_stack_depth_is_capped() {
+        let mut title = String::from("base");
+        let mut stack = Vec::new();
+        for _ in 0..(TITLE_STACK_MAX_DEPTH + 10) {
+            apply_title_osc(&mut title, &mut stack, &[b"22"]);
+        }
+        assert_eq!(stack.len(), TITLE_STACK_MAX_DEPTH);
+    }
+
+    // ===== Double-Width Character Tests =====
+
+    #[test]
+    fn test_wide_char_occupies_two_cells_with_spacer() {
+        let mut parser = TerminalParser::with_size(10, 4);
+        parser.put_char('国');
+        let output = parser.output();
+        assert_eq!(output[0].char, '国');
+        assert!(output[1].is_wide_spacer());
+    }
+
+    #[test]
+    fn test_wide_char_advances_cursor_by_two() {
+        let mut parser = TerminalParser::with_size(10, 4);
+        parser.put_char('国');
+        parser.put_char('A');
+        let output = parser.output();
+        assert_eq!(output[2].char, 'A');
+    }
+
+    #[test]
+    fn test_combining_char_is_dropped_without_consuming_a_cell() {
+        let mut parser = TerminalParser::with_size(10, 4);
+        parser.put_char('e');
+        parser.put_char('\u{0301}'); // combining acute accent
+        parser.put_char('f');
+        let output = parser.output();
+        assert_eq!(output[0].char, 'e');
+        assert_eq!(output[1].char, 'f');
+    }
+
+    #[test]
+    fn test_wide_char_wraps_when_it_does_not_fit_last_column() {
+        let mut parser = TerminalParser::with_size(3, 4);
+        parser.put_char('A');
+        parser.put_char('B');
+        // Only the last column (index 2) is left - not enough for a wide char.
+        parser.put_char('国');
+        let output = parser.output();
+        assert_eq!(output[2].char, ' '); // blanked last column of row 0
+        assert_eq!(output[3].char, '国'); // wrapped to start of row 1
+        assert!(output[4].is_wide_spacer());
+    }
+
+    // ===== Charset (G0/G1, DEC Special Graphics) Tests =====
+
+    #[test]
+    fn test_special_graphics_char_maps_line_drawing() {
+        assert_eq!(special_graphics_char('q'), '─');
+        assert_eq!(special_graphics_char('l'), '┌');
+        assert_eq!(special_graphics_char('j'), '┘');
+        // Bytes outside the mapped set pass through unchanged.
+        assert_eq!(special_graphics_char('z'), 'z');
+    }
+
+    #[test]
+    fn test_esc_designates_g0_charset() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B(0");
+        assert_eq!(parser.state.charset_g0, Charset::SpecialGraphics);
+
+        parser.parse_bytes(b"\x1B(B");
+        assert_eq!(parser.state.charset_g0, Charset::Ascii);
+    }
+
+    #[test]
+    fn test_si_so_switch_active_charset_slot() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B)0"); // designate G1 as special graphics
+        assert_eq!(parser.state.active_charset(), Charset::Ascii); // G0 still active
+
+        parser.parse_bytes(b"\x0E"); // SO - select G1
+        assert_eq!(parser.state.active_charset(), Charset::SpecialGraphics);
+
+        parser.parse_bytes(b"\x0F"); // SI - select G0
+        assert_eq!(parser.state.active_charset(), Charset::Ascii);
+    }
+
+    #[test]
+    fn test_ris_resets_charset_state() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B(0\x0E");
+        assert_eq!(parser.state.charset_slot, CharsetSlot::G1);
+
+        parser.parse_bytes(b"\x1Bc");
+        assert_eq!(parser.state.charset_g0, Charset::Ascii);
+        assert_eq!(parser.state.charset_slot, CharsetSlot::G0);
+    }
+
+    // ===== OSC 8 Hyperlink Tests =====
+
+    #[test]
+    fn test_osc_8_opens_and_put_char_tags_cell() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]8;;https://example.com\x07");
+        assert_eq!(
+            parser.state.open_hyperlink.as_ref().map(|link| link.uri.as_str()),
+            Some("https://example.com")
+        );
+
+        parser.put_char('x');
+        let link = parser.output()[0].hyperlink.as_ref().expect("cell should carry the open link");
+        assert_eq!(link.uri, "https://example.com");
+    }
+
+    #[test]
+    fn test_osc_8_parses_id_param() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]8;id=foo;https://example.com\x07");
+        let link = parser.state.open_hyperlink.as_ref().unwrap();
+        assert_eq!(link.id.as_deref(), Some("foo"));
+        assert_eq!(link.uri, "https://example.com");
+    }
+
+    #[test]
+    fn test_osc_8_empty_uri_closes_link() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]8;;https://example.com\x07");
+        parser.parse_bytes(b"\x1B]8;;\x07");
+        assert!(parser.state.open_hyperlink.is_none());
+
+        parser.put_char('x');
+        assert!(parser.output()[0].hyperlink.is_none());
+    }
+
+    #[test]
+    fn test_osc_8_same_id_reuses_hyperlink_arc() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]8;id=foo;https://example.com\x07");
+        let first = parser.state.open_hyperlink.clone().unwrap();
+
+        parser.parse_bytes(b"\x1B]8;;\x07");
+        parser.parse_bytes(b"\x1B]8;id=foo;https://example.com\x07");
+        let second = parser.state.open_hyperlink.clone().unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_osc_8_without_id_never_shares_hyperlink_arc() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B]8;;https://example.com\x07");
+        let first = parser.state.open_hyperlink.clone().unwrap();
+
+        parser.parse_bytes(b"\x1B]8;;\x07");
+        parser.parse_bytes(b"\x1B]8;;https://example.com\x07");
+        let second = parser.state.open_hyperlink.clone().unwrap();
+
+        assert_eq!(first.uri, second.uri);
+        assert!(!Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_known_hyperlinks_prunes_entries_nothing_still_references() {
+        let mut open = None;
+        let mut known = HashMap::new();
+
+        apply_hyperlink_osc(&mut open, &mut known, &[b"8", b"id=a", b"https://a.example"]);
+        // Stand in for a cell's own `Arc` clone, kept alive independently of
+        // `open_hyperlink`.
+        let cell_ref = open.clone().unwrap();
+        apply_hyperlink_osc(&mut open, &mut known, &[b"8", b""]);
+
+        apply_hyperlink_osc(&mut open, &mut known, &[b"8", b"id=b", b"https://b.example"]);
+        assert!(known.contains_key("a"), "still referenced by cell_ref - must not be pruned yet");
+
+        drop(cell_ref);
+        apply_hyperlink_osc(&mut open, &mut known, &[b"8", b"id=c", b"https://c.example"]);
+        assert!(
+            !known.contains_key("a"),
+            "nothing outside the map references it anymore - should have been pruned"
+        );
+    }
+
+    #[test]
+    fn test_dectcem_toggles_cursor_visibility() {
+        let mut parser = TerminalParser::new();
+        assert!(parser.cursor_visible());
+
+        parser.parse_bytes(b"\x1B[?25l");
+        assert!(!parser.cursor_visible());
+
+        parser.parse_bytes(b"\x1B[?25h");
+        assert!(parser.cursor_visible());
+    }
+
+    #[test]
+    fn test_decset_decrst_track_term_mode_flags() {
+        let mut parser = TerminalParser::new();
+        assert!(parser.has_mode(TermMode::SHOW_CURSOR));
+
+        parser.parse_bytes(b"\x1B[?25l");
+        assert!(!parser.has_mode(TermMode::SHOW_CURSOR));
+
+        parser.parse_bytes(b"\x1B[?25h");
+        assert!(parser.has_mode(TermMode::SHOW_CURSOR));
+
+        parser.parse_bytes(b"\x1B[?1004h");
+        assert!(parser.has_mode(TermMode::REPORT_FOCUS));
+
+        parser.parse_bytes(b"\x1B[?1004l");
+        assert!(!parser.has_mode(TermMode::REPORT_FOCUS));
+    }
+
+    #[test]
+    fn test_term_mode_survives_decsc_decrc_round_trip() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B[?1004h\x1B7");
+        parser.parse_bytes(b"\x1B[?1004l");
+        assert!(!parser.has_mode(TermMode::REPORT_FOCUS));
+
+        parser.parse_bytes(b"\x1B8");
+        assert!(parser.has_mode(TermMode::REPORT_FOCUS));
+    }
+
+    #[test]
+    fn test_custom_tab_stop_via_hts() {
+        let mut parser = TerminalParser::new();
+        parser.state.cursor.col = 3;
+        parser.parse_bytes(b"\x1BH"); // HTS at column 3
+
+        parser.state.cursor.col = 0;
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 3); // custom stop, not the default 8
+
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 8); // falls back to the default stop
+    }
+
+    #[test]
+    fn test_tbc_clears_tab_stops() {
+        let mut parser = TerminalParser::new();
+
+        // CSI 0 g clears just the stop at the cursor.
+        parser.state.cursor.col = 8;
+        parser.parse_bytes(b"\x1B[0g");
+        parser.state.cursor.col = 0;
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 16); // 8 was cleared, 16 remains
+
+        // CSI 3 g clears every stop.
+        parser.parse_bytes(b"\x1B[3g");
+        parser.state.cursor.col = 0;
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 79); // no stops left, last column
+    }
+
+    #[test]
+    fn test_tab_stops_rebuilt_on_resize_and_ris() {
+        let mut parser = TerminalParser::with_size(20, 24);
+        parser.parse_bytes(b"\x1B[3g"); // clear all stops
+        parser.state.cursor.col = 0;
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 19); // no stops left, last column
+
+        parser.resize(20, 24);
+        parser.state.cursor.col = 0;
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 8); // resize rebuilt the default stops
+
+        parser.parse_bytes(b"\x1B[3g"); // clear all stops again
+        parser.parse_bytes(b"\x1Bc"); // RIS
+        parser.state.cursor.col = 0;
+        parser.parse_bytes(b"\t");
+        assert_eq!(parser.cursor_position().col, 8); // RIS rebuilt the default stops
+    }
+
+    #[test]
+    fn test_multiple_bells_in_one_buffer_increment_audible_count() {
+        let mut parser = TerminalParser::new();
+        assert_eq!(parser.audible_bell_count(), 0);
+
+        parser.parse_bytes(b"\x07hello\x07\x07");
+        assert_eq!(parser.audible_bell_count(), 3);
+        assert_eq!(parser.visible_bell_count(), 0);
+    }
+
+    #[test]
+    fn test_visual_bell_mode_counts_separately() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B[?1042h\x07\x07");
+        assert_eq!(parser.visible_bell_count(), 2);
+        assert_eq!(parser.audible_bell_count(), 0);
+
+        parser.parse_bytes(b"\x1B[?1042l\x07");
+        assert_eq!(parser.visible_bell_count(), 2);
+        assert_eq!(parser.audible_bell_count(), 1);
+    }
+
+    #[test]
+    fn test_decscusr_sets_cursor_style() {
+        let mut parser = TerminalParser::new();
+        assert_eq!(parser.cursor_style(), CursorStyle::Block);
+
+        parser.parse_bytes(b"\x1B[4 q");
+        assert_eq!(parser.cursor_style(), CursorStyle::Beam);
+
+        parser.parse_bytes(b"\x1B[1 q");
+        assert_eq!(parser.cursor_style(), CursorStyle::BlinkingBlock);
+    }
+
+    #[test]
+    fn test_decscusr_ignores_out_of_range_param() {
+        let mut parser = TerminalParser::new();
+        parser.parse_bytes(b"\x1B[2 q");
+        assert_eq!(parser.cursor_style(), CursorStyle::Underline);
+
+        // Unknown param leaves the prior style untouched.
+        parser.parse_bytes(b"\x1B[9 q");
+        assert_eq!(parser.cursor_style(), CursorStyle::Underline);
+    }
 }
@@ -3,11 +3,31 @@
 //! This module provides cross-platform PTY spawning and I/O operations
 //! for the terminal emulator.
 
-use std::io::{Read, Write};
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
-
+use std::task::{Context, Poll};
+use std::thread;
+
+#[cfg(unix)]
+use std::ffi::CString;
+#[cfg(unix)]
+use std::fs::File;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+
+use bytes::Bytes;
+use futures::Stream;
 use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
 use thiserror::Error;
+use tokio::io::AsyncWrite;
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+
+use super::pty_record::PtyRecorder;
+use super::shell::ShellKind;
 
 /// Errors that can occur during PTY operations
 #[derive(Error, Debug)]
@@ -37,6 +57,44 @@ pub enum PtyError {
 /// Result type for PTY operations
 pub type PtyResult<T> = Result<T, PtyError>;
 
+/// Parent-side FIFO handles for any `StdioTarget::Piped` stream (see
+/// `PtyConfig::stdin`/`stdout`/`stderr`). Piped-stream redirection only
+/// exists on Unix - it leans on FIFOs plus the session being spawned
+/// through a wrapping shell, neither of which has a ConPTY equivalent - so
+/// on other platforms this carries nothing and every `StdioTarget` other
+/// than `Pty` is rejected at `spawn` time instead.
+#[derive(Default)]
+struct PipedStdio {
+    #[cfg(unix)]
+    stdin: Option<File>,
+    #[cfg(unix)]
+    stdout: Option<File>,
+    #[cfg(unix)]
+    stderr: Option<File>,
+    /// Paths of any FIFOs created for this session, best-effort cleaned up
+    /// once the child has had a chance to open them (see
+    /// `PtySession::drop`).
+    #[cfg(unix)]
+    fifo_paths: Vec<std::path::PathBuf>,
+}
+
+/// Where to attach one of the child's standard streams, overriding the
+/// default of binding it to the PTY slave like every stream did before this
+/// existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StdioTarget {
+    /// Bind to the PTY slave - an interactive stream the shell/program can
+    /// read from and write to like a real terminal.
+    #[default]
+    Pty,
+    /// Discard: redirect to `/dev/null`.
+    Null,
+    /// Capture separately, through a private FIFO. The parent-side handle
+    /// is returned from `PtySession::take_piped_stdin`/`take_piped_stdout`/
+    /// `take_piped_stderr` once the child is spawned.
+    Piped,
+}
+
 /// Configuration for PTY session
 #[derive(Debug, Clone)]
 pub struct PtyConfig {
@@ -50,6 +108,16 @@ pub struct PtyConfig {
     pub working_dir: Option<String>,
     /// Environment variables to set
     pub env: Vec<(String, String)>,
+    /// Where to attach the child's stdin. Defaults to the PTY slave.
+    pub stdin: StdioTarget,
+    /// Where to attach the child's stdout. Defaults to the PTY slave.
+    pub stdout: StdioTarget,
+    /// Where to attach the child's stderr. Defaults to the PTY slave; the
+    /// main use case for overriding any of these three is capturing a
+    /// command's stderr separately while still giving it an interactive
+    /// PTY stdin/stdout (e.g. to keep a progress bar written to stderr out
+    /// of the scrollback the grid renders).
+    pub stderr: StdioTarget,
 }
 
 impl Default for PtyConfig {
@@ -60,10 +128,28 @@ impl Default for PtyConfig {
             rows: 24,
             working_dir: None,
             env: Vec::new(),
+            stdin: StdioTarget::Pty,
+            stdout: StdioTarget::Pty,
+            stderr: StdioTarget::Pty,
         }
     }
 }
 
+/// A signal to deliver to a `PtySession`'s child via `send_signal`. Windows
+/// has no signal equivalent, so every variant there maps to the same
+/// `TerminateProcess` call `portable_pty::Child::kill` already makes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// SIGINT: what Ctrl+C sends.
+    Interrupt,
+    /// SIGTERM: ask the process to exit.
+    Terminate,
+    /// SIGHUP: what closing the controlling terminal sends.
+    Hangup,
+    /// SIGKILL: force-exit, unignorable and unblockable.
+    Kill,
+}
+
 /// Writer handle for sending input to the PTY
 pub struct PtyWriter {
     writer: Box<dyn Write + Send>,
@@ -177,6 +263,117 @@ impl PtyReader {
     }
 }
 
+/// Async, `Stream`-based counterpart to `PtyReader::read_batch`/`take_batch`:
+/// chunks arrive as they're read instead of requiring the caller to poll in
+/// a loop. Fed by a dedicated OS thread (see `PtySession::spawn_async`),
+/// since `portable_pty`'s master fd has no portable way to register with an
+/// async reactor (on Windows it isn't pollable at all).
+pub struct PtyStream {
+    rx: tokio_mpsc::UnboundedReceiver<PtyResult<Bytes>>,
+}
+
+impl Stream for PtyStream {
+    type Item = PtyResult<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// One queued write, handed off to the background writer thread along with
+/// a channel to report back how it went.
+struct PtyWriteRequest {
+    data: Vec<u8>,
+    reply: oneshot::Sender<io::Result<usize>>,
+}
+
+/// `AsyncWrite` counterpart to `PtyWriter`, backed by a dedicated OS thread
+/// that owns the blocking writer - same reasoning as `PtyStream`, but for
+/// the write side (see `PtySession::spawn_async`).
+pub struct PtyAsyncWriter {
+    tx: std::sync::mpsc::Sender<PtyWriteRequest>,
+    pending: Option<oneshot::Receiver<io::Result<usize>>>,
+}
+
+impl AsyncWrite for PtyAsyncWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.pending.is_none() {
+            let (reply, rx) = oneshot::channel();
+            let request = PtyWriteRequest {
+                data: buf.to_vec(),
+                reply,
+            };
+            if self.tx.send(request).is_err() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "PTY writer thread exited",
+                )));
+            }
+            self.pending = Some(rx);
+        }
+
+        let pending = self.pending.as_mut().expect("set above");
+        match Pin::new(pending).poll(cx) {
+            Poll::Ready(result) => {
+                self.pending = None;
+                Poll::Ready(result.unwrap_or_else(|_| {
+                    Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "PTY writer thread dropped the reply",
+                    ))
+                }))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Every write is flushed before its reply is sent (see
+        // `run_async_writer`), so there's nothing left to do here.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Background thread body feeding a `PtyStream`: reads until EOF or error,
+/// forwarding each chunk, then exits (dropping `tx` signals end-of-stream).
+fn run_async_reader(mut reader: Box<dyn Read + Send>, tx: tokio_mpsc::UnboundedSender<PtyResult<Bytes>>) {
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                if tx.send(Ok(Bytes::copy_from_slice(&buf[..n]))).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(Err(PtyError::ReadError(e.to_string())));
+                return;
+            }
+        }
+    }
+}
+
+/// Background thread body draining a `PtyAsyncWriter`'s write requests onto
+/// the blocking writer, one at a time, replying with the outcome of each.
+fn run_async_writer(mut writer: Box<dyn Write + Send>, rx: std::sync::mpsc::Receiver<PtyWriteRequest>) {
+    while let Ok(request) = rx.recv() {
+        let result = writer
+            .write_all(&request.data)
+            .and_then(|_| writer.flush())
+            .map(|_| request.data.len());
+        let _ = request.reply.send(result);
+    }
+}
+
 /// A PTY session that manages the pseudo-terminal lifecycle
 pub struct PtySession {
     /// The PTY pair (primary + replica)
@@ -189,6 +386,16 @@ pub struct PtySession {
     child: Box<dyn portable_pty::Child + Send + Sync>,
     /// Current terminal size
     size: PtySize,
+    /// Shell command this session was spawned with
+    shell: String,
+    /// Working directory this session was spawned with, if one was set
+    working_dir: Option<String>,
+    /// Parent-side handles for any `StdioTarget::Piped` stream, taken via
+    /// `take_piped_stdin`/`take_piped_stdout`/`take_piped_stderr`.
+    piped_stdio: PipedStdio,
+    /// Tees `read`/`write`/`resize` into a transcript, if `record` has been
+    /// called.
+    recorder: Option<PtyRecorder>,
 }
 
 impl PtySession {
@@ -215,8 +422,20 @@ impl PtySession {
         Err(PtyError::ShellNotFound)
     }
 
-    /// Spawn a new shell process in a PTY with the given configuration
-    pub fn spawn(config: PtyConfig) -> PtyResult<Self> {
+    /// Shared setup behind `spawn`/`spawn_async`: resolve the shell, open
+    /// the PTY pair, and launch the child. Returns everything needed to
+    /// build either the blocking reader/writer or their async counterparts,
+    /// since that's the only part the two entry points don't share.
+    fn spawn_raw(
+        config: PtyConfig,
+    ) -> PtyResult<(
+        PtyPair,
+        Box<dyn portable_pty::Child + Send + Sync>,
+        PtySize,
+        String,
+        Option<String>,
+        PipedStdio,
+    )> {
         // Get the native PTY system
         let pty_system = native_pty_system();
 
@@ -239,8 +458,33 @@ impl PtySession {
             .openpty(size)
             .map_err(|e| PtyError::CreationFailed(e.to_string()))?;
 
-        // Build the shell command
-        let mut cmd = CommandBuilder::new(&shell);
+        let all_pty = config.stdin == StdioTarget::Pty
+            && config.stdout == StdioTarget::Pty
+            && config.stderr == StdioTarget::Pty;
+
+        // Build the shell command. When every stream stays on the PTY
+        // (the common case, and the only one before `StdioTarget` existed)
+        // this is exactly the command `spawn_command` has always run.
+        // Otherwise (Unix only - see `PipedStdio`) it's wrapped in a `sh -c`
+        // that redirects the overridden streams before `exec`-ing the real
+        // one; either way it still goes through `slave.spawn_command`, so
+        // the child is still made a session leader with the slave set as
+        // its controlling terminal, exactly like before.
+        #[cfg(unix)]
+        let (mut cmd, piped_stdio) = if all_pty {
+            (CommandBuilder::new(&shell), PipedStdio::default())
+        } else {
+            Self::build_redirected_command(&shell, config.stdin, config.stdout, config.stderr)?
+        };
+        #[cfg(not(unix))]
+        let mut cmd = {
+            if !all_pty {
+                return Err(PtyError::CreationFailed(
+                    "per-stream stdio redirection is only supported on Unix".to_string(),
+                ));
+            }
+            CommandBuilder::new(&shell)
+        };
 
         // Set working directory if specified
         if let Some(ref dir) = config.working_dir {
@@ -261,6 +505,110 @@ impl PtySession {
             .spawn_command(cmd)
             .map_err(|e| PtyError::SpawnFailed(e.to_string()))?;
 
+        let working_dir = config.working_dir;
+        Ok((pair, child, size, shell, working_dir, piped_stdio))
+    }
+
+    /// Build the `sh -c` wrapper used when any of `stdin`/`stdout`/`stderr`
+    /// isn't `StdioTarget::Pty`: a small script that redirects the
+    /// overridden file descriptors (to `/dev/null`, or a freshly created
+    /// FIFO) before `exec`-ing the real shell, replacing the wrapper
+    /// process entirely so the child's pid/signals behave the same as a
+    /// direct spawn.
+    #[cfg(unix)]
+    fn build_redirected_command(
+        shell: &str,
+        stdin: StdioTarget,
+        stdout: StdioTarget,
+        stderr: StdioTarget,
+    ) -> PtyResult<(CommandBuilder, PipedStdio)> {
+        let mut piped = PipedStdio::default();
+        let mut script = String::new();
+
+        if let Some(clause) = Self::redirect_clause(0, "<", stdin, &mut piped.stdin, &mut piped.fifo_paths)? {
+            script.push_str(&clause);
+        }
+        if let Some(clause) = Self::redirect_clause(1, ">", stdout, &mut piped.stdout, &mut piped.fifo_paths)? {
+            script.push_str(&clause);
+        }
+        if let Some(clause) = Self::redirect_clause(2, ">", stderr, &mut piped.stderr, &mut piped.fifo_paths)? {
+            script.push_str(&clause);
+        }
+        script.push_str("exec \"$@\"\n");
+
+        let mut cmd = CommandBuilder::new("/bin/sh");
+        cmd.arg("-c");
+        cmd.arg(script);
+        cmd.arg("--");
+        cmd.arg(shell);
+
+        Ok((cmd, piped))
+    }
+
+    /// Build one `exec N<path`/`exec N>path` redirect line for file
+    /// descriptor `fd`, or `None` if `target` leaves it on the PTY. For
+    /// `StdioTarget::Piped`, also creates the backing FIFO and opens the
+    /// parent's end into `out_handle`.
+    #[cfg(unix)]
+    fn redirect_clause(
+        fd: u32,
+        operator: &str,
+        target: StdioTarget,
+        out_handle: &mut Option<File>,
+        fifo_paths: &mut Vec<std::path::PathBuf>,
+    ) -> PtyResult<Option<String>> {
+        match target {
+            StdioTarget::Pty => Ok(None),
+            StdioTarget::Null => Ok(Some(format!("exec {fd}{operator}/dev/null\n"))),
+            StdioTarget::Piped => {
+                static FIFO_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                let unique = FIFO_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                let path = std::env::temp_dir().join(format!(
+                    "warp-pty-fifo-{}-{}-{}",
+                    std::process::id(),
+                    fd,
+                    unique
+                ));
+                let c_path = CString::new(path.as_os_str().as_bytes())
+                    .map_err(|e| PtyError::CreationFailed(e.to_string()))?;
+
+                if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+                    return Err(PtyError::CreationFailed(format!(
+                        "mkfifo failed for {}: {}",
+                        path.display(),
+                        io::Error::last_os_error()
+                    )));
+                }
+
+                // Open read-write from the parent side, even though only
+                // one direction is ever used: opening a FIFO with only one
+                // of O_RDONLY/O_WRONLY blocks until a peer opens the other
+                // end, but the child hasn't been spawned yet at this point.
+                // O_RDWR is the standard way to sidestep that - it succeeds
+                // immediately, and the child's later one-directional open
+                // (`<`/`>` in the wrapper script) then also succeeds right
+                // away since a peer is already present.
+                let raw_fd = unsafe { libc::open(c_path.as_ptr(), libc::O_RDWR) };
+                if raw_fd < 0 {
+                    let _ = std::fs::remove_file(&path);
+                    return Err(PtyError::CreationFailed(format!(
+                        "failed to open FIFO {}: {}",
+                        path.display(),
+                        io::Error::last_os_error()
+                    )));
+                }
+
+                *out_handle = Some(unsafe { File::from_raw_fd(raw_fd) });
+                fifo_paths.push(path.clone());
+                Ok(Some(format!("exec {fd}{operator}{}\n", path.display())))
+            }
+        }
+    }
+
+    /// Spawn a new shell process in a PTY with the given configuration
+    pub fn spawn(config: PtyConfig) -> PtyResult<Self> {
+        let (pair, child, size, shell, working_dir, piped_stdio) = Self::spawn_raw(config)?;
+
         // Create reader and writer handles
         let writer = PtyWriter::new(
             pair.master
@@ -280,9 +628,60 @@ impl PtySession {
             reader: Arc::new(Mutex::new(reader)),
             size,
             child,
+            shell,
+            working_dir,
+            piped_stdio,
+            recorder: None,
         })
     }
 
+    /// Spawn a shell the same way as `spawn`, but wire its I/O for async
+    /// callers instead of blocking `read`/`write`: output arrives through a
+    /// `PtyStream` (fed by a dedicated reader thread), and input goes
+    /// through a `PtyAsyncWriter` (backed by a dedicated writer thread). The
+    /// returned `PtyAsyncSession` otherwise works the same as `PtySession` -
+    /// `resize`/`is_alive`/`wait` all still apply - letting a caller
+    /// `tokio::select!` over PTY output, child-exit, and resize without
+    /// dedicating a blocking thread to each session itself.
+    pub fn spawn_async(config: PtyConfig) -> PtyResult<(PtyAsyncSession, PtyStream, PtyAsyncWriter)> {
+        // `PtyAsyncSession` has no `take_piped_stdin`/`take_piped_stdout`/
+        // `take_piped_stderr` counterparts yet, so any FIFO handles are
+        // dropped here rather than threaded through - the PTY stream/writer
+        // this function hands back are already the async equivalent of a
+        // piped stdout/stdin, so the main piped-stdio use case (capturing
+        // stderr separately) is the one gap, left for a future request.
+        let (pair, child, size, shell, working_dir, _piped_stdio) = Self::spawn_raw(config)?;
+
+        let read_handle = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| PtyError::CreationFailed(e.to_string()))?;
+        let write_handle = pair
+            .master
+            .take_writer()
+            .map_err(|e| PtyError::CreationFailed(e.to_string()))?;
+
+        let (chunk_tx, chunk_rx) = tokio_mpsc::unbounded_channel();
+        thread::spawn(move || run_async_reader(read_handle, chunk_tx));
+
+        let (write_tx, write_rx) = std::sync::mpsc::channel();
+        thread::spawn(move || run_async_writer(write_handle, write_rx));
+
+        let session = PtyAsyncSession {
+            pair,
+            child,
+            size,
+            shell,
+            working_dir,
+        };
+        let stream = PtyStream { rx: chunk_rx };
+        let writer = PtyAsyncWriter {
+            tx: write_tx,
+            pending: None,
+        };
+        Ok((session, stream, writer))
+    }
+
     /// Spawn a new shell with default configuration
     pub fn spawn_shell() -> PtyResult<Self> {
         Self::spawn(PtyConfig::default())
@@ -300,30 +699,68 @@ impl PtySession {
 
     /// Write data to the PTY (send input to shell)
     pub fn write(&mut self, data: &[u8]) -> PtyResult<usize> {
-        self.writer.write(data)
+        let result = self.writer.write(data);
+        if result.is_ok() {
+            if let Some(recorder) = self.recorder.as_mut() {
+                let _ = recorder.record_input(data);
+            }
+        }
+        result
     }
 
     /// Write a string to the PTY
     pub fn write_str(&mut self, s: &str) -> PtyResult<usize> {
-        self.writer.write_str(s)
+        self.write(s.as_bytes())
     }
 
     /// Read data from the PTY (receive output from shell)
-    pub fn read(&self, buf: &mut [u8]) -> PtyResult<usize> {
-        let mut reader = self
-            .reader
-            .lock()
-            .map_err(|_| PtyError::ReadError("Reader lock poisoned".to_string()))?;
-        reader.read(buf)
+    pub fn read(&mut self, buf: &mut [u8]) -> PtyResult<usize> {
+        let n = {
+            let mut reader = self
+                .reader
+                .lock()
+                .map_err(|_| PtyError::ReadError("Reader lock poisoned".to_string()))?;
+            reader.read(buf)?
+        };
+        if let Some(recorder) = self.recorder.as_mut() {
+            let _ = recorder.record_output(&buf[..n]);
+        }
+        Ok(n)
+    }
+
+    /// Start recording this session's `read`/`write`/`resize` calls into an
+    /// append-only NDJSON transcript, written through `writer` (see
+    /// `pty_record::PtyRecorder`). Replacing any recorder already running
+    /// for this session stops it (its log is simply not written to again -
+    /// there's no separate "stop" step needed).
+    pub fn record(&mut self, writer: impl Write + Send + 'static) {
+        self.recorder = Some(PtyRecorder::new(writer));
     }
 
     /// Resize the PTY to new dimensions
     pub fn resize(&mut self, cols: u16, rows: u16) -> PtyResult<()> {
+        self.notify_resize(cols, rows, 0, 0)
+    }
+
+    /// Resize the PTY, also reporting the cell pixel dimensions child
+    /// programs can read back from `TIOCGWINSZ` (some, like `vim`, use this
+    /// for image/Sixel rendering). Manual entry point for platforms and
+    /// embedders that deliver their own resize events instead of using
+    /// `track_window_size`'s SIGWINCH-driven polling - `resize` is just this
+    /// with the pixel dimensions left at `0` (unknown), matching the prior
+    /// hardcoded behavior.
+    pub fn notify_resize(
+        &mut self,
+        cols: u16,
+        rows: u16,
+        pixel_width: u16,
+        pixel_height: u16,
+    ) -> PtyResult<()> {
         let new_size = PtySize {
             rows,
             cols,
-            pixel_width: 0,
-            pixel_height: 0,
+            pixel_width,
+            pixel_height,
         };
 
         self.pair
@@ -332,6 +769,9 @@ impl PtySession {
             .map_err(|e| PtyError::ResizeError(e.to_string()))?;
 
         self.size = new_size;
+        if let Some(recorder) = self.recorder.as_mut() {
+            let _ = recorder.record_resize(cols, rows);
+        }
         Ok(())
     }
 
@@ -340,6 +780,23 @@ impl PtySession {
         (self.size.cols, self.size.rows)
     }
 
+    /// Get the shell command this session was spawned with
+    pub fn shell(&self) -> &str {
+        &self.shell
+    }
+
+    /// The shell family this session's `shell()` was detected as, for
+    /// callers that need shell-specific behavior (block-grouping hooks,
+    /// argument quoting) without re-parsing the path themselves.
+    pub fn shell_kind(&self) -> ShellKind {
+        ShellKind::detect(&self.shell)
+    }
+
+    /// Get the working directory this session was spawned with, if one was set
+    pub fn working_dir(&self) -> Option<&str> {
+        self.working_dir.as_deref()
+    }
+
     /// Check if the child process is still running
     pub fn is_alive(&mut self) -> PtyResult<bool> {
         // Try to get the exit status without blocking
@@ -357,10 +814,552 @@ impl PtySession {
             .map_err(|e| PtyError::SpawnFailed(e.to_string()))
     }
 
+    /// The child's process id, if it's still known (`portable_pty` stops
+    /// reporting one once the process has been waited on). Since
+    /// `spawn_raw` always spawns the shell as its own session leader, this
+    /// also doubles as its process group id as long as the shell hasn't
+    /// since handed foreground job control to a subprocess - there's no
+    /// portable way to ask the PTY for the *current* foreground group
+    /// without a raw ioctl `portable_pty` doesn't expose, so `send_signal`
+    /// and `shutdown` target this pid rather than the group.
+    pub fn process_id(&self) -> Option<u32> {
+        self.child.process_id()
+    }
+
+    /// Deliver `signal` to the child process. On Unix this is a plain
+    /// `kill(2)`; on other platforms every signal maps to the same
+    /// `Child::kill` (`TerminateProcess`) call, since Windows has nothing
+    /// finer-grained.
+    pub fn send_signal(&mut self, signal: Signal) -> PtyResult<()> {
+        #[cfg(unix)]
+        {
+            let pid = self
+                .process_id()
+                .ok_or_else(|| PtyError::WriteError("child has already exited".to_string()))?;
+            let sig = match signal {
+                Signal::Interrupt => libc::SIGINT,
+                Signal::Terminate => libc::SIGTERM,
+                Signal::Hangup => libc::SIGHUP,
+                Signal::Kill => libc::SIGKILL,
+            };
+            if unsafe { libc::kill(pid as libc::pid_t, sig) } != 0 {
+                return Err(PtyError::WriteError(io::Error::last_os_error().to_string()));
+            }
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = signal;
+            self.child
+                .kill()
+                .map_err(|e| PtyError::WriteError(e.to_string()))
+        }
+    }
+
+    /// Send the TTY's INTR character (Ctrl+C) through the PTY's input side,
+    /// the same way an interactive user cancelling a command would. Prefer
+    /// this over `send_signal(Signal::Interrupt)` when the foreground
+    /// program isn't the shell itself (e.g. a subprocess the shell started)
+    /// - the line discipline delivers SIGINT to the whole foreground job,
+    /// not just the process this session's pid tracks.
+    pub fn interrupt(&mut self) -> PtyResult<()> {
+        self.write(&[0x03]).map(|_| ())
+    }
+
+    /// Ask the child to exit: send SIGTERM, give it up to `timeout` to do so
+    /// on its own (checked via `is_alive`), then escalate to SIGKILL and
+    /// wait for the exit status. Returns the final status either way.
+    pub fn shutdown(&mut self, timeout: std::time::Duration) -> PtyResult<portable_pty::ExitStatus> {
+        self.send_signal(Signal::Terminate)?;
+
+        let deadline = std::time::Instant::now() + timeout;
+        while std::time::Instant::now() < deadline {
+            if !self.is_alive()? {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        if self.is_alive()? {
+            self.send_signal(Signal::Kill)?;
+        }
+
+        self.wait()
+    }
+
     /// Get a clone of the reader for use in another thread
     pub fn reader_clone(&self) -> Arc<Mutex<PtyReader>> {
         Arc::clone(&self.reader)
     }
+
+    /// Start tracking the process's controlling terminal size: installs a
+    /// process-wide SIGWINCH handler (idempotent - see
+    /// `sigwinch::ensure_handler_installed`) and spawns a thread that, on
+    /// each signal, queries the new size via `TIOCGWINSZ` and forwards it
+    /// through `notify_resize`, so child programs like `vim`/`htop` redraw
+    /// correctly when the window changes. Returns a guard that stops the
+    /// thread on drop (the SIGWINCH handler itself stays installed, since
+    /// other trackers may still depend on it).
+    ///
+    /// `session` is shared (`Arc<Mutex<Option<PtySession>>>`) because the
+    /// tracking thread outlives this call - the same ownership shape
+    /// `main.rs`'s `spawn_pty_watcher` already uses for cross-thread PTY
+    /// access.
+    #[cfg(unix)]
+    pub fn track_window_size(session: Arc<Mutex<Option<PtySession>>>) -> WindowSizeGuard {
+        sigwinch::ensure_handler_installed();
+
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let stop_thread = Arc::clone(&stop);
+
+        let handle = thread::spawn(move || {
+            let mut seen_generation = sigwinch::generation();
+            while !stop_thread.load(std::sync::atomic::Ordering::Relaxed) {
+                let current_generation = sigwinch::generation();
+                if current_generation != seen_generation {
+                    seen_generation = current_generation;
+                    if let Some((cols, rows, pixel_width, pixel_height)) = sigwinch::query_window_size() {
+                        if let Ok(mut guard) = session.lock() {
+                            if let Some(session) = guard.as_mut() {
+                                let _ = session.notify_resize(cols, rows, pixel_width, pixel_height);
+                            }
+                        }
+                    }
+                }
+                thread::sleep(std::time::Duration::from_millis(50));
+            }
+        });
+
+        WindowSizeGuard {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Take the parent-side handle for a `stdin: StdioTarget::Piped`
+    /// stream, if the session was spawned with one. Returns `None` on a
+    /// second call, or if `stdin` wasn't `Piped`.
+    #[cfg(unix)]
+    pub fn take_piped_stdin(&mut self) -> Option<File> {
+        self.piped_stdio.stdin.take()
+    }
+
+    /// Take the parent-side handle for a `stdout: StdioTarget::Piped`
+    /// stream, if the session was spawned with one. Returns `None` on a
+    /// second call, or if `stdout` wasn't `Piped`.
+    #[cfg(unix)]
+    pub fn take_piped_stdout(&mut self) -> Option<File> {
+        self.piped_stdio.stdout.take()
+    }
+
+    /// Take the parent-side handle for a `stderr: StdioTarget::Piped`
+    /// stream, if the session was spawned with one. Returns `None` on a
+    /// second call, or if `stderr` wasn't `Piped`.
+    #[cfg(unix)]
+    pub fn take_piped_stderr(&mut self) -> Option<File> {
+        self.piped_stdio.stderr.take()
+    }
+
+    /// Put the calling process's controlling terminal into raw mode for as
+    /// long as the returned `RawGuard` lives, so keystrokes reach this
+    /// session's shell exactly as typed instead of being line-edited and
+    /// echoed by the kernel's tty driver. An associated function rather
+    /// than taking `&self`: the terminal being reconfigured is the
+    /// process's own stdin, not any fd this `PtySession` owns (those are
+    /// the *other* end of the PTY pair) - relevant for a CLI/debug tool
+    /// wiring its real terminal directly to a session, not the windowed
+    /// app's own grid-rendered input handling.
+    #[cfg(unix)]
+    pub fn enter_raw_mode() -> PtyResult<RawGuard> {
+        RawGuard::new(libc::STDIN_FILENO).map_err(|e| PtyError::CreationFailed(e.to_string()))
+    }
+}
+
+#[cfg(unix)]
+impl Drop for PtySession {
+    /// Best-effort clean-up of any FIFOs created for `StdioTarget::Piped`
+    /// streams - the wrapper shell has already `exec`'d into the real
+    /// command by the time this runs, so there's nothing left to race with.
+    fn drop(&mut self) {
+        for path in &self.piped_stdio.fifo_paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Guard returned by `PtySession::track_window_size`. Dropping it stops the
+/// background polling thread; see `track_window_size` for what stays
+/// installed regardless.
+#[cfg(unix)]
+pub struct WindowSizeGuard {
+    stop: Arc<std::sync::atomic::AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+#[cfg(unix)]
+impl Drop for WindowSizeGuard {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// SIGWINCH handling for `PtySession::track_window_size`: a signal-safe
+/// generation counter bumped by the handler, plus the `TIOCGWINSZ` query
+/// used to translate "the window changed" into an actual size.
+#[cfg(unix)]
+mod sigwinch {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Once;
+
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+    static INSTALLED: Once = Once::new();
+
+    /// Signal handler body: only bumps an atomic counter, since that's
+    /// (unlike most other work) safe to do from within a signal handler.
+    /// The actual `TIOCGWINSZ` query happens later, on the poller thread.
+    extern "C" fn handle_sigwinch(_signum: libc::c_int) {
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Install `handle_sigwinch` for `SIGWINCH`, if no tracker has already
+    /// done so this process. Safe to call repeatedly/concurrently.
+    pub fn ensure_handler_installed() {
+        INSTALLED.call_once(|| unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handle_sigwinch as usize;
+            libc::sigemptyset(&mut action.sa_mask);
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut());
+        });
+    }
+
+    /// Current SIGWINCH generation count, for a poller thread to compare
+    /// against the last value it observed.
+    pub fn generation() -> u64 {
+        GENERATION.load(Ordering::Relaxed)
+    }
+
+    /// Query the process's controlling terminal size: `(cols, rows,
+    /// pixel_width, pixel_height)`, or `None` if `TIOCGWINSZ` failed (e.g.
+    /// stdout isn't a terminal).
+    pub fn query_window_size() -> Option<(u16, u16, u16, u16)> {
+        unsafe {
+            let mut ws: libc::winsize = std::mem::zeroed();
+            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) == 0 {
+                Some((ws.ws_col, ws.ws_row, ws.ws_xpixel, ws.ws_ypixel))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// RAII guard returned by `PtySession::enter_raw_mode`. Restores the
+/// terminal's original `termios` settings on drop, including during an
+/// unwind, so a panic while interacting with the shell can't leave the
+/// user's real terminal stuck echo-less/unbuffered.
+#[cfg(unix)]
+pub struct RawGuard {
+    fd: libc::c_int,
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl RawGuard {
+    /// Save `fd`'s current `termios` state and switch it to raw mode
+    /// (`cfmakeraw`: no line buffering, no echo, no signal-generating
+    /// control characters) so every byte typed reaches the child exactly
+    /// as typed instead of being line-edited by the kernel's tty driver.
+    fn new(fd: libc::c_int) -> io::Result<Self> {
+        unsafe {
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(fd, &mut original) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut raw = original;
+            libc::cfmakeraw(&mut raw);
+            if libc::tcsetattr(fd, libc::TCSANOW, &raw) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self { fd, original })
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Async counterpart to `PtySession`, returned by `PtySession::spawn_async`.
+/// I/O goes through the `PtyStream`/`PtyAsyncWriter` handles returned
+/// alongside it; this struct just keeps the PTY and child process alive and
+/// exposes the same lifecycle operations (`resize`, `is_alive`, `wait`, ...).
+pub struct PtyAsyncSession {
+    /// The PTY pair (primary + replica)
+    pair: PtyPair,
+    /// The spawned child process handle
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    /// Current terminal size
+    size: PtySize,
+    /// Shell command this session was spawned with
+    shell: String,
+    /// Working directory this session was spawned with, if one was set
+    working_dir: Option<String>,
+}
+
+impl PtyAsyncSession {
+    /// Resize the PTY to new dimensions
+    pub fn resize(&mut self, cols: u16, rows: u16) -> PtyResult<()> {
+        let new_size = PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+
+        self.pair
+            .master
+            .resize(new_size)
+            .map_err(|e| PtyError::ResizeError(e.to_string()))?;
+
+        self.size = new_size;
+        Ok(())
+    }
+
+    /// Get the current terminal size
+    pub fn size(&self) -> (u16, u16) {
+        (self.size.cols, self.size.rows)
+    }
+
+    /// Get the shell command this session was spawned with
+    pub fn shell(&self) -> &str {
+        &self.shell
+    }
+
+    /// The shell family this session's `shell()` was detected as, mirroring
+    /// `PtySession::shell_kind`.
+    pub fn shell_kind(&self) -> ShellKind {
+        ShellKind::detect(&self.shell)
+    }
+
+    /// Get the working directory this session was spawned with, if one was set
+    pub fn working_dir(&self) -> Option<&str> {
+        self.working_dir.as_deref()
+    }
+
+    /// Check if the child process is still running
+    pub fn is_alive(&mut self) -> PtyResult<bool> {
+        match self.child.try_wait() {
+            Ok(Some(_status)) => Ok(false),
+            Ok(None) => Ok(true),
+            Err(e) => Err(PtyError::ReadError(e.to_string())),
+        }
+    }
+
+    /// Wait for the child process to exit and return its status
+    pub fn wait(&mut self) -> PtyResult<portable_pty::ExitStatus> {
+        self.child
+            .wait()
+            .map_err(|e| PtyError::SpawnFailed(e.to_string()))
+    }
+}
+
+/// How many bytes `PtyEventLoop` pulls from the PTY in one `read` call per
+/// wake-up. Large enough that a burst of shell output (e.g. `cat` on a big
+/// file) drains in a handful of reads instead of thousands of tiny ones.
+/// How long `PtyEventLoop`'s `Shutdown` handling waits for the child to exit
+/// on its own (via `PtySession::shutdown`) before escalating to `SIGKILL`.
+const SHUTDOWN_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_millis(500);
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Cap on how much of a single read `PtyEventLoop` forwards to `output`
+/// before checking for queued `Input`/`Resize` messages again. Smaller than
+/// `READ_BUFFER_SIZE` so a single flood-sized read can't monopolize the
+/// loop for the whole 1 MiB - the loop interleaves a message-queue check
+/// between every `MAX_LOCKED_READ`-sized chunk it hands off, the same way
+/// it already does between reads.
+const MAX_LOCKED_READ: usize = 64 * 1024;
+
+/// A message sent to a running `PtyEventLoop`.
+pub enum PtyEventLoopMsg {
+    /// Bytes to write to the shell.
+    Input(Vec<u8>),
+    /// Resize the PTY to `(cols, rows)`.
+    Resize(u16, u16),
+    /// Stop the loop; its thread exits once this is processed.
+    Shutdown,
+}
+
+/// Long-running driver that owns a `PtySession` on its own thread and is
+/// controlled purely through a channel of `PtyEventLoopMsg`s - callers never
+/// touch the raw reader/writer locks themselves. Output is forwarded to a
+/// caller-supplied channel as it's read, bounded by `READ_BUFFER_SIZE`
+/// (how much to pull per wake-up) and `MAX_LOCKED_READ` (how much to
+/// forward before re-checking for pending messages), so a flood of shell
+/// output can't starve input handling or block the caller indefinitely.
+pub struct PtyEventLoop {
+    handle: thread::JoinHandle<()>,
+    reader_handle: thread::JoinHandle<()>,
+    sender: std::sync::mpsc::Sender<PtyEventLoopMsg>,
+}
+
+impl PtyEventLoop {
+    /// Spawn the driver thread for `session`, forwarding PTY output to
+    /// `output` as it's read. Returns once the thread is running; use
+    /// `input`/`resize`/`shutdown` (or `sender`/`handle` directly) to
+    /// control it from here on.
+    pub fn spawn(mut session: PtySession, output: std::sync::mpsc::Sender<Vec<u8>>) -> Self {
+        let (sender, rx) = std::sync::mpsc::channel();
+        // The PTY master's reader blocks until the shell produces output, so
+        // it's read on its own thread; `run` below only ever waits on it
+        // with a short timeout, which keeps it free to drain queued
+        // messages (including `Shutdown`) even while the shell is idle.
+        let reader = session.reader_clone();
+        let (read_tx, read_rx) = std::sync::mpsc::channel();
+        let reader_handle = thread::spawn(move || Self::run_reader(reader, read_tx));
+        let handle = thread::spawn(move || Self::run(&mut session, &rx, &read_rx, &output));
+        Self { handle, reader_handle, sender }
+    }
+
+    /// Queue bytes to write to the shell.
+    pub fn input(&self, data: Vec<u8>) -> PtyResult<()> {
+        self.sender
+            .send(PtyEventLoopMsg::Input(data))
+            .map_err(|_| PtyError::WriteError("event loop thread has exited".to_string()))
+    }
+
+    /// Queue a resize to `(cols, rows)`.
+    pub fn resize(&self, cols: u16, rows: u16) -> PtyResult<()> {
+        self.sender
+            .send(PtyEventLoopMsg::Resize(cols, rows))
+            .map_err(|_| PtyError::ResizeError("event loop thread has exited".to_string()))
+    }
+
+    /// Ask the loop to stop and block until both of its threads exit. By the
+    /// time this returns the child has been killed too (`run` terminates it
+    /// on seeing `Shutdown` - see `drain_messages`), which is what lets
+    /// `run_reader`'s blocking read unblock: the PTY master sees EOF once
+    /// the child (the only thing with the replica side open) exits.
+    pub fn shutdown(self) {
+        let _ = self.sender.send(PtyEventLoopMsg::Shutdown);
+        let _ = self.handle.join();
+        let _ = self.reader_handle.join();
+    }
+
+    /// The message sender, for callers that want to hold onto it separately
+    /// from the `JoinHandle` (e.g. to clone it across threads - `Sender` is
+    /// `Clone`, unlike `PtyEventLoop` itself).
+    pub fn sender(&self) -> std::sync::mpsc::Sender<PtyEventLoopMsg> {
+        self.sender.clone()
+    }
+
+    /// Background thread body doing the actual (blocking) PTY reads, one
+    /// `READ_BUFFER_SIZE` chunk at a time, and forwarding each as it comes
+    /// in. Mirrors `run_async_reader`'s split between the blocking read and
+    /// whatever is consuming its output - here that's `run`'s `recv_timeout`
+    /// loop rather than a tokio channel.
+    fn run_reader(reader: Arc<Mutex<PtyReader>>, tx: std::sync::mpsc::Sender<PtyResult<Vec<u8>>>) {
+        loop {
+            let mut buf = vec![0u8; READ_BUFFER_SIZE];
+            let result = match reader.lock() {
+                Ok(mut guard) => guard.read(&mut buf),
+                Err(_) => return, // session dropped/poisoned the lock
+            };
+            match result {
+                Ok(0) => {
+                    let _ = tx.send(Ok(Vec::new())); // EOF
+                    return;
+                }
+                Ok(n) => {
+                    buf.truncate(n);
+                    if tx.send(Ok(buf)).is_err() {
+                        return; // run() has exited
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    return;
+                }
+            }
+        }
+    }
+
+    fn run(
+        session: &mut PtySession,
+        rx: &std::sync::mpsc::Receiver<PtyEventLoopMsg>,
+        read_rx: &std::sync::mpsc::Receiver<PtyResult<Vec<u8>>>,
+        output: &std::sync::mpsc::Sender<Vec<u8>>,
+    ) {
+        loop {
+            if !Self::drain_messages(session, rx) {
+                return;
+            }
+
+            // Wait for the next chunk from `run_reader`, but only briefly -
+            // that read is blocking on the shell, so without the timeout
+            // queued `Input`/`Resize`/`Shutdown` messages would sit unread
+            // (and `shutdown()` would hang) for as long as the shell stays
+            // idle.
+            match read_rx.recv_timeout(std::time::Duration::from_millis(20)) {
+                Ok(Ok(data)) => {
+                    if data.is_empty() {
+                        return; // EOF: child exited, nothing left to read
+                    }
+                    for chunk in data.chunks(MAX_LOCKED_READ) {
+                        if output.send(chunk.to_vec()).is_err() {
+                            return; // caller dropped the output receiver
+                        }
+                        if !Self::drain_messages(session, rx) {
+                            return;
+                        }
+                    }
+                }
+                Ok(Err(_)) => return, // real read error from the PTY master
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+
+    /// Apply every message queued so far without blocking. Returns `false`
+    /// once `Shutdown` is seen or the sender side has been dropped, telling
+    /// `run` to stop.
+    fn drain_messages(session: &mut PtySession, rx: &std::sync::mpsc::Receiver<PtyEventLoopMsg>) -> bool {
+        loop {
+            match rx.try_recv() {
+                Ok(PtyEventLoopMsg::Input(data)) => {
+                    if session.write(&data).is_err() {
+                        return false;
+                    }
+                }
+                Ok(PtyEventLoopMsg::Resize(cols, rows)) => {
+                    let _ = session.resize(cols, rows);
+                }
+                Ok(PtyEventLoopMsg::Shutdown) => {
+                    // Without this the child outlives the loop: nothing
+                    // else here ever signals it, so it'd keep running
+                    // orphaned and `run_reader`'s blocking read would never
+                    // see EOF. Best-effort - a session whose child already
+                    // exited errors out of `send_signal` harmlessly.
+                    let _ = session.shutdown(SHUTDOWN_GRACE_PERIOD);
+                    return false;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => return true,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => return false,
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -402,4 +1401,163 @@ mod tests {
         assert_eq!(cols, 120);
         assert_eq!(rows, 40);
     }
+
+    #[test]
+    fn test_spawn_async_write_and_read() {
+        use futures::StreamExt;
+
+        let runtime = tokio::runtime::Runtime::new().expect("should build a test runtime");
+        runtime.block_on(async {
+            let (mut session, mut stream, mut writer) = PtySession::spawn_async(PtyConfig::default())
+                .expect("should spawn an async shell");
+
+            tokio::io::AsyncWriteExt::write_all(&mut writer, b"echo hello\n")
+                .await
+                .expect("should write to the PTY through AsyncWrite");
+
+            let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+                .await
+                .expect("should receive a chunk before the shell times out");
+            assert!(chunk.is_some(), "stream should yield PTY output instead of ending immediately");
+
+            let _ = session.is_alive();
+        });
+    }
+
+    #[test]
+    fn test_event_loop_write_and_shutdown() {
+        let session = PtySession::spawn_shell().expect("Should spawn shell");
+        let pid = session.process_id().expect("Freshly spawned child should have a pid");
+        let (output_tx, output_rx) = std::sync::mpsc::channel();
+        let event_loop = PtyEventLoop::spawn(session, output_tx);
+
+        event_loop
+            .input(b"echo hello\n".to_vec())
+            .expect("Should queue input");
+
+        let chunk = output_rx.recv_timeout(std::time::Duration::from_secs(5));
+        assert!(chunk.is_ok(), "Should receive PTY output forwarded by the event loop");
+
+        event_loop
+            .resize(100, 30)
+            .expect("Should queue a resize while the loop is running");
+
+        event_loop.shutdown();
+
+        // `shutdown()` joins both the `run` thread and `run_reader`, so by
+        // the time it returns the child must already be dead - nothing else
+        // would ever unblock `run_reader`'s read.
+        #[cfg(unix)]
+        assert_ne!(
+            unsafe { libc::kill(pid as libc::pid_t, 0) },
+            0,
+            "child should have been terminated by shutdown(), not left running orphaned"
+        );
+    }
+
+    #[test]
+    fn test_notify_resize_reports_pixel_dimensions() {
+        let mut session = PtySession::spawn_shell().expect("Should spawn shell");
+
+        let result = session.notify_resize(100, 30, 800, 600);
+        assert!(result.is_ok(), "Should be able to resize with pixel dimensions");
+
+        let (cols, rows) = session.size();
+        assert_eq!(cols, 100);
+        assert_eq!(rows, 30);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_track_window_size_guard_stops_cleanly() {
+        let session = Arc::new(Mutex::new(Some(
+            PtySession::spawn_shell().expect("Should spawn shell"),
+        )));
+
+        let guard = PtySession::track_window_size(Arc::clone(&session));
+        // Dropping the guard should join its polling thread without
+        // hanging or panicking.
+        drop(guard);
+
+        assert!(session.lock().unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_piped_stderr_is_captured_separately_from_pty_stdout() {
+        let config = PtyConfig {
+            shell: Some("/bin/sh".to_string()),
+            stderr: StdioTarget::Piped,
+            ..PtyConfig::default()
+        };
+        let mut session = PtySession::spawn(config).expect("Should spawn with piped stderr");
+
+        let mut stderr_handle = session
+            .take_piped_stderr()
+            .expect("stderr should be piped when requested");
+
+        session
+            .write_str("echo oops 1>&2\n")
+            .expect("Should be able to write to PTY stdin");
+
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let mut captured = [0u8; 256];
+        let n = stderr_handle
+            .read(&mut captured)
+            .expect("Should read the redirected stderr");
+        assert!(n > 0, "piped stderr should carry the echoed output");
+        assert!(String::from_utf8_lossy(&captured[..n]).contains("oops"));
+
+        // Taking it again should come back empty - it was already moved out.
+        assert!(session.take_piped_stderr().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send_signal_terminates_child() {
+        let mut session = PtySession::spawn_shell().expect("Should spawn shell");
+        assert!(session.process_id().is_some(), "a freshly spawned child should have a pid");
+
+        session
+            .send_signal(Signal::Terminate)
+            .expect("Should be able to signal the child");
+
+        let status = session.wait().expect("Should be able to wait after signalling");
+        assert!(!status.success(), "a terminated shell shouldn't report success");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shutdown_escalates_to_kill_when_unresponsive() {
+        // `trap '' TERM` makes the shell ignore SIGTERM, so `shutdown`
+        // should time out waiting and fall back to SIGKILL.
+        let config = PtyConfig {
+            shell: Some("/bin/sh".to_string()),
+            ..PtyConfig::default()
+        };
+        let mut session = PtySession::spawn(config).expect("Should spawn shell");
+        session
+            .write_str("trap '' TERM\n")
+            .expect("Should be able to write to PTY");
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        let status = session
+            .shutdown(std::time::Duration::from_millis(300))
+            .expect("shutdown should still report a final status after escalating");
+        assert!(!status.success(), "a killed shell shouldn't report success");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_enter_raw_mode_restores_termios_on_drop() {
+        // Test runs are typically not attached to a real tty, so
+        // `enter_raw_mode` failing with ENOTTY is expected here - the part
+        // worth asserting is that it never panics, and that a successful
+        // guard restores the original settings on drop without hanging.
+        match PtySession::enter_raw_mode() {
+            Ok(guard) => drop(guard),
+            Err(_) => {}
+        }
+    }
 }
@@ -0,0 +1,265 @@
+//! Record/replay PTY I/O for deterministic testing and "asciinema-style"
+//! capture, without requiring a live shell once a transcript has been
+//! recorded.
+//!
+//! `PtyRecorder` tees bytes passed to its `record_output`/`record_input`/
+//! `record_resize` methods into a newline-delimited JSON log of timestamped
+//! `RecordedEvent`s; `PtyReplay` reads that log back and either feeds the
+//! recorded output straight into a `TerminalParser` (ignoring timing, for
+//! tests that just want the end state a live shell would have produced) or
+//! re-emits it as a `Stream` at original or accelerated speed.
+
+use std::future::Future;
+use std::io::{self, BufRead, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+
+use super::parser::TerminalParser;
+
+/// One recorded event, timestamped relative to the start of the recording.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RecordedEvent {
+    /// Bytes read from the PTY (what the shell/program printed).
+    Output { offset_ms: u64, data: Vec<u8> },
+    /// Bytes written to the PTY (what the user/caller sent as input).
+    Input { offset_ms: u64, data: Vec<u8> },
+    /// A resize that happened mid-recording.
+    Resize { offset_ms: u64, cols: u16, rows: u16 },
+}
+
+/// Tees PTY output/input/resize events into an append-only NDJSON log.
+/// Built around `PtySession::record`, which wraps a session's `read`/
+/// `write`/`resize` so every call here also goes through the session as
+/// normal; this struct only owns the timestamping and the log itself.
+pub struct PtyRecorder {
+    start: Instant,
+    writer: Box<dyn Write + Send>,
+}
+
+impl PtyRecorder {
+    /// Start a new recording, timestamped from this call. `writer` is
+    /// typically a `File` opened in the caller's session directory.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            start: Instant::now(),
+            writer: Box::new(writer),
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    /// Record a chunk of PTY output.
+    pub fn record_output(&mut self, data: &[u8]) -> io::Result<()> {
+        self.append(RecordedEvent::Output {
+            offset_ms: self.elapsed_ms(),
+            data: data.to_vec(),
+        })
+    }
+
+    /// Record a chunk of input sent to the PTY.
+    pub fn record_input(&mut self, data: &[u8]) -> io::Result<()> {
+        self.append(RecordedEvent::Input {
+            offset_ms: self.elapsed_ms(),
+            data: data.to_vec(),
+        })
+    }
+
+    /// Record a resize.
+    pub fn record_resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.append(RecordedEvent::Resize {
+            offset_ms: self.elapsed_ms(),
+            cols,
+            rows,
+        })
+    }
+
+    /// Append one event as a line of JSON, flushing immediately so the log
+    /// is durable (and readable by a concurrent tail) even if the recorded
+    /// session is later killed rather than cleanly shut down.
+    fn append(&mut self, event: RecordedEvent) -> io::Result<()> {
+        let line =
+            serde_json::to_string(&event).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(self.writer, "{line}")?;
+        self.writer.flush()
+    }
+}
+
+/// A previously recorded PTY transcript, loaded back for replay.
+pub struct PtyReplay {
+    events: Vec<RecordedEvent>,
+}
+
+impl PtyReplay {
+    /// Load a transcript written by `PtyRecorder`, one `RecordedEvent` per
+    /// line. Blank lines are skipped so a log with a trailing newline
+    /// round-trips cleanly.
+    pub fn load(reader: impl BufRead) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: RecordedEvent = serde_json::from_str(&line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            events.push(event);
+        }
+        Ok(Self { events })
+    }
+
+    /// The recorded events, in the order they happened.
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Feed every recorded `Output` event's bytes into `parser`, in order,
+    /// ignoring timing - for tests comparing recorded vs. live terminal
+    /// state without caring how long the original session took.
+    pub fn replay_into(&self, parser: &mut TerminalParser) {
+        for event in &self.events {
+            if let RecordedEvent::Output { data, .. } = event {
+                parser.parse_bytes(data);
+            }
+        }
+    }
+
+    /// Re-emit the recorded `Output` bytes as a `Stream`, spaced out
+    /// according to their original offsets divided by `speed` (`2.0` plays
+    /// back twice as fast, `0.0` yields every chunk with no delay at all -
+    /// useful for tests that want the real chunk boundaries but not the
+    /// real wall-clock time).
+    pub fn stream(self, speed: f64) -> PtyReplayStream {
+        let mut chunks = std::collections::VecDeque::new();
+        let mut prev_ms = 0u64;
+        for event in self.events {
+            if let RecordedEvent::Output { offset_ms, data } = event {
+                let wait = if speed > 0.0 {
+                    Duration::from_millis((offset_ms.saturating_sub(prev_ms) as f64 / speed) as u64)
+                } else {
+                    Duration::ZERO
+                };
+                prev_ms = offset_ms;
+                chunks.push_back((wait, data));
+            }
+        }
+        PtyReplayStream { chunks, delay: None }
+    }
+}
+
+/// `Stream` of recorded output chunks, returned by `PtyReplay::stream`.
+/// Each chunk is held back by its precomputed gap from the one before it,
+/// so the stream reproduces the original pacing (divided by `speed`)
+/// without re-deriving it from timestamps on every poll.
+pub struct PtyReplayStream {
+    chunks: std::collections::VecDeque<(Duration, Vec<u8>)>,
+    delay: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl Stream for PtyReplayStream {
+    type Item = Vec<u8>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.delay.is_none() {
+            let Some((wait, _)) = self.chunks.front() else {
+                return Poll::Ready(None);
+            };
+            if !wait.is_zero() {
+                self.delay = Some(Box::pin(tokio::time::sleep(*wait)));
+            }
+        }
+
+        if let Some(delay) = self.delay.as_mut() {
+            match delay.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(()) => self.delay = None,
+            }
+        }
+
+        Poll::Ready(self.chunks.pop_front().map(|(_, data)| data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    /// In-memory `Write` sink that's still readable after being handed to
+    /// `PtyRecorder` (which takes ownership of its writer) - tests read the
+    /// log back through the `Arc<Mutex<_>>` once recording is done.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_recorder_writes_ndjson_events() {
+        let log = SharedBuf::default();
+        {
+            let mut recorder = PtyRecorder::new(log.clone());
+            recorder.record_output(b"hello").unwrap();
+            recorder.record_input(b"echo hi\n").unwrap();
+            recorder.record_resize(100, 30).unwrap();
+        }
+
+        let bytes = log.0.lock().unwrap().clone();
+        let replay = PtyReplay::load(bytes.as_slice()).expect("should parse the recorded log");
+        assert_eq!(replay.events().len(), 3);
+        assert!(matches!(&replay.events()[0], RecordedEvent::Output { data, .. } if data == b"hello"));
+        assert!(matches!(&replay.events()[1], RecordedEvent::Input { data, .. } if data == b"echo hi\n"));
+        assert!(matches!(&replay.events()[2], RecordedEvent::Resize { cols: 100, rows: 30, .. }));
+    }
+
+    #[test]
+    fn test_replay_into_parser_renders_recorded_output() {
+        let log = SharedBuf::default();
+        {
+            let mut recorder = PtyRecorder::new(log.clone());
+            recorder.record_output(b"hi").unwrap();
+        }
+
+        let bytes = log.0.lock().unwrap().clone();
+        let replay = PtyReplay::load(bytes.as_slice()).unwrap();
+        let mut parser = TerminalParser::new();
+        replay.replay_into(&mut parser);
+
+        assert_eq!(parser.state.cursor.col, 2, "the parser should have advanced past the replayed bytes");
+    }
+
+    #[test]
+    fn test_stream_yields_chunks_in_recorded_order() {
+        let log = SharedBuf::default();
+        {
+            let mut recorder = PtyRecorder::new(log.clone());
+            recorder.record_output(b"one").unwrap();
+            recorder.record_output(b"two").unwrap();
+        }
+
+        let bytes = log.0.lock().unwrap().clone();
+        let replay = PtyReplay::load(bytes.as_slice()).unwrap();
+        let runtime = tokio::runtime::Runtime::new().expect("should build a test runtime");
+        runtime.block_on(async {
+            // speed=0.0 replays with no delay, so the stream completes
+            // immediately instead of waiting out the real recorded gaps.
+            let mut stream = replay.stream(0.0);
+            assert_eq!(stream.next().await, Some(b"one".to_vec()));
+            assert_eq!(stream.next().await, Some(b"two".to_vec()));
+            assert_eq!(stream.next().await, None);
+        });
+    }
+}
@@ -0,0 +1,135 @@
+//! Shell detection and per-shell behavior (zsh, bash, fish, PowerShell).
+//!
+//! `PtySession` spawns whatever `$SHELL` (or an explicit `PtyConfig::shell`)
+//! points at without caring what it is; this module is what lets callers
+//! that *do* care - block grouping, command quoting for the AI palette's
+//! `run_command` tool - ask "which shell is this, and how does it expect
+//! things". `ShellKind::detect` maps a shell path to a kind by basename;
+//! `command_marker_hook` returns the init snippet a shell needs to emit
+//! OSC-133-style command-start/command-end markers so the UI can group PTY
+//! output into blocks; `quote_arg` applies that shell's quoting rules.
+
+/// A shell family recognized well enough to generate its hook script and
+/// quoting rules. `Unknown` covers anything else - callers fall back to
+/// POSIX-ish behavior (`Bash`'s rules) for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+    Unknown,
+}
+
+impl ShellKind {
+    /// Detect a shell kind from its path or command name, by basename
+    /// (case-insensitively, and ignoring a `.exe` suffix so PowerShell is
+    /// recognized on Windows).
+    pub fn detect(shell_path: &str) -> ShellKind {
+        let basename = shell_path.rsplit(['/', '\\']).next().unwrap_or(shell_path).to_lowercase();
+        let basename = basename.strip_suffix(".exe").unwrap_or(&basename);
+
+        match basename {
+            "bash" | "sh" => ShellKind::Bash,
+            "zsh" => ShellKind::Zsh,
+            "fish" => ShellKind::Fish,
+            "pwsh" | "powershell" => ShellKind::PowerShell,
+            _ => ShellKind::Unknown,
+        }
+    }
+
+    /// The marker strings this shell's hook emits around each command, so
+    /// the host can scan PTY output for them without re-deriving them from
+    /// the hook script text.
+    pub fn command_markers(self) -> (&'static str, &'static str) {
+        ("\u{1b}]133;B\u{7}", "\u{1b}]133;D\u{7}")
+    }
+
+    /// Shell-specific init code that hooks the prompt and command
+    /// lifecycle to emit OSC 133 `B` (command start) and `D` (command end)
+    /// markers, following the FinalTerm/iTerm2 "shell integration" scheme.
+    /// Fed to the shell on startup (e.g. via `--rcfile`/`-c` wrapping, or
+    /// `config.env`'s `PROMPT_COMMAND`/`precmd` hooks); `Unknown` shells
+    /// get no hook since there's no safe generic way to inject one.
+    /// `PowerShell`'s hook only overrides `prompt`, so it emits `D` but no `B`.
+    pub fn command_marker_hook(self) -> Option<&'static str> {
+        match self {
+            ShellKind::Bash => Some(
+                "PROMPT_COMMAND='printf \"\\033]133;D\\007\"'\n\
+                 trap 'printf \"\\033]133;B\\007\"' DEBUG",
+            ),
+            ShellKind::Zsh => Some(
+                "precmd() { printf '\\033]133;D\\007' }\n\
+                 preexec() { printf '\\033]133;B\\007' }",
+            ),
+            ShellKind::Fish => Some(
+                "function __warp_preexec --on-event fish_preexec\n\
+                 printf '\\033]133;B\\007'\n\
+                 end\n\
+                 function __warp_precmd --on-event fish_prompt\n\
+                 printf '\\033]133;D\\007'\n\
+                 end",
+            ),
+            ShellKind::PowerShell => Some(
+                "function prompt {\n\
+                 [Console]::Write(\"`e]133;D`a\")\n\
+                 \"PS $($executionContext.SessionState.Path.CurrentLocation)> \"\n\
+                 }",
+            ),
+            ShellKind::Unknown => None,
+        }
+    }
+
+    /// Quote a single argument so this shell parses it back as one token.
+    /// `Bash`/`Zsh`/`Unknown` use POSIX single-quoting (`'` escaped as
+    /// `'\''`); `Fish` is the same save for not needing a `~`-escape
+    /// special case that POSIX shells don't have either, so it shares the
+    /// implementation; `PowerShell` doubles embedded single quotes inside
+    /// a single-quoted string instead.
+    pub fn quote_arg(self, arg: &str) -> String {
+        match self {
+            ShellKind::PowerShell => format!("'{}'", arg.replace('\'', "''")),
+            ShellKind::Bash | ShellKind::Zsh | ShellKind::Fish | ShellKind::Unknown => {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_recognizes_common_shells_by_basename() {
+        assert_eq!(ShellKind::detect("/bin/bash"), ShellKind::Bash);
+        assert_eq!(ShellKind::detect("/usr/bin/zsh"), ShellKind::Zsh);
+        assert_eq!(ShellKind::detect("/usr/local/bin/fish"), ShellKind::Fish);
+        assert_eq!(ShellKind::detect(r"C:\Program Files\PowerShell\7\pwsh.exe"), ShellKind::PowerShell);
+        assert_eq!(ShellKind::detect("/bin/tcsh"), ShellKind::Unknown);
+    }
+
+    #[test]
+    fn test_detect_is_case_insensitive() {
+        assert_eq!(ShellKind::detect("ZSH"), ShellKind::Zsh);
+        assert_eq!(ShellKind::detect("PowerShell.exe"), ShellKind::PowerShell);
+    }
+
+    #[test]
+    fn test_unknown_shell_has_no_marker_hook() {
+        assert!(ShellKind::Unknown.command_marker_hook().is_none());
+        assert!(ShellKind::Bash.command_marker_hook().is_some());
+    }
+
+    #[test]
+    fn test_quote_arg_escapes_embedded_single_quotes() {
+        assert_eq!(ShellKind::Bash.quote_arg("it's"), "'it'\\''s'");
+        assert_eq!(ShellKind::PowerShell.quote_arg("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_quote_arg_round_trips_simple_args() {
+        assert_eq!(ShellKind::Zsh.quote_arg("hello"), "'hello'");
+        assert_eq!(ShellKind::Fish.quote_arg("hello world"), "'hello world'");
+    }
+}
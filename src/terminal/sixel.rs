@@ -0,0 +1,268 @@
+//! Sixel graphics decoder (DCS `ESC P … q … ST`).
+//!
+//! A Sixel payload packs pixels six rows at a time: each data byte in
+//! `0x3F..=0x7E` is a "sixel" - a column of up to six pixels, one bit per
+//! row, drawn in whatever color register is currently selected. `#`
+//! introduces or redefines a color register, `!` repeats the next sixel
+//! column, `$` returns to the start of the current six-row band, and `-`
+//! advances to the next band. This module turns that stream into a plain
+//! RGBA raster; `terminal::grid::SixelImageStore` anchors the result to the
+//! grid cell it was drawn at, and the renderer uploads it as a texture.
+
+/// A decoded Sixel raster: a tightly packed, row-major RGBA8 image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SixelImage {
+    pub width: usize,
+    pub height: usize,
+    /// `width * height * 4` bytes, row-major RGBA8.
+    pub rgba: Vec<u8>,
+}
+
+/// Default 16-color register palette a Sixel stream starts with before any
+/// `#Pc;2;Pr;Pg;Pb` redefinition - lifted from xterm's sixel defaults.
+/// Components are 0-100 percentages, the same scale the inline `#` color
+/// introducer uses for color-space 2 (RGB).
+const DEFAULT_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (20, 20, 80),
+    (80, 13, 13),
+    (20, 80, 20),
+    (80, 20, 80),
+    (20, 80, 80),
+    (80, 80, 20),
+    (53, 53, 53),
+    (26, 26, 26),
+    (33, 33, 60),
+    (60, 26, 26),
+    (33, 60, 33),
+    (60, 33, 60),
+    (33, 60, 60),
+    (60, 60, 33),
+    (80, 80, 80),
+];
+
+fn percent_to_u8(percent: u16) -> u8 {
+    ((percent.min(100) as u32) * 255 / 100) as u8
+}
+
+/// Parse a `;`-separated run of decimal parameters starting at the front of
+/// `bytes` (e.g. the `Pc;2;Pr;Pg;Pb` after a `#`). Returns the parsed values
+/// and how many bytes were consumed; stops at the first byte that's neither
+/// a digit nor `;`, the same way `vte::Params` stops at a command byte.
+fn parse_params(bytes: &[u8]) -> (Vec<u16>, usize) {
+    let mut params = Vec::new();
+    let mut current: Option<u32> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'0'..=b'9' => {
+                let digit = (bytes[i] - b'0') as u32;
+                current = Some(current.unwrap_or(0) * 10 + digit);
+                i += 1;
+            }
+            b';' => {
+                params.push(current.take().unwrap_or(0).min(u16::MAX as u32) as u16);
+                i += 1;
+            }
+            _ => break,
+        }
+    }
+    if let Some(value) = current {
+        params.push(value.min(u16::MAX as u32) as u16);
+    }
+    (params, i)
+}
+
+/// Grow `canvas` (row-major, `*width` x `*height`) to cover at least
+/// `need_width` x `need_height`, preserving existing pixels. Most Sixel
+/// streams never send a raster-attributes header, so the canvas has to grow
+/// on demand as sixels arrive rather than being sized once up front.
+fn ensure_canvas(
+    canvas: &mut Vec<Option<(u8, u8, u8)>>,
+    width: &mut usize,
+    height: &mut usize,
+    need_width: usize,
+    need_height: usize,
+) {
+    if need_width <= *width && need_height <= *height {
+        return;
+    }
+    let new_width = need_width.max(*width);
+    let new_height = need_height.max(*height);
+    let mut grown = vec![None; new_width * new_height];
+    for row in 0..*height {
+        let src_start = row * *width;
+        let dst_start = row * new_width;
+        grown[dst_start..dst_start + *width].copy_from_slice(&canvas[src_start..src_start + *width]);
+    }
+    *canvas = grown;
+    *width = new_width;
+    *height = new_height;
+}
+
+/// Decode a Sixel DCS payload (the bytes between the `q` that introduces it
+/// and the terminating `ST`) into an RGBA raster. Returns `None` if the
+/// payload never produced any pixels (no raster attributes and no sixel
+/// data bytes).
+pub fn decode(payload: &[u8]) -> Option<SixelImage> {
+    let mut registers: std::collections::HashMap<u16, (u8, u8, u8)> = DEFAULT_PALETTE
+        .iter()
+        .enumerate()
+        .map(|(index, &(r, g, b))| {
+            (
+                index as u16,
+                (percent_to_u8(r as u16), percent_to_u8(g as u16), percent_to_u8(b as u16)),
+            )
+        })
+        .collect();
+
+    let mut canvas: Vec<Option<(u8, u8, u8)>> = Vec::new();
+    let mut width = 0usize;
+    let mut height = 0usize;
+
+    let mut x = 0usize;
+    let mut y = 0usize;
+    let mut color = 0u16;
+    let mut repeat = 1usize;
+
+    let mut i = 0;
+    while i < payload.len() {
+        match payload[i] {
+            b'"' => {
+                // Raster attributes: "Pan;Pad;Ph;Pv - only Ph/Pv (pixel
+                // width/height) matter here, the aspect ratio is cosmetic.
+                i += 1;
+                let (params, consumed) = parse_params(&payload[i..]);
+                i += consumed;
+                if let (Some(&w), Some(&h)) = (params.get(2), params.get(3)) {
+                    ensure_canvas(&mut canvas, &mut width, &mut height, w as usize, h as usize);
+                }
+            }
+            b'#' => {
+                // Color introducer: `#Pc` selects register Pc, `#Pc;2;Pr;Pg;Pb`
+                // also (re)defines it as an RGB percentage. Color-space 1
+                // (HLS) isn't supported and leaves the register untouched.
+                i += 1;
+                let (params, consumed) = parse_params(&payload[i..]);
+                i += consumed;
+                if let Some(&pc) = params.first() {
+                    color = pc;
+                    if params.get(1) == Some(&2) {
+                        if let (Some(&r), Some(&g), Some(&b)) = (params.get(2), params.get(3), params.get(4)) {
+                            registers.insert(pc, (percent_to_u8(r), percent_to_u8(g), percent_to_u8(b)));
+                        }
+                    }
+                }
+            }
+            b'!' => {
+                // Repeat introducer: `!Pn` repeats the next sixel Pn times.
+                i += 1;
+                let (params, consumed) = parse_params(&payload[i..]);
+                i += consumed;
+                repeat = params.first().copied().unwrap_or(1).max(1) as usize;
+            }
+            b'$' => {
+                // Carriage return: back to the start of the current band.
+                x = 0;
+                i += 1;
+            }
+            b'-' => {
+                // Newline: down one band (six pixel rows), back to column 0.
+                x = 0;
+                y += 6;
+                i += 1;
+            }
+            byte @ 0x3F..=0x7E => {
+                let bits = byte - 0x3F;
+                ensure_canvas(&mut canvas, &mut width, &mut height, x + repeat, y + 6);
+                let rgb = registers.get(&color).copied().unwrap_or((0, 0, 0));
+                for col in x..x + repeat {
+                    for bit in 0..6u8 {
+                        if bits & (1 << bit) != 0 {
+                            let row = y + bit as usize;
+                            if col < width && row < height {
+                                canvas[row * width + col] = Some(rgb);
+                            }
+                        }
+                    }
+                }
+                x += repeat;
+                repeat = 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for pixel in canvas {
+        match pixel {
+            Some((r, g, b)) => rgba.extend_from_slice(&[r, g, b, 255]),
+            None => rgba.extend_from_slice(&[0, 0, 0, 0]),
+        }
+    }
+    Some(SixelImage { width, height, rgba })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_empty_payload_is_none() {
+        assert!(decode(b"").is_none());
+    }
+
+    #[test]
+    fn test_decode_single_sixel_sets_column() {
+        // Select register 0 (black, opaque once drawn), draw one sixel
+        // with every bit set (0x3F + 0b111111 = 0x7E = '~').
+        let image = decode(b"#0~").unwrap();
+        assert_eq!(image.width, 1);
+        assert_eq!(image.height, 6);
+        for row in 0..6 {
+            let offset = row * 4;
+            assert_eq!(&image.rgba[offset..offset + 4], &[0, 0, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn test_decode_respects_raster_attributes() {
+        let image = decode(b"\"1;1;4;6#0?").unwrap();
+        assert_eq!(image.width, 4);
+        assert_eq!(image.height, 6);
+    }
+
+    #[test]
+    fn test_decode_repeat_introducer() {
+        // '~' (0x7E) is an all-ones sixel; `!5` draws it across five
+        // columns, so the canvas grows to five wide.
+        let image = decode(b"#0!5~").unwrap();
+        assert_eq!(image.width, 5);
+    }
+
+    #[test]
+    fn test_decode_newline_advances_band() {
+        let image = decode(b"#0~-~").unwrap();
+        assert_eq!(image.height, 12);
+    }
+
+    #[test]
+    fn test_decode_carriage_return_resets_column() {
+        let image = decode(b"#0~~$~").unwrap();
+        // Two sixels advance to column 2, then `$` resets to column 0
+        // before a third sixel is drawn - still only 2 columns wide.
+        assert_eq!(image.width, 2);
+    }
+
+    #[test]
+    fn test_decode_custom_color_register() {
+        // Register 5 redefined to pure red (100%, 0%, 0%), then selected.
+        let image = decode(b"#5;2;100;0;0#5~").unwrap();
+        assert_eq!(&image.rgba[0..4], &[255, 0, 0, 255]);
+    }
+}
@@ -3,11 +3,46 @@
 //! Provides a UI overlay that allows users to interact with AI
 //! for command suggestions, explanations, and assistance.
 
-use crate::ai::openai::{OpenAIConfig, OpenAIProvider};
-use crate::ai::provider::{AIProvider, CompletionOptions};
-use std::sync::{Arc, Mutex};
-use std::thread;
+use crate::ai::history::ConversationHistory;
+use crate::ai::provider::{AIProvider, CompletionOptions, ToolCall, ToolOutcome};
+use crate::ai::shell_context::{ShellContext, ShellContextConfig};
+use crate::ai::ProviderKind;
+use crate::ui::markdown::{self, ResponseSegment};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
+use tokio::runtime::Runtime;
+
+/// Shared runtime all AI requests are spawned on, instead of each
+/// `submit_command` call spinning up (and potentially leaking, on panic)
+/// its own `Runtime` and OS thread.
+fn ai_runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start AI runtime")
+    })
+}
+
+/// Progress reported by the background AI task, drained non-blockingly by
+/// `update()` each render tick. Replaces polling the response buffer and
+/// guessing completion from "is it non-empty" - a heuristic that mistook
+/// an error message for a success and never noticed an empty-but-done
+/// response.
+#[derive(Debug)]
+enum AiEvent {
+    /// Text is available (streamed providers may send several of these;
+    /// the current providers send the full response as a single chunk).
+    Chunk(String),
+    /// The model proposed a command via the `run_command` tool.
+    Command(ToolCall),
+    /// The request finished successfully.
+    Done,
+    /// The request failed.
+    Error(String),
+}
 
 /// State of the AI command palette
 #[derive(Debug, Clone, PartialEq)]
@@ -16,10 +51,21 @@ pub enum PaletteState {
     Hidden,
     /// Palette is open and waiting for input
     Open,
-    /// Processing AI request
+    /// Processing AI request - waiting for the first chunk of the response
     Processing,
+    /// Receiving the response incrementally; `update()` keeps reading as
+    /// deltas land and resets the timeout on each one
+    Streaming,
     /// Displaying AI response
     ShowingResponse,
+    /// The model proposed a command via the `run_command` tool; waiting on
+    /// the user to confirm (or re-confirm, if `dangerous`) before it is
+    /// handed to the shell
+    ConfirmAction {
+        command: String,
+        explanation: String,
+        dangerous: bool,
+    },
 }
 
 /// AI command palette for AI-assisted commands
@@ -29,13 +75,36 @@ pub struct AICommandPalette {
     /// User input buffer
     pub input: String,
     /// AI response buffer
-    pub response: Arc<Mutex<String>>,
+    response: String,
     /// Cursor position in input buffer
     pub cursor_pos: usize,
-    /// OpenAI provider (optional - may not be configured)
-    provider: Option<OpenAIProvider>,
-    /// Timestamp when processing started (for timeout)
+    /// Active AI backend (optional - may not be configured yet)
+    provider: Option<Arc<dyn AIProvider>>,
+    /// Which backend `initialize_provider` should instantiate
+    pub provider_kind: ProviderKind,
+    /// Timestamp when processing started, or when the last delta was
+    /// received while streaming (for timeout)
     processing_start: Option<Instant>,
+    /// Receiving end of the channel the in-flight request's worker task
+    /// reports progress on; `None` when no request is in flight
+    event_rx: Option<Receiver<AiEvent>>,
+    /// Whether a dangerous `ConfirmAction` has already had its first Enter
+    /// acknowledged; reset whenever a new `ConfirmAction` is entered
+    dangerous_ack: bool,
+    /// Command handed off by the user from `ConfirmAction`, drained by the
+    /// caller via `take_confirmed_command` and staged on the shell's input
+    /// line (not submitted) so the user reviews it before running it
+    confirmed_command: Option<String>,
+    /// Ambient shell state (cwd, git branch, last command) folded into the
+    /// prompt; refreshed by the caller via `set_shell_context` before the
+    /// palette is opened
+    pub shell_context: ShellContext,
+    /// Which `shell_context` fields are actually sent to the AI provider
+    pub shell_context_config: ShellContextConfig,
+    /// Accumulated conversation turns, sent as the transcript on each
+    /// request and persisted to disk so a reopened palette (even after
+    /// restarting the app) remembers the conversation so far
+    pub history: ConversationHistory,
     /// Error message if any
     pub error: Option<String>,
 }
@@ -46,17 +115,37 @@ impl AICommandPalette {
         Self {
             state: PaletteState::Hidden,
             input: String::new(),
-            response: Arc::new(Mutex::new(String::new())),
+            response: String::new(),
             cursor_pos: 0,
             provider: None,
+            provider_kind: ProviderKind::OpenAI,
             processing_start: None,
+            event_rx: None,
+            dangerous_ack: false,
+            confirmed_command: None,
+            shell_context: ShellContext::default(),
+            shell_context_config: ShellContextConfig::default(),
+            history: ConversationHistory::load().unwrap_or_default(),
             error: None,
         }
     }
 
-    /// Initialize the AI provider
+    /// Start a fresh conversation, discarding the persisted session.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        let _ = self.history.save();
+    }
+
+    /// Refresh the ambient shell context used to ground prompts. The
+    /// caller (which owns the focused pane) should call this with the
+    /// pane's working directory before opening the palette.
+    pub fn set_shell_context(&mut self, context: ShellContext) {
+        self.shell_context = context;
+    }
+
+    /// Initialize the active AI provider (see `provider_kind`)
     pub fn initialize_provider(&mut self) -> Result<(), String> {
-        match OpenAIProvider::from_keyring(None) {
+        match crate::ai::create_provider(self.provider_kind) {
             Ok(provider) => {
                 self.provider = Some(provider);
                 Ok(())
@@ -69,9 +158,7 @@ impl AICommandPalette {
     pub fn open(&mut self) {
         self.state = PaletteState::Open;
         self.input.clear();
-        if let Ok(mut response) = self.response.lock() {
-            response.clear();
-        }
+        self.response.clear();
         self.cursor_pos = 0;
         self.error = None;
     }
@@ -80,10 +167,9 @@ impl AICommandPalette {
     pub fn close(&mut self) {
         self.state = PaletteState::Hidden;
         self.input.clear();
-        if let Ok(mut response) = self.response.lock() {
-            response.clear();
-        }
+        self.response.clear();
         self.cursor_pos = 0;
+        self.dangerous_ack = false;
         self.error = None;
     }
 
@@ -124,16 +210,36 @@ impl AICommandPalette {
         }
     }
 
-    /// Handle Enter key - submit the command
+    /// Handle Enter key - submit the command, confirm a response, or - for
+    /// a pending `ConfirmAction` - hand the suggested command to the shell
     pub fn handle_enter(&mut self) {
-        if self.state == PaletteState::Open && !self.input.is_empty() {
-            self.submit_command();
-        } else if self.state == PaletteState::ShowingResponse {
-            // Close after viewing response
-            self.close();
+        match &self.state {
+            PaletteState::Open if !self.input.is_empty() => self.submit_command(),
+            PaletteState::ShowingResponse => self.close(),
+            PaletteState::ConfirmAction {
+                command, dangerous, ..
+            } => {
+                if *dangerous && !self.dangerous_ack {
+                    // First Enter on a dangerous command only acknowledges
+                    // it; a second Enter is required to hand it off.
+                    self.dangerous_ack = true;
+                } else {
+                    self.confirmed_command = Some(command.clone());
+                    self.close();
+                }
+            }
+            _ => {}
         }
     }
 
+    /// Take the command the user confirmed from `ConfirmAction`, if any.
+    /// The caller (which owns the shell/PTY) writes it to the pane's input
+    /// line without a trailing newline - staged for the user to edit or
+    /// run themselves, not auto-submitted.
+    pub fn take_confirmed_command(&mut self) -> Option<String> {
+        self.confirmed_command.take()
+    }
+
     /// Handle escape key
     pub fn handle_escape(&mut self) {
         self.close();
@@ -177,79 +283,136 @@ impl AICommandPalette {
         if let Some(provider) = &self.provider {
             self.state = PaletteState::Processing;
             self.processing_start = Some(Instant::now());
-            if let Ok(mut response) = self.response.lock() {
-                response.clear();
-            }
+            self.response.clear();
             self.error = None;
 
-            // Clone necessary data for the async thread
+            self.history.push_user(self.input.clone());
+
+            let context_section = self
+                .shell_context
+                .to_prompt_section(&self.shell_context_config);
+            let transcript = self.history.to_transcript();
             let prompt = format!(
-                "You are a terminal assistant. The user asks: {}\n\nProvide a helpful response. If suggesting a command, put it in a code block. Keep responses concise.",
-                self.input
+                "You are a terminal assistant. {}Conversation so far:\n{}\n\nIf the best response is a shell command, use the run_command tool. Otherwise reply in plain, concise prose.",
+                context_section, transcript
             );
-            let api_key = provider.api_key().to_string();
-            let model = provider.model().to_string();
-            let response_arc = Arc::clone(&self.response);
-
-            // Spawn a thread with tokio runtime to handle the async API call
-            thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                    let config = OpenAIConfig {
-                        api_key,
-                        model,
-                    };
-                    let async_provider = OpenAIProvider::new(config);
-
-                    let opts = CompletionOptions {
-                        max_tokens: Some(500),
-                        temperature: Some(0.7),
-                    };
-
-                    match async_provider.complete(&prompt, Some(opts)).await {
-                        Ok(result) => {
-                            if let Ok(mut response) = response_arc.lock() {
-                                *response = result;
-                            }
-                        }
-                        Err(e) => {
-                            if let Ok(mut response) = response_arc.lock() {
-                                *response = format!("Error: {}", e);
-                            }
-                        }
+            let provider = Arc::clone(provider);
+            let (tx, rx) = mpsc::channel();
+            self.event_rx = Some(rx);
+
+            ai_runtime().spawn(async move {
+                let opts = CompletionOptions {
+                    max_tokens: Some(500),
+                    temperature: Some(0.7),
+                };
+
+                match provider.complete_with_tool(&prompt, Some(opts)).await {
+                    Ok(ToolOutcome::Text(text)) => {
+                        let _ = tx.send(AiEvent::Chunk(text));
+                        let _ = tx.send(AiEvent::Done);
+                    }
+                    Ok(ToolOutcome::Command(tool_call)) => {
+                        let _ = tx.send(AiEvent::Command(tool_call));
+                        let _ = tx.send(AiEvent::Done);
+                    }
+                    Err(e) => {
+                        let _ = tx.send(AiEvent::Error(e.to_string()));
                     }
-                });
+                }
             });
         } else {
-            self.error = Some("AI provider not configured. Please set up OpenAI API key using: warp-foss config set-openai-key <key>".to_string());
+            self.error = Some("AI provider not configured. Please store an API key for the selected provider in the system keyring.".to_string());
         }
     }
 
     /// Get suggested commands based on context
     pub fn get_suggestions(&self, _context: &str) -> Vec<String> {
-        // TODO: Implement context-aware suggestions
-        vec![
+        let mut suggestions = vec![
             "explain last command".to_string(),
             "suggest fix for error".to_string(),
             "generate command for...".to_string(),
-        ]
+        ];
+
+        if self.shell_context.last_command_failed() {
+            // Surface the grounded version first - `shell_context` already
+            // has the failing command and its stderr to answer with.
+            suggestions.insert(0, "explain why the last command failed".to_string());
+        }
+
+        if self.shell_context.git_dirty == Some(true) {
+            suggestions.push("explain git status".to_string());
+        }
+
+        suggestions
     }
 
-    /// Update processing state (call this in render loop)
+    /// Update processing state (call this in render loop). Drains every
+    /// event the worker task has queued up since the last tick - there is
+    /// no mutex to poll, just a channel to empty.
     pub fn update(&mut self) {
-        // Check for timeout
         if let Some(start) = self.processing_start {
             if start.elapsed().as_secs() > 30 {
                 self.error = Some("AI request timed out".to_string());
                 self.state = PaletteState::Open;
                 self.processing_start = None;
-            } else {
-                // Check if response is ready
-                if let Ok(response) = self.response.lock() {
-                    if !response.is_empty() {
+                self.event_rx = None;
+                return;
+            }
+        }
+
+        let Some(rx) = &self.event_rx else {
+            return;
+        };
+
+        loop {
+            match rx.try_recv() {
+                Ok(AiEvent::Chunk(text)) => {
+                    self.response.push_str(&text);
+                    if self.state == PaletteState::Processing {
+                        self.state = PaletteState::Streaming;
+                    }
+                    self.processing_start = Some(Instant::now());
+                }
+                Ok(AiEvent::Command(tool_call)) => {
+                    self.history.push_assistant(format!(
+                        "Proposed command: {} ({})",
+                        tool_call.command, tool_call.explanation
+                    ));
+                    let _ = self.history.save();
+                    self.dangerous_ack = false;
+                    self.state = PaletteState::ConfirmAction {
+                        command: tool_call.command,
+                        explanation: tool_call.explanation,
+                        dangerous: tool_call.dangerous,
+                    };
+                    self.processing_start = None;
+                }
+                Ok(AiEvent::Done) => {
+                    if matches!(
+                        self.state,
+                        PaletteState::Processing | PaletteState::Streaming
+                    ) {
                         self.state = PaletteState::ShowingResponse;
-                        self.processing_start = None;
+                        if !self.response.is_empty() {
+                            self.history.push_assistant(self.response.clone());
+                            let _ = self.history.save();
+                        }
                     }
+                    self.processing_start = None;
+                    self.event_rx = None;
+                    break;
+                }
+                Ok(AiEvent::Error(e)) => {
+                    self.error = Some(e);
+                    self.state = PaletteState::Open;
+                    self.processing_start = None;
+                    self.event_rx = None;
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.event_rx = None;
+                    break;
                 }
             }
         }
@@ -257,10 +420,31 @@ impl AICommandPalette {
 
     /// Get the current response text
     pub fn get_response(&self) -> String {
-        if let Ok(response) = self.response.lock() {
-            response.clone()
-        } else {
-            String::new()
+        self.response.clone()
+    }
+
+    /// Get the current response split into prose and fenced-code-block
+    /// segments, so the UI can render code distinctly and offer per-block
+    /// actions instead of flat text.
+    pub fn get_rendered_response(&self) -> Vec<ResponseSegment> {
+        markdown::parse(&self.response)
+    }
+
+    /// Promote the first fenced code block in the response into a
+    /// `ConfirmAction`, routing it through the same confirm-before-run
+    /// gate as a model-proposed `run_command` tool call.
+    pub fn promote_first_code_block(&mut self) {
+        if self.state != PaletteState::ShowingResponse {
+            return;
+        }
+
+        if let Some(code_block) = markdown::first_code_block(&self.get_rendered_response()) {
+            self.dangerous_ack = false;
+            self.state = PaletteState::ConfirmAction {
+                command: code_block.code.clone(),
+                explanation: "Extracted from the AI response".to_string(),
+                dangerous: false,
+            };
         }
     }
 }
@@ -280,9 +464,7 @@ mod tests {
         let palette = AICommandPalette::new();
         assert_eq!(palette.state, PaletteState::Hidden);
         assert!(palette.input.is_empty());
-        if let Ok(response) = palette.response.lock() {
-            assert!(response.is_empty());
-        }; // Semicolon to drop the guard
+        assert!(palette.response.is_empty());
     }
 
     #[test]
@@ -353,13 +535,151 @@ mod tests {
     }
 
     #[test]
-    fn test_get_response() {
-        let palette = AICommandPalette::new();
-        {
-            if let Ok(mut response) = palette.response.lock() {
-                *response = "Test response".to_string();
+    fn test_update_drains_chunk_then_done_into_showing_response() {
+        let mut palette = AICommandPalette::new();
+        palette.state = PaletteState::Processing;
+        palette.processing_start = Some(Instant::now());
+        let (tx, rx) = mpsc::channel();
+        palette.event_rx = Some(rx);
+
+        // No events yet - stays in Processing.
+        palette.update();
+        assert_eq!(palette.state, PaletteState::Processing);
+
+        // First chunk arrives - moves to Streaming.
+        tx.send(AiEvent::Chunk("Hel".to_string())).unwrap();
+        palette.update();
+        assert_eq!(palette.state, PaletteState::Streaming);
+        assert_eq!(palette.response, "Hel");
+
+        // Done - moves to ShowingResponse.
+        tx.send(AiEvent::Done).unwrap();
+        palette.update();
+        assert_eq!(palette.state, PaletteState::ShowingResponse);
+        assert!(palette.event_rx.is_none());
+    }
+
+    #[test]
+    fn test_update_moves_to_confirm_action_when_command_event_lands() {
+        let mut palette = AICommandPalette::new();
+        palette.state = PaletteState::Processing;
+        palette.processing_start = Some(Instant::now());
+        let (tx, rx) = mpsc::channel();
+        palette.event_rx = Some(rx);
+        tx.send(AiEvent::Command(ToolCall {
+            command: "ls -la".to_string(),
+            explanation: "List files".to_string(),
+            dangerous: false,
+        }))
+        .unwrap();
+        tx.send(AiEvent::Done).unwrap();
+
+        palette.update();
+
+        assert_eq!(
+            palette.state,
+            PaletteState::ConfirmAction {
+                command: "ls -la".to_string(),
+                explanation: "List files".to_string(),
+                dangerous: false,
             }
-        }
+        );
+    }
+
+    #[test]
+    fn test_update_surfaces_error_event() {
+        let mut palette = AICommandPalette::new();
+        palette.state = PaletteState::Processing;
+        palette.processing_start = Some(Instant::now());
+        let (tx, rx) = mpsc::channel();
+        palette.event_rx = Some(rx);
+        tx.send(AiEvent::Error("boom".to_string())).unwrap();
+
+        palette.update();
+
+        assert_eq!(palette.state, PaletteState::Open);
+        assert_eq!(palette.error, Some("boom".to_string()));
+        assert!(palette.event_rx.is_none());
+    }
+
+    #[test]
+    fn test_dangerous_command_requires_two_confirmations() {
+        let mut palette = AICommandPalette::new();
+        palette.state = PaletteState::ConfirmAction {
+            command: "rm -rf /tmp/foo".to_string(),
+            explanation: "Delete the directory".to_string(),
+            dangerous: true,
+        };
+
+        // First Enter only acknowledges the danger.
+        palette.handle_enter();
+        assert!(palette.dangerous_ack);
+        assert!(matches!(palette.state, PaletteState::ConfirmAction { .. }));
+        assert_eq!(palette.take_confirmed_command(), None);
+
+        // Second Enter actually confirms and hands off the command.
+        palette.handle_enter();
+        assert_eq!(palette.state, PaletteState::Hidden);
+        assert_eq!(
+            palette.take_confirmed_command(),
+            Some("rm -rf /tmp/foo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_non_dangerous_command_confirms_on_first_enter() {
+        let mut palette = AICommandPalette::new();
+        palette.state = PaletteState::ConfirmAction {
+            command: "ls -la".to_string(),
+            explanation: "List files".to_string(),
+            dangerous: false,
+        };
+
+        palette.handle_enter();
+
+        assert_eq!(palette.state, PaletteState::Hidden);
+        assert_eq!(
+            palette.take_confirmed_command(),
+            Some("ls -la".to_string())
+        );
+    }
+
+    #[test]
+    fn test_suggestions_are_context_aware() {
+        let mut palette = AICommandPalette::new();
+        let base = palette.get_suggestions("");
+        assert!(!base.contains(&"explain why the last command failed".to_string()));
+        assert!(!base.contains(&"explain git status".to_string()));
+
+        palette
+            .shell_context
+            .record_last_command("cargo build".to_string(), 1, vec!["error".to_string()]);
+        palette.shell_context.git_dirty = Some(true);
+
+        let suggestions = palette.get_suggestions("");
+        assert_eq!(
+            suggestions.first(),
+            Some(&"explain why the last command failed".to_string())
+        );
+        assert!(suggestions.contains(&"explain git status".to_string()));
+    }
+
+    #[test]
+    fn test_clear_history_empties_turns() {
+        let mut palette = AICommandPalette::new();
+        palette.history.push_user("list files");
+        palette.history.push_assistant("ls -la");
+        assert!(!palette.history.turns.is_empty());
+
+        palette.clear_history();
+
+        assert!(palette.history.turns.is_empty());
+    }
+
+    #[test]
+    fn test_get_response() {
+        let mut palette = AICommandPalette::new();
+        palette.response = "Test response".to_string();
         assert_eq!(palette.get_response(), "Test response");
     }
 }
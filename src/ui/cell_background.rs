@@ -0,0 +1,251 @@
+//! GPU-rendered solid-color quads for per-cell terminal backgrounds and the
+//! cursor - the piece `text::TextRenderer` doesn't do itself (it draws
+//! glyphs, not the cell behind them; `queue_char` takes a `bg_color` but
+//! never acts on it). One instance per non-default-background cell, plus
+//! one more for the cursor, drawn in a single `draw(0..6, 0..instance_count)`
+//! before the text pass so glyphs render on top.
+//!
+//! Deliberately separate from `shapes::RectRenderer`: that renderer's own
+//! doc comments scope it to fixed UI chrome (borders, the cursor outline,
+//! overlay frames), not arbitrary cell colors, and it has no outline/border
+//! mode to skip here.
+
+use wgpu::{Device, Queue, TextureFormat};
+
+use super::text::{indexed_color_rgba, to_linear};
+use crate::terminal::parser::Color;
+
+/// One instance: a solid-color quad covering one cell.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct CellRectInstance {
+    /// Top-left corner, in NDC.
+    pos_min: [f32; 2],
+    /// Bottom-right corner, in NDC.
+    pos_max: [f32; 2],
+    /// Color (RGBA8).
+    color: [u8; 4],
+}
+
+impl CellRectInstance {
+    const ATTRIBS: [wgpu::VertexAttribute; 3] = [
+        wgpu::VertexAttribute {
+            offset: 0,
+            shader_location: 0,
+            format: wgpu::VertexFormat::Float32x2,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 2]>() as u64,
+            shader_location: 1,
+            format: wgpu::VertexFormat::Float32x2,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 4]>() as u64,
+            shader_location: 2,
+            format: wgpu::VertexFormat::Unorm8x4,
+        },
+    ];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<CellRectInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// Fixed fill color for the block cursor.
+const CURSOR_COLOR: [u8; 4] = [230, 230, 230, 255];
+
+/// Resolve a cell's background `Color` to RGBA8, or `None` if it shouldn't
+/// paint over the clear color: `Color::Default` (no background set) and the
+/// non-palette `Named` slots (`Foreground`/`Background`/`Cursor`). The 16
+/// ANSI `Named` slots (SGR 40-47/100-107) paint their indexed-palette color.
+///
+/// Terminal colors are specified in sRGB/gamma space, so the result is run
+/// through `to_linear` before quantizing to u8 - same conversion
+/// `text::color_to_rgba` applies - or backgrounds would come out too
+/// bright on the sRGB surface.
+fn cell_color_to_rgba8(color: Color) -> Option<[u8; 4]> {
+    let rgba = match color {
+        // `NamedColor` declares the 16 ANSI slots first, in palette order.
+        Color::Named(named) if (named as u8) < 16 => indexed_color_rgba(named as u8),
+        Color::Default | Color::Named(_) => return None,
+        Color::Indexed(idx) => indexed_color_rgba(idx),
+        Color::Rgb(r, g, b) => [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0],
+    };
+    Some(linear_rgba_to_u8(to_linear(rgba)))
+}
+
+/// Quantize a linear-space RGBA f32 color to u8, clamping to `[0, 255]` so
+/// an out-of-range channel can't wrap instead of saturating.
+fn linear_rgba_to_u8(color: [f32; 4]) -> [u8; 4] {
+    [
+        (color[0] * 255.0).clamp(0.0, 255.0) as u8,
+        (color[1] * 255.0).clamp(0.0, 255.0) as u8,
+        (color[2] * 255.0).clamp(0.0, 255.0) as u8,
+        (color[3] * 255.0).clamp(0.0, 255.0) as u8,
+    ]
+}
+
+/// GPU-backed renderer for per-cell background fills and the cursor,
+/// drawn in one pass immediately before text.
+pub struct CellBackgroundRenderer {
+    pipeline: wgpu::RenderPipeline,
+    instance_buffer: Option<wgpu::Buffer>,
+    instances: Vec<CellRectInstance>,
+    screen_size: (u32, u32),
+}
+
+impl CellBackgroundRenderer {
+    /// Build the pipeline and an empty instance list for `screen_size`.
+    pub fn new(device: &Device, format: TextureFormat, screen_size: (u32, u32)) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Cell Background Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/cell_background.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Cell Background Pipeline Layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Cell Background Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[CellRectInstance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            instance_buffer: None,
+            instances: Vec::new(),
+            screen_size,
+        }
+    }
+
+    /// Allocate the instance buffer, mirroring `TextRenderer::init_buffer`.
+    pub fn init_buffer(&mut self, device: &Device) {
+        self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Cell Background Instance Buffer"),
+            size: 64 * 1024,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.screen_size = (width, height);
+    }
+
+    /// Clear queued rects for a new frame.
+    pub fn clear(&mut self) {
+        self.instances.clear();
+    }
+
+    fn to_ndc(&self, x: f32, y: f32, width: f32, height: f32) -> ([f32; 2], [f32; 2]) {
+        let (screen_w, screen_h) = self.screen_size;
+        let screen_w = screen_w as f32;
+        let screen_h = screen_h as f32;
+
+        let ndc_x = x / screen_w * 2.0 - 1.0;
+        let ndc_y = 1.0 - y / screen_h * 2.0;
+        let ndc_w = width / screen_w * 2.0;
+        let ndc_h = height / screen_h * 2.0;
+
+        ([ndc_x, ndc_y], [ndc_x + ndc_w, ndc_y - ndc_h])
+    }
+
+    /// Queue a cell's background fill at pixel-space `(x, y, width,
+    /// height)`. No-op if `color` shouldn't paint (see `cell_color_to_rgba8`).
+    pub fn queue_cell(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let Some(color) = cell_color_to_rgba8(color) else { return };
+        let (pos_min, pos_max) = self.to_ndc(x, y, width, height);
+        self.instances.push(CellRectInstance { pos_min, pos_max, color });
+    }
+
+    /// Queue the cursor's fill at pixel-space `(x, y, width, height)`.
+    pub fn queue_cursor(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let (pos_min, pos_max) = self.to_ndc(x, y, width, height);
+        // `CURSOR_COLOR` is picked by eye in sRGB space like every other
+        // terminal color, so it needs the same linearization.
+        let color = linear_rgba_to_u8(to_linear([
+            CURSOR_COLOR[0] as f32 / 255.0,
+            CURSOR_COLOR[1] as f32 / 255.0,
+            CURSOR_COLOR[2] as f32 / 255.0,
+            CURSOR_COLOR[3] as f32 / 255.0,
+        ]));
+        self.instances.push(CellRectInstance { pos_min, pos_max, color });
+    }
+
+    /// Upload instance data, same pattern as `TextRenderer::prepare`.
+    pub fn prepare(&mut self, device: &Device, queue: &Queue) {
+        if self.instances.is_empty() {
+            return;
+        }
+
+        let instance_data: &[u8] = bytemuck::cast_slice(&self.instances);
+        let needed_size = instance_data.len() as u64;
+
+        if let Some(ref buffer) = self.instance_buffer {
+            if buffer.size() < needed_size {
+                self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Cell Background Instance Buffer"),
+                    size: needed_size * 2,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+            }
+        }
+
+        if let Some(ref buffer) = self.instance_buffer {
+            queue.write_buffer(buffer, 0, instance_data);
+        }
+    }
+
+    /// Draw every queued cell/cursor fill in one instanced draw call.
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.instances.is_empty() {
+            return;
+        }
+        let Some(ref instance_buffer) = self.instance_buffer else { return };
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, instance_buffer.slice(..));
+        render_pass.draw(0..6, 0..self.instances.len() as u32);
+    }
+}
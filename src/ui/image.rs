@@ -0,0 +1,262 @@
+//! GPU texture rendering for inline Sixel images (`terminal::sixel`).
+//!
+//! Unlike `text::Cache`'s single glyph atlas, each decoded image is its own
+//! texture - there's no benefit to atlas-packing a handful of large,
+//! unrelated rasters the way glyphs are packed. Every cached entry owns a
+//! full texture/bind group/uniform buffer, keyed by the identity of the
+//! `Arc<terminal::sixel::SixelImage>` it was built from (see
+//! `terminal::grid::SixelImageStore`).
+
+use std::collections::{HashMap, HashSet};
+
+use wgpu::{Device, Queue, TextureFormat};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct RectUniform {
+    pos_min: [f32; 2],
+    pos_max: [f32; 2],
+}
+
+struct CachedImage {
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+}
+
+/// Uploads and draws decoded Sixel rasters as textured quads, one draw call
+/// per image queued this frame.
+pub struct ImageRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    cache: HashMap<u64, CachedImage>,
+    queued: Vec<u64>,
+    screen_size: (u32, u32),
+}
+
+impl ImageRenderer {
+    /// Build the pipeline and an empty texture cache for `screen_size`.
+    pub fn new(device: &Device, format: TextureFormat, screen_size: (u32, u32)) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Image Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/image.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Image Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Image Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Image Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Image Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            cache: HashMap::new(),
+            queued: Vec::new(),
+            screen_size,
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.screen_size = (width, height);
+    }
+
+    /// Clear the draw queue for a new frame. Cached textures survive - see
+    /// `evict_unused`.
+    pub fn clear(&mut self) {
+        self.queued.clear();
+    }
+
+    fn to_ndc(&self, x: f32, y: f32, width: f32, height: f32) -> ([f32; 2], [f32; 2]) {
+        let (screen_w, screen_h) = self.screen_size;
+        let screen_w = screen_w as f32;
+        let screen_h = screen_h as f32;
+
+        let ndc_x = x / screen_w * 2.0 - 1.0;
+        let ndc_y = 1.0 - y / screen_h * 2.0;
+        let ndc_w = width / screen_w * 2.0;
+        let ndc_h = height / screen_h * 2.0;
+
+        ([ndc_x, ndc_y], [ndc_x + ndc_w, ndc_y - ndc_h])
+    }
+
+    /// Queue a decoded raster for blitting at pixel-space `(x, y, width,
+    /// height)`, uploading its texture the first time `id` is seen. `id`
+    /// should uniquely identify the raster's pixel content - callers pass
+    /// the anchoring `Arc<SixelImage>`'s pointer identity.
+    pub fn queue_image(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        id: u64,
+        rgba: &[u8],
+        pixel_width: u32,
+        pixel_height: u32,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) {
+        if !self.cache.contains_key(&id) {
+            self.cache.insert(id, self.upload(device, queue, rgba, pixel_width, pixel_height));
+        }
+
+        let (pos_min, pos_max) = self.to_ndc(x, y, width, height);
+        if let Some(cached) = self.cache.get(&id) {
+            queue.write_buffer(
+                &cached.uniform_buffer,
+                0,
+                bytemuck::bytes_of(&RectUniform { pos_min, pos_max }),
+            );
+        }
+        self.queued.push(id);
+    }
+
+    fn upload(&self, device: &Device, queue: &Queue, rgba: &[u8], width: u32, height: u32) -> CachedImage {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Sixel Image Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Image Uniform Buffer"),
+            size: std::mem::size_of::<RectUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Image Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: uniform_buffer.as_entire_binding() },
+            ],
+        });
+
+        CachedImage { bind_group, uniform_buffer }
+    }
+
+    /// Drop cached textures for images that weren't queued this frame - the
+    /// image scrolled off, was overwritten, or its pane closed (see
+    /// `terminal::grid::SixelImageStore`'s own row-granular eviction).
+    pub fn evict_unused(&mut self) {
+        let live: HashSet<u64> = self.queued.iter().copied().collect();
+        self.cache.retain(|id, _| live.contains(id));
+    }
+
+    /// Draw every image queued this frame, in queue order.
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.queued.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        for id in &self.queued {
+            if let Some(cached) = self.cache.get(id) {
+                render_pass.set_bind_group(0, &cached.bind_group, &[]);
+                render_pass.draw(0..6, 0..1);
+            }
+        }
+    }
+}
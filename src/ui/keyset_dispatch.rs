@@ -0,0 +1,120 @@
+//! Decode-time half of the keyset subsystem
+//!
+//! `config::keyset::KeyChord` knows nothing about `winit` - it's a plain,
+//! testable string/modifier model. This module is the glue: it turns a
+//! decoded `winit::keyboard::Key` and `ModifiersState` into the
+//! `KeyChord` that `Keyset::resolve`'s bindings are keyed on, so a
+//! `Dispatcher::resolve` call is what the winit key-event handler would
+//! consult to ask "does this keypress correspond to a bound `Action`?"
+
+use crate::config::keyset::{Action, KeyChord, Keyset};
+use std::collections::HashMap;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+/// A loaded keyset's resolved bindings, ready for per-keypress lookup.
+/// Built once (from `Keyset::resolve`) and reused for every key event,
+/// rather than re-parsing chord strings on every keypress.
+pub struct Dispatcher {
+    bindings: HashMap<Action, KeyChord>,
+    is_macos: bool,
+}
+
+impl Dispatcher {
+    pub fn new(keyset: &Keyset, is_macos: bool) -> Self {
+        let (bindings, _errors) = keyset.resolve();
+        Self { bindings, is_macos }
+    }
+
+    /// The `Action` bound to `key` under `modifiers`, if any.
+    pub fn resolve(&self, key: &Key, modifiers: ModifiersState) -> Option<Action> {
+        let chord = chord_from_winit(key, modifiers)?;
+        self.bindings
+            .iter()
+            .find(|(_, bound)| bound.matches(&chord, self.is_macos))
+            .map(|(&action, _)| action)
+    }
+}
+
+/// Convert a decoded `winit` key event into a `KeyChord`, or `None` for
+/// keys with no stable chord name (e.g. dead keys, `Unidentified`).
+fn chord_from_winit(key: &Key, modifiers: ModifiersState) -> Option<KeyChord> {
+    let key_name = match key {
+        Key::Character(c) => c.to_lowercase(),
+        Key::Named(named) => named_key_name(*named)?.to_string(),
+        Key::Unidentified(_) | Key::Dead(_) => return None,
+    };
+
+    Some(KeyChord {
+        modifiers: crate::config::keyset::ChordModifiers {
+            shift: modifiers.shift_key(),
+            control: modifiers.control_key(),
+            alt: modifiers.alt_key(),
+            super_key: modifiers.super_key(),
+        },
+        key: key_name,
+    })
+}
+
+/// The chord-spec name for a `NamedKey`, matching the lowercase vocabulary
+/// `config::keyset::KeyChord::parse` accepts (e.g. `"pageup"`, `"enter"`).
+/// Keys with no natural chord name (e.g. individual modifier keys) yield
+/// `None` - a bare Shift press can't itself be "the key" of a chord.
+fn named_key_name(named: NamedKey) -> Option<&'static str> {
+    Some(match named {
+        NamedKey::Enter => "enter",
+        NamedKey::Tab => "tab",
+        NamedKey::Space => "space",
+        NamedKey::Escape => "escape",
+        NamedKey::Backspace => "backspace",
+        NamedKey::Delete => "delete",
+        NamedKey::ArrowLeft => "arrowleft",
+        NamedKey::ArrowRight => "arrowright",
+        NamedKey::ArrowUp => "arrowup",
+        NamedKey::ArrowDown => "arrowdown",
+        NamedKey::Home => "home",
+        NamedKey::End => "end",
+        NamedKey::PageUp => "pageup",
+        NamedKey::PageDown => "pagedown",
+        NamedKey::F1 => "f1",
+        NamedKey::F2 => "f2",
+        NamedKey::F3 => "f3",
+        NamedKey::F4 => "f4",
+        NamedKey::F5 => "f5",
+        NamedKey::F6 => "f6",
+        NamedKey::F7 => "f7",
+        NamedKey::F8 => "f8",
+        NamedKey::F9 => "f9",
+        NamedKey::F10 => "f10",
+        NamedKey::F11 => "f11",
+        NamedKey::F12 => "f12",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::keyset::default_keyset;
+
+    #[test]
+    fn test_dispatcher_resolves_a_bound_chord() {
+        let dispatcher = Dispatcher::new(&default_keyset(), false);
+        let modifiers = ModifiersState::CONTROL;
+        let resolved = dispatcher.resolve(&Key::Character("c".into()), modifiers);
+        assert_eq!(resolved, Some(Action::Copy));
+    }
+
+    #[test]
+    fn test_dispatcher_resolves_named_key_chord() {
+        let dispatcher = Dispatcher::new(&default_keyset(), false);
+        let resolved = dispatcher.resolve(&Key::Named(NamedKey::PageUp), ModifiersState::SHIFT);
+        assert_eq!(resolved, Some(Action::ScrollPageUp));
+    }
+
+    #[test]
+    fn test_dispatcher_returns_none_for_unbound_chord() {
+        let dispatcher = Dispatcher::new(&default_keyset(), false);
+        let resolved = dispatcher.resolve(&Key::Character("z".into()), ModifiersState::empty());
+        assert_eq!(resolved, None);
+    }
+}
@@ -4,8 +4,11 @@
 
 use crate::terminal::grid::TerminalGrid;
 use crate::terminal::parser::TerminalParser;
-use crate::terminal::pty::PtySession;
-use std::sync::{Arc, Mutex};
+use crate::terminal::pty::{PtyConfig, PtySession};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Rectangle representing a pane's bounds
@@ -46,14 +49,34 @@ pub enum SplitDirection {
     Vertical,
 }
 
+/// Geometric direction for directional focus movement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Length of the overlap between two 1D spans, or 0 if they don't overlap
+fn overlap_1d(a_start: u32, a_end: u32, b_start: u32, b_end: u32) -> u32 {
+    a_end.min(b_end).saturating_sub(a_start.max(b_start))
+}
+
 /// A single terminal pane
 pub struct Pane {
     /// Unique identifier for this pane
     pub id: Uuid,
     /// Pane title (can be set dynamically from shell)
     pub title: String,
-    /// PTY session for this pane
-    pub pty: Arc<Mutex<PtySession>>,
+    /// PTY session for this pane, `None` for a placeholder slot that
+    /// hasn't had a shell spawned into it yet (see `ensure_pty`)
+    pub pty: Arc<Mutex<Option<PtySession>>>,
+    /// Bytes read off `pty` by its background watcher thread (see
+    /// `spawn_pty_watcher` in `main.rs`) but not yet parsed into `grid`.
+    /// Draining this on the main thread keeps `grid`/`parser` single-threaded
+    /// while letting the actual PTY reads happen off the winit event loop.
+    pub pending_output: Arc<Mutex<Vec<u8>>>,
     /// Terminal grid (screen buffer)
     pub grid: TerminalGrid,
     /// Terminal parser
@@ -76,7 +99,8 @@ impl Pane {
         Self {
             id,
             title,
-            pty: Arc::new(Mutex::new(pty)),
+            pty: Arc::new(Mutex::new(Some(pty))),
+            pending_output: Arc::new(Mutex::new(Vec::new())),
             grid: TerminalGrid::with_size(cols, rows),
             parser: TerminalParser::new(),
             bounds,
@@ -89,6 +113,140 @@ impl Pane {
         let rows = (self.bounds.height / cell_height) as usize;
         (cols.max(1), rows.max(1))
     }
+
+    /// Make sure this pane has a live `PtySession`, lazily spawning a
+    /// default shell into it if it's still an empty placeholder slot.
+    /// Returns whether a shell was actually spawned, so callers know
+    /// whether this pane still needs a PTY watcher thread started for it
+    /// (see `TerminalApp::spawn_pty_watcher`).
+    ///
+    /// Placeholder panes (from `create_placeholder_pane`) carry no process
+    /// at all rather than a throwaway cached one, so the first real write
+    /// or resize against them needs to spawn a shell on demand.
+    pub fn ensure_pty(&self) -> Result<bool, String> {
+        let mut session = self.pty.lock();
+        if session.is_none() {
+            *session = Some(
+                PtySession::spawn(PtyConfig::default())
+                    .map_err(|e| format!("failed to spawn PTY: {}", e))?,
+            );
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+/// A pane floating above the tiled layout tree with explicit geometry,
+/// e.g. a quick pop-up shell or scratch terminal
+///
+/// Unlike tiled panes, a floating pane's `Rect` is set directly via
+/// `LayoutTree::spawn_floating`/`move_floating`/`resize_floating` rather
+/// than computed by `calculate_layout`.
+pub struct FloatingPane {
+    /// The floating terminal pane itself
+    pub pane: Pane,
+    /// Absolute position and size, in the same coordinate space as
+    /// `calculate_layout`'s `total_bounds`
+    pub rect: Rect,
+    /// Stacking order; the pane with the highest value is drawn (and
+    /// receives focus from `toggle_floating_focus`) on top
+    pub z_index: u32,
+}
+
+/// Smallest width/height a floating pane may be resized down to
+const MIN_FLOATING_SIZE: u32 = 4;
+
+/// Shift `rect` by `(dx, dy)`, clamping so it stays fully within `bounds`
+fn clamp_floating_position(rect: Rect, dx: i32, dy: i32, bounds: Rect) -> Rect {
+    let max_x = bounds.x + bounds.width.saturating_sub(rect.width);
+    let max_y = bounds.y + bounds.height.saturating_sub(rect.height);
+
+    let new_x = (rect.x as i32 + dx).clamp(bounds.x as i32, max_x as i32) as u32;
+    let new_y = (rect.y as i32 + dy).clamp(bounds.y as i32, max_y as i32) as u32;
+
+    Rect::new(new_x, new_y, rect.width, rect.height)
+}
+
+/// Grow/shrink `rect` by `(dw, dh)`, clamping so it never drops below
+/// `MIN_FLOATING_SIZE` or grows past the edge of `bounds`
+fn clamp_floating_size(rect: Rect, dw: i32, dh: i32, bounds: Rect) -> Rect {
+    let max_width = (bounds.x + bounds.width).saturating_sub(rect.x).max(MIN_FLOATING_SIZE);
+    let max_height = (bounds.y + bounds.height).saturating_sub(rect.y).max(MIN_FLOATING_SIZE);
+
+    let new_width = (rect.width as i32 + dw).clamp(MIN_FLOATING_SIZE as i32, max_width as i32) as u32;
+    let new_height = (rect.height as i32 + dh).clamp(MIN_FLOATING_SIZE as i32, max_height as i32) as u32;
+
+    Rect::new(rect.x, rect.y, new_width, new_height)
+}
+
+/// A child's size within a split
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    /// An exact number of cells (columns for a horizontal split, rows for
+    /// a vertical one). Subtracted off the split's extent before `Percent`
+    /// children are distributed.
+    Fixed(u32),
+    /// A share (0.0-1.0) of whatever extent is left after `Fixed` siblings
+    /// have claimed their cells, normalized against the other `Percent`
+    /// siblings in the same split.
+    Percent(f32),
+}
+
+impl std::fmt::Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dimension::Fixed(cells) => write!(f, "{}cells", cells),
+            Dimension::Percent(percent) => {
+                let pct = percent * 100.0;
+                if (pct.round() - pct).abs() < 0.001 {
+                    write!(f, "{}%", pct.round() as i64)
+                } else {
+                    write!(f, "{:.1}%", pct)
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Dimension {
+    type Err = String;
+
+    /// Parse a bare size value, e.g. `"30cells"` or `"50%"` (no `size=` prefix)
+    fn from_str(value: &str) -> Result<Self, String> {
+        if let Some(cells) = value.strip_suffix("cells") {
+            cells
+                .parse::<u32>()
+                .map(Dimension::Fixed)
+                .map_err(|_| format!("invalid fixed size '{}'", value))
+        } else if let Some(pct) = value.strip_suffix('%') {
+            pct.parse::<f32>()
+                .map(|p| Dimension::Percent(p / 100.0))
+                .map_err(|_| format!("invalid percent size '{}'", value))
+        } else {
+            Err(format!("invalid size '{}': expected '<n>cells' or '<n>%'", value))
+        }
+    }
+}
+
+// Serialized as the same bare string form used by the text layout format
+// (minus the `size=` prefix), so a `LayoutTemplate` reads naturally in TOML.
+impl Serialize for Dimension {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Dimension {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
 }
 
 /// A node in the layout tree
@@ -98,12 +256,12 @@ pub enum LayoutNode {
     /// Horizontal split (panes side by side)
     HorizontalSplit {
         children: Vec<Box<LayoutNode>>,
-        ratios: Vec<f32>,
+        dimensions: Vec<Dimension>,
     },
     /// Vertical split (panes stacked)
     VerticalSplit {
         children: Vec<Box<LayoutNode>>,
-        ratios: Vec<f32>,
+        dimensions: Vec<Dimension>,
     },
 }
 
@@ -183,10 +341,27 @@ impl LayoutNode {
 
 /// Layout tree managing all panes
 pub struct LayoutTree {
-    /// Root node of the layout tree
+    /// Root node of the tiled layout tree
     root: LayoutNode,
-    /// Currently focused pane ID
+    /// Currently focused pane ID within the tiled tree
     focused_pane: Uuid,
+    /// Panes floating above the tiled tree, e.g. pop-up shells
+    floating: Vec<FloatingPane>,
+    /// ID of the floating pane with focus, if focus is currently on one
+    /// rather than on `focused_pane`
+    focused_floating: Option<Uuid>,
+    /// Counter handed out as each new floating pane's z-order index, so
+    /// later spawns always stack above earlier ones
+    next_floating_z: u32,
+    /// The tiled pane currently zoomed to full screen, if any
+    zoomed: Option<Uuid>,
+    /// Named swap layouts registered via `register_swap_layout`, in
+    /// registration order so `next_swap_layout`/`prev_swap_layout` have a
+    /// stable cycle
+    swap_layouts: Vec<(String, SwapLayoutTemplate)>,
+    /// Index into `swap_layouts` of the arrangement last applied via
+    /// `apply_swap_layout`/`next_swap_layout`/`prev_swap_layout`
+    active_swap_layout: Option<usize>,
 }
 
 impl LayoutTree {
@@ -195,32 +370,58 @@ impl LayoutTree {
         let focused_pane = initial_pane.id;
         let root = LayoutNode::Pane(initial_pane);
 
-        Self { root, focused_pane }
+        Self {
+            root,
+            focused_pane,
+            floating: Vec::new(),
+            focused_floating: None,
+            next_floating_z: 0,
+            zoomed: None,
+            swap_layouts: Vec::new(),
+            active_swap_layout: None,
+        }
     }
 
-    /// Get the focused pane ID
+    /// Get the focused pane ID (tiled or floating, whichever has focus)
     pub fn focused_pane_id(&self) -> Uuid {
-        self.focused_pane
+        self.focused_floating.unwrap_or(self.focused_pane)
     }
 
-    /// Set focus to a specific pane
+    /// Set focus to a specific pane, searching the tiled tree first and
+    /// then the floating set
     pub fn set_focus(&mut self, pane_id: Uuid) -> bool {
-        if self.root.find_pane(pane_id).is_some() {
+        let found = if self.root.find_pane(pane_id).is_some() {
             self.focused_pane = pane_id;
+            self.focused_floating = None;
+            true
+        } else if self.floating.iter().any(|f| f.pane.id == pane_id) {
+            self.focused_floating = Some(pane_id);
             true
         } else {
             false
+        };
+        if found {
+            self.clear_zoom();
         }
+        found
     }
 
-    /// Get the currently focused pane
+    /// Get the currently focused pane, whether it's in the tiled tree or
+    /// the floating set
     pub fn focused_pane(&self) -> Option<&Pane> {
-        self.root.find_pane(self.focused_pane)
+        match self.focused_floating {
+            Some(id) => self.floating.iter().find(|f| f.pane.id == id).map(|f| &f.pane),
+            None => self.root.find_pane(self.focused_pane),
+        }
     }
 
-    /// Get the currently focused pane (mutable)
+    /// Get the currently focused pane (mutable), whether it's in the
+    /// tiled tree or the floating set
     pub fn focused_pane_mut(&mut self) -> Option<&mut Pane> {
-        self.root.find_pane_mut(self.focused_pane)
+        match self.focused_floating {
+            Some(id) => self.floating.iter_mut().find(|f| f.pane.id == id).map(|f| &mut f.pane),
+            None => self.root.find_pane_mut(self.focused_pane),
+        }
     }
 
     /// Get a pane by ID
@@ -243,21 +444,44 @@ impl LayoutTree {
         self.root.collect_pane_ids()
     }
 
-    /// Split the focused pane in the given direction
+    /// Split the focused pane in the given direction, with an equal
+    /// `Percent` share for both the existing and new pane
     pub fn split_focused(&mut self, direction: SplitDirection, new_pane: Pane) -> Result<(), String> {
+        self.split_focused_with_dimension(direction, new_pane, Dimension::Percent(0.5))
+    }
+
+    /// Split the focused pane in the given direction, giving the new pane
+    /// an explicit `Dimension` instead of the default equal `Percent` share
+    ///
+    /// The existing pane gets the complementary share: `1.0 - p` when
+    /// `new_pane_dimension` is a `Percent`, or `Percent(1.0)` when it's
+    /// `Fixed`, so it simply fills whatever space the fixed sibling
+    /// doesn't claim.
+    pub fn split_focused_with_dimension(
+        &mut self,
+        direction: SplitDirection,
+        new_pane: Pane,
+        new_pane_dimension: Dimension,
+    ) -> Result<(), String> {
         if self.root.pane_count() >= 8 {
             return Err("Maximum pane limit (8) reached".to_string());
         }
 
         let focused_id = self.focused_pane;
         let new_pane_id = new_pane.id;
+        let existing_dimension = match new_pane_dimension {
+            Dimension::Percent(p) => Dimension::Percent((1.0 - p).max(0.0)),
+            Dimension::Fixed(_) => Dimension::Percent(1.0),
+        };
 
         // Recursively rebuild the tree with the split, avoiding placeholder extraction
         // This prevents spawning PTYs just for tree manipulation
-        match self.split_node_recursive(std::mem::take(&mut self.root), focused_id, direction, new_pane) {
+        let old_root = std::mem::replace(&mut self.root, LayoutNode::Pane(create_placeholder_pane()));
+        match self.split_node_recursive(old_root, focused_id, direction, new_pane, existing_dimension, new_pane_dimension) {
             Ok(new_root) => {
                 self.root = new_root;
                 self.focused_pane = new_pane_id;
+                self.clear_zoom();
                 Ok(())
             }
             Err((old_root, err)) => {
@@ -274,6 +498,8 @@ impl LayoutTree {
         target_id: Uuid,
         direction: SplitDirection,
         new_pane: Pane,
+        existing_dimension: Dimension,
+        new_dimension: Dimension,
     ) -> Result<LayoutNode, (LayoutNode, String)> {
         match node {
             LayoutNode::Pane(pane) if pane.id == target_id => {
@@ -284,155 +510,104 @@ impl LayoutTree {
                             Box::new(LayoutNode::Pane(pane)),
                             Box::new(LayoutNode::Pane(new_pane)),
                         ],
-                        ratios: vec![0.5, 0.5],
+                        dimensions: vec![existing_dimension, new_dimension],
                     },
                     SplitDirection::Vertical => LayoutNode::VerticalSplit {
                         children: vec![
                             Box::new(LayoutNode::Pane(pane)),
                             Box::new(LayoutNode::Pane(new_pane)),
                         ],
-                        ratios: vec![0.5, 0.5],
+                        dimensions: vec![existing_dimension, new_dimension],
                     },
                 })
             }
             LayoutNode::Pane(pane) => Ok(LayoutNode::Pane(pane)),
-            LayoutNode::HorizontalSplit { mut children, ratios } => {
+            LayoutNode::HorizontalSplit { mut children, dimensions } => {
                 // Recurse into children to find the target
                 for i in 0..children.len() {
                     if children[i].find_pane(target_id).is_some() {
                         let child = Box::new(std::mem::replace(&mut children[i], LayoutNode::Pane(create_placeholder_pane())));
-                        match self.split_node_recursive(*child, target_id, direction, new_pane) {
+                        match self.split_node_recursive(*child, target_id, direction, new_pane, existing_dimension, new_dimension) {
                             Ok(new_child) => {
                                 children[i] = Box::new(new_child);
-                                return Ok(LayoutNode::HorizontalSplit { children, ratios });
+                                return Ok(LayoutNode::HorizontalSplit { children, dimensions });
                             }
                             Err((old_child, err)) => {
                                 children[i] = Box::new(old_child);
-                                return Err((LayoutNode::HorizontalSplit { children, ratios }, err));
+                                return Err((LayoutNode::HorizontalSplit { children, dimensions }, err));
                             }
                         }
                     }
                 }
-                Ok(LayoutNode::HorizontalSplit { children, ratios })
+                Ok(LayoutNode::HorizontalSplit { children, dimensions })
             }
-            LayoutNode::VerticalSplit { mut children, ratios } => {
+            LayoutNode::VerticalSplit { mut children, dimensions } => {
                 // Recurse into children to find the target
                 for i in 0..children.len() {
                     if children[i].find_pane(target_id).is_some() {
                         let child = Box::new(std::mem::replace(&mut children[i], LayoutNode::Pane(create_placeholder_pane())));
-                        match self.split_node_recursive(*child, target_id, direction, new_pane) {
+                        match self.split_node_recursive(*child, target_id, direction, new_pane, existing_dimension, new_dimension) {
                             Ok(new_child) => {
                                 children[i] = Box::new(new_child);
-                                return Ok(LayoutNode::VerticalSplit { children, ratios });
+                                return Ok(LayoutNode::VerticalSplit { children, dimensions });
                             }
                             Err((old_child, err)) => {
                                 children[i] = Box::new(old_child);
-                                return Err((LayoutNode::VerticalSplit { children, ratios }, err));
+                                return Err((LayoutNode::VerticalSplit { children, dimensions }, err));
                             }
                         }
                     }
                 }
-                Ok(LayoutNode::VerticalSplit { children, ratios })
+                Ok(LayoutNode::VerticalSplit { children, dimensions })
             }
         }
     }
 
-    /// Try to split a node, returning the node on error
-    fn try_split_node(
-        &self,
-        node: LayoutNode,
-        target_id: Uuid,
-        direction: SplitDirection,
-        new_pane: Pane,
-    ) -> Result<LayoutNode, (LayoutNode, String)> {
-        match node {
-            LayoutNode::Pane(pane) if pane.id == target_id => {
-                // Found the pane to split
-                let old_pane = pane;
-                let split = match direction {
-                    SplitDirection::Horizontal => LayoutNode::HorizontalSplit {
-                        children: vec![
-                            Box::new(LayoutNode::Pane(old_pane)),
-                            Box::new(LayoutNode::Pane(new_pane)),
-                        ],
-                        ratios: vec![0.5, 0.5],
-                    },
-                    SplitDirection::Vertical => LayoutNode::VerticalSplit {
-                        children: vec![
-                            Box::new(LayoutNode::Pane(old_pane)),
-                            Box::new(LayoutNode::Pane(new_pane)),
-                        ],
-                        ratios: vec![0.5, 0.5],
-                    },
-                };
-                Ok(split)
-            }
-            LayoutNode::Pane(pane) => {
-                // Not the target pane, return unchanged
-                Ok(LayoutNode::Pane(pane))
-            }
-            LayoutNode::HorizontalSplit { mut children, ratios } => {
-                // Recurse into children
-                let mut found = false;
-                for child in &mut children {
-                    if child.find_pane(target_id).is_some() {
-                        // Take ownership of child
-                        let old_child = std::mem::replace(child, Box::new(LayoutNode::Pane(create_placeholder_pane())));
-                        match self.try_split_node(*old_child, target_id, direction, new_pane) {
-                            Ok(new_child) => {
-                                *child = Box::new(new_child);
-                                found = true;
-                                break;
-                            }
-                            Err((old_child, err)) => {
-                                *child = Box::new(old_child);
-                                return Err((LayoutNode::HorizontalSplit { children, ratios }, err));
-                            }
-                        }
-                    }
-                }
-                if found {
-                    Ok(LayoutNode::HorizontalSplit { children, ratios })
-                } else {
-                    Ok(LayoutNode::HorizontalSplit { children, ratios })
-                }
-            }
-            LayoutNode::VerticalSplit { mut children, ratios } => {
-                // Recurse into children
-                let mut found = false;
-                for child in &mut children {
-                    if child.find_pane(target_id).is_some() {
-                        // Take ownership of child
-                        let old_child = std::mem::replace(child, Box::new(LayoutNode::Pane(create_placeholder_pane())));
-                        match self.try_split_node(*old_child, target_id, direction, new_pane) {
-                            Ok(new_child) => {
-                                *child = Box::new(new_child);
-                                found = true;
-                                break;
-                            }
-                            Err((old_child, err)) => {
-                                *child = Box::new(old_child);
-                                return Err((LayoutNode::VerticalSplit { children, ratios }, err));
-                            }
-                        }
-                    }
-                }
-                if found {
-                    Ok(LayoutNode::VerticalSplit { children, ratios })
-                } else {
-                    Ok(LayoutNode::VerticalSplit { children, ratios })
-                }
+    /// Calculate layout bounds for all panes
+    ///
+    /// If a pane is zoomed, it alone is given the entire `total_bounds` and
+    /// every other pane keeps whatever bounds it was last assigned, so
+    /// rendering can draw just the zoomed pane at full size without
+    /// disturbing the split arrangement underneath it.
+    pub fn calculate_layout(&mut self, total_bounds: Rect) {
+        if let Some(zoomed_id) = self.zoomed {
+            if let Some(pane) = self.root.find_pane_mut(zoomed_id) {
+                pane.bounds = total_bounds;
+                return;
             }
+            // The zoomed pane no longer exists (e.g. it was closed) - fall
+            // back to a normal layout pass instead of staying stuck.
+            self.zoomed = None;
         }
-    }
 
-    /// Calculate layout bounds for all panes
-    pub fn calculate_layout(&mut self, total_bounds: Rect) {
         let root = std::mem::replace(&mut self.root, LayoutNode::Pane(create_placeholder_pane()));
         let new_root = self.calculate_node_layout_owned(root, total_bounds);
         self.root = new_root;
     }
 
+    /// Toggle full-screen zoom on the focused tiled pane
+    ///
+    /// While zoomed, `calculate_layout` gives the zoomed pane the entire
+    /// bounds and leaves the rest of the tree's bounds untouched. Toggling
+    /// again (or any focus change/split) restores the normal layout.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = match self.zoomed {
+            Some(_) => None,
+            None => Some(self.focused_pane),
+        };
+    }
+
+    /// Whether a pane is currently zoomed, and if so which one
+    pub fn zoomed_pane_id(&self) -> Option<Uuid> {
+        self.zoomed
+    }
+
+    /// Clear zoom state; called whenever focus changes or the tree is
+    /// split, so zoom never outlives the arrangement it zoomed into
+    fn clear_zoom(&mut self) {
+        self.zoomed = None;
+    }
+
     /// Recursively calculate bounds for a node (takes ownership to avoid borrow issues)
     fn calculate_node_layout_owned(&self, node: LayoutNode, bounds: Rect) -> LayoutNode {
         match node {
@@ -440,13 +615,11 @@ impl LayoutTree {
                 pane.bounds = bounds;
                 LayoutNode::Pane(pane)
             }
-            LayoutNode::HorizontalSplit { children, ratios } => {
-                let children_len = children.len();
+            LayoutNode::HorizontalSplit { children, dimensions } => {
+                let sizes = allocate_extent(&dimensions, bounds.width);
                 let mut x = bounds.x;
-                let mut new_children = Vec::with_capacity(children_len);
-                for (i, child) in children.into_iter().enumerate() {
-                    let ratio = ratios.get(i).copied().unwrap_or(1.0 / children_len as f32);
-                    let width = (bounds.width as f32 * ratio) as u32;
+                let mut new_children = Vec::with_capacity(children.len());
+                for (child, width) in children.into_iter().zip(sizes) {
                     let child_bounds = Rect::new(x, bounds.y, width, bounds.height);
                     let new_child = self.calculate_node_layout_owned(*child, child_bounds);
                     new_children.push(Box::new(new_child));
@@ -454,16 +627,14 @@ impl LayoutTree {
                 }
                 LayoutNode::HorizontalSplit {
                     children: new_children,
-                    ratios,
+                    dimensions,
                 }
             }
-            LayoutNode::VerticalSplit { children, ratios } => {
-                let children_len = children.len();
+            LayoutNode::VerticalSplit { children, dimensions } => {
+                let sizes = allocate_extent(&dimensions, bounds.height);
                 let mut y = bounds.y;
-                let mut new_children = Vec::with_capacity(children_len);
-                for (i, child) in children.into_iter().enumerate() {
-                    let ratio = ratios.get(i).copied().unwrap_or(1.0 / children_len as f32);
-                    let height = (bounds.height as f32 * ratio) as u32;
+                let mut new_children = Vec::with_capacity(children.len());
+                for (child, height) in children.into_iter().zip(sizes) {
                     let child_bounds = Rect::new(bounds.x, y, bounds.width, height);
                     let new_child = self.calculate_node_layout_owned(*child, child_bounds);
                     new_children.push(Box::new(new_child));
@@ -471,7 +642,7 @@ impl LayoutTree {
                 }
                 LayoutNode::VerticalSplit {
                     children: new_children,
-                    ratios,
+                    dimensions,
                 }
             }
         }
@@ -487,6 +658,8 @@ impl LayoutTree {
         if let Some(current_idx) = pane_ids.iter().position(|&id| id == self.focused_pane) {
             let next_idx = (current_idx + 1) % pane_ids.len();
             self.focused_pane = pane_ids[next_idx];
+            self.focused_floating = None;
+            self.clear_zoom();
         }
     }
 
@@ -504,6 +677,107 @@ impl LayoutTree {
                 current_idx - 1
             };
             self.focused_pane = pane_ids[prev_idx];
+            self.focused_floating = None;
+            self.clear_zoom();
+        }
+    }
+
+    /// Move focus to the nearest pane in the given geometric direction,
+    /// based on the `bounds` computed by `calculate_layout`
+    ///
+    /// The candidate must lie entirely on the correct side of the focused
+    /// pane's edge and share some overlap along the perpendicular axis.
+    /// Among candidates, the closest edge wins; ties are broken by the
+    /// greatest overlap along the perpendicular axis. Returns `true` if
+    /// focus moved, `false` if there was no suitable candidate.
+    pub fn focus_direction(&mut self, dir: Direction) -> bool {
+        let Some(focused) = self.focused_pane() else {
+            return false;
+        };
+        let focused_bounds = focused.bounds;
+        let focused_id = self.focused_pane;
+
+        // (pane id, distance to the focused pane's edge, perpendicular overlap)
+        let mut best: Option<(Uuid, u32, u32)> = None;
+
+        for id in self.all_pane_ids() {
+            if id == focused_id {
+                continue;
+            }
+            let Some(bounds) = self.get_pane(id).map(|p| p.bounds) else {
+                continue;
+            };
+
+            let candidate = match dir {
+                Direction::Left => {
+                    let right_edge = bounds.x + bounds.width;
+                    let overlap = overlap_1d(
+                        focused_bounds.y,
+                        focused_bounds.y + focused_bounds.height,
+                        bounds.y,
+                        bounds.y + bounds.height,
+                    );
+                    (right_edge <= focused_bounds.x && overlap > 0)
+                        .then(|| (focused_bounds.x - right_edge, overlap))
+                }
+                Direction::Right => {
+                    let focused_right_edge = focused_bounds.x + focused_bounds.width;
+                    let overlap = overlap_1d(
+                        focused_bounds.y,
+                        focused_bounds.y + focused_bounds.height,
+                        bounds.y,
+                        bounds.y + bounds.height,
+                    );
+                    (bounds.x >= focused_right_edge && overlap > 0)
+                        .then(|| (bounds.x - focused_right_edge, overlap))
+                }
+                Direction::Up => {
+                    let bottom_edge = bounds.y + bounds.height;
+                    let overlap = overlap_1d(
+                        focused_bounds.x,
+                        focused_bounds.x + focused_bounds.width,
+                        bounds.x,
+                        bounds.x + bounds.width,
+                    );
+                    (bottom_edge <= focused_bounds.y && overlap > 0)
+                        .then(|| (focused_bounds.y - bottom_edge, overlap))
+                }
+                Direction::Down => {
+                    let focused_bottom_edge = focused_bounds.y + focused_bounds.height;
+                    let overlap = overlap_1d(
+                        focused_bounds.x,
+                        focused_bounds.x + focused_bounds.width,
+                        bounds.x,
+                        bounds.x + bounds.width,
+                    );
+                    (bounds.y >= focused_bottom_edge && overlap > 0)
+                        .then(|| (bounds.y - focused_bottom_edge, overlap))
+                }
+            };
+
+            let Some((distance, overlap)) = candidate else {
+                continue;
+            };
+
+            let is_closer = match best {
+                None => true,
+                Some((_, best_distance, best_overlap)) => {
+                    distance < best_distance || (distance == best_distance && overlap > best_overlap)
+                }
+            };
+            if is_closer {
+                best = Some((id, distance, overlap));
+            }
+        }
+
+        match best {
+            Some((id, _, _)) => {
+                self.focused_pane = id;
+                self.focused_floating = None;
+                self.clear_zoom();
+                true
+            }
+            None => false,
         }
     }
 
@@ -517,10 +791,185 @@ impl LayoutTree {
         &mut self.root
     }
 
+    /// Get the floating panes, topmost-last, for rendering above the tiled tree
+    pub fn floating(&self) -> &[FloatingPane] {
+        &self.floating
+    }
+
+    /// Spawn a floating pane with explicit geometry, giving it focus and
+    /// placing it above all existing floating panes
+    pub fn spawn_floating(&mut self, pane: Pane, rect: Rect) -> Uuid {
+        let id = pane.id;
+        let z_index = self.next_floating_z;
+        self.next_floating_z += 1;
+
+        self.floating.push(FloatingPane { pane, rect, z_index });
+        self.focused_floating = Some(id);
+        id
+    }
+
+    /// The floating pane with the highest z-order index, if any
+    fn topmost_floating(&self) -> Option<&FloatingPane> {
+        self.floating.iter().max_by_key(|f| f.z_index)
+    }
+
+    /// Move focus between the tiled tree and the topmost floating pane
+    ///
+    /// Returns `true` if focus moved. Moving away from the tiled tree always
+    /// succeeds if a floating pane exists; moving away from a floating pane
+    /// back to the tiled tree always succeeds.
+    pub fn toggle_floating_focus(&mut self) -> bool {
+        let moved = if self.focused_floating.is_some() {
+            self.focused_floating = None;
+            true
+        } else if let Some(top) = self.topmost_floating() {
+            self.focused_floating = Some(top.pane.id);
+            true
+        } else {
+            false
+        };
+        if moved {
+            self.clear_zoom();
+        }
+        moved
+    }
+
+    /// Move the currently focused floating pane by `(dx, dy)`, clamping so
+    /// it stays fully within `terminal_bounds`
+    pub fn move_floating(&mut self, dx: i32, dy: i32, terminal_bounds: Rect) -> Result<(), String> {
+        let floating = self.focused_floating_mut()?;
+        floating.rect = clamp_floating_position(floating.rect, dx, dy, terminal_bounds);
+        Ok(())
+    }
+
+    /// Resize the currently focused floating pane by `(dw, dh)`, clamping
+    /// so it never shrinks below a minimum size or grows outside
+    /// `terminal_bounds`
+    pub fn resize_floating(&mut self, dw: i32, dh: i32, terminal_bounds: Rect) -> Result<(), String> {
+        let floating = self.focused_floating_mut()?;
+        floating.rect = clamp_floating_size(floating.rect, dw, dh, terminal_bounds);
+        Ok(())
+    }
+
+    /// The `FloatingPane` entry that currently has focus, if any
+    fn focused_floating_mut(&mut self) -> Result<&mut FloatingPane, String> {
+        let id = self
+            .focused_floating
+            .ok_or_else(|| "no floating pane is focused".to_string())?;
+        self.floating
+            .iter_mut()
+            .find(|f| f.pane.id == id)
+            .ok_or_else(|| "focused floating pane not found".to_string())
+    }
+
+    /// Lift the focused tiled pane out of the split tree and into the
+    /// floating set at `rect`, giving it focus there
+    ///
+    /// Mirrors `close_focused`'s "last pane" guard, since floating away the
+    /// tree's only pane would leave the tiled tree empty.
+    pub fn float_focused(&mut self, rect: Rect) -> Result<(), String> {
+        if self.focused_floating.is_some() {
+            return Err("a floating pane already has focus".to_string());
+        }
+        if self.pane_count() <= 1 {
+            return Err("Cannot float the last tiled pane".to_string());
+        }
+
+        let focused_id = self.focused_pane;
+        let old_root = std::mem::replace(&mut self.root, LayoutNode::Pane(create_placeholder_pane()));
+        let (new_root, extracted) = extract_pane_from_node(old_root, focused_id);
+        self.root = new_root;
+
+        let pane = match extracted {
+            Some(pane) => pane,
+            None => return Err("focused pane not found".to_string()),
+        };
+
+        let pane_ids = self.all_pane_ids();
+        if !pane_ids.is_empty() {
+            self.focused_pane = pane_ids[0];
+        }
+
+        self.spawn_floating(pane, rect);
+        self.clear_zoom();
+        Ok(())
+    }
+
+    /// Move the focused floating pane back into the tiled tree, splitting
+    /// it off the currently focused tiled pane in `direction` - the
+    /// inverse of `float_focused`
+    pub fn unfloat_focused(&mut self, direction: SplitDirection) -> Result<(), String> {
+        if self.focused_floating.is_none() {
+            return Err("no floating pane is focused".to_string());
+        }
+        if self.root.pane_count() >= 8 {
+            return Err("Maximum pane limit (8) reached".to_string());
+        }
+
+        let floating_id = self.focused_floating.unwrap();
+        let idx = self
+            .floating
+            .iter()
+            .position(|f| f.pane.id == floating_id)
+            .ok_or_else(|| "focused floating pane not found".to_string())?;
+        let floating = self.floating.remove(idx);
+
+        self.focused_floating = self.topmost_floating().map(|f| f.pane.id);
+        self.split_focused(direction, floating.pane)
+    }
+
+    /// Raise the focused floating pane above every other floating pane
+    pub fn raise_focused_floating(&mut self) -> Result<(), String> {
+        let id = self
+            .focused_floating
+            .ok_or_else(|| "no floating pane is focused".to_string())?;
+        self.floating.sort_by_key(|f| f.z_index);
+        if let Some(pos) = self.floating.iter().position(|f| f.pane.id == id) {
+            let floating = self.floating.remove(pos);
+            self.floating.push(floating);
+        }
+        self.renumber_floating_z();
+        Ok(())
+    }
+
+    /// Lower the focused floating pane beneath every other floating pane
+    pub fn lower_focused_floating(&mut self) -> Result<(), String> {
+        let id = self
+            .focused_floating
+            .ok_or_else(|| "no floating pane is focused".to_string())?;
+        self.floating.sort_by_key(|f| f.z_index);
+        if let Some(pos) = self.floating.iter().position(|f| f.pane.id == id) {
+            let floating = self.floating.remove(pos);
+            self.floating.insert(0, floating);
+        }
+        self.renumber_floating_z();
+        Ok(())
+    }
+
+    /// Reassign every floating pane's `z_index` to match its position in
+    /// `self.floating`, so `raise_focused_floating`/`lower_focused_floating`
+    /// never need to worry about overflow or tie-breaking
+    fn renumber_floating_z(&mut self) {
+        for (i, floating) in self.floating.iter_mut().enumerate() {
+            floating.z_index = i as u32;
+        }
+        self.next_floating_z = self.floating.len() as u32;
+    }
+
     /// Close the focused pane
     ///
-    /// Returns Ok(()) if pane was closed, Err if this is the last pane or pane not found
+    /// If a floating pane has focus, it is always closed, and focus moves to
+    /// the next-topmost floating pane or back to the tiled tree if none
+    /// remain. Otherwise closes the focused tiled pane.
+    ///
+    /// Returns Ok(()) if pane was closed, Err if this is the last tiled pane or pane not found
     pub fn close_focused(&mut self) -> Result<(), String> {
+        if let Some(floating_id) = self.focused_floating {
+            self.floating.retain(|f| f.pane.id != floating_id);
+            self.focused_floating = self.topmost_floating().map(|f| f.pane.id);
+            return Ok(());
+        }
+
         let pane_count = self.pane_count();
         if pane_count <= 1 {
             return Err("Cannot close the last pane".to_string());
@@ -540,6 +989,7 @@ impl LayoutTree {
                 if !pane_ids.is_empty() {
                     self.focused_pane = pane_ids[0];
                 }
+                self.clear_zoom();
 
                 Ok(())
             }
@@ -549,19 +999,61 @@ impl LayoutTree {
                 if !pane_ids.is_empty() {
                     self.focused_pane = pane_ids[0];
                 }
+                self.clear_zoom();
                 Err(e)
             }
         }
     }
 
+    /// Locate the split divider nearest `(x, y)`, if any lies within
+    /// `tolerance` pixels of it - used by `handle_mouse_button` to tell a
+    /// divider-drag press apart from a selection press.
+    ///
+    /// Recomputes bounds the same way `calculate_layout` does rather than
+    /// trusting `Pane::bounds`, so a divider can still be found mid-drag
+    /// before the next full layout pass has run. Returns `None` while a
+    /// pane is zoomed, since there's nothing to divide.
+    pub fn find_divider(&self, x: u32, y: u32, tolerance: u32, total_bounds: Rect) -> Option<DividerHit> {
+        if self.zoomed.is_some() {
+            return None;
+        }
+        let mut path = Vec::new();
+        find_divider_in_node(&self.root, total_bounds, tolerance, x, y, &mut path)
+    }
+
+    /// Nudge the ratio at `hit` by a pixel delta along its split's primary
+    /// axis, converting it to a `Dimension::Percent` delta via the extent
+    /// captured when the divider was found. Returns `true` if the ratio
+    /// actually changed.
+    ///
+    /// Callers (`handle_mouse_motion`) still need to run `calculate_layout`
+    /// afterward to turn the updated ratio into new pane bounds.
+    pub fn drag_divider(&mut self, hit: &DividerHit, delta_px: i32) -> bool {
+        if hit.extent == 0 {
+            return false;
+        }
+        let delta = delta_px as f32 / hit.extent as f32;
+        let root = std::mem::replace(&mut self.root, LayoutNode::Pane(create_placeholder_pane()));
+        let (new_root, changed) = apply_divider_delta(root, &hit.path, hit.index, delta);
+        self.root = new_root;
+        changed
+    }
+
     /// Resize the focused pane
     ///
+    /// If the split immediately enclosing the focused pane doesn't run in
+    /// `direction` (or is already saturated, e.g. a `Fixed` neighbor), this
+    /// walks up the ancestor chain looking for the nearest enclosing split
+    /// that does and applies the delta there instead, "reducing" into
+    /// whichever neighbor still has room - see `resize_pane_in_node`.
+    ///
     /// # Arguments
     /// * `direction` - Direction to resize (Horizontal for left/right, Vertical for up/down)
     /// * `delta` - Amount to resize (positive to grow, negative to shrink)
     ///
     /// # Returns
-    /// Ok(()) if resize was successful, Err if resize not possible
+    /// Ok(()) if some split actually changed size, Err if the resize had no
+    /// effect anywhere along the pane's ancestor chain.
     pub fn resize_focused(&mut self, direction: SplitDirection, delta: f32) -> Result<(), String> {
         let focused_id = self.focused_pane;
 
@@ -569,512 +1061,2339 @@ impl LayoutTree {
         let old_root = std::mem::replace(&mut self.root, LayoutNode::Pane(create_placeholder_pane()));
 
         match resize_pane_in_node(old_root, focused_id, direction, delta) {
-            Ok(new_root) => {
+            Ok((new_root, true)) => {
                 self.root = new_root;
                 Ok(())
             }
+            Ok((new_root, false)) => {
+                self.root = new_root;
+                Err("Resize had no effect: no enclosing split could absorb the delta".to_string())
+            }
             Err((old_root, err)) => {
                 self.root = old_root;
                 Err(err)
             }
         }
     }
-}
 
-/// Recursively close a pane in a node (standalone function to avoid borrow issues)
-fn close_pane_in_node(node: LayoutNode, pane_id: Uuid) -> Result<LayoutNode, String> {
-    match node {
-        LayoutNode::Pane(pane) if pane.id == pane_id => {
-            // This pane should be removed - caller will handle it
-            Err(format!("Pane {} found", pane_id))
+    /// Serialize this tree to the nested block layout format, e.g.
+    /// `horizontal { pane size=50% { title="sh" cwd="/" cmd="/bin/sh" } vertical size=50% { pane pane } }`
+    pub fn to_layout_string(&self) -> String {
+        format_node(&self.root, None)
+    }
+
+    /// Rebuild a `LayoutTree` from the nested block layout format
+    ///
+    /// Since PTYs aren't serializable, `spawn` is called once per `pane`
+    /// leaf with the metadata recorded for it (title/cwd/cmd) so the
+    /// caller can start a fresh `PtySession`.
+    pub fn from_layout_string(
+        s: &str,
+        mut spawn: impl FnMut(PaneSpec) -> PtySession,
+    ) -> Result<LayoutTree, String> {
+        let tokens = tokenize(s)?;
+        let mut stream = TokenStream::new(&tokens);
+        let mut pane_count = 0usize;
+
+        let (root, _) = parse_node(&mut stream, &mut spawn, &mut pane_count)?;
+
+        if let Some(trailing) = stream.peek() {
+            return Err(format!("unexpected trailing content starting at '{}'", trailing));
         }
-        LayoutNode::Pane(pane) => {
-            // Not the target pane
-            Ok(LayoutNode::Pane(pane))
+
+        let focused_pane = root
+            .collect_pane_ids()
+            .first()
+            .copied()
+            .ok_or_else(|| "layout must contain at least one pane".to_string())?;
+
+        Ok(LayoutTree {
+            root,
+            focused_pane,
+            floating: Vec::new(),
+            focused_floating: None,
+            next_floating_z: 0,
+            zoomed: None,
+            swap_layouts: Vec::new(),
+            active_swap_layout: None,
+        })
+    }
+
+    /// Build a `LayoutTree` from a declarative `LayoutTemplate` - e.g. a
+    /// named layout loaded from the config file - spawning each leaf's PTY
+    /// via `spawn`
+    ///
+    /// Mirrors `from_layout_string`'s spawn-on-demand approach and enforces
+    /// the same 8-pane maximum. A split child that omits `size` defaults to
+    /// an equal share, same as an un-sized `pane`/`horizontal`/`vertical` in
+    /// the text layout format.
+    pub fn from_template(
+        template: &LayoutTemplate,
+        mut spawn: impl FnMut(PaneSpec) -> PtySession,
+    ) -> Result<LayoutTree, String> {
+        let mut pane_count = 0usize;
+        let (root, _) = build_template_node(template, &mut spawn, &mut pane_count)?;
+
+        let focused_pane = root
+            .collect_pane_ids()
+            .first()
+            .copied()
+            .ok_or_else(|| "layout must contain at least one pane".to_string())?;
+
+        Ok(LayoutTree {
+            root,
+            focused_pane,
+            floating: Vec::new(),
+            focused_floating: None,
+            next_floating_z: 0,
+            zoomed: None,
+            swap_layouts: Vec::new(),
+            active_swap_layout: None,
+        })
+    }
+
+    /// Register a named swap layout so it can be applied later by name via
+    /// `apply_swap_layout`, or cycled through with
+    /// `next_swap_layout`/`prev_swap_layout`
+    ///
+    /// Re-registering an existing `name` replaces it in place, keeping its
+    /// position in the cycle order.
+    pub fn register_swap_layout(&mut self, name: impl Into<String>, template: SwapLayoutTemplate) {
+        let name = name.into();
+        if let Some(entry) = self.swap_layouts.iter_mut().find(|(n, _)| *n == name) {
+            entry.1 = template;
+        } else {
+            self.swap_layouts.push((name, template));
         }
-        LayoutNode::HorizontalSplit { mut children, mut ratios } => {
-            // Try to remove the pane from children
-            let mut found_idx = None;
-            for (i, child) in children.iter().enumerate() {
-                if child.find_pane(pane_id).is_some() {
-                    found_idx = Some(i);
-                    break;
-                }
-            }
+    }
 
-            if let Some(idx) = found_idx {
-                // Remove the child
-                children.remove(idx);
-                ratios.remove(idx);
+    /// Reflow the tree's existing live panes into the named, registered
+    /// swap layout, Zellij-style (e.g. "main-vertical", "even-horizontal",
+    /// "tiled") - without spawning any new PTYs
+    pub fn apply_swap_layout(&mut self, name: &str) -> Result<(), String> {
+        let index = self
+            .swap_layouts
+            .iter()
+            .position(|(n, _)| n == name)
+            .ok_or_else(|| format!("no swap layout registered named '{}'", name))?;
+
+        self.apply_swap_layout_at(index)?;
+        self.active_swap_layout = Some(index);
+        Ok(())
+    }
 
-                // Normalize ratios
-                let total: f32 = ratios.iter().sum();
-                if total > 0.0 {
-                    for ratio in &mut ratios {
-                        *ratio /= total;
-                    }
-                }
+    /// Apply whichever registered swap layout comes after the last one
+    /// applied (or the first one, if none has been applied yet), wrapping
+    /// around at the end
+    pub fn next_swap_layout(&mut self) -> Result<(), String> {
+        self.cycle_swap_layout(1)
+    }
 
-                // If only one child left, collapse the split
-                if children.len() == 1 {
-                    return Ok(*children.remove(0));
-                }
+    /// Apply whichever registered swap layout comes before the last one
+    /// applied (or the last one, if none has been applied yet), wrapping
+    /// around at the start
+    pub fn prev_swap_layout(&mut self) -> Result<(), String> {
+        self.cycle_swap_layout(-1)
+    }
 
-                Ok(LayoutNode::HorizontalSplit { children, ratios })
-            } else {
-                Ok(LayoutNode::HorizontalSplit { children, ratios })
-            }
+    fn cycle_swap_layout(&mut self, step: i32) -> Result<(), String> {
+        if self.swap_layouts.is_empty() {
+            return Err("no swap layouts registered".to_string());
         }
-        LayoutNode::VerticalSplit { mut children, mut ratios } => {
-            // Try to remove the pane from children
-            let mut found_idx = None;
-            for (i, child) in children.iter().enumerate() {
-                if child.find_pane(pane_id).is_some() {
-                    found_idx = Some(i);
-                    break;
-                }
-            }
 
-            if let Some(idx) = found_idx {
-                // Remove the child
-                children.remove(idx);
-                ratios.remove(idx);
+        let len = self.swap_layouts.len() as i32;
+        let current = self.active_swap_layout.map(|i| i as i32).unwrap_or(-1);
+        let next = (current + step).rem_euclid(len) as usize;
 
-                // Normalize ratios
-                let total: f32 = ratios.iter().sum();
-                if total > 0.0 {
-                    for ratio in &mut ratios {
-                        *ratio /= total;
-                    }
-                }
+        self.apply_swap_layout_at(next)?;
+        self.active_swap_layout = Some(next);
+        Ok(())
+    }
 
-                // If only one child left, collapse the split
-                if children.len() == 1 {
-                    return Ok(*children.remove(0));
-                }
+    /// Pick the arrangement `self.swap_layouts[index]` defines for the
+    /// tree's current pane count, then re-home the real `Pane` values into
+    /// the new leaf positions in the same left-to-right order
+    /// `collect_pane_ids` already returns them in - the same
+    /// ownership-transfer approach `split_node_recursive` uses to avoid
+    /// placeholder PTY spawns.
+    ///
+    /// A leaf slot left over once every live pane has been placed gets a
+    /// placeholder pane (via `create_placeholder_pane`) rather than failing
+    /// the whole reflow; there being *fewer* slots than live panes is still
+    /// an error, since that would mean losing a pane. The previously
+    /// focused pane keeps focus if it's still present.
+    fn apply_swap_layout_at(&mut self, index: usize) -> Result<(), String> {
+        let pane_ids = self.root.collect_pane_ids();
+        let node = self.swap_layouts[index]
+            .1
+            .arrangement_for(pane_ids.len())
+            .ok_or_else(|| format!("no swap layout defined for {} panes", pane_ids.len()))?
+            .clone();
+
+        let leaf_count = node.leaf_count();
+        if leaf_count < pane_ids.len() {
+            return Err(format!(
+                "swap layout for {} panes has only {} leaf slots",
+                pane_ids.len(),
+                leaf_count
+            ));
+        }
 
-                Ok(LayoutNode::VerticalSplit { children, ratios })
-            } else {
-                Ok(LayoutNode::VerticalSplit { children, ratios })
+        let focused_id = self.focused_pane;
+        let old_root = std::mem::replace(&mut self.root, LayoutNode::Pane(create_placeholder_pane()));
+
+        let mut panes = Vec::with_capacity(pane_ids.len());
+        take_all_panes(old_root, &mut panes);
+        let mut panes = panes.into_iter();
+
+        let (new_root, _) = build_swap_node(&node, &mut panes)?;
+        self.root = new_root;
+        self.clear_zoom();
+        if self.root.find_pane(focused_id).is_some() {
+            self.focused_pane = focused_id;
+        }
+
+        Ok(())
+    }
+
+    /// Snapshot this tree to an indented, human-readable manifest - nested
+    /// `horizontal`/`vertical` blocks recording each pane's split ratio,
+    /// title, and working directory - plus which pane currently has focus,
+    /// so it can be handed to `from_manifest` later (e.g. a config file's
+    /// "restore last session" startup layout)
+    ///
+    /// Reuses `LayoutTemplate` as the intermediate node shape, since a
+    /// manifest is exactly a template captured from a live tree rather than
+    /// hand-written.
+    pub fn serialize(&self) -> String {
+        let pane_ids = self.root.collect_pane_ids();
+        let focused_leaf = pane_ids
+            .iter()
+            .position(|id| *id == self.focused_pane)
+            .unwrap_or(0);
+
+        let manifest = LayoutManifest {
+            root: node_to_template(&self.root, None),
+            focused_leaf,
+        };
+
+        serde_json::to_string_pretty(&manifest).expect("LayoutManifest always serializes")
+    }
+
+    /// Rebuild a `LayoutTree` from a manifest produced by `serialize`
+    ///
+    /// Re-spawns a fresh `PtySession` per leaf pane via `spawn` using its
+    /// recorded title/cwd/command (delegating to `from_template`), then
+    /// restores focus to whichever leaf position was focused when the
+    /// manifest was captured via `set_focus`.
+    pub fn from_manifest(
+        s: &str,
+        spawn: impl FnMut(PaneSpec) -> PtySession,
+    ) -> Result<LayoutTree, String> {
+        let manifest: LayoutManifest =
+            serde_json::from_str(s).map_err(|e| format!("invalid layout manifest: {}", e))?;
+
+        let mut tree = Self::from_template(&manifest.root, spawn)?;
+
+        let pane_ids = tree.root.collect_pane_ids();
+        if let Some(&focused_id) = pane_ids.get(manifest.focused_leaf) {
+            tree.set_focus(focused_id);
+        }
+
+        Ok(tree)
+    }
+}
+
+/// Metadata needed to spawn a pane's `PtySession` when rebuilding a layout
+/// tree from text via `LayoutTree::from_layout_string`
+#[derive(Debug, Clone, Default)]
+pub struct PaneSpec {
+    pub title: Option<String>,
+    pub working_dir: Option<String>,
+    pub command: Option<String>,
+}
+
+/// A named, declarative startup layout, typically loaded from the config
+/// file so a session can open with more than a single bare pane
+///
+/// Unlike the runtime `to_layout_string`/`from_layout_string` round-trip,
+/// templates are meant to be hand-written: splits nest further templates,
+/// and leaves carry the metadata passed to the caller's PTY-spawn closure
+/// via `LayoutTree::from_template`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LayoutTemplate {
+    /// A leaf pane
+    Pane {
+        #[serde(default)]
+        title: Option<String>,
+        /// Working directory the leaf's command/shell should start in
+        #[serde(default)]
+        cwd: Option<String>,
+        /// Command to run in the pane instead of the default shell
+        #[serde(default)]
+        command: Option<String>,
+        /// Share of the parent split's extent; omitting this defaults to
+        /// an equal share among siblings that also omit `size`
+        #[serde(default)]
+        size: Option<Dimension>,
+    },
+    /// Panes side by side
+    Horizontal {
+        children: Vec<LayoutTemplate>,
+        #[serde(default)]
+        size: Option<Dimension>,
+    },
+    /// Panes stacked
+    Vertical {
+        children: Vec<LayoutTemplate>,
+        #[serde(default)]
+        size: Option<Dimension>,
+    },
+}
+
+/// Recursively build a `LayoutNode` from a `LayoutTemplate`, spawning each
+/// leaf's PTY via `spawn` and enforcing the same 8-pane maximum as
+/// `split_focused`/`from_layout_string`. Returns the node alongside the
+/// `Dimension` its own `size` declared, defaulting to an equal `Percent`
+/// share if omitted.
+fn build_template_node(
+    template: &LayoutTemplate,
+    spawn: &mut impl FnMut(PaneSpec) -> PtySession,
+    pane_count: &mut usize,
+) -> Result<(LayoutNode, Dimension), String> {
+    match template {
+        LayoutTemplate::Pane { title, cwd, command, size } => {
+            *pane_count += 1;
+            if *pane_count > 8 {
+                return Err("Maximum pane limit (8) reached while building layout".to_string());
             }
+
+            let spec = PaneSpec {
+                title: title.clone(),
+                working_dir: cwd.clone(),
+                command: command.clone(),
+            };
+            let title = spec.title.clone().unwrap_or_else(|| "Terminal".to_string());
+            let pty = spawn(spec);
+            let mut pane = Pane::new(pty, 80, 24, Rect::new(0, 0, 80, 24));
+            pane.title = title;
+
+            Ok((LayoutNode::Pane(pane), size.unwrap_or(Dimension::Percent(1.0))))
+        }
+        LayoutTemplate::Horizontal { children, size } => {
+            let (children, dimensions) = build_template_children(children, spawn, pane_count)?;
+            Ok((
+                LayoutNode::HorizontalSplit { children, dimensions },
+                size.unwrap_or(Dimension::Percent(1.0)),
+            ))
+        }
+        LayoutTemplate::Vertical { children, size } => {
+            let (children, dimensions) = build_template_children(children, spawn, pane_count)?;
+            Ok((
+                LayoutNode::VerticalSplit { children, dimensions },
+                size.unwrap_or(Dimension::Percent(1.0)),
+            ))
         }
     }
 }
 
-/// Recursively resize a pane in a node (standalone function to avoid borrow issues)
-fn resize_pane_in_node(
-    node: LayoutNode,
-    pane_id: Uuid,
-    direction: SplitDirection,
-    delta: f32,
-) -> Result<LayoutNode, (LayoutNode, String)> {
+/// Build every child of a split template, collecting their nodes and
+/// declared (or defaulted) dimensions in order
+fn build_template_children(
+    templates: &[LayoutTemplate],
+    spawn: &mut impl FnMut(PaneSpec) -> PtySession,
+    pane_count: &mut usize,
+) -> Result<(Vec<Box<LayoutNode>>, Vec<Dimension>), String> {
+    if templates.is_empty() {
+        return Err("a split template must have at least one child".to_string());
+    }
+
+    let mut children = Vec::with_capacity(templates.len());
+    let mut dimensions = Vec::with_capacity(templates.len());
+    for template in templates {
+        let (node, dimension) = build_template_node(template, spawn, pane_count)?;
+        children.push(Box::new(node));
+        dimensions.push(dimension);
+    }
+    Ok((children, dimensions))
+}
+
+/// A point-in-time snapshot of a `LayoutTree`, produced by
+/// `LayoutTree::serialize` and consumed by `LayoutTree::from_manifest`
+///
+/// `root` is exactly the shape a `LayoutTemplate` already describes -
+/// splits, ratios, and per-pane title/cwd/command - so rebuilding a
+/// manifest is just `from_template` plus restoring focus. `focused_leaf` is
+/// a position (the index `collect_pane_ids` would assign it), not a
+/// `Uuid`, since the pane's id is re-generated on every respawn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutManifest {
+    pub root: LayoutTemplate,
+    pub focused_leaf: usize,
+}
+
+/// Capture a live `LayoutNode` as a `LayoutTemplate`, recording each pane's
+/// title, working directory, and command alongside the split ratio it was
+/// given by its parent (`size`, `None` for the root)
+fn node_to_template(node: &LayoutNode, size: Option<Dimension>) -> LayoutTemplate {
     match node {
         LayoutNode::Pane(pane) => {
-            // Single pane cannot be resized
-            Err((LayoutNode::Pane(pane), "Cannot resize: no adjacent pane".to_string()))
+            let (cwd, command) = match pane.pty.lock().as_ref() {
+                Some(session) => (
+                    session.working_dir().map(|s| s.to_string()),
+                    Some(session.shell().to_string()),
+                ),
+                None => (None, None),
+            };
+            LayoutTemplate::Pane {
+                title: Some(pane.title.clone()),
+                cwd,
+                command,
+                size,
+            }
         }
-        LayoutNode::HorizontalSplit { mut children, mut ratios } => {
-            // Only resize if direction matches
-            if direction == SplitDirection::Horizontal {
-                // Find which child contains the pane
-                for i in 0..children.len() {
-                    if children[i].find_pane(pane_id).is_some() {
-                        // Found the pane, adjust ratios
-                        // If pane is in child i, we adjust ratios[i] and ratios[i+1] or ratios[i-1]
-                        // For simplicity, we adjust the pane's ratio up/down
-                        
-                        if i < ratios.len() {
-                            let new_ratio = (ratios[i] + delta).clamp(0.1, 0.9);
-                            let diff = new_ratio - ratios[i];
-                            ratios[i] = new_ratio;
-                            
-                            // Adjust adjacent pane(s) to maintain total of 1.0
-                            if i + 1 < ratios.len() {
-                                ratios[i + 1] -= diff;
-                                ratios[i + 1] = ratios[i + 1].clamp(0.1, 0.9);
-                            } else if i > 0 {
-                                ratios[i - 1] -= diff;
-                                ratios[i - 1] = ratios[i - 1].clamp(0.1, 0.9);
-                            }
-                            
-                            // Normalize to ensure total is 1.0
-                            let total: f32 = ratios.iter().sum();
-                            if total > 0.0 {
-                                for ratio in &mut ratios {
-                                    *ratio /= total;
-                                }
-                            }
-                        }
-                        
-                        // Recurse into children to find the actual pane
-                        let mut new_children = Vec::with_capacity(children.len());
-                        for child in children.into_iter() {
-                            let new_child = resize_pane_in_node(*child, pane_id, direction, delta);
-                            match new_child {
-                                Ok(c) => new_children.push(Box::new(c)),
-                                Err((c, _)) => new_children.push(Box::new(c)),
-                            }
-                        }
-                        
-                        return Ok(LayoutNode::HorizontalSplit {
-                            children: new_children,
-                            ratios,
-                        });
-                    }
-                }
+        LayoutNode::HorizontalSplit { children, dimensions } => LayoutTemplate::Horizontal {
+            children: children
+                .iter()
+                .zip(dimensions.iter())
+                .map(|(child, dim)| node_to_template(child, Some(*dim)))
+                .collect(),
+            size,
+        },
+        LayoutNode::VerticalSplit { children, dimensions } => LayoutTemplate::Vertical {
+            children: children
+                .iter()
+                .zip(dimensions.iter())
+                .map(|(child, dim)| node_to_template(child, Some(*dim)))
+                .collect(),
+            size,
+        },
+    }
+}
+
+/// A set of preset split arrangements for `LayoutTree::apply_swap_layout`,
+/// keyed by how many panes are currently open so the right one is picked
+/// for 2, 3, 4... panes
+#[derive(Debug, Clone, Default)]
+pub struct SwapLayoutTemplate {
+    pub arrangements: HashMap<usize, SwapLayoutNode>,
+}
+
+impl SwapLayoutTemplate {
+    fn arrangement_for(&self, pane_count: usize) -> Option<&SwapLayoutNode> {
+        self.arrangements.get(&pane_count)
+    }
+}
+
+/// A node in a swap layout arrangement - unlike `LayoutTemplate`, leaves
+/// carry no pane metadata, since `apply_swap_layout` fills each one in
+/// from a pane already live in the tree rather than spawning a new one
+#[derive(Debug, Clone)]
+pub enum SwapLayoutNode {
+    /// A leaf slot, filled with the next pane in position order
+    Pane { size: Option<Dimension> },
+    /// Panes side by side
+    Horizontal {
+        children: Vec<SwapLayoutNode>,
+        size: Option<Dimension>,
+    },
+    /// Panes stacked
+    Vertical {
+        children: Vec<SwapLayoutNode>,
+        size: Option<Dimension>,
+    },
+}
+
+impl SwapLayoutNode {
+    /// Count the leaf slots in this subtree
+    fn leaf_count(&self) -> usize {
+        match self {
+            SwapLayoutNode::Pane { .. } => 1,
+            SwapLayoutNode::Horizontal { children, .. } | SwapLayoutNode::Vertical { children, .. } => {
+                children.iter().map(SwapLayoutNode::leaf_count).sum()
             }
-            
-            // Direction doesn't match or pane not found, just recurse
-            let mut new_children = Vec::with_capacity(children.len());
-            for child in children.into_iter() {
-                let new_child = resize_pane_in_node(*child, pane_id, direction, delta);
-                match new_child {
-                    Ok(c) => new_children.push(Box::new(c)),
-                    Err((c, _)) => new_children.push(Box::new(c)),
+        }
+    }
+}
+
+/// Take ownership of every pane in `node`, in the same left-to-right,
+/// depth-first order as `collect_pane_ids`, consuming the node entirely
+fn take_all_panes(node: LayoutNode, out: &mut Vec<Pane>) {
+    match node {
+        LayoutNode::Pane(pane) => out.push(pane),
+        LayoutNode::HorizontalSplit { children, .. } | LayoutNode::VerticalSplit { children, .. } => {
+            for child in children {
+                take_all_panes(*child, out);
+            }
+        }
+    }
+}
+
+/// Recursively build a `LayoutNode` from a `SwapLayoutNode`, pulling each
+/// leaf's pane from `panes` (in position order) instead of spawning one.
+/// Returns the node alongside the `Dimension` its own `size` declared,
+/// defaulting to an equal `Percent` share if omitted.
+///
+/// Once `panes` runs dry, remaining leaf slots are filled with a
+/// placeholder pane (via `create_placeholder_pane`) rather than failing -
+/// callers are expected to have already checked that the arrangement has
+/// at least as many leaf slots as there are live panes to place.
+fn build_swap_node(
+    node: &SwapLayoutNode,
+    panes: &mut std::vec::IntoIter<Pane>,
+) -> Result<(LayoutNode, Dimension), String> {
+    match node {
+        SwapLayoutNode::Pane { size } => {
+            let pane = panes.next().unwrap_or_else(create_placeholder_pane);
+            Ok((LayoutNode::Pane(pane), size.unwrap_or(Dimension::Percent(1.0))))
+        }
+        SwapLayoutNode::Horizontal { children, size } => {
+            let (children, dimensions) = build_swap_children(children, panes)?;
+            Ok((
+                LayoutNode::HorizontalSplit { children, dimensions },
+                size.unwrap_or(Dimension::Percent(1.0)),
+            ))
+        }
+        SwapLayoutNode::Vertical { children, size } => {
+            let (children, dimensions) = build_swap_children(children, panes)?;
+            Ok((
+                LayoutNode::VerticalSplit { children, dimensions },
+                size.unwrap_or(Dimension::Percent(1.0)),
+            ))
+        }
+    }
+}
+
+/// Build every child of a swap layout split, collecting their nodes and
+/// declared (or defaulted) dimensions in order
+fn build_swap_children(
+    nodes: &[SwapLayoutNode],
+    panes: &mut std::vec::IntoIter<Pane>,
+) -> Result<(Vec<Box<LayoutNode>>, Vec<Dimension>), String> {
+    if nodes.is_empty() {
+        return Err("a swap layout split must have at least one child".to_string());
+    }
+
+    let mut children = Vec::with_capacity(nodes.len());
+    let mut dimensions = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let (child, dimension) = build_swap_node(node, panes)?;
+        children.push(Box::new(child));
+        dimensions.push(dimension);
+    }
+    Ok((children, dimensions))
+}
+
+/// Render a `Dimension` as the `size=...` token used in the layout format
+fn format_dimension(dimension: Dimension) -> String {
+    format!("size={}", dimension)
+}
+
+/// Parse a `size=...` token into a `Dimension`
+fn parse_dimension(token: &str) -> Result<Dimension, String> {
+    let value = token
+        .strip_prefix("size=")
+        .ok_or_else(|| format!("expected 'size=...', found '{}'", token))?;
+    value.parse()
+}
+
+/// Wrap a string in double quotes, escaping embedded quotes/backslashes
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+/// Unwrap a quoted token, undoing `quote`'s escaping
+fn unquote(token: &str) -> Result<String, String> {
+    let inner = token
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string, found '{}'", token))?;
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                out.push(escaped);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    Ok(out)
+}
+
+/// Render a node (and the `Dimension` its parent split gave it, if any) to
+/// the nested block layout format
+fn format_node(node: &LayoutNode, dimension: Option<Dimension>) -> String {
+    let size_suffix = dimension.map(|d| format!(" {}", format_dimension(d))).unwrap_or_default();
+
+    match node {
+        LayoutNode::Pane(pane) => {
+            let mut attrs = vec![format!("title={}", quote(&pane.title))];
+            if let Some(session) = pane.pty.lock().as_ref() {
+                if let Some(dir) = session.working_dir() {
+                    attrs.push(format!("cwd={}", quote(dir)));
                 }
+                attrs.push(format!("cmd={}", quote(session.shell())));
             }
-            
-            Ok(LayoutNode::HorizontalSplit {
-                children: new_children,
-                ratios,
-            })
-        }
-        LayoutNode::VerticalSplit { mut children, mut ratios } => {
-            // Only resize if direction matches
-            if direction == SplitDirection::Vertical {
-                // Find which child contains the pane
-                for i in 0..children.len() {
-                    if children[i].find_pane(pane_id).is_some() {
-                        // Found the pane, adjust ratios
-                        if i < ratios.len() {
-                            let new_ratio = (ratios[i] + delta).clamp(0.1, 0.9);
-                            let diff = new_ratio - ratios[i];
-                            ratios[i] = new_ratio;
-                            
-                            // Adjust adjacent pane(s)
-                            if i + 1 < ratios.len() {
-                                ratios[i + 1] -= diff;
-                                ratios[i + 1] = ratios[i + 1].clamp(0.1, 0.9);
-                            } else if i > 0 {
-                                ratios[i - 1] -= diff;
-                                ratios[i - 1] = ratios[i - 1].clamp(0.1, 0.9);
-                            }
-                            
-                            // Normalize
-                            let total: f32 = ratios.iter().sum();
-                            if total > 0.0 {
-                                for ratio in &mut ratios {
-                                    *ratio /= total;
-                                }
-                            }
-                        }
-                        
-                        // Recurse into children
-                        let mut new_children = Vec::with_capacity(children.len());
-                        for child in children.into_iter() {
-                            let new_child = resize_pane_in_node(*child, pane_id, direction, delta);
-                            match new_child {
-                                Ok(c) => new_children.push(Box::new(c)),
-                                Err((c, _)) => new_children.push(Box::new(c)),
-                            }
+            format!("pane{} {{ {} }}", size_suffix, attrs.join(" "))
+        }
+        LayoutNode::HorizontalSplit { children, dimensions } => {
+            format_split("horizontal", children, dimensions, &size_suffix)
+        }
+        LayoutNode::VerticalSplit { children, dimensions } => {
+            format_split("vertical", children, dimensions, &size_suffix)
+        }
+    }
+}
+
+fn format_split(keyword: &str, children: &[Box<LayoutNode>], dimensions: &[Dimension], size_suffix: &str) -> String {
+    let body = children
+        .iter()
+        .enumerate()
+        .map(|(i, child)| format_node(child, dimensions.get(i).copied()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("{}{} {{ {} }}", keyword, size_suffix, body)
+}
+
+/// Split `s` into the word/brace tokens of the layout format, keeping
+/// quoted `"..."` values (which may contain spaces) as a single token
+fn tokenize(s: &str) -> Result<Vec<String>, String> {
+    let mut chars = s.chars().peekable();
+    let mut tokens = Vec::new();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '{' || c == '}' {
+            tokens.push(c.to_string());
+            chars.next();
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '{' || c == '}' {
+                break;
+            }
+            if c == '"' {
+                word.push(chars.next().unwrap());
+                let mut closed = false;
+                while let Some(&qc) = chars.peek() {
+                    word.push(chars.next().unwrap());
+                    if qc == '\\' {
+                        if let Some(&escaped) = chars.peek() {
+                            word.push(escaped);
+                            chars.next();
                         }
-                        
-                        return Ok(LayoutNode::VerticalSplit {
-                            children: new_children,
-                            ratios,
-                        });
+                        continue;
+                    }
+                    if qc == '"' {
+                        closed = true;
+                        break;
                     }
                 }
+                if !closed {
+                    return Err("unterminated string literal".to_string());
+                }
+                continue;
             }
-            
-            // Direction doesn't match or pane not found, just recurse
-            let mut new_children = Vec::with_capacity(children.len());
-            for child in children.into_iter() {
-                let new_child = resize_pane_in_node(*child, pane_id, direction, delta);
-                match new_child {
-                    Ok(c) => new_children.push(Box::new(c)),
-                    Err((c, _)) => new_children.push(Box::new(c)),
+            word.push(chars.next().unwrap());
+        }
+        if !word.is_empty() {
+            tokens.push(word);
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Cursor over a token slice for the layout format's recursive-descent parser
+struct TokenStream<'a> {
+    tokens: &'a [String],
+    pos: usize,
+}
+
+impl<'a> TokenStream<'a> {
+    fn new(tokens: &'a [String]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).map(|s| s.as_str())
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected '{}', found '{}'", expected, token)),
+            None => Err(format!("expected '{}', found end of input", expected)),
+        }
+    }
+}
+
+/// Parse one `pane`/`horizontal`/`vertical` node, returning it alongside
+/// the `Dimension` its own `size=...` token declared (defaulting to an
+/// equal `Percent` share if omitted, since `Percent` values are weights
+/// normalized against their siblings rather than absolute fractions)
+fn parse_node(
+    stream: &mut TokenStream,
+    spawn: &mut impl FnMut(PaneSpec) -> PtySession,
+    pane_count: &mut usize,
+) -> Result<(LayoutNode, Dimension), String> {
+    let keyword = stream
+        .next()
+        .ok_or_else(|| "unexpected end of input: expected 'pane', 'horizontal', or 'vertical'".to_string())?;
+
+    let dimension = match stream.peek() {
+        Some(token) if token.starts_with("size=") => {
+            let dimension = parse_dimension(token)?;
+            stream.next();
+            dimension
+        }
+        _ => Dimension::Percent(1.0),
+    };
+
+    match keyword {
+        "pane" => {
+            *pane_count += 1;
+            if *pane_count > 8 {
+                return Err("Maximum pane limit (8) reached while parsing layout".to_string());
+            }
+
+            let spec = if stream.peek() == Some("{") {
+                stream.next();
+                parse_pane_attrs(stream)?
+            } else {
+                PaneSpec::default()
+            };
+
+            let title = spec.title.clone().unwrap_or_else(|| "Terminal".to_string());
+            let pty = spawn(spec);
+            let mut pane = Pane::new(pty, 80, 24, Rect::new(0, 0, 80, 24));
+            pane.title = title;
+
+            Ok((LayoutNode::Pane(pane), dimension))
+        }
+        "horizontal" | "vertical" => {
+            stream.expect("{")?;
+
+            let mut children = Vec::new();
+            let mut dimensions = Vec::new();
+            while stream.peek() != Some("}") {
+                if stream.peek().is_none() {
+                    return Err(format!("unterminated '{}' split: missing '}}'", keyword));
                 }
+                let (child, child_dimension) = parse_node(stream, spawn, pane_count)?;
+                children.push(Box::new(child));
+                dimensions.push(child_dimension);
             }
-            
-            Ok(LayoutNode::VerticalSplit {
-                children: new_children,
-                ratios,
-            })
+            stream.next(); // consume "}"
+
+            if children.is_empty() {
+                return Err(format!("'{}' split must have at least one child", keyword));
+            }
+
+            Ok((
+                if keyword == "horizontal" {
+                    LayoutNode::HorizontalSplit { children, dimensions }
+                } else {
+                    LayoutNode::VerticalSplit { children, dimensions }
+                },
+                dimension,
+            ))
         }
+        other => Err(format!(
+            "unexpected token '{}': expected 'pane', 'horizontal', or 'vertical'",
+            other
+        )),
     }
 }
 
-use std::sync::{Arc, Mutex, OnceLock};
-use uuid::Uuid;
+/// Parse the `{ title="..." cwd="..." cmd="..." }` attribute block after a
+/// `pane` keyword (the opening `{` has already been consumed)
+fn parse_pane_attrs(stream: &mut TokenStream) -> Result<PaneSpec, String> {
+    let mut spec = PaneSpec::default();
+
+    loop {
+        match stream.peek() {
+            Some("}") => {
+                stream.next();
+                break;
+            }
+            Some(token) if token.starts_with("title=") => {
+                spec.title = Some(unquote(&token["title=".len()..])?);
+                stream.next();
+            }
+            Some(token) if token.starts_with("cwd=") => {
+                spec.working_dir = Some(unquote(&token["cwd=".len()..])?);
+                stream.next();
+            }
+            Some(token) if token.starts_with("cmd=") => {
+                spec.command = Some(unquote(&token["cmd=".len()..])?);
+                stream.next();
+            }
+            Some(token) => return Err(format!("unexpected pane attribute '{}'", token)),
+            None => return Err("unterminated pane attributes: missing '}'".to_string()),
+        }
+    }
 
-/// Cached placeholder PTY to avoid spawning multiple times on Windows
-/// This is created once and reused for all placeholder operations
-static PLACEHOLDER_PTY: OnceLock<Arc<Mutex<PtySession>>> = OnceLock::new();
-
-/// Get or create the cached placeholder PTY
-fn get_placeholder_pty() -> Arc<Mutex<PtySession>> {
-    PLACEHOLDER_PTY.get_or_init(|| {
-        use crate::terminal::pty::PtyConfig;
-        // Spawn once with minimal size
-        let pty = PtySession::spawn(PtyConfig {
-            cols: 1,
-            rows: 1,
-            shell: None,  // Use default shell
-            env: vec![],
-            working_dir: None,
-        }).expect("Failed to spawn placeholder PTY");
-        Arc::new(Mutex::new(pty))
-    })
+    Ok(spec)
 }
 
-/// Create a placeholder pane (used internally for tree manipulation)
+/// Allocate integer cell counts to a split's children along one axis
 ///
-/// Uses a cached PTY to avoid spawning multiple sessions on Windows,
-/// which prevents stack overflow from repeated PTY creation.
-fn create_placeholder_pane() -> Pane {
-    Pane {
-        id: Uuid::new_v4(),
-        title: "Placeholder".to_string(),
-        pty: get_placeholder_pty(),  // Reuse cached PTY
-        grid: TerminalGrid::with_size(1, 1),
-        parser: TerminalParser::new(),
-        bounds: Rect::new(0, 0, 1, 1),
+/// `Fixed` children get their exact cell count; the rest of `extent` is
+/// distributed among `Percent` children in proportion to their values.
+/// Each `Percent` share is computed as a float and floored, then the
+/// leftover cells (`extent` minus the sum of the floors) are handed out
+/// one-by-one to the children with the largest fractional remainder, so
+/// the sizes always sum to exactly `extent` with no gap or overlap.
+fn allocate_extent(dimensions: &[Dimension], extent: u32) -> Vec<u32> {
+    let fixed_total: u32 = dimensions
+        .iter()
+        .map(|d| match d {
+            Dimension::Fixed(cells) => *cells,
+            Dimension::Percent(_) => 0,
+        })
+        .sum();
+    let remaining = extent.saturating_sub(fixed_total);
+
+    let percent_total: f32 = dimensions
+        .iter()
+        .map(|d| match d {
+            Dimension::Percent(p) => *p,
+            Dimension::Fixed(_) => 0.0,
+        })
+        .sum();
+
+    let mut sizes = vec![0u32; dimensions.len()];
+    let mut remainders: Vec<(usize, f32)> = Vec::new();
+    let mut floor_total = 0u32;
+
+    for (i, dim) in dimensions.iter().enumerate() {
+        match dim {
+            Dimension::Fixed(cells) => sizes[i] = *cells,
+            Dimension::Percent(p) => {
+                let share = if percent_total > 0.0 {
+                    remaining as f32 * (p / percent_total)
+                } else {
+                    0.0
+                };
+                let floor = share.floor();
+                sizes[i] = floor as u32;
+                floor_total += sizes[i];
+                remainders.push((i, share - floor));
+            }
+        }
     }
+
+    let mut leftover = remaining.saturating_sub(floor_total);
+    remainders.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    for (i, _) in remainders {
+        if leftover == 0 {
+            break;
+        }
+        sizes[i] += 1;
+        leftover -= 1;
+    }
+
+    sizes
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::terminal::pty::PtyConfig;
+/// Remove `pane_id` from `node` and hand the extracted `Pane` back intact,
+/// instead of dropping it the way `close_pane_in_node` does - used by
+/// `LayoutTree::float_focused` to relocate a live tiled pane into the
+/// floating set without killing its PTY
+///
+/// Returns `None` alongside the (unchanged) node if `pane_id` is the node
+/// itself (i.e. it's the tree's only pane, which the caller is expected to
+/// have already guarded against) or isn't found at all.
+fn extract_pane_from_node(node: LayoutNode, pane_id: Uuid) -> (LayoutNode, Option<Pane>) {
+    match node {
+        LayoutNode::Pane(pane) => (LayoutNode::Pane(pane), None),
+        LayoutNode::HorizontalSplit { mut children, mut dimensions } => {
+            match children.iter().position(|child| child.find_pane(pane_id).is_some()) {
+                Some(idx) if children[idx].is_pane() => {
+                    let removed = *children.remove(idx);
+                    dimensions.remove(idx);
+                    let extracted = match removed {
+                        LayoutNode::Pane(pane) => Some(pane),
+                        _ => unreachable!("is_pane() just confirmed this is a Pane"),
+                    };
+                    let new_node = if children.len() == 1 {
+                        *children.remove(0)
+                    } else {
+                        LayoutNode::HorizontalSplit { children, dimensions }
+                    };
+                    (new_node, extracted)
+                }
+                Some(idx) => {
+                    let child = children.remove(idx);
+                    let (new_child, extracted) = extract_pane_from_node(*child, pane_id);
+                    children.insert(idx, Box::new(new_child));
+                    (LayoutNode::HorizontalSplit { children, dimensions }, extracted)
+                }
+                None => (LayoutNode::HorizontalSplit { children, dimensions }, None),
+            }
+        }
+        LayoutNode::VerticalSplit { mut children, mut dimensions } => {
+            match children.iter().position(|child| child.find_pane(pane_id).is_some()) {
+                Some(idx) if children[idx].is_pane() => {
+                    let removed = *children.remove(idx);
+                    dimensions.remove(idx);
+                    let extracted = match removed {
+                        LayoutNode::Pane(pane) => Some(pane),
+                        _ => unreachable!("is_pane() just confirmed this is a Pane"),
+                    };
+                    let new_node = if children.len() == 1 {
+                        *children.remove(0)
+                    } else {
+                        LayoutNode::VerticalSplit { children, dimensions }
+                    };
+                    (new_node, extracted)
+                }
+                Some(idx) => {
+                    let child = children.remove(idx);
+                    let (new_child, extracted) = extract_pane_from_node(*child, pane_id);
+                    children.insert(idx, Box::new(new_child));
+                    (LayoutNode::VerticalSplit { children, dimensions }, extracted)
+                }
+                None => (LayoutNode::VerticalSplit { children, dimensions }, None),
+            }
+        }
+    }
+}
+
+/// Recursively close a pane in a node (standalone function to avoid borrow issues)
+fn close_pane_in_node(node: LayoutNode, pane_id: Uuid) -> Result<LayoutNode, String> {
+    match node {
+        LayoutNode::Pane(pane) if pane.id == pane_id => {
+            // This pane should be removed - caller will handle it
+            Err(format!("Pane {} found", pane_id))
+        }
+        LayoutNode::Pane(pane) => {
+            // Not the target pane
+            Ok(LayoutNode::Pane(pane))
+        }
+        LayoutNode::HorizontalSplit { mut children, mut dimensions } => {
+            // Try to remove the pane from children
+            let mut found_idx = None;
+            for (i, child) in children.iter().enumerate() {
+                if child.find_pane(pane_id).is_some() {
+                    found_idx = Some(i);
+                    break;
+                }
+            }
+
+            if let Some(idx) = found_idx {
+                // Remove the child; remaining Percent siblings are
+                // normalized against each other by allocate_extent, so no
+                // explicit renormalization is needed here
+                children.remove(idx);
+                dimensions.remove(idx);
+
+                // If only one child left, collapse the split
+                if children.len() == 1 {
+                    return Ok(*children.remove(0));
+                }
+
+                Ok(LayoutNode::HorizontalSplit { children, dimensions })
+            } else {
+                Ok(LayoutNode::HorizontalSplit { children, dimensions })
+            }
+        }
+        LayoutNode::VerticalSplit { mut children, mut dimensions } => {
+            // Try to remove the pane from children
+            let mut found_idx = None;
+            for (i, child) in children.iter().enumerate() {
+                if child.find_pane(pane_id).is_some() {
+                    found_idx = Some(i);
+                    break;
+                }
+            }
+
+            if let Some(idx) = found_idx {
+                // Remove the child; remaining Percent siblings are
+                // normalized against each other by allocate_extent, so no
+                // explicit renormalization is needed here
+                children.remove(idx);
+                dimensions.remove(idx);
+
+                // If only one child left, collapse the split
+                if children.len() == 1 {
+                    return Ok(*children.remove(0));
+                }
+
+                Ok(LayoutNode::VerticalSplit { children, dimensions })
+            } else {
+                Ok(LayoutNode::VerticalSplit { children, dimensions })
+            }
+        }
+    }
+}
+
+/// A split divider located by `LayoutTree::find_divider`, identified by a
+/// path of child indices from the root down to the split node that owns it.
+///
+/// Held across a drag (mouse-down to mouse-up) so `LayoutTree::drag_divider`
+/// can keep finding the same split even as its ratios - and therefore the
+/// on-screen pixel position of the divider - change underneath the drag.
+#[derive(Debug, Clone)]
+pub struct DividerHit {
+    /// Indices of children to descend through from the root to reach the
+    /// split node that owns this divider.
+    path: Vec<usize>,
+    /// Direction of the split that owns this divider: `Horizontal` tracks
+    /// the cursor's x position, `Vertical` tracks y.
+    direction: SplitDirection,
+    /// Index of the earlier of the two children the divider sits between.
+    index: usize,
+    /// Pixel extent of the split along its primary axis at the moment the
+    /// divider was found, used to convert a drag's pixel delta into a
+    /// `Dimension::Percent` delta.
+    extent: u32,
+}
+
+impl DividerHit {
+    /// Direction of the split this divider belongs to, so a caller can
+    /// track the cursor axis (x vs. y) that actually moves it.
+    pub fn direction(&self) -> SplitDirection {
+        self.direction
+    }
+}
+
+/// Recursively search `node` for a divider within `tolerance` pixels of
+/// `(x, y)`, mirroring the bounds `calculate_node_layout_owned` would
+/// assign within `bounds`. `path` accumulates the child indices visited so
+/// far and is restored before returning.
+fn find_divider_in_node(
+    node: &LayoutNode,
+    bounds: Rect,
+    tolerance: u32,
+    x: u32,
+    y: u32,
+    path: &mut Vec<usize>,
+) -> Option<DividerHit> {
+    match node {
+        LayoutNode::Pane(_) => None,
+        LayoutNode::HorizontalSplit { children, dimensions } => {
+            let sizes = allocate_extent(dimensions, bounds.width);
+            if y >= bounds.y && y < bounds.y + bounds.height {
+                let mut boundary = bounds.x;
+                for (i, width) in sizes.iter().enumerate().take(sizes.len().saturating_sub(1)) {
+                    boundary += width;
+                    if x.abs_diff(boundary) <= tolerance {
+                        return Some(DividerHit {
+                            path: path.clone(),
+                            direction: SplitDirection::Horizontal,
+                            index: i,
+                            extent: bounds.width,
+                        });
+                    }
+                }
+            }
+            let mut child_x = bounds.x;
+            for (i, (child, width)) in children.iter().zip(sizes.iter()).enumerate() {
+                let child_bounds = Rect::new(child_x, bounds.y, *width, bounds.height);
+                if child_bounds.contains(x, y) {
+                    path.push(i);
+                    let hit = find_divider_in_node(child, child_bounds, tolerance, x, y, path);
+                    path.pop();
+                    return hit;
+                }
+                child_x += width;
+            }
+            None
+        }
+        LayoutNode::VerticalSplit { children, dimensions } => {
+            let sizes = allocate_extent(dimensions, bounds.height);
+            if x >= bounds.x && x < bounds.x + bounds.width {
+                let mut boundary = bounds.y;
+                for (i, height) in sizes.iter().enumerate().take(sizes.len().saturating_sub(1)) {
+                    boundary += height;
+                    if y.abs_diff(boundary) <= tolerance {
+                        return Some(DividerHit {
+                            path: path.clone(),
+                            direction: SplitDirection::Vertical,
+                            index: i,
+                            extent: bounds.height,
+                        });
+                    }
+                }
+            }
+            let mut child_y = bounds.y;
+            for (i, (child, height)) in children.iter().zip(sizes.iter()).enumerate() {
+                let child_bounds = Rect::new(bounds.x, child_y, bounds.width, *height);
+                if child_bounds.contains(x, y) {
+                    path.push(i);
+                    let hit = find_divider_in_node(child, child_bounds, tolerance, x, y, path);
+                    path.pop();
+                    return hit;
+                }
+                child_y += height;
+            }
+            None
+        }
+    }
+}
+
+/// Apply `adjust_dimension_pair` to the split node reached by following
+/// `path` from `node`, e.g. the one `find_divider_in_node` found. Returns
+/// the (possibly unchanged) node and whether the ratio actually changed.
+///
+/// Walking back down by index rather than holding a direct reference keeps
+/// this consistent with the rest of the tree's ownership style (see
+/// `resize_pane_in_node`/`split_node_recursive`, which take and return
+/// owned nodes to avoid borrow conflicts with `&mut self.root`).
+fn apply_divider_delta(node: LayoutNode, path: &[usize], index: usize, delta: f32) -> (LayoutNode, bool) {
+    let Some((&i, rest)) = path.split_first() else {
+        return match node {
+            LayoutNode::HorizontalSplit { children, mut dimensions } => {
+                let changed = index < dimensions.len() && adjust_dimension_pair(&mut dimensions, index, delta);
+                (LayoutNode::HorizontalSplit { children, dimensions }, changed)
+            }
+            LayoutNode::VerticalSplit { children, mut dimensions } => {
+                let changed = index < dimensions.len() && adjust_dimension_pair(&mut dimensions, index, delta);
+                (LayoutNode::VerticalSplit { children, dimensions }, changed)
+            }
+            pane @ LayoutNode::Pane(_) => (pane, false),
+        };
+    };
+
+    match node {
+        LayoutNode::HorizontalSplit { mut children, dimensions } if i < children.len() => {
+            let child = std::mem::replace(&mut children[i], Box::new(LayoutNode::Pane(create_placeholder_pane())));
+            let (new_child, changed) = apply_divider_delta(*child, rest, index, delta);
+            children[i] = Box::new(new_child);
+            (LayoutNode::HorizontalSplit { children, dimensions }, changed)
+        }
+        LayoutNode::VerticalSplit { mut children, dimensions } if i < children.len() => {
+            let child = std::mem::replace(&mut children[i], Box::new(LayoutNode::Pane(create_placeholder_pane())));
+            let (new_child, changed) = apply_divider_delta(*child, rest, index, delta);
+            children[i] = Box::new(new_child);
+            (LayoutNode::VerticalSplit { children, dimensions }, changed)
+        }
+        // The tree changed shape since this `DividerHit` was found (e.g. a
+        // pane was closed mid-drag) and `path` no longer resolves.
+        other => (other, false),
+    }
+}
+
+/// Minimum share a `Percent` child is allowed to shrink to during a resize
+const MIN_PERCENT: f32 = 0.05;
+/// Maximum share a `Percent` child is allowed to grow to during a resize
+const MAX_PERCENT: f32 = 0.95;
+
+/// Resize `dimensions[i]` by `delta`, shifting the opposite adjustment onto
+/// an adjacent sibling (the one at `i + 1`, or `i - 1` if `i` is last).
+///
+/// `Fixed` children are locked: they never grow or shrink from a resize,
+/// whether they're the one being dragged or the adjacent sibling - e.g. a
+/// 30-column sidebar stays exactly 30 columns while the main pane absorbs
+/// the rest of the resize. Only `Percent` children shift `delta` of their
+/// share to/from an adjacent `Percent` sibling, clamped so neither drops
+/// below `MIN_PERCENT`.
+///
+/// If the primary neighbor is already sitting at `MIN_PERCENT` (or is
+/// `Fixed` and can't give anything back), this "reduces" into the sibling
+/// on the *other* side instead of silently dropping the leftover - e.g.
+/// growing the rightmost of three panes pulls from the middle one first,
+/// then from the leftmost once the middle is exhausted.
+///
+/// Returns `true` if `dimensions[i]` actually changed size.
+fn adjust_dimension_pair(dimensions: &mut [Dimension], i: usize, delta: f32) -> bool {
+    if matches!(dimensions[i], Dimension::Fixed(_)) {
+        return false;
+    }
+
+    let percent = match dimensions[i] {
+        Dimension::Percent(p) => p,
+        Dimension::Fixed(_) => unreachable!("checked above"),
+    };
+
+    let new_percent = (percent + delta).clamp(MIN_PERCENT, MAX_PERCENT);
+    let mut remaining = new_percent - percent;
+    if remaining == 0.0 {
+        return false;
+    }
+    dimensions[i] = Dimension::Percent(new_percent);
+
+    let (primary, secondary) = if i + 1 < dimensions.len() {
+        (Some(i + 1), if i > 0 { Some(i - 1) } else { None })
+    } else if i > 0 {
+        (Some(i - 1), None)
+    } else {
+        (None, None)
+    };
+
+    for neighbor in [primary, secondary].into_iter().flatten() {
+        if remaining == 0.0 {
+            break;
+        }
+        if let Dimension::Percent(neighbor_percent) = dimensions[neighbor] {
+            let new_neighbor = (neighbor_percent - remaining).clamp(MIN_PERCENT, MAX_PERCENT);
+            let absorbed = neighbor_percent - new_neighbor;
+            dimensions[neighbor] = Dimension::Percent(new_neighbor);
+            remaining -= absorbed;
+        }
+    }
+
+    true
+}
+
+/// Recursively resize a pane in a node (standalone function to avoid borrow issues)
+///
+/// Descends to the pane first, then lets the resize bubble back up through
+/// the ancestor chain: each enclosing split only tries to absorb the delta
+/// if a more deeply nested split hasn't already done so (the `bool` half of
+/// the `Ok` result). That gives a pane buried in a split running the
+/// "wrong" direction the behavior users expect from tmux/zellij - grow
+/// left/right/up/down regardless of nesting - by walking outward past
+/// mismatched-direction splits (which never absorb) until it finds one that
+/// matches, and past a matching split that's already fully saturated (e.g.
+/// a `Fixed` neighbor) to the next matching one further out.
+///
+/// Returns `Ok((new_node, true))` if some split along the path actually
+/// changed size, `Ok((new_node, false))` if the tree is unchanged (no
+/// matching split absorbed anything), or `Err` if `pane_id` doesn't exist
+/// in this subtree at all.
+fn resize_pane_in_node(
+    node: LayoutNode,
+    pane_id: Uuid,
+    direction: SplitDirection,
+    delta: f32,
+) -> Result<(LayoutNode, bool), (LayoutNode, String)> {
+    match node {
+        LayoutNode::Pane(pane) => {
+            if pane.id == pane_id {
+                Ok((LayoutNode::Pane(pane), false))
+            } else {
+                Err((LayoutNode::Pane(pane), "Cannot resize: no adjacent pane".to_string()))
+            }
+        }
+        LayoutNode::HorizontalSplit { children, mut dimensions } => {
+            let pane_child = children.iter().position(|c| c.find_pane(pane_id).is_some());
+
+            let mut new_children = Vec::with_capacity(children.len());
+            let mut child_absorbed = false;
+            for (idx, child) in children.into_iter().enumerate() {
+                match resize_pane_in_node(*child, pane_id, direction, delta) {
+                    Ok((c, absorbed)) => {
+                        if Some(idx) == pane_child {
+                            child_absorbed = absorbed;
+                        }
+                        new_children.push(Box::new(c));
+                    }
+                    Err((c, _)) => new_children.push(Box::new(c)),
+                }
+            }
+
+            let absorbed = if !child_absorbed && direction == SplitDirection::Horizontal {
+                match pane_child {
+                    Some(i) if i < dimensions.len() => adjust_dimension_pair(&mut dimensions, i, delta),
+                    _ => false,
+                }
+            } else {
+                child_absorbed
+            };
+
+            Ok((
+                LayoutNode::HorizontalSplit {
+                    children: new_children,
+                    dimensions,
+                },
+                absorbed,
+            ))
+        }
+        LayoutNode::VerticalSplit { children, mut dimensions } => {
+            let pane_child = children.iter().position(|c| c.find_pane(pane_id).is_some());
+
+            let mut new_children = Vec::with_capacity(children.len());
+            let mut child_absorbed = false;
+            for (idx, child) in children.into_iter().enumerate() {
+                match resize_pane_in_node(*child, pane_id, direction, delta) {
+                    Ok((c, absorbed)) => {
+                        if Some(idx) == pane_child {
+                            child_absorbed = absorbed;
+                        }
+                        new_children.push(Box::new(c));
+                    }
+                    Err((c, _)) => new_children.push(Box::new(c)),
+                }
+            }
+
+            let absorbed = if !child_absorbed && direction == SplitDirection::Vertical {
+                match pane_child {
+                    Some(i) if i < dimensions.len() => adjust_dimension_pair(&mut dimensions, i, delta),
+                    _ => false,
+                }
+            } else {
+                child_absorbed
+            };
+
+            Ok((
+                LayoutNode::VerticalSplit {
+                    children: new_children,
+                    dimensions,
+                },
+                absorbed,
+            ))
+        }
+    }
+}
+
+/// Create a placeholder pane (used internally for tree manipulation)
+///
+/// Carries no `PtySession` at all - `Pane::pty` is `None` - so taking a
+/// node's slot while rebuilding the tree never spawns a process just to
+/// hold a place. Whatever real pane eventually replaces it keeps its own
+/// PTY; if a placeholder itself is ever written to or resized before
+/// being replaced, `Pane::ensure_pty` spawns a shell into it lazily.
+fn create_placeholder_pane() -> Pane {
+    Pane {
+        id: Uuid::new_v4(),
+        title: "Placeholder".to_string(),
+        pty: Arc::new(Mutex::new(None)),
+        pending_output: Arc::new(Mutex::new(Vec::new())),
+        grid: TerminalGrid::with_size(1, 1),
+        parser: TerminalParser::new(),
+        bounds: Rect::new(0, 0, 1, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::pty::PtyConfig;
+
+    fn create_test_pane() -> Pane {
+        let pty = PtySession::spawn(PtyConfig::default()).unwrap();
+        Pane::new(pty, 80, 24, Rect::new(0, 0, 800, 600))
+    }
+
+    #[test]
+    fn test_rect_creation() {
+        let rect = Rect::new(10, 20, 100, 200);
+        assert_eq!(rect.x, 10);
+        assert_eq!(rect.y, 20);
+        assert_eq!(rect.width, 100);
+        assert_eq!(rect.height, 200);
+    }
+
+    #[test]
+    fn test_layout_surface_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        // Compiles only if every field of these types is itself Send+Sync,
+        // e.g. `Pane::pty` being a `parking_lot::Mutex` rather than
+        // something thread-confined - this is what lets the layout be
+        // shared between a UI thread and a PTY-reader thread.
+        assert_send_sync::<Pane>();
+        assert_send_sync::<LayoutNode>();
+        assert_send_sync::<LayoutTree>();
+    }
+
+    #[test]
+    fn test_ensure_pty_lazily_spawns_a_shell_into_a_placeholder() {
+        let pane = create_placeholder_pane();
+        assert!(pane.pty.lock().is_none());
+
+        pane.ensure_pty().unwrap();
+        assert!(pane.pty.lock().is_some());
+    }
+
+    #[test]
+    fn test_rect_contains() {
+        let rect = Rect::new(10, 20, 100, 200);
+        assert!(rect.contains(50, 100)); // Inside
+        assert!(rect.contains(10, 20)); // On edge
+        assert!(!rect.contains(200, 100)); // Outside X
+        assert!(!rect.contains(50, 300)); // Outside Y
+    }
+
+    #[test]
+    fn test_pane_creation() {
+        let pane = create_test_pane();
+        assert!(!pane.id.is_nil());
+        assert_eq!(pane.title, "Terminal");
+    }
+
+    #[test]
+    fn test_pane_terminal_size() {
+        let pane = create_test_pane();
+        let (cols, rows) = pane.terminal_size(10, 20);
+        assert_eq!(cols, 80);
+        assert_eq!(rows, 30);
+    }
+
+    #[test]
+    fn test_layout_tree_creation() {
+        let pane = create_test_pane();
+        let pane_id = pane.id;
+        let tree = LayoutTree::new(pane);
+        
+        assert_eq!(tree.focused_pane_id(), pane_id);
+        assert_eq!(tree.pane_count(), 1);
+    }
+
+    #[test]
+    fn test_layout_tree_focused_pane() {
+        let pane = create_test_pane();
+        let pane_id = pane.id;
+        let mut tree = LayoutTree::new(pane);
+        
+        let focused = tree.focused_pane();
+        assert!(focused.is_some());
+        assert_eq!(focused.unwrap().id, pane_id);
+    }
+
+    #[test]
+    fn test_layout_node_pane_count() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+        
+        let node = LayoutNode::HorizontalSplit {
+            children: vec![
+                Box::new(LayoutNode::Pane(pane1)),
+                Box::new(LayoutNode::Pane(pane2)),
+            ],
+            dimensions: vec![Dimension::Percent(0.5), Dimension::Percent(0.5)],
+        };
+        
+        assert_eq!(node.pane_count(), 2);
+    }
+
+    #[test]
+    fn test_layout_tree_split_horizontal() {
+        let pane1 = create_test_pane();
+        let pane1_id = pane1.id;
+        let pane2 = create_test_pane();
+        let pane2_id = pane2.id;
+        
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        
+        assert_eq!(tree.pane_count(), 2);
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+        assert!(tree.get_pane(pane1_id).is_some());
+        assert!(tree.get_pane(pane2_id).is_some());
+    }
+
+    #[test]
+    fn test_layout_tree_split_vertical() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+        
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Vertical, pane2).unwrap();
+        
+        assert_eq!(tree.pane_count(), 2);
+        assert_eq!(tree.root().pane_count(), 2);
+    }
+
+    #[test]
+    fn test_layout_tree_focus_navigation() {
+        let pane1 = create_test_pane();
+        let pane1_id = pane1.id;
+        let pane2 = create_test_pane();
+        let pane2_id = pane2.id;
+        let pane3 = create_test_pane();
+        let pane3_id = pane3.id;
+        
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        tree.set_focus(pane1_id);
+        tree.split_focused(SplitDirection::Vertical, pane3).unwrap();
+        
+        // Test focus_next
+        tree.set_focus(pane1_id);
+        tree.focus_next();
+        assert_eq!(tree.focused_pane_id(), pane3_id);
+        
+        tree.focus_next();
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+        
+        tree.focus_next();
+        assert_eq!(tree.focused_pane_id(), pane1_id);
+        
+        // Test focus_prev
+        tree.focus_prev();
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+    }
+
+    #[test]
+    fn test_focus_direction_navigates_a_2x2_grid() {
+        let pane1 = create_test_pane();
+        let pane1_id = pane1.id;
+        let pane2 = create_test_pane();
+        let pane2_id = pane2.id;
+        let pane3 = create_test_pane();
+        let pane3_id = pane3.id;
+        let pane4 = create_test_pane();
+        let pane4_id = pane4.id;
+
+        // [pane1 | pane2]    ->    [pane1 pane3 | pane2 pane4]
+        //                          (pane1 top-left, pane3 bottom-left,
+        //                           pane2 top-right, pane4 bottom-right)
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        tree.set_focus(pane1_id);
+        tree.split_focused(SplitDirection::Vertical, pane3).unwrap();
+        tree.set_focus(pane2_id);
+        tree.split_focused(SplitDirection::Vertical, pane4).unwrap();
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 100));
+
+        tree.set_focus(pane1_id);
+        assert!(tree.focus_direction(Direction::Right));
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+
+        tree.set_focus(pane1_id);
+        assert!(tree.focus_direction(Direction::Down));
+        assert_eq!(tree.focused_pane_id(), pane3_id);
+
+        tree.set_focus(pane4_id);
+        assert!(tree.focus_direction(Direction::Left));
+        assert_eq!(tree.focused_pane_id(), pane3_id);
+
+        tree.set_focus(pane4_id);
+        assert!(tree.focus_direction(Direction::Up));
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+
+        tree.set_focus(pane1_id);
+        assert!(!tree.focus_direction(Direction::Up));
+        assert_eq!(tree.focused_pane_id(), pane1_id);
+    }
+
+    #[test]
+    fn test_layout_tree_calculate_layout() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+        
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        
+        let total_bounds = Rect::new(0, 0, 1000, 800);
+        tree.calculate_layout(total_bounds);
+        
+        // Check that panes have bounds set
+        let pane_ids = tree.all_pane_ids();
+        for id in pane_ids {
+            let pane = tree.get_pane(id).unwrap();
+            assert!(pane.bounds.width > 0);
+            assert!(pane.bounds.height > 0);
+        }
+    }
+
+    #[test]
+    fn test_allocate_extent_splits_evenly() {
+        let dimensions = vec![Dimension::Percent(0.5), Dimension::Percent(0.5)];
+        let sizes = allocate_extent(&dimensions, 100);
+        assert_eq!(sizes, vec![50, 50]);
+    }
+
+    #[test]
+    fn test_allocate_extent_subtracts_fixed_before_percent() {
+        let dimensions = vec![Dimension::Fixed(30), Dimension::Percent(1.0)];
+        let sizes = allocate_extent(&dimensions, 100);
+        assert_eq!(sizes, vec![30, 70]);
+    }
+
+    #[test]
+    fn test_allocate_extent_distributes_remainder_to_largest_fractions() {
+        // 100 cells split three ways: each percent share is 33.33..., so
+        // the floors (33, 33, 33) leave one cell over, which should go to
+        // the child with the largest fractional remainder - here, all
+        // three are tied, so the first one wins the tie.
+        let dimensions = vec![
+            Dimension::Percent(1.0),
+            Dimension::Percent(1.0),
+            Dimension::Percent(1.0),
+        ];
+        let sizes = allocate_extent(&dimensions, 100);
+        assert_eq!(sizes.iter().sum::<u32>(), 100);
+        assert_eq!(sizes, vec![34, 33, 33]);
+    }
+
+    #[test]
+    fn test_layout_tree_split_with_fixed_dimension() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused_with_dimension(SplitDirection::Horizontal, pane2, Dimension::Fixed(30))
+            .unwrap();
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 50));
+        let pane_ids = tree.all_pane_ids();
+        let widths: Vec<u32> = pane_ids
+            .iter()
+            .map(|id| tree.get_pane(*id).unwrap().bounds.width)
+            .collect();
+        assert!(widths.contains(&30));
+        assert_eq!(widths.iter().sum::<u32>(), 100);
+    }
+
+    #[test]
+    fn test_resize_fixed_pane_is_locked_and_never_changes_size() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused_with_dimension(SplitDirection::Horizontal, pane2, Dimension::Fixed(30))
+            .unwrap();
+        // The new pane (Fixed(30)) is focused after the split, and there's
+        // no other enclosing split to walk up to, so the resize has no
+        // effect anywhere and reports an error rather than a silent no-op.
+        let result = tree.resize_focused(SplitDirection::Horizontal, 5.0);
+        assert!(result.is_err());
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 50));
+        let pane_ids = tree.all_pane_ids();
+        let widths: Vec<u32> = pane_ids
+            .iter()
+            .map(|id| tree.get_pane(*id).unwrap().bounds.width)
+            .collect();
+        assert!(widths.contains(&30)); // unchanged - Fixed panes never resize
+        assert_eq!(widths.iter().sum::<u32>(), 100);
+    }
+
+    #[test]
+    fn test_resize_walks_up_to_the_nearest_enclosing_split_of_the_right_direction() {
+        // horizontal { pane1, vertical { pane2 (focused), pane3 } }
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+        let pane2_id = pane2.id;
+        let pane3 = create_test_pane();
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        tree.split_focused(SplitDirection::Vertical, pane3).unwrap();
+        tree.set_focus(pane2_id);
+
+        // pane2 is focused, nested inside a vertical split; a horizontal
+        // resize can't be absorbed by that inner split so it should walk up
+        // to the outer horizontal split instead of doing nothing.
+        tree.resize_focused(SplitDirection::Horizontal, 0.2).unwrap();
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 50));
+        let pane_ids = tree.all_pane_ids();
+        let widths: Vec<u32> = pane_ids
+            .iter()
+            .map(|id| tree.get_pane(*id).unwrap().bounds.width)
+            .collect();
+        // The outer split's two halves no longer split the width evenly.
+        assert_ne!(widths[0], widths[1]);
+        assert_eq!(widths.iter().sum::<u32>(), 100);
+    }
+
+    #[test]
+    fn test_resize_reduces_into_the_opposite_neighbor_once_the_primary_is_saturated() {
+        // A flat three-way horizontal split - only reachable via a template,
+        // since `split_focused` always nests binary splits - so the middle
+        // child's primary neighbor can be driven to MIN_PERCENT, forcing the
+        // resize to reduce into the far neighbor instead.
+        let template = LayoutTemplate::Horizontal {
+            children: vec![
+                LayoutTemplate::Pane {
+                    title: None,
+                    cwd: None,
+                    command: None,
+                    size: Some(Dimension::Percent(0.34)),
+                },
+                LayoutTemplate::Pane {
+                    title: None,
+                    cwd: None,
+                    command: None,
+                    size: Some(Dimension::Percent(0.33)),
+                },
+                LayoutTemplate::Pane {
+                    title: None,
+                    cwd: None,
+                    command: None,
+                    size: Some(Dimension::Percent(0.33)),
+                },
+            ],
+            size: None,
+        };
+        let mut tree = LayoutTree::from_template(&template, |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        })
+        .unwrap();
+        let pane_ids = tree.all_pane_ids();
+        let middle_id = pane_ids[1];
+        let last_id = pane_ids[2];
+
+        // Drive the last pane (the middle pane's primary neighbor) down to
+        // MIN_PERCENT first, which grows the middle pane as a side effect.
+        tree.set_focus(last_id);
+        tree.resize_focused(SplitDirection::Horizontal, -0.9).unwrap();
+
+        tree.calculate_layout(Rect::new(0, 0, 120, 50));
+        let before: Vec<u32> = pane_ids
+            .iter()
+            .map(|id| tree.get_pane(*id).unwrap().bounds.width)
+            .collect();
+
+        // Grow the middle pane further: its primary neighbor (the last
+        // pane) is already at MIN_PERCENT and has no more to give, so the
+        // leftover should be pulled from the opposite neighbor (the first
+        // pane) instead of the resize silently doing nothing.
+        tree.set_focus(middle_id);
+        tree.resize_focused(SplitDirection::Horizontal, 0.3).unwrap();
+        tree.calculate_layout(Rect::new(0, 0, 120, 50));
+        let after: Vec<u32> = pane_ids
+            .iter()
+            .map(|id| tree.get_pane(*id).unwrap().bounds.width)
+            .collect();
+
+        assert!(after[1] > before[1], "focused pane should have grown");
+        assert!(after[0] < before[0], "far neighbor should give up the space");
+        assert_eq!(after.iter().sum::<u32>(), 120);
+    }
+
+    #[test]
+    fn test_layout_tree_max_panes() {
+        let pane1 = create_test_pane();
+        let mut tree = LayoutTree::new(pane1);
+        
+        // Add 7 more panes (total 8)
+        for _ in 0..7 {
+            let new_pane = create_test_pane();
+            tree.split_focused(SplitDirection::Horizontal, new_pane).unwrap();
+        }
+        
+        assert_eq!(tree.pane_count(), 8);
+        
+        // Try to add 9th pane (should fail)
+        let pane9 = create_test_pane();
+        let result = tree.split_focused(SplitDirection::Horizontal, pane9);
+        assert!(result.is_err());
+        assert_eq!(tree.pane_count(), 8);
+    }
+
+    #[test]
+    fn test_layout_node_find_pane() {
+        let pane1 = create_test_pane();
+        let pane1_id = pane1.id;
+        let pane2 = create_test_pane();
+        let pane2_id = pane2.id;
+        
+        let node = LayoutNode::HorizontalSplit {
+            children: vec![
+                Box::new(LayoutNode::Pane(pane1)),
+                Box::new(LayoutNode::Pane(pane2)),
+            ],
+            dimensions: vec![Dimension::Percent(0.5), Dimension::Percent(0.5)],
+        };
+        
+        assert!(node.find_pane(pane1_id).is_some());
+        assert!(node.find_pane(pane2_id).is_some());
+        assert!(node.find_pane(Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn test_layout_node_collect_pane_ids() {
+        let pane1 = create_test_pane();
+        let pane1_id = pane1.id;
+        let pane2 = create_test_pane();
+        let pane2_id = pane2.id;
+        
+        let node = LayoutNode::HorizontalSplit {
+            children: vec![
+                Box::new(LayoutNode::Pane(pane1)),
+                Box::new(LayoutNode::Pane(pane2)),
+            ],
+            dimensions: vec![Dimension::Percent(0.5), Dimension::Percent(0.5)],
+        };
+        
+        let ids = node.collect_pane_ids();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&pane1_id));
+        assert!(ids.contains(&pane2_id));
+    }
+
+    #[test]
+    fn test_to_layout_string_records_split_direction_and_sizes() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused_with_dimension(SplitDirection::Horizontal, pane2, Dimension::Fixed(30))
+            .unwrap();
+
+        let layout = tree.to_layout_string();
+        assert!(layout.starts_with("horizontal {"));
+        assert!(layout.contains("size=30cells"));
+        assert!(layout.contains("size=100%"));
+        assert!(layout.contains("pane"));
+    }
+
+    #[test]
+    fn test_layout_string_roundtrips_through_parse() {
+        let pane1 = create_test_pane();
+        let pane2 = create_test_pane();
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Vertical, pane2).unwrap();
+
+        let layout = tree.to_layout_string();
+        let rebuilt = LayoutTree::from_layout_string(&layout, |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        })
+        .unwrap();
+
+        assert_eq!(rebuilt.pane_count(), 2);
+        assert_eq!(rebuilt.to_layout_string(), layout);
+    }
+
+    #[test]
+    fn test_from_layout_string_passes_pane_metadata_to_spawn() {
+        let mut seen_cwd = None;
+        let layout = r#"pane { title="shell" cwd="/tmp" cmd="/bin/bash" }"#;
+
+        let tree = LayoutTree::from_layout_string(layout, |spec| {
+            seen_cwd = spec.working_dir.clone();
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        })
+        .unwrap();
+
+        assert_eq!(seen_cwd, Some("/tmp".to_string()));
+        assert_eq!(tree.pane_count(), 1);
+        assert_eq!(tree.focused_pane().unwrap().title, "shell");
+    }
+
+    #[test]
+    fn test_from_layout_string_rejects_malformed_nesting() {
+        let result = LayoutTree::from_layout_string("horizontal { pane", |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_layout_string_rejects_too_many_panes() {
+        let mut layout = "horizontal { ".to_string();
+        for _ in 0..9 {
+            layout.push_str("pane ");
+        }
+        layout.push('}');
+
+        let result = LayoutTree::from_layout_string(&layout, |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_template_builds_splits_and_passes_leaf_metadata() {
+        let template = LayoutTemplate::Horizontal {
+            children: vec![
+                LayoutTemplate::Pane {
+                    title: Some("editor".to_string()),
+                    cwd: Some("/tmp".to_string()),
+                    command: Some("$EDITOR".to_string()),
+                    size: Some(Dimension::Percent(0.7)),
+                },
+                LayoutTemplate::Pane {
+                    title: None,
+                    cwd: None,
+                    command: None,
+                    size: None,
+                },
+            ],
+            size: None,
+        };
+
+        let mut seen_commands = Vec::new();
+        let tree = LayoutTree::from_template(&template, |spec| {
+            seen_commands.push(spec.command.clone());
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        })
+        .unwrap();
+
+        assert_eq!(tree.pane_count(), 2);
+        assert_eq!(seen_commands, vec![Some("$EDITOR".to_string()), None]);
+        assert_eq!(tree.focused_pane().unwrap().title, "editor");
+    }
+
+    #[test]
+    fn test_from_template_defaults_unspecified_sizes_to_equal_shares() {
+        let template = LayoutTemplate::Vertical {
+            children: vec![
+                LayoutTemplate::Pane { title: None, cwd: None, command: None, size: None },
+                LayoutTemplate::Pane { title: None, cwd: None, command: None, size: None },
+            ],
+            size: None,
+        };
 
-    fn create_test_pane() -> Pane {
-        let pty = PtySession::spawn(PtyConfig::default()).unwrap();
-        Pane::new(pty, 80, 24, Rect::new(0, 0, 800, 600))
+        let mut tree = LayoutTree::from_template(&template, |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        })
+        .unwrap();
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 100));
+        let pane_ids = tree.all_pane_ids();
+        let heights: Vec<u32> = pane_ids
+            .iter()
+            .map(|id| tree.get_pane(*id).unwrap().bounds.height)
+            .collect();
+        assert_eq!(heights, vec![50, 50]);
     }
 
-    // Helper function for placeholder panes (used in split logic)
-    // Uses same no-PTY approach to avoid test failures on Windows
-    fn create_placeholder_pane() -> Pane {
-        use std::sync::{Arc, Mutex};
-        use uuid::Uuid;
+    #[test]
+    fn test_from_template_rejects_too_many_panes() {
+        let children = (0..9)
+            .map(|_| LayoutTemplate::Pane { title: None, cwd: None, command: None, size: None })
+            .collect();
+        let template = LayoutTemplate::Horizontal { children, size: None };
+
+        let result = LayoutTree::from_template(&template, |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        });
+        assert!(result.is_err());
+    }
 
-        Pane {
-            id: Uuid::new_v4(),
-            title: "Placeholder".to_string(),
-            pty: Arc::new(Mutex::new(None)),
-            grid: TerminalGrid::with_size(1, 1),
-            parser: TerminalParser::new(),
-            bounds: Rect::new(0, 0, 1, 1),
-        }
+    #[test]
+    fn test_dimension_roundtrips_through_toml_string() {
+        let fixed: Dimension = "30cells".parse().unwrap();
+        let percent: Dimension = "50%".parse().unwrap();
+        assert_eq!(fixed, Dimension::Fixed(30));
+        assert_eq!(percent, Dimension::Percent(0.5));
+        assert_eq!(fixed.to_string(), "30cells");
+        assert_eq!(percent.to_string(), "50%");
     }
 
     #[test]
-    fn test_rect_creation() {
-        let rect = Rect::new(10, 20, 100, 200);
-        assert_eq!(rect.x, 10);
-        assert_eq!(rect.y, 20);
-        assert_eq!(rect.width, 100);
-        assert_eq!(rect.height, 200);
+    fn test_spawn_floating_gives_it_focus_and_geometry() {
+        let pane1 = create_test_pane();
+        let mut tree = LayoutTree::new(pane1);
+
+        let float_pane = create_test_pane();
+        let float_rect = Rect::new(10, 10, 40, 20);
+        let float_id = tree.spawn_floating(float_pane, float_rect);
+
+        assert_eq!(tree.pane_count(), 1); // tiled count is unaffected
+        assert_eq!(tree.floating().len(), 1);
+        assert_eq!(tree.focused_pane_id(), float_id);
+        assert_eq!(tree.focused_pane().unwrap().id, float_id);
+        assert_eq!(tree.floating()[0].rect, float_rect);
     }
 
     #[test]
-    fn test_rect_contains() {
-        let rect = Rect::new(10, 20, 100, 200);
-        assert!(rect.contains(50, 100)); // Inside
-        assert!(rect.contains(10, 20)); // On edge
-        assert!(!rect.contains(200, 100)); // Outside X
-        assert!(!rect.contains(50, 300)); // Outside Y
+    fn test_toggle_floating_focus_moves_between_tiled_and_floating() {
+        let pane1 = create_test_pane();
+        let tiled_id = pane1.id;
+        let mut tree = LayoutTree::new(pane1);
+
+        assert!(!tree.toggle_floating_focus()); // nothing floating yet
+
+        let float_id = tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 20, 10));
+        assert_eq!(tree.focused_pane_id(), float_id);
+
+        assert!(tree.toggle_floating_focus());
+        assert_eq!(tree.focused_pane_id(), tiled_id);
+
+        assert!(tree.toggle_floating_focus());
+        assert_eq!(tree.focused_pane_id(), float_id);
     }
 
     #[test]
-    fn test_pane_creation() {
-        let pane = create_test_pane();
-        assert!(!pane.id.is_nil());
-        assert_eq!(pane.title, "Terminal");
+    fn test_move_floating_clamps_to_terminal_bounds() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 20, 10));
+
+        let terminal_bounds = Rect::new(0, 0, 50, 30);
+        tree.move_floating(1000, 1000, terminal_bounds).unwrap();
+
+        let rect = tree.floating()[0].rect;
+        assert_eq!(rect.x, 30); // 50 - 20
+        assert_eq!(rect.y, 20); // 30 - 10
     }
 
     #[test]
-    fn test_pane_terminal_size() {
-        let pane = create_test_pane();
-        let (cols, rows) = pane.terminal_size(10, 20);
-        assert_eq!(cols, 80);
-        assert_eq!(rows, 30);
+    fn test_resize_floating_clamps_to_minimum_and_bounds() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        tree.spawn_floating(create_test_pane(), Rect::new(5, 5, 10, 10));
+
+        let terminal_bounds = Rect::new(0, 0, 50, 30);
+        tree.resize_floating(-100, -100, terminal_bounds).unwrap();
+        assert_eq!(tree.floating()[0].rect.width, MIN_FLOATING_SIZE);
+        assert_eq!(tree.floating()[0].rect.height, MIN_FLOATING_SIZE);
+
+        tree.resize_floating(1000, 1000, terminal_bounds).unwrap();
+        let rect = tree.floating()[0].rect;
+        assert_eq!(rect.width, 45); // 50 - x(5)
+        assert_eq!(rect.height, 25); // 30 - y(5)
     }
 
     #[test]
-    fn test_layout_tree_creation() {
-        let pane = create_test_pane();
-        let pane_id = pane.id;
-        let tree = LayoutTree::new(pane);
-        
-        assert_eq!(tree.focused_pane_id(), pane_id);
+    fn test_close_focused_floating_always_succeeds_and_restores_focus() {
+        let pane1 = create_test_pane();
+        let tiled_id = pane1.id;
+        let mut tree = LayoutTree::new(pane1);
+
+        tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 20, 10));
+        tree.close_focused().unwrap();
+
+        assert_eq!(tree.floating().len(), 0);
         assert_eq!(tree.pane_count(), 1);
+        assert_eq!(tree.focused_pane_id(), tiled_id);
     }
 
     #[test]
-    fn test_layout_tree_focused_pane() {
-        let pane = create_test_pane();
-        let pane_id = pane.id;
-        let mut tree = LayoutTree::new(pane);
-        
-        let focused = tree.focused_pane();
-        assert!(focused.is_some());
-        assert_eq!(focused.unwrap().id, pane_id);
+    fn test_close_focused_forbids_closing_last_tiled_pane_even_with_floating() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 20, 10));
+        tree.toggle_floating_focus(); // back to the sole tiled pane
+
+        let result = tree.close_focused();
+        assert!(result.is_err());
+        assert_eq!(tree.pane_count(), 1);
     }
 
     #[test]
-    fn test_layout_node_pane_count() {
+    fn test_toggle_zoom_gives_the_focused_pane_the_full_bounds() {
         let pane1 = create_test_pane();
+        let pane1_id = pane1.id;
         let pane2 = create_test_pane();
-        
-        let node = LayoutNode::HorizontalSplit {
-            children: vec![
-                Box::new(LayoutNode::Pane(pane1)),
-                Box::new(LayoutNode::Pane(pane2)),
-            ],
-            ratios: vec![0.5, 0.5],
-        };
-        
-        assert_eq!(node.pane_count(), 2);
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        tree.set_focus(pane1_id);
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 50));
+        assert_ne!(tree.get_pane(pane1_id).unwrap().bounds.width, 100);
+
+        tree.toggle_zoom();
+        assert_eq!(tree.zoomed_pane_id(), Some(pane1_id));
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 50));
+        assert_eq!(tree.get_pane(pane1_id).unwrap().bounds, Rect::new(0, 0, 100, 50));
+
+        tree.toggle_zoom();
+        assert_eq!(tree.zoomed_pane_id(), None);
+
+        tree.calculate_layout(Rect::new(0, 0, 100, 50));
+        assert_ne!(tree.get_pane(pane1_id).unwrap().bounds.width, 100);
     }
 
     #[test]
-    fn test_layout_tree_split_horizontal() {
+    fn test_focus_change_clears_zoom() {
         let pane1 = create_test_pane();
         let pane1_id = pane1.id;
         let pane2 = create_test_pane();
         let pane2_id = pane2.id;
-        
+
         let mut tree = LayoutTree::new(pane1);
         tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
-        
-        assert_eq!(tree.pane_count(), 2);
-        assert_eq!(tree.focused_pane_id(), pane2_id);
-        assert!(tree.get_pane(pane1_id).is_some());
-        assert!(tree.get_pane(pane2_id).is_some());
+        tree.set_focus(pane1_id);
+        tree.toggle_zoom();
+        assert_eq!(tree.zoomed_pane_id(), Some(pane1_id));
+
+        tree.set_focus(pane2_id);
+        assert_eq!(tree.zoomed_pane_id(), None);
     }
 
     #[test]
-    fn test_layout_tree_split_vertical() {
+    fn test_splitting_clears_zoom() {
         let pane1 = create_test_pane();
-        let pane2 = create_test_pane();
-        
         let mut tree = LayoutTree::new(pane1);
-        tree.split_focused(SplitDirection::Vertical, pane2).unwrap();
-        
-        assert_eq!(tree.pane_count(), 2);
-        assert_eq!(tree.root().pane_count(), 2);
+        tree.toggle_zoom();
+        assert!(tree.zoomed_pane_id().is_some());
+
+        tree.split_focused(SplitDirection::Horizontal, create_test_pane()).unwrap();
+        assert_eq!(tree.zoomed_pane_id(), None);
     }
 
     #[test]
-    fn test_layout_tree_focus_navigation() {
+    fn test_apply_swap_layout_rehomes_panes_without_spawning() {
         let pane1 = create_test_pane();
         let pane1_id = pane1.id;
         let pane2 = create_test_pane();
         let pane2_id = pane2.id;
         let pane3 = create_test_pane();
         let pane3_id = pane3.id;
-        
+
         let mut tree = LayoutTree::new(pane1);
         tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
-        tree.set_focus(pane1_id);
-        tree.split_focused(SplitDirection::Vertical, pane3).unwrap();
-        
-        // Test focus_next
-        tree.set_focus(pane1_id);
-        tree.focus_next();
-        assert_eq!(tree.focused_pane_id(), pane3_id);
-        
-        tree.focus_next();
-        assert_eq!(tree.focused_pane_id(), pane2_id);
-        
-        tree.focus_next();
-        assert_eq!(tree.focused_pane_id(), pane1_id);
-        
-        // Test focus_prev
-        tree.focus_prev();
-        assert_eq!(tree.focused_pane_id(), pane2_id);
+        tree.split_focused(SplitDirection::Horizontal, pane3).unwrap();
+        let original_ids: std::collections::HashSet<_> = tree.all_pane_ids().into_iter().collect();
+
+        let mut arrangements = HashMap::new();
+        arrangements.insert(
+            3,
+            SwapLayoutNode::Vertical {
+                children: vec![
+                    SwapLayoutNode::Pane { size: None },
+                    SwapLayoutNode::Horizontal {
+                        children: vec![
+                            SwapLayoutNode::Pane { size: None },
+                            SwapLayoutNode::Pane { size: None },
+                        ],
+                        size: None,
+                    },
+                ],
+                size: None,
+            },
+        );
+        let template = SwapLayoutTemplate { arrangements };
+        tree.register_swap_layout("main-vertical", template);
+
+        tree.apply_swap_layout("main-vertical").unwrap();
+
+        assert_eq!(tree.pane_count(), 3);
+        let rehomed_ids: std::collections::HashSet<_> = tree.all_pane_ids().into_iter().collect();
+        assert_eq!(original_ids, rehomed_ids);
+        assert!(tree.get_pane(pane1_id).is_some());
+        assert!(tree.get_pane(pane2_id).is_some());
+        assert!(tree.get_pane(pane3_id).is_some());
+        assert!(matches!(tree.root(), LayoutNode::VerticalSplit { .. }));
     }
 
     #[test]
-    fn test_layout_tree_calculate_layout() {
+    fn test_apply_swap_layout_preserves_focus() {
         let pane1 = create_test_pane();
         let pane2 = create_test_pane();
-        
+        let pane2_id = pane2.id;
+
         let mut tree = LayoutTree::new(pane1);
         tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
-        
-        let total_bounds = Rect::new(0, 0, 1000, 800);
-        tree.calculate_layout(total_bounds);
-        
-        // Check that panes have bounds set
-        let pane_ids = tree.all_pane_ids();
-        for id in pane_ids {
-            let pane = tree.get_pane(id).unwrap();
-            assert!(pane.bounds.width > 0);
-            assert!(pane.bounds.height > 0);
-        }
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+
+        let mut arrangements = HashMap::new();
+        arrangements.insert(
+            2,
+            SwapLayoutNode::Vertical {
+                children: vec![
+                    SwapLayoutNode::Pane { size: None },
+                    SwapLayoutNode::Pane { size: None },
+                ],
+                size: None,
+            },
+        );
+        let template = SwapLayoutTemplate { arrangements };
+        tree.register_swap_layout("even-vertical", template);
+
+        tree.apply_swap_layout("even-vertical").unwrap();
+        assert_eq!(tree.focused_pane_id(), pane2_id);
     }
 
     #[test]
-    fn test_layout_tree_max_panes() {
-        let pane1 = create_test_pane();
-        let mut tree = LayoutTree::new(pane1);
-        
-        // Add 7 more panes (total 8)
-        for _ in 0..7 {
-            let new_pane = create_test_pane();
-            tree.split_focused(SplitDirection::Horizontal, new_pane).unwrap();
-        }
-        
-        assert_eq!(tree.pane_count(), 8);
-        
-        // Try to add 9th pane (should fail)
-        let pane9 = create_test_pane();
-        let result = tree.split_focused(SplitDirection::Horizontal, pane9);
+    fn test_apply_swap_layout_errors_when_no_arrangement_for_pane_count() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        tree.register_swap_layout("tiled", SwapLayoutTemplate::default());
+
+        let result = tree.apply_swap_layout("tiled");
         assert!(result.is_err());
-        assert_eq!(tree.pane_count(), 8);
+        assert_eq!(tree.pane_count(), 1);
     }
 
     #[test]
-    fn test_layout_node_find_pane() {
+    fn test_apply_swap_layout_errors_for_unregistered_name() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        let result = tree.apply_swap_layout("does-not-exist");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_apply_swap_layout_fills_extra_slots_with_placeholders() {
         let pane1 = create_test_pane();
         let pane1_id = pane1.id;
-        let pane2 = create_test_pane();
-        let pane2_id = pane2.id;
-        
-        let node = LayoutNode::HorizontalSplit {
-            children: vec![
-                Box::new(LayoutNode::Pane(pane1)),
-                Box::new(LayoutNode::Pane(pane2)),
-            ],
-            ratios: vec![0.5, 0.5],
+        let mut tree = LayoutTree::new(pane1);
+
+        let mut arrangements = HashMap::new();
+        arrangements.insert(
+            1,
+            SwapLayoutNode::Horizontal {
+                children: vec![
+                    SwapLayoutNode::Pane { size: None },
+                    SwapLayoutNode::Pane { size: None },
+                ],
+                size: None,
+            },
+        );
+        tree.register_swap_layout("split-with-room-to-grow", SwapLayoutTemplate { arrangements });
+
+        tree.apply_swap_layout("split-with-room-to-grow").unwrap();
+
+        assert_eq!(tree.pane_count(), 2);
+        assert!(tree.get_pane(pane1_id).is_some());
+    }
+
+    #[test]
+    fn test_next_and_prev_swap_layout_cycle_through_registered_names() {
+        let mut tree = LayoutTree::new(create_test_pane());
+
+        let single = |n: usize| {
+            let mut arrangements = HashMap::new();
+            arrangements.insert(n, SwapLayoutNode::Pane { size: None });
+            SwapLayoutTemplate { arrangements }
         };
-        
-        assert!(node.find_pane(pane1_id).is_some());
-        assert!(node.find_pane(pane2_id).is_some());
-        assert!(node.find_pane(Uuid::new_v4()).is_none());
+        tree.register_swap_layout("tiled", single(1));
+        tree.register_swap_layout("even-horizontal", single(1));
+
+        tree.next_swap_layout().unwrap();
+        assert_eq!(tree.active_swap_layout, Some(0));
+
+        tree.next_swap_layout().unwrap();
+        assert_eq!(tree.active_swap_layout, Some(1));
+
+        tree.next_swap_layout().unwrap();
+        assert_eq!(tree.active_swap_layout, Some(0));
+
+        tree.prev_swap_layout().unwrap();
+        assert_eq!(tree.active_swap_layout, Some(1));
     }
 
     #[test]
-    fn test_layout_node_collect_pane_ids() {
+    fn test_serialize_produces_an_indented_manifest_with_ratios_and_titles() {
+        let mut pane1 = create_test_pane();
+        pane1.title = "left".to_string();
+        let mut pane2 = create_test_pane();
+        pane2.title = "right".to_string();
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused_with_dimension(
+            SplitDirection::Horizontal,
+            pane2,
+            Dimension::Percent(0.3),
+        )
+        .unwrap();
+
+        let manifest = tree.serialize();
+        assert!(manifest.contains('\n'), "expected indented, multi-line output");
+        assert!(manifest.contains("\"left\""));
+        assert!(manifest.contains("\"right\""));
+        assert!(manifest.contains("30%") || manifest.contains("0.3"));
+    }
+
+    #[test]
+    fn test_from_manifest_round_trips_shape_and_focus() {
+        let mut pane1 = create_test_pane();
+        pane1.title = "left".to_string();
+        let mut pane2 = create_test_pane();
+        pane2.title = "right".to_string();
+        let pane2_id = pane2.id;
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+
+        let manifest = tree.serialize();
+
+        let mut seen_titles = Vec::new();
+        let restored = LayoutTree::from_manifest(&manifest, |spec| {
+            seen_titles.push(spec.title.clone());
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        })
+        .unwrap();
+
+        assert_eq!(restored.pane_count(), 2);
+        assert_eq!(seen_titles, vec![Some("left".to_string()), Some("right".to_string())]);
+        // The original focused pane was the second (rightmost) leaf, so the
+        // respawned tree's focus should land on that same position.
+        assert_eq!(restored.focused_pane().unwrap().title, "right");
+    }
+
+    #[test]
+    fn test_from_manifest_rejects_invalid_json() {
+        let result = LayoutTree::from_manifest("not json", |_spec| {
+            PtySession::spawn(PtyConfig::default()).unwrap()
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_float_focused_moves_tiled_pane_into_floating_set() {
         let pane1 = create_test_pane();
-        let pane1_id = pane1.id;
         let pane2 = create_test_pane();
         let pane2_id = pane2.id;
-        
-        let node = LayoutNode::HorizontalSplit {
-            children: vec![
-                Box::new(LayoutNode::Pane(pane1)),
-                Box::new(LayoutNode::Pane(pane2)),
-            ],
-            ratios: vec![0.5, 0.5],
-        };
-        
-        let ids = node.collect_pane_ids();
-        assert_eq!(ids.len(), 2);
-        assert!(ids.contains(&pane1_id));
-        assert!(ids.contains(&pane2_id));
+
+        let mut tree = LayoutTree::new(pane1);
+        tree.split_focused(SplitDirection::Horizontal, pane2).unwrap();
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+        assert_eq!(tree.pane_count(), 2);
+
+        tree.float_focused(Rect::new(5, 5, 30, 15)).unwrap();
+
+        assert_eq!(tree.pane_count(), 1);
+        assert_eq!(tree.floating().len(), 1);
+        assert_eq!(tree.focused_pane_id(), pane2_id);
+        assert!(tree.get_pane(pane2_id).is_none());
+    }
+
+    #[test]
+    fn test_float_focused_forbids_floating_the_last_tiled_pane() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        let result = tree.float_focused(Rect::new(0, 0, 20, 10));
+        assert!(result.is_err());
+        assert_eq!(tree.pane_count(), 1);
+        assert_eq!(tree.floating().len(), 0);
+    }
+
+    #[test]
+    fn test_unfloat_focused_moves_floating_pane_back_into_the_tiled_tree() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        let float_id = tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 20, 10));
+        assert_eq!(tree.focused_pane_id(), float_id);
+
+        tree.unfloat_focused(SplitDirection::Vertical).unwrap();
+
+        assert_eq!(tree.floating().len(), 0);
+        assert_eq!(tree.pane_count(), 2);
+        assert_eq!(tree.focused_pane_id(), float_id);
+        assert!(tree.get_pane(float_id).is_some());
+    }
+
+    #[test]
+    fn test_unfloat_focused_errors_when_nothing_floating_has_focus() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        let result = tree.unfloat_focused(SplitDirection::Horizontal);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_raise_and_lower_focused_floating_reorders_z_index() {
+        let mut tree = LayoutTree::new(create_test_pane());
+        let bottom_id = tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 10, 10));
+        let top_id = tree.spawn_floating(create_test_pane(), Rect::new(0, 0, 10, 10));
+
+        // `top_id` was spawned last, so it's already topmost; lower it
+        // beneath `bottom_id`.
+        tree.lower_focused_floating().unwrap();
+        assert_eq!(
+            tree.floating().iter().max_by_key(|f| f.z_index).unwrap().pane.id,
+            bottom_id
+        );
+
+        tree.set_focus(top_id);
+        tree.raise_focused_floating().unwrap();
+        assert_eq!(
+            tree.floating().iter().max_by_key(|f| f.z_index).unwrap().pane.id,
+            top_id
+        );
     }
 }
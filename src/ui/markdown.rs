@@ -0,0 +1,158 @@
+//! Minimal markdown segmentation for AI responses
+//!
+//! Splits a response into prose and fenced code block segments so the UI
+//! can render code distinctly and offer per-block actions (copy, extract
+//! as a runnable command) instead of showing everything as flat text.
+//! Only triple-backtick fences are recognized - the providers in `ai/`
+//! always fence code, so indented code blocks aren't worth the extra
+//! parsing complexity.
+
+/// A parsed segment of a markdown-formatted response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseSegment {
+    /// Plain prose, rendered as-is.
+    Text(String),
+    /// A fenced code block.
+    Code(CodeBlock),
+}
+
+/// A fenced code block extracted from a response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    /// The language tag on the opening fence (e.g. ` ```bash `), if any.
+    pub language: Option<String>,
+    pub code: String,
+}
+
+/// Split `markdown` into an ordered list of prose and code segments.
+pub fn parse(markdown: &str) -> Vec<ResponseSegment> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut lines = markdown.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        match line.trim_start().strip_prefix("```") {
+            Some(lang_tag) => {
+                if !text.is_empty() {
+                    segments.push(ResponseSegment::Text(
+                        text.trim_end_matches('\n').to_string(),
+                    ));
+                    text = String::new();
+                }
+
+                let language = {
+                    let trimmed = lang_tag.trim();
+                    (!trimmed.is_empty()).then(|| trimmed.to_string())
+                };
+
+                let mut code = String::new();
+                for code_line in lines.by_ref() {
+                    if code_line.trim_start().starts_with("```") {
+                        break;
+                    }
+                    code.push_str(code_line);
+                    code.push('\n');
+                }
+
+                segments.push(ResponseSegment::Code(CodeBlock {
+                    language,
+                    code: code.trim_end_matches('\n').to_string(),
+                }));
+            }
+            None => {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push(ResponseSegment::Text(
+            text.trim_end_matches('\n').to_string(),
+        ));
+    }
+
+    segments
+}
+
+/// The first code block in a parsed response, if any - used to promote a
+/// suggested snippet into the same confirm-before-run flow as a
+/// model-proposed `run_command` tool call.
+pub fn first_code_block(segments: &[ResponseSegment]) -> Option<&CodeBlock> {
+    segments.iter().find_map(|segment| match segment {
+        ResponseSegment::Code(code_block) => Some(code_block),
+        ResponseSegment::Text(_) => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_text_is_a_single_text_segment() {
+        let segments = parse("just some prose\nover two lines");
+        assert_eq!(
+            segments,
+            vec![ResponseSegment::Text(
+                "just some prose\nover two lines".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_extracts_language_tag() {
+        let segments = parse("Run this:\n```bash\nls -la\n```\nThen check the output.");
+        assert_eq!(
+            segments,
+            vec![
+                ResponseSegment::Text("Run this:".to_string()),
+                ResponseSegment::Code(CodeBlock {
+                    language: Some("bash".to_string()),
+                    code: "ls -la".to_string(),
+                }),
+                ResponseSegment::Text("Then check the output.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_fence_without_language_tag() {
+        let segments = parse("```\necho hi\n```");
+        assert_eq!(
+            segments,
+            vec![ResponseSegment::Code(CodeBlock {
+                language: None,
+                code: "echo hi".to_string(),
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_multiple_code_blocks() {
+        let segments = parse("```sh\nfirst\n```\nmiddle\n```sh\nsecond\n```");
+        assert_eq!(
+            segments,
+            vec![
+                ResponseSegment::Code(CodeBlock {
+                    language: Some("sh".to_string()),
+                    code: "first".to_string(),
+                }),
+                ResponseSegment::Text("middle".to_string()),
+                ResponseSegment::Code(CodeBlock {
+                    language: Some("sh".to_string()),
+                    code: "second".to_string(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_code_block_skips_leading_text() {
+        let segments = parse("no code here");
+        assert!(first_code_block(&segments).is_none());
+
+        let segments = parse("prose\n```\nfirst\n```\n```\nsecond\n```");
+        assert_eq!(first_code_block(&segments).unwrap().code, "first");
+    }
+}
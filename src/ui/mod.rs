@@ -1,9 +1,18 @@
 //! GPU-accelerated UI rendering
 
 pub mod ai_command_palette;
+pub mod cell_background;
+pub mod image;
 pub mod input;
+pub mod keyset_dispatch;
 pub mod layout;
+pub mod markdown;
+pub mod postprocess;
 pub mod renderer;
 pub mod selection;
+pub mod shapes;
 pub mod status_bar;
 pub mod text;
+pub mod theme;
+pub mod vi_mode;
+pub mod workflow_prompt;
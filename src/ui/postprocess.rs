@@ -0,0 +1,489 @@
+//! RetroArch-style post-processing shader chain for `Renderer`.
+//!
+//! Loading a preset (see `parse_preset`) swaps `Renderer::render`'s direct
+//! grid-to-surface draw for: render the grid+text into an offscreen color
+//! texture, then run each preset pass as a fullscreen-triangle draw that
+//! samples the previous pass's output (the first pass samples the offscreen
+//! terminal render), with the last pass writing straight to the surface.
+//! Passes can render at a different resolution than the window via their
+//! `scale` factor - smaller for a cheap pre-blur, larger for supersampled
+//! scanlines - and `PostProcess::resize` rebuilds every intermediate texture
+//! from the new window size. This lets users drop in a CRT/bloom/scanline
+//! look (or anything else expressible as a chain of WGSL fragment shaders)
+//! without recompiling.
+//!
+//! Note: `main.rs`'s actual live render path draws through its own
+//! `RendererHolder`, a separate struct that doesn't (yet) use this
+//! `Renderer`/`PostProcess` pair. This module wires fully into `Renderer` as
+//! a standalone capability; adopting it in `RendererHolder` is a larger,
+//! separate change to that already-shipped render path.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use thiserror::Error;
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+
+#[derive(Error, Debug)]
+pub enum PostProcessError {
+    #[error("Failed to read shader {0:?}: {1}")]
+    ShaderIo(PathBuf, String),
+
+    #[error("Preset line {0} is missing '=': {1:?}")]
+    MalformedLine(usize, String),
+
+    #[error("Preset is missing required key {0:?}")]
+    MissingKey(String),
+
+    #[error("Preset declares 0 passes")]
+    Empty,
+
+    #[error("Invalid scale factor on pass {0}: {1:?}")]
+    InvalidScale(usize, String),
+
+    #[error("Unknown filter mode on pass {0}: {1:?} (expected \"linear\" or \"nearest\")")]
+    InvalidFilter(usize, String),
+}
+
+/// Texture filtering for a pass's input sampler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    Linear,
+    Nearest,
+}
+
+impl FilterMode {
+    fn as_wgpu(self) -> wgpu::FilterMode {
+        match self {
+            FilterMode::Linear => wgpu::FilterMode::Linear,
+            FilterMode::Nearest => wgpu::FilterMode::Nearest,
+        }
+    }
+}
+
+/// One pass parsed out of a preset file: a WGSL fragment shader plus how big
+/// to render it and how to sample its input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassConfig {
+    pub shader_path: PathBuf,
+    /// Output resolution as a multiple of the window size; `1.0` renders
+    /// this pass at native resolution.
+    pub scale: f32,
+    pub filter: FilterMode,
+}
+
+/// Parse a preset file listing an ordered shader chain, in the form:
+///
+/// ```text
+/// passes = 2
+/// shader0 = crt.wgsl
+/// scale0 = 1.0
+/// filter0 = linear
+/// shader1 = bloom.wgsl
+/// scale1 = 0.5
+/// filter1 = nearest
+/// ```
+///
+/// `scaleN`/`filterN` default to `1.0`/`linear` if omitted. `shaderN` is
+/// resolved relative to `base_dir` (the preset file's own directory),
+/// mirroring RetroArch's `.slangp`/`.glslp` convention so a preset and its
+/// shaders can be dropped into a directory and moved around as a unit.
+pub fn parse_preset(source: &str, base_dir: &Path) -> Result<Vec<PassConfig>, PostProcessError> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    for (lineno, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| PostProcessError::MalformedLine(lineno + 1, line.to_string()))?;
+        values.insert(key.trim().to_string(), value.trim().to_string());
+    }
+
+    let passes: usize = values
+        .get("passes")
+        .ok_or_else(|| PostProcessError::MissingKey("passes".to_string()))?
+        .parse()
+        .map_err(|_| PostProcessError::MissingKey("passes".to_string()))?;
+    if passes == 0 {
+        return Err(PostProcessError::Empty);
+    }
+
+    (0..passes)
+        .map(|i| {
+            let shader_key = format!("shader{i}");
+            let shader = values
+                .get(&shader_key)
+                .ok_or_else(|| PostProcessError::MissingKey(shader_key.clone()))?;
+
+            let scale = values.get(&format!("scale{i}")).map(String::as_str).unwrap_or("1.0");
+            let scale: f32 = scale
+                .parse()
+                .map_err(|_| PostProcessError::InvalidScale(i, scale.to_string()))?;
+
+            let filter = values.get(&format!("filter{i}")).map(String::as_str).unwrap_or("linear");
+            let filter = match filter {
+                "linear" => FilterMode::Linear,
+                "nearest" => FilterMode::Nearest,
+                other => return Err(PostProcessError::InvalidFilter(i, other.to_string())),
+            };
+
+            Ok(PassConfig { shader_path: base_dir.join(shader), scale, filter })
+        })
+        .collect()
+}
+
+/// The user's shader presets directory (`~/.config/warp-foss/shaders/`),
+/// honoring `$XDG_CONFIG_HOME` the same way `ui::theme::user_themes_dir`
+/// resolves themes.
+pub fn user_shaders_dir() -> Result<PathBuf> {
+    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+        return Ok(PathBuf::from(xdg_config).join("warp-foss").join("shaders"));
+    }
+
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home.join(".config").join("warp-foss").join("shaders"))
+}
+
+/// Load and parse `<dir>/<name>.preset`.
+pub fn load_preset(dir: &Path, name: &str) -> Result<Vec<PassConfig>, PostProcessError> {
+    let path = dir.join(format!("{name}.preset"));
+    let source = fs::read_to_string(&path).map_err(|e| PostProcessError::ShaderIo(path.clone(), e.to_string()))?;
+    parse_preset(&source, dir)
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PassUniform {
+    source_resolution: [f32; 2],
+    output_resolution: [f32; 2],
+}
+
+/// One pass's pipeline and its own intermediate render target (unused for
+/// the chain's last pass, which renders straight into the caller's target).
+struct Pass {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    scale: f32,
+    view: TextureView,
+    width: u32,
+    height: u32,
+}
+
+impl Pass {
+    fn new(
+        device: &Device,
+        format: TextureFormat,
+        config: &PassConfig,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, PostProcessError> {
+        let source = fs::read_to_string(&config.shader_path)
+            .map_err(|e| PostProcessError::ShaderIo(config.shader_path.clone(), e.to_string()))?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(&format!("Post-process Pass Shader: {}", config.shader_path.display())),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Post-process Pass Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post-process Pass Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Post-process Pass Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post-process Pass Sampler"),
+            mag_filter: config.filter.as_wgpu(),
+            min_filter: config.filter.as_wgpu(),
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Post-process Pass Uniform Buffer"),
+            size: std::mem::size_of::<PassUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let (width, height) = Self::scaled_size(config.scale, width, height);
+        let view = Self::create_target(device, format, width, height);
+
+        Ok(Self { pipeline, bind_group_layout, sampler, uniform_buffer, scale: config.scale, view, width, height })
+    }
+
+    fn scaled_size(scale: f32, width: u32, height: u32) -> (u32, u32) {
+        let width = ((width as f32) * scale).round().max(1.0) as u32;
+        let height = ((height as f32) * scale).round().max(1.0) as u32;
+        (width, height)
+    }
+
+    fn create_target(device: &Device, format: TextureFormat, width: u32, height: u32) -> TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Post-process Pass Target"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    fn resize(&mut self, device: &Device, format: TextureFormat, width: u32, height: u32) {
+        let (width, height) = Self::scaled_size(self.scale, width, height);
+        self.view = Self::create_target(device, format, width, height);
+        self.width = width;
+        self.height = height;
+    }
+
+    fn bind_group(&self, device: &Device, source_view: &TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Post-process Pass Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: self.uniform_buffer.as_entire_binding() },
+            ],
+        })
+    }
+}
+
+/// An ordered shader chain loaded from a preset, run as a post-process over
+/// the offscreen terminal render target (see `Renderer::render`).
+pub struct PostProcess {
+    passes: Vec<Pass>,
+    format: TextureFormat,
+}
+
+impl PostProcess {
+    /// Build every pass's pipeline and intermediate texture, sized from
+    /// `width`/`height` (the current window size) and each pass's scale.
+    pub fn new(
+        device: &Device,
+        format: TextureFormat,
+        configs: &[PassConfig],
+        width: u32,
+        height: u32,
+    ) -> Result<Self, PostProcessError> {
+        if configs.is_empty() {
+            return Err(PostProcessError::Empty);
+        }
+        let passes =
+            configs.iter().map(|config| Pass::new(device, format, config, width, height)).collect::<Result<_, _>>()?;
+        Ok(Self { passes, format })
+    }
+
+    /// Rebuild every pass's intermediate texture for the new window size.
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        for pass in &mut self.passes {
+            pass.resize(device, self.format, width, height);
+        }
+    }
+
+    /// How many passes this chain runs.
+    pub fn pass_count(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Run the full chain: the first pass samples `source_view` (the
+    /// offscreen terminal render), each later pass samples the previous
+    /// pass's output, and the last pass writes to `target_view` (the
+    /// surface) instead of its own intermediate texture.
+    pub fn run(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        source_view: &TextureView,
+        source_size: (u32, u32),
+        target_view: &TextureView,
+        target_size: (u32, u32),
+    ) {
+        let last = self.passes.len() - 1;
+        let mut prev_view = source_view;
+        let mut prev_size = source_size;
+
+        for (i, pass) in self.passes.iter().enumerate() {
+            let (output_view, output_size) =
+                if i == last { (target_view, target_size) } else { (&pass.view, (pass.width, pass.height)) };
+
+            queue.write_buffer(
+                &pass.uniform_buffer,
+                0,
+                bytemuck::bytes_of(&PassUniform {
+                    source_resolution: [prev_size.0 as f32, prev_size.1 as f32],
+                    output_resolution: [output_size.0 as f32, output_size.1 as f32],
+                }),
+            );
+            let bind_group = pass.bind_group(device, prev_view);
+
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Post-process Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: output_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                render_pass.set_pipeline(&pass.pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                // A fullscreen triangle covering the whole clip space -
+                // `vs_main` is expected to derive its 3 vertices from
+                // `vertex_index` alone, the same no-vertex-buffer pattern
+                // `shaders/image.wgsl` uses for its quad.
+                render_pass.draw(0..3, 0..1);
+            }
+
+            prev_view = output_view;
+            prev_size = output_size;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_preset_defaults_scale_and_filter() {
+        let source = "passes = 1\nshader0 = crt.wgsl\n";
+        let passes = parse_preset(source, Path::new("/shaders")).unwrap();
+        assert_eq!(passes.len(), 1);
+        assert_eq!(passes[0].shader_path, Path::new("/shaders/crt.wgsl"));
+        assert_eq!(passes[0].scale, 1.0);
+        assert_eq!(passes[0].filter, FilterMode::Linear);
+    }
+
+    #[test]
+    fn test_parse_preset_reads_explicit_scale_and_filter_per_pass() {
+        let source = "\
+passes = 2
+shader0 = crt.wgsl
+scale0 = 1.0
+filter0 = linear
+shader1 = bloom.wgsl
+scale1 = 0.5
+filter1 = nearest
+";
+        let passes = parse_preset(source, Path::new("/shaders")).unwrap();
+        assert_eq!(passes.len(), 2);
+        assert_eq!(passes[1].shader_path, Path::new("/shaders/bloom.wgsl"));
+        assert_eq!(passes[1].scale, 0.5);
+        assert_eq!(passes[1].filter, FilterMode::Nearest);
+    }
+
+    #[test]
+    fn test_parse_preset_ignores_comments_and_blank_lines() {
+        let source = "# a CRT preset\n\npasses = 1\nshader0 = crt.wgsl\n";
+        let passes = parse_preset(source, Path::new("/shaders")).unwrap();
+        assert_eq!(passes.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_preset_rejects_zero_passes() {
+        let err = parse_preset("passes = 0\n", Path::new("/shaders")).unwrap_err();
+        assert!(matches!(err, PostProcessError::Empty));
+    }
+
+    #[test]
+    fn test_parse_preset_rejects_missing_shader_key() {
+        let err = parse_preset("passes = 1\n", Path::new("/shaders")).unwrap_err();
+        assert!(matches!(err, PostProcessError::MissingKey(key) if key == "shader0"));
+    }
+
+    #[test]
+    fn test_parse_preset_rejects_unknown_filter() {
+        let source = "passes = 1\nshader0 = crt.wgsl\nfilter0 = blurry\n";
+        let err = parse_preset(source, Path::new("/shaders")).unwrap_err();
+        assert!(matches!(err, PostProcessError::InvalidFilter(0, f) if f == "blurry"));
+    }
+
+    #[test]
+    fn test_parse_preset_rejects_malformed_line() {
+        let err = parse_preset("passes\npasses = 1\n", Path::new("/shaders")).unwrap_err();
+        assert!(matches!(err, PostProcessError::MalformedLine(1, _)));
+    }
+}
@@ -9,7 +9,9 @@ use thiserror::Error;
 use wgpu::{Device, PresentMode, Queue, Surface, SurfaceConfiguration, TextureViewDescriptor};
 use winit::window::Window;
 
-use super::text::{TextError, TextRenderer};
+use super::cell_background::CellBackgroundRenderer;
+use super::postprocess::{PassConfig, PostProcess, PostProcessError};
+use super::text::{Cache, TextError, TextRenderer};
 use crate::terminal::grid::TerminalGrid;
 
 #[derive(Error, Debug)]
@@ -34,22 +36,56 @@ pub enum RendererError {
 
     #[error("Text rendering error: {0}")]
     Text(#[from] TextError),
+
+    #[error("Post-process preset error: {0}")]
+    PostProcess(#[from] PostProcessError),
 }
 
 /// Default font size in pixels
 const DEFAULT_FONT_SIZE: f32 = 16.0;
 
+/// `StagingBelt` chunk size - matches the text instance buffer's initial
+/// capacity, so a typical frame's upload fits in a single chunk.
+const STAGING_BELT_CHUNK_SIZE: wgpu::BufferAddress = 1024 * 1024;
+
 /// GPU-accelerated renderer using wgpu
 pub struct Renderer<'window> {
     device: Device,
     queue: Queue,
-    surface: Surface<'window>,
+    /// `None` for a headless renderer (see `headless_target`).
+    surface: Option<Surface<'window>>,
     config: SurfaceConfiguration,
     render_pipeline: wgpu::RenderPipeline,
+    /// Shared pipeline/bind group layout/sampler, reusable across every
+    /// `TextRenderer` this `Renderer` owns.
+    text_cache: Cache,
     /// Text renderer for terminal content
     text_renderer: TextRenderer,
     /// Text bind group (recreated each frame if needed)
     text_bind_group: Option<wgpu::BindGroup>,
+    /// Offscreen target the grid+text draw into when `post_process` is
+    /// loaded, so the chain has something to sample (see `postprocess`'s
+    /// module docs). Kept around - and kept sized to the window - even
+    /// when `post_process` is `None`, so loading a preset doesn't need a
+    /// resize to allocate it.
+    offscreen_texture: wgpu::Texture,
+    offscreen_view: wgpu::TextureView,
+    /// The active shader chain, if a preset has been loaded via
+    /// `set_shader_preset`. `None` renders straight to the surface, same as
+    /// before this subsystem existed.
+    post_process: Option<PostProcess>,
+    /// `None` for a window-backed renderer (`new`), which presents into a
+    /// `Surface`. `Some` for a headless renderer (`new_headless`), which has
+    /// no `Surface` and instead renders into this owned texture so
+    /// `capture_frame` has something to read back.
+    headless_target: Option<wgpu::Texture>,
+    /// Ring of reusable staging buffers the text instance upload is carved
+    /// out of each frame (see `TextRenderer::prepare`), so a full screen of
+    /// glyphs doesn't reallocate or directly stall the queue every frame.
+    staging_belt: wgpu::util::StagingBelt,
+    /// Per-cell background fills and the cursor, drawn in one instanced
+    /// pass before text so glyphs render on top of them.
+    cell_background: CellBackgroundRenderer,
 }
 
 impl<'window> Renderer<'window> {
@@ -89,19 +125,7 @@ impl<'window> Renderer<'window> {
 
         let size = window.inner_size();
 
-        // Request device and queue
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
-                    label: None,
-                    memory_hints: Default::default(),
-                },
-                None,
-            )
-            .await
-            .map_err(|e| RendererError::DeviceRequest(e.to_string()))?;
+        let (device, queue) = Self::request_device(&adapter).await?;
 
         // Configure surface
         let config = SurfaceConfiguration {
@@ -120,25 +144,175 @@ impl<'window> Renderer<'window> {
         // Create a basic render pipeline (placeholder for now)
         let render_pipeline = Self::create_render_pipeline(&device, config.format);
 
-        // Create text renderer
+        // Create the shared pipeline/layout/sampler once, then a text
+        // renderer that borrows it.
+        let text_cache = Cache::new(&device, config.format);
         let mut text_renderer =
             TextRenderer::new(&device, DEFAULT_FONT_SIZE, (config.width, config.height))?;
-        text_renderer.init_pipeline(&device, config.format);
+        text_renderer.init_buffer(&device);
 
         // Create initial bind group
-        let text_bind_group = text_renderer.create_bind_group(&device);
+        let text_bind_group = text_renderer.create_bind_group(&device, &text_cache);
+
+        let (offscreen_texture, offscreen_view) =
+            Self::create_offscreen_target(&device, config.format, config.width, config.height);
+
+        let mut cell_background =
+            CellBackgroundRenderer::new(&device, config.format, (config.width, config.height));
+        cell_background.init_buffer(&device);
+
+        Ok(Self {
+            device,
+            queue,
+            surface: Some(surface),
+            config,
+            render_pipeline,
+            text_cache,
+            text_renderer,
+            text_bind_group,
+            offscreen_texture,
+            offscreen_view,
+            post_process: None,
+            headless_target: None,
+            staging_belt: wgpu::util::StagingBelt::new(STAGING_BELT_CHUNK_SIZE),
+            cell_background,
+        })
+    }
+
+    /// Create a renderer with no window or `Surface`, for use in tests and
+    /// CI where no display is available. Renders into an owned texture
+    /// (usage `RENDER_ATTACHMENT | COPY_SRC`) instead of a swapchain; use
+    /// `capture_frame` to read the pixels back to the CPU.
+    pub async fn new_headless(width: u32, height: u32) -> Result<Self, RendererError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or_else(|| {
+                RendererError::AdapterRequest("No suitable adapter found".to_string())
+            })?;
+
+        let (device, queue) = Self::request_device(&adapter).await?;
+
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let config = SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        let render_pipeline = Self::create_render_pipeline(&device, config.format);
+
+        let text_cache = Cache::new(&device, config.format);
+        let mut text_renderer =
+            TextRenderer::new(&device, DEFAULT_FONT_SIZE, (config.width, config.height))?;
+        text_renderer.init_buffer(&device);
+        let text_bind_group = text_renderer.create_bind_group(&device, &text_cache);
+
+        let (offscreen_texture, offscreen_view) =
+            Self::create_offscreen_target(&device, config.format, config.width, config.height);
+
+        let headless_target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Headless Render Target"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let mut cell_background =
+            CellBackgroundRenderer::new(&device, config.format, (config.width, config.height));
+        cell_background.init_buffer(&device);
 
         Ok(Self {
             device,
             queue,
-            surface,
+            surface: None,
             config,
             render_pipeline,
+            text_cache,
             text_renderer,
             text_bind_group,
+            offscreen_texture,
+            offscreen_view,
+            post_process: None,
+            headless_target: Some(headless_target),
+            staging_belt: wgpu::util::StagingBelt::new(STAGING_BELT_CHUNK_SIZE),
+            cell_background,
         })
     }
 
+    /// Request a device/queue pair from `adapter`, with the same feature
+    /// and limit requirements used by both `new` and `new_headless`.
+    async fn request_device(adapter: &wgpu::Adapter) -> Result<(Device, Queue), RendererError> {
+        adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::default(),
+                    label: None,
+                    memory_hints: Default::default(),
+                },
+                None,
+            )
+            .await
+            .map_err(|e| RendererError::DeviceRequest(e.to_string()))
+    }
+
+    fn create_offscreen_target(
+        device: &Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Render Target"),
+            size: wgpu::Extent3d { width: width.max(1), height: height.max(1), depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    /// Load a shader chain (see `postprocess::parse_preset`), replacing the
+    /// direct grid-to-surface draw with the offscreen render + chain
+    /// described in the `postprocess` module's docs. Pass `None` to go back
+    /// to rendering straight to the surface.
+    pub fn set_shader_preset(&mut self, passes: Option<&[PassConfig]>) -> Result<(), RendererError> {
+        self.post_process = match passes {
+            Some(passes) => {
+                Some(PostProcess::new(&self.device, self.config.format, passes, self.config.width, self.config.height)?)
+            }
+            None => None,
+        };
+        Ok(())
+    }
+
     /// Create a basic render pipeline
     fn create_render_pipeline(
         device: &Device,
@@ -200,20 +374,46 @@ impl<'window> Renderer<'window> {
         if width > 0 && height > 0 {
             self.config.width = width;
             self.config.height = height;
-            self.surface.configure(&self.device, &self.config);
+            if let Some(ref surface) = self.surface {
+                surface.configure(&self.device, &self.config);
+            }
             self.text_renderer.resize(width, height);
+
+            let (offscreen_texture, offscreen_view) =
+                Self::create_offscreen_target(&self.device, self.config.format, width, height);
+            self.offscreen_texture = offscreen_texture;
+            self.offscreen_view = offscreen_view;
+
+            if self.headless_target.is_some() {
+                self.headless_target = Some(self.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("Headless Render Target"),
+                    size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.config.format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                }));
+            }
+
+            if let Some(ref mut post_process) = self.post_process {
+                post_process.resize(&self.device, width, height);
+            }
+
+            self.cell_background.resize(width, height);
         }
     }
 
     /// Render a frame with the terminal grid content
     pub fn render_grid(&mut self, grid: &TerminalGrid) -> Result<(), RendererError> {
-        // Clear any previous frame's text
+        // Clear any previous frame's text and cell fills
         self.text_renderer.clear();
+        self.cell_background.clear();
 
-        // Calculate cell dimensions
-        let font_size = self.text_renderer.font_size();
-        let cell_width = font_size * 0.6; // Approximate monospace character width
-        let cell_height = font_size;
+        // Cell dimensions from the loaded faces' actual advance/line
+        // metrics, not an approximation - see `TextRenderer::cell_size`.
+        let (cell_width, cell_height) = self.text_renderer.cell_size();
 
         // Render all visible cells
         let rows = grid.rows();
@@ -222,10 +422,15 @@ impl<'window> Renderer<'window> {
         for row in 0..rows {
             for col in 0..cols {
                 if let Some(cell) = grid.get_cell(row, col) {
-                    if cell.char != ' ' {
-                        let x = col as f32 * cell_width;
-                        let y = row as f32 * cell_height;
+                    let x = col as f32 * cell_width;
+                    let y = row as f32 * cell_height;
+
+                    // Queued regardless of the glyph below it - a space
+                    // still needs its background painted.
+                    self.cell_background.queue_cell(x, y, cell_width, cell_height, cell.bg_color);
 
+                    if cell.char != ' ' {
+                        let decorations = super::text::decorations_for(&cell.attributes);
                         self.text_renderer.queue_char(
                             cell.char,
                             x,
@@ -234,7 +439,7 @@ impl<'window> Renderer<'window> {
                             cell.bg_color,
                             cell.attributes.bold,
                             cell.attributes.italic,
-                            cell.attributes.underline,
+                            &decorations,
                             cell.attributes.blink,
                         )?;
                     }
@@ -242,12 +447,17 @@ impl<'window> Renderer<'window> {
             }
         }
 
-        // Prepare text renderer (upload glyph atlas and vertex data)
-        self.text_renderer.prepare(&self.device, &self.queue);
+        let cursor = grid.cursor();
+        self.cell_background.queue_cursor(
+            cursor.col as f32 * cell_width,
+            cursor.row as f32 * cell_height,
+            cell_width,
+            cell_height,
+        );
 
         // Update bind group if needed
         if self.text_bind_group.is_none() {
-            self.text_bind_group = self.text_renderer.create_bind_group(&self.device);
+            self.text_bind_group = self.text_renderer.create_bind_group(&self.device, &self.text_cache);
         }
 
         // Render the frame
@@ -256,14 +466,23 @@ impl<'window> Renderer<'window> {
 
     /// Render a frame (basic clear only)
     pub fn render(&mut self) -> Result<(), RendererError> {
-        let output = self
-            .surface
-            .get_current_texture()
-            .map_err(|e| RendererError::TextureAcquisition(e.to_string()))?;
+        let output = match &self.surface {
+            Some(surface) => Some(
+                surface
+                    .get_current_texture()
+                    .map_err(|e| RendererError::TextureAcquisition(e.to_string()))?,
+            ),
+            None => None,
+        };
 
-        let view = output
-            .texture
-            .create_view(&TextureViewDescriptor::default());
+        let surface_view = match &output {
+            Some(output) => output.texture.create_view(&TextureViewDescriptor::default()),
+            None => self
+                .headless_target
+                .as_ref()
+                .expect("Renderer has neither a Surface nor a headless_target")
+                .create_view(&TextureViewDescriptor::default()),
+        };
 
         let mut encoder = self
             .device
@@ -271,11 +490,23 @@ impl<'window> Renderer<'window> {
                 label: Some("Render Encoder"),
             });
 
+        // Prepare text renderer (upload glyph atlas and vertex data); the
+        // instance buffer write is carved out of `staging_belt` into this
+        // same encoder, ahead of the render pass that reads it below.
+        self.text_renderer
+            .prepare(&self.device, &self.queue, &mut encoder, &mut self.staging_belt);
+        self.cell_background.prepare(&self.device, &self.queue);
+
+        // With a shader chain loaded, the grid+text draw into the offscreen
+        // target so the chain has something to sample; otherwise they draw
+        // straight to the surface, same as before this subsystem existed.
+        let target_view = if self.post_process.is_some() { &self.offscreen_view } else { &surface_view };
+
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: target_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
@@ -292,20 +523,113 @@ impl<'window> Renderer<'window> {
                 occlusion_query_set: None,
             });
 
+            // Cell backgrounds and the cursor first, so text then draws on
+            // top of them.
+            self.cell_background.render(&mut render_pass);
+
             // Render text if we have vertices and bind group
             if let Some(ref bind_group) = self.text_bind_group {
-                if self.text_renderer.vertex_count() > 0 {
-                    self.text_renderer.render(&mut render_pass, bind_group);
+                if self.text_renderer.instance_count() > 0 {
+                    self.text_renderer.render(&mut render_pass, bind_group, &self.text_cache);
                 }
             }
         }
 
+        if let Some(ref post_process) = self.post_process {
+            let size = (self.config.width, self.config.height);
+            post_process.run(&self.device, &self.queue, &mut encoder, &self.offscreen_view, size, &surface_view, size);
+        }
+
+        self.staging_belt.finish();
         self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        if let Some(output) = output {
+            output.present();
+        }
+        // Reclaims the chunks this frame wrote into once the GPU is done
+        // with them, so they go back into the free pool for reuse instead
+        // of the belt growing a new chunk every frame.
+        self.staging_belt.recall();
 
         Ok(())
     }
 
+    /// Render `grid` into the headless target and read the result back to
+    /// the CPU as tightly-packed RGBA8 pixels (`width * height * 4` bytes,
+    /// row-major, no padding). Requires a renderer created via
+    /// `new_headless`; unblocks deterministic golden-image tests of text
+    /// layout and colors in CI, where no window/swapchain exists.
+    pub fn capture_frame(&mut self, grid: &TerminalGrid) -> Result<Vec<u8>, RendererError> {
+        if self.headless_target.is_none() {
+            return Err(RendererError::Render(
+                "capture_frame requires a renderer created via Renderer::new_headless".to_string(),
+            ));
+        }
+
+        self.render_grid(grid)?;
+
+        let width = self.config.width;
+        let height = self.config.height;
+        let unpadded_bytes_per_row = width * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+        let buffer_size = (padded_bytes_per_row * height) as wgpu::BufferAddress;
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Screenshot Readback Buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let headless_target = self.headless_target.as_ref().expect("checked above");
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Screenshot Copy Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: headless_target,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()
+            .map_err(|_| RendererError::Render("Readback buffer map callback never fired".to_string()))?
+            .map_err(|e| RendererError::Render(format!("Failed to map readback buffer: {e}")))?;
+
+        let mapped = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for row in 0..height {
+            let start = (row * padded_bytes_per_row) as usize;
+            let end = start + unpadded_bytes_per_row as usize;
+            pixels.extend_from_slice(&mapped[start..end]);
+        }
+        drop(mapped);
+        readback_buffer.unmap();
+
+        Ok(pixels)
+    }
+
     /// Get reference to device
     pub fn device(&self) -> &Device {
         &self.device
@@ -326,11 +650,16 @@ impl<'window> Renderer<'window> {
         self.text_renderer.font_size()
     }
 
+    /// The monospace cell advance width and line height the loaded faces
+    /// were measured for (see `TextRenderer::cell_size`), used to lay out
+    /// the grid and report column/row counts.
+    pub fn cell_size(&self) -> (f32, f32) {
+        self.text_renderer.cell_size()
+    }
+
     /// Calculate terminal dimensions based on current window size
     pub fn terminal_dimensions(&self) -> (usize, usize) {
-        let font_size = self.text_renderer.font_size();
-        let cell_width = font_size * 0.6;
-        let cell_height = font_size;
+        let (cell_width, cell_height) = self.text_renderer.cell_size();
 
         let cols = (self.config.width as f32 / cell_width).floor() as usize;
         let rows = (self.config.height as f32 / cell_height).floor() as usize;
@@ -5,24 +5,42 @@
 //! - Click and drag selection
 //! - Copy to clipboard
 //! - Paste from clipboard
+//! - A separate PRIMARY selection buffer (`ClipboardType::Selection`) for
+//!   copy-on-select and middle-click paste, on platforms that have one
 
 use crate::terminal::grid::{Cell, Cursor};
 use std::sync::{Arc, Mutex};
 use anyhow::Result;
-
-/// Mouse tracking mode flags
+#[cfg(target_os = "linux")]
+use arboard::{GetExtLinux, LinuxClipboardKind, SetExtLinux};
+
+/// Mouse tracking mode flags. `TerminalApp`'s own mouse-reporting decisions
+/// go through `ParserState::mouse_reporting`/`TermMode` instead of this
+/// struct (see its doc comment there) - this is the protocol-level flag set
+/// `encode_event` consumes, kept as a plain value type so it can be built
+/// from whichever representation a caller already has.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct MouseMode {
     /// Track button press and release events (CSI ?1000h)
     pub button_tracking: bool,
     /// Track button press events only (CSI ?1001h)
     pub button_press_only: bool,
+    /// Also report motion while a button is held (CSI ?1002h)
+    pub button_event_tracking: bool,
+    /// Report all motion, button held or not (CSI ?1003h)
+    pub any_motion_tracking: bool,
+    /// Use the SGR extended coordinate encoding (CSI ?1006h) instead of the
+    /// legacy `CSI M Cb Cx Cy` byte encoding.
+    pub sgr: bool,
 }
 
 impl MouseMode {
     /// Check if mouse tracking is enabled
     pub fn is_enabled(&self) -> bool {
-        self.button_tracking || self.button_press_only
+        self.button_tracking
+            || self.button_press_only
+            || self.button_event_tracking
+            || self.any_motion_tracking
     }
 
     /// Enable button tracking (CSI ?1000h)
@@ -45,13 +63,155 @@ impl MouseMode {
         self.button_press_only = false;
     }
 
+    /// Enable button-event (drag) tracking (CSI ?1002h)
+    pub fn enable_button_event_tracking(&mut self) {
+        self.button_event_tracking = true;
+    }
+
+    /// Disable button-event (drag) tracking (CSI ?1002l)
+    pub fn disable_button_event_tracking(&mut self) {
+        self.button_event_tracking = false;
+    }
+
+    /// Enable any-motion tracking (CSI ?1003h)
+    pub fn enable_any_motion_tracking(&mut self) {
+        self.any_motion_tracking = true;
+    }
+
+    /// Disable any-motion tracking (CSI ?1003l)
+    pub fn disable_any_motion_tracking(&mut self) {
+        self.any_motion_tracking = false;
+    }
+
+    /// Enable SGR extended coordinates (CSI ?1006h)
+    pub fn enable_sgr(&mut self) {
+        self.sgr = true;
+    }
+
+    /// Disable SGR extended coordinates (CSI ?1006l)
+    pub fn disable_sgr(&mut self) {
+        self.sgr = false;
+    }
+
     /// Disable all mouse tracking
     pub fn disable_all(&mut self) {
-        self.button_tracking = false;
-        self.button_press_only = false;
+        *self = Self::default();
+    }
+
+    /// Whether a button press/release should be reported - true once any of
+    /// 1000/1002/1003 is on, since each broader mode implies the narrower
+    /// ones (same ordering as `terminal::parser::MouseReportMode`).
+    fn reports_clicks(&self) -> bool {
+        self.button_tracking || self.button_event_tracking || self.any_motion_tracking
+    }
+
+    /// Whether drag motion (a button held while moving) should be reported.
+    fn reports_drag(&self) -> bool {
+        self.button_event_tracking || self.any_motion_tracking
+    }
+}
+
+/// A mouse button, or the absence of one, as encoded in xterm mouse reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    /// No button held - only reportable under `?1003h` any-motion tracking.
+    None,
+    WheelUp,
+    WheelDown,
+}
+
+impl MouseButton {
+    fn base_code(self) -> u8 {
+        match self {
+            MouseButton::Left => 0,
+            MouseButton::Middle => 1,
+            MouseButton::Right => 2,
+            MouseButton::None => 3,
+            MouseButton::WheelUp => 64,
+            MouseButton::WheelDown => 65,
+        }
     }
 }
 
+/// What happened to `button`, for `encode_event`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Press,
+    Release,
+    Motion,
+}
+
+/// The modifier keys xterm mouse reporting folds into the button byte
+/// (Shift=4, Meta/Alt=8, Ctrl=16) - a plain bool set so this module doesn't
+/// need to depend on a windowing crate's modifiers type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MouseModifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+/// Encode a mouse event as the bytes to send to the PTY, per `mode`, or
+/// `None` if `mode` doesn't cover this kind of event (e.g. a plain-motion
+/// report with no button held, but only `?1002h` drag tracking is on).
+/// `pos` is 0-indexed `(col, row)`; both output encodings use 1-based
+/// coordinates.
+///
+/// Produces `CSI < Cb ; Cx ; Cy M` (press/motion) or `...m` (release) when
+/// `mode.sgr`, or the legacy `CSI M Cb Cx Cy` byte encoding (coordinates and
+/// button code offset by 32, clamped to a single byte) otherwise - the
+/// legacy encoding can't say which button went up, so every release reports
+/// `Cb = 3` regardless of `button`, per the xterm spec.
+pub fn encode_event(
+    mode: MouseMode,
+    button: MouseButton,
+    action: MouseAction,
+    pos: (usize, usize),
+    modifiers: MouseModifiers,
+) -> Option<Vec<u8>> {
+    let reportable = match action {
+        MouseAction::Press | MouseAction::Release => mode.reports_clicks(),
+        MouseAction::Motion if button == MouseButton::None => mode.any_motion_tracking,
+        MouseAction::Motion => mode.reports_drag(),
+    };
+    if !reportable {
+        return None;
+    }
+
+    let mut code = button.base_code();
+    if modifiers.shift {
+        code += 4;
+    }
+    if modifiers.alt {
+        code += 8;
+    }
+    if modifiers.ctrl {
+        code += 16;
+    }
+    if action == MouseAction::Motion {
+        code += 32;
+    }
+
+    let (col, row) = pos;
+    if mode.sgr {
+        let terminator = if action == MouseAction::Release { 'm' } else { 'M' };
+        return Some(format!("\x1b[<{};{};{}{}", code, col + 1, row + 1, terminator).into_bytes());
+    }
+
+    let cb = if action == MouseAction::Release { 3 } else { code };
+    Some(vec![
+        0x1b,
+        b'[',
+        b'M',
+        cb + 32,
+        (col + 1 + 32).min(255) as u8,
+        (row + 1 + 32).min(255) as u8,
+    ])
+}
+
 /// Selection region defined by start and end positions
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SelectionRegion {
@@ -61,6 +221,10 @@ pub struct SelectionRegion {
     pub end: Cursor,
     /// Whether this selection has been initialized (distinguishes from "no selection")
     pub active: bool,
+    /// Whether this is a rectangular (block) selection - every row between
+    /// `start.row` and `end.row` uses the same `start.col..=end.col` window,
+    /// independent of where each row's text actually wraps.
+    pub block: bool,
 }
 
 impl SelectionRegion {
@@ -73,7 +237,24 @@ impl SelectionRegion {
             (end, start)
         };
         // Explicitly created regions are always active
-        Self { start, end, active: true }
+        Self { start, end, active: true, block: false }
+    }
+
+    /// Create a new rectangular (block) selection spanning the corners `a`
+    /// and `b`. Unlike `new`, rows and columns are normalized
+    /// independently, since a block selection's row span and column span
+    /// don't have to agree on which corner came first.
+    pub fn new_block(a: Cursor, b: Cursor) -> Self {
+        let top = a.row.min(b.row);
+        let bottom = a.row.max(b.row);
+        let left = a.col.min(b.col);
+        let right = a.col.max(b.col);
+        Self {
+            start: Cursor::new(top, left),
+            end: Cursor::new(bottom, right),
+            active: true,
+            block: true,
+        }
     }
 
     /// Create an empty selection region
@@ -82,6 +263,7 @@ impl SelectionRegion {
             start: Cursor::origin(),
             end: Cursor::origin(),
             active: false,
+            block: false,
         }
     }
 
@@ -95,16 +277,19 @@ impl SelectionRegion {
         if self.is_empty() {
             return false;
         }
-        if pos.row >= self.start.row && pos.row <= self.end.row {
-            if pos.row == self.start.row && pos.col < self.start.col {
-                return false;
-            }
-            if pos.row == self.end.row && pos.col > self.end.col {
-                return false;
-            }
-            return true;
+        if pos.row < self.start.row || pos.row > self.end.row {
+            return false;
         }
-        false
+        if self.block {
+            return pos.col >= self.start.col && pos.col <= self.end.col;
+        }
+        if pos.row == self.start.row && pos.col < self.start.col {
+            return false;
+        }
+        if pos.row == self.end.row && pos.col > self.end.col {
+            return false;
+        }
+        true
     }
 
     /// Get the range of columns for a given row
@@ -115,6 +300,9 @@ impl SelectionRegion {
         if row < self.start.row || row > self.end.row {
             return None;
         }
+        if self.block {
+            return Some((self.start.col, self.end.col));
+        }
         let start_col = if row == self.start.row { self.start.col } else { 0 };
         let end_col = if row == self.end.row { self.end.col } else { usize::MAX };
         Some((start_col, end_col))
@@ -127,6 +315,76 @@ impl Default for SelectionRegion {
     }
 }
 
+/// What a selection drag snaps to, alacritty `SelectionType`-style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionType {
+    /// Plain character-by-character selection (single click and drag).
+    #[default]
+    Normal,
+    /// Expands to whole words, anchored at the word under a double-click.
+    Semantic,
+    /// Expands to whole lines, anchored at the row under a triple-click.
+    Line,
+    /// Rectangular selection independent of line wrapping, anchored at a
+    /// single cell (typically started with a modifier-held drag).
+    Block,
+}
+
+/// Characters alacritty's default semantic selection treats as part of a
+/// word's boundary rather than separators - this keeps paths and URLs
+/// selectable as a single word on double-click.
+const SEMANTIC_WORD_CHARS: &str = "/.-_~:?#[]@!$&'()*+,;=%";
+
+/// Whether `c` counts as part of a word for semantic selection and vi-mode
+/// `w`/`b` motion - kept `pub(crate)` so both share one definition of "word".
+pub(crate) fn is_semantic_word_char(c: char) -> bool {
+    !c.is_whitespace() && (c.is_alphanumeric() || SEMANTIC_WORD_CHARS.contains(c))
+}
+
+/// Whether `a` comes strictly before `b` in reading order.
+fn before(a: Cursor, b: Cursor) -> bool {
+    a.row < b.row || (a.row == b.row && a.col < b.col)
+}
+
+/// Expand `pos` to the bounds of the word it falls in, per
+/// `is_semantic_word_char`. Falls back to a zero-width selection at `pos`
+/// when the row is empty or `pos` lands on a separator.
+fn word_bounds(grid: &[Vec<Cell>], pos: Cursor) -> (Cursor, Cursor) {
+    let Some(row) = grid.get(pos.row) else {
+        return (pos, pos);
+    };
+    if row.is_empty() || pos.col >= row.len() || !is_semantic_word_char(row[pos.col].char) {
+        return (pos, pos);
+    }
+
+    let mut start_col = pos.col;
+    while start_col > 0 && is_semantic_word_char(row[start_col - 1].char) {
+        start_col -= 1;
+    }
+    let mut end_col = pos.col;
+    while end_col + 1 < row.len() && is_semantic_word_char(row[end_col + 1].char) {
+        end_col += 1;
+    }
+    (Cursor::new(pos.row, start_col), Cursor::new(pos.row, end_col))
+}
+
+/// Expand `row` to span a whole logical line, for line selection. Walks
+/// backward/forward over `is_wrapped` (true when a row continues onto the
+/// next) so triple-clicking anywhere in a soft-wrapped line selects all of
+/// it, not just the one physical row under the cursor.
+fn line_bounds(grid: &[Vec<Cell>], row: usize, is_wrapped: &dyn Fn(usize) -> bool) -> (Cursor, Cursor) {
+    let mut start_row = row;
+    while start_row > 0 && is_wrapped(start_row - 1) {
+        start_row -= 1;
+    }
+    let mut end_row = row;
+    while end_row + 1 < grid.len() && is_wrapped(end_row) {
+        end_row += 1;
+    }
+    let width = grid.get(end_row).map_or(0, |r| r.len());
+    (Cursor::new(start_row, 0), Cursor::new(end_row, width.saturating_sub(1)))
+}
+
 /// Selection state
 #[derive(Debug, Clone)]
 pub struct SelectionState {
@@ -136,6 +394,13 @@ pub struct SelectionState {
     pub selecting: bool,
     /// Mouse tracking mode
     pub mouse_mode: MouseMode,
+    /// How the current drag snaps cells to the selection - set once by
+    /// `start_selection` and held fixed until the drag ends.
+    pub kind: SelectionType,
+    /// The expanded range under the click that started the drag, kept
+    /// separately from `region` so dragging back past the start point
+    /// doesn't lose it (unlike plain min/max of `region.start`/`pos`).
+    anchor: (Cursor, Cursor),
 }
 
 impl Default for SelectionState {
@@ -144,6 +409,8 @@ impl Default for SelectionState {
             region: SelectionRegion::empty(),
             selecting: false,
             mouse_mode: MouseMode::default(),
+            kind: SelectionType::Normal,
+            anchor: (Cursor::origin(), Cursor::origin()),
         }
     }
 }
@@ -154,40 +421,126 @@ impl SelectionState {
         Self::default()
     }
 
-    /// Start a selection at the given position
-    pub fn start_selection(&mut self, pos: Cursor) {
-        // Create a region with same start and end, but mark as inactive until dragged
+    /// Start a selection at the given position, snapping it per `kind`
+    /// using `grid` (the focused pane's rows, oldest-first). Equivalent to
+    /// `start_selection_wrapped` with no soft-wrap info, so `Line` selection
+    /// only ever spans the one physical row.
+    pub fn start_selection(&mut self, pos: Cursor, kind: SelectionType, grid: &[Vec<Cell>]) {
+        self.start_selection_wrapped(pos, kind, grid, |_| false)
+    }
+
+    /// Like `start_selection`, but `is_wrapped(row)` reports whether `row`
+    /// soft-wraps into the next one, so a triple-click's `Line` selection
+    /// can span the whole logical line (see `line_bounds`).
+    pub fn start_selection_wrapped(
+        &mut self,
+        pos: Cursor,
+        kind: SelectionType,
+        grid: &[Vec<Cell>],
+        is_wrapped: impl Fn(usize) -> bool,
+    ) {
+        let (start, end) = Self::snap(kind, pos, grid, &is_wrapped);
+        self.kind = kind;
+        self.anchor = (start, end);
         self.region = SelectionRegion {
-            start: pos,
-            end: pos,
-            active: false, // Not active until user drags
+            start,
+            end,
+            // Semantic/Line selections are visible as soon as they're
+            // anchored; Normal and Block only become active once dragged
+            // (see `update_selection`), so a plain click doesn't highlight
+            // a single cell.
+            active: kind != SelectionType::Normal && kind != SelectionType::Block,
+            block: kind == SelectionType::Block,
         };
         self.selecting = true;
     }
 
-    /// Update the selection to include a new position
-    pub fn update_selection(&mut self, pos: Cursor) {
-        if self.selecting {
-            let (start, end) = if self.region.start.row < pos.row
-                || (self.region.start.row == pos.row && self.region.start.col <= pos.col)
-            {
-                (self.region.start, pos)
-            } else {
-                (pos, self.region.start)
-            };
-            self.region = SelectionRegion {
-                start,
-                end,
-                active: self.region.start != pos, // Active only if dragged to different position
-            };
+    /// Update the selection to include a new position. Equivalent to
+    /// `update_selection_wrapped` with no soft-wrap info.
+    pub fn update_selection(&mut self, pos: Cursor, grid: &[Vec<Cell>]) {
+        self.update_selection_wrapped(pos, grid, |_| false)
+    }
+
+    /// Like `update_selection`, but `is_wrapped(row)` reports whether `row`
+    /// soft-wraps into the next one, so dragging a `Line` selection across a
+    /// soft-wrapped row keeps expanding by logical line.
+    pub fn update_selection_wrapped(
+        &mut self,
+        pos: Cursor,
+        grid: &[Vec<Cell>],
+        is_wrapped: impl Fn(usize) -> bool,
+    ) {
+        if !self.selecting {
+            return;
+        }
+        let (cur_start, cur_end) = Self::snap(self.kind, pos, grid, &is_wrapped);
+        let (anchor_start, anchor_end) = self.anchor;
+
+        if self.kind == SelectionType::Block {
+            let mut region = SelectionRegion::new_block(anchor_start, cur_start);
+            region.active = anchor_start != cur_start;
+            self.region = region;
+            return;
+        }
+
+        let (start, end) = if before(cur_start, anchor_start) {
+            (cur_start, anchor_end)
+        } else {
+            (anchor_start, cur_end)
+        };
+
+        self.region = SelectionRegion {
+            start,
+            end,
+            active: self.kind != SelectionType::Normal || start != end,
+            block: false,
+        };
+    }
+
+    /// Snap `pos` to the selection unit `kind` expands to.
+    fn snap(
+        kind: SelectionType,
+        pos: Cursor,
+        grid: &[Vec<Cell>],
+        is_wrapped: &dyn Fn(usize) -> bool,
+    ) -> (Cursor, Cursor) {
+        match kind {
+            SelectionType::Normal | SelectionType::Block => (pos, pos),
+            SelectionType::Semantic => word_bounds(grid, pos),
+            SelectionType::Line => line_bounds(grid, pos.row, is_wrapped),
         }
     }
 
-    /// End the selection
+    /// End the selection. Doesn't touch the clipboard - see
+    /// `end_selection_and_copy` for the "copy on select" variant most
+    /// mouse-driven callers want.
     pub fn end_selection(&mut self) {
         self.selecting = false;
     }
 
+    /// End the selection and, if it's non-empty, push its extracted text
+    /// into `clipboard`'s PRIMARY selection - "copy on select", the
+    /// standard behavior in terminals like Alacritty, so a middle-click
+    /// elsewhere can paste it without an explicit copy. Returns the
+    /// extracted text so callers that also copy to the normal clipboard on
+    /// release don't have to extract it a second time.
+    pub fn end_selection_and_copy(
+        &mut self,
+        grid: &[Vec<Cell>],
+        is_wrapped: impl Fn(usize) -> bool,
+        clipboard: &Clipboard,
+    ) -> String {
+        self.selecting = false;
+        if !self.has_selection() {
+            return String::new();
+        }
+        let text = extract_selected_text_wrapped(grid, &self.region, is_wrapped);
+        if !text.is_empty() {
+            let _ = clipboard.copy_as(&text, ClipboardType::Selection);
+        }
+        text
+    }
+
     /// Clear the selection
     pub fn clear(&mut self) {
         self.region = SelectionRegion::empty();
@@ -200,8 +553,24 @@ impl SelectionState {
     }
 }
 
-/// Extract selected text from the grid
+/// Extract selected text from the grid, treating every row as a hard line
+/// break. A thin wrapper over `extract_selected_text_wrapped` for callers
+/// that don't track soft-wrap state (e.g. the plain `&[Vec<Cell>]` live grid
+/// passed in from mouse selection).
 pub fn extract_selected_text(grid: &[Vec<Cell>], selection: &SelectionRegion) -> String {
+    extract_selected_text_wrapped(grid, selection, |_| false)
+}
+
+/// Extract selected text, joining rows `is_wrapped` reports as soft-wrapped
+/// without inserting a `\n` between them - mirroring how `to_string` joins
+/// the live grid, but driven by a selection rectangle instead of the whole
+/// screen. Wide-char spacer cells are skipped so a double-width character
+/// doesn't produce a duplicate or blank column in the copied text.
+pub fn extract_selected_text_wrapped(
+    grid: &[Vec<Cell>],
+    selection: &SelectionRegion,
+    is_wrapped: impl Fn(usize) -> bool,
+) -> String {
     if !selection.active || grid.is_empty() {
         return String::new();
     }
@@ -212,14 +581,15 @@ pub fn extract_selected_text(grid: &[Vec<Cell>], selection: &SelectionRegion) ->
         let row_data = &grid[row];
         let (start_col, end_col) = selection.cols_for_row(row).unwrap();
 
-        // For multi-line selections, use the minimum column across rows for both start and end
-        let effective_start = if selection.start.row != selection.end.row {
+        // For multi-line (non-block) selections, use the minimum column
+        // across rows for both start and end.
+        let effective_start = if !selection.block && selection.start.row != selection.end.row {
             selection.start.col.min(selection.end.col)
         } else {
             start_col
         };
 
-        let effective_end = if selection.start.row != selection.end.row {
+        let effective_end = if !selection.block && selection.start.row != selection.end.row {
             selection.start.col.max(selection.end.col)
         } else {
             end_col
@@ -227,8 +597,10 @@ pub fn extract_selected_text(grid: &[Vec<Cell>], selection: &SelectionRegion) ->
 
         let actual_end = effective_end.min(row_data.len().saturating_sub(1));
 
-        // Trim trailing whitespace on the last line only
-        let trim_end = if row == selection.end.row {
+        // Trim trailing whitespace on the last line only - never for a block
+        // selection, which extracts the exact column rectangle on every row
+        // with no collapsing.
+        let trim_end = if !selection.block && row == selection.end.row {
             row_data[effective_start..=actual_end]
                 .iter()
                 .rev()
@@ -238,8 +610,9 @@ pub fn extract_selected_text(grid: &[Vec<Cell>], selection: &SelectionRegion) ->
             0
         };
 
-        // Trim leading whitespace on the first line only
-        let trim_start = if row == selection.start.row {
+        // Trim leading whitespace on the first line only - same block
+        // exception as `trim_end`.
+        let trim_start = if !selection.block && row == selection.start.row {
             row_data[effective_start..=actual_end]
                 .iter()
                 .take_while(|c| c.char.is_whitespace())
@@ -257,11 +630,21 @@ pub fn extract_selected_text(grid: &[Vec<Cell>], selection: &SelectionRegion) ->
 
         for col in final_start..=final_end {
             let cell = &row_data[col];
+            if cell.attributes.wide_char_spacer {
+                continue;
+            }
             result.push(cell.char);
         }
 
-        // Add newline between rows, but not after the last row
-        if row < selection.end.row && row < grid.len().saturating_sub(1) {
+        // Add newline between rows, but not after the last row, and not
+        // where the row soft-wraps into the next one - except for a block
+        // selection, which always joins with `\n` between rows since its
+        // rectangle cuts across wrap boundaries arbitrarily rather than
+        // following text flow.
+        if row < selection.end.row
+            && row < grid.len().saturating_sub(1)
+            && (selection.block || !is_wrapped(row))
+        {
             result.push('\n');
         }
     }
@@ -269,7 +652,9 @@ pub fn extract_selected_text(grid: &[Vec<Cell>], selection: &SelectionRegion) ->
     result
 }
 
-/// Extract selected text including leading whitespace
+/// Extract selected text including leading whitespace, treating every row
+/// as a hard line break. See `extract_selected_text_wrapped` for a
+/// wrap-aware variant.
 pub fn extract_selected_text_preserve_ws(grid: &[Vec<Cell>], selection: &SelectionRegion) -> String {
     if !selection.active || grid.is_empty() {
         return String::new();
@@ -281,14 +666,15 @@ pub fn extract_selected_text_preserve_ws(grid: &[Vec<Cell>], selection: &Selecti
         let row_data = &grid[row];
         let (start_col, end_col) = selection.cols_for_row(row).unwrap();
 
-        // For multi-line selections, use the same columns for all rows
-        let effective_start = if selection.start.row != selection.end.row {
+        // For multi-line (non-block) selections, use the same columns for
+        // all rows.
+        let effective_start = if !selection.block && selection.start.row != selection.end.row {
             selection.start.col.min(selection.end.col)
         } else {
             start_col
         };
 
-        let effective_end = if selection.start.row != selection.end.row {
+        let effective_end = if !selection.block && selection.start.row != selection.end.row {
             selection.start.col.max(selection.end.col)
         } else {
             end_col
@@ -298,6 +684,9 @@ pub fn extract_selected_text_preserve_ws(grid: &[Vec<Cell>], selection: &Selecti
 
         for col in effective_start..=actual_end {
             let cell = &row_data[col];
+            if cell.attributes.wide_char_spacer {
+                continue;
+            }
             result.push(cell.char);
         }
 
@@ -310,6 +699,19 @@ pub fn extract_selected_text_preserve_ws(grid: &[Vec<Cell>], selection: &Selecti
     result
 }
 
+/// Which system clipboard buffer `Clipboard::copy`/`paste` targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipboardType {
+    /// The normal copy/paste clipboard (Ctrl+C/Ctrl+Shift+V).
+    #[default]
+    Clipboard,
+    /// X11/Wayland's PRIMARY selection: populated by merely selecting text
+    /// and read back with a middle-click. Only meaningful on Linux/BSD via
+    /// `arboard`'s `LinuxClipboardKind::Primary` - everywhere else it falls
+    /// back to `Clipboard`, since there's no separate buffer to target.
+    Selection,
+}
+
 /// Clipboard manager
 pub struct Clipboard {
     clipboard: Arc<Mutex<Option<arboard::Clipboard>>>,
@@ -330,26 +732,51 @@ impl Clipboard {
         Ok(())
     }
 
-    /// Copy text to the clipboard
+    /// Copy text to the normal clipboard. Shorthand for
+    /// `copy_as(text, ClipboardType::Clipboard)`.
     pub fn copy(&self, text: &str) -> Result<()> {
+        self.copy_as(text, ClipboardType::Clipboard)
+    }
+
+    /// Copy text to the given clipboard buffer.
+    pub fn copy_as(&self, text: &str, kind: ClipboardType) -> Result<()> {
         let mut inner = self.clipboard.lock().unwrap();
-        if let Some(clipboard) = inner.as_mut() {
-            clipboard.set_text(text)?;
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Clipboard not initialized"))
+        let clipboard = inner
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Clipboard not initialized"))?;
+        match kind {
+            ClipboardType::Clipboard => clipboard.set_text(text)?,
+            #[cfg(target_os = "linux")]
+            ClipboardType::Selection => clipboard
+                .set()
+                .clipboard(LinuxClipboardKind::Primary)
+                .text(text)?,
+            #[cfg(not(target_os = "linux"))]
+            ClipboardType::Selection => clipboard.set_text(text)?,
         }
+        Ok(())
     }
 
-    /// Get text from the clipboard
+    /// Get text from the normal clipboard. Shorthand for
+    /// `paste_from(ClipboardType::Clipboard)`.
     pub fn paste(&self) -> Result<String> {
+        self.paste_from(ClipboardType::Clipboard)
+    }
+
+    /// Get text from the given clipboard buffer.
+    pub fn paste_from(&self, kind: ClipboardType) -> Result<String> {
         let mut inner = self.clipboard.lock().unwrap();
-        if let Some(clipboard) = inner.as_mut() {
-            let text = clipboard.get_text()?;
-            Ok(text)
-        } else {
-            Err(anyhow::anyhow!("Clipboard not initialized"))
-        }
+        let clipboard = inner
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Clipboard not initialized"))?;
+        let text = match kind {
+            ClipboardType::Clipboard => clipboard.get_text()?,
+            #[cfg(target_os = "linux")]
+            ClipboardType::Selection => clipboard.get().clipboard(LinuxClipboardKind::Primary).text()?,
+            #[cfg(not(target_os = "linux"))]
+            ClipboardType::Selection => clipboard.get_text()?,
+        };
+        Ok(text)
     }
 
     /// Check if clipboard is available
@@ -575,17 +1002,128 @@ mod tests {
         assert!(!mode.is_enabled());
     }
 
+    #[test]
+    fn test_encode_event_sgr_press_and_release() {
+        let mut mode = MouseMode::default();
+        mode.enable_button_tracking();
+        mode.enable_sgr();
+
+        let press = encode_event(
+            mode,
+            MouseButton::Left,
+            MouseAction::Press,
+            (4, 9),
+            MouseModifiers::default(),
+        );
+        assert_eq!(press, Some(b"\x1b[<0;5;10M".to_vec()));
+
+        let release = encode_event(
+            mode,
+            MouseButton::Left,
+            MouseAction::Release,
+            (4, 9),
+            MouseModifiers::default(),
+        );
+        assert_eq!(release, Some(b"\x1b[<0;5;10m".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_event_sgr_folds_in_modifiers_and_wheel() {
+        let mut mode = MouseMode::default();
+        mode.enable_button_tracking();
+        mode.enable_sgr();
+
+        let report = encode_event(
+            mode,
+            MouseButton::WheelUp,
+            MouseAction::Press,
+            (0, 0),
+            MouseModifiers { shift: true, alt: false, ctrl: true },
+        );
+        // 64 (wheel up) + 4 (shift) + 16 (ctrl) = 84
+        assert_eq!(report, Some(b"\x1b[<84;1;1M".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_event_legacy_byte_encoding() {
+        let mut mode = MouseMode::default();
+        mode.enable_button_tracking();
+        // sgr left false - legacy encoding.
+
+        let press = encode_event(
+            mode,
+            MouseButton::Left,
+            MouseAction::Press,
+            (0, 0),
+            MouseModifiers::default(),
+        );
+        assert_eq!(press, Some(vec![0x1b, b'[', b'M', 32, 33, 33]));
+
+        // Legacy encoding can't name which button went up - always code 3.
+        let release = encode_event(
+            mode,
+            MouseButton::Left,
+            MouseAction::Release,
+            (0, 0),
+            MouseModifiers::default(),
+        );
+        assert_eq!(release, Some(vec![0x1b, b'[', b'M', 32 + 3, 33, 33]));
+    }
+
+    #[test]
+    fn test_encode_event_respects_tracking_granularity() {
+        let mut click_only = MouseMode::default();
+        click_only.enable_button_tracking();
+
+        // Plain motion (no button) needs ?1003h, which isn't on here.
+        assert_eq!(
+            encode_event(
+                click_only,
+                MouseButton::None,
+                MouseAction::Motion,
+                (0, 0),
+                MouseModifiers::default(),
+            ),
+            None
+        );
+
+        // Drag (button held while moving) needs ?1002h or ?1003h.
+        assert_eq!(
+            encode_event(
+                click_only,
+                MouseButton::Left,
+                MouseAction::Motion,
+                (0, 0),
+                MouseModifiers::default(),
+            ),
+            None
+        );
+
+        let mut drag_tracking = MouseMode::default();
+        drag_tracking.enable_button_event_tracking();
+        drag_tracking.enable_sgr();
+        assert!(encode_event(
+            drag_tracking,
+            MouseButton::Left,
+            MouseAction::Motion,
+            (0, 0),
+            MouseModifiers::default(),
+        )
+        .is_some());
+    }
+
     #[test]
     fn test_selection_state() {
+        let grid = create_test_grid();
         let mut state = SelectionState::new();
         assert!(!state.has_selection());
         assert!(!state.selecting);
 
-        state.start_selection(Cursor::new(2, 3));
+        state.start_selection(Cursor::new(2, 3), SelectionType::Normal, &grid);
         assert!(state.selecting);
         assert!(!state.has_selection()); // Single char is still considered empty
 
-        state.update_selection(Cursor::new(5, 10));
+        state.update_selection(Cursor::new(5, 10), &grid);
         assert!(state.selecting);
         assert!(state.has_selection());
 
@@ -597,4 +1135,138 @@ mod tests {
         assert!(!state.selecting);
         assert!(!state.has_selection());
     }
+
+    #[test]
+    fn test_semantic_selection_snaps_to_word() {
+        let grid = create_test_grid();
+        let mut state = SelectionState::new();
+
+        // Row 0 is "Hello World"; double-clicking inside "World" (col 6-10)
+        // should snap the whole word regardless of which cell was hit.
+        state.start_selection(Cursor::new(0, 8), SelectionType::Semantic, &grid);
+        assert!(state.has_selection());
+        assert_eq!(state.region.start, Cursor::new(0, 6));
+        assert_eq!(state.region.end, Cursor::new(0, 10));
+    }
+
+    #[test]
+    fn test_line_selection_spans_full_row() {
+        let grid = create_test_grid();
+        let mut state = SelectionState::new();
+
+        state.start_selection(Cursor::new(0, 3), SelectionType::Line, &grid);
+        assert!(state.has_selection());
+        assert_eq!(state.region.start, Cursor::new(0, 0));
+        assert_eq!(state.region.end, Cursor::new(0, grid[0].len() - 1));
+    }
+
+    #[test]
+    fn test_line_selection_follows_soft_wrap_continuation() {
+        let grid = create_test_grid();
+        let mut state = SelectionState::new();
+
+        // Rows 0 and 1 are one soft-wrapped logical line; triple-clicking
+        // anywhere in row 1 must pull in row 0 too, not just its own row.
+        state.start_selection_wrapped(
+            Cursor::new(1, 2),
+            SelectionType::Line,
+            &grid,
+            |row| row == 0,
+        );
+        assert!(state.has_selection());
+        assert_eq!(state.region.start, Cursor::new(0, 0));
+        assert_eq!(state.region.end, Cursor::new(1, grid[1].len() - 1));
+    }
+
+    #[test]
+    fn test_semantic_selection_stops_at_punctuation() {
+        let grid = vec![vec![
+            Cell::new('f'),
+            Cell::new('o'),
+            Cell::new('o'),
+            Cell::new('"'),
+            Cell::new('b'),
+            Cell::new('a'),
+            Cell::new('r'),
+        ]];
+        let mut state = SelectionState::new();
+
+        // Double-clicking inside "foo" must not pull in the quote or "bar"
+        // on the other side of it - `"` isn't one of the word chars
+        // `SEMANTIC_WORD_CHARS` carves out for paths/URLs.
+        state.start_selection(Cursor::new(0, 1), SelectionType::Semantic, &grid);
+        assert_eq!(state.region.start, Cursor::new(0, 0));
+        assert_eq!(state.region.end, Cursor::new(0, 2));
+    }
+
+    #[test]
+    fn test_block_selection_spans_three_rows() {
+        let grid = create_test_grid();
+        let mut state = SelectionState::new();
+
+        state.start_selection(Cursor::new(0, 2), SelectionType::Block, &grid);
+        state.update_selection(Cursor::new(2, 5), &grid);
+
+        assert!(state.region.block);
+        assert_eq!(state.region.start, Cursor::new(0, 2));
+        assert_eq!(state.region.end, Cursor::new(2, 5));
+
+        // Every row in the rectangle uses the same column window,
+        // regardless of where each line's own text starts or ends.
+        let text = extract_selected_text(&grid, &state.region);
+        assert_eq!(text, "llo \nst L\nothe");
+    }
+
+    #[test]
+    fn test_block_selection_does_not_trim_whitespace_on_boundary_rows() {
+        let grid = create_test_grid();
+        let mut state = SelectionState::new();
+
+        // Column window 5..=7 on "Hello World" is " Wo" (leading space) -
+        // a non-block selection would trim that leading whitespace since
+        // row 0 is the selection's start row; a block selection must keep
+        // it so every row's column rectangle lines up exactly.
+        state.start_selection(Cursor::new(0, 5), SelectionType::Block, &grid);
+        state.update_selection(Cursor::new(1, 7), &grid);
+
+        let text = extract_selected_text(&grid, &state.region);
+        assert_eq!(text, " Wo\nLin");
+    }
+
+    #[test]
+    fn test_block_selection_joins_with_newline_even_across_a_wrapped_row() {
+        let grid = create_test_grid();
+        let selection = SelectionRegion::new_block(Cursor::new(0, 0), Cursor::new(1, 3));
+
+        // Row 0 soft-wraps into row 1 in this grid's terms, but a block
+        // selection's rows aren't a text-flow unit the way a normal
+        // selection's are, so the join must still insert `\n`.
+        let text = extract_selected_text_wrapped(&grid, &selection, |row| row == 0);
+        assert_eq!(text, "Hell\nTest");
+    }
+
+    #[test]
+    fn test_extract_selected_text_wrapped_joins_soft_wrap_without_newline() {
+        let grid = create_test_grid();
+        let selection = SelectionRegion::new(Cursor::new(0, 0), Cursor::new(1, 10));
+
+        // Row 0 soft-wraps into row 1, so the join shouldn't insert a `\n`
+        // the way a hard line end would.
+        let text = extract_selected_text_wrapped(&grid, &selection, |row| row == 0);
+        assert_eq!(text, "Hello WorldTest Line 2");
+    }
+
+    #[test]
+    fn test_semantic_selection_drag_keeps_anchor_word() {
+        let grid = create_test_grid();
+        let mut state = SelectionState::new();
+
+        state.start_selection(Cursor::new(0, 8), SelectionType::Semantic, &grid);
+        // Drag left into "Hello" - the anchor word ("World") must stay
+        // selected alongside the newly covered word, not get dropped the
+        // way a plain min/max of the last two points would.
+        state.update_selection(Cursor::new(0, 1), &grid);
+        assert_eq!(state.region.start, Cursor::new(0, 0));
+        assert_eq!(state.region.end, Cursor::new(0, 10));
+    }
 }
@@ -0,0 +1,298 @@
+//! GPU-rendered solid-color rectangles, for UI chrome that used to be drawn
+//! as glyphs (`+`/`-`/`|` pane borders, the `▏` cursor/caret) and so came
+//! out blurry and tied to whatever the font happened to render those
+//! characters as. A filled or outlined rect here is crisp at any font size
+//! and DPI.
+//!
+//! Unlike `text::Cache`/`TextRenderer`, there's only ever one `RectRenderer`
+//! per `RendererHolder`, so the pipeline lives directly on it rather than
+//! being split into a separate shareable cache.
+
+use wgpu::{Device, Queue, TextureFormat};
+
+use crate::terminal::parser::Color;
+
+/// One instance: a solid-color rectangle, or its outline if `params` carries
+/// a nonzero border thickness.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct RectInstance {
+    /// Top-left corner, in NDC.
+    pos_min: [f32; 2],
+    /// Bottom-right corner, in NDC.
+    pos_max: [f32; 2],
+    /// Color (RGBA).
+    color: [f32; 4],
+    /// x/y: border thickness as a fraction of the rect's width/height (0 =
+    /// filled). z/w unused, reserved the way `GlyphInstance::attributes`
+    /// leaves room for flags this shader doesn't read yet.
+    params: [f32; 4],
+}
+
+impl RectInstance {
+    const ATTRIBS: [wgpu::VertexAttribute; 4] = [
+        wgpu::VertexAttribute {
+            offset: 0,
+            shader_location: 0,
+            format: wgpu::VertexFormat::Float32x2,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 2]>() as u64,
+            shader_location: 1,
+            format: wgpu::VertexFormat::Float32x2,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 4]>() as u64,
+            shader_location: 2,
+            format: wgpu::VertexFormat::Float32x4,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 8]>() as u64,
+            shader_location: 3,
+            format: wgpu::VertexFormat::Float32x4,
+        },
+    ];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<RectInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// Convert a terminal `Color` to RGBA, for the fixed UI colors (borders,
+/// cursor, overlay frames) this renderer draws - not arbitrary cell colors,
+/// so unlike `text::TextRenderer::color_to_rgba` this doesn't need the
+/// indexed-palette lookup.
+fn color_to_rgba(color: Color, default: [f32; 4]) -> [f32; 4] {
+    match color {
+        Color::Rgb(r, g, b) => [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0],
+        Color::Default | Color::Indexed(_) | Color::Named(_) => default,
+    }
+}
+
+/// GPU-backed renderer for solid-color and outlined rectangles, drawn in
+/// the same render pass as text: once before it for backdrop chrome (pane
+/// borders, the AI palette frame) and once after for the cursor, so the
+/// cursor overlays whatever glyph is underneath. Kept as two separate
+/// instance lists rather than a single list with a cutoff index, since
+/// backdrop and cursor rects are queued interleaved per-pane rather than
+/// in two contiguous passes.
+pub struct RectRenderer {
+    pipeline: wgpu::RenderPipeline,
+    instance_buffer: Option<wgpu::Buffer>,
+    background: Vec<RectInstance>,
+    foreground: Vec<RectInstance>,
+    /// Where the foreground instances start in the uploaded buffer, set by
+    /// `prepare`.
+    foreground_start: usize,
+    screen_size: (u32, u32),
+}
+
+impl RectRenderer {
+    /// Build the pipeline and an empty instance list for `screen_size`.
+    pub fn new(device: &Device, format: TextureFormat, screen_size: (u32, u32)) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Rect Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/rect.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Rect Pipeline Layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Rect Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[RectInstance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            instance_buffer: None,
+            background: Vec::new(),
+            foreground: Vec::new(),
+            foreground_start: 0,
+            screen_size,
+        }
+    }
+
+    /// Allocate the instance buffer, mirroring `TextRenderer::init_buffer`.
+    pub fn init_buffer(&mut self, device: &Device) {
+        self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Rect Instance Buffer"),
+            size: 64 * 1024,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.screen_size = (width, height);
+    }
+
+    /// Clear queued rects for a new frame.
+    pub fn clear(&mut self) {
+        self.background.clear();
+        self.foreground.clear();
+        self.foreground_start = 0;
+    }
+
+    fn to_ndc(&self, x: f32, y: f32, width: f32, height: f32) -> ([f32; 2], [f32; 2]) {
+        let (screen_w, screen_h) = self.screen_size;
+        let screen_w = screen_w as f32;
+        let screen_h = screen_h as f32;
+
+        let ndc_x = x / screen_w * 2.0 - 1.0;
+        let ndc_y = 1.0 - y / screen_h * 2.0;
+        let ndc_w = width / screen_w * 2.0;
+        let ndc_h = height / screen_h * 2.0;
+
+        ([ndc_x, ndc_y], [ndc_x + ndc_w, ndc_y - ndc_h])
+    }
+
+    /// Queue a filled backdrop rectangle (pane borders, overlay frames) at
+    /// pixel-space `(x, y, width, height)`.
+    pub fn queue_filled(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let instance = self.build_instance(x, y, width, height, color, 0.0, 0.0);
+        self.background.push(instance);
+    }
+
+    /// Queue just the outline of a backdrop rectangle, `thickness` pixels
+    /// wide.
+    pub fn queue_outline(&mut self, x: f32, y: f32, width: f32, height: f32, thickness: f32, color: Color) {
+        let border_x = (thickness / width.max(1.0)).min(0.5);
+        let border_y = (thickness / height.max(1.0)).min(0.5);
+        let instance = self.build_instance(x, y, width, height, color, border_x, border_y);
+        self.background.push(instance);
+    }
+
+    /// Queue a filled rectangle that draws on top of text - the cursor.
+    pub fn queue_filled_foreground(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let instance = self.build_instance(x, y, width, height, color, 0.0, 0.0);
+        self.foreground.push(instance);
+    }
+
+    /// Queue an outlined rectangle that draws on top of text - the hollow
+    /// block cursor shown in unfocused panes.
+    pub fn queue_outline_foreground(&mut self, x: f32, y: f32, width: f32, height: f32, thickness: f32, color: Color) {
+        let border_x = (thickness / width.max(1.0)).min(0.5);
+        let border_y = (thickness / height.max(1.0)).min(0.5);
+        let instance = self.build_instance(x, y, width, height, color, border_x, border_y);
+        self.foreground.push(instance);
+    }
+
+    fn build_instance(&self, x: f32, y: f32, width: f32, height: f32, color: Color, border_x: f32, border_y: f32) -> RectInstance {
+        let (pos_min, pos_max) = self.to_ndc(x, y, width, height);
+        RectInstance {
+            pos_min,
+            pos_max,
+            color: color_to_rgba(color, [1.0, 1.0, 1.0, 1.0]),
+            params: [border_x, border_y, 0.0, 0.0],
+        }
+    }
+
+    /// Upload instance data, same pattern as `TextRenderer::prepare`. The
+    /// background instances are uploaded first, then the foreground ones,
+    /// with `foreground_start` recording the split so `render_foreground`
+    /// knows which instance range to draw.
+    pub fn prepare(&mut self, device: &Device, queue: &Queue) {
+        self.foreground_start = self.background.len();
+
+        if self.background.is_empty() && self.foreground.is_empty() {
+            return;
+        }
+
+        let mut instances = Vec::with_capacity(self.background.len() + self.foreground.len());
+        instances.extend_from_slice(&self.background);
+        instances.extend_from_slice(&self.foreground);
+
+        let instance_data: &[u8] = bytemuck::cast_slice(&instances);
+        let needed_size = instance_data.len() as u64;
+
+        if let Some(ref buffer) = self.instance_buffer {
+            if buffer.size() < needed_size {
+                self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Rect Instance Buffer"),
+                    size: needed_size * 2,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+            }
+        }
+
+        if let Some(ref buffer) = self.instance_buffer {
+            queue.write_buffer(buffer, 0, instance_data);
+        }
+    }
+
+    /// Draw the backdrop layer (borders, overlay frames).
+    pub fn render_background<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        self.draw_range(render_pass, 0..self.foreground_start as u32);
+    }
+
+    /// Draw the foreground layer (the cursor), on top of whatever text was
+    /// drawn in between.
+    pub fn render_foreground<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        let total = self.foreground_start + self.foreground.len();
+        self.draw_range(render_pass, self.foreground_start as u32..total as u32);
+    }
+
+    fn draw_range<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, range: std::ops::Range<u32>) {
+        if range.is_empty() {
+            return;
+        }
+        let Some(ref instance_buffer) = self.instance_buffer else { return };
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, instance_buffer.slice(..));
+        render_pass.draw(0..6, range);
+    }
+}
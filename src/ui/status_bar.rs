@@ -5,18 +5,268 @@
 //! - Git branch (if in a git repository)
 //! - Other useful information
 
+use crate::config::settings::ResolvedStatusBarStyle;
+use chrono::Local;
+use git2::{Repository, Status, StatusOptions};
+use std::fs;
 use std::path::Path;
-use std::process::Command;
+
+/// An in-progress git operation, detected from the layout of the `.git`
+/// directory (mirrors how shell prompt tools report rebase/merge state).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoState {
+    /// `rebase-merge`/`rebase-apply` present, with `step/total` parsed from
+    /// the rebase's `msgnum`/`end` files when available.
+    Rebasing {
+        step: Option<usize>,
+        total: Option<usize>,
+    },
+    /// `MERGE_HEAD` present.
+    Merging,
+    /// `CHERRY_PICK_HEAD` present.
+    CherryPicking,
+    /// `REVERT_HEAD` present.
+    Reverting,
+    /// `BISECT_LOG` present.
+    Bisecting,
+}
+
+impl std::fmt::Display for RepoState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoState::Rebasing {
+                step: Some(step),
+                total: Some(total),
+            } => write!(f, "REBASING {}/{}", step, total),
+            RepoState::Rebasing { .. } => write!(f, "REBASING"),
+            RepoState::Merging => write!(f, "MERGING"),
+            RepoState::CherryPicking => write!(f, "CHERRY-PICKING"),
+            RepoState::Reverting => write!(f, "REVERTING"),
+            RepoState::Bisecting => write!(f, "BISECTING"),
+        }
+    }
+}
+
+/// Structured view of a repository's working-tree state, as shown in the
+/// status bar summary (e.g. `main ↑2 ↓1 !3 +1 ?2`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitStatus {
+    /// Branch name, or the short commit hash when `detached` is true.
+    pub branch: String,
+    /// Whether `HEAD` is not pointing at a branch.
+    pub detached: bool,
+    /// Commits the local branch has that its upstream doesn't.
+    pub ahead: usize,
+    /// Commits the upstream has that the local branch doesn't.
+    pub behind: usize,
+    /// Entries staged for the next commit.
+    pub staged: usize,
+    /// Tracked entries with unstaged working-tree changes.
+    pub modified: usize,
+    /// Entries not tracked by git.
+    pub untracked: usize,
+    /// Entries with unresolved merge conflicts.
+    pub conflicted: usize,
+    /// In-progress rebase/merge/cherry-pick/revert/bisect, if any.
+    pub repo_state: Option<RepoState>,
+}
+
+impl GitStatus {
+    /// Render the ahead/behind/staged/modified/untracked/conflicted/repo-state
+    /// portion of the status summary, without the branch name, e.g.
+    /// `↑2 ↓1 !3 +1 ?2`. `None` when there's nothing to report.
+    pub fn describe(&self) -> Option<String> {
+        let mut parts = Vec::new();
+
+        if self.ahead > 0 {
+            parts.push(format!("↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            parts.push(format!("↓{}", self.behind));
+        }
+        if self.conflicted > 0 {
+            parts.push(format!("={}", self.conflicted));
+        }
+        if self.modified > 0 {
+            parts.push(format!("!{}", self.modified));
+        }
+        if self.staged > 0 {
+            parts.push(format!("+{}", self.staged));
+        }
+        if self.untracked > 0 {
+            parts.push(format!("?{}", self.untracked));
+        }
+        if let Some(repo_state) = &self.repo_state {
+            parts.push(format!("[{}]", repo_state));
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+}
+
+/// Default format used when `StatusBarConfig::format` isn't overridden;
+/// kept in sync with `config::settings::default_status_bar_format`.
+const DEFAULT_FORMAT: &str = "$directory $git_branch$git_status";
+
+/// Shared state built once per `StatusBar::update`, so every segment reads
+/// from it instead of re-querying git itself.
+#[derive(Debug, Clone)]
+pub struct StatusContext {
+    /// Current working directory, as passed to `StatusBar::update`.
+    pub current_dir: String,
+    /// Git working-tree status (None if not in a git repo).
+    pub git_status: Option<GitStatus>,
+}
+
+/// A status bar segment: produces display text from the shared context, or
+/// `None` when it has nothing to show (e.g. `git_branch` outside a repo).
+pub trait Segment {
+    fn render(&self, ctx: &StatusContext) -> Option<String>;
+}
+
+/// `$directory` - the current working directory.
+pub struct DirectorySegment;
+
+impl Segment for DirectorySegment {
+    fn render(&self, ctx: &StatusContext) -> Option<String> {
+        if ctx.current_dir.is_empty() {
+            None
+        } else {
+            Some(ctx.current_dir.clone())
+        }
+    }
+}
+
+/// `$git_branch` - the branch name (or short commit hash when detached).
+pub struct GitBranchSegment;
+
+impl Segment for GitBranchSegment {
+    fn render(&self, ctx: &StatusContext) -> Option<String> {
+        ctx.git_status.as_ref().map(|status| status.branch.clone())
+    }
+}
+
+/// `$git_status` - ahead/behind/staged/modified/untracked/conflicted/repo-state.
+pub struct GitStatusSegment;
+
+impl Segment for GitStatusSegment {
+    fn render(&self, ctx: &StatusContext) -> Option<String> {
+        ctx.git_status.as_ref()?.describe()
+    }
+}
+
+/// `$time` - the current local time.
+pub struct TimeSegment;
+
+impl Segment for TimeSegment {
+    fn render(&self, _ctx: &StatusContext) -> Option<String> {
+        Some(Local::now().format("%H:%M:%S").to_string())
+    }
+}
+
+/// The built-in segments a format string can reference by `$name`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentKind {
+    Directory,
+    GitBranch,
+    GitStatus,
+    Time,
+}
+
+impl SegmentKind {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "directory" => Some(SegmentKind::Directory),
+            "git_branch" => Some(SegmentKind::GitBranch),
+            "git_status" => Some(SegmentKind::GitStatus),
+            "time" => Some(SegmentKind::Time),
+            _ => None,
+        }
+    }
+
+    fn render(self, ctx: &StatusContext) -> Option<String> {
+        match self {
+            SegmentKind::Directory => DirectorySegment.render(ctx),
+            SegmentKind::GitBranch => GitBranchSegment.render(ctx),
+            SegmentKind::GitStatus => GitStatusSegment.render(ctx),
+            SegmentKind::Time => TimeSegment.render(ctx),
+        }
+    }
+}
+
+/// One piece of a parsed format string: literal text, or a segment to
+/// expand against the `StatusContext` at render time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FormatToken {
+    Literal(String),
+    Segment(SegmentKind),
+}
+
+/// Parse a format string into an ordered list of literal text and segment
+/// tokens. A `$name` that doesn't match a known segment (e.g. a segment
+/// this build doesn't ship yet) passes through as literal text rather than
+/// being silently dropped.
+fn parse_format(format: &str) -> Vec<FormatToken> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            literal.push(c);
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(&next) = chars.peek() {
+            if next.is_alphanumeric() || next == '_' {
+                name.push(next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        match SegmentKind::from_name(&name) {
+            Some(kind) => {
+                if !literal.is_empty() {
+                    tokens.push(FormatToken::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(FormatToken::Segment(kind));
+            }
+            None => {
+                literal.push('$');
+                literal.push_str(&name);
+            }
+        }
+    }
+
+    if !literal.is_empty() {
+        tokens.push(FormatToken::Literal(literal));
+    }
+
+    tokens
+}
 
 /// Status bar information
 #[derive(Debug, Clone)]
 pub struct StatusBar {
     /// Current working directory
     pub current_dir: String,
-    /// Git branch (None if not in a git repo)
+    /// Git branch (None if not in a git repo), kept for quick display
     pub git_branch: Option<String>,
+    /// Full git working-tree status (None if not in a git repo)
+    pub git_status: Option<GitStatus>,
     /// Whether the status bar is visible
     pub visible: bool,
+    /// Resolved theme styling for the directory and git segments
+    pub style: ResolvedStatusBarStyle,
+    /// Format string expanded by `render`, e.g. `"$directory $git_branch$git_status"`
+    pub format: String,
 }
 
 impl StatusBar {
@@ -25,37 +275,203 @@ impl StatusBar {
         Self {
             current_dir: String::new(),
             git_branch: None,
+            git_status: None,
             visible: true,
+            style: ResolvedStatusBarStyle::default(),
+            format: DEFAULT_FORMAT.to_string(),
+        }
+    }
+
+    /// Apply a resolved theme style, e.g. from `ThemeConfig::resolve_status_bar_style`.
+    pub fn set_style(&mut self, style: ResolvedStatusBarStyle) {
+        self.style = style;
+    }
+
+    /// Foreground color for the git segment, chosen by whether the
+    /// working tree is clean or dirty.
+    pub fn git_fg(&self) -> Option<&str> {
+        let status = self.git_status.as_ref()?;
+        let dirty = status.staged > 0
+            || status.modified > 0
+            || status.untracked > 0
+            || status.conflicted > 0;
+        if dirty {
+            self.style.git_dirty_fg.as_deref()
+        } else {
+            self.style.git_clean_fg.as_deref()
         }
     }
 
     /// Update the status bar with the current directory
     pub fn update(&mut self, dir: &str) {
         self.current_dir = dir.to_string();
-        self.git_branch = Self::get_git_branch(dir);
+        self.git_status = Self::get_git_status(dir);
+        self.git_branch = self.git_status.as_ref().map(|s| s.branch.clone());
     }
 
-    /// Get the git branch for a directory
-    fn get_git_branch(dir: &str) -> Option<String> {
-        let path = Path::new(dir);
+    /// Query the git working-tree state for a directory, in-process via
+    /// `git2` rather than shelling out to the `git` binary.
+    fn get_git_status(dir: &str) -> Option<GitStatus> {
+        let repo = Repository::discover(Path::new(dir)).ok()?;
 
-        // Try to get git branch using git command
-        let output = Command::new("git")
-            .args(&["rev-parse", "--abbrev-ref", "HEAD"])
-            .current_dir(path)
-            .output()
-            .ok()?;
+        let (branch, detached) = Self::head_description(&repo);
+
+        let (ahead, behind) = Self::ahead_behind(&repo).unwrap_or((0, 0));
 
-        if output.status.success() {
-            let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !branch.is_empty() && branch != "HEAD" {
-                return Some(branch);
+        let mut staged = 0;
+        let mut modified = 0;
+        let mut untracked = 0;
+        let mut conflicted = 0;
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true);
+        if let Ok(statuses) = repo.statuses(Some(&mut opts)) {
+            for entry in statuses.iter() {
+                let status = entry.status();
+                if status.intersects(Status::CONFLICTED) {
+                    conflicted += 1;
+                } else if status.intersects(
+                    Status::INDEX_NEW
+                        | Status::INDEX_MODIFIED
+                        | Status::INDEX_DELETED
+                        | Status::INDEX_RENAMED
+                        | Status::INDEX_TYPECHANGE,
+                ) {
+                    staged += 1;
+                } else if status.intersects(Status::WT_NEW) {
+                    untracked += 1;
+                } else if status.intersects(
+                    Status::WT_MODIFIED
+                        | Status::WT_DELETED
+                        | Status::WT_RENAMED
+                        | Status::WT_TYPECHANGE,
+                ) {
+                    modified += 1;
+                }
             }
         }
 
+        let repo_state = Self::detect_repo_state(&repo);
+
+        Some(GitStatus {
+            branch,
+            detached,
+            ahead,
+            behind,
+            staged,
+            modified,
+            untracked,
+            conflicted,
+            repo_state,
+        })
+    }
+
+    /// Detect an in-progress rebase/merge/cherry-pick/revert/bisect by
+    /// inspecting the layout of the repository's `.git` directory.
+    fn detect_repo_state(repo: &Repository) -> Option<RepoState> {
+        let git_dir = repo.path();
+
+        if git_dir.join("rebase-merge").is_dir() {
+            let (step, total) = Self::rebase_progress(&git_dir.join("rebase-merge"));
+            return Some(RepoState::Rebasing { step, total });
+        }
+        if git_dir.join("rebase-apply").is_dir() {
+            let (step, total) = Self::rebase_progress(&git_dir.join("rebase-apply"));
+            return Some(RepoState::Rebasing { step, total });
+        }
+        if git_dir.join("MERGE_HEAD").is_file() {
+            return Some(RepoState::Merging);
+        }
+        if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+            return Some(RepoState::CherryPicking);
+        }
+        if git_dir.join("REVERT_HEAD").is_file() {
+            return Some(RepoState::Reverting);
+        }
+        if git_dir.join("BISECT_LOG").is_file() {
+            return Some(RepoState::Bisecting);
+        }
+
         None
     }
 
+    /// Parse the `msgnum`/`end` files in a `rebase-merge`/`rebase-apply`
+    /// directory into the current step and total step count.
+    fn rebase_progress(rebase_dir: &Path) -> (Option<usize>, Option<usize>) {
+        let step = fs::read_to_string(rebase_dir.join("msgnum"))
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        let total = fs::read_to_string(rebase_dir.join("end"))
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        (step, total)
+    }
+
+    /// Resolve `HEAD` to a branch name, or a short commit hash when detached.
+    fn head_description(repo: &Repository) -> (String, bool) {
+        match repo.head() {
+            Ok(head) if head.is_branch() => {
+                let name = head.shorthand().unwrap_or("HEAD").to_string();
+                (name, false)
+            }
+            Ok(head) => {
+                let short = head
+                    .target()
+                    .map(|oid| oid.to_string()[..7.min(oid.to_string().len())].to_string())
+                    .unwrap_or_else(|| "HEAD".to_string());
+                (short, true)
+            }
+            Err(_) => ("HEAD".to_string(), true),
+        }
+    }
+
+    /// Compare the local branch tip to its upstream tracking ref.
+    fn ahead_behind(repo: &Repository) -> Option<(usize, usize)> {
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        let local_oid = head.target()?;
+        let branch_name = head.shorthand()?;
+        let branch = repo
+            .find_branch(branch_name, git2::BranchType::Local)
+            .ok()?;
+        let upstream = branch.upstream().ok()?;
+        let upstream_oid = upstream.get().target()?;
+        repo.graph_ahead_behind(local_oid, upstream_oid).ok()
+    }
+
+    /// Render the git summary shown in the status bar, e.g. `main ↑2 ↓1 !3 +1 ?2`.
+    pub fn git_summary(&self) -> Option<String> {
+        let status = self.git_status.as_ref()?;
+        match status.describe() {
+            Some(rest) => Some(format!("{} {}", status.branch, rest)),
+            None => Some(status.branch.clone()),
+        }
+    }
+
+    /// Render the bar by expanding `self.format` against the current
+    /// directory/git state, concatenating every non-empty segment.
+    pub fn render(&self) -> String {
+        let ctx = StatusContext {
+            current_dir: self.current_dir.clone(),
+            git_status: self.git_status.clone(),
+        };
+
+        parse_format(&self.format)
+            .into_iter()
+            .filter_map(|token| match token {
+                FormatToken::Literal(text) => Some(text),
+                FormatToken::Segment(kind) => kind.render(&ctx),
+            })
+            .collect()
+    }
+
+    /// Set the format string used by `render`, e.g. from `StatusBarConfig::format`.
+    pub fn set_format(&mut self, format: String) {
+        self.format = format;
+    }
+
     /// Toggle status bar visibility
     pub fn toggle(&mut self) {
         self.visible = !self.visible;
@@ -83,6 +499,7 @@ mod tests {
         let status_bar = StatusBar::new();
         assert!(status_bar.current_dir.is_empty());
         assert!(status_bar.git_branch.is_none());
+        assert!(status_bar.git_status.is_none());
         assert!(status_bar.visible);
     }
 
@@ -109,16 +526,135 @@ mod tests {
     }
 
     #[test]
-    fn test_git_branch_in_repo() {
+    fn test_git_status_in_repo() {
         // This test assumes we're running in a git repository
         let current_dir = env::current_dir().unwrap();
         let dir_str = current_dir.to_string_lossy();
 
-        let branch = StatusBar::get_git_branch(&dir_str);
+        let status = StatusBar::get_git_status(&dir_str);
         // In a git repo, we should get a branch name
         // (unless in detached HEAD state)
-        if let Some(branch_name) = branch {
-            assert!(!branch_name.is_empty());
+        if let Some(status) = status {
+            assert!(!status.branch.is_empty());
         }
     }
+
+    #[test]
+    fn test_git_summary_formatting() {
+        let status_bar = StatusBar {
+            current_dir: String::new(),
+            git_branch: Some("main".to_string()),
+            git_status: Some(GitStatus {
+                branch: "main".to_string(),
+                detached: false,
+                ahead: 2,
+                behind: 1,
+                staged: 1,
+                modified: 3,
+                untracked: 2,
+                conflicted: 0,
+                repo_state: None,
+            }),
+            visible: true,
+            style: ResolvedStatusBarStyle::default(),
+            format: DEFAULT_FORMAT.to_string(),
+        };
+
+        assert_eq!(
+            status_bar.git_summary().as_deref(),
+            Some("main ↑2 ↓1 !3 +1 ?2")
+        );
+    }
+
+    #[test]
+    fn test_git_summary_with_repo_state() {
+        let status_bar = StatusBar {
+            current_dir: String::new(),
+            git_branch: Some("main".to_string()),
+            git_status: Some(GitStatus {
+                branch: "main".to_string(),
+                detached: false,
+                ahead: 0,
+                behind: 0,
+                staged: 0,
+                modified: 0,
+                untracked: 0,
+                conflicted: 0,
+                repo_state: Some(RepoState::Rebasing {
+                    step: Some(2),
+                    total: Some(5),
+                }),
+            }),
+            visible: true,
+            style: ResolvedStatusBarStyle::default(),
+            format: DEFAULT_FORMAT.to_string(),
+        };
+
+        assert_eq!(
+            status_bar.git_summary().as_deref(),
+            Some("main [REBASING 2/5]")
+        );
+    }
+
+    #[test]
+    fn test_git_fg_chooses_clean_or_dirty_color() {
+        let mut status_bar = StatusBar::new();
+        status_bar.set_style(ResolvedStatusBarStyle {
+            directory_fg: None,
+            directory_bg: None,
+            git_clean_fg: Some("#a3be8c".to_string()),
+            git_dirty_fg: Some("#bf616a".to_string()),
+            separator: "│".to_string(),
+        });
+
+        status_bar.git_status = Some(GitStatus {
+            branch: "main".to_string(),
+            detached: false,
+            ahead: 0,
+            behind: 0,
+            staged: 0,
+            modified: 0,
+            untracked: 0,
+            conflicted: 0,
+            repo_state: None,
+        });
+        assert_eq!(status_bar.git_fg(), Some("#a3be8c"));
+
+        status_bar.git_status.as_mut().unwrap().modified = 1;
+        assert_eq!(status_bar.git_fg(), Some("#bf616a"));
+    }
+
+    #[test]
+    fn test_render_composes_only_non_empty_segments() {
+        let mut status_bar = StatusBar::new();
+        status_bar.update("/tmp/not-a-repo");
+        status_bar.set_format("$directory$git_branch".to_string());
+
+        // Outside a git repo, $git_branch contributes nothing.
+        assert_eq!(status_bar.render(), "/tmp/not-a-repo");
+    }
+
+    #[test]
+    fn test_render_keeps_unknown_segment_as_literal() {
+        let mut status_bar = StatusBar::new();
+        status_bar.set_format("$directory$cmd_duration".to_string());
+        status_bar.current_dir = "~/project".to_string();
+
+        assert_eq!(status_bar.render(), "~/project$cmd_duration");
+    }
+
+    #[test]
+    fn test_parse_format_splits_literal_and_segment_tokens() {
+        let tokens = parse_format("$directory | $git_branch$git_status");
+
+        assert_eq!(
+            tokens,
+            vec![
+                FormatToken::Segment(SegmentKind::Directory),
+                FormatToken::Literal(" | ".to_string()),
+                FormatToken::Segment(SegmentKind::GitBranch),
+                FormatToken::Segment(SegmentKind::GitStatus),
+            ]
+        );
+    }
 }
@@ -23,6 +23,26 @@ const ATLAS_ROWS: u32 = 16;
 /// Total atlas size
 const ATLAS_SIZE: u32 = MAX_GLYPH_SIZE * ATLAS_COLUMNS;
 
+/// Look up the RGBA value for ANSI index `idx` (XTerm 256-color palette).
+/// Shared with `cell_background`'s pipeline, so there's a single source of
+/// truth for the palette.
+pub(crate) fn indexed_color_rgba(idx: u8) -> [f32; 4] {
+    ANSI_PALETTE[idx as usize]
+}
+
+/// Convert an sRGB/gamma-space color (how terminal colors are specified -
+/// the ANSI palette, `Color::Rgb`, and the cursor's fixed fill all assume
+/// this) to linear space, via the standard `c.powf(2.2)` approximation.
+/// The surface format is chosen as sRGB (`Renderer::new`'s
+/// `find(|f| f.is_srgb())`), which re-applies the gamma curve on its own
+/// when writing out - so colors must be linearized before upload, or they
+/// come out too bright. Alpha is already linear and passes through
+/// unconverted. Shared by `color_to_rgba` and `cell_background`'s color
+/// resolution so every color path agrees.
+pub(crate) fn to_linear(color: [f32; 4]) -> [f32; 4] {
+    [color[0].powf(2.2), color[1].powf(2.2), color[2].powf(2.2), color[3]]
+}
+
 /// Static ANSI color palette (256 colors, each with RGBA f32)
 /// Using LazyLock to avoid stack allocation during runtime
 static ANSI_PALETTE: LazyLock<[[f32; 4]; 256]> = LazyLock::new(|| {
@@ -84,9 +104,161 @@ pub enum TextError {
     
     #[error("Glyph not in atlas: {0}")]
     GlyphNotInAtlas(char),
-    
+
     #[error("Atlas is full")]
     AtlasFull,
+
+    #[error("Custom glyph {0} is not registered")]
+    CustomGlyphNotRegistered(CustomGlyphId),
+}
+
+/// Identifier for a registered custom (non-font) glyph - e.g. a powerline
+/// symbol, emoji bitmap, or status icon - queued inline with terminal text.
+pub type CustomGlyphId = u32;
+
+/// Whether a registered custom glyph samples the atlas directly (already
+/// colored, e.g. an emoji bitmap) or is a single-channel mask tinted by
+/// `CustomGlyph::color` at render time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomGlyphKind {
+    /// Sample the atlas RGBA directly.
+    Colored,
+    /// Treat the atlas alpha as a mask, tinted by the instance color.
+    Mask,
+}
+
+/// A queued instance of a registered custom glyph, positioned in the same
+/// pixel space as `TextRenderer::queue_char`.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomGlyph {
+    /// Which registered glyph to draw.
+    pub id: CustomGlyphId,
+    /// Left edge, in pixels.
+    pub left: f32,
+    /// Top edge, in pixels.
+    pub top: f32,
+    /// Width, in pixels.
+    pub width: f32,
+    /// Height, in pixels.
+    pub height: f32,
+    /// Tint applied when the glyph is a `Mask`; ignored for `Colored` glyphs.
+    pub color: Option<Color>,
+}
+
+/// Rendering style for an underline or strikethrough line (SGR 4:1-4:5, 9).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// A single flat line - the long-standing default.
+    Solid,
+    /// Two parallel flat lines (SGR 4:2).
+    Double,
+    /// A line of round dots (SGR 4:4).
+    Dotted,
+    /// A line of short dashes (SGR 4:5).
+    Dashed,
+    /// An undulating line, a.k.a. undercurl (SGR 4:3) - commonly used for
+    /// spell-check and diagnostics.
+    Curly,
+}
+
+/// Where a decoration line sits relative to the glyph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinePosition {
+    /// Below the baseline (underline).
+    Under,
+    /// Through the middle of the glyph (SGR 9 strikethrough).
+    Strike,
+}
+
+/// A decoration line's rendering style and position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineStyle {
+    pub kind: LineKind,
+    pub position: LinePosition,
+}
+
+/// An underline or strikethrough to draw alongside a glyph, queued
+/// alongside it via `TextRenderer::queue_char`. `color` overrides the
+/// glyph's foreground color (SGR 58 underline color); `None` matches it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineDecoration {
+    pub style: LineStyle,
+    pub color: Option<Color>,
+}
+
+/// Map the parser's underline style onto the renderer's line kind. `None`
+/// has no `LineKind` equivalent - callers check for it separately to decide
+/// whether to draw an underline at all.
+fn underline_line_kind(style: crate::terminal::parser::UnderlineStyle) -> Option<LineKind> {
+    use crate::terminal::parser::UnderlineStyle;
+    match style {
+        UnderlineStyle::None => None,
+        UnderlineStyle::Single => Some(LineKind::Solid),
+        UnderlineStyle::Double => Some(LineKind::Double),
+        UnderlineStyle::Curly => Some(LineKind::Curly),
+        UnderlineStyle::Dotted => Some(LineKind::Dotted),
+        UnderlineStyle::Dashed => Some(LineKind::Dashed),
+    }
+}
+
+/// Build the decoration list for a terminal cell's attributes. Strikethrough
+/// still draws in the default `Solid` style for now, until the terminal
+/// parser surfaces an SGR 58 underline color to plumb through too.
+pub fn decorations_for(attributes: &crate::terminal::parser::TextAttributes) -> Vec<LineDecoration> {
+    let mut decorations = Vec::new();
+    if let Some(kind) = underline_line_kind(attributes.underline) {
+        decorations.push(LineDecoration {
+            style: LineStyle {
+                kind,
+                position: LinePosition::Under,
+            },
+            color: None,
+        });
+    }
+    if attributes.strikethrough {
+        decorations.push(LineDecoration {
+            style: LineStyle {
+                kind: LineKind::Solid,
+                position: LinePosition::Strike,
+            },
+            color: None,
+        });
+    }
+    decorations
+}
+
+/// A pixel-space clip rectangle applied to a batch of queued glyphs.
+///
+/// Set via `TextRenderer::set_bounds` before queueing a region's glyphs
+/// (e.g. a pane's visible rows, or a scrollback viewport), so `render` can
+/// scissor each region independently instead of drawing every queued glyph
+/// unconditionally.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextBounds {
+    /// Left edge, in pixels.
+    pub left: f32,
+    /// Top edge, in pixels.
+    pub top: f32,
+    /// Right edge, in pixels.
+    pub right: f32,
+    /// Bottom edge, in pixels.
+    pub bottom: f32,
+}
+
+impl TextBounds {
+    /// Clamp this rectangle to the screen and convert it into the integer
+    /// `(x, y, width, height)` that `set_scissor_rect` expects.
+    fn to_scissor_rect(self, screen_w: u32, screen_h: u32) -> (u32, u32, u32, u32) {
+        let screen_w = screen_w as f32;
+        let screen_h = screen_h as f32;
+
+        let left = self.left.max(0.0).min(screen_w);
+        let top = self.top.max(0.0).min(screen_h);
+        let right = self.right.max(left).min(screen_w);
+        let bottom = self.bottom.max(top).min(screen_h);
+
+        (left as u32, top as u32, (right - left) as u32, (bottom - top) as u32)
+    }
 }
 
 /// A glyph's position in the atlas texture.
@@ -108,60 +280,137 @@ pub struct AtlasGlyph {
     pub ascent: f32,
 }
 
+/// Which embedded face a glyph is rasterized from. Selected per-cell from
+/// `cell.attributes.bold`/`italic` (see `TextRenderer::queue_char`) instead
+/// of synthesizing bold/italic from the regular face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontStyle {
+    Regular,
+    Bold,
+    /// Falls back to `Regular` where no italic face is embedded - see
+    /// `GlyphAtlas::select_font`.
+    Italic,
+}
+
 /// Glyph atlas for caching rendered glyphs in a GPU texture.
 pub struct GlyphAtlas {
-    /// The fontdue font
+    /// Upright face.
     font: Font,
+    /// Dedicated bold face, always embedded.
+    bold_font: Font,
+    /// Dedicated italic (oblique) face, if the font family ships one.
+    /// `None` falls back to `font` in `select_font`.
+    italic_font: Option<Font>,
     /// Font size in pixels
     font_size: f32,
     /// The atlas texture
     texture: Option<wgpu::Texture>,
     /// Texture view for sampling
     texture_view: Option<wgpu::TextureView>,
-    /// Sampler for the texture
-    sampler: Option<wgpu::Sampler>,
-    /// Map from character to atlas position
-    glyph_cache: HashMap<char, AtlasGlyph>,
+    /// Map from (character, face) to atlas position - each face caches its
+    /// own glyph, since the same character rasterizes differently per face.
+    glyph_cache: HashMap<(char, FontStyle), AtlasGlyph>,
+    /// Map from custom glyph id to its atlas position and colored/mask kind
+    custom_glyph_cache: HashMap<CustomGlyphId, (AtlasGlyph, CustomGlyphKind)>,
     /// Next position in the atlas (column, row)
     next_pos: (u32, u32),
     /// Temporary pixel buffer for atlas updates
     atlas_buffer: Vec<u8>,
     /// Whether the atlas needs to be uploaded to GPU
     needs_upload: bool,
+    /// Monospace cell advance width and line height, derived once from the
+    /// regular face at construction (see `measure_cell`).
+    cell_size: (f32, f32),
 }
 
 impl GlyphAtlas {
-    /// Create a new glyph atlas with embedded monospace font.
+    /// Create a new glyph atlas with embedded monospace faces: a normal and
+    /// bold DejaVu Sans Mono weight, plus its oblique (italic) variant.
     pub fn new(_device: &Device, font_size: f32) -> Result<Self, TextError> {
+        let settings = |scale| FontSettings {
+            collection_index: 0,
+            scale,
+            load_substitutions: true,
+        };
+
         // Load embedded monospace font (DejaVu Sans Mono is a good default)
         let font_data = include_bytes!("../fonts/DejaVuSansMono.ttf");
-        let font = Font::from_bytes(
-            font_data.as_slice(),
-            FontSettings {
-                collection_index: 0,
-                scale: font_size,
-                load_substitutions: true,
-            },
-        )
-        .map_err(|e| TextError::FontLoad(e.to_string()))?;
+        let font = Font::from_bytes(font_data.as_slice(), settings(font_size))
+            .map_err(|e| TextError::FontLoad(e.to_string()))?;
+
+        let bold_data = include_bytes!("../fonts/DejaVuSansMono-Bold.ttf");
+        let bold_font = Font::from_bytes(bold_data.as_slice(), settings(font_size))
+            .map_err(|e| TextError::FontLoad(e.to_string()))?;
+
+        // Optional: fall back to the upright face (via `select_font`) if
+        // the embedded family has no oblique variant, or it fails to parse.
+        let italic_data = include_bytes!("../fonts/DejaVuSansMono-Oblique.ttf");
+        let italic_font = Font::from_bytes(italic_data.as_slice(), settings(font_size)).ok();
+
+        let cell_size = Self::measure_cell(&font, font_size);
 
         // Initialize atlas buffer (RGBA)
         let atlas_buffer = vec![0u8; (ATLAS_SIZE * ATLAS_SIZE) as usize * 4];
 
         Ok(Self {
             font,
+            bold_font,
+            italic_font,
             font_size,
             texture: None,
             texture_view: None,
-            sampler: None,
             glyph_cache: HashMap::new(),
+            custom_glyph_cache: HashMap::new(),
             next_pos: (0, 0),
             atlas_buffer,
             needs_upload: false,
+            cell_size,
         })
     }
 
-    /// Initialize GPU resources
+    /// Pick the face a glyph should rasterize from. Bold wins when a cell
+    /// is both bold and italic - there's no embedded bold-italic face, and
+    /// that's the priority most terminal emulators fall back to.
+    fn select_font(&self, style: FontStyle) -> &Font {
+        match style {
+            FontStyle::Regular => &self.font,
+            FontStyle::Bold => &self.bold_font,
+            FontStyle::Italic => self.italic_font.as_ref().unwrap_or(&self.font),
+        }
+    }
+
+    /// Whether a dedicated italic face is embedded, as opposed to falling
+    /// back to the upright face. `TextRenderer::queue_char` uses this to
+    /// decide whether the shader still needs to approximate italics with a
+    /// shear, for fonts that don't ship an oblique variant.
+    pub fn has_italic_face(&self) -> bool {
+        self.italic_font.is_some()
+    }
+
+    /// Derive the monospace cell size from `font`: advance width of a
+    /// reference glyph ('M', which DejaVu Sans Mono - like most monospace
+    /// fonts - gives the same advance as every other printable character),
+    /// and line height from the face's ascent/descent.
+    fn measure_cell(font: &Font, font_size: f32) -> (f32, f32) {
+        let (metrics, _) = font.rasterize('M', font_size);
+        let width = metrics.advance_width;
+
+        let height = font
+            .horizontal_line_metrics(font_size)
+            .map(|m| m.ascent - m.descent)
+            .unwrap_or(font_size);
+
+        (width, height)
+    }
+
+    /// The monospace cell advance width and line height this atlas was
+    /// built for (see `measure_cell`).
+    pub fn cell_size(&self) -> (f32, f32) {
+        self.cell_size
+    }
+
+    /// Initialize GPU resources. The sampler lives on the shared `Cache`
+    /// instead of here, since its settings never vary per atlas.
     pub fn init_gpu(&mut self, device: &Device) {
         // Create the atlas texture
         let texture = device.create_texture(&wgpu::TextureDescriptor {
@@ -181,33 +430,105 @@ impl GlyphAtlas {
 
         let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("Glyph Atlas Sampler"),
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Nearest,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
-
         self.texture = Some(texture);
         self.texture_view = Some(texture_view);
-        self.sampler = Some(sampler);
     }
 
-    /// Cache a glyph in the atlas.
-    pub fn cache_glyph(&mut self, c: char) -> Result<AtlasGlyph, TextError> {
+    /// Cache a glyph rasterized from `style`'s face in the atlas.
+    pub fn cache_glyph(&mut self, c: char, style: FontStyle) -> Result<AtlasGlyph, TextError> {
         // Check if already cached
-        if let Some(&glyph) = self.glyph_cache.get(&c) {
+        if let Some(&glyph) = self.glyph_cache.get(&(c, style)) {
             return Ok(glyph);
         }
 
         // Rasterize the glyph
-        let (metrics, bitmap) = self.font.rasterize(c, self.font_size);
+        let (metrics, bitmap) = self.select_font(style).rasterize(c, self.font_size);
+
+        // fontdue gives a single alpha channel; expand to RGBA (white with
+        // alpha from the glyph), same convention `place_in_atlas` expects.
+        let mut rgba = vec![0u8; bitmap.len() * 4];
+        for (i, &alpha) in bitmap.iter().enumerate() {
+            rgba[i * 4] = 255;
+            rgba[i * 4 + 1] = 255;
+            rgba[i * 4 + 2] = 255;
+            rgba[i * 4 + 3] = alpha;
+        }
 
-        // Check if atlas is full
+        let (uv_min, uv_max) = self.place_in_atlas(metrics.width, metrics.height, &rgba)?;
+
+        let atlas_glyph = AtlasGlyph {
+            uv_min,
+            uv_max,
+            width: metrics.width as u32,
+            height: metrics.height as u32,
+            advance_width: metrics.advance_width,
+            left_side_bearing: metrics.bounds.xmin,
+            // Ascent is from baseline to top of glyph
+            ascent: metrics.bounds.height + metrics.bounds.ymin,
+        };
+
+        self.glyph_cache.insert((c, style), atlas_glyph);
+
+        Ok(atlas_glyph)
+    }
+
+    /// Register a custom (non-font) glyph - e.g. a powerline symbol, emoji
+    /// bitmap, or status icon - from a pre-rasterized RGBA8 image, keyed by
+    /// `id` so it can be queued inline with text via `CustomGlyph`.
+    pub fn register_custom_glyph(
+        &mut self,
+        id: CustomGlyphId,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        kind: CustomGlyphKind,
+    ) -> Result<(), TextError> {
+        let (uv_min, uv_max) = self.place_in_atlas(width as usize, height as usize, rgba)?;
+
+        let atlas_glyph = AtlasGlyph {
+            uv_min,
+            uv_max,
+            width,
+            height,
+            advance_width: width as f32,
+            left_side_bearing: 0.0,
+            ascent: height as f32,
+        };
+
+        self.custom_glyph_cache.insert(id, (atlas_glyph, kind));
+
+        Ok(())
+    }
+
+    /// Like `register_custom_glyph`, but rasterizes lazily via `rasterize`
+    /// (e.g. an SVG/vector icon renderer) instead of taking a pre-built
+    /// RGBA8 buffer.
+    pub fn register_custom_glyph_with(
+        &mut self,
+        id: CustomGlyphId,
+        width: u32,
+        height: u32,
+        kind: CustomGlyphKind,
+        rasterize: impl FnOnce(u32, u32) -> Vec<u8>,
+    ) -> Result<(), TextError> {
+        let rgba = rasterize(width, height);
+        self.register_custom_glyph(id, width, height, &rgba, kind)
+    }
+
+    /// Look up a registered custom glyph's atlas position and kind.
+    pub fn get_custom_glyph(&self, id: CustomGlyphId) -> Option<(AtlasGlyph, CustomGlyphKind)> {
+        self.custom_glyph_cache.get(&id).copied()
+    }
+
+    /// Copy an RGBA8 image into the next free atlas slot, returning its UV
+    /// rect. Shared by `cache_glyph` and `register_custom_glyph` so both
+    /// paths advance the same atlas cursor and upload flag.
+    fn place_in_atlas(
+        &mut self,
+        width: usize,
+        height: usize,
+        rgba: &[u8],
+    ) -> Result<((f32, f32), (f32, f32)), TextError> {
         if self.next_pos.1 >= ATLAS_ROWS {
             return Err(TextError::AtlasFull);
         }
@@ -216,46 +537,25 @@ impl GlyphAtlas {
         let x_offset = col * MAX_GLYPH_SIZE;
         let y_offset = row * MAX_GLYPH_SIZE;
 
-        // Copy glyph bitmap into atlas buffer (convert to RGBA)
-        for y in 0..metrics.height {
-            for x in 0..metrics.width {
-                let src_idx = y * metrics.width + x;
-                let alpha = bitmap[src_idx];
-                
+        for y in 0..height {
+            for x in 0..width {
+                let src_idx = (y * width + x) * 4;
                 let dst_x = x_offset as usize + x;
                 let dst_y = y_offset as usize + y;
                 let dst_idx = (dst_y * ATLAS_SIZE as usize + dst_x) * 4;
-
-                // Write RGBA (white with alpha from glyph)
-                self.atlas_buffer[dst_idx] = 255;     // R
-                self.atlas_buffer[dst_idx + 1] = 255; // G
-                self.atlas_buffer[dst_idx + 2] = 255; // B
-                self.atlas_buffer[dst_idx + 3] = alpha; // A
+                self.atlas_buffer[dst_idx..dst_idx + 4].copy_from_slice(&rgba[src_idx..src_idx + 4]);
             }
         }
 
-        // Calculate UV coordinates (normalized 0-1)
         let uv_min = (
             x_offset as f32 / ATLAS_SIZE as f32,
             y_offset as f32 / ATLAS_SIZE as f32,
         );
         let uv_max = (
-            (x_offset + metrics.width as u32) as f32 / ATLAS_SIZE as f32,
-            (y_offset + metrics.height as u32) as f32 / ATLAS_SIZE as f32,
+            (x_offset + width as u32) as f32 / ATLAS_SIZE as f32,
+            (y_offset + height as u32) as f32 / ATLAS_SIZE as f32,
         );
 
-        let atlas_glyph = AtlasGlyph {
-            uv_min,
-            uv_max,
-            width: metrics.width as u32,
-            height: metrics.height as u32,
-            advance_width: metrics.advance_width,
-            left_side_bearing: metrics.bounds.xmin,
-            // Ascent is from baseline to top of glyph
-            ascent: metrics.bounds.height + metrics.bounds.ymin,
-        };
-
-        self.glyph_cache.insert(c, atlas_glyph);
         self.needs_upload = true;
 
         // Advance position in atlas
@@ -265,7 +565,7 @@ impl GlyphAtlas {
             self.next_pos.1 += 1;
         }
 
-        Ok(atlas_glyph)
+        Ok((uv_min, uv_max))
     }
 
     /// Upload the atlas texture to GPU.
@@ -299,9 +599,9 @@ impl GlyphAtlas {
         self.needs_upload = false;
     }
 
-    /// Get the cached glyph info.
-    pub fn get_glyph(&self, c: char) -> Option<&AtlasGlyph> {
-        self.glyph_cache.get(&c)
+    /// Get the cached glyph info for `c` rasterized from `style`'s face.
+    pub fn get_glyph(&self, c: char, style: FontStyle) -> Option<&AtlasGlyph> {
+        self.glyph_cache.get(&(c, style))
     }
 
     /// Get the texture view for binding.
@@ -309,46 +609,70 @@ impl GlyphAtlas {
         self.texture_view.as_ref()
     }
 
-    /// Get the sampler for binding.
-    pub fn sampler(&self) -> Option<&wgpu::Sampler> {
-        self.sampler.as_ref()
-    }
-
     /// Get the font size.
     pub fn font_size(&self) -> f32 {
         self.font_size
     }
 
-    /// Cache common ASCII characters.
+    /// Cache common ASCII characters, in every face a cell might request.
     pub fn cache_common_glyphs(&mut self) -> Result<(), TextError> {
-        // Cache ASCII printable characters
         for c in ' '..='~' {
-            self.cache_glyph(c)?;
+            self.cache_glyph(c, FontStyle::Regular)?;
+            self.cache_glyph(c, FontStyle::Bold)?;
+            if self.italic_font.is_some() {
+                self.cache_glyph(c, FontStyle::Italic)?;
+            }
         }
         Ok(())
     }
 }
 
-/// Vertex data for text rendering.
+/// Per-frame values shared by every draw in the text pipeline: the
+/// viewport size (so a shader can work in pixels without a separate
+/// push constant) and a free-running clock used to animate the `blink`
+/// attribute. `_pad` only exists to round the struct up to two `vec2`s,
+/// wgpu's minimum uniform alignment.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
-pub struct TextVertex {
-    /// Position in pixels (x, y)
-    pub position: [f32; 2],
-    /// UV coordinates
-    pub uv: [f32; 2],
+struct TextUniforms {
+    resolution: [f32; 2],
+    time_secs: f32,
+    _pad: f32,
+}
+
+/// Per-glyph instance data for text rendering.
+///
+/// One instance covers an entire glyph quad, instead of six full
+/// `TextVertex`-style structs (two triangles) per glyph. The vertex shader
+/// reconstructs the quad corner from `vertex_index` and lerps between
+/// `pos_min`/`pos_max` and `uv_min`/`uv_max`, so this cuts the per-glyph
+/// buffer footprint to roughly 1/6 the bytes and removes the hand-written
+/// corner arithmetic from the CPU path.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlyphInstance {
+    /// Top-left corner of the quad, in NDC.
+    pub pos_min: [f32; 2],
+    /// Bottom-right corner of the quad, in NDC.
+    pub pos_max: [f32; 2],
+    /// Top-left UV coordinate in the atlas.
+    pub uv_min: [f32; 2],
+    /// Bottom-right UV coordinate in the atlas.
+    pub uv_max: [f32; 2],
     /// Color (RGBA)
     pub color: [f32; 4],
     /// Text attributes packed as flags:
-    /// - x: bold (1.0 or 0.0)
-    /// - y: italic (1.0 or 0.0)
+    /// - x: bold (1.0 or 0.0) - informational; the bold face is selected at
+    ///   glyph-cache time (see `FontStyle`), not applied in the shader
+    /// - y: italic shear needed (1.0 or 0.0) - only set when no italic face
+    ///   is embedded, so the shader approximates it on the upright glyph
     /// - z: underline (1.0 or 0.0)
     /// - w: blink (1.0 or 0.0)
     pub attributes: [f32; 4],
 }
 
-impl TextVertex {
-    const ATTRIBS: [wgpu::VertexAttribute; 4] = [
+impl GlyphInstance {
+    const ATTRIBS: [wgpu::VertexAttribute; 6] = [
         wgpu::VertexAttribute {
             offset: 0,
             shader_location: 0,
@@ -362,62 +686,50 @@ impl TextVertex {
         wgpu::VertexAttribute {
             offset: std::mem::size_of::<[f32; 4]>() as u64,
             shader_location: 2,
-            format: wgpu::VertexFormat::Float32x4,
+            format: wgpu::VertexFormat::Float32x2,
         },
         wgpu::VertexAttribute {
-            offset: std::mem::size_of::<[f32; 8]>() as u64,
+            offset: std::mem::size_of::<[f32; 6]>() as u64,
             shader_location: 3,
+            format: wgpu::VertexFormat::Float32x2,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 8]>() as u64,
+            shader_location: 4,
+            format: wgpu::VertexFormat::Float32x4,
+        },
+        wgpu::VertexAttribute {
+            offset: std::mem::size_of::<[f32; 12]>() as u64,
+            shader_location: 5,
             format: wgpu::VertexFormat::Float32x4,
         },
     ];
 
     pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         wgpu::VertexBufferLayout {
-            array_stride: std::mem::size_of::<TextVertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
+            array_stride: std::mem::size_of::<GlyphInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
             attributes: &Self::ATTRIBS,
         }
     }
 }
 
-/// Text renderer that uses the glyph atlas and wgpu.
-pub struct TextRenderer {
-    /// Glyph atlas
-    atlas: GlyphAtlas,
-    /// Render pipeline
-    pipeline: Option<wgpu::RenderPipeline>,
-    /// Bind group layout
-    bind_group_layout: Option<wgpu::BindGroupLayout>,
-    /// Vertex buffer
-    vertex_buffer: Option<wgpu::Buffer>,
-    /// Staging vertices
-    vertices: Vec<TextVertex>,
-    /// Screen dimensions
-    screen_size: (u32, u32),
+/// Shared GPU state for text rendering: the render pipeline, its bind
+/// group layout, and the atlas sampler. Following glyphon's `Cache`
+/// refactor, this is created once per `Device` and shared across many
+/// `TextRenderer`s (e.g. one per pane/tab), so opening and closing panes
+/// doesn't recompile the shader or duplicate bind group layouts.
+pub struct Cache {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
 }
 
-impl TextRenderer {
-    /// Create a new text renderer.
-    pub fn new(device: &Device, font_size: f32, screen_size: (u32, u32)) -> Result<Self, TextError> {
-        let mut atlas = GlyphAtlas::new(device, font_size)?;
-        atlas.init_gpu(device);
-        
-        // Cache common glyphs
-        atlas.cache_common_glyphs()?;
-
-        Ok(Self {
-            atlas,
-            pipeline: None,
-            bind_group_layout: None,
-            vertex_buffer: None,
-            vertices: Vec::new(),
-            screen_size,
-        })
-    }
-
-    /// Initialize the render pipeline.
-    pub fn init_pipeline(&mut self, device: &Device, format: TextureFormat) {
-        // Create bind group layout for texture sampler
+impl Cache {
+    /// Build the shared pipeline, bind group layout, and sampler for the
+    /// given surface format. Each `TextRenderer` that targets this format
+    /// can then borrow the same `Cache`.
+    pub fn new(device: &Device, format: TextureFormat) -> Self {
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Text Bind Group Layout"),
             entries: &[
@@ -437,10 +749,19 @@ impl TextRenderer {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
-        // Create shader module
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Text Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("shaders/text.wgsl").into()),
@@ -458,7 +779,7 @@ impl TextRenderer {
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: Some("vs_main"),
-                buffers: &[TextVertex::desc()],
+                buffers: &[GlyphInstance::desc()],
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
             },
             fragment: Some(wgpu::FragmentState {
@@ -501,24 +822,112 @@ impl TextRenderer {
             cache: None,
         });
 
-        // Create vertex buffer with some initial capacity
-        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Text Vertex Buffer"),
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Glyph Atlas Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    /// Get the shared bind group layout, for building per-renderer bind groups.
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.bind_group_layout
+    }
+}
+
+/// Text renderer that uses the glyph atlas and wgpu.
+pub struct TextRenderer {
+    /// Glyph atlas
+    atlas: GlyphAtlas,
+    /// Instance buffer
+    instance_buffer: Option<wgpu::Buffer>,
+    /// Staging instances, one per glyph (plus one more for a bold duplicate)
+    instances: Vec<GlyphInstance>,
+    /// Clip rectangle applied to glyphs queued since the last `set_bounds`
+    /// call; `None` means unclipped (the full viewport).
+    current_bounds: Option<TextBounds>,
+    /// Instance index where the current bounds region started.
+    region_start: usize,
+    /// Finalized `(bounds, instance range)` pairs, in queue order, built by
+    /// `close_region` and consumed by `render` as one scissor+draw each.
+    regions: Vec<(Option<TextBounds>, std::ops::Range<u32>)>,
+    /// Screen dimensions
+    screen_size: (u32, u32),
+    /// Backs `TextUniforms` (resolution + clock), bound alongside the atlas
+    /// texture and sampler. Allocated in `init_buffer`, like the instance
+    /// buffer.
+    uniform_buffer: Option<wgpu::Buffer>,
+    /// Captured once at construction; `prepare` derives `time_secs` from
+    /// how long it's been running so the blink square wave has a stable
+    /// phase instead of jumping whenever the uniform was last written.
+    start_time: std::time::Instant,
+}
+
+impl TextRenderer {
+    /// Create a new text renderer.
+    pub fn new(device: &Device, font_size: f32, screen_size: (u32, u32)) -> Result<Self, TextError> {
+        let mut atlas = GlyphAtlas::new(device, font_size)?;
+        atlas.init_gpu(device);
+
+        // Cache common glyphs
+        atlas.cache_common_glyphs()?;
+
+        Ok(Self {
+            atlas,
+            instance_buffer: None,
+            instances: Vec::new(),
+            current_bounds: None,
+            region_start: 0,
+            regions: Vec::new(),
+            screen_size,
+            uniform_buffer: None,
+            start_time: std::time::Instant::now(),
+        })
+    }
+
+    /// Allocate the instance and uniform buffers. The pipeline, bind group
+    /// layout, and sampler are no longer owned here - they live on the
+    /// shared `Cache` (see `Cache::new`), so opening a renderer only needs
+    /// its own buffers and atlas, not a fresh pipeline compile.
+    pub fn init_buffer(&mut self, device: &Device) {
+        // Create instance buffer with some initial capacity. One instance
+        // is a fraction of the six-vertex footprint it replaces, so this
+        // initial allocation covers a lot more glyphs than the old buffer did.
+        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Text Instance Buffer"),
             size: 1024 * 1024, // 1MB initial capacity
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
-        self.pipeline = Some(pipeline);
-        self.bind_group_layout = Some(bind_group_layout);
-        self.vertex_buffer = Some(vertex_buffer);
+        self.instance_buffer = Some(instance_buffer);
+
+        self.uniform_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Text Uniform Buffer"),
+            size: std::mem::size_of::<TextUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
     }
 
-    /// Create bind group for the current frame.
-    pub fn create_bind_group(&self, device: &Device) -> Option<wgpu::BindGroup> {
-        let layout = self.bind_group_layout.as_ref()?;
+    /// Create bind group for the current frame, borrowing the layout and
+    /// sampler from the shared `Cache`.
+    pub fn create_bind_group(&self, device: &Device, cache: &Cache) -> Option<wgpu::BindGroup> {
+        let layout = cache.bind_group_layout();
         let texture_view = self.atlas.texture_view()?;
-        let sampler = self.atlas.sampler()?;
+        let sampler = &cache.sampler;
+        let uniform_buffer = self.uniform_buffer.as_ref()?;
 
         Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Text Bind Group"),
@@ -532,18 +941,31 @@ impl TextRenderer {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
             ],
         }))
     }
 
+    /// The monospace cell advance width and line height this renderer's
+    /// atlas was built for. Callers use this instead of an approximation
+    /// like `font_size * 0.6` to lay out the grid and report column/row
+    /// counts, so layout matches the actual rendered glyph boxes.
+    pub fn cell_size(&self) -> (f32, f32) {
+        self.atlas.cell_size()
+    }
+
     /// Resize the screen.
     pub fn resize(&mut self, width: u32, height: u32) {
         self.screen_size = (width, height);
     }
 
-    /// Convert Color to RGBA f32 array.
+    /// Convert Color to a linear-space RGBA f32 array, ready to upload to
+    /// the sRGB surface (see `to_linear`).
     fn color_to_rgba(color: Color, default_fg: [f32; 4], _default_bg: [f32; 4]) -> [f32; 4] {
-        match color {
+        let rgba = match color {
             Color::Default => default_fg,
             Color::Indexed(idx) => {
                 // Use static ANSI color palette to avoid stack allocation
@@ -561,10 +983,37 @@ impl TextRenderer {
                 b as f32 / 255.0,
                 1.0,
             ],
+        };
+        to_linear(rgba)
+    }
+
+    /// Set the clip rectangle applied to glyphs queued from this point on,
+    /// until the next call. Pass `None` to go back to unclipped (the full
+    /// viewport). Callers confine a batch to a rectangle - e.g. a pane's
+    /// body, or a scrollback viewport - without pre-trimming glyphs on the
+    /// CPU; `render` turns each region into its own scissor+draw.
+    pub fn set_bounds(&mut self, bounds: Option<TextBounds>) {
+        if bounds == self.current_bounds {
+            return;
+        }
+        self.close_region();
+        self.current_bounds = bounds;
+    }
+
+    /// Finalize the region that started at `region_start` under
+    /// `current_bounds`, recording its instance range if non-empty.
+    fn close_region(&mut self) {
+        let start = self.region_start as u32;
+        let end = self.instances.len() as u32;
+        if end > start {
+            self.regions.push((self.current_bounds, start..end));
         }
+        self.region_start = self.instances.len();
     }
 
-    /// Queue a character for rendering.
+    /// Queue a character for rendering, plus zero or more decoration lines
+    /// (underline and/or strikethrough, in any `LineKind`) queued alongside
+    /// it. Pass an empty slice for plain text.
     pub fn queue_char(
         &mut self,
         c: char,
@@ -574,16 +1023,24 @@ impl TextRenderer {
         bg_color: Color,
         bold: bool,
         italic: bool,
-        underline: bool,
+        decorations: &[LineDecoration],
         blink: bool,
     ) -> Result<(), TextError> {
+        // Bold wins over italic when a cell carries both (see
+        // `GlyphAtlas::select_font`); there's no embedded bold-italic face.
+        let style = match (bold, italic) {
+            (true, _) => FontStyle::Bold,
+            (false, true) => FontStyle::Italic,
+            (false, false) => FontStyle::Regular,
+        };
+
         // Cache glyph if not already cached
-        if self.atlas.get_glyph(c).is_none() {
-            self.atlas.cache_glyph(c)?;
+        if self.atlas.get_glyph(c, style).is_none() {
+            self.atlas.cache_glyph(c, style)?;
         }
 
-        let glyph = self.atlas.get_glyph(c).ok_or(TextError::GlyphNotInAtlas(c))?;
-        
+        let glyph = self.atlas.get_glyph(c, style).ok_or(TextError::GlyphNotInAtlas(c))?;
+
         let (screen_w, screen_h) = self.screen_size;
         let screen_w = screen_w as f32;
         let screen_h = screen_h as f32;
@@ -609,185 +1066,237 @@ impl TextRenderer {
         let (u_min, v_min) = glyph.uv_min;
         let (u_max, v_max) = glyph.uv_max;
 
-        // Create two triangles (quad) for the glyph background
-        let _bg_ndc_x = x / screen_w * 2.0 - 1.0;
-        let _bg_ndc_y = 1.0 - y / screen_h * 2.0;
-        let _cell_w = glyph.advance_width / screen_w * 2.0;
-        let _cell_h = self.atlas.font_size() / screen_h * 2.0;
-
-        // Pack attributes into a vec4 for the shader
+        let has_underline = decorations
+            .iter()
+            .any(|d| d.style.position == LinePosition::Under);
+
+        // Pack attributes into a vec4 for the shader. Bold is realized by
+        // rasterizing from the bold face (`style` above) rather than a
+        // shader-side effect, so attributes.x is informational only. Italic
+        // is shader-applied shear, but only as a fallback for `style`
+        // falling back to the upright face (no embedded oblique variant) -
+        // a real italic face needs no extra shear on top of its own glyphs.
+        let needs_italic_shear = italic && !self.atlas.has_italic_face();
         let attr_flags = [
             if bold { 1.0 } else { 0.0 },
-            if italic { 1.0 } else { 0.0 },
-            if underline { 1.0 } else { 0.0 },
+            if needs_italic_shear { 1.0 } else { 0.0 },
+            if has_underline { 1.0 } else { 0.0 },
             if blink { 1.0 } else { 0.0 },
         ];
 
-        // Apply italic shear transformation to x coordinate based on y position
-        // This creates a slanted appearance for italic text
-        let italic_shear = if italic { 0.2 } else { 0.0 };
-
-        // Foreground quad vertices (two triangles)
-        let vertices = [
-            // Triangle 1
-            TextVertex {
-                position: [ndc_x + italic_shear * ndc_h, ndc_y],
-                uv: [u_min, v_min],
-                color: fg,
-                attributes: attr_flags,
-            },
-            TextVertex {
-                position: [ndc_x + ndc_w + italic_shear * ndc_h, ndc_y],
-                uv: [u_max, v_min],
-                color: fg,
-                attributes: attr_flags,
-            },
-            TextVertex {
-                position: [ndc_x, ndc_y - ndc_h],
-                uv: [u_min, v_max],
-                color: fg,
-                attributes: attr_flags,
-            },
-            // Triangle 2
-            TextVertex {
-                position: [ndc_x + ndc_w + italic_shear * ndc_h, ndc_y],
-                uv: [u_max, v_min],
-                color: fg,
-                attributes: attr_flags,
-            },
-            TextVertex {
-                position: [ndc_x + ndc_w, ndc_y - ndc_h],
-                uv: [u_max, v_max],
-                color: fg,
-                attributes: attr_flags,
-            },
-            TextVertex {
-                position: [ndc_x, ndc_y - ndc_h],
-                uv: [u_min, v_max],
-                color: fg,
-                attributes: attr_flags,
-            },
-        ];
+        // pos_min is the top-left corner (paired with uv_min), pos_max the
+        // bottom-right corner (paired with uv_max) - not a numeric min/max.
+        self.instances.push(GlyphInstance {
+            pos_min: [ndc_x, ndc_y],
+            pos_max: [ndc_x + ndc_w, ndc_y - ndc_h],
+            uv_min: [u_min, v_min],
+            uv_max: [u_max, v_max],
+            color: fg,
+            attributes: attr_flags,
+        });
 
-        self.vertices.extend_from_slice(&vertices);
-
-        // For bold, render the glyph again with a slight horizontal offset for a bolder appearance
-        if bold {
-            let bold_offset = 0.5 / screen_w * 2.0; // Small offset in NDC
-            let bold_vertices = [
-                // Triangle 1
-                TextVertex {
-                    position: [ndc_x + italic_shear * ndc_h + bold_offset, ndc_y],
-                    uv: [u_min, v_min],
-                    color: fg,
-                    attributes: attr_flags,
-                },
-                TextVertex {
-                    position: [ndc_x + ndc_w + italic_shear * ndc_h + bold_offset, ndc_y],
-                    uv: [u_max, v_min],
-                    color: fg,
-                    attributes: attr_flags,
-                },
-                TextVertex {
-                    position: [ndc_x + bold_offset, ndc_y - ndc_h],
-                    uv: [u_min, v_max],
-                    color: fg,
-                    attributes: attr_flags,
-                },
-                // Triangle 2
-                TextVertex {
-                    position: [ndc_x + ndc_w + italic_shear * ndc_h + bold_offset, ndc_y],
-                    uv: [u_max, v_min],
-                    color: fg,
-                    attributes: attr_flags,
-                },
-                TextVertex {
-                    position: [ndc_x + ndc_w + bold_offset, ndc_y - ndc_h],
-                    uv: [u_max, v_max],
-                    color: fg,
-                    attributes: attr_flags,
-                },
-                TextVertex {
-                    position: [ndc_x + bold_offset, ndc_y - ndc_h],
-                    uv: [u_min, v_max],
-                    color: fg,
-                    attributes: attr_flags,
-                },
-            ];
-            self.vertices.extend_from_slice(&bold_vertices);
+        for decoration in decorations {
+            self.queue_decoration(decoration, ndc_x, ndc_y, ndc_w, ndc_h, fg, default_fg, default_bg, screen_h);
         }
 
-        // For underline, render a horizontal line at the baseline
-        if underline {
-            let underline_y = ndc_y - ndc_h + (2.0 / screen_h * 2.0); // 2 pixels below baseline
-            let underline_h = 1.0 / screen_h * 2.0; // 1 pixel height
-            let underline_color = fg;
-
-            let underline_vertices = [
-                // Single quad for underline
-                TextVertex {
-                    position: [ndc_x, underline_y],
-                    uv: [0.0, 0.0],
-                    color: underline_color,
-                    attributes: [0.0, 0.0, 0.0, 0.0], // No attributes for underline
-                },
-                TextVertex {
-                    position: [ndc_x + ndc_w, underline_y],
-                    uv: [0.0, 0.0],
-                    color: underline_color,
-                    attributes: [0.0, 0.0, 0.0, 0.0],
-                },
-                TextVertex {
-                    position: [ndc_x, underline_y - underline_h],
-                    uv: [0.0, 0.0],
-                    color: underline_color,
-                    attributes: [0.0, 0.0, 0.0, 0.0],
-                },
-                TextVertex {
-                    position: [ndc_x + ndc_w, underline_y],
-                    uv: [0.0, 0.0],
-                    color: underline_color,
-                    attributes: [0.0, 0.0, 0.0, 0.0],
-                },
-                TextVertex {
-                    position: [ndc_x + ndc_w, underline_y - underline_h],
-                    uv: [0.0, 0.0],
-                    color: underline_color,
-                    attributes: [0.0, 0.0, 0.0, 0.0],
-                },
-                TextVertex {
-                    position: [ndc_x, underline_y - underline_h],
-                    uv: [0.0, 0.0],
-                    color: underline_color,
-                    attributes: [0.0, 0.0, 0.0, 0.0],
-                },
-            ];
-            self.vertices.extend_from_slice(&underline_vertices);
+        Ok(())
+    }
+
+    /// Queue a single underline/strikethrough quad alongside a glyph
+    /// already placed at `(ndc_x, ndc_y)` with size `(ndc_w, ndc_h)`.
+    ///
+    /// `Solid`/`Double` are plain-color quads, same as the old flat
+    /// underline. `Dotted`/`Dashed`/`Curly` instead span `uv.x` 0..1 across
+    /// the quad's width and leave `uv.y` at 0, so the fragment shader can
+    /// read the horizontal position back out of the interpolated UV to mask
+    /// dots/dashes or undulate a sine wave for the curly case - there's no
+    /// geometry difference from the CPU's point of view, only how the
+    /// shader samples it. `Double` instead draws two thinner quads with a
+    /// gap rather than widening one, since glyphon-style renderers keep the
+    /// quad math per-line simple and let line count vary instead.
+    #[allow(clippy::too_many_arguments)]
+    fn queue_decoration(
+        &mut self,
+        decoration: &LineDecoration,
+        ndc_x: f32,
+        ndc_y: f32,
+        ndc_w: f32,
+        ndc_h: f32,
+        fg: [f32; 4],
+        default_fg: [f32; 4],
+        default_bg: [f32; 4],
+        screen_h: f32,
+    ) {
+        let color = decoration
+            .color
+            .map(|c| Self::color_to_rgba(c, default_fg, default_bg))
+            .unwrap_or(fg);
+
+        let px = 1.0 / screen_h * 2.0;
+        let (line_y, line_h) = match decoration.style.position {
+            // 2 pixels below the baseline, 1 pixel thick.
+            LinePosition::Under => (ndc_y - ndc_h + 2.0 * px, px),
+            // Through the middle of the glyph, 1 pixel thick.
+            LinePosition::Strike => (ndc_y - ndc_h / 2.0, px),
+        };
+
+        // attributes.x carries the kind as a shader-side enum code; y/w are
+        // reserved, z marks the instance as a decoration quad (as opposed
+        // to a glyph or custom-glyph quad, which use that slot for their
+        // own flags).
+        let kind_code = match decoration.style.kind {
+            LineKind::Solid => 0.0,
+            LineKind::Double => 1.0,
+            LineKind::Dotted => 2.0,
+            LineKind::Dashed => 3.0,
+            LineKind::Curly => 4.0,
+        };
+        let attributes = [kind_code, 0.0, 1.0, 0.0];
+
+        let needs_phase = matches!(
+            decoration.style.kind,
+            LineKind::Dotted | LineKind::Dashed | LineKind::Curly
+        );
+        let (uv_min, uv_max) = if needs_phase {
+            ([0.0, 0.0], [1.0, 0.0])
+        } else {
+            ([0.0, 0.0], [0.0, 0.0])
+        };
+
+        if decoration.style.kind == LineKind::Double {
+            let gap = px;
+            for offset in [0.0, line_h + gap] {
+                self.instances.push(GlyphInstance {
+                    pos_min: [ndc_x, line_y - offset],
+                    pos_max: [ndc_x + ndc_w, line_y - offset - line_h],
+                    uv_min,
+                    uv_max,
+                    color,
+                    attributes,
+                });
+            }
+        } else {
+            self.instances.push(GlyphInstance {
+                pos_min: [ndc_x, line_y],
+                pos_max: [ndc_x + ndc_w, line_y - line_h],
+                uv_min,
+                uv_max,
+                color,
+                attributes,
+            });
         }
+    }
+
+    /// Queue a registered custom (non-font) glyph for rendering inline with
+    /// text, in the same NDC space as `queue_char`.
+    ///
+    /// `attributes.x` is repurposed here (bold/italic/underline/blink don't
+    /// apply to icon glyphs) as the colored-vs-mask flag: `1.0` samples the
+    /// atlas RGBA directly, `0.0` treats it as an alpha mask tinted by
+    /// `glyph.color`.
+    pub fn queue_custom_glyph(&mut self, glyph: CustomGlyph) -> Result<(), TextError> {
+        let (atlas_glyph, kind) = self
+            .atlas
+            .get_custom_glyph(glyph.id)
+            .ok_or(TextError::CustomGlyphNotRegistered(glyph.id))?;
+
+        let (screen_w, screen_h) = self.screen_size;
+        let screen_w = screen_w as f32;
+        let screen_h = screen_h as f32;
+
+        let ndc_x = glyph.left / screen_w * 2.0 - 1.0;
+        let ndc_y = 1.0 - glyph.top / screen_h * 2.0;
+        let ndc_w = glyph.width / screen_w * 2.0;
+        let ndc_h = glyph.height / screen_h * 2.0;
+
+        let default_fg = [0.9, 0.9, 0.9, 1.0];
+        let default_bg = [0.05, 0.05, 0.05, 1.0];
+        let color = glyph
+            .color
+            .map(|c| Self::color_to_rgba(c, default_fg, default_bg))
+            .unwrap_or([1.0, 1.0, 1.0, 1.0]);
+
+        let colored = match kind {
+            CustomGlyphKind::Colored => 1.0,
+            CustomGlyphKind::Mask => 0.0,
+        };
+
+        self.instances.push(GlyphInstance {
+            pos_min: [ndc_x, ndc_y],
+            pos_max: [ndc_x + ndc_w, ndc_y - ndc_h],
+            uv_min: [atlas_glyph.uv_min.0, atlas_glyph.uv_min.1],
+            uv_max: [atlas_glyph.uv_max.0, atlas_glyph.uv_max.1],
+            color,
+            attributes: [colored, 0.0, 0.0, 0.0],
+        });
 
         Ok(())
     }
 
-    /// Clear queued vertices.
+    /// Clear queued instances and bounds regions for a new frame.
     pub fn clear(&mut self) {
-        self.vertices.clear();
+        self.instances.clear();
+        self.regions.clear();
+        self.region_start = 0;
+        self.current_bounds = None;
     }
 
-    /// Upload vertex data and prepare for rendering.
-    pub fn prepare(&mut self, device: &Device, queue: &Queue) {
+    /// Upload instance data and prepare for rendering.
+    ///
+    /// The instance (vertex) buffer is written through `belt` so a full
+    /// screen of glyphs doesn't stall on a direct `queue.write_buffer` every
+    /// frame - the belt carves the write out of a reusable ring of staging
+    /// buffers instead. `belt.finish()`/`belt.recall()` are the caller's
+    /// responsibility (see `Renderer::render`), since they bracket the
+    /// encoder submission, not this call.
+    ///
+    /// The glyph atlas is still uploaded via a direct `queue.write_texture`
+    /// in `GlyphAtlas::upload` - `StagingBelt` only carves out buffer
+    /// writes, and the atlas is already far from a per-frame cost: it only
+    /// re-uploads when `needs_upload` is set by caching a new glyph, not on
+    /// every `prepare` call.
+    pub fn prepare(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        belt: &mut wgpu::util::StagingBelt,
+    ) {
+        // Close out the in-progress bounds region so `render` sees every
+        // queued glyph accounted for, including any queued since the last
+        // explicit `set_bounds` call.
+        self.close_region();
+
         // Upload atlas if needed
         self.atlas.upload(queue);
 
-        // Upload vertex data
-        if !self.vertices.is_empty() {
-            let vertex_data: &[u8] = bytemuck::cast_slice(&self.vertices);
-            
+        // Refresh the shared per-frame uniform (resolution + clock) the
+        // fragment shader reads to drive the blink square wave. This is
+        // four floats, so a direct write is fine - no need to route it
+        // through `belt` like the much larger instance buffer below.
+        if let Some(ref uniform_buffer) = self.uniform_buffer {
+            let (screen_w, screen_h) = self.screen_size;
+            queue.write_buffer(
+                uniform_buffer,
+                0,
+                bytemuck::bytes_of(&TextUniforms {
+                    resolution: [screen_w as f32, screen_h as f32],
+                    time_secs: self.start_time.elapsed().as_secs_f32(),
+                    _pad: 0.0,
+                }),
+            );
+        }
+
+        // Upload instance data
+        if !self.instances.is_empty() {
+            let instance_data: &[u8] = bytemuck::cast_slice(&self.instances);
+
             // Re-create buffer if needed
-            let needed_size = vertex_data.len() as u64;
-            if let Some(ref buffer) = self.vertex_buffer {
+            let needed_size = instance_data.len() as u64;
+            if let Some(ref buffer) = self.instance_buffer {
                 if buffer.size() < needed_size {
-                    self.vertex_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
-                        label: Some("Text Vertex Buffer"),
+                    self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("Text Instance Buffer"),
                         size: needed_size * 2, // Double for growth
                         usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
                         mapped_at_creation: false,
@@ -795,25 +1304,57 @@ impl TextRenderer {
                 }
             }
 
-            if let Some(ref buffer) = self.vertex_buffer {
-                queue.write_buffer(buffer, 0, vertex_data);
+            if let Some(ref buffer) = self.instance_buffer {
+                if let Some(size) = wgpu::BufferSize::new(needed_size) {
+                    let mut view = belt.write_buffer(encoder, buffer, 0, size, device);
+                    view.copy_from_slice(instance_data);
+                }
             }
         }
     }
 
-    /// Render the queued text.
-    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, bind_group: &'a wgpu::BindGroup) {
-        if self.vertices.is_empty() {
+    /// Render the queued text using the shared `Cache`'s pipeline. Each
+    /// instance reconstructs its quad corner in the vertex shader from
+    /// `vertex_index` over a fixed 6-vertex fan. A single vertex buffer may
+    /// hold several bounded regions (see `set_bounds`), so this emits one
+    /// `set_scissor_rect` + `draw` per region rather than a single
+    /// unconditional draw over every instance.
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        bind_group: &'a wgpu::BindGroup,
+        cache: &'a Cache,
+    ) {
+        if self.instances.is_empty() {
             return;
         }
 
-        let Some(ref pipeline) = self.pipeline else { return };
-        let Some(ref vertex_buffer) = self.vertex_buffer else { return };
+        let Some(ref instance_buffer) = self.instance_buffer else { return };
 
-        render_pass.set_pipeline(pipeline);
+        render_pass.set_pipeline(&cache.pipeline);
         render_pass.set_bind_group(0, bind_group, &[]);
-        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-        render_pass.draw(0..self.vertices.len() as u32, 0..1);
+        render_pass.set_vertex_buffer(0, instance_buffer.slice(..));
+
+        let (screen_w, screen_h) = self.screen_size;
+
+        for (bounds, range) in &self.regions {
+            if range.is_empty() {
+                continue;
+            }
+
+            match bounds {
+                Some(bounds) => {
+                    let (x, y, w, h) = bounds.to_scissor_rect(screen_w, screen_h);
+                    if w == 0 || h == 0 {
+                        continue;
+                    }
+                    render_pass.set_scissor_rect(x, y, w, h);
+                }
+                None => render_pass.set_scissor_rect(0, 0, screen_w, screen_h),
+            }
+
+            render_pass.draw(0..6, range.clone());
+        }
     }
 
     /// Get the font size.
@@ -821,9 +1362,9 @@ impl TextRenderer {
         self.atlas.font_size()
     }
 
-    /// Get the number of queued vertices.
-    pub fn vertex_count(&self) -> usize {
-        self.vertices.len()
+    /// Get the number of queued glyph instances.
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
     }
 }
 
@@ -838,4 +1379,53 @@ mod tests {
         let err = TextError::FontLoad("test".to_string());
         assert!(err.to_string().contains("test"));
     }
+
+    #[test]
+    fn test_custom_glyph_not_registered_error() {
+        let err = TextError::CustomGlyphNotRegistered(42);
+        assert!(err.to_string().contains("42"));
+    }
+
+    #[test]
+    fn test_text_bounds_clamps_to_screen() {
+        let bounds = TextBounds {
+            left: -10.0,
+            top: 5.0,
+            right: 2000.0,
+            bottom: 50.0,
+        };
+        assert_eq!(bounds.to_scissor_rect(800, 600), (0, 5, 800, 45));
+    }
+
+    #[test]
+    fn test_text_bounds_empty_when_inverted() {
+        // right < left after clamping collapses to a zero-width rect rather
+        // than a negative one.
+        let bounds = TextBounds {
+            left: 100.0,
+            top: 0.0,
+            right: 50.0,
+            bottom: 10.0,
+        };
+        assert_eq!(bounds.to_scissor_rect(800, 600), (100, 0, 0, 10));
+    }
+
+    #[test]
+    fn test_line_decoration_distinguishes_kind_and_position() {
+        let underline = LineDecoration {
+            style: LineStyle {
+                kind: LineKind::Curly,
+                position: LinePosition::Under,
+            },
+            color: None,
+        };
+        let strikethrough = LineDecoration {
+            style: LineStyle {
+                kind: LineKind::Curly,
+                position: LinePosition::Strike,
+            },
+            color: None,
+        };
+        assert_ne!(underline, strikethrough);
+    }
 }
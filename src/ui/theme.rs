@@ -0,0 +1,327 @@
+//! Pluggable theme engine
+//!
+//! Loads terminal color themes from YAML files in a themes directory, so
+//! users can drop in one of the many community-contributed terminal themes
+//! without recompiling. A `Theme` supplies the default foreground/
+//! background, the 16 standard/bright ANSI slots, the cursor and selection
+//! colors, and an optional accent gradient for UI chrome; `Theme::apply_to_palette`
+//! pushes all of that into a `terminal::parser::Palette`. `ThemeRegistry`
+//! indexes every theme in a directory by name and `refresh`es its catalog
+//! whenever a file's mtime changes, so editing a theme on disk picks it up
+//! on the next lookup without restarting the app.
+
+use crate::terminal::parser::{Palette, Rgb};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// One theme's full color set, deserialized from a YAML file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub foreground: String,
+    pub background: String,
+    pub cursor: String,
+    pub selection: String,
+    /// The 16 standard/bright ANSI slots, in palette order (black, red,
+    /// green, yellow, blue, magenta, cyan, white, then their bright
+    /// counterparts).
+    pub ansi: [String; 16],
+    /// Colors for an optional gradient accent (e.g. a status bar or
+    /// prompt highlight), each a hex string, outermost stop first.
+    #[serde(default)]
+    pub accent_gradient: Vec<String>,
+}
+
+impl Theme {
+    /// Validate that every color field is a well-formed `#rrggbb`/`#rgb`
+    /// hex string, so a typo'd theme file is rejected at load time rather
+    /// than silently resolving to black everywhere.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("theme is missing a name");
+        }
+        parse_hex(&self.foreground).with_context(|| format!("theme {:?}: invalid foreground color", self.name))?;
+        parse_hex(&self.background).with_context(|| format!("theme {:?}: invalid background color", self.name))?;
+        parse_hex(&self.cursor).with_context(|| format!("theme {:?}: invalid cursor color", self.name))?;
+        parse_hex(&self.selection).with_context(|| format!("theme {:?}: invalid selection color", self.name))?;
+        for (i, hex) in self.ansi.iter().enumerate() {
+            parse_hex(hex).with_context(|| format!("theme {:?}: invalid ansi[{}] color", self.name, i))?;
+        }
+        for (i, hex) in self.accent_gradient.iter().enumerate() {
+            parse_hex(hex).with_context(|| format!("theme {:?}: invalid accent_gradient[{}] color", self.name, i))?;
+        }
+        Ok(())
+    }
+
+    pub fn foreground_rgb(&self) -> Rgb {
+        parse_hex(&self.foreground).unwrap_or(Rgb { r: 0xe5, g: 0xe5, b: 0xe5 })
+    }
+
+    pub fn background_rgb(&self) -> Rgb {
+        parse_hex(&self.background).unwrap_or(Rgb { r: 0x00, g: 0x00, b: 0x00 })
+    }
+
+    pub fn cursor_rgb(&self) -> Rgb {
+        parse_hex(&self.cursor).unwrap_or(self.foreground_rgb())
+    }
+
+    pub fn selection_rgb(&self) -> Rgb {
+        parse_hex(&self.selection).unwrap_or(Rgb { r: 0x3a, g: 0x5c, b: 0x9e })
+    }
+
+    /// Push this theme's foreground, background, cursor, and 16 ANSI
+    /// slots into `palette` - what drives the terminal grid's rendered
+    /// colors. Call `Theme::validate` first; a malformed hex string here
+    /// just falls back to the slot's previous value.
+    pub fn apply_to_palette(&self, palette: &mut Palette) {
+        palette.set_foreground(self.foreground_rgb());
+        palette.set_background(self.background_rgb());
+        palette.set_cursor(self.cursor_rgb());
+        for (i, hex) in self.ansi.iter().enumerate() {
+            if let Some(rgb) = parse_hex(hex) {
+                palette.set(i as u8, rgb);
+            }
+        }
+    }
+}
+
+/// Parse a `#rrggbb` or `#rgb` hex color string into an `Rgb`, rejecting
+/// anything else (missing `#`, wrong digit count, non-hex digits).
+fn parse_hex(hex: &str) -> Option<Rgb> {
+    let digits = hex.strip_prefix('#')?;
+    let expand = |c: char| -> Option<u8> { u8::from_str_radix(&format!("{c}{c}"), 16).ok() };
+
+    match digits.len() {
+        6 => {
+            let r = u8::from_str_radix(&digits[0..2], 16).ok()?;
+            let g = u8::from_str_radix(&digits[2..4], 16).ok()?;
+            let b = u8::from_str_radix(&digits[4..6], 16).ok()?;
+            Some(Rgb { r, g, b })
+        }
+        3 => {
+            let mut chars = digits.chars();
+            let r = expand(chars.next()?)?;
+            let g = expand(chars.next()?)?;
+            let b = expand(chars.next()?)?;
+            Some(Rgb { r, g, b })
+        }
+        _ => None,
+    }
+}
+
+/// A loaded theme's source file, paired with the mtime it was loaded at -
+/// what `ThemeRegistry::refresh` compares against to detect edits.
+#[derive(Debug, Clone)]
+struct LoadedTheme {
+    theme: Theme,
+    path: PathBuf,
+    loaded_at: Option<SystemTime>,
+}
+
+/// Indexes every theme YAML file in a directory by name, re-reading a file
+/// whenever its mtime has advanced past what was last loaded.
+#[derive(Debug, Default)]
+pub struct ThemeRegistry {
+    dir: PathBuf,
+    themes: HashMap<String, LoadedTheme>,
+}
+
+impl ThemeRegistry {
+    /// Build a registry over `dir` and do an initial load. A missing
+    /// directory yields an empty registry rather than an error - there's
+    /// nothing wrong with a user who has never installed a custom theme.
+    pub fn load(dir: impl Into<PathBuf>) -> Self {
+        let mut registry = Self { dir: dir.into(), themes: HashMap::new() };
+        registry.refresh();
+        registry
+    }
+
+    /// Re-scan `dir` for new files and re-read any tracked file whose
+    /// mtime has advanced, reporting `(path, message)` for anything that
+    /// fails to parse or validate. Already-loaded themes are left in place
+    /// if their file disappears or fails to re-read, so a transient I/O
+    /// error or an in-progress edit doesn't yank the active theme out from
+    /// under the user.
+    pub fn refresh(&mut self) -> Vec<(PathBuf, String)> {
+        let mut errors = Vec::new();
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return errors;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !matches!(path.extension().and_then(|e| e.to_str()), Some("yaml") | Some("yml")) {
+                continue;
+            }
+
+            let mtime = entry.metadata().and_then(|m| m.modified()).ok();
+            let needs_load = self
+                .themes
+                .values()
+                .find(|loaded| loaded.path == path)
+                .map(|loaded| loaded.loaded_at != mtime)
+                .unwrap_or(true);
+            if !needs_load {
+                continue;
+            }
+
+            match load_theme_file(&path) {
+                Ok(theme) => {
+                    self.themes
+                        .retain(|_, loaded| loaded.path != path || loaded.theme.name == theme.name);
+                    self.themes.insert(theme.name.clone(), LoadedTheme { theme, path, loaded_at: mtime });
+                }
+                Err(e) => errors.push((path, e.to_string())),
+            }
+        }
+
+        errors
+    }
+
+    /// Look up a loaded theme by name.
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.get(name).map(|loaded| &loaded.theme)
+    }
+
+    /// Every loaded theme's name, for populating a theme picker.
+    pub fn names(&self) -> Vec<&str> {
+        self.themes.keys().map(|s| s.as_str()).collect()
+    }
+}
+
+fn load_theme_file(path: &Path) -> Result<Theme> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let theme: Theme = serde_yaml::from_str(&contents).with_context(|| format!("Failed to parse {:?}", path))?;
+    theme.validate()?;
+    Ok(theme)
+}
+
+/// The user's themes directory (`~/.config/warp-foss/themes/`), honoring
+/// `$XDG_CONFIG_HOME` the same way `config::Config` does.
+pub fn user_themes_dir() -> Result<PathBuf> {
+    if let Ok(xdg_config) = std::env::var("XDG_CONFIG_HOME") {
+        return Ok(PathBuf::from(xdg_config).join("warp-foss").join("themes"));
+    }
+
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    Ok(home.join(".config").join("warp-foss").join("themes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_theme() -> Theme {
+        Theme {
+            name: "Nord".to_string(),
+            foreground: "#d8dee9".to_string(),
+            background: "#2e3440".to_string(),
+            cursor: "#d8dee9".to_string(),
+            selection: "#434c5e".to_string(),
+            ansi: [
+                "#3b4252".to_string(),
+                "#bf616a".to_string(),
+                "#a3be8c".to_string(),
+                "#ebcb8b".to_string(),
+                "#81a1c1".to_string(),
+                "#b48ead".to_string(),
+                "#88c0d0".to_string(),
+                "#e5e9f0".to_string(),
+                "#4c566a".to_string(),
+                "#bf616a".to_string(),
+                "#a3be8c".to_string(),
+                "#ebcb8b".to_string(),
+                "#81a1c1".to_string(),
+                "#b48ead".to_string(),
+                "#8fbcbb".to_string(),
+                "#eceff4".to_string(),
+            ],
+            accent_gradient: vec!["#5e81ac".to_string(), "#88c0d0".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_hex_accepts_six_digit_and_three_digit_forms() {
+        assert_eq!(parse_hex("#ff0000"), Some(Rgb { r: 0xff, g: 0x00, b: 0x00 }));
+        assert_eq!(parse_hex("#f00"), Some(Rgb { r: 0xff, g: 0x00, b: 0x00 }));
+    }
+
+    #[test]
+    fn test_parse_hex_rejects_malformed_strings() {
+        assert_eq!(parse_hex("ff0000"), None);
+        assert_eq!(parse_hex("#ff00"), None);
+        assert_eq!(parse_hex("#gggggg"), None);
+    }
+
+    #[test]
+    fn test_validate_accepts_well_formed_theme() {
+        assert!(sample_theme().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_hex_color() {
+        let mut theme = sample_theme();
+        theme.background = "not-a-color".to_string();
+        assert!(theme.validate().is_err());
+    }
+
+    #[test]
+    fn test_apply_to_palette_sets_fg_bg_cursor_and_ansi_slots() {
+        let theme = sample_theme();
+        let mut palette = Palette::new();
+        theme.apply_to_palette(&mut palette);
+
+        assert_eq!(palette.foreground(), theme.foreground_rgb());
+        assert_eq!(palette.background(), theme.background_rgb());
+        assert_eq!(palette.cursor(), theme.cursor_rgb());
+        assert_eq!(palette.get(1), Rgb { r: 0xbf, g: 0x61, b: 0x6a });
+    }
+
+    #[test]
+    fn test_registry_loads_and_reports_malformed_files() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-theme-registry-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+
+        let theme = sample_theme();
+        std::fs::write(scratch.join("nord.yaml"), serde_yaml::to_string(&theme).unwrap()).unwrap();
+        std::fs::write(scratch.join("broken.yaml"), "not: [a, theme").unwrap();
+
+        let mut registry = ThemeRegistry::load(&scratch);
+        assert_eq!(registry.get("Nord"), Some(&theme));
+        assert_eq!(registry.names().len(), 1);
+
+        let errors = registry.refresh();
+        std::fs::remove_dir_all(&scratch).ok();
+        assert!(errors.is_empty() || errors.iter().all(|(p, _)| p.ends_with("broken.yaml")));
+    }
+
+    #[test]
+    fn test_registry_refresh_picks_up_edited_file() {
+        let scratch = std::env::temp_dir().join(format!(
+            "warp-foss-test-theme-refresh-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&scratch).unwrap();
+
+        let mut theme = sample_theme();
+        std::fs::write(scratch.join("nord.yaml"), serde_yaml::to_string(&theme).unwrap()).unwrap();
+        let mut registry = ThemeRegistry::load(&scratch);
+        assert_eq!(registry.get("Nord").unwrap().background, "#2e3440");
+
+        theme.background = "#000000".to_string();
+        std::fs::write(scratch.join("nord.yaml"), serde_yaml::to_string(&theme).unwrap()).unwrap();
+        registry.refresh();
+
+        let result = registry.get("Nord").unwrap().background.clone();
+        std::fs::remove_dir_all(&scratch).ok();
+        assert_eq!(result, "#000000");
+    }
+}
@@ -0,0 +1,279 @@
+//! Pure, testable vi-mode cursor motion over a single grid snapshot.
+//!
+//! `TerminalApp`'s vi mode (Ctrl+Shift+Space to enter, see `main.rs`'s
+//! `ViModeState`) already drives keyboard-only navigation and copying
+//! against the live pane's absolute scrollback addressing, including
+//! half-page and buffer-edge jumps that need the pane's total row count.
+//! This module factors out the grid-local motions - the ones that only
+//! need the rows currently in view, not the whole scrollback - into a
+//! plain `ViMotion`/`ViCursor` pair that doesn't depend on `Pane` or
+//! `TerminalApp` at all, so the stepping and word-boundary logic can be
+//! unit-tested directly. It also distinguishes `WordForward`/`WordBackward`
+//! (any run of non-whitespace) from `SemanticLeft`/`SemanticRight` (the
+//! richer `is_semantic_word_char` delimiter set selection uses), a
+//! granularity `main.rs`'s vi mode doesn't currently expose since its own
+//! `w`/`b` motions already use the semantic delimiter set.
+
+use crate::terminal::grid::{Cell, Cursor};
+use crate::ui::selection::is_semantic_word_char;
+
+/// A single vi-mode cursor motion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViMotion {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Next word boundary, treating any run of non-whitespace as a word.
+    WordForward,
+    /// Previous word boundary, treating any run of non-whitespace as a word.
+    WordBackward,
+    LineStart,
+    LineEnd,
+    /// First row of `grid`.
+    First,
+    /// Last row of `grid`.
+    Last,
+    /// Next word boundary per `is_semantic_word_char`, the same delimiter
+    /// set semantic mouse selection uses.
+    SemanticLeft,
+    /// Previous word boundary per `is_semantic_word_char`, the same
+    /// delimiter set semantic mouse selection uses.
+    SemanticRight,
+}
+
+/// Whether `c` is part of a "simple" word for `WordForward`/`WordBackward` -
+/// any non-whitespace character, unlike `is_semantic_word_char`'s narrower
+/// alphanumeric-plus-path-punctuation set.
+fn is_simple_word_char(c: char) -> bool {
+    !c.is_whitespace()
+}
+
+fn row_len(grid: &[Vec<Cell>], row: usize) -> usize {
+    grid.get(row).map_or(0, |r| r.len())
+}
+
+fn char_at(grid: &[Vec<Cell>], row: usize, col: usize) -> char {
+    grid.get(row).and_then(|r| r.get(col)).map_or(' ', |c| c.char)
+}
+
+/// Step `(row, col)` one cell back in reading order, wrapping to the end of
+/// the previous row at column 0. Returns `false` at the buffer start.
+fn step_back(grid: &[Vec<Cell>], row: &mut usize, col: &mut usize) -> bool {
+    if *col > 0 {
+        *col -= 1;
+        return true;
+    }
+    if *row == 0 {
+        return false;
+    }
+    *row -= 1;
+    *col = row_len(grid, *row).saturating_sub(1);
+    true
+}
+
+/// Step `(row, col)` one cell forward in reading order, wrapping to the
+/// start of the next row. Returns `false` at the buffer end.
+fn step_forward(grid: &[Vec<Cell>], row: &mut usize, col: &mut usize) -> bool {
+    if *col + 1 < row_len(grid, *row) {
+        *col += 1;
+        return true;
+    }
+    if *row + 1 >= grid.len() {
+        return false;
+    }
+    *row += 1;
+    *col = 0;
+    true
+}
+
+fn word_forward(grid: &[Vec<Cell>], pos: Cursor, is_word_char: fn(char) -> bool) -> Cursor {
+    let mut row = pos.row;
+    let mut col = pos.col;
+
+    // Skip the rest of the current word, if the cursor started on one.
+    if is_word_char(char_at(grid, row, col)) {
+        while col < row_len(grid, row) && is_word_char(char_at(grid, row, col)) {
+            col += 1;
+        }
+    }
+    // Skip separators until the next word, wrapping to following rows, or
+    // stop at the end of the buffer.
+    loop {
+        if col >= row_len(grid, row) {
+            if row + 1 >= grid.len() {
+                col = row_len(grid, row).saturating_sub(1);
+                break;
+            }
+            row += 1;
+            col = 0;
+            continue;
+        }
+        if is_word_char(char_at(grid, row, col)) {
+            break;
+        }
+        col += 1;
+    }
+    Cursor::new(row, col)
+}
+
+fn word_backward(grid: &[Vec<Cell>], pos: Cursor, is_word_char: fn(char) -> bool) -> Cursor {
+    let mut row = pos.row;
+    let mut col = pos.col;
+
+    if !step_back(grid, &mut row, &mut col) {
+        return Cursor::new(row, col);
+    }
+    while !is_word_char(char_at(grid, row, col)) {
+        if !step_back(grid, &mut row, &mut col) {
+            return Cursor::new(row, col);
+        }
+    }
+    while col > 0 && is_word_char(char_at(grid, row, col - 1)) {
+        col -= 1;
+    }
+    Cursor::new(row, col)
+}
+
+/// Apply `motion` to `pos` over `grid`, clamping to its bounds. `grid` is
+/// whatever rows the caller considers in play for vi navigation - the live
+/// grid alone, or the live grid plus however much scrollback it collected
+/// into one `Vec` - since this function only ever indexes into it directly.
+pub fn apply_motion(grid: &[Vec<Cell>], pos: Cursor, motion: ViMotion) -> Cursor {
+    match motion {
+        ViMotion::Left => Cursor::new(pos.row, pos.col.saturating_sub(1)),
+        ViMotion::Right => {
+            Cursor::new(pos.row, (pos.col + 1).min(row_len(grid, pos.row).saturating_sub(1)))
+        }
+        ViMotion::Up => Cursor::new(pos.row.saturating_sub(1), pos.col),
+        ViMotion::Down => Cursor::new((pos.row + 1).min(grid.len().saturating_sub(1)), pos.col),
+        ViMotion::LineStart => Cursor::new(pos.row, 0),
+        ViMotion::LineEnd => Cursor::new(pos.row, row_len(grid, pos.row).saturating_sub(1)),
+        ViMotion::First => Cursor::new(0, pos.col),
+        ViMotion::Last => Cursor::new(grid.len().saturating_sub(1), pos.col),
+        ViMotion::WordForward => word_forward(grid, pos, is_simple_word_char),
+        ViMotion::WordBackward => word_backward(grid, pos, is_simple_word_char),
+        ViMotion::SemanticLeft => word_backward(grid, pos, is_semantic_word_char),
+        ViMotion::SemanticRight => word_forward(grid, pos, is_semantic_word_char),
+    }
+}
+
+/// A vi-mode virtual cursor, advanced over a grid snapshot via
+/// `apply_motion` without needing a live `Pane`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViCursor {
+    pub point: Cursor,
+}
+
+impl ViCursor {
+    /// Create a cursor at `point`.
+    pub fn new(point: Cursor) -> Self {
+        Self { point }
+    }
+
+    /// Advance this cursor by one `motion` over `grid`, returning the new
+    /// cursor position.
+    pub fn apply_motion(&self, grid: &[Vec<Cell>], motion: ViMotion) -> ViCursor {
+        ViCursor { point: apply_motion(grid, self.point, motion) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_grid() -> Vec<Vec<Cell>> {
+        fn row(s: &str) -> Vec<Cell> {
+            s.chars().map(Cell::new).collect()
+        }
+        vec![row("foo bar.baz"), row("qux quux"), row("end")]
+    }
+
+    #[test]
+    fn test_left_right_clamp_to_row_bounds() {
+        let grid = test_grid();
+        let cursor = ViCursor::new(Cursor::new(0, 0));
+        assert_eq!(cursor.apply_motion(&grid, ViMotion::Left).point, Cursor::new(0, 0));
+
+        let at_end = ViCursor::new(Cursor::new(2, 2));
+        assert_eq!(at_end.apply_motion(&grid, ViMotion::Right).point, Cursor::new(2, 2));
+    }
+
+    #[test]
+    fn test_up_down_clamp_to_grid_bounds() {
+        let grid = test_grid();
+        let top = ViCursor::new(Cursor::new(0, 1));
+        assert_eq!(top.apply_motion(&grid, ViMotion::Up).point, Cursor::new(0, 1));
+
+        let bottom = ViCursor::new(Cursor::new(2, 1));
+        assert_eq!(bottom.apply_motion(&grid, ViMotion::Down).point, Cursor::new(2, 1));
+    }
+
+    #[test]
+    fn test_line_start_and_end() {
+        let grid = test_grid();
+        let cursor = ViCursor::new(Cursor::new(0, 5));
+        assert_eq!(cursor.apply_motion(&grid, ViMotion::LineStart).point, Cursor::new(0, 0));
+        assert_eq!(cursor.apply_motion(&grid, ViMotion::LineEnd).point, Cursor::new(0, 10));
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let grid = test_grid();
+        let cursor = ViCursor::new(Cursor::new(1, 2));
+        assert_eq!(cursor.apply_motion(&grid, ViMotion::First).point, Cursor::new(0, 2));
+        assert_eq!(cursor.apply_motion(&grid, ViMotion::Last).point, Cursor::new(2, 2));
+    }
+
+    #[test]
+    fn test_word_forward_stops_at_whitespace_run() {
+        let grid = test_grid();
+        // "foo bar.baz" - WordForward (simple) treats "bar.baz" as one word.
+        let cursor = ViCursor::new(Cursor::new(0, 0));
+        let next = cursor.apply_motion(&grid, ViMotion::WordForward);
+        assert_eq!(next.point, Cursor::new(0, 4));
+    }
+
+    #[test]
+    fn test_semantic_right_treats_quotes_as_a_delimiter_unlike_word_forward() {
+        // `"` isn't in `is_semantic_word_char`'s delimiter set the way every
+        // other non-whitespace character is for `WordForward`, so semantic
+        // motion stops one cell later than simple word motion: simple jumps
+        // straight to the opening quote (col 4), semantic treats it as a
+        // separator and lands on "bar" itself (col 5).
+        let grid = vec!["foo \"bar\" baz".chars().map(Cell::new).collect()];
+        let cursor = ViCursor::new(Cursor::new(0, 0));
+
+        let simple = cursor.apply_motion(&grid, ViMotion::WordForward);
+        assert_eq!(simple.point, Cursor::new(0, 4));
+
+        let semantic = cursor.apply_motion(&grid, ViMotion::SemanticRight);
+        assert_eq!(semantic.point, Cursor::new(0, 5));
+    }
+
+    #[test]
+    fn test_word_backward_from_end_of_line() {
+        let grid = test_grid();
+        let cursor = ViCursor::new(Cursor::new(0, 10));
+        let prev = cursor.apply_motion(&grid, ViMotion::WordBackward);
+        assert_eq!(prev.point, Cursor::new(0, 4));
+    }
+
+    #[test]
+    fn test_word_forward_wraps_to_next_row() {
+        let grid = test_grid();
+        // End of row 0 (on "baz") has no further word on that row, so
+        // WordForward should land at the start of row 1's first word.
+        let cursor = ViCursor::new(Cursor::new(0, 10));
+        let next = cursor.apply_motion(&grid, ViMotion::WordForward);
+        assert_eq!(next.point, Cursor::new(1, 0));
+    }
+
+    #[test]
+    fn test_word_backward_wraps_to_previous_row() {
+        let grid = test_grid();
+        let cursor = ViCursor::new(Cursor::new(1, 0));
+        let prev = cursor.apply_motion(&grid, ViMotion::WordBackward);
+        assert_eq!(prev.point, Cursor::new(0, 8));
+    }
+}
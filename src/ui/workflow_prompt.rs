@@ -0,0 +1,189 @@
+//! Argument fill-in prompt for the workflows palette
+//!
+//! Once the user picks a `Workflow` out of the search results, its
+//! `{{argument}}` placeholders (if any) need values before the command can
+//! be run. `WorkflowPrompt` walks the user through the missing arguments
+//! one at a time, collecting typed values in order and falling back to
+//! each argument's default when the user leaves a field blank, then hands
+//! back the fully substituted command via `finished_command`.
+
+use crate::search::workflows::Workflow;
+use std::collections::HashMap;
+
+/// State of the workflow argument fill-in prompt.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptState {
+    /// No workflow selected; nothing to prompt for.
+    Hidden,
+    /// Collecting a value for the argument at `missing[index]`.
+    CollectingArgument,
+    /// Every argument has a value (typed or defaulted); `finished_command`
+    /// returns the substituted command.
+    Ready,
+}
+
+/// Walks the user through filling in one workflow's missing arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowPrompt {
+    pub state: PromptState,
+    workflow: Option<Workflow>,
+    /// Names of arguments still needing a value, in the order they're
+    /// prompted for (the order they first appear in `workflow.command`).
+    missing: Vec<String>,
+    /// Index into `missing` of the argument currently being collected.
+    index: usize,
+    /// Values collected so far, keyed by argument name.
+    values: HashMap<String, String>,
+    /// Current input buffer for the argument being collected.
+    pub input: String,
+}
+
+impl Default for WorkflowPrompt {
+    fn default() -> Self {
+        Self {
+            state: PromptState::Hidden,
+            workflow: None,
+            missing: Vec::new(),
+            index: 0,
+            values: HashMap::new(),
+            input: String::new(),
+        }
+    }
+}
+
+impl WorkflowPrompt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin prompting for `workflow`'s missing arguments, or go straight
+    /// to `Ready` if every argument already has a default.
+    pub fn start(&mut self, workflow: Workflow) {
+        let missing: Vec<String> = workflow
+            .missing_arguments(&HashMap::new())
+            .into_iter()
+            .map(|arg| arg.name.clone())
+            .collect();
+
+        self.values.clear();
+        self.input.clear();
+        self.index = 0;
+        self.state = if missing.is_empty() { PromptState::Ready } else { PromptState::CollectingArgument };
+        self.missing = missing;
+        self.workflow = Some(workflow);
+    }
+
+    /// The argument currently awaiting a value, or `None` if not
+    /// `CollectingArgument` (either hidden, or every argument is filled).
+    pub fn current_argument(&self) -> Option<&str> {
+        if self.state != PromptState::CollectingArgument {
+            return None;
+        }
+        self.missing.get(self.index).map(|s| s.as_str())
+    }
+
+    /// Accept `self.input` as the value for the current argument (an empty
+    /// input leaves the argument to fall back to its default) and advance
+    /// to the next one, or to `Ready` once all are collected.
+    pub fn submit_current(&mut self) {
+        let Some(name) = self.current_argument().map(|s| s.to_string()) else {
+            return;
+        };
+
+        if !self.input.is_empty() {
+            self.values.insert(name, std::mem::take(&mut self.input));
+        } else {
+            self.input.clear();
+        }
+
+        self.index += 1;
+        if self.index >= self.missing.len() {
+            self.state = PromptState::Ready;
+        }
+    }
+
+    /// The fully substituted command, once `state` is `Ready`.
+    pub fn finished_command(&self) -> Option<anyhow::Result<String>> {
+        if self.state != PromptState::Ready {
+            return None;
+        }
+        self.workflow.as_ref().map(|w| w.substitute(&self.values))
+    }
+
+    /// Reset to `Hidden`, discarding any in-progress collection.
+    pub fn cancel(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::workflows::WorkflowArgument;
+
+    fn workflow_with_two_args() -> Workflow {
+        Workflow {
+            name: "Find large files".to_string(),
+            description: String::new(),
+            tags: Vec::new(),
+            command: "find {{directory}} -size +{{size}}".to_string(),
+            arguments: vec![
+                WorkflowArgument {
+                    name: "directory".to_string(),
+                    description: None,
+                    default_value: Some(".".to_string()),
+                },
+                WorkflowArgument { name: "size".to_string(), description: None, default_value: None },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_start_only_prompts_for_arguments_without_defaults() {
+        let mut prompt = WorkflowPrompt::new();
+        prompt.start(workflow_with_two_args());
+
+        assert_eq!(prompt.state, PromptState::CollectingArgument);
+        assert_eq!(prompt.current_argument(), Some("size"));
+    }
+
+    #[test]
+    fn test_start_with_all_defaults_goes_straight_to_ready() {
+        let mut workflow = workflow_with_two_args();
+        workflow.arguments[1].default_value = Some("1G".to_string());
+
+        let mut prompt = WorkflowPrompt::new();
+        prompt.start(workflow);
+
+        assert_eq!(prompt.state, PromptState::Ready);
+        assert_eq!(prompt.finished_command().unwrap().unwrap(), "find '.' -size +'1G'");
+    }
+
+    #[test]
+    fn test_submit_current_falls_back_to_default_when_input_is_blank() {
+        let mut prompt = WorkflowPrompt::new();
+        prompt.start(workflow_with_two_args());
+        prompt.input = "1G".to_string();
+        prompt.submit_current();
+
+        assert_eq!(prompt.state, PromptState::Ready);
+        assert_eq!(prompt.finished_command().unwrap().unwrap(), "find '.' -size +'1G'");
+    }
+
+    #[test]
+    fn test_cancel_resets_to_hidden() {
+        let mut prompt = WorkflowPrompt::new();
+        prompt.start(workflow_with_two_args());
+        prompt.cancel();
+
+        assert_eq!(prompt.state, PromptState::Hidden);
+        assert_eq!(prompt.current_argument(), None);
+    }
+
+    #[test]
+    fn test_finished_command_is_none_before_ready() {
+        let mut prompt = WorkflowPrompt::new();
+        prompt.start(workflow_with_two_args());
+        assert!(prompt.finished_command().is_none());
+    }
+}